@@ -7,7 +7,9 @@
 
 use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
 use pg_trickle::dvm::diff::DiffContext;
-use pg_trickle::dvm::parser::{AggExpr, AggFunc, Column, Expr, OpTree, SortExpr, WindowExpr};
+use pg_trickle::dvm::parser::{
+    AggExpr, AggFunc, Column, Expr, OpTree, Resolved, SortExpr, WindowExpr,
+};
 use pg_trickle::version::Frontier;
 use std::time::Duration;
 
@@ -119,6 +121,7 @@ fn bench_diff_filter(c: &mut Criterion) {
                 column_name: "amount".to_string(),
             }),
             right: Box::new(Expr::Literal("100".to_string())),
+            resolved: Resolved::default(),
         },
         child: Box::new(make_scan("orders", 16384, &["id", "customer_id", "amount"])),
     };
@@ -150,6 +153,7 @@ fn bench_diff_project(c: &mut Criterion) {
                     table_alias: Some("orders".to_string()),
                     column_name: "qty".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
         ],
         aliases: vec!["id".to_string(), "total".to_string()],
@@ -272,6 +276,7 @@ fn bench_diff_inner_join(c: &mut Criterion) {
                 table_alias: Some("c".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(make_scan("o", 16384, &["id", "customer_id", "amount"])),
         right: Box::new(make_scan("c", 16385, &["id", "name", "region"])),
@@ -299,6 +304,7 @@ fn bench_diff_left_join(c: &mut Criterion) {
                 table_alias: Some("c".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(make_scan("o", 16384, &["id", "customer_id", "amount"])),
         right: Box::new(make_scan("c", 16385, &["id", "name"])),
@@ -421,6 +427,7 @@ fn bench_diff_composite(c: &mut Criterion) {
                 table_alias: Some("c".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(make_scan("o", 16384, &["id", "customer_id", "amount"])),
         right: Box::new(make_scan("c", 16385, &["id", "name", "region"])),
@@ -478,6 +485,7 @@ fn bench_differentiate_full(c: &mut Criterion) {
                 column_name: "amount".to_string(),
             }),
             right: Box::new(Expr::Literal("0".to_string())),
+            resolved: Resolved::default(),
         },
         child: Box::new(make_scan("orders", 16384, &["id", "customer_id", "amount"])),
     };
@@ -536,6 +544,7 @@ fn bench_diff_tpch_q01(c: &mut Criterion) {
                 column_name: "l_shipdate".to_string(),
             }),
             right: Box::new(Expr::Literal("'1998-09-02'".to_string())),
+            resolved: Resolved::default(),
         },
         child: Box::new(lineitem),
     };
@@ -590,7 +599,9 @@ fn bench_diff_tpch_q01(c: &mut Criterion) {
                             table_alias: Some("l".to_string()),
                             column_name: "l_discount".to_string(),
                         }),
+                        resolved: Resolved::default(),
                     }),
+                    resolved: Resolved::default(),
                 }),
                 alias: "sum_disc_price".to_string(),
                 is_distinct: false,
@@ -672,6 +683,7 @@ fn bench_diff_tpch_q05(c: &mut Criterion) {
                 table_alias: Some("n".to_string()),
                 column_name: "n_regionkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(region),
         right: Box::new(nation1),
@@ -687,6 +699,7 @@ fn bench_diff_tpch_q05(c: &mut Criterion) {
                 table_alias: Some("c".to_string()),
                 column_name: "c_nationkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j1),
         right: Box::new(customer),
@@ -702,6 +715,7 @@ fn bench_diff_tpch_q05(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "o_custkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j2),
         right: Box::new(orders),
@@ -717,6 +731,7 @@ fn bench_diff_tpch_q05(c: &mut Criterion) {
                 table_alias: Some("l".to_string()),
                 column_name: "l_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j3),
         right: Box::new(lineitem),
@@ -732,6 +747,7 @@ fn bench_diff_tpch_q05(c: &mut Criterion) {
                 table_alias: Some("s".to_string()),
                 column_name: "s_suppkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j4),
         right: Box::new(supplier),
@@ -756,7 +772,9 @@ fn bench_diff_tpch_q05(c: &mut Criterion) {
                         table_alias: Some("l".to_string()),
                         column_name: "l_discount".to_string(),
                     }),
+                    resolved: Resolved::default(),
                 }),
+                resolved: Resolved::default(),
             }),
             alias: "revenue".to_string(),
             is_distinct: false,
@@ -809,6 +827,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("l".to_string()),
                 column_name: "l_partkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(part),
         right: Box::new(lineitem),
@@ -824,6 +843,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("s".to_string()),
                 column_name: "s_suppkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j1),
         right: Box::new(supplier),
@@ -839,6 +859,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "o_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j2),
         right: Box::new(orders),
@@ -854,6 +875,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("c".to_string()),
                 column_name: "c_custkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j3),
         right: Box::new(customer),
@@ -869,6 +891,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("n1".to_string()),
                 column_name: "n_nationkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j4),
         right: Box::new(nation1),
@@ -884,6 +907,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("n2".to_string()),
                 column_name: "n_nationkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j5),
         right: Box::new(nation2),
@@ -899,6 +923,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("r".to_string()),
                 column_name: "r_regionkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j6),
         right: Box::new(region),
@@ -911,6 +936,7 @@ fn bench_diff_tpch_q08(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "o_orderdate".to_string(),
             }],
+            resolved: Resolved::default(),
         }],
         aggregates: vec![
             AggExpr {
@@ -1000,6 +1026,7 @@ fn bench_diff_tpch_q18(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "o_custkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(customer),
         right: Box::new(orders),
@@ -1017,6 +1044,7 @@ fn bench_diff_tpch_q18(c: &mut Criterion) {
                 table_alias: Some("li".to_string()),
                 column_name: "l_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(cust_ord),
         right: Box::new(inner_agg),
@@ -1034,6 +1062,7 @@ fn bench_diff_tpch_q18(c: &mut Criterion) {
                 table_alias: Some("l".to_string()),
                 column_name: "l_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(semijoin),
         right: Box::new(lineitem_outer),
@@ -1125,6 +1154,7 @@ fn bench_diff_tpch_q21(c: &mut Criterion) {
                 table_alias: Some("l1".to_string()),
                 column_name: "l_suppkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(supplier),
         right: Box::new(lineitem1),
@@ -1140,6 +1170,7 @@ fn bench_diff_tpch_q21(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "o_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j1),
         right: Box::new(orders),
@@ -1155,6 +1186,7 @@ fn bench_diff_tpch_q21(c: &mut Criterion) {
                 table_alias: Some("n".to_string()),
                 column_name: "n_nationkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j2),
         right: Box::new(nation),
@@ -1172,6 +1204,7 @@ fn bench_diff_tpch_q21(c: &mut Criterion) {
                 table_alias: Some("l2".to_string()),
                 column_name: "l_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(j3),
         right: Box::new(lineitem2),
@@ -1189,6 +1222,7 @@ fn bench_diff_tpch_q21(c: &mut Criterion) {
                 table_alias: Some("l3".to_string()),
                 column_name: "l_orderkey".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(semijoin),
         right: Box::new(lineitem3),
@@ -1236,6 +1270,7 @@ fn bench_diff_semi_join(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(make_scan(
             "o",
@@ -1270,6 +1305,7 @@ fn bench_diff_anti_join(c: &mut Criterion) {
                 table_alias: Some("o".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(make_scan(
             "o",
@@ -1447,6 +1483,7 @@ fn bench_diff_join_chain(c: &mut Criterion) {
                 table_alias: Some(format!("t{i}")),
                 column_name: format!("t{}_id", i - 1),
             }),
+            resolved: Resolved::default(),
         };
 
         let mut tree: OpTree = make_scan("t0", 16384, &["id", "name", "value"]);
@@ -1634,6 +1671,7 @@ fn bench_a44_7_join_codegen_by_depth(c: &mut Criterion) {
                     table_alias: Some(format!("t{i}")),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(make_scan(&format!("t{i}"), 16384 + i, &["id", "val"])),
@@ -10,7 +10,7 @@
 use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
 use pg_trickle::dag::{DagNode, NodeId, StDag, StStatus};
 use pg_trickle::dvm::diff::{col_list, prefixed_col_list, quote_ident};
-use pg_trickle::dvm::parser::{AggExpr, AggFunc, Column, Expr, OpTree};
+use pg_trickle::dvm::parser::{AggExpr, AggFunc, Column, Expr, OpTree, Resolved};
 use pg_trickle::version::{Frontier, lsn_gt, select_canonical_period_secs};
 use std::time::Duration;
 
@@ -85,8 +85,10 @@ fn bench_expr_to_sql(c: &mut Criterion) {
                 table_alias: Some("t".to_string()),
                 column_name: "qty".to_string(),
             }),
+            resolved: Resolved::default(),
         }),
         right: Box::new(Expr::Literal("100".to_string())),
+        resolved: Resolved::default(),
     };
 
     let func_call = Expr::FuncCall {
@@ -98,6 +100,7 @@ fn bench_expr_to_sql(c: &mut Criterion) {
             },
             Expr::Literal("0".to_string()),
         ],
+        resolved: Resolved::default(),
     };
 
     let mut group = c.benchmark_group("expr_to_sql");
@@ -0,0 +1,101 @@
+-- pg_trickle 0.48.0 -> 0.49.0 upgrade migration
+--
+-- v0.49.0 changes that add catalog objects register them below, one step
+-- per change.
+--
+--   CORR-1:   Point-in-time reads of temporal stream tables.
+--               New functions: as_of(), history().
+--   POLL-HWM: Foreign tables can be polled above a high-water mark instead
+--               of being re-read in full on every poll.
+--               pgtrickle.pgt_polling_config catalog table.
+--               New functions: configure_foreign_table_polling(),
+--                              reset_foreign_table_polling().
+--               The mark itself is kept per source in a hwm_<stable_name>
+--               table in the change buffer schema, created on the first
+--               reconciliation.
+--
+-- Schema changes:
+--   NEW TABLE: pgtrickle.pgt_polling_config
+--   NEW FUNCTIONS:
+--     pgtrickle.as_of()
+--     pgtrickle.history()
+--     pgtrickle.configure_foreign_table_polling()
+--     pgtrickle.reset_foreign_table_polling()
+
+-- ── Step 1: Register CORR-1 temporal read functions ──────────────────────
+-- The implementations live in the .so; these stubs delegate to the C wrappers.
+
+CREATE FUNCTION pgtrickle."as_of"(
+    "stream_table" TEXT,
+    "ts"           TIMESTAMPTZ
+)
+RETURNS SETOF jsonb
+LANGUAGE c /* Rust */
+AS 'MODULE_PATHNAME', 'as_of_wrapper';
+
+COMMENT ON FUNCTION pgtrickle."as_of"(TEXT,TIMESTAMPTZ) IS
+    'CORR-1 (v0.49.0): Return the rows of a temporal stream table as they were '
+    'at ts, one jsonb object per row.';
+
+CREATE FUNCTION pgtrickle."history"(
+    "stream_table" TEXT,
+    "key"          jsonb DEFAULT NULL
+)
+RETURNS TABLE(
+    "valid_from" TIMESTAMPTZ,
+    "valid_to"   TIMESTAMPTZ,
+    "row_data"   jsonb
+)
+LANGUAGE c /* Rust */
+AS 'MODULE_PATHNAME', 'history_wrapper';
+
+COMMENT ON FUNCTION pgtrickle."history"(TEXT,jsonb) IS
+    'CORR-1 (v0.49.0): Return every version of the temporal stream table rows '
+    'matching key (jsonb containment); a NULL key returns the full history.';
+
+-- ── Step 2: POLL-HWM — Create polling configuration catalog table ────────
+
+CREATE TABLE IF NOT EXISTS pgtrickle.pgt_polling_config (
+    source_relid        OID         NOT NULL PRIMARY KEY,
+    watermark_column    TEXT        NOT NULL,
+    key_columns         TEXT[]      NOT NULL,
+    soft_delete_column  TEXT,
+    reconcile_interval  INTERVAL,
+    last_reconciled_at  TIMESTAMPTZ,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE pgtrickle.pgt_polling_config IS
+    'POLL-HWM (v0.49.0): High-water-mark polling configuration per foreign table. '
+    'Populated via pgtrickle.configure_foreign_table_polling() / pgtrickle.reset_foreign_table_polling().';
+
+-- ── Step 3: Register POLL-HWM functions ──────────────────────────────────
+
+CREATE FUNCTION pgtrickle."configure_foreign_table_polling"(
+    "source"             TEXT,
+    "watermark_column"   TEXT,
+    "key_columns"        TEXT[],
+    "soft_delete_column" TEXT DEFAULT NULL,
+    "reconcile_interval" TEXT DEFAULT '1 day'
+)
+RETURNS VOID
+LANGUAGE c /* Rust */
+AS 'MODULE_PATHNAME', 'configure_foreign_table_polling_wrapper';
+
+COMMENT ON FUNCTION pgtrickle."configure_foreign_table_polling"(TEXT,TEXT,TEXT[],TEXT,TEXT) IS
+    'POLL-HWM (v0.49.0): Poll a foreign table for rows at or above the highest '
+    'watermark_column value seen so far, matched against the snapshot on '
+    'key_columns. Rows with soft_delete_column set are emitted as deletes; a full '
+    'reconciliation runs on the next poll and then every reconcile_interval.';
+
+CREATE FUNCTION pgtrickle."reset_foreign_table_polling"(
+    "source" TEXT
+)
+RETURNS VOID
+LANGUAGE c /* Rust */
+AS 'MODULE_PATHNAME', 'reset_foreign_table_polling_wrapper';
+
+COMMENT ON FUNCTION pgtrickle."reset_foreign_table_polling"(TEXT) IS
+    'POLL-HWM (v0.49.0): Remove the high-water-mark configuration of a foreign '
+    'table and drop its mark table; subsequent polls diff the whole table against '
+    'the snapshot again.';
@@ -1878,6 +1878,115 @@ pub(super) fn vector_status() -> TableIterator<
     TableIterator::new(rows)
 }
 
+// ── POLL-HWM (v0.49.0): Foreign-table high-water-mark polling ────────────
+
+/// Poll a foreign table incrementally instead of re-reading it in full.
+///
+/// Each poll fetches only rows whose `watermark_column` (an `updated_at`
+/// timestamp, a sequence id, ...) is at or above the highest value seen so
+/// far, and matches them against the previous snapshot on `key_columns`.
+/// Rows whose `soft_delete_column` is set (non-NULL and not `false`) are
+/// emitted as deletes. Physical deletes are only visible to a full
+/// reconciliation, which runs on the next poll and then every
+/// `reconcile_interval` (NULL disables the periodic reconciliation).
+#[pg_extern(schema = "pgtrickle")]
+pub(super) fn configure_foreign_table_polling(
+    source: &str,
+    watermark_column: &str,
+    key_columns: Vec<String>,
+    soft_delete_column: default!(Option<&str>, "NULL"),
+    reconcile_interval: default!(Option<&str>, "'1 day'"),
+) -> Result<(), PgTrickleError> {
+    let source_relid = resolve_source_oid(source)?;
+    let relkind = Spi::get_one_with_args::<String>(
+        "SELECT relkind::text FROM pg_class WHERE oid = $1",
+        &[source_relid.into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    if relkind.as_deref() != Some("f") {
+        return Err(PgTrickleError::InvalidArgument(format!(
+            "{source} is not a foreign table"
+        )));
+    }
+    if key_columns.is_empty() {
+        return Err(PgTrickleError::InvalidArgument(
+            "key_columns must name at least one column".into(),
+        ));
+    }
+    for column in std::iter::once(watermark_column)
+        .chain(key_columns.iter().map(String::as_str))
+        .chain(soft_delete_column)
+    {
+        let exists = Spi::get_one_with_args::<bool>(
+            "SELECT EXISTS(SELECT 1 FROM pg_attribute \
+             WHERE attrelid = $1 AND attname = $2 AND attnum > 0 AND NOT attisdropped)",
+            &[source_relid.into(), column.into()],
+        )
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+        .unwrap_or(false);
+        if !exists {
+            return Err(PgTrickleError::InvalidArgument(format!(
+                "column \"{column}\" does not exist in {source}"
+            )));
+        }
+    }
+
+    // A new configuration starts unreconciled, so the next poll diffs the
+    // snapshot in full and rebuilds the high-water mark and key index.
+    Spi::run_with_args(
+        "INSERT INTO pgtrickle.pgt_polling_config \
+         (source_relid, watermark_column, key_columns, soft_delete_column, reconcile_interval) \
+         VALUES ($1, $2, $3, $4, $5::interval) \
+         ON CONFLICT (source_relid) DO UPDATE SET \
+             watermark_column = EXCLUDED.watermark_column, \
+             key_columns = EXCLUDED.key_columns, \
+             soft_delete_column = EXCLUDED.soft_delete_column, \
+             reconcile_interval = EXCLUDED.reconcile_interval, \
+             last_reconciled_at = NULL",
+        &[
+            source_relid.into(),
+            watermark_column.into(),
+            key_columns.into(),
+            soft_delete_column.into(),
+            reconcile_interval.into(),
+        ],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    pgrx::info!(
+        "pg_trickle: foreign table {} (oid={}) is now polled above the high-water mark of {}",
+        source,
+        source_relid.to_u32(),
+        watermark_column
+    );
+    Ok(())
+}
+
+/// Remove the high-water-mark configuration of a foreign table and drop its
+/// mark table; subsequent polls diff the whole table against the snapshot
+/// again.
+#[pg_extern(schema = "pgtrickle")]
+pub(super) fn reset_foreign_table_polling(source: &str) -> Result<(), PgTrickleError> {
+    let source_relid = resolve_source_oid(source)?;
+    let removed = Spi::get_one_with_args::<bool>(
+        "WITH d AS (DELETE FROM pgtrickle.pgt_polling_config \
+                    WHERE source_relid = $1 RETURNING 1) \
+         SELECT EXISTS(SELECT 1 FROM d)",
+        &[source_relid.into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+    .unwrap_or(false);
+    if !removed {
+        return Err(PgTrickleError::NotFound(format!(
+            "foreign table {source} has no polling configuration"
+        )));
+    }
+    crate::cdc::polling::drop_high_water_mark_state(
+        source_relid,
+        &config::pg_trickle_change_buffer_schema(),
+    )
+}
+
 // ── TEST-7 (v0.24.0): Unit tests for diagnostics pure-logic helpers ─────
 
 #[cfg(test)]
@@ -271,8 +271,8 @@ pub(super) fn cleanup_cdc_for_source(
         // If WAL-based CDC was active (or transitioning), clean up
         // the replication slot and publication first.
         if matches!(cdc_mode, CdcMode::Wal | CdcMode::Transitioning) {
-            let slot_name = wal_decoder::slot_name_for_source(source_oid);
-            if let Err(e) = wal_decoder::drop_replication_slot(&slot_name) {
+            let slot_name = wal_decoder::slot_name_in_use(source_oid);
+            if let Err(e) = wal_decoder::release_source_slot(source_oid, &slot_name) {
                 pgrx::warning!(
                     "Failed to drop replication slot {} for oid {}: {}",
                     slot_name,
@@ -314,6 +314,7 @@ pub(super) fn cleanup_cdc_for_source(
             source_oid.to_u32(),
         );
         let _ = Spi::run(&drop_snap_sql);
+        let _ = cdc::polling::drop_high_water_mark_state(source_oid, &change_schema);
 
         // Delete tracking record
         let _ = Spi::run_with_args(
@@ -1875,6 +1876,9 @@ pub(super) fn initialize_st(
         }
     } else if let Some(ua_sql) = crate::dvm::try_union_all_refresh_sql(query) {
         ua_sql
+    } else if let Some(row_id_sql) = crate::dvm::grouping_sets_row_id_sql(query) {
+        // GSETS-NATIVE: the row ID hashes GROUPING(…) inside the query.
+        inject_row_id(&effective_query, &row_id_sql)
     } else if let Some(info) = topk_info {
         // TopK: use the full query (with ORDER BY + LIMIT) for initial population,
         // so only the top K rows are inserted.
@@ -1897,6 +1901,12 @@ pub(super) fn initialize_st(
     Spi::run(&insert_sql)
         .map_err(|e| PgTrickleError::SpiError(format!("Failed to initialize ST: {}", e)))?;
 
+    // MINMAX-MS / ORDSET-HIST: Populate aggregate side tables alongside the
+    // initial fill.
+    if let Some(st) = StreamTableMeta::get_by_id(pgt_id)? {
+        crate::refresh::rebuild_agg_side_tables(&st)?;
+    }
+
     // Seed the initial frontier at creation time so every initialized stream
     // table participates in shared change-buffer bookkeeping immediately.
     // Without this, one branch of a diamond can remain frontier-less after the
@@ -2115,6 +2125,24 @@ pub fn inject_covar_aux(query: &str, covar_aux_columns: &[(String, String)]) ->
     }
 }
 
+/// Inject `{row_id_sql} AS __pgt_row_id` as the first column of a query.
+///
+/// GSETS-NATIVE: a grouping-sets row ID hashes `GROUPING(…)`, which is only
+/// valid inside the grouping query itself, so the full refresh evaluates it
+/// there instead of over a `sub.*` wrapper.
+pub fn inject_row_id(query: &str, row_id_sql: &str) -> String {
+    if let Some(pos) = find_top_level_keyword(query, "SELECT") {
+        let body = pos + "SELECT".len();
+        format!(
+            "{} {row_id_sql} AS __pgt_row_id,{}",
+            &query[..body],
+            &query[body..],
+        )
+    } else {
+        query.to_string()
+    }
+}
+
 /// Result of stripping DISTINCT from a query.
 pub(super) struct DistinctStripped {
     /// The query with DISTINCT removed.
@@ -1191,8 +1191,6 @@ fn run_query_rewrite_pipeline(query: &str) -> Result<RewriteResult, PgTrickleErr
         }
         query = q;
     }
-    // ROWS FROM() multi-function rewrite
-    let query = crate::dvm::rewrite_rows_from(&query)?;
     Ok(RewriteResult {
         query,
         had_nested_window_rewrite,
@@ -1511,11 +1509,16 @@ fn validate_and_parse_query(
 
     // DVM parse for DIFFERENTIAL/IMMEDIATE (non-TopK).
     // AUTO mode: if DVM parsing fails, downgrade to FULL instead of erroring.
+    // TOPK-PART: a filtered Window that cannot refill its partitions from
+    // the source counts as a parse failure.
     let parsed_tree = if (*refresh_mode == RefreshMode::Differential
         || *refresh_mode == RefreshMode::Immediate)
         && topk_info.is_none()
     {
-        match crate::dvm::parse_defining_query_full(q) {
+        match crate::dvm::parse_defining_query_full(q).and_then(|tree| {
+            crate::dvm::check_window_truncation_support(&tree)?;
+            Ok(tree)
+        }) {
             Ok(mut tree) => {
                 // Emit advisory warnings (e.g. NATURAL JOIN column drift) exactly
                 // once here — downstream parse calls (cache pre-warm, row-id
@@ -1594,7 +1597,11 @@ fn validate_and_parse_query(
                     }
                 }
             }
-            's' => {
+            // SLIDE-1: clock comparisons in sliding-window filters are
+            // maintained by expiry deltas, so they alone do not warrant a warning.
+            's' if crate::dvm::parser::tree_worst_volatility_outside_sliding_windows(pr)?
+                == 's' =>
+            {
                 pgrx::warning!(
                     "Defining query contains stable functions (e.g., now(), \
                      current_timestamp). These return the same value within a \
@@ -3314,6 +3321,25 @@ fn create_stream_table_impl(opts: CreateStreamTableOptions<'_>) -> Result<(), Pg
         }
     }
 
+    // CORR-1 (v0.49.0): Validate temporal flag. Versions are keyed by
+    // __pgt_row_id, and IMMEDIATE maintenance bypasses the temporal apply.
+    if temporal_mode {
+        if refresh_mode.is_immediate() {
+            return Err(PgTrickleError::InvalidArgument(
+                "temporal is not supported with IMMEDIATE refresh mode. \
+                 Use DIFFERENTIAL, FULL, or AUTO refresh mode."
+                    .to_string(),
+            ));
+        }
+        if vq.has_keyless_source {
+            return Err(PgTrickleError::InvalidArgument(
+                "temporal is not supported for stream tables with keyless sources. \
+                 Add a PRIMARY KEY to all source tables first."
+                    .to_string(),
+            ));
+        }
+    }
+
     // Check for duplicate
     if StreamTableMeta::get_by_name(&schema, &table_name).is_ok() {
         return Err(PgTrickleError::AlreadyExists(format!(
@@ -4354,6 +4380,26 @@ fn drop_stream_table_impl_inner(
         }
     }
 
+    // MINMAX-MS / ORDSET-HIST: Drop the aggregate side tables.
+    if let Err(e) = refresh::drop_agg_side_tables(st.pgt_id) {
+        pgrx::warning!(
+            "Failed to drop aggregate side tables for pgt_id {}: {}",
+            st.pgt_id,
+            e
+        );
+    }
+
+    // CORR-1 (v0.49.0): Drop the temporal history table.
+    if st.temporal_mode
+        && let Err(e) = refresh::drop_temporal_history_table(st.pgt_id)
+    {
+        pgrx::warning!(
+            "Failed to drop temporal history table for pgt_id {}: {}",
+            st.pgt_id,
+            e
+        );
+    }
+
     // ST-ST-1: Drop this ST's own change buffer (if it had downstream consumers).
     {
         let change_schema = config::pg_trickle_change_buffer_schema();
@@ -5141,6 +5187,9 @@ fn execute_manual_full_refresh(
         }
     } else if let Some(ua_sql) = crate::dvm::try_union_all_refresh_sql(&st.defining_query) {
         ua_sql
+    } else if let Some(row_id_sql) = crate::dvm::grouping_sets_row_id_sql(&st.defining_query) {
+        // GSETS-NATIVE: the row ID hashes GROUPING(…) inside the query.
+        inject_row_id(&effective_query, &row_id_sql)
     } else {
         let row_id_expr = crate::dvm::row_id_expr_for_query(&st.defining_query);
         format!("SELECT {row_id_expr} AS __pgt_row_id, sub.* FROM ({effective_query}) sub",)
@@ -5154,6 +5203,9 @@ fn execute_manual_full_refresh(
         Ok::<usize, PgTrickleError>(result.len())
     })?;
 
+    // MINMAX-MS / ORDSET-HIST: Rebuild (or drop) the aggregate side tables.
+    refresh::rebuild_agg_side_tables(st)?;
+
     // Re-enable user triggers and emit NOTIFY so listeners know a FULL
     // refresh occurred.
     if has_triggers {
@@ -5300,7 +5352,8 @@ fn execute_manual_differential_refresh(
     }
 
     // Get current WAL positions for non-ST sources (reuses source_oids — G-N3)
-    let slot_positions = cdc::get_slot_positions(source_oids)?;
+    let mut slot_positions = cdc::get_slot_positions(source_oids)?;
+    wal_decoder::receiver::cap_to_flushed_lsn(st.pgt_id, &mut slot_positions, &prev_frontier);
     let data_ts = get_data_timestamp_str();
     let new_frontier = version::compute_new_frontier(&slot_positions, &data_ts);
 
@@ -6025,7 +6078,7 @@ fn explain_stream_table(name: &str) -> Result<String, PgTrickleError> {
             if capture_mode == crate::config::CdcCaptureMode::Discard {
                 "changes are DISCARDED; reinitialize after un-pausing"
             } else {
-                "changes are HELD in buffer"
+                "changes are HELD in buffer; scheduled refreshes are deferred"
             }
         )
     } else {
@@ -6324,8 +6377,9 @@ fn cdc_pause_status() -> TableIterator<
                     .to_string()
             }
             crate::config::CdcCaptureMode::Hold => {
-                "CDC is PAUSED in HOLD mode (reserved). Falling back to DISCARD semantics. \
-                 Changes arriving now are being DROPPED."
+                "CDC is PAUSED in HOLD mode — changes are still captured into the change \
+                 buffers, but the scheduler is not refreshing stream tables. Refreshes \
+                 resume differentially once cdc_paused is turned off."
                     .to_string()
             }
         }
@@ -6449,90 +6503,39 @@ fn bulk_drop_stream_tables(names: Vec<Option<String>>) -> i32 {
 
 // ── v0.36.0: CREATE STREAM TABLE SQL syntax (F11) ─────────────────────────
 
-/// F11 (v0.36.0): Execute a `CREATE [OR REPLACE] STREAM TABLE` SQL statement.
+/// F11 (v0.36.0): Execute a stream-table DDL statement given as text.
 ///
-/// Parses the custom `CREATE STREAM TABLE name AS SELECT ...` syntax (which
-/// PostgreSQL's parser does not understand natively) and translates it to a
-/// `pgtrickle.create_stream_table()` call.
+/// Parses the custom `STREAM TABLE` syntax (which PostgreSQL's parser does
+/// not understand natively) and translates it to the matching function-API
+/// call. F11b (v0.49.0): options, ALTER and REFRESH are supported; see
+/// [`stream_ddl`] for the grammar.
 ///
 /// Supported syntax variants:
 /// ```sql
-/// CREATE STREAM TABLE my_st AS SELECT ...;
-/// CREATE OR REPLACE STREAM TABLE my_st AS SELECT ...;
-/// DROP STREAM TABLE my_st;
+/// CREATE [OR REPLACE] STREAM TABLE [IF NOT EXISTS] name
+///     [WITH (schedule = '1m', refresh_mode = 'DIFFERENTIAL', ...)]
+///     AS SELECT ...;
+/// ALTER STREAM TABLE [IF EXISTS] name SET (schedule = '5m', ...);
+/// REFRESH STREAM TABLE name;
+/// DROP STREAM TABLE [IF EXISTS] name [, ...] [CASCADE | RESTRICT];
 /// ```
 ///
-/// All `create_stream_table()` options default to their standard defaults.
-/// For full control, call `pgtrickle.create_stream_table()` directly.
+/// `WITH` options map one-to-one onto the parameters of
+/// `create_stream_table()`; `SET` options onto `alter_stream_table()`.
 ///
 /// # Example
 /// ```sql
 /// SELECT pgtrickle.exec_stream_ddl(
-///   'CREATE STREAM TABLE revenue AS SELECT SUM(amount) FROM orders'
+///   'CREATE STREAM TABLE revenue WITH (schedule = ''1m'') AS SELECT SUM(amount) FROM orders'
 /// );
 /// ```
 #[pg_extern(schema = "pgtrickle")]
 fn exec_stream_ddl(cmd: &str) -> bool {
-    let cmd = cmd.trim();
-    let upper = cmd.to_uppercase();
-
-    // ── DROP STREAM TABLE ──
-    if upper.starts_with("DROP STREAM TABLE") {
-        let rest = cmd[17..].trim(); // after "DROP STREAM TABLE"
-        let name = rest.trim_end_matches(';').trim();
-        if name.is_empty() {
-            pgrx::error!("exec_stream_ddl: missing table name in DROP STREAM TABLE");
-        }
-        // Use $1 parameterized call to avoid SQL injection.
-        Spi::run_with_args("SELECT pgtrickle.drop_stream_table($1)", &[name.into()])
-            .unwrap_or_else(|e| pgrx::error!("exec_stream_ddl DROP: {}", e));
-        return true;
-    }
-
-    // ── CREATE [OR REPLACE] STREAM TABLE ──
-    let (or_replace, rest) = if upper.starts_with("CREATE OR REPLACE STREAM TABLE ") {
-        (true, &cmd[31..]) // after "CREATE OR REPLACE STREAM TABLE "
-    } else if upper.starts_with("CREATE STREAM TABLE ") {
-        (false, &cmd[20..]) // after "CREATE STREAM TABLE "
-    } else {
-        pgrx::error!(
-            "exec_stream_ddl: unrecognised syntax. Expected \
-             'CREATE [OR REPLACE] STREAM TABLE name AS SELECT ...' \
-             or 'DROP STREAM TABLE name'."
-        );
-    };
-
-    // Split name from "AS SELECT ..."
-    let upper_rest = rest.to_uppercase();
-    let as_idx = upper_rest.find(" AS ").unwrap_or_else(|| {
-        pgrx::error!(
-            "exec_stream_ddl: missing AS keyword. Syntax: CREATE STREAM TABLE name AS SELECT ..."
-        )
-    });
-    let name = rest[..as_idx].trim();
-    let query = rest[as_idx + 4..].trim().trim_end_matches(';').trim();
-
-    if name.is_empty() {
-        pgrx::error!("exec_stream_ddl: missing table name");
-    }
-    if query.is_empty() {
-        pgrx::error!("exec_stream_ddl: missing query after AS");
-    }
-
-    if or_replace {
-        Spi::run_with_args(
-            "SELECT pgtrickle.create_or_replace_stream_table($1, $2)",
-            &[name.into(), query.into()],
-        )
-        .unwrap_or_else(|e| pgrx::error!("exec_stream_ddl CREATE OR REPLACE: {}", e));
-    } else {
-        Spi::run_with_args(
-            "SELECT pgtrickle.create_stream_table($1, $2)",
-            &[name.into(), query.into()],
-        )
-        .unwrap_or_else(|e| pgrx::error!("exec_stream_ddl CREATE: {}", e));
+    let result =
+        stream_ddl::parse_stream_ddl(cmd).and_then(|(_, ddl)| stream_ddl::execute_stream_ddl(&ddl));
+    if let Err(e) = result {
+        raise_error_with_context(e);
     }
-
     true
 }
 
@@ -6629,6 +6632,8 @@ pub(crate) mod metrics_ext;
 pub(crate) mod planner;
 mod self_monitoring;
 pub(crate) mod snapshot;
+pub(crate) mod stream_ddl;
+pub(crate) mod temporal;
 
 // Re-export public items from sub-modules so external callers are unaffected.
 pub use helpers::*;
@@ -6692,6 +6697,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_inject_row_id_after_top_level_select() {
+        let query = "WITH s AS (SELECT * FROM sales) SELECT region, SUM(amount) \
+                     FROM s GROUP BY ROLLUP(region)";
+        let result = inject_row_id(query, "h(GROUPING(region))");
+        assert_eq!(
+            result,
+            "WITH s AS (SELECT * FROM sales) SELECT h(GROUPING(region)) AS __pgt_row_id, \
+             region, SUM(amount) FROM s GROUP BY ROLLUP(region)"
+        );
+    }
+
     #[test]
     fn test_detect_and_strip_distinct_none_for_non_distinct() {
         let query = "SELECT color, size FROM prop_dist";
@@ -0,0 +1,976 @@
+//! F11b (v0.49.0): DDL-style stream table commands.
+//!
+//! Parses the stream-table DDL accepted by `pgtrickle.exec_stream_ddl()` and
+//! by the `ProcessUtility` hook in `hooks.rs`, and maps each statement onto
+//! the function API (`create_stream_table`, `alter_stream_table`,
+//! `refresh_stream_table`, `drop_stream_table`).
+//!
+//! PostgreSQL's grammar is not extensible, so `CREATE STREAM TABLE` typed
+//! directly into psql is rejected by the parser before any hook runs. The
+//! hook therefore intercepts the materialized-view spelling, which parses
+//! natively and carries options in a `WITH (pgtrickle.*)` clause:
+//!
+//! ```sql
+//! CREATE MATERIALIZED VIEW order_totals
+//!   WITH (pgtrickle.schedule = '1m', pgtrickle.refresh_mode = 'DIFFERENTIAL')
+//!   AS SELECT region, SUM(amount) FROM orders GROUP BY region;
+//! ALTER MATERIALIZED VIEW order_totals SET (pgtrickle.schedule = '5m');
+//! REFRESH MATERIALIZED VIEW order_totals;
+//! DROP MATERIALIZED VIEW IF EXISTS order_totals CASCADE;
+//! ```
+//!
+//! The `STREAM TABLE` spelling of the same statements is accepted by
+//! `exec_stream_ddl()` for migration tooling that stores DDL as text.
+
+use pgrx::prelude::*;
+
+use crate::error::PgTrickleError;
+
+// ── Parsed statement ───────────────────────────────────────────────────────
+
+/// Which object keyword the statement used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DdlSpelling {
+    /// `... STREAM TABLE ...` (via `exec_stream_ddl()`).
+    StreamTable,
+    /// `... MATERIALIZED VIEW ...` (via the ProcessUtility hook).
+    MaterializedView,
+}
+
+/// A possibly schema-qualified relation name, with identifier case folding
+/// already applied (unquoted identifiers are lower-cased).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DdlName {
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl DdlName {
+    /// Name in the `schema.table` form expected by the function API.
+    pub fn api_name(&self) -> String {
+        match &self.schema {
+            Some(s) => format!("{}.{}", s, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Properly quoted name, suitable for `to_regclass()`.
+    pub fn quoted(&self) -> String {
+        let q = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+        match &self.schema {
+            Some(s) => format!("{}.{}", q(s), q(&self.name)),
+            None => q(&self.name),
+        }
+    }
+}
+
+/// One `key = value` entry from a `WITH (...)` / `SET (...)` option list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DdlOption {
+    pub key: String,
+    pub value: String,
+}
+
+/// A parsed stream-table DDL statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StreamDdl {
+    Create {
+        or_replace: bool,
+        if_not_exists: bool,
+        name: DdlName,
+        options: Vec<DdlOption>,
+        query: String,
+    },
+    Alter {
+        if_exists: bool,
+        name: DdlName,
+        options: Vec<DdlOption>,
+    },
+    Refresh {
+        name: DdlName,
+    },
+    Drop {
+        if_exists: bool,
+        names: Vec<DdlName>,
+        cascade: bool,
+    },
+}
+
+/// Options accepted by `CREATE ... STREAM TABLE`, with the SQL type each
+/// value is cast to when calling `create_stream_table()`.
+const CREATE_OPTIONS: &[(&str, &str)] = &[
+    ("schedule", "text"),
+    ("refresh_mode", "text"),
+    ("initialize", "bool"),
+    ("diamond_consistency", "text"),
+    ("diamond_schedule_policy", "text"),
+    ("cdc_mode", "text"),
+    ("append_only", "bool"),
+    ("pooler_compatibility_mode", "bool"),
+    ("partition_by", "text"),
+    ("max_differential_joins", "int4"),
+    ("max_delta_fraction", "float8"),
+    ("output_distribution_column", "text"),
+    ("temporal", "bool"),
+    ("storage_backend", "text"),
+];
+
+/// Options accepted by `ALTER ... STREAM TABLE ... SET (...)`.
+const ALTER_OPTIONS: &[(&str, &str)] = &[
+    ("query", "text"),
+    ("schedule", "text"),
+    ("refresh_mode", "text"),
+    ("status", "text"),
+    ("diamond_consistency", "text"),
+    ("diamond_schedule_policy", "text"),
+    ("cdc_mode", "text"),
+    ("append_only", "bool"),
+    ("pooler_compatibility_mode", "bool"),
+    ("tier", "text"),
+    ("fuse", "text"),
+    ("fuse_ceiling", "int8"),
+    ("fuse_sensitivity", "int4"),
+    ("partition_by", "text"),
+    ("max_differential_joins", "int4"),
+    ("max_delta_fraction", "float8"),
+    ("post_refresh_action", "text"),
+    ("reindex_drift_threshold", "float8"),
+];
+
+/// Namespace prefix for options on the materialized-view spelling.
+const OPTION_NAMESPACE: &str = "pgtrickle";
+
+// ── Lexer ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    /// Unquoted identifier or keyword, lower-cased.
+    Word(String),
+    /// Double-quoted identifier, verbatim.
+    Ident(String),
+    /// Single-quoted string literal, unescaped.
+    Str(String),
+    /// Numeric literal (optionally signed).
+    Num(String),
+    Punct(char),
+}
+
+/// A minimal SQL lexer — enough to read statement headers and option
+/// lists. The defining query after `AS` is sliced verbatim from the input
+/// and never tokenized.
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Lexer { src, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("--") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let end = trimmed
+                    .find("*/")
+                    .ok_or_else(|| "unterminated /* comment".to_string())?;
+                self.pos += end + 2;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Tok>, String> {
+        self.skip_trivia()?;
+        let rest = self.rest();
+        let Some(c) = rest.chars().next() else {
+            return Ok(None);
+        };
+
+        if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+                .unwrap_or(rest.len());
+            self.pos += len;
+            return Ok(Some(Tok::Word(rest[..len].to_lowercase())));
+        }
+
+        if c == '"' || c == '\'' {
+            let mut out = String::new();
+            let mut chars = rest.char_indices().skip(1).peekable();
+            while let Some((i, ch)) = chars.next() {
+                if ch == c {
+                    if matches!(chars.peek(), Some((_, n)) if *n == c) {
+                        out.push(c);
+                        chars.next();
+                        continue;
+                    }
+                    self.pos += i + 1;
+                    return Ok(Some(if c == '"' {
+                        Tok::Ident(out)
+                    } else {
+                        Tok::Str(out)
+                    }));
+                }
+                out.push(ch);
+            }
+            return Err(format!(
+                "unterminated quoted {}",
+                if c == '"' { "identifier" } else { "string" }
+            ));
+        }
+
+        if c.is_ascii_digit()
+            || ((c == '-' || c == '+' || c == '.')
+                && rest[1..].starts_with(|ch: char| ch.is_ascii_digit()))
+        {
+            let len = rest[1..]
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '.'))
+                .map_or(rest.len(), |n| n + 1);
+            self.pos += len;
+            return Ok(Some(Tok::Num(rest[..len].to_string())));
+        }
+
+        self.pos += c.len_utf8();
+        Ok(Some(Tok::Punct(c)))
+    }
+
+    fn peek(&mut self) -> Result<Option<Tok>, String> {
+        let saved = self.pos;
+        let tok = self.next();
+        self.pos = saved;
+        tok
+    }
+
+    /// Consume the next token if it is the keyword `kw`.
+    fn eat_word(&mut self, kw: &str) -> Result<bool, String> {
+        if matches!(self.peek()?, Some(Tok::Word(ref w)) if w == kw) {
+            self.next()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn expect_word(&mut self, kw: &str) -> Result<(), String> {
+        if self.eat_word(kw)? {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected {} but found {}",
+                kw.to_uppercase(),
+                describe(self.peek()?.as_ref())
+            ))
+        }
+    }
+
+    fn eat_punct(&mut self, p: char) -> Result<bool, String> {
+        if self.peek()? == Some(Tok::Punct(p)) {
+            self.next()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn expect_punct(&mut self, p: char) -> Result<(), String> {
+        if self.eat_punct(p)? {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected \"{}\" but found {}",
+                p,
+                describe(self.peek()?.as_ref())
+            ))
+        }
+    }
+
+    /// True when only whitespace, comments and semicolons remain.
+    fn at_end(&mut self) -> Result<bool, String> {
+        while self.eat_punct(';')? {}
+        Ok(self.peek()?.is_none())
+    }
+}
+
+fn describe(tok: Option<&Tok>) -> String {
+    match tok {
+        None => "end of input".to_string(),
+        Some(Tok::Word(w)) => format!("\"{w}\""),
+        Some(Tok::Ident(i)) => format!("\"{i}\""),
+        Some(Tok::Str(s)) => format!("'{s}'"),
+        Some(Tok::Num(n)) => n.clone(),
+        Some(Tok::Punct(p)) => format!("\"{p}\""),
+    }
+}
+
+// ── Parser ─────────────────────────────────────────────────────────────────
+
+/// Parse a stream-table DDL statement in either spelling.
+pub(crate) fn parse_stream_ddl(sql: &str) -> Result<(DdlSpelling, StreamDdl), PgTrickleError> {
+    parse_inner(sql).map_err(|e| PgTrickleError::QueryParseError(format!("stream table DDL: {e}")))
+}
+
+fn parse_inner(sql: &str) -> Result<(DdlSpelling, StreamDdl), String> {
+    let mut lx = Lexer::new(sql);
+    let verb = match lx.next()? {
+        Some(Tok::Word(w)) => w,
+        other => {
+            return Err(format!(
+                "expected a command but found {}",
+                describe(other.as_ref())
+            ));
+        }
+    };
+
+    match verb.as_str() {
+        "create" => {
+            let or_replace = if lx.eat_word("or")? {
+                lx.expect_word("replace")?;
+                true
+            } else {
+                false
+            };
+            let spelling = parse_object_kind(&mut lx)?;
+            let if_not_exists = if lx.eat_word("if")? {
+                lx.expect_word("not")?;
+                lx.expect_word("exists")?;
+                true
+            } else {
+                false
+            };
+            let name = parse_name(&mut lx)?;
+            if lx.peek()? == Some(Tok::Punct('(')) {
+                return Err("a column list is not supported; name the columns in the query".into());
+            }
+            let mut options = Vec::new();
+            if lx.eat_word("with")? {
+                options = parse_options(&mut lx, spelling)?;
+            }
+            lx.expect_word("as")?;
+            let query = match spelling {
+                DdlSpelling::StreamTable => trim_statement_tail(lx.rest()),
+                DdlSpelling::MaterializedView => strip_with_data(trim_statement_tail(lx.rest())),
+            };
+            if query.is_empty() {
+                return Err("missing query after AS".into());
+            }
+
+            // The materialized-view grammar has no OR REPLACE; accept it as
+            // an option instead.
+            let mut or_replace = or_replace;
+            options.retain(|o| {
+                if o.key == "replace" {
+                    or_replace = parse_bool(&o.value).unwrap_or(false);
+                    false
+                } else {
+                    o.key != "stream"
+                }
+            });
+
+            Ok((
+                spelling,
+                StreamDdl::Create {
+                    or_replace,
+                    if_not_exists,
+                    name,
+                    options,
+                    query: query.to_string(),
+                },
+            ))
+        }
+        "alter" => {
+            let spelling = parse_object_kind(&mut lx)?;
+            let if_exists = parse_if_exists(&mut lx)?;
+            let name = parse_name(&mut lx)?;
+            lx.expect_word("set")?;
+            let options = parse_options(&mut lx, spelling)?;
+            if !lx.at_end()? {
+                return Err(format!(
+                    "unexpected {} after SET (...)",
+                    describe(lx.peek()?.as_ref())
+                ));
+            }
+            Ok((
+                spelling,
+                StreamDdl::Alter {
+                    if_exists,
+                    name,
+                    options,
+                },
+            ))
+        }
+        "refresh" => {
+            let spelling = parse_object_kind(&mut lx)?;
+            // CONCURRENTLY is accepted and ignored: stream table refreshes
+            // never block readers.
+            lx.eat_word("concurrently")?;
+            let name = parse_name(&mut lx)?;
+            if lx.eat_word("with")? {
+                if lx.eat_word("no")? {
+                    return Err(
+                        "REFRESH ... WITH NO DATA is not supported for stream tables".into(),
+                    );
+                }
+                lx.expect_word("data")?;
+            }
+            if !lx.at_end()? {
+                return Err(format!("unexpected {}", describe(lx.peek()?.as_ref())));
+            }
+            Ok((spelling, StreamDdl::Refresh { name }))
+        }
+        "drop" => {
+            let spelling = parse_object_kind(&mut lx)?;
+            let if_exists = parse_if_exists(&mut lx)?;
+            let mut names = vec![parse_name(&mut lx)?];
+            while lx.eat_punct(',')? {
+                names.push(parse_name(&mut lx)?);
+            }
+            let cascade = if lx.eat_word("cascade")? {
+                true
+            } else {
+                lx.eat_word("restrict")?;
+                false
+            };
+            if !lx.at_end()? {
+                return Err(format!("unexpected {}", describe(lx.peek()?.as_ref())));
+            }
+            Ok((
+                spelling,
+                StreamDdl::Drop {
+                    if_exists,
+                    names,
+                    cascade,
+                },
+            ))
+        }
+        other => Err(format!(
+            "unsupported command \"{}\"; expected CREATE, ALTER, REFRESH or DROP",
+            other.to_uppercase()
+        )),
+    }
+}
+
+fn parse_object_kind(lx: &mut Lexer<'_>) -> Result<DdlSpelling, String> {
+    if lx.eat_word("stream")? {
+        lx.expect_word("table")?;
+        Ok(DdlSpelling::StreamTable)
+    } else if lx.eat_word("materialized")? {
+        lx.expect_word("view")?;
+        Ok(DdlSpelling::MaterializedView)
+    } else {
+        Err(format!(
+            "expected STREAM TABLE but found {}",
+            describe(lx.peek()?.as_ref())
+        ))
+    }
+}
+
+fn parse_if_exists(lx: &mut Lexer<'_>) -> Result<bool, String> {
+    if lx.eat_word("if")? {
+        lx.expect_word("exists")?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+fn parse_ident(lx: &mut Lexer<'_>) -> Result<String, String> {
+    match lx.next()? {
+        Some(Tok::Word(w)) => Ok(w),
+        Some(Tok::Ident(i)) if !i.is_empty() => Ok(i),
+        other => Err(format!(
+            "expected a name but found {}",
+            describe(other.as_ref())
+        )),
+    }
+}
+
+fn parse_name(lx: &mut Lexer<'_>) -> Result<DdlName, String> {
+    let first = parse_ident(lx)?;
+    if lx.eat_punct('.')? {
+        let second = parse_ident(lx)?;
+        if lx.peek()? == Some(Tok::Punct('.')) {
+            return Err("cross-database references are not supported".into());
+        }
+        return Ok(DdlName {
+            schema: Some(first),
+            name: second,
+        });
+    }
+    Ok(DdlName {
+        schema: None,
+        name: first,
+    })
+}
+
+/// Parse `( key [= value] [, ...] )`.
+///
+/// On the materialized-view spelling every key must carry the `pgtrickle.`
+/// namespace; on the stream-table spelling the namespace is optional.
+fn parse_options(lx: &mut Lexer<'_>, spelling: DdlSpelling) -> Result<Vec<DdlOption>, String> {
+    lx.expect_punct('(')?;
+    let mut options: Vec<DdlOption> = Vec::new();
+    loop {
+        let first = parse_ident(lx)?;
+        let (namespace, key) = if lx.eat_punct('.')? {
+            (Some(first), parse_ident(lx)?)
+        } else {
+            (None, first)
+        };
+        match (spelling, namespace.as_deref()) {
+            (_, Some(OPTION_NAMESPACE)) | (DdlSpelling::StreamTable, None) => {}
+            (_, Some(ns)) => {
+                return Err(format!(
+                    "option \"{ns}.{key}\" is not supported on a stream table; \
+                     only {OPTION_NAMESPACE}.* options are allowed"
+                ));
+            }
+            (DdlSpelling::MaterializedView, None) => {
+                return Err(format!(
+                    "option \"{key}\" is not supported on a stream table; \
+                     use {OPTION_NAMESPACE}.{key}"
+                ));
+            }
+        }
+
+        let value = if lx.eat_punct('=')? {
+            match lx.next()? {
+                Some(Tok::Str(s)) | Some(Tok::Num(s)) | Some(Tok::Word(s))
+                | Some(Tok::Ident(s)) => s,
+                other => {
+                    return Err(format!(
+                        "expected a value for option \"{key}\" but found {}",
+                        describe(other.as_ref())
+                    ));
+                }
+            }
+        } else {
+            "true".to_string()
+        };
+
+        if options.iter().any(|o| o.key == key) {
+            return Err(format!("option \"{key}\" specified more than once"));
+        }
+        options.push(DdlOption { key, value });
+
+        if lx.eat_punct(')')? {
+            return Ok(options);
+        }
+        lx.expect_punct(',')?;
+    }
+}
+
+/// Trim whitespace and trailing semicolons from the statement tail.
+fn trim_statement_tail(s: &str) -> &str {
+    s.trim().trim_end_matches(';').trim_end()
+}
+
+/// Remove a trailing `WITH [NO] DATA` clause from a materialized-view query.
+fn strip_with_data(query: &str) -> &str {
+    let upper = query.to_ascii_uppercase();
+    let words: Vec<&str> = upper.split_whitespace().collect();
+    if matches!(
+        words.as_slice(),
+        [.., "WITH", "NO", "DATA"] | [.., "WITH", "DATA"]
+    ) && let Some(cut) = upper.rfind("WITH")
+    {
+        return query[..cut].trim_end();
+    }
+    query
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" | "t" => Some(true),
+        "false" | "off" | "no" | "0" | "f" => Some(false),
+        _ => None,
+    }
+}
+
+// ── Function-API mapping ───────────────────────────────────────────────────
+
+/// Build a named-argument call `SELECT pgtrickle.<func>(...)` with every
+/// value passed as a text parameter and cast to its declared type.
+///
+/// Returns the SQL text and the positional parameter values.
+fn build_call(
+    func: &str,
+    fixed: &[(&str, String)],
+    options: &[DdlOption],
+    allowed: &[(&str, &str)],
+) -> Result<(String, Vec<String>), String> {
+    let mut args = Vec::new();
+    let mut params = Vec::new();
+    for (key, value) in fixed {
+        params.push(value.clone());
+        args.push(format!("{key} => ${}", params.len()));
+    }
+    for opt in options {
+        let Some((_, ty)) = allowed.iter().find(|(k, _)| *k == opt.key) else {
+            let names: Vec<&str> = allowed.iter().map(|(k, _)| *k).collect();
+            return Err(format!(
+                "unknown option \"{}\" (valid options: {})",
+                opt.key,
+                names.join(", ")
+            ));
+        };
+        params.push(opt.value.clone());
+        args.push(format!("{} => ${}::{}", opt.key, params.len(), ty));
+    }
+    Ok((
+        format!("SELECT pgtrickle.{func}({})", args.join(", ")),
+        params,
+    ))
+}
+
+/// Map a parsed statement to one or more function-API calls.
+fn to_calls(ddl: &StreamDdl) -> Result<Vec<(String, Vec<String>)>, String> {
+    match ddl {
+        StreamDdl::Create {
+            or_replace,
+            if_not_exists,
+            name,
+            options,
+            query,
+        } => {
+            if *or_replace && *if_not_exists {
+                return Err("OR REPLACE and IF NOT EXISTS cannot be combined".into());
+            }
+            let func = if *or_replace {
+                "create_or_replace_stream_table"
+            } else if *if_not_exists {
+                "create_stream_table_if_not_exists"
+            } else {
+                "create_stream_table"
+            };
+            Ok(vec![build_call(
+                func,
+                &[("name", name.api_name()), ("query", query.clone())],
+                options,
+                CREATE_OPTIONS,
+            )?])
+        }
+        StreamDdl::Alter { name, options, .. } => {
+            if options.is_empty() {
+                return Err("SET (...) requires at least one option".into());
+            }
+            Ok(vec![build_call(
+                "alter_stream_table",
+                &[("name", name.api_name())],
+                options,
+                ALTER_OPTIONS,
+            )?])
+        }
+        StreamDdl::Refresh { name } => Ok(vec![build_call(
+            "refresh_stream_table",
+            &[("name", name.api_name())],
+            &[],
+            &[],
+        )?]),
+        StreamDdl::Drop { names, cascade, .. } => names
+            .iter()
+            .map(|n| {
+                build_call(
+                    "drop_stream_table",
+                    &[("name", n.api_name())],
+                    &[DdlOption {
+                        key: "cascade".into(),
+                        value: cascade.to_string(),
+                    }],
+                    &[("cascade", "bool")],
+                )
+            })
+            .collect(),
+    }
+}
+
+// ── Execution ──────────────────────────────────────────────────────────────
+
+/// Return true if `name` resolves to a registered stream table.
+pub(crate) fn is_stream_table(name: &DdlName) -> Result<bool, PgTrickleError> {
+    Spi::get_one_with_args::<bool>(
+        "SELECT EXISTS (\
+             SELECT 1 FROM pgtrickle.pgt_stream_tables \
+             WHERE pgt_relid = to_regclass($1)\
+         )",
+        &[name.quoted().as_str().into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))
+    .map(|v| v.unwrap_or(false))
+}
+
+/// Execute a parsed stream-table DDL statement via the function API.
+pub(crate) fn execute_stream_ddl(ddl: &StreamDdl) -> Result<(), PgTrickleError> {
+    // IF EXISTS: skip missing objects with a NOTICE, like PostgreSQL does.
+    let ddl = match ddl {
+        StreamDdl::Alter {
+            if_exists: true,
+            name,
+            ..
+        } if !is_stream_table(name)? => {
+            pgrx::notice!(
+                "stream table \"{}\" does not exist, skipping",
+                name.api_name()
+            );
+            return Ok(());
+        }
+        StreamDdl::Drop {
+            if_exists: true,
+            names,
+            cascade,
+        } => {
+            let mut present = Vec::new();
+            for n in names {
+                if is_stream_table(n)? {
+                    present.push(n.clone());
+                } else {
+                    pgrx::notice!("stream table \"{}\" does not exist, skipping", n.api_name());
+                }
+            }
+            &StreamDdl::Drop {
+                if_exists: true,
+                names: present,
+                cascade: *cascade,
+            }
+        }
+        other => other,
+    };
+
+    for (sql, params) in to_calls(ddl).map_err(PgTrickleError::InvalidArgument)? {
+        let args: Vec<pgrx::datum::DatumWithOid> =
+            params.iter().map(|p| p.as_str().into()).collect();
+        Spi::run_with_args(&sql, &args).map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(sql: &str) -> (DdlSpelling, StreamDdl) {
+        parse_inner(sql).unwrap_or_else(|e| panic!("parse failed for {sql:?}: {e}"))
+    }
+
+    fn name(schema: Option<&str>, n: &str) -> DdlName {
+        DdlName {
+            schema: schema.map(str::to_string),
+            name: n.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_create_stream_table_with_options() {
+        let (spelling, ddl) = parse(
+            "CREATE STREAM TABLE public.Revenue WITH (schedule = '1m', refresh_mode = DIFFERENTIAL, \
+             append_only) AS SELECT region, SUM(amount) AS total FROM orders GROUP BY region;",
+        );
+        assert_eq!(spelling, DdlSpelling::StreamTable);
+        assert_eq!(
+            ddl,
+            StreamDdl::Create {
+                or_replace: false,
+                if_not_exists: false,
+                name: name(Some("public"), "revenue"),
+                options: vec![
+                    DdlOption {
+                        key: "schedule".into(),
+                        value: "1m".into()
+                    },
+                    DdlOption {
+                        key: "refresh_mode".into(),
+                        value: "differential".into()
+                    },
+                    DdlOption {
+                        key: "append_only".into(),
+                        value: "true".into()
+                    },
+                ],
+                query: "SELECT region, SUM(amount) AS total FROM orders GROUP BY region".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_create_or_replace_keeps_query_verbatim() {
+        let (_, ddl) =
+            parse("create or replace stream table \"My ST\" as select ' AS ' as x, $$ ; $$ from t");
+        let StreamDdl::Create {
+            or_replace,
+            name: n,
+            query,
+            ..
+        } = ddl
+        else {
+            panic!("expected Create");
+        };
+        assert!(or_replace);
+        assert_eq!(n, name(None, "My ST"));
+        assert_eq!(query, "select ' AS ' as x, $$ ; $$ from t");
+    }
+
+    #[test]
+    fn test_parse_materialized_view_spelling() {
+        let (spelling, ddl) = parse(
+            "CREATE MATERIALIZED VIEW IF NOT EXISTS s.mv \
+             WITH (pgtrickle.stream, pgtrickle.schedule = '30s', pgtrickle.replace = true) \
+             AS SELECT 1 AS a FROM t WITH NO DATA",
+        );
+        assert_eq!(spelling, DdlSpelling::MaterializedView);
+        let StreamDdl::Create {
+            or_replace,
+            if_not_exists,
+            options,
+            query,
+            ..
+        } = ddl
+        else {
+            panic!("expected Create");
+        };
+        assert!(or_replace);
+        assert!(if_not_exists);
+        assert_eq!(
+            options,
+            vec![DdlOption {
+                key: "schedule".into(),
+                value: "30s".into()
+            }]
+        );
+        assert_eq!(query, "SELECT 1 AS a FROM t");
+    }
+
+    #[test]
+    fn test_parse_materialized_view_rejects_foreign_options() {
+        let err = parse_inner("CREATE MATERIALIZED VIEW mv WITH (fillfactor = 70) AS SELECT 1")
+            .unwrap_err();
+        assert!(err.contains("pgtrickle.fillfactor"), "{err}");
+        let err =
+            parse_inner("CREATE MATERIALIZED VIEW mv WITH (timescaledb.continuous) AS SELECT 1")
+                .unwrap_err();
+        assert!(err.contains("only pgtrickle.*"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_alter_refresh_drop() {
+        let (_, ddl) =
+            parse("ALTER STREAM TABLE IF EXISTS st SET (schedule = '5m', fuse_ceiling = 1000)");
+        assert_eq!(
+            ddl,
+            StreamDdl::Alter {
+                if_exists: true,
+                name: name(None, "st"),
+                options: vec![
+                    DdlOption {
+                        key: "schedule".into(),
+                        value: "5m".into()
+                    },
+                    DdlOption {
+                        key: "fuse_ceiling".into(),
+                        value: "1000".into()
+                    },
+                ],
+            }
+        );
+
+        let (_, ddl) = parse("REFRESH MATERIALIZED VIEW CONCURRENTLY a.b");
+        assert_eq!(
+            ddl,
+            StreamDdl::Refresh {
+                name: name(Some("a"), "b")
+            }
+        );
+
+        let (_, ddl) = parse("DROP STREAM TABLE IF EXISTS a, b.c CASCADE;");
+        assert_eq!(
+            ddl,
+            StreamDdl::Drop {
+                if_exists: true,
+                names: vec![name(None, "a"), name(Some("b"), "c")],
+                cascade: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_inner("CREATE STREAM TABLE st SELECT 1").is_err());
+        assert!(parse_inner("CREATE STREAM TABLE st (a int) AS SELECT 1").is_err());
+        assert!(parse_inner("CREATE STREAM TABLE st AS ;").is_err());
+        assert!(parse_inner("TRUNCATE STREAM TABLE st").is_err());
+        assert!(parse_inner("DROP STREAM TABLE st extra").is_err());
+        assert!(
+            parse_inner("ALTER STREAM TABLE st SET (schedule = '1m', schedule = '2m')").is_err()
+        );
+        assert!(parse_inner("REFRESH STREAM TABLE st WITH NO DATA").is_err());
+        assert!(parse_inner("DROP STREAM TABLE 'st'").is_err());
+    }
+
+    #[test]
+    fn test_to_calls_create_casts_typed_options() {
+        let (_, ddl) = parse(
+            "CREATE STREAM TABLE st WITH (schedule = '1m', initialize = false, max_delta_fraction = 0.25) \
+             AS SELECT 1",
+        );
+        let calls = to_calls(&ddl).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0].0,
+            "SELECT pgtrickle.create_stream_table(name => $1, query => $2, schedule => $3::text, \
+             initialize => $4::bool, max_delta_fraction => $5::float8)"
+        );
+        assert_eq!(calls[0].1, vec!["st", "SELECT 1", "1m", "false", "0.25"]);
+    }
+
+    #[test]
+    fn test_to_calls_rejects_unknown_and_misplaced_options() {
+        let (_, ddl) = parse("CREATE STREAM TABLE st WITH (tier = 'hot') AS SELECT 1");
+        let err = to_calls(&ddl).unwrap_err();
+        assert!(err.contains("unknown option \"tier\""), "{err}");
+
+        let (_, ddl) = parse("ALTER STREAM TABLE st SET (temporal = true)");
+        assert!(to_calls(&ddl).is_err());
+    }
+
+    #[test]
+    fn test_to_calls_drop_and_refresh() {
+        let (_, ddl) = parse("DROP MATERIALIZED VIEW a, s.b");
+        let calls = to_calls(&ddl).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            calls[1].0,
+            "SELECT pgtrickle.drop_stream_table(name => $1, cascade => $2::bool)"
+        );
+        assert_eq!(calls[1].1, vec!["s.b", "false"]);
+
+        let (_, ddl) = parse("REFRESH STREAM TABLE s.b");
+        assert_eq!(
+            to_calls(&ddl).unwrap()[0].0,
+            "SELECT pgtrickle.refresh_stream_table(name => $1)"
+        );
+    }
+
+    #[test]
+    fn test_ddl_name_quoting() {
+        let n = name(Some("my schema"), "we\"ird");
+        assert_eq!(n.quoted(), "\"my schema\".\"we\"\"ird\"");
+        assert_eq!(n.api_name(), "my schema.we\"ird");
+    }
+
+    #[test]
+    fn test_strip_with_data_variants() {
+        assert_eq!(strip_with_data("SELECT 1 WITH DATA"), "SELECT 1");
+        assert_eq!(strip_with_data("SELECT 1\n  WITH  NO\tDATA"), "SELECT 1");
+        assert_eq!(strip_with_data("SELECT (1) WITH DATA"), "SELECT (1)");
+        assert_eq!(
+            strip_with_data("SELECT with_data FROM t"),
+            "SELECT with_data FROM t"
+        );
+    }
+}
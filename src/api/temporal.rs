@@ -0,0 +1,285 @@
+//! CORR-1 (v0.49.0): Point-in-time and history queries for temporal stream
+//! tables.
+//!
+//! Provides `as_of()` and `history()` SQL functions over stream tables
+//! created with `temporal => true`.
+//!
+//! # Design
+//!
+//! The storage table holds the current version of every row, valid from
+//! `__pgt_valid_from`. Closed versions live in the history table
+//! `{change_schema}.history_{pgt_id}` with their half-open validity interval
+//! `[valid_from, valid_to)` (see `refresh::merge::temporal`). Both functions
+//! read the union of the two.
+//!
+//! Rows are returned as `jsonb` objects holding only the user-visible
+//! columns, because the functions serve any stream table shape. Use
+//! `jsonb_populate_record()` to get typed rows back. Results are streamed
+//! from an SPI cursor in batches rather than collected in memory.
+
+use pgrx::prelude::*;
+use pgrx::spi::SpiHeapTupleData;
+
+use super::helpers::{parse_qualified_name, quote_identifier};
+use super::raise_error_with_context;
+use crate::catalog::StreamTableMeta;
+use crate::error::PgTrickleError;
+use crate::refresh::merge::{temporal_history_table, visible_row_projection};
+
+/// Rows fetched from the cursor per SPI session.
+const CURSOR_BATCH_ROWS: std::ffi::c_long = 1000;
+
+// ── Internal helpers ───────────────────────────────────────────────────────
+
+/// A resolved temporal stream table.
+struct TemporalSt {
+    /// Quoted storage table (current versions).
+    quoted_table: String,
+    /// Quoted history table, or `None` when nothing has been closed yet.
+    history: Option<String>,
+    /// `LATERAL (…) v` projection of the visible columns of `st`.
+    projection: String,
+}
+
+fn resolve_temporal_st(name: &str) -> Result<TemporalSt, PgTrickleError> {
+    let (schema, table_name) = parse_qualified_name(name)?;
+    let st = StreamTableMeta::get_by_name(&schema, &table_name)?;
+    if !st.temporal_mode {
+        return Err(PgTrickleError::InvalidArgument(format!(
+            "stream table {schema}.{table_name} is not temporal; \
+             create it with temporal => true to keep row history"
+        )));
+    }
+    let cols = crate::cdc::resolve_st_output_columns(st.pgt_relid)?;
+    let history = temporal_history_table(st.pgt_id);
+    let history_exists = Spi::get_one_with_args::<bool>(
+        "SELECT to_regclass($1) IS NOT NULL",
+        &[history.as_str().into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+    .unwrap_or(false);
+    Ok(TemporalSt {
+        quoted_table: format!(
+            "{}.{}",
+            quote_identifier(&st.pgt_schema),
+            quote_identifier(&st.pgt_name)
+        ),
+        history: history_exists.then_some(history),
+        projection: visible_row_projection(cols.iter().map(|(c, _)| c.as_str())),
+    })
+}
+
+/// Build the version query shared by `as_of` and `history`: every current
+/// and closed version as `(row_id, valid_from, valid_to, row_data)`.
+fn all_versions_sql(st: &TemporalSt) -> String {
+    let current = format!(
+        "SELECT st.__pgt_row_id AS row_id, st.__pgt_valid_from AS valid_from, \
+                NULL::timestamptz AS valid_to, to_jsonb(v) AS row_data \
+         FROM {} AS st, {}",
+        st.quoted_table, st.projection
+    );
+    match &st.history {
+        Some(history) => format!(
+            "{current} UNION ALL \
+             SELECT __pgt_row_id, valid_from, valid_to, row_data FROM {history}"
+        ),
+        None => current,
+    }
+}
+
+/// Streams the rows of a detached SPI cursor, one batch per SPI session.
+///
+/// Tuple tables are only released when their SPI session ends, so each
+/// batch is fetched in its own `Spi::connect` and the cursor is detached
+/// again by name. The cursor is closed once it is exhausted; if the caller
+/// stops early it is closed at transaction end.
+struct CursorRows<T> {
+    cursor: Option<String>,
+    batch: std::vec::IntoIter<T>,
+    decode: fn(&SpiHeapTupleData<'_>) -> Result<T, PgTrickleError>,
+}
+
+impl<T> CursorRows<T> {
+    fn open(
+        sql: &str,
+        args: Vec<pgrx::datum::DatumWithOid<'static>>,
+        decode: fn(&SpiHeapTupleData<'_>) -> Result<T, PgTrickleError>,
+    ) -> Result<Self, PgTrickleError> {
+        let cursor = Spi::connect(|client| {
+            client
+                .try_open_cursor(sql, &args)
+                .map(|cursor| cursor.detach_into_name())
+        })
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        Ok(Self {
+            cursor: Some(cursor),
+            batch: Vec::new().into_iter(),
+            decode,
+        })
+    }
+
+    fn fetch_batch(&self, name: &str) -> Result<(Vec<T>, Option<String>), PgTrickleError> {
+        Spi::connect(|client| {
+            let mut cursor = client
+                .find_cursor(name)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+            let rows = cursor
+                .fetch(CURSOR_BATCH_ROWS)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+            let mut out = Vec::new();
+            for row in rows {
+                out.push((self.decode)(&row)?);
+            }
+            // Dropping an exhausted cursor closes it.
+            let next = (!out.is_empty()).then(|| cursor.detach_into_name());
+            Ok((out, next))
+        })
+    }
+}
+
+impl<T> Iterator for CursorRows<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(row) = self.batch.next() {
+                return Some(row);
+            }
+            let name = self.cursor.take()?;
+            match self.fetch_batch(&name) {
+                Ok((rows, next)) => {
+                    self.batch = rows.into_iter();
+                    self.cursor = next;
+                }
+                Err(e) => raise_error_with_context(e),
+            }
+        }
+    }
+}
+
+// ── as_of ──────────────────────────────────────────────────────────────────
+
+/// CORR-1 (v0.49.0): Return the contents of a temporal stream table as they
+/// were at `ts`.
+///
+/// A version is visible when `__pgt_valid_from <= ts` and it was not yet
+/// closed at `ts`.
+///
+/// ```sql
+/// SELECT r.* FROM pgtrickle.as_of('public.balances', now() - interval '1 day') j,
+///      jsonb_populate_record(NULL::public.balance_row, j) r;
+/// ```
+#[pg_extern(schema = "pgtrickle")]
+pub fn as_of(stream_table: &str, ts: TimestampWithTimeZone) -> SetOfIterator<'static, pgrx::JsonB> {
+    match as_of_impl(stream_table, ts) {
+        Ok(rows) => SetOfIterator::new(rows),
+        Err(e) => raise_error_with_context(e),
+    }
+}
+
+fn as_of_impl(
+    stream_table: &str,
+    ts: TimestampWithTimeZone,
+) -> Result<CursorRows<pgrx::JsonB>, PgTrickleError> {
+    let st = resolve_temporal_st(stream_table)?;
+    let sql = format!(
+        "SELECT h.row_data FROM ({}) h \
+         WHERE h.valid_from <= $1 AND (h.valid_to IS NULL OR h.valid_to > $1) \
+         ORDER BY h.row_id",
+        all_versions_sql(&st)
+    );
+    CursorRows::open(&sql, vec![ts.into()], |row| {
+        row.get::<pgrx::JsonB>(1)
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+            .ok_or_else(|| PgTrickleError::SpiError("as_of: NULL row_data".to_string()))
+    })
+}
+
+// ── history ────────────────────────────────────────────────────────────────
+
+/// CORR-1 (v0.49.0): Return every version of the rows matching `key`.
+///
+/// `key` is a `jsonb` object matched by containment against each version's
+/// visible columns, e.g. `'{"account_id": 42}'`. A NULL key returns the full
+/// history. Versions of the same row are adjacent and ordered by
+/// `valid_from`; `valid_to` is NULL for the current version.
+///
+/// ```sql
+/// SELECT * FROM pgtrickle.history('public.balances', '{"account_id": 42}');
+/// ```
+#[pg_extern(schema = "pgtrickle")]
+#[allow(clippy::type_complexity)]
+pub fn history(
+    stream_table: &str,
+    key: default!(Option<pgrx::JsonB>, "NULL"),
+) -> TableIterator<
+    'static,
+    (
+        name!(valid_from, Option<TimestampWithTimeZone>),
+        name!(valid_to, Option<TimestampWithTimeZone>),
+        name!(row_data, Option<pgrx::JsonB>),
+    ),
+> {
+    match history_impl(stream_table, key) {
+        Ok(rows) => TableIterator::new(rows),
+        Err(e) => raise_error_with_context(e),
+    }
+}
+
+/// One `history()` output row.
+type HistoryRow = (
+    Option<TimestampWithTimeZone>,
+    Option<TimestampWithTimeZone>,
+    Option<pgrx::JsonB>,
+);
+
+fn history_impl(
+    stream_table: &str,
+    key: Option<pgrx::JsonB>,
+) -> Result<CursorRows<HistoryRow>, PgTrickleError> {
+    let st = resolve_temporal_st(stream_table)?;
+    let sql = format!(
+        "SELECT h.valid_from, h.valid_to, h.row_data FROM ({}) h \
+         WHERE $1::jsonb IS NULL OR h.row_data @> $1::jsonb \
+         ORDER BY h.row_id, h.valid_from",
+        all_versions_sql(&st)
+    );
+    CursorRows::open(&sql, vec![key.into()], |row| {
+        let spi_err = |e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string());
+        Ok((
+            row.get::<TimestampWithTimeZone>(1).map_err(spi_err)?,
+            row.get::<TimestampWithTimeZone>(2).map_err(spi_err)?,
+            row.get::<pgrx::JsonB>(3).map_err(spi_err)?,
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn st(history: Option<&str>) -> TemporalSt {
+        TemporalSt {
+            quoted_table: "\"public\".\"t\"".to_string(),
+            history: history.map(str::to_string),
+            projection: visible_row_projection(["id"].into_iter()),
+        }
+    }
+
+    #[test]
+    fn test_all_versions_sql_unions_history() {
+        let sql = all_versions_sql(&st(Some("\"pgtrickle_changes\".\"history_7\"")));
+        assert!(sql.contains("FROM \"public\".\"t\" AS st, LATERAL (SELECT st.\"id\") v"));
+        assert!(sql.contains(
+            "UNION ALL SELECT __pgt_row_id, valid_from, valid_to, row_data \
+             FROM \"pgtrickle_changes\".\"history_7\""
+        ));
+    }
+
+    #[test]
+    fn test_all_versions_sql_without_history_reads_storage_only() {
+        let sql = all_versions_sql(&st(None));
+        assert!(!sql.contains("UNION ALL"));
+        assert!(sql.contains("NULL::timestamptz AS valid_to"));
+    }
+}
@@ -640,7 +640,8 @@ impl StreamTableMeta {
              SET data_timestamp = $1, is_populated = true, \
              last_refresh_at = now(), consecutive_errors = 0, \
              status = 'ACTIVE', needs_reinit = false, \
-             frontier = $3, updated_at = now() \
+             frontier = $3 || jsonb_build_object('clock', now()), \
+             updated_at = now() \
              WHERE pgt_id = $2",
             &[
                 data_ts.into(),
@@ -670,7 +671,8 @@ impl StreamTableMeta {
              last_refresh_at = now(), consecutive_errors = 0, \
              status = 'ACTIVE', needs_reinit = false, \
              last_error_message = NULL, last_error_at = NULL, \
-             frontier = $3, updated_at = now() \
+             frontier = $3 || jsonb_build_object('clock', now()), \
+             updated_at = now() \
              WHERE pgt_id = $1 \
              RETURNING data_timestamp",
             &[
@@ -691,7 +693,8 @@ impl StreamTableMeta {
 
         Spi::run_with_args(
             "UPDATE pgtrickle.pgt_stream_tables \
-             SET frontier = $1, updated_at = now() \
+             SET frontier = $1 || jsonb_build_object('clock', now()), \
+             updated_at = now() \
              WHERE pgt_id = $2",
             &[pgrx::JsonB(frontier_json).into(), pgt_id.into()],
         )
@@ -745,7 +748,8 @@ impl StreamTableMeta {
 
         Spi::run_with_args(
             "UPDATE pgtrickle.pgt_stream_tables \
-             SET tentative_frontier = $1, updated_at = now() \
+             SET tentative_frontier = $1 || jsonb_build_object('clock', now()), \
+             updated_at = now() \
              WHERE pgt_id = $2",
             &[pgrx::JsonB(frontier_json).into(), pgt_id.into()],
         )
@@ -877,6 +881,31 @@ impl StreamTableMeta {
         .map_err(|e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string()))
     }
 
+    /// REN-1: Replace the stored query texts after a source rename.
+    ///
+    /// Leaves the frontier and `is_populated` untouched so the next refresh
+    /// stays differential.
+    pub fn update_queries_after_rename(
+        pgt_id: i64,
+        defining_query: &str,
+        original_query: Option<&str>,
+        topk_order_by: Option<&str>,
+    ) -> Result<(), PgTrickleError> {
+        Spi::run_with_args(
+            "UPDATE pgtrickle.pgt_stream_tables \
+             SET defining_query = $1, original_query = $2, topk_order_by = $3, \
+                 updated_at = now() \
+             WHERE pgt_id = $4",
+            &[
+                defining_query.into(),
+                original_query.into(),
+                topk_order_by.into(),
+                pgt_id.into(),
+            ],
+        )
+        .map_err(|e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string()))
+    }
+
     /// Persist the function source hashes for a stream table (EC-16).
     ///
     /// `hashes_json` is a JSON text string mapping `{ "func_name": "md5hex", ... }`.
@@ -1583,6 +1612,21 @@ impl StDependency {
         .map_err(|e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string()))
     }
 
+    /// REN-1: Replace the tracked column names of a dependency.
+    pub fn update_columns_used(
+        pgt_id: i64,
+        source_relid: pg_sys::Oid,
+        columns_used: Option<Vec<String>>,
+    ) -> Result<(), PgTrickleError> {
+        Spi::run_with_args(
+            "UPDATE pgtrickle.pgt_dependencies \
+             SET columns_used = $1 \
+             WHERE pgt_id = $2 AND source_relid = $3",
+            &[columns_used.into(), pgt_id.into(), source_relid.into()],
+        )
+        .map_err(|e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string()))
+    }
+
     /// Update the CDC mode and related fields for a dependency.
     pub fn update_cdc_mode(
         pgt_id: i64,
@@ -1645,6 +1689,22 @@ impl StDependency {
         .map_err(|e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string()))
     }
 
+    /// Record the decoder-confirmed LSN for every WAL-mode dependency on a
+    /// replication slot, leaving `cdc_mode` and `transition_started_at`
+    /// untouched.
+    pub fn update_confirmed_lsn_for_slot(
+        slot_name: &str,
+        decoder_confirmed_lsn: &str,
+    ) -> Result<(), PgTrickleError> {
+        Spi::run_with_args(
+            "UPDATE pgtrickle.pgt_dependencies \
+             SET decoder_confirmed_lsn = $1::pg_lsn \
+             WHERE slot_name = $2 AND cdc_mode IN ('WAL', 'TRANSITIONING')",
+            &[decoder_confirmed_lsn.into(), slot_name.into()],
+        )
+        .map_err(|e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string()))
+    }
+
     /// Resolve the effective CDC request for a source across all deferred STs.
     ///
     /// Precedence is conservative: if any dependent ST requests `trigger`, the
@@ -1930,12 +1990,19 @@ pub fn build_column_snapshot(
     // PT2: Include partition child count so the fingerprint changes when
     // ATTACH/DETACH PARTITION modifies the partition structure.
     let partition_child_count = query_partition_child_count(source_oid)?;
+    // PT5: The child OIDs tell an ATTACH/DETACH handler which partition moved.
+    let partition_children = query_partition_children(source_oid)?;
+    // REN-1: The relation name lets the DDL tracker tell a RENAME / SET SCHEMA.
+    let (schema_name, table_name) = query_relation_name(source_oid)?;
 
     let snapshot_obj = serde_json::json!({
+        "schema_name": schema_name,
+        "table_name": table_name,
         "columns": entries,
         "rls_enabled": rls_enabled,
         "rls_forced": rls_forced,
         "partition_child_count": partition_child_count,
+        "partition_children": partition_children,
     });
 
     let json_str = serde_json::to_string(&snapshot_obj)
@@ -1954,6 +2021,45 @@ pub fn build_column_snapshot(
     Ok((snapshot, fingerprint))
 }
 
+/// REN-1: Current `(schema, relation)` name of a table.
+#[cfg(not(test))]
+pub fn query_relation_name(source_oid: pg_sys::Oid) -> Result<(String, String), PgTrickleError> {
+    Spi::connect(|client| {
+        let mut result = client
+            .select(
+                "SELECT n.nspname::text, c.relname::text \
+                 FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE c.oid = $1",
+                None,
+                &[source_oid.into()],
+            )
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        match result.next() {
+            Some(row) => {
+                let schema: String = row
+                    .get(1)
+                    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                    .unwrap_or_default();
+                let name: String = row
+                    .get(2)
+                    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                    .unwrap_or_default();
+                Ok((schema, name))
+            }
+            None => Err(PgTrickleError::NotFound(format!(
+                "relation with OID {} not found",
+                source_oid.to_u32()
+            ))),
+        }
+    })
+}
+
+/// Test-only stub for `query_relation_name`.
+#[cfg(test)]
+pub fn query_relation_name(_source_oid: pg_sys::Oid) -> Result<(String, String), PgTrickleError> {
+    Ok((String::new(), String::new()))
+}
+
 /// Query the current RLS state of a table from `pg_class`.
 ///
 /// Returns `(relrowsecurity, relforcerowsecurity)`.
@@ -2022,6 +2128,35 @@ pub fn query_partition_child_count(_source_oid: pg_sys::Oid) -> Result<i64, PgTr
     Ok(0)
 }
 
+/// PT5: OIDs of the direct child partitions of a table, in ascending order.
+#[cfg(not(test))]
+pub fn query_partition_children(source_oid: pg_sys::Oid) -> Result<Vec<u32>, PgTrickleError> {
+    Spi::connect(|client| {
+        let result = client
+            .select(
+                "SELECT inhrelid::bigint FROM pg_inherits WHERE inhparent = $1 ORDER BY inhrelid",
+                None,
+                &[source_oid.into()],
+            )
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        let mut children = Vec::new();
+        for row in result {
+            let oid: i64 = row
+                .get(1)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                .unwrap_or(0);
+            children.push(oid as u32);
+        }
+        Ok(children)
+    })
+}
+
+/// Test-only stub for `query_partition_children`.
+#[cfg(test)]
+pub fn query_partition_children(_source_oid: pg_sys::Oid) -> Result<Vec<u32>, PgTrickleError> {
+    Ok(Vec::new())
+}
+
 /// Get the stored column snapshot for a dependency pair.
 ///
 /// Returns `None` if no snapshot is stored.
@@ -71,6 +71,18 @@ pub use rebuild::{
     rebuild_cdc_trigger_function, trigger_exists,
 };
 
+// ── CDC pause guard ────────────────────────────────────────────────────────
+
+/// A07: Early-return guard emitted at the top of every CDC trigger body.
+///
+/// While `pg_trickle.cdc_paused` is on, captured changes are discarded —
+/// except in `cdc_capture_mode = 'hold'`, where the trigger keeps writing
+/// to the change buffer and the scheduler defers the refreshes instead.
+const CDC_PAUSED_GUARD_SQL: &str = "IF (current_setting('pg_trickle.cdc_paused', true) = 'on'
+                 AND lower(coalesce(current_setting('pg_trickle.cdc_capture_mode', true), 'discard')) <> 'hold') THEN
+                 RETURN NULL;
+             END IF;";
+
 // ── Reserved change-buffer column names ────────────────────────────────────
 
 /// Built-in CDC metadata column names that live at the top of every change-buffer
@@ -313,9 +325,7 @@ pub fn create_change_trigger(
          SET search_path = pgtrickle_changes, pgtrickle, pg_catalog, pg_temp AS $$
          BEGIN
              -- A07: CDC cdc_paused guard (A07).
-             IF (current_setting('pg_trickle.cdc_paused', true) = 'on') THEN
-                 RETURN NULL;
-             END IF;
+             {CDC_PAUSED_GUARD_SQL}
              INSERT INTO {change_schema}.changes_{name}
                  (lsn, action)
              VALUES (pg_current_wal_lsn(), 'T');
@@ -561,6 +571,110 @@ pub fn rebuild_publication_for_partitioned_source(
     Ok(())
 }
 
+/// PT5 (v0.49.0): Write every row of a partition to the change buffer of its
+/// partitioned parent, as inserts (`'I'`, after ATTACH PARTITION) or deletes
+/// (`'D'`, after DETACH PARTITION).
+///
+/// ATTACH and DETACH move rows in or out of the parent without DML, so
+/// neither CDC triggers nor the WAL decoder see them. Row-level triggers on
+/// the parent are cloned onto (or removed from) the partition by PostgreSQL,
+/// and a publication on the parent covers its current partitions, so no CDC
+/// objects change. Returns the number of rows written.
+pub fn capture_partition_rows(
+    source_oid: pg_sys::Oid,
+    partition_oid: pg_sys::Oid,
+    action: char,
+    change_schema: &str,
+) -> Result<i64, PgTrickleError> {
+    debug_assert!(action == 'I' || action == 'D');
+    let partition = resolve_relation_name(partition_oid)?.ok_or_else(|| {
+        PgTrickleError::NotFound(format!(
+            "Partition with OID {} not found",
+            partition_oid.to_u32()
+        ))
+    })?;
+    let pk_columns = resolve_pk_columns(source_oid)?;
+    // Same column set as the CDC trigger writes (F15 selective capture).
+    let columns = resolve_referenced_column_defs(source_oid)?;
+    let pk_hash = build_pk_hash_stmt_expr("p", &pk_columns, &columns);
+    let cn: String = columns
+        .iter()
+        .map(|(n, _)| format!(", \"{}\"", cb_col_name(n).replace('"', "\"\"")))
+        .collect();
+    let pv: String = columns
+        .iter()
+        .map(|(n, _)| format!(", p.\"{}\"", n.replace('"', "\"\"")))
+        .collect();
+    let buffer = buffer_qualified_name_for_oid(change_schema, source_oid);
+
+    // Partition columns match the parent's by name (attribute order may not).
+    Spi::get_one::<i64>(&format!(
+        "WITH __pgt_rows AS ( \
+             INSERT INTO {buffer} (lsn, action, pk_hash{cn}) \
+             SELECT pg_current_wal_insert_lsn(), '{action}', {pk_hash}{pv} \
+             FROM {partition} p \
+             RETURNING 1) \
+         SELECT count(*) FROM __pgt_rows"
+    ))
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))
+    .map(|n| n.unwrap_or(0))
+}
+
+/// REN-1 (v0.49.0): Rename change buffer columns after RENAME COLUMN on the
+/// source, so rows captured before the rename stay readable by the next
+/// differential refresh.
+///
+/// Must run before `rebuild_cdc_trigger_function`, whose column sync would
+/// otherwise drop the old column and add an empty new one. Renames go
+/// through temporary names so swaps (`a → b`, `b → a`) work. A source with
+/// no change buffer (IMMEDIATE mode only) is a no-op.
+pub fn rename_change_buffer_columns(
+    source_oid: pg_sys::Oid,
+    change_schema: &str,
+    renames: &[(String, String)],
+) -> Result<(), PgTrickleError> {
+    let buffer = buffer_qualified_name_for_oid(change_schema, source_oid);
+    let exists = Spi::get_one_with_args::<bool>(
+        "SELECT to_regclass($1) IS NOT NULL",
+        &[buffer.as_str().into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+    .unwrap_or(false);
+    if !exists {
+        return Ok(());
+    }
+
+    let mut staged = Vec::new();
+    for (i, (old, new)) in renames.iter().enumerate() {
+        let has_old = Spi::get_one_with_args::<bool>(
+            "SELECT EXISTS (SELECT 1 FROM pg_attribute \
+             WHERE attrelid = $1::regclass AND attname = $2 \
+               AND attnum > 0 AND NOT attisdropped)",
+            &[buffer.as_str().into(), cb_col_name(old).as_str().into()],
+        )
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+        .unwrap_or(false);
+        if !has_old {
+            continue;
+        }
+        let temp = format!("__pgt_rename_{i}");
+        Spi::run(&format!(
+            "ALTER TABLE {buffer} RENAME COLUMN \"{}\" TO \"{temp}\"",
+            cb_col_name(old).replace('"', "\"\""),
+        ))
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        staged.push((temp, new));
+    }
+    for (temp, new) in staged {
+        Spi::run(&format!(
+            "ALTER TABLE {buffer} RENAME COLUMN \"{temp}\" TO \"{}\"",
+            cb_col_name(new).replace('"', "\"\""),
+        ))
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+    Ok(())
+}
+
 /// CDC-3 (v0.24.0): Build a TOAST-aware column hash expression.
 ///
 /// For columns with `attstorage IN ('e', 'x')` (external or extended TOAST
@@ -1826,9 +1940,7 @@ fn build_row_trigger_fn_sql(
          SET search_path = pgtrickle_changes, pgtrickle, pg_catalog, pg_temp AS $$
          BEGIN
              -- A07: CDC cdc_paused guard (A07).
-             IF (current_setting('pg_trickle.cdc_paused', true) = 'on') THEN
-                 RETURN NULL;
-             END IF;
+             {CDC_PAUSED_GUARD_SQL}
              IF TG_OP = 'INSERT' THEN
                  INSERT INTO {cs}.changes_{name}
                      (lsn, action, pk_hash{cn}, __pgt_trace_context)
@@ -1930,9 +2042,7 @@ fn build_stmt_trigger_fn_sql(
          SET search_path = pgtrickle_changes, pgtrickle, pg_catalog, pg_temp AS $$
          BEGIN
              -- A07: CDC cdc_paused guard (A07).
-             IF (current_setting('pg_trickle.cdc_paused', true) = 'on') THEN
-                 RETURN NULL;
-             END IF;
+             {CDC_PAUSED_GUARD_SQL}
              INSERT INTO {cs}.changes_{name}
                  (lsn, action, pk_hash{cn}, __pgt_trace_context)
              SELECT pg_current_wal_insert_lsn(), 'I', {pkn}{ncr},
@@ -1959,9 +2069,7 @@ fn build_stmt_trigger_fn_sql(
          SET search_path = pgtrickle_changes, pgtrickle, pg_catalog, pg_temp AS $$
          BEGIN
              -- A07: CDC cdc_paused guard (A07).
-             IF (current_setting('pg_trickle.cdc_paused', true) = 'on') THEN
-                 RETURN NULL;
-             END IF;
+             {CDC_PAUSED_GUARD_SQL}
              -- D-row (OLD values) — must be emitted before I-row.
              INSERT INTO {cs}.changes_{name}
                  (lsn, action, pk_hash{cn}, __pgt_trace_context)
@@ -2008,9 +2116,7 @@ fn build_stmt_trigger_fn_sql(
          SET search_path = pgtrickle_changes, pgtrickle, pg_catalog, pg_temp AS $$
          BEGIN
              -- A07: CDC cdc_paused guard (A07).
-             IF (current_setting('pg_trickle.cdc_paused', true) = 'on') THEN
-                 RETURN NULL;
-             END IF;
+             {CDC_PAUSED_GUARD_SQL}
              -- D+I pair for keyed UPDATE (UNION ALL — one heap open, two rows).
              -- D-row must be first row in the UNION ALL (change_id ordering invariant).
              INSERT INTO {cs}.changes_{name}
@@ -2055,9 +2161,7 @@ fn build_stmt_trigger_fn_sql(
          SET search_path = pgtrickle_changes, pgtrickle, pg_catalog, pg_temp AS $$
          BEGIN
              -- A07: CDC cdc_paused guard (A07).
-             IF (current_setting('pg_trickle.cdc_paused', true) = 'on') THEN
-                 RETURN NULL;
-             END IF;
+             {CDC_PAUSED_GUARD_SQL}
              INSERT INTO {cs}.changes_{name}
                  (lsn, action, pk_hash{cn}, __pgt_trace_context)
              SELECT pg_current_wal_insert_lsn(), 'D', {pko}{ocr},
@@ -3378,4 +3482,22 @@ mod tests {
         assert!(classify_holdback(200, 100));
         assert!(classify_holdback(200, 1));
     }
+
+    // ── A07: pause guard ─────────────────────────────────────────────
+
+    #[test]
+    fn test_trigger_bodies_emit_pause_guard_once() {
+        let pk = vec!["id".to_string()];
+        let cols = vec![
+            ("id".to_string(), "integer".to_string()),
+            ("val".to_string(), "text".to_string()),
+        ];
+        let row = build_row_trigger_fn_sql("pgtrickle_changes", "16384", &pk, &cols);
+        assert_eq!(row.matches(CDC_PAUSED_GUARD_SQL).count(), 1);
+
+        let (ins, upd, del) = build_stmt_trigger_fn_sql("pgtrickle_changes", "16384", &pk, &cols);
+        for body in [&ins, &upd, &del] {
+            assert_eq!(body.matches(CDC_PAUSED_GUARD_SQL).count(), 1);
+        }
+    }
 }
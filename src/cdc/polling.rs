@@ -53,7 +53,14 @@ pub fn setup_foreign_table_polling(
         Spi::run(&create_snap_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?; // nosemgrep: semgrep.rust.spi.run.dynamic-format
 
         // Seed the snapshot with the current foreign table contents.
-        let seed_snap_sql = format!("INSERT INTO {snapshot_table} SELECT * FROM {source_table}");
+        // POLL-HWM: a configured soft-delete column keeps deleted rows out.
+        let live_filter = load_polling_config(source_oid)?
+            .and_then(|config| config.soft_delete_column)
+            .map(|sd| format!(" WHERE {}", live_row_condition(&sd, "__pgt_src")))
+            .unwrap_or_default();
+        let seed_snap_sql = format!(
+            "INSERT INTO {snapshot_table} SELECT * FROM {source_table} __pgt_src{live_filter}"
+        );
         Spi::run(&seed_snap_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?; // nosemgrep: semgrep.rust.spi.run.dynamic-format
 
         // Record tracking with synthetic slot_name indicating polling CDC.
@@ -85,9 +92,15 @@ pub fn setup_foreign_table_polling(
 
 /// Poll a foreign table source for changes and populate the change buffer.
 ///
-/// Computes the symmetric difference between the current foreign table
-/// contents and the snapshot table using EXCEPT ALL, inserts the deltas
-/// into the change buffer, and refreshes the snapshot.
+/// By default computes the symmetric difference between the current foreign
+/// table contents and the snapshot table using EXCEPT ALL, inserts the
+/// deltas into the change buffer, and refreshes the snapshot.
+///
+/// POLL-HWM (v0.49.0): sources configured with
+/// `pgtrickle.configure_foreign_table_polling()` only fetch rows at or above
+/// the stored high-water mark (see [`poll_above_high_water_mark`]) and fall
+/// back to the full diff when a reconciliation is due or the mark table is
+/// missing.
 ///
 /// Uses `pg_current_wal_insert_lsn()` as the LSN for delta rows (even
 /// though the foreign table itself has no WAL entries, the local write
@@ -100,49 +113,114 @@ pub fn poll_foreign_table_changes(
     // CITUS-4: Use stable names for change buffer and snapshot tables.
     let stable_name =
         crate::citus::stable_name_for_oid(source_oid).unwrap_or_else(|_| oid_u32.to_string());
-    let change_table = format!("\"{change_schema}\".changes_{stable_name}");
-    let snapshot_table = format!("\"{change_schema}\".snapshot_{stable_name}");
+    let tables = PollTables {
+        change_table: format!("\"{change_schema}\".changes_{stable_name}"),
+        snapshot_table: format!("\"{change_schema}\".snapshot_{stable_name}"),
+        hwm_table: format!("\"{change_schema}\".hwm_{stable_name}"),
+        key_index: format!("snapshot_{stable_name}_poll_key"),
+        change_schema: format!("\"{change_schema}\""),
+        source_table: Spi::get_one_with_args::<String>(
+            "SELECT $1::oid::regclass::text",
+            &[source_oid.into()],
+        )
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+        .ok_or_else(|| {
+            PgTrickleError::NotFound(format!("Foreign table with OID {oid_u32} not found"))
+        })?,
+    };
 
-    let source_table =
-        Spi::get_one_with_args::<String>("SELECT $1::oid::regclass::text", &[source_oid.into()])
-            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
-            .ok_or_else(|| {
-                PgTrickleError::NotFound(format!("Foreign table with OID {oid_u32} not found"))
-            })?;
+    let Some(cols) = poll_columns(source_oid)? else {
+        return Ok(());
+    };
 
+    match load_polling_config(source_oid)? {
+        None => poll_snapshot_diff(&tables, &cols, None),
+        Some(config) if config.reconcile_due || !relation_exists(&tables.hwm_table)? => {
+            poll_snapshot_diff(&tables, &cols, config.soft_delete_column.as_deref())?;
+            record_reconciliation(source_oid, &config, &tables)
+        }
+        Some(config) => poll_above_high_water_mark(&config, &tables, &cols),
+    }
+}
+
+/// Relations involved in one foreign-table poll.
+struct PollTables {
+    change_table: String,
+    snapshot_table: String,
+    /// POLL-HWM: single-row table holding the high-water mark in the
+    /// watermark column's own type.
+    hwm_table: String,
+    /// POLL-HWM: unqualified name of the snapshot's key-column index.
+    key_index: String,
+    /// Quoted change-buffer schema.
+    change_schema: String,
+    /// PostgreSQL's own `regclass::text` output — already properly quoted.
+    source_table: String,
+}
+
+/// Column lists shared by the polling queries.
+struct PollColumns {
+    /// Change-buffer INSERT target names (uses `cb_col_name()` for reserved
+    /// columns).
+    cb_col_list: String,
+    /// Quoted source column names, in table order.
+    src_cols: Vec<String>,
+    /// `pk_hash` expression over the unqualified source columns.
+    pk_hash_expr: String,
+}
+
+impl PollColumns {
+    fn src_col_list(&self) -> String {
+        self.src_cols.join(", ")
+    }
+
+    /// Source columns qualified with `alias`.
+    fn qualified(&self, alias: &str) -> String {
+        self.src_cols
+            .iter()
+            .map(|c| format!("{alias}.{c}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn quote_col(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Build the polling column lists, or `None` for a source without columns.
+fn poll_columns(source_oid: pg_sys::Oid) -> Result<Option<PollColumns>, PgTrickleError> {
     let col_defs = super::resolve_source_column_defs(source_oid)?;
     if col_defs.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
     let pk_columns = super::resolve_pk_columns(source_oid)?;
 
-    // Build column lists for INSERT into change buffer.
     // A44-10: Flat column names (no new_/old_ prefix) matching base table
     // change buffer schema created by super::create_change_buffer_table().
-    // cb_col_names: change-buffer INSERT target names (uses super::cb_col_name() for reserved cols).
-    // src_col_names: SELECT source names from the actual table (original names).
-    let cb_col_names: Vec<String> = col_defs
-        .iter()
-        .map(|(name, _)| format!("\"{}\"", super::cb_col_name(name).replace('"', "\"\"")))
-        .collect();
-    let src_col_names: Vec<String> = col_defs
+    // Values are matched positionally between the two lists.
+    let cb_col_list = col_defs
         .iter()
-        .map(|(name, _)| format!("\"{}\"", name.replace('"', "\"\"")))
-        .collect();
+        .map(|(name, _)| quote_col(&super::cb_col_name(name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let src_cols: Vec<String> = col_defs.iter().map(|(name, _)| quote_col(name)).collect();
 
     // Build pk_hash expression for delta rows.
     let hash_cols: Vec<String> = if pk_columns.is_empty() {
         col_defs.iter().map(|(n, _)| n.clone()).collect()
     } else {
-        pk_columns.clone()
+        pk_columns
     };
     let pk_hash_expr = if hash_cols.len() == 1 {
-        let c = format!("\"{}\"", hash_cols[0].replace('"', "\"\""));
-        format!("pgtrickle.pg_trickle_hash({c}::text)")
+        format!(
+            "pgtrickle.pg_trickle_hash({}::text)",
+            quote_col(&hash_cols[0])
+        )
     } else {
         let items: Vec<String> = hash_cols
             .iter()
-            .map(|c| format!("\"{}\"::text", c.replace('"', "\"\"")))
+            .map(|c| format!("{}::text", quote_col(c)))
             .collect();
         format!(
             "pgtrickle.pg_trickle_hash_multi(ARRAY[{}])",
@@ -150,20 +228,54 @@ pub fn poll_foreign_table_changes(
         )
     };
 
-    let cb_col_list = cb_col_names.join(", ");
-    let src_col_list = src_col_names.join(", ");
+    Ok(Some(PollColumns {
+        cb_col_list,
+        src_cols,
+        pk_hash_expr,
+    }))
+}
+
+/// Condition under which a row is live, i.e. its soft-delete column is
+/// unset: NULL, or `false` for a boolean column.
+fn live_row_condition(soft_delete_column: &str, alias: &str) -> String {
+    let col = format!("{alias}.{}", quote_col(soft_delete_column));
+    format!("({col} IS NULL OR {col}::text = 'false')")
+}
+
+/// Diff the whole foreign table against the snapshot and replace the
+/// snapshot with the current contents.
+///
+/// With a soft-delete column, only live rows count as current contents.
+fn poll_snapshot_diff(
+    tables: &PollTables,
+    cols: &PollColumns,
+    soft_delete_column: Option<&str>,
+) -> Result<(), PgTrickleError> {
+    let PollTables {
+        change_table,
+        snapshot_table,
+        source_table,
+    } = tables;
+    let cb_col_list = &cols.cb_col_list;
+    let src_col_list = cols.src_col_list();
+    let pk_hash_expr = &cols.pk_hash_expr;
+    let current = match soft_delete_column {
+        Some(sd) => format!(
+            "(SELECT * FROM {source_table} __pgt_src WHERE {}) __pgt_live",
+            live_row_condition(sd, "__pgt_src")
+        ),
+        None => source_table.clone(),
+    };
 
     // ── Deleted rows: in snapshot but not in current foreign table ──
     // These appear as 'D' (delete) rows in the change buffer.
-    // INSERT target uses cb_col_list (change-buffer names); SELECT uses src_col_list
-    // (original source names). Values are matched positionally.
     let deleted_sql = format!(
         "INSERT INTO {change_table} (lsn, action, pk_hash, {cb_col_list}) \
          SELECT pg_current_wal_insert_lsn(), 'D', {pk_hash_expr}, {src_col_list} \
          FROM (\
            SELECT {src_col_list} FROM {snapshot_table} \
            EXCEPT ALL \
-           SELECT {src_col_list} FROM {source_table}\
+           SELECT {src_col_list} FROM {current}\
          ) __pgt_del"
     );
     Spi::run(&deleted_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
@@ -174,7 +286,7 @@ pub fn poll_foreign_table_changes(
         "INSERT INTO {change_table} (lsn, action, pk_hash, {cb_col_list}) \
          SELECT pg_current_wal_insert_lsn(), 'I', {pk_hash_expr}, {src_col_list} \
          FROM (\
-           SELECT {src_col_list} FROM {source_table} \
+           SELECT {src_col_list} FROM {current} \
            EXCEPT ALL \
            SELECT {src_col_list} FROM {snapshot_table}\
          ) __pgt_ins"
@@ -185,17 +297,242 @@ pub fn poll_foreign_table_changes(
     // snapshot_table: extension-controlled name; source_table: PostgreSQL regclass::text (safe).
     let truncate_sql = format!("TRUNCATE {snapshot_table}");
     Spi::run(&truncate_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?; // nosemgrep: semgrep.rust.spi.run.dynamic-format
-    let refresh_sql = format!("INSERT INTO {snapshot_table} SELECT * FROM {source_table}");
+    let refresh_sql = format!("INSERT INTO {snapshot_table} SELECT * FROM {current}");
     Spi::run(&refresh_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?; // nosemgrep: semgrep.rust.spi.run.dynamic-format
 
     Ok(())
 }
 
+// ── POLL-HWM (v0.49.0): High-water-mark polling ───────────────────────────
+
+/// High-water-mark polling configuration of a foreign table
+/// (`pgtrickle.pgt_polling_config`).
+pub(crate) struct PollingConfig {
+    pub watermark_column: String,
+    pub key_columns: Vec<String>,
+    pub soft_delete_column: Option<String>,
+    /// No reconciliation yet, or `reconcile_interval` has elapsed.
+    pub reconcile_due: bool,
+}
+
+/// Load the high-water-mark configuration of a source, if any.
+pub(crate) fn load_polling_config(
+    source_oid: pg_sys::Oid,
+) -> Result<Option<PollingConfig>, PgTrickleError> {
+    Spi::connect(|client| {
+        let table = client
+            .select(
+                "SELECT watermark_column, key_columns, soft_delete_column, \
+                        last_reconciled_at IS NULL \
+                        OR (reconcile_interval IS NOT NULL \
+                            AND now() - last_reconciled_at >= reconcile_interval) \
+                 FROM pgtrickle.pgt_polling_config WHERE source_relid = $1",
+                None,
+                &[source_oid.into()],
+            )
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        if table.is_empty() {
+            return Ok(None);
+        }
+        let row = table.first();
+        let map_spi = |e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string());
+        Ok(Some(PollingConfig {
+            watermark_column: row.get::<String>(1).map_err(map_spi)?.unwrap_or_default(),
+            key_columns: row
+                .get::<Vec<String>>(2)
+                .map_err(map_spi)?
+                .unwrap_or_default(),
+            soft_delete_column: row.get::<String>(3).map_err(map_spi)?,
+            reconcile_due: row.get::<bool>(4).map_err(map_spi)?.unwrap_or(true),
+        }))
+    })
+}
+
+fn relation_exists(name: &str) -> Result<bool, PgTrickleError> {
+    Spi::get_one_with_args::<bool>("SELECT to_regclass($1) IS NOT NULL", &[name.into()])
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))
+        .map(|exists| exists.unwrap_or(false))
+}
+
+/// After a full diff, restart the high-water mark from the snapshot.
+///
+/// The mark table is recreated from `max()` of the watermark column, so it
+/// always has the column's current type, and the key index is rebuilt on
+/// the configured key columns; both may have changed since the last
+/// reconciliation.
+fn record_reconciliation(
+    source_oid: pg_sys::Oid,
+    config: &PollingConfig,
+    tables: &PollTables,
+) -> Result<(), PgTrickleError> {
+    let PollTables {
+        snapshot_table,
+        hwm_table,
+        key_index,
+        change_schema,
+        ..
+    } = tables;
+    let wm = quote_col(&config.watermark_column);
+    let keys = config
+        .key_columns
+        .iter()
+        .map(|k| quote_col(k))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // hwm_table, snapshot_table, key_index: extension-controlled names;
+    // wm, keys: quoted identifiers.
+    for sql in [
+        format!("DROP TABLE IF EXISTS {hwm_table}"),
+        format!(
+            "CREATE TABLE {hwm_table} AS SELECT max({wm}) AS high_water_mark FROM {snapshot_table}"
+        ),
+        format!("DROP INDEX IF EXISTS {change_schema}.{key_index}"),
+        format!("CREATE INDEX {key_index} ON {snapshot_table} ({keys})"),
+    ] {
+        Spi::run(&sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?; // nosemgrep: semgrep.rust.spi.run.dynamic-format
+    }
+    Spi::run_with_args(
+        "UPDATE pgtrickle.pgt_polling_config SET last_reconciled_at = now() \
+         WHERE source_relid = $1",
+        &[source_oid.into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))
+}
+
+/// Drop the high-water-mark table and snapshot key index of a foreign
+/// table whose polling configuration was removed.
+pub(crate) fn drop_high_water_mark_state(
+    source_oid: pg_sys::Oid,
+    change_schema: &str,
+) -> Result<(), PgTrickleError> {
+    let stable_name = crate::citus::stable_name_for_oid(source_oid)
+        .unwrap_or_else(|_| source_oid.to_u32().to_string());
+    // Extension-controlled names derived from the change schema and OID.
+    for sql in [
+        format!("DROP TABLE IF EXISTS \"{change_schema}\".hwm_{stable_name}"),
+        format!("DROP INDEX IF EXISTS \"{change_schema}\".snapshot_{stable_name}_poll_key"),
+    ] {
+        Spi::run(&sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?; // nosemgrep: semgrep.rust.spi.run.dynamic-format
+    }
+    Ok(())
+}
+
+/// Fetch only rows whose watermark column is at or above the stored
+/// high-water mark and apply them to the snapshot by key.
+///
+/// A fetched row that differs from its snapshot version emits a delete of
+/// the old version; a live row not (or no longer) in the snapshot emits an
+/// insert.  Rows with the soft-delete column set leave the snapshot without
+/// an insert.  Fetching with `>=` re-reads rows tied at the mark; identical
+/// rows produce no change.  Physically deleted rows, and rows that commit
+/// remotely with a watermark below the mark, are picked up by the next
+/// reconciliation.  Keys are matched with `=` so the snapshot's key index
+/// applies; key columns are expected to be non-NULL.
+fn poll_above_high_water_mark(
+    config: &PollingConfig,
+    tables: &PollTables,
+    cols: &PollColumns,
+) -> Result<(), PgTrickleError> {
+    let PollTables {
+        change_table,
+        snapshot_table,
+        hwm_table,
+        source_table,
+        ..
+    } = tables;
+    let cb_col_list = &cols.cb_col_list;
+    let src_col_list = cols.src_col_list();
+    let pk_hash_expr = &cols.pk_hash_expr;
+    let wm = quote_col(&config.watermark_column);
+
+    // The mark is compared in the column's own type; postgres_fdw sends the
+    // uncorrelated sub-select's value as a remote parameter.  An empty
+    // snapshot has no mark yet, so everything is fetched.
+    let has_mark = Spi::get_one::<bool>(&format!(
+        "SELECT EXISTS(SELECT 1 FROM {hwm_table} WHERE high_water_mark IS NOT NULL)"
+    ))
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+    .unwrap_or(false);
+    let filter = if has_mark {
+        format!(" WHERE {wm} >= (SELECT high_water_mark FROM {hwm_table})")
+    } else {
+        String::new()
+    };
+
+    let key_match = config
+        .key_columns
+        .iter()
+        .map(|k| format!("s.{0} = f.{0}", quote_col(k)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let changed = format!(
+        "{key_match} AND ROW({}) IS DISTINCT FROM ROW({})",
+        cols.qualified("s"),
+        cols.qualified("f")
+    );
+    let live = config
+        .soft_delete_column
+        .as_deref()
+        .map(|sd| format!(" AND {}", live_row_condition(sd, "f")))
+        .unwrap_or_default();
+
+    // The only remote read: rows at or above the mark.
+    Spi::run("DROP TABLE IF EXISTS pg_temp.__pgt_poll_fetch")
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    Spi::run(&format!(
+        "CREATE TEMP TABLE __pgt_poll_fetch ON COMMIT DROP AS \
+         SELECT * FROM {source_table}{filter}"
+    ))
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    // ── Old versions of changed or soft-deleted rows ──
+    let deleted_sql = format!(
+        "INSERT INTO {change_table} (lsn, action, pk_hash, {cb_col_list}) \
+         SELECT pg_current_wal_insert_lsn(), 'D', {pk_hash_expr}, {src_col_list} \
+         FROM (\
+           SELECT s.* FROM {snapshot_table} s \
+           WHERE EXISTS (SELECT 1 FROM pg_temp.__pgt_poll_fetch f WHERE {changed})\
+         ) __pgt_del"
+    );
+    Spi::run(&deleted_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    Spi::run(&format!(
+        "DELETE FROM {snapshot_table} s USING pg_temp.__pgt_poll_fetch f WHERE {changed}"
+    ))
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    // ── New versions of live rows ──
+    let new_rows = format!(
+        "SELECT f.* FROM pg_temp.__pgt_poll_fetch f \
+         WHERE NOT EXISTS (SELECT 1 FROM {snapshot_table} s WHERE {key_match}){live}"
+    );
+    let inserted_sql = format!(
+        "INSERT INTO {change_table} (lsn, action, pk_hash, {cb_col_list}) \
+         SELECT pg_current_wal_insert_lsn(), 'I', {pk_hash_expr}, {src_col_list} \
+         FROM ({new_rows}) __pgt_ins"
+    );
+    Spi::run(&inserted_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    Spi::run(&format!("INSERT INTO {snapshot_table} {new_rows}"))
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    // Every fetched row is at or above the old mark, so the fetched maximum
+    // is the new one.
+    Spi::run(&format!(
+        "UPDATE {hwm_table} SET high_water_mark = COALESCE(\
+             (SELECT max({wm}) FROM pg_temp.__pgt_poll_fetch), high_water_mark)"
+    ))
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    Spi::run("DROP TABLE pg_temp.__pgt_poll_fetch")
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    Ok(())
+}
+
 /// Set up polling-based CDC for a materialized view source (P2-4).
 ///
 /// Identical to [`setup_foreign_table_polling`] — creates a snapshot table
 /// and change buffer so that EXCEPT ALL can compute deltas when the matview
-/// is refreshed externally.
+/// is refreshed externally (see [`poll_matview_changes`]).
 pub fn setup_matview_polling(
     source_oid: pg_sys::Oid,
     pgt_id: i64,
@@ -262,10 +599,14 @@ pub fn setup_matview_polling(
     Ok(())
 }
 
-/// Poll a materialized view source for changes (P2-4).
+/// Capture the changes of a materialized view source (P2-4).
 ///
 /// Identical to [`poll_foreign_table_changes`] — uses EXCEPT ALL between
 /// the current matview contents and the snapshot to detect inserts/deletes.
+///
+/// MV-CDC (v0.49.0): called once per `REFRESH MATERIALIZED VIEW` from the
+/// DDL event trigger (`hooks::handle_matview_refresh`) rather than on every
+/// scheduler tick.
 pub fn poll_matview_changes(
     source_oid: pg_sys::Oid,
     change_schema: &str,
@@ -1373,10 +1373,10 @@ pub static PGS_TRACE_ID: GucSetting<Option<std::ffi::CString>> =
 ///   reinitialized after un-pausing to recover from the data gap. This is the
 ///   legacy `cdc_paused` behaviour.
 ///
-/// - `"hold"`: Future mode — intended to keep CDC triggers active but pause
-///   the scheduler from consuming the change buffer. Changes accumulate in the
-///   buffer and are processed when the pause is lifted. **Not yet implemented;**
-///   setting this emits a WARNING and falls back to `"discard"`.
+/// - `"hold"`: CDC triggers keep appending to the change buffers while the
+///   scheduler stops dispatching refreshes. Changes accumulate in the buffer
+///   and are consumed differentially once the pause is lifted — no
+///   reinitialization is needed.
 ///
 /// Default: `"discard"`.
 ///
@@ -1389,7 +1389,7 @@ pub static PGS_CDC_CAPTURE_MODE: GucSetting<Option<std::ffi::CString>> =
 pub enum CdcCaptureMode {
     /// Changes are discarded while paused. Reinit required after un-pause.
     Discard,
-    /// (Future) Changes accumulate in the buffer while refreshes are paused.
+    /// Changes accumulate in the buffer while refreshes are paused.
     Hold,
 }
 
@@ -1495,13 +1495,60 @@ pub static PGS_DEEP_JOIN_L0_SCAN_THRESHOLD: GucSetting<i32> = GucSetting::<i32>:
 /// A44-3 (v0.43.0): Maximum number of changes fetched per WAL poll cycle.
 ///
 /// Controls the `max_changes` parameter passed to
-/// `pg_logical_slot_get_changes()`. Increasing this value raises throughput
+/// `pg_logical_slot_get_binary_changes()`. Increasing this value raises throughput
 /// at the cost of larger per-tick memory usage; decreasing it reduces latency
 /// for high-volume sources but increases poll overhead.
 ///
 /// Default: 10 000. Range: 100–1 000 000.
 pub static PGS_WAL_MAX_CHANGES_PER_POLL: GucSetting<i32> = GucSetting::<i32>::new(10_000);
 
+/// WAL-PGO (v0.49.0): Share one logical replication slot per database
+/// across all WAL-mode sources.
+///
+/// When enabled, sources that transition to WAL-based CDC join the
+/// database-wide `pgtrickle_shared_<db oid>` slot instead of getting a
+/// dedicated slot.  The slot is decoded once per scheduler tick and
+/// `pgoutput` publication filtering routes each change to its source's
+/// change buffer, so the WAL stream is read once instead of once per
+/// source.  Sources that already have a dedicated slot keep it.
+///
+/// Default: false.
+pub static PGS_WAL_SHARED_SLOT: GucSetting<bool> = GucSetting::<bool>::new(false);
+
+/// WAL-STREAM (v0.49.0): Consume the shared replication slot with a
+/// streaming-replication background worker instead of SPI polling.
+///
+/// When enabled, each database's shared `pgoutput` slot is read by a
+/// dedicated `pg_trickle wal receiver` worker over a replication-protocol
+/// connection (`START_REPLICATION ... LOGICAL`).  The scheduler no longer
+/// polls that slot, so capture latency no longer depends on the refresh
+/// tick and a large transaction cannot stall it.  Per-source slots are
+/// still polled.  Requires `pg_hba.conf` to allow local replication
+/// connections for the bootstrap superuser (or the role named in
+/// `pg_trickle.wal_receiver_conninfo`).
+///
+/// Default: false.
+pub static PGS_WAL_RECEIVER: GucSetting<bool> = GucSetting::<bool>::new(false);
+
+/// WAL-STREAM (v0.49.0): Number of decoded changes after which the WAL
+/// receiver commits its change-buffer transaction.
+///
+/// Batches always end on a source transaction boundary, so a single large
+/// transaction is written in one batch; smaller transactions are grouped
+/// until this many changes accumulate or the stream goes idle.
+///
+/// Default: 10 000. Range: 1–1 000 000.
+pub static PGS_WAL_RECEIVER_BATCH_SIZE: GucSetting<i32> = GucSetting::<i32>::new(10_000);
+
+/// WAL-STREAM (v0.49.0): libpq connection string for the WAL receiver's
+/// replication connection.
+///
+/// Empty (the default) connects over the server's first Unix socket
+/// directory and port as the bootstrap superuser.  `dbname` and
+/// `replication` are always set by the worker.
+pub static PGS_WAL_RECEIVER_CONNINFO: GucSetting<Option<std::ffi::CString>> =
+    GucSetting::<Option<std::ffi::CString>>::new(None);
+
 /// A44-3 (v0.43.0): Maximum WAL lag bytes before emitting a warning.
 ///
 /// When the decoded WAL lag (bytes between the slot's `restart_lsn` and the
@@ -1563,6 +1610,57 @@ pub static PGS_LAG_AWARE_SCHEDULING: GucSetting<bool> = GucSetting::<bool>::new(
 /// Default: 0.20. Range: 0.01–1.0.
 pub static PGS_REINDEX_DRIFT_THRESHOLD: GucSetting<f64> = GucSetting::<f64>::new(0.20);
 
+// ── v0.49.0 GUCs ──────────────────────────────────────────────────────────
+
+/// WIN-SUFFIX (v0.49.0): Maintain cumulative window functions by
+/// recomputing only the changed suffix of each partition.
+///
+/// Applies to `ROW_NUMBER()`, `COUNT()` and `SUM()` over a single-column
+/// ORDER BY with a cumulative frame (`UNBOUNDED PRECEDING` to
+/// `CURRENT ROW`). Rows ordered before the earliest changed key are left
+/// untouched; the recomputed suffix is seeded from the stored value of the
+/// preceding row. Other window shapes always recompute whole partitions.
+///
+/// Changing this setting affects delta SQL generated after the next
+/// template invalidation.
+///
+/// Default: true.
+pub static PGS_WINDOW_SUFFIX_RECOMPUTE: GucSetting<bool> = GucSetting::<bool>::new(true);
+
+/// MINMAX-MS (v0.49.0): Back top-level MIN/MAX aggregates of DIFFERENTIAL
+/// stream tables with an ordered multiset side table.
+///
+/// The side table keeps a count per `(group, value)`. When a refresh
+/// deletes a group's current extremum, the replacement is read from the
+/// side table's index instead of re-aggregating the group from source.
+/// Side tables are created or dropped by the next FULL refresh (or
+/// reinitialize) of each stream table.
+///
+/// Default: false.
+pub static PGS_MINMAX_MULTISET: GucSetting<bool> = GucSetting::<bool>::new(false);
+
+/// ORDSET-HIST (v0.49.0): Back top-level MODE, PERCENTILE_CONT and
+/// PERCENTILE_DISC aggregates of DIFFERENTIAL stream tables with a
+/// per-group value-count histogram.
+///
+/// A changed group's new value is computed from its histogram instead of
+/// re-sorting the group's source rows. Histograms are created or dropped by
+/// the next FULL refresh (or reinitialize) of each stream table.
+///
+/// Default: false.
+pub static PGS_ORDERED_SET_HISTOGRAM: GucSetting<bool> = GucSetting::<bool>::new(false);
+
+/// ORDSET-HIST (v0.49.0): Significant digits of the approximate rounded
+/// histogram used for PERCENTILE_CONT over double precision columns.
+///
+/// Values are bucketed by rounding to this many significant digits and each
+/// bucket contributes its mean, so the histogram stays small for continuous
+/// data at a relative error below `10^(1 - digits)`. `0` keeps an exact
+/// histogram. Applies at the next FULL refresh.
+///
+/// Default: 0 (exact). Range: 0–15.
+pub static PGS_PERCENTILE_SKETCH_DIGITS: GucSetting<i32> = GucSetting::<i32>::new(0);
+
 /// Register all GUC variables. Called from `_PG_init()`.
 pub fn register_gucs() {
     GucRegistry::define_bool_guc(
@@ -1944,6 +2042,61 @@ pub fn register_gucs() {
         GucFlags::default(),
     );
 
+    // WIN-SUFFIX (v0.49.0): Suffix recompute for cumulative windows.
+    GucRegistry::define_bool_guc(
+        c"pg_trickle.window_suffix_recompute",
+        c"Recompute only the changed suffix of partitions for cumulative window functions.",
+        c"When true (default), ROW_NUMBER/COUNT/SUM windows with a cumulative frame and a \
+           single ORDER BY column recompute only rows at or after the earliest changed key, \
+           seeded from the stored value of the preceding row. Set to false to always \
+           recompute whole partitions.",
+        &PGS_WINDOW_SUFFIX_RECOMPUTE,
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
+    // MINMAX-MS (v0.49.0): Ordered multiset side tables for MIN/MAX.
+    GucRegistry::define_bool_guc(
+        c"pg_trickle.minmax_multiset",
+        c"Maintain MIN/MAX aggregates with an ordered multiset side table.",
+        c"When true, the next FULL refresh of a DIFFERENTIAL stream table creates a side \
+           table per top-level MIN/MAX aggregate holding a count per (group, value). \
+           Deleting a group's extremum then reads the replacement from the side table \
+           instead of rescanning the group. When false (default), the next FULL refresh \
+           drops any side tables.",
+        &PGS_MINMAX_MULTISET,
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
+    // ORDSET-HIST (v0.49.0): Value-count histograms for ordered-set aggregates.
+    GucRegistry::define_bool_guc(
+        c"pg_trickle.ordered_set_histogram",
+        c"Maintain MODE/PERCENTILE aggregates with a per-group value-count histogram.",
+        c"When true, the next FULL refresh of a DIFFERENTIAL stream table creates a side \
+           table per top-level MODE, PERCENTILE_CONT or PERCENTILE_DISC aggregate holding a \
+           count per (group, value). Changed groups are then recomputed from the histogram \
+           instead of re-sorting their source rows. When false (default), the next FULL \
+           refresh drops any histograms.",
+        &PGS_ORDERED_SET_HISTOGRAM,
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
+    GucRegistry::define_int_guc(
+        c"pg_trickle.percentile_sketch_digits",
+        c"Significant digits of the rounded PERCENTILE_CONT histogram (0 = exact).",
+        c"When greater than 0, PERCENTILE_CONT over double precision columns is backed by a \
+           histogram of values rounded to this many significant digits instead of an exact \
+           histogram. Requires pg_trickle.ordered_set_histogram; applies at the \
+           next FULL refresh.",
+        &PGS_PERCENTILE_SKETCH_DIGITS,
+        0,  // min
+        15, // max
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
     // G14-SHC: Cross-backend template cache.
     GucRegistry::define_bool_guc(
         c"pg_trickle.template_cache",
@@ -2035,7 +2188,7 @@ pub fn register_gucs() {
         c"When true, materialized views in defining queries are supported via \
            snapshot-comparison (same mechanism as foreign table polling). \
            A local shadow table stores the previous state; EXCEPT ALL computes \
-           the delta on each refresh cycle.",
+           the delta on each REFRESH MATERIALIZED VIEW.",
         &PGS_MATVIEW_POLLING,
         GucContext::Suset,
         GucFlags::default(),
@@ -2769,8 +2922,9 @@ pub fn register_gucs() {
         c"pg_trickle.cdc_paused",
         c"A07: Pause CDC trigger writes cluster-wide (durable hold).",
         c"When true, CDC trigger bodies return NULL immediately without writing to \
-          the change buffer. Survives session reconnects unlike pg_trickle.enabled. \
-          Default false (CDC writes enabled).",
+          the change buffer (or, with cdc_capture_mode='hold', keep writing while the \
+          scheduler defers refreshes). Survives session reconnects unlike \
+          pg_trickle.enabled. Default false (CDC writes enabled).",
         &PGS_CDC_PAUSED,
         GucContext::Suset,
         GucFlags::default(),
@@ -2939,12 +3093,12 @@ pub fn register_gucs() {
     // O39-8 (v0.39.0): CDC capture mode — explicit discard vs hold semantics.
     GucRegistry::define_string_guc(
         c"pg_trickle.cdc_capture_mode",
-        c"O39-8: CDC capture mode when cdc_paused=on: 'discard' (default) or 'hold' (reserved).",
+        c"O39-8: CDC capture mode when cdc_paused=on: 'discard' (default) or 'hold'.",
         c"Controls what happens to CDC writes while pg_trickle.cdc_paused=on. \
           'discard' (default): trigger bodies return NULL; changes arriving while \
           paused are dropped — stream tables MUST be reinitialized after un-pausing. \
-          'hold': reserved for future use; setting this emits a WARNING and falls back \
-          to 'discard' until a durable hold path is implemented. \
+          'hold': triggers keep writing to the change buffers and the scheduler \
+          defers refreshes; stream tables resume differentially after un-pausing. \
           Check pgtrickle.cdc_pause_status() to see the active mode.",
         &PGS_CDC_CAPTURE_MODE,
         GucContext::Suset,
@@ -2986,7 +3140,7 @@ pub fn register_gucs() {
     GucRegistry::define_int_guc(
         c"pg_trickle.wal_max_changes_per_poll",
         c"A44-3: Maximum WAL changes fetched per poll cycle.",
-        c"Controls the max_changes argument to pg_logical_slot_get_changes(). \
+        c"Controls the max_changes argument to pg_logical_slot_get_binary_changes(). \
           Higher values increase throughput at the cost of larger per-tick memory \
           usage. Lower values reduce per-change latency for high-volume sources. \
           Default: 10000.",
@@ -2997,6 +3151,53 @@ pub fn register_gucs() {
         GucFlags::default(),
     );
 
+    GucRegistry::define_bool_guc(
+        c"pg_trickle.wal_shared_slot",
+        c"WAL-PGO: Share one replication slot per database across WAL-mode sources.",
+        c"When on, sources transitioning to WAL-based CDC join a database-wide \
+          pgoutput slot that is decoded once per tick and routed to each source's \
+          change buffer by publication. Existing dedicated slots are kept. \
+          Default: off.",
+        &PGS_WAL_SHARED_SLOT,
+        GucContext::Suset,
+        GucFlags::default(),
+    );
+
+    GucRegistry::define_bool_guc(
+        c"pg_trickle.wal_receiver",
+        c"WAL-STREAM: Stream the shared WAL slot with a background worker.",
+        c"When on, a per-database worker consumes the shared pgoutput slot over a \
+          replication connection and writes changes to the change buffers \
+          continuously; the scheduler stops polling that slot. Requires \
+          pg_trickle.wal_shared_slot. Default: off.",
+        &PGS_WAL_RECEIVER,
+        GucContext::Sighup,
+        GucFlags::default(),
+    );
+
+    GucRegistry::define_int_guc(
+        c"pg_trickle.wal_receiver_batch_size",
+        c"WAL-STREAM: Changes per WAL receiver change-buffer commit.",
+        c"The WAL receiver commits its change-buffer writes at the first source \
+          transaction boundary after this many changes, or when the stream is \
+          idle. Default: 10000.",
+        &PGS_WAL_RECEIVER_BATCH_SIZE,
+        1,         // min
+        1_000_000, // max
+        GucContext::Sighup,
+        GucFlags::default(),
+    );
+
+    GucRegistry::define_string_guc(
+        c"pg_trickle.wal_receiver_conninfo",
+        c"WAL-STREAM: Connection string for the WAL receiver.",
+        c"libpq connection string used for the replication connection. Empty \
+          connects over the local Unix socket as the bootstrap superuser.",
+        &PGS_WAL_RECEIVER_CONNINFO,
+        GucContext::Sighup,
+        GucFlags::default(),
+    );
+
     GucRegistry::define_int_guc(
         c"pg_trickle.wal_max_lag_bytes",
         c"A44-3: WAL lag bytes threshold for lag warnings.",
@@ -3117,6 +3318,44 @@ pub fn pg_trickle_wal_max_changes_per_poll() -> i64 {
     }
 }
 
+/// WAL-PGO: Returns whether new WAL transitions use the shared slot.
+pub fn pg_trickle_wal_shared_slot() -> bool {
+    #[cfg(test)]
+    {
+        false
+    }
+    #[cfg(not(test))]
+    {
+        PGS_WAL_SHARED_SLOT.get()
+    }
+}
+
+/// WAL-STREAM: Returns whether the shared slot is consumed by the WAL
+/// receiver worker.
+pub fn pg_trickle_wal_receiver() -> bool {
+    #[cfg(test)]
+    {
+        false
+    }
+    #[cfg(not(test))]
+    {
+        PGS_WAL_RECEIVER.get()
+    }
+}
+
+/// WAL-STREAM: Returns the WAL receiver batch size in changes.
+pub fn pg_trickle_wal_receiver_batch_size() -> i64 {
+    PGS_WAL_RECEIVER_BATCH_SIZE.get().max(1) as i64
+}
+
+/// WAL-STREAM: Returns the configured WAL receiver conninfo (empty = local).
+pub fn pg_trickle_wal_receiver_conninfo() -> String {
+    PGS_WAL_RECEIVER_CONNINFO
+        .get()
+        .and_then(|cs| cs.to_str().ok().map(str::to_string))
+        .unwrap_or_default()
+}
+
 /// A44-3: Returns the WAL max lag bytes threshold as i64.
 pub fn pg_trickle_wal_max_lag_bytes() -> i64 {
     #[cfg(test)]
@@ -3387,6 +3626,19 @@ pub fn pg_trickle_aggregate_fast_path() -> bool {
     PGS_AGGREGATE_FAST_PATH.get()
 }
 
+/// WIN-SUFFIX (v0.49.0): Returns whether cumulative window functions use
+/// suffix recomputation instead of whole-partition recomputation.
+pub fn pg_trickle_window_suffix_recompute() -> bool {
+    #[cfg(test)]
+    {
+        true
+    }
+    #[cfg(not(test))]
+    {
+        PGS_WINDOW_SUFFIX_RECOMPUTE.get()
+    }
+}
+
 /// G14-SHC: Returns whether the cross-backend template cache is enabled.
 pub fn pg_trickle_template_cache_enabled() -> bool {
     PGS_TEMPLATE_CACHE.get()
@@ -3804,25 +4056,43 @@ pub fn pg_trickle_trace_id() -> Option<String> {
 /// O39-8 (v0.39.0): Returns the active CDC capture mode.
 ///
 /// When `cdc_paused = on`, this determines whether changes are discarded (default)
-/// or held for later processing. `Hold` mode is reserved; if configured, a WARNING
-/// is emitted and the function returns `Discard`.
+/// or held in the change buffers for later processing.
 pub fn pg_trickle_cdc_capture_mode() -> CdcCaptureMode {
     let raw = PGS_CDC_CAPTURE_MODE
         .get()
         .and_then(|s| s.to_str().ok().map(|v| v.to_string()));
-    let mode = normalize_cdc_capture_mode(raw);
-    if mode == CdcCaptureMode::Hold {
-        pgrx::warning!(
-            "pg_trickle: cdc_capture_mode='hold' is not yet implemented. \
-             Falling back to 'discard'. Changes arriving while cdc_paused=on \
-             will be dropped — reinitialize stream tables after un-pausing."
-        );
-        CdcCaptureMode::Discard
-    } else {
-        mode
+    normalize_cdc_capture_mode(raw)
+}
+
+/// MINMAX-MS (v0.49.0): Returns whether FULL refresh maintains ordered
+/// multiset side tables for MIN/MAX aggregates.
+pub fn pg_trickle_minmax_multiset() -> bool {
+    PGS_MINMAX_MULTISET.get()
+}
+
+/// ORDSET-HIST (v0.49.0): Returns whether FULL refresh maintains value-count
+/// histograms for ordered-set aggregates.
+pub fn pg_trickle_ordered_set_histogram() -> bool {
+    PGS_ORDERED_SET_HISTOGRAM.get()
+}
+
+/// ORDSET-HIST (v0.49.0): Returns the significant digits of the
+/// PERCENTILE_CONT centroid sketch, or `None` for exact histograms.
+pub fn pg_trickle_percentile_sketch_digits() -> Option<u32> {
+    match PGS_PERCENTILE_SKETCH_DIGITS.get() {
+        d if d > 0 => Some(d as u32),
+        _ => None,
     }
 }
 
+/// CDC-HOLD (v0.49.0): Returns true when CDC is paused in `'hold'` mode.
+///
+/// The scheduler defers refresh dispatch while this is true so the change
+/// buffers keep every row captured during the pause.
+pub fn pg_trickle_cdc_hold_active() -> bool {
+    pg_trickle_cdc_paused() && pg_trickle_cdc_capture_mode() == CdcCaptureMode::Hold
+}
+
 /// VP-2 (v0.47.0): Returns the global default drift threshold for
 /// drift-triggered REINDEX operations.
 pub fn pg_trickle_reindex_drift_threshold() -> f64 {
@@ -122,8 +122,6 @@ fn explain_query_rewrite_impl(
         None,
     ));
 
-    let q = apply_pass!("rows_from", q, dvm::rewrite_rows_from);
-
     // TopK detection.
     let topk = dvm::detect_topk_pattern(&q)?;
     let (effective_q, has_topk) = if let Some(ref info) = topk {
@@ -243,6 +241,20 @@ fn collect_tree_constructs_inner(
             }
             collect_tree_constructs_inner(child, cte_registry, out);
         }
+        OpTree::GroupingSets {
+            aggregates,
+            sets,
+            child,
+            ..
+        } => {
+            out.push(format!("grouping_sets:{}", sets.len()));
+            for agg in aggregates {
+                let strategy = dvm::classify_agg_strategy(agg);
+                let label = agg_label(agg);
+                out.push(format!("aggregate:{}({})", label, strategy));
+            }
+            collect_tree_constructs_inner(child, cte_registry, out);
+        }
         OpTree::FullJoin { left, right, .. } => {
             out.push("join:FULL_OUTER".to_string());
             collect_tree_constructs_inner(left, cte_registry, out);
@@ -814,7 +826,6 @@ fn validate_query_impl(query: &str) -> Result<Vec<(String, String, String)>, PgT
         }
         q = q2;
     }
-    let q = apply_rewrite_safe(&q, dvm::rewrite_rows_from, &mut rows);
 
     // TopK detection.
     let topk = dvm::detect_topk_pattern(&q)?;
@@ -1619,7 +1630,7 @@ fn count_complexity(tree: &OpTree) -> (u32, u32, bool, u32) {
             has_window = has_window || lw || rw;
             subqueries += ls + rs;
         }
-        OpTree::Aggregate { child, .. } => {
+        OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
             agg_depth += 1;
             let (cj, ca, cw, cs) = count_complexity(child);
             joins += cj;
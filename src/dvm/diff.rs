@@ -10,6 +10,7 @@
 
 use crate::config::pg_trickle_change_buffer_schema;
 use crate::dvm::operators;
+use crate::dvm::operators::aggregate::HistogramTable;
 use crate::dvm::parser::{CteRegistry, OpTree};
 use crate::error::PgTrickleError;
 use crate::version::Frontier;
@@ -141,6 +142,17 @@ pub struct DiffContext {
     /// HAVING threshold (absent from the ST) and are now crossing it upward.
     /// Reset to `false` after the child diff returns.
     pub having_filter: bool,
+    /// TOPK-PART: How a `Filter` directly above a `Window` (e.g. `rn <= 3`)
+    /// truncates each partition in the ST.  Set by `diff_filter` before
+    /// differentiating its child and taken by `diff_window`, which must then
+    /// refill partitions from the source because the ST no longer holds
+    /// every input row.  `None` when the Window output is stored in full.
+    pub window_truncation: Option<operators::window::WindowTruncation>,
+    /// SLIDE-1: SQL expression yielding the clock of the previous refresh
+    /// (see [`prev_refresh_clock_sql`]). Set by [`Self::with_pgt_name`];
+    /// `diff_filter` needs it to emit expiry deltas for sliding-window
+    /// predicates. `None` disables sliding-window maintenance.
+    pub prev_clock_sql: Option<String>,
     /// P2-5: CDC column names per source table, ordered by `attnum`.
     ///
     /// Maps `table_oid` → ordered CDC column names (from
@@ -223,6 +235,21 @@ pub struct DiffContext {
     /// - `Some(default)` → use the algebraic formula (result is `default` for empty groups)
     /// - `None` → return NULL (bare SUM result for empty groups)
     pub agg_sum_coalesce_defaults: HashMap<String, String>,
+    /// MINMAX-MS (v0.49.0): Ordered multiset side tables backing top-level
+    /// MIN/MAX aggregates, keyed by aggregate alias. Values are quoted,
+    /// schema-qualified table names.
+    ///
+    /// Resolved at context creation from the tables that actually exist, so
+    /// the delta SQL only references side tables a FULL refresh populated.
+    pub minmax_multisets: HashMap<String, String>,
+    /// ORDSET-HIST (v0.49.0): Value-count histograms backing top-level
+    /// MODE / PERCENTILE_CONT / PERCENTILE_DISC aggregates, keyed by
+    /// aggregate alias. Resolved like [`Self::minmax_multisets`].
+    pub ordered_set_histograms: HashMap<String, HistogramTable>,
+    /// MINMAX-MS / ORDSET-HIST: `(side table, upsert body)` pairs registered
+    /// by `diff_aggregate`. The body references CTEs of this context and is
+    /// wrapped with [`DiffContext::build_with_statement`] before execution.
+    pub side_table_maintenance: Vec<(String, String)>,
 }
 
 /// A41-1: Build a collision-resistant structural fingerprint of an OpTree
@@ -347,6 +374,28 @@ fn snapshot_cache_key(op: &crate::dvm::parser::OpTree) -> String {
                 build_fingerprint(child, out);
                 push(out, ")");
             }
+            OpTree::GroupingSets {
+                group_by,
+                sets,
+                aggregates,
+                grouping_funcs,
+                child,
+            } => {
+                push(out, "GS");
+                for g in group_by {
+                    push_expr(out, g);
+                }
+                push(out, &format!("{:?}", sets));
+                for agg in aggregates {
+                    push(out, &format!("{:?}", agg));
+                }
+                for gf in grouping_funcs {
+                    push(out, &format!("{:?}", gf));
+                }
+                push(out, "(");
+                build_fingerprint(child, out);
+                push(out, ")");
+            }
             OpTree::Distinct { child } => {
                 push(out, "D(");
                 build_fingerprint(child, out);
@@ -532,6 +581,8 @@ impl DiffContext {
             delta_source: DeltaSource::ChangeBuffer,
             st_column_alias_map: None,
             having_filter: false,
+            window_truncation: None,
+            prev_clock_sql: None,
             source_cdc_columns: HashMap::new(),
             source_key_columns: HashMap::new(),
             scan_pushed_predicate: None,
@@ -542,6 +593,9 @@ impl DiffContext {
             fallback_leaf_oids: HashSet::new(),
             source_buffer_names: HashMap::new(),
             agg_sum_coalesce_defaults: HashMap::new(),
+            minmax_multisets: HashMap::new(),
+            ordered_set_histograms: HashMap::new(),
+            side_table_maintenance: Vec::new(),
         }
     }
 
@@ -569,6 +623,8 @@ impl DiffContext {
             delta_source: DeltaSource::ChangeBuffer,
             st_column_alias_map: None,
             having_filter: false,
+            window_truncation: None,
+            prev_clock_sql: None,
             source_cdc_columns: HashMap::new(),
             source_key_columns: HashMap::new(),
             scan_pushed_predicate: None,
@@ -579,6 +635,9 @@ impl DiffContext {
             fallback_leaf_oids: HashSet::new(),
             source_buffer_names: HashMap::new(),
             agg_sum_coalesce_defaults: HashMap::new(),
+            minmax_multisets: HashMap::new(),
+            ordered_set_histograms: HashMap::new(),
+            side_table_maintenance: Vec::new(),
         }
     }
 
@@ -645,6 +704,7 @@ impl DiffContext {
             schema.replace('"', "\"\""),
             name.replace('"', "\"\""),
         ));
+        self.prev_clock_sql = Some(prev_refresh_clock_sql(schema, name));
         self
     }
 
@@ -706,6 +766,7 @@ impl DiffContext {
             OpTree::LeftJoin { .. } => operators::outer_join::diff_left_join(self, op),
             OpTree::FullJoin { .. } => operators::full_join::diff_full_join(self, op),
             OpTree::Aggregate { .. } => operators::aggregate::diff_aggregate(self, op),
+            OpTree::GroupingSets { .. } => operators::grouping_sets::diff_grouping_sets(self, op),
             OpTree::Distinct { .. } => operators::distinct::diff_distinct(self, op),
             OpTree::UnionAll { .. } => operators::union_all::diff_union_all(self, op),
             OpTree::Intersect { .. } => operators::intersect::diff_intersect(self, op),
@@ -838,8 +899,16 @@ impl DiffContext {
 
     /// Build the final WITH query from accumulated CTEs.
     pub(crate) fn build_with_query(&self, final_cte: &str) -> String {
+        self.build_with_statement(&format!("SELECT * FROM {final_cte}"))
+    }
+
+    /// Prefix an arbitrary statement with the accumulated CTEs.
+    ///
+    /// Used for data-modifying statements (e.g. MINMAX-MS side-table
+    /// maintenance) that read the same delta CTEs as the delta query.
+    pub(crate) fn build_with_statement(&self, body: &str) -> String {
         if self.ctes.is_empty() {
-            return format!("SELECT * FROM {final_cte}");
+            return body.to_string();
         }
 
         let has_recursive = self.ctes.iter().any(|(_, _, is_rec, _)| *is_rec);
@@ -863,10 +932,7 @@ impl DiffContext {
             })
             .collect();
 
-        format!(
-            "{with_keyword} {}\nSELECT * FROM {final_cte}",
-            cte_defs.join(",\n"),
-        )
+        format!("{with_keyword} {}\n{body}", cte_defs.join(",\n"))
     }
 }
 
@@ -901,6 +967,23 @@ pub fn prefixed_col_list(prefix: &str, cols: &[String]) -> String {
     out
 }
 
+/// SLIDE-1: SQL expression for the clock of the previous refresh of a
+/// stream table.
+///
+/// Read from the stored frontier at execution time (rather than baked into
+/// the SQL) so cached delta templates stay valid across refreshes. Falls
+/// back to `data_timestamp` for frontiers written before the clock was
+/// recorded, and to `now()` when neither is available (no expiry).
+pub fn prev_refresh_clock_sql(schema: &str, name: &str) -> String {
+    format!(
+        "COALESCE((SELECT COALESCE((__pgt_st.frontier ->> 'clock')::timestamptz, \
+         __pgt_st.data_timestamp) FROM pgtrickle.pgt_stream_tables __pgt_st \
+         WHERE __pgt_st.pgt_schema = '{}' AND __pgt_st.pgt_name = '{}'), now())",
+        schema.replace('\'', "''"),
+        name.replace('\'', "''"),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,13 +57,14 @@ pub mod row_id;
 
 pub use diff::DiffContext;
 pub use parser::{
-    CteRegistry, ParseResult, TopKInfo, check_ivm_support, check_ivm_support_with_registry,
-    check_monotonicity, classify_agg_strategy, detect_topk_pattern, has_order_by_without_limit,
-    parse_defining_query, parse_defining_query_full, query_has_cte, query_has_recursive_cte,
-    reject_limit_offset, reject_materialized_views, reject_unsupported_constructs,
-    rewrite_correlated_scalar_in_select, rewrite_demorgan_sublinks, rewrite_distinct_on,
-    rewrite_grouping_sets, rewrite_nested_window_exprs, rewrite_rows_from,
-    rewrite_scalar_subquery_in_where, rewrite_sublinks_in_or, rewrite_views_inline,
+    CteRegistry, ParseResult, SourceRename, TopKInfo, check_ivm_support,
+    check_ivm_support_with_registry, check_monotonicity, check_window_truncation_support,
+    classify_agg_strategy, detect_topk_pattern, has_order_by_without_limit, parse_defining_query,
+    parse_defining_query_full, query_has_cte, query_has_recursive_cte, reject_limit_offset,
+    reject_materialized_views, reject_unsupported_constructs, rewrite_correlated_scalar_in_select,
+    rewrite_demorgan_sublinks, rewrite_distinct_on, rewrite_grouping_sets,
+    rewrite_nested_window_exprs, rewrite_scalar_subquery_in_where, rewrite_source_rename,
+    rewrite_source_rename_order_by, rewrite_sublinks_in_or, rewrite_views_inline,
     tree_worst_volatility_with_registry, validate_immediate_mode_support,
     warn_limit_without_order_in_subqueries,
 };
@@ -114,6 +115,18 @@ thread_local! {
     /// Local snapshot of the shared `CACHE_GENERATION` counter.
     /// When the shared value advances past this, the entire cache is flushed.
     static LOCAL_DELTA_CACHE_GEN: Cell<u64> = const { Cell::new(0) };
+
+    /// SLIDE-1: Per-session cache of sliding-window boundaries, keyed by
+    /// `pgt_id` and holding `(query_hash, boundaries)`. `None` means the
+    /// defining query has no sliding-window filter.
+    static SLIDING_PROBE_CACHE: RefCell<HashMap<i64, (u64, Option<Vec<SlidingBoundary>>)>> =
+        RefCell::new(HashMap::new());
+
+    /// SLIDE-1: Per-session cache of the next sliding-window boundaries,
+    /// keyed by `pgt_id` and holding `(clock_key, crossed_checks)`; see
+    /// [`sliding_window_crossed_checks`].
+    static SLIDING_NEXT_CACHE: RefCell<HashMap<i64, (String, Vec<String>)>> =
+        RefCell::new(HashMap::new());
 }
 
 /// Hash a string using the default hasher (for cache invalidation).
@@ -255,6 +268,12 @@ pub fn invalidate_delta_cache(pgt_id: i64) {
     DELTA_TEMPLATE_CACHE.with(|cache| {
         cache.borrow_mut().remove(&pgt_id);
     });
+    SLIDING_PROBE_CACHE.with(|cache| {
+        cache.borrow_mut().remove(&pgt_id);
+    });
+    SLIDING_NEXT_CACHE.with(|cache| {
+        cache.borrow_mut().remove(&pgt_id);
+    });
 }
 
 /// CACHE-3: Flush all entries from the thread-local delta template cache.
@@ -263,6 +282,8 @@ pub fn invalidate_delta_cache(pgt_id: i64) {
 /// full `pgtrickle.clear_caches()` operation.
 pub fn flush_all_delta_caches() {
     DELTA_TEMPLATE_CACHE.with(|cache| cache.borrow_mut().clear());
+    SLIDING_PROBE_CACHE.with(|cache| cache.borrow_mut().clear());
+    SLIDING_NEXT_CACHE.with(|cache| cache.borrow_mut().clear());
 }
 
 /// UX-1 / CACHE-OBS: Return the current number of entries in the L1
@@ -422,6 +443,48 @@ pub fn generate_delta_query(
     pgt_schema: &str,
     pgt_name: &str,
 ) -> Result<DeltaQueryResult, PgTrickleError> {
+    let PreparedDelta {
+        tree,
+        mut ctx,
+        source_oids,
+    } = prepare_delta_context(
+        defining_query,
+        prev_frontier,
+        new_frontier,
+        pgt_schema,
+        pgt_name,
+    )?;
+
+    let (delta_sql, output_columns, diff_dedup, diff_has_key_changed) =
+        ctx.differentiate_with_columns(&tree)?;
+
+    Ok(DeltaQueryResult {
+        delta_sql,
+        output_columns,
+        source_oids,
+        is_deduplicated: diff_dedup,
+        has_key_changed: diff_has_key_changed,
+        is_all_algebraic: tree.is_all_algebraic_agg(),
+    })
+}
+
+/// A parsed defining query with a fully configured (not yet differentiated)
+/// diff context for one refresh cycle.
+struct PreparedDelta {
+    tree: parser::OpTree,
+    ctx: DiffContext,
+    source_oids: Vec<u32>,
+}
+
+/// Parse and validate a defining query and build the [`DiffContext`] used
+/// by the uncached delta paths.
+fn prepare_delta_context(
+    defining_query: &str,
+    prev_frontier: &Frontier,
+    new_frontier: &Frontier,
+    pgt_schema: &str,
+    pgt_name: &str,
+) -> Result<PreparedDelta, PgTrickleError> {
     // Step 1: Parse the defining query into an operator tree + CTE registry.
     // This now handles recursive CTEs via OpTree::RecursiveCte, so no
     // early bypass is needed.
@@ -448,12 +511,15 @@ pub fn generate_delta_query(
         PgTrickleError::InvalidArgument(format!("RowIdSchema verification failed: {e}"))
     })?;
 
-    // Step 3: Generate the delta query.
-    // Use differentiate_with_columns() to get the diff result's column list,
-    // which includes auxiliary columns (e.g. __pgt_count) for aggregate/distinct.
+    // Step 3: Build the context for the delta query.
+    // Callers use differentiate_with_columns() to get the diff result's column
+    // list, which includes auxiliary columns (e.g. __pgt_count) for
+    // aggregate/distinct.
     let st_user_cols = result.tree.output_columns();
     let is_scan_chain = is_scan_chain_tree(&result.tree);
     let has_pgt_count = result.tree.needs_pgt_count();
+    let side_tables =
+        resolve_agg_side_tables(&result.tree, &result.cte_registry, pgt_schema, pgt_name);
     let mut ctx = DiffContext::new(prev_frontier.clone(), new_frontier.clone())
         .with_pgt_name(pgt_schema, pgt_name)
         .with_cte_registry(result.cte_registry)
@@ -485,19 +551,55 @@ pub fn generate_delta_query(
     // exceeds max_delta_fraction use EXCEPT ALL instead of NOT EXISTS.
     ctx.fallback_leaf_oids = crate::refresh::get_fallback_leaf_oids();
 
-    let (delta_sql, output_columns, diff_dedup, diff_has_key_changed) =
-        ctx.differentiate_with_columns(&result.tree)?;
+    // MINMAX-MS / ORDSET-HIST: side tables populated by the last FULL refresh.
+    ctx.minmax_multisets = side_tables.minmax;
+    ctx.ordered_set_histograms = side_tables.histograms;
 
-    Ok(DeltaQueryResult {
-        delta_sql,
-        output_columns,
+    Ok(PreparedDelta {
+        tree: result.tree,
+        ctx,
         source_oids,
-        is_deduplicated: diff_dedup,
-        has_key_changed: diff_has_key_changed,
-        is_all_algebraic: result.tree.is_all_algebraic_agg(),
     })
 }
 
+/// MINMAX-MS / ORDSET-HIST (v0.49.0): Generate the statements that bring a
+/// stream table's aggregate side tables (MIN/MAX multisets, ordered-set
+/// histograms) up to date with the changes between `prev_frontier` and
+/// `new_frontier`.
+///
+/// Each side table gets an upsert of the net per-value counts from the
+/// aggregate's child delta, followed by a DELETE of values whose count
+/// reached zero. The statements must run before the delta query of the same
+/// cycle, which reads the updated side tables. Returns an empty list when
+/// the stream table has no side tables.
+pub fn generate_side_table_maintenance(
+    defining_query: &str,
+    prev_frontier: &Frontier,
+    new_frontier: &Frontier,
+    pgt_schema: &str,
+    pgt_name: &str,
+) -> Result<Vec<String>, PgTrickleError> {
+    let PreparedDelta { tree, mut ctx, .. } = prepare_delta_context(
+        defining_query,
+        prev_frontier,
+        new_frontier,
+        pgt_schema,
+        pgt_name,
+    )?;
+    if ctx.minmax_multisets.is_empty() && ctx.ordered_set_histograms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    ctx.differentiate(&tree)?;
+
+    let mut statements = Vec::with_capacity(ctx.side_table_maintenance.len() * 2);
+    for (table, upsert) in &ctx.side_table_maintenance {
+        statements.push(ctx.build_with_statement(upsert));
+        statements.push(format!("DELETE FROM {table} WHERE cnt <= 0"));
+    }
+    Ok(statements)
+}
+
 /// Generate the full delta SQL query, using a per-session cache to avoid
 /// re-parsing and re-differentiating the defining query on every refresh.
 ///
@@ -671,6 +773,8 @@ pub fn generate_delta_query_cached(
     let is_scan_chain = is_scan_chain_tree(&result.tree);
     let st_user_cols = result.tree.output_columns();
     let has_pgt_count = result.tree.needs_pgt_count();
+    let side_tables =
+        resolve_agg_side_tables(&result.tree, &result.cte_registry, pgt_schema, pgt_name);
     let mut ctx = DiffContext::new(Frontier::new(), Frontier::new())
         .with_placeholders()
         .with_pgt_name(pgt_schema, pgt_name)
@@ -693,6 +797,10 @@ pub fn generate_delta_query_cached(
     // does not need to call SPI during SQL generation.
     ctx.source_buffer_names = resolve_buffer_names_for_sources(&source_oids);
 
+    // MINMAX-MS / ORDSET-HIST: side tables populated by the last FULL refresh.
+    ctx.minmax_multisets = side_tables.minmax;
+    ctx.ordered_set_histograms = side_tables.histograms;
+
     let (template_sql, output_columns, diff_dedup, diff_has_key_changed) =
         ctx.differentiate_with_columns(&result.tree)?;
 
@@ -781,6 +889,125 @@ fn resolve_st_source_pgt_ids(source_oids: &[u32]) -> HashMap<u32, i64> {
     map
 }
 
+/// Aggregate side tables that exist for a stream table, keyed by aggregate
+/// alias.
+#[derive(Default)]
+struct AggSideTables {
+    /// MINMAX-MS: quoted, schema-qualified MIN/MAX multiset tables.
+    minmax: HashMap<String, String>,
+    /// ORDSET-HIST: ordered-set histograms, exact or rounded.
+    histograms: HashMap<String, operators::aggregate::HistogramTable>,
+}
+
+/// MINMAX-MS / ORDSET-HIST (v0.49.0): Map aggregate aliases to the side
+/// tables that exist for the target stream table.
+///
+/// Side tables are created and dropped by FULL refresh according to
+/// `pg_trickle.minmax_multiset` / `pg_trickle.ordered_set_histogram`;
+/// existence (not the GUCs) decides whether the delta SQL reads them, so a
+/// GUC change takes effect consistently at the next FULL refresh.
+fn resolve_agg_side_tables(
+    tree: &parser::OpTree,
+    registry: &CteRegistry,
+    pgt_schema: &str,
+    pgt_name: &str,
+) -> AggSideTables {
+    use operators::aggregate::{HistogramTable, MinMaxMultisetSpec, OrderedSetHistogramSpec};
+
+    let minmax_candidates = operators::aggregate::minmax_multiset_candidates(tree, registry);
+    let hist_candidates = operators::aggregate::ordered_set_histogram_candidates(tree, registry);
+    if minmax_candidates.is_empty() && hist_candidates.is_empty() {
+        return AggSideTables::default();
+    }
+    let Some(pgt_id) = pgrx::Spi::get_one_with_args::<i64>(
+        "SELECT pgt_id FROM pgtrickle.pgt_stream_tables \
+         WHERE pgt_schema = $1 AND pgt_name = $2",
+        &[pgt_schema.into(), pgt_name.into()],
+    )
+    .unwrap_or(None) else {
+        return AggSideTables::default();
+    };
+    let change_schema = crate::config::pg_trickle_change_buffer_schema();
+    let qualify = |name: &str| {
+        format!(
+            "{}.{}",
+            diff::quote_ident(&change_schema),
+            diff::quote_ident(name)
+        )
+    };
+    let exists = |table: &str| {
+        pgrx::Spi::get_one_with_args::<bool>("SELECT to_regclass($1) IS NOT NULL", &[table.into()])
+            .unwrap_or(None)
+            .unwrap_or(false)
+    };
+
+    let minmax = minmax_candidates
+        .into_iter()
+        .filter_map(|spec| {
+            let table = qualify(&MinMaxMultisetSpec::table_name(pgt_id, spec.ordinal));
+            exists(&table).then_some((spec.alias, table))
+        })
+        .collect();
+
+    let histograms = hist_candidates
+        .into_iter()
+        .filter_map(|spec| {
+            let exact = qualify(&OrderedSetHistogramSpec::table_name(
+                pgt_id,
+                spec.ordinal,
+                None,
+            ));
+            if exists(&exact) {
+                let hist = HistogramTable {
+                    table: exact,
+                    sketch_digits: None,
+                };
+                return Some((spec.alias, hist));
+            }
+            if !spec.sketchable {
+                return None;
+            }
+            // A rounded histogram's precision is part of its name: hist_{id}_{n}_s{digits}.
+            let prefix = format!("hist_{pgt_id}_{}_s", spec.ordinal);
+            let name = pgrx::Spi::get_one_with_args::<String>(
+                "SELECT c.relname::text FROM pg_catalog.pg_class c \
+                 JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE n.nspname = $1 AND starts_with(c.relname::text, $2) LIMIT 1",
+                &[change_schema.as_str().into(), prefix.as_str().into()],
+            )
+            .unwrap_or(None)?;
+            let digits = name.strip_prefix(&prefix)?.parse::<u32>().ok()?;
+            let hist = HistogramTable {
+                table: qualify(&name),
+                sketch_digits: Some(digits),
+            };
+            Some((spec.alias, hist))
+        })
+        .collect();
+
+    AggSideTables { minmax, histograms }
+}
+
+/// MINMAX-MS (v0.49.0): MIN/MAX aggregates of a defining query that can be
+/// backed by ordered multiset side tables. Empty if the query does not parse.
+pub fn query_minmax_multiset_candidates(
+    defining_query: &str,
+) -> Vec<operators::aggregate::MinMaxMultisetSpec> {
+    parse_defining_query_full(defining_query)
+        .map(|r| operators::aggregate::minmax_multiset_candidates(&r.tree, &r.cte_registry))
+        .unwrap_or_default()
+}
+
+/// ORDSET-HIST (v0.49.0): Ordered-set aggregates of a defining query that
+/// can be backed by value-count histograms. Empty if the query does not parse.
+pub fn query_ordered_set_histogram_candidates(
+    defining_query: &str,
+) -> Vec<operators::aggregate::OrderedSetHistogramSpec> {
+    parse_defining_query_full(defining_query)
+        .map(|r| operators::aggregate::ordered_set_histogram_candidates(&r.tree, &r.cte_registry))
+        .unwrap_or_default()
+}
+
 /// CITUS-4: Resolve the change buffer base name for each source OID.
 ///
 /// For base tables (not ST sources), the buffer is named
@@ -888,6 +1115,9 @@ pub(crate) fn reclassify_vector_aggregates(
     match tree {
         OpTree::Aggregate {
             aggregates, child, ..
+        }
+        | OpTree::GroupingSets {
+            aggregates, child, ..
         } => {
             reclassify_vector_aggregates(child, vector_cols);
             let child_oids = child.source_oids();
@@ -1030,6 +1260,9 @@ pub(crate) fn extract_vector_agg_output_dims(tree: &parser::OpTree) -> Vec<(Stri
         match tree {
             OpTree::Aggregate {
                 aggregates, child, ..
+            }
+            | OpTree::GroupingSets {
+                aggregates, child, ..
             } => {
                 walk(child, result);
                 let child_oids = child.source_oids();
@@ -1095,6 +1328,167 @@ pub(crate) fn extract_vector_agg_output_dims(_tree: &parser::OpTree) -> Vec<(Str
     Vec::new()
 }
 
+/// SLIDE-1: Placeholder for the row value in [`SlidingBoundary::crossed_sql`].
+pub const SLIDING_BOUNDARY_VALUE: &str = "__PGT_SLIDING_BOUNDARY_VALUE__";
+
+/// SLIDE-1: The next boundary of one sliding comparison
+/// (`ts > now() - interval '1 hour'` and similar) of a defining query.
+#[derive(Debug, Clone)]
+pub struct SlidingBoundary {
+    /// `SELECT min(value)::text, pg_typeof(min(value))::text` over the
+    /// source rows whose comparison flips first as the clock advances,
+    /// evaluated at the previous refresh clock: the oldest row still inside
+    /// a `>` / `>=` window, or the oldest row not yet inside a `<` / `<=`
+    /// one. A B-tree index on the compared column serves it.
+    pub next_sql: String,
+    /// `SELECT` of whether that row's comparison has flipped at `now()`,
+    /// with the row value in place of [`SLIDING_BOUNDARY_VALUE`]. Reads no
+    /// table.
+    pub crossed_sql: String,
+}
+
+/// SLIDE-1: The sliding-window boundaries of a defining query, one per
+/// sliding comparison.
+///
+/// Returns `None` when the defining query has no sliding-window filter.
+/// Sliding windows change without DML on their sources, so the scheduler
+/// and the no-change short-circuit check them before skipping a refresh
+/// (see [`sliding_window_crossed_checks`]). Cached per session, keyed by
+/// `pgt_id` and query hash.
+pub fn sliding_window_boundaries(
+    pgt_id: i64,
+    defining_query: &str,
+    pgt_schema: &str,
+    pgt_name: &str,
+) -> Option<Vec<SlidingBoundary>> {
+    let query_hash = hash_string(defining_query);
+    if let Some(cached) = SLIDING_PROBE_CACHE.with(|cache| {
+        cache
+            .borrow()
+            .get(&pgt_id)
+            .filter(|(h, _)| *h == query_hash)
+            .map(|(_, boundaries)| boundaries.clone())
+    }) {
+        return cached;
+    }
+
+    let boundaries = parse_defining_query_full(defining_query)
+        .ok()
+        .and_then(|result| {
+            let prev_clock = diff::prev_refresh_clock_sql(pgt_schema, pgt_name);
+            let boundaries: Vec<SlidingBoundary> = parser::collect_sliding_window_filters(&result)
+                .into_iter()
+                .filter_map(|(predicate, scan)| match scan {
+                    parser::OpTree::Scan {
+                        schema,
+                        table_name,
+                        alias,
+                        ..
+                    } => Some((predicate, schema, table_name, alias)),
+                    _ => None,
+                })
+                .flat_map(|(predicate, schema, table_name, alias)| {
+                    let from = format!(
+                        "{}.{} {}",
+                        diff::quote_ident(schema),
+                        diff::quote_ident(table_name),
+                        diff::quote_ident(alias),
+                    );
+                    let prev_clock = &prev_clock;
+                    parser::sliding_comparisons(predicate).into_iter().map(
+                        move |(value, op, bound)| {
+                            sliding_boundary(&from, value, op, bound, prev_clock)
+                        },
+                    )
+                })
+                .collect();
+            (!boundaries.is_empty()).then_some(boundaries)
+        });
+
+    SLIDING_PROBE_CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .insert(pgt_id, (query_hash, boundaries.clone()));
+    });
+    boundaries
+}
+
+/// Build the [`SlidingBoundary`] of `value op bound` over `from`.
+fn sliding_boundary(
+    from: &str,
+    value: &parser::Expr,
+    op: &str,
+    bound: &parser::Expr,
+    prev_clock: &str,
+) -> SlidingBoundary {
+    let value_sql = value.to_sql();
+    let bound_prev = parser::substitute_clock(bound, prev_clock).to_sql();
+    let bound_now = bound.to_sql();
+    let crossed = format!("({SLIDING_BOUNDARY_VALUE}) {op} ({bound_now})");
+    // Rows leave a lower bound and enter an upper bound.
+    let (pending_op, crossed) = match op {
+        ">" | ">=" => (op, format!("NOT ({crossed})")),
+        "<" => (">=", crossed),
+        _ => (">", crossed),
+    };
+    SlidingBoundary {
+        next_sql: format!(
+            "SELECT min({value_sql})::text, pg_typeof(min({value_sql}))::text \
+             FROM {from} WHERE ({value_sql}) {pending_op} ({bound_prev})"
+        ),
+        crossed_sql: format!("SELECT COALESCE({crossed}, FALSE)"),
+    }
+}
+
+/// SLIDE-1: Table-free checks, one per sliding comparison with a pending
+/// row, of whether that row crossed its boundary since the previous
+/// refresh.
+///
+/// The next boundaries are read from the sources once per previous
+/// refresh, identified by `clock_key`, and cached; until the next refresh
+/// each scheduler tick only evaluates the checks against `now()`. Without a
+/// `clock_key` (never refreshed) nothing is cached.
+pub fn sliding_window_crossed_checks(
+    pgt_id: i64,
+    boundaries: &[SlidingBoundary],
+    clock_key: Option<&str>,
+) -> Result<Vec<String>, PgTrickleError> {
+    if let Some(key) = clock_key
+        && let Some(cached) = SLIDING_NEXT_CACHE.with(|cache| {
+            cache
+                .borrow()
+                .get(&pgt_id)
+                .filter(|(k, _)| k == key)
+                .map(|(_, checks)| checks.clone())
+        })
+    {
+        return Ok(cached);
+    }
+
+    let mut checks = Vec::new();
+    for boundary in boundaries {
+        let (value, type_name) = pgrx::Spi::get_two::<String, String>(&boundary.next_sql) // nosemgrep: rust.spi.query.dynamic-format
+            .map_err(|e| PgTrickleError::SpiError(format!("sliding-window boundary: {e}")))?;
+        if let (Some(value), Some(type_name)) = (value, type_name) {
+            let literal = format!("CAST('{}' AS {type_name})", value.replace('\'', "''"));
+            checks.push(
+                boundary
+                    .crossed_sql
+                    .replace(SLIDING_BOUNDARY_VALUE, &literal),
+            );
+        }
+    }
+
+    if let Some(key) = clock_key {
+        SLIDING_NEXT_CACHE.with(|cache| {
+            cache
+                .borrow_mut()
+                .insert(pgt_id, (key.to_string(), checks.clone()));
+        });
+    }
+    Ok(checks)
+}
+
 /// Check whether a defining query needs the `__pgt_count` auxiliary column
 /// (the top-level operator is Aggregate or Distinct).
 ///
@@ -1230,6 +1624,21 @@ pub fn row_id_expr_for_query(defining_query: &str) -> String {
     }
 }
 
+/// GSETS-NATIVE: row ID expression for a grouping-sets defining query.
+///
+/// Grouping-sets rows cannot be keyed from the output columns alone (a
+/// rolled-up NULL and a NULL group value look the same), so the hash also
+/// covers `GROUPING(…)` and must be evaluated inside the query itself; see
+/// `inject_row_id`. Returns `None` when the root is not a GroupingSets node.
+pub fn grouping_sets_row_id_sql(defining_query: &str) -> Option<String> {
+    match parse_defining_query(defining_query).ok()? {
+        parser::OpTree::GroupingSets { group_by, .. } => {
+            Some(operators::grouping_sets::full_refresh_row_id_sql(&group_by))
+        }
+        _ => None,
+    }
+}
+
 /// Check whether the root of an OpTree is a scalar aggregate (GROUP BY
 /// with no columns). Looks through transparent wrappers (Filter, Project,
 /// Subquery) to find the Aggregate node.
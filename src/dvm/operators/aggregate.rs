@@ -10,8 +10,9 @@
 //! - Changes value → UPDATE (emitted as DELETE + INSERT pair)
 
 use crate::dvm::diff::{DeltaSource, DiffContext, DiffResult, quote_ident};
+use crate::dvm::operators::grouping_sets::{GROUPING_ID_COL, GroupingSetsLayout};
 use crate::dvm::operators::scan::build_hash_expr;
-use crate::dvm::parser::{AggExpr, AggFunc, CteRegistry, Expr, OpTree};
+use crate::dvm::parser::{AggExpr, AggFunc, CteRegistry, Expr, OpTree, SortExpr};
 use crate::error::PgTrickleError;
 
 /// Resolve a column reference expression against child CTE column names.
@@ -83,7 +84,7 @@ fn resolve_group_col(expr: &Expr, child_cols: &[String]) -> String {
 /// Compound SQL expressions (containing `(`) are returned as-is — quoting
 /// them as identifiers would cause PostgreSQL to look for a column whose
 /// name is literally the expression string.
-fn col_ref_or_sql_expr(s: &str) -> String {
+pub(crate) fn col_ref_or_sql_expr(s: &str) -> String {
     if s.contains('(') {
         s.to_string()
     } else {
@@ -99,25 +100,35 @@ fn col_ref_or_sql_expr(s: &str) -> String {
 fn resolve_expr_for_child(expr: &Expr, child_cols: &[String]) -> String {
     match expr {
         Expr::ColumnRef { .. } => resolve_col_for_child(expr, child_cols),
-        Expr::BinaryOp { op, left, right } => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } => {
             format!(
                 "({} {op} {})",
                 resolve_expr_for_child(left, child_cols),
                 resolve_expr_for_child(right, child_cols),
             )
         }
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name, args, ..
+        } => {
             let resolved_args: Vec<String> = args
                 .iter()
                 .map(|a| resolve_expr_for_child(a, child_cols))
                 .collect();
             format!("{func_name}({})", resolved_args.join(", "))
         }
+        Expr::SubLink { .. } => {
+            crate::dvm::operators::filter::replace_column_refs_in_raw(&expr.to_sql(), child_cols)
+        }
         Expr::Raw(sql) => {
             // Best-effort: replace column refs in raw SQL
             crate::dvm::operators::filter::replace_column_refs_in_raw(sql, child_cols)
         }
-        _ => expr.to_sql(),
+        Expr::Literal(_) | Expr::Star { .. } => expr.to_sql(),
+        _ => expr
+            .map_children(|c| Expr::Raw(resolve_expr_for_child(c, child_cols)))
+            .to_sql(),
     }
 }
 
@@ -152,7 +163,7 @@ fn child_has_full_join(op: &OpTree) -> bool {
 /// emitting the CTE alias as a bare table reference (which would fail at
 /// SQL execution time for chained CTEs that are not real relations), we
 /// recursively resolve the CTE body and emit its underlying source.
-fn child_to_from_sql(child: &OpTree, registry: &CteRegistry) -> Option<String> {
+pub(crate) fn child_to_from_sql(child: &OpTree, registry: &CteRegistry) -> Option<String> {
     match child {
         OpTree::Scan {
             schema,
@@ -309,6 +320,477 @@ fn child_to_from_sql(child: &OpTree, registry: &CteRegistry) -> Option<String> {
     }
 }
 
+// ── MINMAX-MS: ordered multiset side tables for MIN/MAX ────────────
+
+/// A top-level MIN/MAX aggregate that can be backed by an ordered multiset
+/// side table (`pg_trickle.minmax_multiset`).
+///
+/// The side table holds one row per `(group, value)` with the number of
+/// source rows carrying that value. When a refresh deletes a group's stored
+/// extremum, the replacement is read from the side table's primary-key
+/// index instead of re-aggregating the group from source data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinMaxMultisetSpec {
+    /// Output alias of the aggregate (and its stream table column).
+    pub alias: String,
+    /// Position of the aggregate in its Aggregate node; names the side table.
+    pub ordinal: usize,
+    /// `SELECT grp, val, cnt` over current source data, used to populate
+    /// the side table on FULL refresh.
+    pub populate_sql: String,
+}
+
+impl MinMaxMultisetSpec {
+    /// Unquoted side table name for aggregate `ordinal` of stream table
+    /// `pgt_id`. Side tables live in the change buffer schema.
+    pub fn table_name(pgt_id: i64, ordinal: usize) -> String {
+        format!("minmax_{pgt_id}_{ordinal}")
+    }
+}
+
+/// Whether a MIN/MAX aggregate can use an ordered multiset side table.
+///
+/// DISTINCT does not change MIN/MAX, but such aggregates take the DISTINCT
+/// merge path and are left on the rescan CTE.
+fn is_minmax_multiset_eligible(agg: &AggExpr) -> bool {
+    matches!(agg.function, AggFunc::Min | AggFunc::Max)
+        && !agg.is_distinct
+        && agg.argument.is_some()
+}
+
+/// Group key stored in a side table's `grp` column.
+///
+/// Evaluates to the same value as the merge CTE's row-id hash
+/// (`pg_trickle_hash(d.col::TEXT)` / `pg_trickle_hash_multi(...)`), so the
+/// merge can look a group up with its own `row_id_expr`.
+fn minmax_group_hash(group_sql: &[String]) -> String {
+    if group_sql.is_empty() {
+        "pgtrickle.pg_trickle_hash('__singleton_group')".to_string()
+    } else {
+        let texts: Vec<String> = group_sql.iter().map(|g| format!("({g})::TEXT")).collect();
+        build_hash_expr(&texts)
+    }
+}
+
+/// Find the MIN/MAX aggregates of a defining query that can be backed by
+/// ordered multiset side tables.
+///
+/// Only the aggregate that produces the stream table rows qualifies (the
+/// tree root, optionally under a Project). HAVING forces a full rescan of
+/// affected groups, and children whose FROM clause cannot be reconstructed
+/// cannot populate the side table, so both yield no candidates.
+pub fn minmax_multiset_candidates(
+    tree: &OpTree,
+    registry: &CteRegistry,
+) -> Vec<MinMaxMultisetSpec> {
+    let Some(source) = MultisetSource::of(tree, registry) else {
+        return Vec::new();
+    };
+    source
+        .aggregates
+        .iter()
+        .enumerate()
+        .filter(|(_, agg)| is_minmax_multiset_eligible(agg))
+        .filter_map(|(ordinal, agg)| {
+            let arg = agg.argument.as_ref()?.to_sql();
+            Some(MinMaxMultisetSpec {
+                alias: agg.alias.clone(),
+                ordinal,
+                populate_sql: format!(
+                    "SELECT {}, {arg}, COUNT(*)\n{}\nGROUP BY 1, 2",
+                    source.grp,
+                    source.from_where(agg, &arg),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// The top-level Aggregate of a defining query, with what is needed to
+/// populate multiset side tables from current source data.
+struct MultisetSource<'a> {
+    aggregates: &'a [AggExpr],
+    /// Group hash over source columns (see [`minmax_group_hash`]).
+    grp: String,
+    from_sql: String,
+    /// `WHERE`, or `AND` when `from_sql` already ends in a WHERE clause.
+    keyword: &'static str,
+}
+
+impl<'a> MultisetSource<'a> {
+    fn of(tree: &'a OpTree, registry: &CteRegistry) -> Option<Self> {
+        let agg_node = match tree {
+            OpTree::Project { child, .. } => child.as_ref(),
+            other => other,
+        };
+        let OpTree::Aggregate {
+            group_by,
+            aggregates,
+            child,
+        } = agg_node
+        else {
+            return None;
+        };
+        let from_sql = child_to_from_sql(child, registry)?;
+        // Same outer-WHERE detection as build_rescan_cte.
+        let has_outer_where = from_sql.contains(" WHERE ") && !from_sql.starts_with('(');
+        let group_sql: Vec<String> = group_by.iter().map(|e| e.to_sql()).collect();
+        Some(MultisetSource {
+            aggregates,
+            grp: minmax_group_hash(&group_sql),
+            from_sql,
+            keyword: if has_outer_where { "AND" } else { "WHERE" },
+        })
+    }
+
+    /// `FROM ... WHERE` restricted to the rows `agg` aggregates over:
+    /// non-NULL `value` and the aggregate's FILTER clause.
+    fn from_where(&self, agg: &AggExpr, value: &str) -> String {
+        let mut conds = vec![format!("({value}) IS NOT NULL")];
+        if let Some(f) = &agg.filter {
+            conds.push(format!("({})", f.to_sql()));
+        }
+        format!(
+            "FROM {}\n{} {}",
+            self.from_sql,
+            self.keyword,
+            conds.join(" AND ")
+        )
+    }
+}
+
+/// Upsert that applies the net per-value change of one refresh cycle's
+/// child delta to a MIN/MAX side table.
+///
+/// Rows whose count drops to zero are removed by a follow-up DELETE issued
+/// by the refresh executor.
+fn minmax_multiset_maintenance_sql(
+    agg: &AggExpr,
+    group_by: &[Expr],
+    child_cte: &str,
+    child_cols: &[String],
+    table: &str,
+) -> String {
+    let arg = agg
+        .argument
+        .as_ref()
+        .map(|e| resolve_expr_for_child(e, child_cols))
+        .unwrap_or_else(|| "NULL".into());
+    multiset_upsert_sql(agg, group_by, child_cte, child_cols, table, &arg, None)
+}
+
+/// Shared upsert of per-(group, value) net counts from a child delta into
+/// a multiset side table (`grp`, `val`, `cnt` [, `total`]).
+///
+/// `bucket` maps the value to the stored `val` (rounded histograms); the
+/// `total` column then accumulates the raw values of each bucket.
+fn multiset_upsert_sql(
+    agg: &AggExpr,
+    group_by: &[Expr],
+    child_cte: &str,
+    child_cols: &[String],
+    table: &str,
+    value: &str,
+    bucket: Option<&str>,
+) -> String {
+    let group_sql: Vec<String> = group_by
+        .iter()
+        .map(|e| col_ref_or_sql_expr(&resolve_group_col(e, child_cols)))
+        .collect();
+    let grp = minmax_group_hash(&group_sql);
+    let filter_and = agg
+        .filter
+        .as_ref()
+        .map(|f| format!(" AND {}", resolve_expr_for_child(f, child_cols)))
+        .unwrap_or_default();
+    let net = "SUM(CASE WHEN __pgt_action = 'I' THEN 1 ELSE -1 END)";
+    match bucket {
+        None => format!(
+            "INSERT INTO {table} AS __pgt_mm (grp, val, cnt)\n\
+             SELECT {grp}, {value}, {net}\n\
+             FROM {child_cte}\n\
+             WHERE {value} IS NOT NULL{filter_and}\n\
+             GROUP BY 1, 2\n\
+             HAVING {net} <> 0\n\
+             ON CONFLICT (grp, val) DO UPDATE SET cnt = __pgt_mm.cnt + EXCLUDED.cnt"
+        ),
+        Some(bucket) => {
+            let net_total = format!(
+                "SUM(CASE WHEN __pgt_action = 'I' THEN ({value})::float8 \
+                 ELSE -({value})::float8 END)"
+            );
+            format!(
+                "INSERT INTO {table} AS __pgt_mm (grp, val, cnt, total)\n\
+                 SELECT {grp}, {bucket}, {net}, {net_total}\n\
+                 FROM {child_cte}\n\
+                 WHERE {value} IS NOT NULL{filter_and}\n\
+                 GROUP BY 1, 2\n\
+                 HAVING {net} <> 0 OR {net_total} <> 0\n\
+                 ON CONFLICT (grp, val) DO UPDATE SET cnt = __pgt_mm.cnt + EXCLUDED.cnt, \
+                 total = __pgt_mm.total + EXCLUDED.total"
+            )
+        }
+    }
+}
+
+/// Merge expression for a MIN/MAX aggregate backed by an ordered multiset
+/// side table.
+///
+/// The side table has already absorbed this cycle's child delta, so when
+/// the stored extremum was deleted the first remaining value of the group
+/// in index order is the new extremum. Otherwise the algebraic
+/// LEAST/GREATEST merge is exact, as in [`agg_merge_expr`].
+fn minmax_multiset_merge_expr(agg: &AggExpr, st_col: &str, table: &str, grp: &str) -> String {
+    let (func, dir) = if matches!(agg.function, AggFunc::Min) {
+        ("LEAST", "ASC")
+    } else {
+        ("GREATEST", "DESC")
+    };
+    let qt = quote_ident(st_col);
+    let ins = quote_ident(&format!("__ins_{}", agg.alias));
+    let del = quote_ident(&format!("__del_{}", agg.alias));
+    format!(
+        "CASE WHEN d.{del} IS NOT NULL AND d.{del} = st.{qt} \
+         THEN (SELECT __pgt_mm.val FROM {table} __pgt_mm \
+         WHERE __pgt_mm.grp = {grp} AND __pgt_mm.cnt > 0 \
+         ORDER BY __pgt_mm.val {dir} LIMIT 1) \
+         ELSE {func}(st.{qt}, d.{ins}) END"
+    )
+}
+
+// ── ORDSET-HIST: value-count histograms for ordered-set aggregates ──
+
+/// A top-level MODE / PERCENTILE_CONT / PERCENTILE_DISC aggregate that can
+/// be backed by a per-group value-count histogram
+/// (`pg_trickle.ordered_set_histogram`).
+///
+/// The histogram has the same `(grp, val, cnt)` shape as a MIN/MAX side
+/// table. A changed group's new value is read from its histogram, whose
+/// size is the number of distinct values in the group, instead of sorting
+/// the group's source rows again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderedSetHistogramSpec {
+    /// Output alias of the aggregate (and its stream table column).
+    pub alias: String,
+    /// Position of the aggregate in its Aggregate node; names the side table.
+    pub ordinal: usize,
+    /// PERCENTILE_CONT may use an approximate rounded histogram instead of an
+    /// exact one (double precision columns only; checked by the caller).
+    pub sketchable: bool,
+    grp: String,
+    value: String,
+    from_where: String,
+}
+
+impl OrderedSetHistogramSpec {
+    /// Unquoted side table name for aggregate `ordinal` of stream table
+    /// `pgt_id`. A rounded histogram records its precision in the name so that the
+    /// delta SQL buckets values exactly like the FULL refresh did.
+    pub fn table_name(pgt_id: i64, ordinal: usize, sketch_digits: Option<u32>) -> String {
+        match sketch_digits {
+            None => format!("hist_{pgt_id}_{ordinal}"),
+            Some(digits) => format!("hist_{pgt_id}_{ordinal}_s{digits}"),
+        }
+    }
+
+    /// `SELECT grp, val, cnt [, total]` over current source data, used to
+    /// populate the side table on FULL refresh.
+    pub fn populate_sql(&self, sketch_digits: Option<u32>) -> String {
+        let (grp, value, from_where) = (&self.grp, &self.value, &self.from_where);
+        match sketch_digits {
+            None => format!("SELECT {grp}, {value}, COUNT(*)\n{from_where}\nGROUP BY 1, 2"),
+            Some(digits) => format!(
+                "SELECT {grp}, {}, COUNT(*), SUM(({value})::float8)\n{from_where}\nGROUP BY 1, 2",
+                sketch_bucket_sql(value, digits),
+            ),
+        }
+    }
+}
+
+/// An ordered-set histogram side table resolved for one refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistogramTable {
+    /// Quoted, schema-qualified table name.
+    pub table: String,
+    /// Significant digits of a rounded histogram, or `None` for an exact
+    /// one.
+    pub sketch_digits: Option<u32>,
+}
+
+/// The single WITHIN GROUP sort key of an ordered-set aggregate.
+fn ordered_set_sort_key(agg: &AggExpr) -> Option<&SortExpr> {
+    match agg.order_within_group.as_deref() {
+        Some([sort]) => Some(sort),
+        _ => None,
+    }
+}
+
+/// The fraction of a PERCENTILE_* aggregate, when it is a literal in
+/// `[0, 1]`. Array fractions and parameters keep the group rescan.
+fn percentile_fraction(agg: &AggExpr) -> Option<&str> {
+    match &agg.argument {
+        Some(Expr::Literal(f)) => f
+            .parse::<f64>()
+            .ok()
+            .filter(|v| (0.0..=1.0).contains(v))
+            .map(|_| f.as_str()),
+        _ => None,
+    }
+}
+
+/// Whether an ordered-set aggregate can use a value-count histogram.
+fn is_ordered_set_histogram_eligible(agg: &AggExpr) -> bool {
+    let args_ok = match agg.function {
+        AggFunc::Mode => agg.argument.is_none(),
+        AggFunc::PercentileCont | AggFunc::PercentileDisc => percentile_fraction(agg).is_some(),
+        _ => false,
+    };
+    args_ok && !agg.is_distinct && ordered_set_sort_key(agg).is_some()
+}
+
+/// Bucket of a double precision value in a rounded histogram: the value
+/// rounded to `digits` significant decimal digits. Rounding is monotone, so
+/// buckets sort like their values, and the relative error of a bucket's mean
+/// is below `10^(1 - digits)`. This is plain fixed-precision bucketing, not
+/// an adaptive quantile sketch: the number of buckets of a group is bounded
+/// by the precision and the value range, not by the accuracy needed at the
+/// tails.
+fn sketch_bucket_sql(value: &str, digits: u32) -> String {
+    let v = format!("({value})::float8");
+    format!(
+        "CASE WHEN {v} = 0 OR {v} IN ('Infinity', '-Infinity', 'NaN') THEN {v} \
+         ELSE round({v}::numeric, {digits} - 1 - floor(log(abs({v}::numeric)))::int)::float8 END"
+    )
+}
+
+/// Find the ordered-set aggregates of a defining query that can be backed
+/// by value-count histograms. Same placement rules as
+/// [`minmax_multiset_candidates`].
+pub fn ordered_set_histogram_candidates(
+    tree: &OpTree,
+    registry: &CteRegistry,
+) -> Vec<OrderedSetHistogramSpec> {
+    let Some(source) = MultisetSource::of(tree, registry) else {
+        return Vec::new();
+    };
+    source
+        .aggregates
+        .iter()
+        .enumerate()
+        .filter(|(_, agg)| is_ordered_set_histogram_eligible(agg))
+        .filter_map(|(ordinal, agg)| {
+            let value = ordered_set_sort_key(agg)?.expr.to_sql();
+            Some(OrderedSetHistogramSpec {
+                alias: agg.alias.clone(),
+                ordinal,
+                sketchable: matches!(agg.function, AggFunc::PercentileCont),
+                grp: source.grp.clone(),
+                from_where: source.from_where(agg, &value),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Upsert that applies one refresh cycle's child delta to an ordered-set
+/// histogram (exact or rounded).
+fn ordered_set_histogram_maintenance_sql(
+    agg: &AggExpr,
+    group_by: &[Expr],
+    child_cte: &str,
+    child_cols: &[String],
+    hist: &HistogramTable,
+) -> String {
+    let value = ordered_set_sort_key(agg)
+        .map(|s| resolve_expr_for_child(&s.expr, child_cols))
+        .unwrap_or_else(|| "NULL".into());
+    let bucket = hist.sketch_digits.map(|d| sketch_bucket_sql(&value, d));
+    multiset_upsert_sql(
+        agg,
+        group_by,
+        child_cte,
+        child_cols,
+        &hist.table,
+        &value,
+        bucket.as_deref(),
+    )
+}
+
+/// Scalar subquery computing an ordered-set aggregate for group `grp` from
+/// its histogram, following PostgreSQL's definitions:
+///
+/// - MODE: the most frequent value, ties broken by sort order;
+/// - PERCENTILE_DISC(f): the first value whose cumulative count reaches
+///   `ceil(f * n)`;
+/// - PERCENTILE_CONT(f): linear interpolation between the values at
+///   0-based positions `floor(f * (n - 1))` and `ceil(f * (n - 1))`.
+///
+/// A rounded histogram stands in each bucket's mean (`total / cnt`) for its
+/// values.
+///
+/// Every lookup reads the group's whole histogram, so a changed group costs
+/// O(distinct values) (O(buckets) when rounded), not O(delta).
+fn ordered_set_histogram_lookup(agg: &AggExpr, hist: &HistogramTable, grp: &str) -> String {
+    let table = &hist.table;
+    let dir = match ordered_set_sort_key(agg) {
+        Some(sort) if !sort.ascending => "DESC",
+        _ => "ASC",
+    };
+    let fraction = percentile_fraction(agg).unwrap_or("0.5");
+    let rep = if hist.sketch_digits.is_some() {
+        "__pgt_hv.total / __pgt_hv.cnt"
+    } else {
+        "__pgt_hv.val"
+    };
+    let rows = format!(
+        "SELECT {rep} AS v, \
+         SUM(__pgt_hv.cnt) OVER (ORDER BY __pgt_hv.val {dir}) AS cum, \
+         SUM(__pgt_hv.cnt) OVER () AS tot \
+         FROM {table} __pgt_hv \
+         WHERE __pgt_hv.grp = {grp} AND __pgt_hv.cnt > 0"
+    );
+    match agg.function {
+        AggFunc::Mode => format!(
+            "(SELECT __pgt_hv.val FROM {table} __pgt_hv \
+             WHERE __pgt_hv.grp = {grp} AND __pgt_hv.cnt > 0 \
+             ORDER BY __pgt_hv.cnt DESC, __pgt_hv.val {dir} LIMIT 1)"
+        ),
+        AggFunc::PercentileDisc => format!(
+            "(SELECT __pgt_h.v FROM ({rows}) __pgt_h \
+             WHERE __pgt_h.cum >= GREATEST(CEIL(({fraction}) * __pgt_h.tot), 1) \
+             ORDER BY __pgt_h.cum LIMIT 1)"
+        ),
+        _ => format!(
+            "(WITH __pgt_h AS ({rows}), \
+             __pgt_pos AS (SELECT ({fraction})::float8 * (MAX(tot) - 1) AS p FROM __pgt_h) \
+             SELECT lo.v + (pos.p - FLOOR(pos.p)) * (hi.v - lo.v) \
+             FROM __pgt_pos pos, \
+             LATERAL (SELECT v FROM __pgt_h WHERE cum > FLOOR(pos.p) ORDER BY cum LIMIT 1) lo, \
+             LATERAL (SELECT v FROM __pgt_h WHERE cum > CEIL(pos.p) ORDER BY cum LIMIT 1) hi)"
+        ),
+    }
+}
+
+/// Merge expression for an ordered-set aggregate backed by a histogram:
+/// changed groups read their new value from the (already updated)
+/// histogram, unchanged groups keep the stored value.
+fn ordered_set_histogram_merge_expr(
+    agg: &AggExpr,
+    st_col: &str,
+    hist: &HistogramTable,
+    grp: &str,
+) -> String {
+    let qt = quote_ident(st_col);
+    let ins = quote_ident(&format!("__ins_{}", agg.alias));
+    let del = quote_ident(&format!("__del_{}", agg.alias));
+    format!(
+        "CASE WHEN COALESCE(d.{ins}, 0) > 0 OR COALESCE(d.{del}, 0) > 0 \
+         THEN {} \
+         ELSE st.{qt} END",
+        ordered_set_histogram_lookup(agg, hist, grp)
+    )
+}
+
 /// Reconstruct an aggregate function call as SQL text for the rescan CTE.
 ///
 /// Handles regular aggregates (`BIT_AND(flags)`), aggregates with DISTINCT,
@@ -449,16 +931,13 @@ fn is_algebraically_invertible(agg: &AggExpr) -> bool {
 /// A42-11: Return true if the expression tree contains a CASE expression at
 /// any level — including CASE wrapped in casts, function calls, or binary ops.
 ///
-/// Since CASE expressions reach us as `Expr::Raw(s)` (the parser does not yet
-/// have a dedicated `Expr::Case` variant), we identify them by checking
-/// whether the normalized raw text starts with "CASE". We recurse into
-/// `FuncCall` and `BinaryOp` nodes to catch wrapped CASE expressions.
+/// The parser produces `Expr::Case`; hand-built `Expr::Raw` fragments are
+/// identified by checking whether the normalized text starts with "CASE".
 fn expr_contains_case(expr: &Expr) -> bool {
     match expr {
+        Expr::Case { .. } => true,
         Expr::Raw(s) => s.trim_start().to_uppercase().starts_with("CASE"),
-        Expr::FuncCall { args, .. } => args.iter().any(expr_contains_case),
-        Expr::BinaryOp { left, right, .. } => expr_contains_case(left) || expr_contains_case(right),
-        Expr::ColumnRef { .. } | Expr::Literal(_) | Expr::Star { .. } => false,
+        _ => expr.children().into_iter().any(expr_contains_case),
     }
 }
 
@@ -901,6 +1380,37 @@ FROM {new_rescan_cte} n",
     }
 }
 
+/// The aggregates whose new value is read from the rescan CTE.
+///
+/// Group-rescan aggregates AND MIN/MAX (which need rescan when the old
+/// extremum is deleted), except aggregates backed by a multiset side table
+/// (`multiset_aliases`). When `force_all_aggs` is true (HAVING context),
+/// ALL aggregates are included so that the merge CTE can use the correct
+/// full aggregate value for groups that were absent from the ST (below the
+/// HAVING threshold).
+pub(crate) fn rescan_aggregates<'a>(
+    aggregates: &'a [AggExpr],
+    force_all_aggs: bool,
+    multiset_aliases: &[String],
+) -> Vec<&'a AggExpr> {
+    if force_all_aggs {
+        aggregates.iter().collect()
+    } else {
+        aggregates
+            .iter()
+            .filter(|a| {
+                (a.is_distinct
+                    || a.function.is_group_rescan()
+                    || matches!(a.function, AggFunc::Min | AggFunc::Max))
+                    && !multiset_aliases.contains(&a.alias)
+                // P2-2: SUM over a FULL JOIN child no longer needs a rescan CTE.
+                // The __pgt_aux_nonnull_* auxiliary column provides algebraic
+                // NULL-transition correction without rescanning source data.
+            })
+            .collect()
+    }
+}
+
 /// Build a rescan CTE that re-aggregates affected groups from the source
 /// table. Used for group-rescan aggregates (BIT_AND, STRING_AGG, etc.)
 /// and MIN/MAX (semi-algebraic: needs rescan when extremum is deleted).
@@ -911,6 +1421,7 @@ FROM {new_rescan_cte} n",
 /// re-aggregates those groups.
 ///
 /// Returns `Some(cte_name)` if a rescan CTE was created, `None` otherwise.
+#[allow(clippy::too_many_arguments)]
 fn build_rescan_cte(
     ctx: &mut DiffContext,
     child: &OpTree,
@@ -919,27 +1430,9 @@ fn build_rescan_cte(
     aggregates: &[AggExpr],
     delta_cte: &str,
     force_all_aggs: bool,
+    multiset_aliases: &[String],
 ) -> Option<String> {
-    // Include group-rescan aggregates AND MIN/MAX (which need rescan
-    // when the old extremum is deleted).
-    // When `force_all_aggs` is true (HAVING context), include ALL aggregates
-    // so that the merge CTE can use the correct full aggregate value for
-    // groups that were absent from the ST (below the HAVING threshold).
-    let rescan_aggs: Vec<&AggExpr> = if force_all_aggs {
-        aggregates.iter().collect()
-    } else {
-        aggregates
-            .iter()
-            .filter(|a| {
-                a.is_distinct
-                    || a.function.is_group_rescan()
-                    || matches!(a.function, AggFunc::Min | AggFunc::Max)
-                // P2-2: SUM over a FULL JOIN child no longer needs a rescan CTE.
-                // The __pgt_aux_nonnull_* auxiliary column provides algebraic
-                // NULL-transition correction without rescanning source data.
-            })
-            .collect()
-    };
+    let rescan_aggs = rescan_aggregates(aggregates, force_all_aggs, multiset_aliases);
     if rescan_aggs.is_empty() {
         return None;
     }
@@ -1179,9 +1672,10 @@ fn is_direct_agg_eligible(
                 Expr::ColumnRef { .. } => {
                     // Simple column ref — always P5-eligible.
                 }
-                Expr::Raw(case_sql)
+                Expr::Case { .. } | Expr::Raw(_)
                     if matches!(agg.function, AggFunc::Sum) && expr_contains_case(arg) =>
                 {
+                    let case_sql = arg.to_sql();
                     // A44-2 (v0.43.0): SUM(CASE ...) is P5-eligible when the
                     // CASE expression references only simple column identifiers
                     // that are present in the CDC change buffer for this table.
@@ -1193,7 +1687,7 @@ fn is_direct_agg_eligible(
                         _ => return false,
                     };
                     if let Some(cols) = cdc_cols {
-                        let extracted = extract_case_columns(case_sql, cols);
+                        let extracted = extract_case_columns(&case_sql, cols);
                         if extracted.is_empty() {
                             return false;
                         }
@@ -1377,20 +1871,21 @@ fn generate_direct_agg_delta(
                         arg_cols.push(name);
                     }
                 }
-                Expr::Raw(case_sql)
+                Expr::Case { .. } | Expr::Raw(_)
                     if matches!(agg.function, AggFunc::Sum) && expr_contains_case(arg) =>
                 {
+                    let case_sql = arg.to_sql();
                     // A44-2: DI-2 UPDATE split for SUM(CASE ...).
                     // Extract column refs from the CASE expression and
                     // rewrite it to use LATERAL VALUES aliases.
-                    let case_cols = extract_case_columns(case_sql, &cdc_cols_for_case);
+                    let case_cols = extract_case_columns(&case_sql, &cdc_cols_for_case);
                     if !case_cols.is_empty() {
                         for col in &case_cols {
                             if !arg_cols.contains(*col) {
                                 arg_cols.push((*col).clone());
                             }
                         }
-                        let rewritten = rewrite_case_for_lateral(case_sql, &case_cols);
+                        let rewritten = rewrite_case_for_lateral(&case_sql, &case_cols);
                         case_rewrites.insert(agg.alias.clone(), rewritten);
                         _has_case_aggregate = true;
                     } else {
@@ -1574,7 +2069,23 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
             "diff_aggregate called on non-Aggregate node".into(),
         ));
     };
+    diff_grouped_aggregate(ctx, group_by, aggregates, child, None)
+}
 
+/// Differentiate an aggregate over `group_by`, or over every grouping set
+/// of `grouping_sets` (GSETS-NATIVE).
+///
+/// With grouping sets, each child delta row is fanned out once per set in
+/// the delta CTE, so a group is identified by its grouping-set id (the
+/// leading `__pgt_grouping_id` group column) together with the grouping
+/// columns, NULL where the set does not group by them.
+pub(crate) fn diff_grouped_aggregate(
+    ctx: &mut DiffContext,
+    group_by: &[Expr],
+    aggregates: &[AggExpr],
+    child: &OpTree,
+    grouping_sets: Option<&GroupingSetsLayout<'_>>,
+) -> Result<DiffResult, PgTrickleError> {
     // ── CTE 1: Choose between P5 direct bypass or standard path ────────
     //
     // P5: For Scan → Aggregate trees where all aggregates are decomposable
@@ -1587,9 +2098,12 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
     // (IMMEDIATE mode), we always use the standard path which correctly
     // reads from the trigger transition temp tables via diff_scan.
     let use_p5 = matches!(ctx.delta_source, DeltaSource::ChangeBuffer)
+        && grouping_sets.is_none()
         && is_direct_agg_eligible(child, group_by, aggregates, ctx);
-    let (delta_cte, group_output) = if use_p5 {
-        generate_direct_agg_delta(ctx, child, group_by, aggregates)?
+    let (delta_cte, group_output, child_delta) = if use_p5 {
+        let (delta_cte, group_output) =
+            generate_direct_agg_delta(ctx, child, group_by, aggregates)?;
+        (delta_cte, group_output, None)
     } else {
         // ── Standard path: differentiate child first ───────────────────
         let child_result = ctx.diff_node(child)?;
@@ -1600,9 +2114,9 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
             .iter()
             .map(|e| resolve_group_col(e, child_cols))
             .collect();
-        let group_output: Vec<String> = group_by.iter().map(|e| e.output_name()).collect();
+        let mut group_output: Vec<String> = group_by.iter().map(|e| e.output_name()).collect();
 
-        let group_by_clause = if group_resolved.is_empty() {
+        let mut group_by_clause = if group_resolved.is_empty() {
             String::new()
         } else {
             let gb_cols: Vec<String> = group_resolved
@@ -1614,23 +2128,40 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
 
         let delta_cte = ctx.next_cte_name("agg_delta");
         let mut delta_selects = Vec::new();
-
-        // Group by columns — alias to output name for consistent downstream refs.
-        // When the output name is a SQL expression (contains '('), always add an
-        // explicit alias so the delta CTE column is named by the full expression
-        // as a quoted identifier. Without this guard, `split_part(x, y, z)` would
-        // be implicitly named `split_part` by PostgreSQL, causing the merge CTE's
-        // `d."split_part(x, y, z)"` reference to fail with "column does not exist".
-        // build_rescan_cte uses the same `&& !expr_sql.contains('(')` guard.
-        for (resolved, output) in group_resolved.iter().zip(group_output.iter()) {
-            if resolved == output && !output.contains('(') {
-                delta_selects.push(col_ref_or_sql_expr(resolved));
-            } else {
-                delta_selects.push(format!(
-                    "{} AS {}",
-                    col_ref_or_sql_expr(resolved),
-                    quote_ident(output)
-                ));
+        let mut delta_from = child_result.cte_name.clone();
+
+        if let Some(layout) = grouping_sets {
+            // GSETS-NATIVE: one delta row per (child row, grouping set),
+            // with the columns the set does not group by masked to NULL.
+            let selects = layout.delta_group_selects(&group_resolved, &group_output);
+            group_by_clause = format!(
+                "\nGROUP BY {}",
+                (1..=selects.len())
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            delta_selects.extend(selects);
+            delta_from = format!("{delta_from} CROSS JOIN {}", layout.values_sql());
+            group_output.insert(0, GROUPING_ID_COL.to_string());
+        } else {
+            // Group by columns — alias to output name for consistent downstream refs.
+            // When the output name is a SQL expression (contains '('), always add an
+            // explicit alias so the delta CTE column is named by the full expression
+            // as a quoted identifier. Without this guard, `split_part(x, y, z)` would
+            // be implicitly named `split_part` by PostgreSQL, causing the merge CTE's
+            // `d."split_part(x, y, z)"` reference to fail with "column does not exist".
+            // build_rescan_cte uses the same `&& !expr_sql.contains('(')` guard.
+            for (resolved, output) in group_resolved.iter().zip(group_output.iter()) {
+                if resolved == output && !output.contains('(') {
+                    delta_selects.push(col_ref_or_sql_expr(resolved));
+                } else {
+                    delta_selects.push(format!(
+                        "{} AS {}",
+                        col_ref_or_sql_expr(resolved),
+                        quote_ident(output)
+                    ));
+                }
             }
         }
 
@@ -1704,14 +2235,14 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
         }
 
         let delta_sql = format!(
-            "SELECT {selects}\nFROM {child_cte}{group_by}",
+            "SELECT {selects}\nFROM {delta_from}{group_by}",
             selects = delta_selects.join(",\n       "),
-            child_cte = child_result.cte_name,
             group_by = group_by_clause,
         );
         ctx.add_cte(delta_cte.clone(), delta_sql);
 
-        (delta_cte, group_output)
+        let child_delta = (child_result.cte_name.clone(), child_cols.clone());
+        (delta_cte, group_output, Some(child_delta))
     };
 
     // ── Detect intermediate aggregate ───────────────────────────────
@@ -1729,13 +2260,19 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
     // Filter(CteScan{...}).  The aggregate's group/value columns match
     // the ST's user columns, but `__pgt_count` was never added because
     // `needs_pgt_count()` returns false for the top-level CteScan.
+    //
+    // The grouping-set id is not a stream table column.
+    let user_group_output = match grouping_sets {
+        Some(_) => &group_output[1..],
+        None => &group_output[..],
+    };
     let is_intermediate = if let Some(ref st_cols) = ctx.st_user_columns {
         if !ctx.st_has_pgt_count {
             // ST has no __pgt_count → aggregate merge cannot read st.__pgt_count
             true
-        } else if !group_output.is_empty() {
+        } else if !user_group_output.is_empty() {
             // Grouped aggregate: check if any group column is missing from ST
-            group_output.iter().any(|g| !st_cols.contains(g))
+            user_group_output.iter().any(|g| !st_cols.contains(g))
         } else if !aggregates.is_empty() {
             // Global aggregate (no GROUP BY): check if aggregate output
             // columns exist in the stream table. If not, this is an
@@ -1748,6 +2285,13 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
         false
     };
 
+    if is_intermediate && grouping_sets.is_some() {
+        return Err(PgTrickleError::UnsupportedOperator(
+            "GROUPING SETS / CUBE / ROLLUP is only supported at the top level of a \
+             DIFFERENTIAL defining query"
+                .into(),
+        ));
+    }
     if is_intermediate {
         return build_intermediate_agg_delta(
             ctx,
@@ -1764,15 +2308,57 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
     // that groups crossing the threshold upward (absent from the ST) receive
     // the correct full aggregate value rather than just the per-cycle delta.
     let use_having_rescan = ctx.having_filter;
-    let rescan_cte = build_rescan_cte(
-        ctx,
-        child,
-        group_by,
-        &group_output,
-        aggregates,
-        &delta_cte,
-        use_having_rescan,
-    );
+
+    // MINMAX-MS: MIN/MAX aggregates backed by an ordered multiset side
+    // table read a deleted extremum's replacement from that table instead
+    // of the rescan CTE. ORDSET-HIST: ordered-set aggregates backed by a
+    // value-count histogram read their new value from it. Side tables are
+    // brought up to date from the child delta by separate statements that
+    // run before this query.
+    let multiset_aliases: Vec<String> = match &child_delta {
+        Some((child_cte, child_cols)) if !use_having_rescan && grouping_sets.is_none() => {
+            let mut aliases = Vec::new();
+            for agg in aggregates {
+                let maintenance = if is_minmax_multiset_eligible(agg) {
+                    ctx.minmax_multisets.get(&agg.alias).map(|table| {
+                        let upsert = minmax_multiset_maintenance_sql(
+                            agg, group_by, child_cte, child_cols, table,
+                        );
+                        (table.clone(), upsert)
+                    })
+                } else if is_ordered_set_histogram_eligible(agg) {
+                    ctx.ordered_set_histograms.get(&agg.alias).map(|hist| {
+                        let upsert = ordered_set_histogram_maintenance_sql(
+                            agg, group_by, child_cte, child_cols, hist,
+                        );
+                        (hist.table.clone(), upsert)
+                    })
+                } else {
+                    None
+                };
+                if let Some(entry) = maintenance {
+                    ctx.side_table_maintenance.push(entry);
+                    aliases.push(agg.alias.clone());
+                }
+            }
+            aliases
+        }
+        _ => Vec::new(),
+    };
+
+    let rescan_cte = match grouping_sets {
+        Some(layout) => layout.build_rescan_cte(ctx, child, group_by, aggregates, &delta_cte)?,
+        None => build_rescan_cte(
+            ctx,
+            child,
+            group_by,
+            &group_output,
+            aggregates,
+            &delta_cte,
+            use_having_rescan,
+            &multiset_aliases,
+        ),
+    };
     let has_rescan = rescan_cte.is_some();
 
     // ── CTE 2: Merge with existing ST state to classify actions ────────
@@ -1867,16 +2453,27 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
         } else {
             has_rescan
         };
-        let new_val_expr = agg_merge_expr_mapped(
-            agg,
-            agg_has_rescan,
-            use_having_rescan,
-            agg_has_nonnull_aux,
-            &st_col_name(&agg.alias),
-            ctx.agg_sum_coalesce_defaults
-                .get(&agg.alias)
-                .map(|s| s.as_str()),
-        );
+        let new_val_expr = if !multiset_aliases.contains(&agg.alias) {
+            agg_merge_expr_mapped(
+                agg,
+                agg_has_rescan,
+                use_having_rescan,
+                agg_has_nonnull_aux,
+                &st_col_name(&agg.alias),
+                ctx.agg_sum_coalesce_defaults
+                    .get(&agg.alias)
+                    .map(|s| s.as_str()),
+            )
+        } else if let Some(hist) = ctx.ordered_set_histograms.get(&agg.alias) {
+            ordered_set_histogram_merge_expr(agg, &st_col_name(&agg.alias), hist, &row_id_expr)
+        } else {
+            minmax_multiset_merge_expr(
+                agg,
+                &st_col_name(&agg.alias),
+                &ctx.minmax_multisets[&agg.alias],
+                &row_id_expr,
+            )
+        };
         merge_selects.push(format!(
             "{new_val_expr} AS {}",
             quote_ident(&format!("new_{}", agg.alias)),
@@ -1957,7 +2554,15 @@ pub fn diff_aggregate(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult,
     // one row: `SELECT SUM(x) FROM empty_table` → 1 row (NULL).  The
     // singleton ST row must **never** be deleted, so we omit the 'D'
     // classification for scalar aggregates and emit 'U' instead.
+    //
+    // GSETS-NATIVE: the empty grouping set behaves like a scalar
+    // aggregate, so its row is never deleted either.
     let is_scalar_agg = group_by.is_empty();
+    let empty_set_id = grouping_sets.and_then(|layout| layout.empty_set_id());
+    let delete_guard = match empty_set_id {
+        Some(id) => format!("d.{} <> {id} AND ", quote_ident(GROUPING_ID_COL)),
+        None => String::new(),
+    };
 
     // Action classification (same COALESCE guards as new_count)
     let action_case = if is_scalar_agg {
@@ -1968,18 +2573,23 @@ CASE
 END AS __pgt_meta_action"
             .to_string()
     } else {
-        "\
+        format!(
+            "\
 CASE
     WHEN st.__pgt_count IS NULL AND (COALESCE(d.__ins_count, 0) - COALESCE(d.__del_count, 0)) > 0 THEN 'I'
-    WHEN COALESCE(st.__pgt_count, 0) + COALESCE(d.__ins_count, 0) - COALESCE(d.__del_count, 0) <= 0 THEN 'D'
+    WHEN {delete_guard}COALESCE(st.__pgt_count, 0) + COALESCE(d.__ins_count, 0) - COALESCE(d.__del_count, 0) <= 0 THEN 'D'
     ELSE 'U'
 END AS __pgt_meta_action"
-            .to_string()
+        )
     };
     merge_selects.push(action_case);
 
-    // Join condition on group-by columns
-    let join_cond = if group_output.is_empty() {
+    // Join condition on group-by columns. Grouping-set rows are matched by
+    // row ID: the grouping-set id is not stored, and a NULL grouping column
+    // alone cannot tell a rolled-up row from a group whose value is NULL.
+    let join_cond = if grouping_sets.is_some() {
+        format!("st.__pgt_row_id = {row_id_expr}")
+    } else if group_output.is_empty() {
         "TRUE".to_string()
     } else {
         group_output
@@ -2034,7 +2644,10 @@ END AS __pgt_meta_action"
 
     // Build output column list
     let mut output_cols = Vec::new();
-    output_cols.extend(group_output.iter().cloned());
+    output_cols.extend(user_group_output.iter().cloned());
+    if let Some(layout) = grouping_sets {
+        output_cols.extend(layout.grouping_funcs.iter().map(|gf| gf.alias.clone()));
+    }
     output_cols.push("__pgt_count".to_string());
     for agg in aggregates {
         output_cols.push(agg.alias.clone());
@@ -2059,11 +2672,15 @@ END AS __pgt_meta_action"
         }
     }
 
-    let group_col_refs = group_output
+    let mut group_col_refs = user_group_output
         .iter()
         .map(|c| format!("m.{}", quote_ident(c)))
-        .collect::<Vec<_>>()
-        .join(", ");
+        .collect::<Vec<_>>();
+    if let Some(layout) = grouping_sets {
+        let gid = format!("m.{}", quote_ident(GROUPING_ID_COL));
+        group_col_refs.extend(layout.grouping_func_selects(&gid));
+    }
+    let group_col_refs = group_col_refs.join(", ");
 
     let extra_group = if group_col_refs.is_empty() {
         String::new()
@@ -2082,8 +2699,9 @@ END AS __pgt_meta_action"
         // NULL — not 0 — when new_count drops to 0, matching PostgreSQL's
         // `SELECT SUM(x) FROM empty_table` → NULL semantics.  COUNT(*) and
         // COUNT(col) correctly yield 0 from the count arithmetic, so they
-        // don't need this override.
-        let needs_null_on_empty = is_scalar_agg
+        // don't need this override. The same holds for the empty grouping
+        // set, the only grouping-set row that is kept at a zero count.
+        let needs_null_on_empty = (is_scalar_agg || empty_set_id.is_some())
             && matches!(
                 agg.function,
                 AggFunc::Sum
@@ -2942,6 +3560,7 @@ pub fn agg_merge_expr(agg: &AggExpr, has_rescan: bool) -> String {
 mod tests {
     use super::*;
     use crate::dvm::operators::test_helpers::*;
+    use crate::dvm::parser::Resolved;
 
     /// Wrapper for tests: calls is_direct_agg_eligible with an empty ctx
     /// (no ST sources), matching the old 3-argument signature.
@@ -3104,6 +3723,7 @@ mod tests {
                 Expr::Literal("' > '".to_string()),
                 Expr::Literal("2".to_string()),
             ],
+            resolved: Resolved::default(),
         };
         let mut ctx = test_ctx_with_st("public", "department_report");
         let child = filter(
@@ -3662,6 +4282,264 @@ mod tests {
         );
     }
 
+    // ── MINMAX-MS: ordered multiset side table tests ────────────────
+
+    const MM_TABLE: &str = "\"pgtrickle_changes\".\"minmax_7_0\"";
+
+    #[test]
+    fn test_minmax_multiset_replaces_rescan() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        ctx.minmax_multisets
+            .insert("min_amt".to_string(), MM_TABLE.to_string());
+        let tree = aggregate(
+            vec![colref("region")],
+            vec![min_col("amount", "min_amt")],
+            scan(1, "t", "public", "t", &["region", "amount"]),
+        );
+        let result = diff_aggregate(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_not_contains(&sql, "agg_rescan");
+        assert_sql_contains(&sql, &format!("FROM {MM_TABLE} __pgt_mm"));
+        assert_sql_contains(&sql, "ORDER BY __pgt_mm.val ASC LIMIT 1");
+        assert_sql_contains(&sql, "LEAST(st.\"min_amt\", d.\"__ins_min_amt\")");
+
+        assert_eq!(ctx.side_table_maintenance.len(), 1);
+        let (table, upsert) = &ctx.side_table_maintenance[0];
+        assert_eq!(table, MM_TABLE);
+        assert_sql_contains(upsert, &format!("INSERT INTO {MM_TABLE} AS __pgt_mm"));
+        assert_sql_contains(upsert, "ON CONFLICT (grp, val) DO UPDATE");
+        let stmt = ctx.build_with_statement(upsert);
+        assert!(
+            stmt.starts_with("WITH "),
+            "maintenance must carry the delta CTEs: {stmt}"
+        );
+    }
+
+    #[test]
+    fn test_minmax_multiset_group_key_matches_row_id() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        ctx.minmax_multisets
+            .insert("max_amt".to_string(), MM_TABLE.to_string());
+        let tree = aggregate(
+            vec![colref("region")],
+            vec![max_col("amount", "max_amt")],
+            scan(1, "t", "public", "t", &["region", "amount"]),
+        );
+        let result = diff_aggregate(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        // The lookup keys the side table by the merge CTE's own row-id hash.
+        assert_sql_contains(
+            &sql,
+            "__pgt_mm.grp = pgtrickle.pg_trickle_hash(d.\"region\"::TEXT)",
+        );
+        assert_sql_contains(&sql, "ORDER BY __pgt_mm.val DESC LIMIT 1");
+        // Maintenance hashes the same group value from the child delta.
+        let (_, upsert) = &ctx.side_table_maintenance[0];
+        assert_sql_contains(upsert, "pgtrickle.pg_trickle_hash((\"region\")::TEXT)");
+    }
+
+    #[test]
+    fn test_minmax_multiset_only_for_backed_aggregates() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        ctx.minmax_multisets
+            .insert("min_amt".to_string(), MM_TABLE.to_string());
+        let tree = aggregate(
+            vec![colref("region")],
+            vec![min_col("amount", "min_amt"), max_col("amount", "max_amt")],
+            scan(1, "t", "public", "t", &["region", "amount"]),
+        );
+        let result = diff_aggregate(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(&sql, "agg_rescan");
+        assert_sql_contains(&sql, "THEN r.\"max_amt\"");
+        assert_sql_not_contains(&sql, "THEN r.\"min_amt\"");
+        assert_eq!(ctx.side_table_maintenance.len(), 1);
+    }
+
+    #[test]
+    fn test_minmax_multiset_unused_under_having() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        ctx.having_filter = true;
+        ctx.minmax_multisets
+            .insert("min_amt".to_string(), MM_TABLE.to_string());
+        let tree = aggregate(
+            vec![colref("region")],
+            vec![min_col("amount", "min_amt")],
+            scan(1, "t", "public", "t", &["region", "amount"]),
+        );
+        let result = diff_aggregate(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_not_contains(&sql, "__pgt_mm");
+        assert!(ctx.side_table_maintenance.is_empty());
+    }
+
+    #[test]
+    fn test_minmax_multiset_candidates() {
+        let tree = aggregate(
+            vec![colref("region")],
+            vec![
+                min_col("amount", "min_amt"),
+                sum_col("amount", "total"),
+                max_col("amount", "max_amt"),
+            ],
+            filter(
+                binop(">", colref("amount"), lit("0")),
+                scan(1, "t", "public", "t", &["region", "amount"]),
+            ),
+        );
+        let specs = minmax_multiset_candidates(&tree, &CteRegistry::default());
+
+        let found: Vec<(&str, usize)> = specs
+            .iter()
+            .map(|s| (s.alias.as_str(), s.ordinal))
+            .collect();
+        assert_eq!(found, vec![("min_amt", 0), ("max_amt", 2)]);
+        let sql = &specs[0].populate_sql;
+        assert_sql_contains(sql, "pgtrickle.pg_trickle_hash(");
+        // The child filter already opened a WHERE clause.
+        assert_sql_contains(sql, "AND (amount) IS NOT NULL");
+        assert_sql_contains(sql, "GROUP BY 1, 2");
+        assert_eq!(MinMaxMultisetSpec::table_name(7, 2), "minmax_7_2");
+    }
+
+    #[test]
+    fn test_minmax_multiset_candidates_skip_having() {
+        let tree = filter(
+            binop(">", colref("min_amt"), lit("0")),
+            aggregate(
+                vec![colref("region")],
+                vec![min_col("amount", "min_amt")],
+                scan(1, "t", "public", "t", &["region", "amount"]),
+            ),
+        );
+        assert!(minmax_multiset_candidates(&tree, &CteRegistry::default()).is_empty());
+    }
+
+    // ── ORDSET-HIST: ordered-set histogram tests ────────────────────
+
+    const HIST_TABLE: &str = "\"pgtrickle_changes\".\"hist_7_0\"";
+
+    fn exact_hist() -> HistogramTable {
+        HistogramTable {
+            table: HIST_TABLE.to_string(),
+            sketch_digits: None,
+        }
+    }
+
+    #[test]
+    fn test_ordered_set_histogram_replaces_rescan() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        ctx.ordered_set_histograms
+            .insert("p50".to_string(), exact_hist());
+        let tree = aggregate(
+            vec![colref("endpoint")],
+            vec![percentile_cont_col("0.5", "latency", "p50")],
+            scan(1, "t", "public", "t", &["endpoint", "latency"]),
+        );
+        let result = diff_aggregate(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_not_contains(&sql, "agg_rescan");
+        assert_sql_contains(&sql, &format!("FROM {HIST_TABLE} __pgt_hv"));
+        assert_sql_contains(
+            &sql,
+            "__pgt_hv.grp = pgtrickle.pg_trickle_hash(d.\"endpoint\"::TEXT)",
+        );
+        assert_sql_contains(&sql, "(0.5)::float8 * (MAX(tot) - 1)");
+        assert_sql_contains(&sql, "lo.v + (pos.p - FLOOR(pos.p)) * (hi.v - lo.v)");
+
+        assert_eq!(ctx.side_table_maintenance.len(), 1);
+        let (table, upsert) = &ctx.side_table_maintenance[0];
+        assert_eq!(table, HIST_TABLE);
+        assert_sql_contains(
+            upsert,
+            "SELECT pgtrickle.pg_trickle_hash((\"endpoint\")::TEXT), latency,",
+        );
+        assert_sql_contains(
+            upsert,
+            "ON CONFLICT (grp, val) DO UPDATE SET cnt = __pgt_mm.cnt + EXCLUDED.cnt",
+        );
+    }
+
+    #[test]
+    fn test_ordered_set_histogram_mode_and_disc() {
+        let hist = exact_hist();
+        let mode = mode_col("status", "m");
+        let lookup = ordered_set_histogram_lookup(&mode, &hist, "g");
+        assert_sql_contains(
+            &lookup,
+            "ORDER BY __pgt_hv.cnt DESC, __pgt_hv.val ASC LIMIT 1",
+        );
+
+        let mut disc = percentile_disc_col("0.9", "latency", "p90");
+        if let Some(sorts) = disc.order_within_group.as_mut() {
+            sorts[0].ascending = false;
+        }
+        let lookup = ordered_set_histogram_lookup(&disc, &hist, "g");
+        assert_sql_contains(&lookup, "OVER (ORDER BY __pgt_hv.val DESC) AS cum");
+        assert_sql_contains(
+            &lookup,
+            "__pgt_h.cum >= GREATEST(CEIL((0.9) * __pgt_h.tot), 1)",
+        );
+    }
+
+    #[test]
+    fn test_ordered_set_histogram_sketch() {
+        let hist = HistogramTable {
+            table: HIST_TABLE.to_string(),
+            sketch_digits: Some(3),
+        };
+        let agg = percentile_cont_col("0.99", "latency", "p99");
+        let lookup = ordered_set_histogram_lookup(&agg, &hist, "g");
+        assert_sql_contains(&lookup, "SELECT __pgt_hv.total / __pgt_hv.cnt AS v");
+
+        let upsert = ordered_set_histogram_maintenance_sql(
+            &agg,
+            &[colref("endpoint")],
+            "delta",
+            &["endpoint".to_string(), "latency".to_string()],
+            &hist,
+        );
+        assert_sql_contains(&upsert, "(grp, val, cnt, total)");
+        assert_sql_contains(&upsert, "round((latency)::float8::numeric, 3 - 1");
+        assert_sql_contains(&upsert, "total = __pgt_mm.total + EXCLUDED.total");
+    }
+
+    #[test]
+    fn test_ordered_set_histogram_candidates() {
+        let tree = aggregate(
+            vec![colref("endpoint")],
+            vec![
+                percentile_cont_col("0.5", "latency", "p50"),
+                percentile_cont_col("'{0.5,0.9}'", "latency", "ps"),
+                mode_col("status", "m"),
+                min_col("latency", "lo"),
+            ],
+            scan(1, "t", "public", "t", &["endpoint", "latency", "status"]),
+        );
+        let specs = ordered_set_histogram_candidates(&tree, &CteRegistry::default());
+
+        let found: Vec<(&str, usize, bool)> = specs
+            .iter()
+            .map(|s| (s.alias.as_str(), s.ordinal, s.sketchable))
+            .collect();
+        assert_eq!(found, vec![("p50", 0, true), ("m", 2, false)]);
+        let exact = specs[0].populate_sql(None);
+        assert_sql_contains(&exact, "WHERE (latency) IS NOT NULL");
+        assert_sql_contains(&exact, "COUNT(*)\nFROM");
+        let sketch = specs[0].populate_sql(Some(2));
+        assert_sql_contains(&sketch, "COUNT(*), SUM((latency)::float8)");
+        assert_eq!(OrderedSetHistogramSpec::table_name(7, 0, None), "hist_7_0");
+        assert_eq!(
+            OrderedSetHistogramSpec::table_name(7, 0, Some(2)),
+            "hist_7_0_s2"
+        );
+    }
+
     // ── B5: FILTER clause tests ──────────────────────────────────────
 
     #[test]
@@ -5304,6 +6182,7 @@ mod tests {
                 Expr::FuncCall {
                     func_name: "extract_year".to_string(),
                     args: vec![colref("o_orderdate")],
+                    resolved: Resolved::default(),
                 },
                 colref("o_totalprice"),
             ],
@@ -11,7 +11,11 @@
 //! kept — net result: INSERT into the ST. The converse is also correct.
 
 use crate::dvm::diff::{DeltaSource, DiffContext, DiffResult, quote_ident};
-use crate::dvm::parser::{Expr, OpTree, unwrap_transparent};
+use crate::dvm::operators::join_common::build_pk_hash_expr;
+use crate::dvm::operators::window::{WindowTruncation, classify_window_filter};
+use crate::dvm::parser::{
+    Expr, OpTree, is_sliding_window_filter, substitute_clock, unwrap_transparent,
+};
 use crate::error::PgTrickleError;
 
 /// Differentiate a Filter node.
@@ -27,6 +31,19 @@ pub fn diff_filter(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
     // that cross the HAVING threshold from below.
     let is_having = matches!(child.as_ref(), OpTree::Aggregate { .. });
 
+    // ── SLIDE-1: Sliding-window predicate over a base table ──────────
+    //
+    // `ts > now() - interval '1 hour'` changes its result as the clock
+    // advances, with no DML on the source. Checked before P2-7 because
+    // the expiry delta needs the scan delta as a separate CTE.
+    if !is_having
+        && matches!(ctx.delta_source, DeltaSource::ChangeBuffer)
+        && is_sliding_window_filter(predicate, child)
+        && let Some(prev_clock) = ctx.prev_clock_sql.clone()
+    {
+        return diff_sliding_window_filter(ctx, predicate, child, &prev_clock);
+    }
+
     // ── P2-7: Predicate pushdown into Scan ───────────────────────────
     //
     // When the child is a PK-based Scan in ChangeBuffer mode and the
@@ -62,11 +79,23 @@ pub fn diff_filter(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
         ctx.having_filter = true;
     }
 
+    // TOPK-PART: tell the Window below how this predicate truncates its
+    // stored partitions. A second Filter between us and the Window means
+    // the stored rows are no longer a plain prefix.
+    if matches!(unwrap_transparent(child), OpTree::Window { .. }) {
+        ctx.window_truncation = Some(if ctx.window_truncation.is_some() {
+            WindowTruncation::Subset
+        } else {
+            classify_window_filter(predicate, child)
+        });
+    }
+
     // First, differentiate the child
     let child_result = ctx.diff_node(child)?;
 
     // Restore the having_filter flag after the child diff.
     ctx.having_filter = prev_having_filter;
+    ctx.window_truncation = None;
 
     let cte_name = ctx.next_cte_name("filter");
 
@@ -200,6 +229,99 @@ pub fn diff_filter(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
     })
 }
 
+// ── SLIDE-1: Sliding-window filters ─────────────────────────────────
+
+/// Differentiate a sliding-window Filter directly over a PK-based Scan.
+///
+/// With `P(t)` the predicate evaluated at clock `t`, `t0` the clock of the
+/// previous refresh and `t1 = now()`, the delta is:
+///
+/// 1. Changed rows: DELETEs whose old values satisfied `P(t0)` (they are in
+///    the ST) and INSERTs whose new values satisfy `P(t1)`.
+/// 2. Unchanged rows that crossed the boundary, read from the source table:
+///    `P(t0) AND NOT P(t1)` → DELETE (expired), `P(t1) AND NOT P(t0)` →
+///    INSERT (entered, e.g. future-dated rows or `ts < now()` windows).
+///
+/// The two parts are disjoint by row id, so the child's dedup property is
+/// preserved.
+fn diff_sliding_window_filter(
+    ctx: &mut DiffContext,
+    predicate: &Expr,
+    child: &OpTree,
+    prev_clock: &str,
+) -> Result<DiffResult, PgTrickleError> {
+    let OpTree::Scan {
+        schema,
+        table_name,
+        alias,
+        columns,
+        pk_columns,
+        ..
+    } = child
+    else {
+        return Err(PgTrickleError::InternalError(
+            "sliding-window filter requires a Scan child".into(),
+        ));
+    };
+
+    let child_result = ctx.diff_node(child)?;
+    let cte_name = ctx.next_cte_name("filter");
+
+    let alias_q = quote_ident(alias);
+    let pred_now = predicate.to_sql();
+    let pred_prev = substitute_clock(predicate, prev_clock).to_sql();
+    let row_id = build_pk_hash_expr(alias, columns, pk_columns);
+    let src = format!("{}.{}", quote_ident(schema), quote_ident(table_name));
+    let cols = child_result
+        .columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let src_cols = child_result
+        .columns
+        .iter()
+        .map(|c| format!("{alias_q}.{}", quote_ident(c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let sql = format!(
+        "-- Changed rows: old values judged at the previous clock, new at now()\n\
+         SELECT __pgt_row_id, __pgt_action, {cols}\n\
+         FROM {child_cte} {alias_q}\n\
+         WHERE (__pgt_action = 'D' AND ({pred_prev}))\n\
+            OR (__pgt_action = 'I' AND ({pred_now}))\n\
+         \n\
+         UNION ALL\n\
+         \n\
+         -- Unchanged rows that slid out of the window\n\
+         SELECT {row_id} AS __pgt_row_id, 'D'::TEXT AS __pgt_action, {src_cols}\n\
+         FROM {src} {alias_q}\n\
+         WHERE ({pred_prev}) AND NOT ({pred_now})\n\
+           AND NOT EXISTS (SELECT 1 FROM {child_cte} __pgt_d \
+         WHERE __pgt_d.__pgt_row_id = {row_id})\n\
+         \n\
+         UNION ALL\n\
+         \n\
+         -- Unchanged rows that slid into the window\n\
+         SELECT {row_id} AS __pgt_row_id, 'I'::TEXT AS __pgt_action, {src_cols}\n\
+         FROM {src} {alias_q}\n\
+         WHERE ({pred_now}) AND NOT ({pred_prev})\n\
+           AND NOT EXISTS (SELECT 1 FROM {child_cte} __pgt_d \
+         WHERE __pgt_d.__pgt_row_id = {row_id})",
+        child_cte = child_result.cte_name,
+    );
+
+    ctx.add_cte(cte_name.clone(), sql);
+
+    Ok(DiffResult {
+        cte_name,
+        columns: child_result.columns,
+        is_deduplicated: child_result.is_deduplicated,
+        has_key_changed: false,
+    })
+}
+
 // ── Predicate column resolution ──────────────────────────────────────
 
 /// Resolve a predicate expression's column references against the child
@@ -212,9 +334,9 @@ pub fn diff_filter(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
 /// `customer.c_custkey`.  This function maps each column reference to
 /// the matching CTE column name so the generated SQL is valid.
 ///
-/// For `Expr::Raw` nodes that contain flattened SQL text with embedded
-/// column references, a best-effort string replacement is applied using
-/// the column name mapping built from `child_cols`.
+/// For `Expr::Raw` nodes and subquery bodies that contain flattened SQL text
+/// with embedded column references, a best-effort string replacement is
+/// applied using the column name mapping built from `child_cols`.
 fn resolve_predicate_for_child(predicate: &Expr, child_cols: &[String]) -> String {
     match predicate {
         Expr::ColumnRef {
@@ -257,14 +379,18 @@ fn resolve_predicate_for_child(predicate: &Expr, child_cols: &[String]) -> Strin
             // Fallback: unquoted column name (let PostgreSQL resolve)
             quote_ident(column_name)
         }
-        Expr::BinaryOp { op, left, right } => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } => {
             format!(
                 "({} {op} {})",
                 resolve_predicate_for_child(left, child_cols),
                 resolve_predicate_for_child(right, child_cols),
             )
         }
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name, args, ..
+        } => {
             let resolved: Vec<String> = args
                 .iter()
                 .map(|a| resolve_predicate_for_child(a, child_cols))
@@ -272,6 +398,14 @@ fn resolve_predicate_for_child(predicate: &Expr, child_cols: &[String]) -> Strin
             format!("{func_name}({})", resolved.join(", "))
         }
         Expr::Star { .. } | Expr::Literal(_) => predicate.to_sql(),
+        Expr::SubLink { .. } => replace_column_refs_in_raw(&predicate.to_sql(), child_cols),
+        Expr::UnaryOp { .. }
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Array(_)
+        | Expr::Row(_) => predicate
+            .map_children(|c| Expr::Raw(resolve_predicate_for_child(c, child_cols)))
+            .to_sql(),
         Expr::Raw(sql) => {
             // Best-effort: replace known column name patterns in the Raw SQL
             // string.  Build a mapping from original column names (the suffix
@@ -507,6 +641,7 @@ fn replace_word_boundary(text: &str, word: &str, replacement: &str) -> String {
 mod tests {
     use super::*;
     use crate::dvm::operators::test_helpers::*;
+    use crate::dvm::parser::Resolved;
 
     #[test]
     fn test_diff_filter_basic() {
@@ -599,6 +734,65 @@ mod tests {
         assert_eq!(result.columns, vec!["id", "name", "status"]);
     }
 
+    fn sliding_predicate() -> Expr {
+        binop(
+            ">",
+            qcolref("e", "ts"),
+            binop(
+                "-",
+                Expr::FuncCall {
+                    func_name: "now".to_string(),
+                    args: vec![],
+                    resolved: Resolved::default(),
+                },
+                Expr::Raw("CAST('1 hour' AS interval)".to_string()),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_sliding_window_filter_emits_expiry_delta() {
+        let mut ctx = test_ctx_with_st("public", "recent");
+        let child = scan_with_pk(1, "events", "public", "e", &["id", "ts"], &["id"]);
+        let tree = filter(sliding_predicate(), child);
+        let result = diff_filter(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        // Not pushed into the scan: a separate filter CTE is produced.
+        assert!(result.cte_name.contains("filter"));
+        // Previous clock is read from the stored frontier at run time.
+        assert_sql_contains(&sql, "frontier ->> 'clock'");
+        // Expired and entered rows are read from the source table.
+        assert_sql_contains(&sql, "FROM \"public\".\"events\" \"e\"");
+        assert_sql_contains(&sql, "pgtrickle.pg_trickle_hash(\"e\".\"id\"::text)");
+        assert_sql_contains(&sql, "'D'::TEXT AS __pgt_action");
+        assert_sql_contains(&sql, "NOT EXISTS (SELECT 1 FROM");
+        assert_eq!(result.columns, vec!["id", "ts"]);
+    }
+
+    #[test]
+    fn test_sliding_window_filter_requires_st_name() {
+        // Without a stream table there is no previous clock: plain filter.
+        let mut ctx = test_ctx();
+        let child = scan_with_pk(1, "events", "public", "e", &["id", "ts"], &["id"]);
+        let tree = filter(sliding_predicate(), child);
+        let result = diff_filter(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_not_contains(&sql, "frontier ->> 'clock'");
+    }
+
+    #[test]
+    fn test_sliding_window_filter_not_used_for_keyless_scan() {
+        let mut ctx = test_ctx_with_st("public", "recent");
+        let child = scan(1, "events", "public", "e", &["id", "ts"]);
+        let tree = filter(sliding_predicate(), child);
+        let result = diff_filter(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_not_contains(&sql, "frontier ->> 'clock'");
+    }
+
     #[test]
     fn test_replace_column_refs_in_raw_rewrites_qualified_lateral_columns() {
         let sql = "CAST(\"e\".\"value\" AS integer) > 12";
@@ -0,0 +1,431 @@
+//! GROUPING SETS / CUBE / ROLLUP differentiation (GSETS-NATIVE).
+//!
+//! A single `GroupingSets` node maintains every grouping set of the query
+//! instead of one aggregate per set glued together with UNION ALL.
+//!
+//! The delta CTE fans each child delta row out over all grouping sets with
+//! a `CROSS JOIN` against the list of grouping-set ids and masks the
+//! columns a set does not group by to NULL, so one `GROUP BY` pass rolls
+//! the change up through every level. From there on the node is an
+//! ordinary aggregate (see `diff_grouped_aggregate`) whose groups are
+//! keyed by the grouping-set id plus the grouping columns.
+//!
+//! The grouping-set id is the value of `GROUPING(g1, …, gn)` over all
+//! grouping columns: bit `n - 1 - i` is set when `g_i` is not grouped by.
+//! It is not stored — the FULL refresh hashes `GROUPING(…)` into the row ID
+//! (see `full_refresh_row_id_sql`) and `GROUPING()` outputs are derived
+//! from it.
+
+use crate::dvm::diff::{DiffContext, DiffResult, quote_ident};
+use crate::dvm::operators::aggregate::{
+    agg_to_rescan_sql, child_to_from_sql, col_ref_or_sql_expr, diff_grouped_aggregate,
+    rescan_aggregates,
+};
+use crate::dvm::operators::scan::build_hash_expr;
+use crate::dvm::parser::{AggExpr, Expr, GroupingFuncExpr, OpTree};
+use crate::error::PgTrickleError;
+
+/// Name of the grouping-set id column carried between the aggregate CTEs.
+pub(crate) const GROUPING_ID_COL: &str = "__pgt_grouping_id";
+
+/// `GROUPING(g1, …, gn)` value of a grouping set given as indices into the
+/// `column_count` grouping columns.
+pub(crate) fn grouping_set_id(set: &[usize], column_count: usize) -> i64 {
+    (0..column_count)
+        .filter(|i| !set.contains(i))
+        .fold(0, |id, i| id | 1 << (column_count - 1 - i))
+}
+
+/// Row ID of a grouping-sets row in the FULL refresh query: the hash of
+/// `GROUPING(g1, …, gn)` and the grouping columns, matching the hash the
+/// delta computes from its grouping-set id column.
+pub(crate) fn full_refresh_row_id_sql(group_by: &[Expr]) -> String {
+    let cols: Vec<String> = group_by.iter().map(|e| e.to_sql()).collect();
+    let mut items = vec![format!("GROUPING({})", cols.join(", "))];
+    items.extend(cols);
+    build_hash_expr(&items)
+}
+
+/// Grouping sets of a `GroupingSets` node, as seen by the aggregate diff.
+pub(crate) struct GroupingSetsLayout<'a> {
+    column_count: usize,
+    sets: &'a [Vec<usize>],
+    pub grouping_funcs: &'a [GroupingFuncExpr],
+}
+
+impl GroupingSetsLayout<'_> {
+    /// Bit of grouping column `i` in the grouping-set id.
+    fn bit(&self, i: usize) -> i64 {
+        1 << (self.column_count - 1 - i)
+    }
+
+    /// Id of the empty grouping set `()`, if the query has one.
+    pub(crate) fn empty_set_id(&self) -> Option<i64> {
+        self.sets
+            .iter()
+            .any(|set| set.is_empty())
+            .then_some((1 << self.column_count) - 1)
+    }
+
+    /// The grouping-set ids as a FROM item `__pgt_gs(__pgt_gid)`.
+    ///
+    /// A full CUBE is every id from 0 to 2^n - 1, which a `generate_series`
+    /// spells without listing them.
+    pub(crate) fn values_sql(&self) -> String {
+        if self.sets.len() == 1 << self.column_count {
+            return format!(
+                "generate_series(0, {}) __pgt_gs(__pgt_gid)",
+                (1i64 << self.column_count) - 1
+            );
+        }
+        let ids: Vec<String> = self
+            .sets
+            .iter()
+            .map(|set| format!("({})", grouping_set_id(set, self.column_count)))
+            .collect();
+        format!("(VALUES {}) __pgt_gs(__pgt_gid)", ids.join(", "))
+    }
+
+    /// Delta CTE group columns: the grouping-set id, then each grouping
+    /// column masked to NULL in the sets that do not group by it.
+    pub(crate) fn delta_group_selects(
+        &self,
+        group_resolved: &[String],
+        group_output: &[String],
+    ) -> Vec<String> {
+        let mut selects = vec![format!(
+            "__pgt_gs.__pgt_gid AS {}",
+            quote_ident(GROUPING_ID_COL)
+        )];
+        for (i, (resolved, output)) in group_resolved.iter().zip(group_output).enumerate() {
+            selects.push(format!(
+                "CASE WHEN (__pgt_gs.__pgt_gid & {bit}) = 0 THEN {col} END AS {alias}",
+                bit = self.bit(i),
+                col = col_ref_or_sql_expr(resolved),
+                alias = quote_ident(output),
+            ));
+        }
+        selects
+    }
+
+    /// `GROUPING(…)` outputs computed from the grouping-set id `gid`.
+    ///
+    /// Argument `j` of `m` contributes bit `m - 1 - j`, copied from the
+    /// id bit of the grouping column it names.
+    pub(crate) fn grouping_func_selects(&self, gid: &str) -> Vec<String> {
+        self.grouping_funcs
+            .iter()
+            .map(|gf| {
+                let value = if gf.args.iter().copied().eq(0..self.column_count) {
+                    gid.to_string()
+                } else {
+                    let m = gf.args.len();
+                    gf.args
+                        .iter()
+                        .enumerate()
+                        .map(|(j, &arg)| {
+                            let bit = format!("(({gid} >> {}) & 1)", self.column_count - 1 - arg);
+                            match m - 1 - j {
+                                0 => bit,
+                                shift => format!("({bit} << {shift})"),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                };
+                format!("({value}) AS {}", quote_ident(&gf.alias))
+            })
+            .collect()
+    }
+
+    /// Re-aggregate the affected groups of rescan aggregates (MIN/MAX,
+    /// group-rescan and DISTINCT aggregates) with native grouping sets.
+    ///
+    /// A source row is read when it belongs to some changed group: for each
+    /// grouping column, either the delta row's set does not group by it or
+    /// the values match.
+    pub(crate) fn build_rescan_cte(
+        &self,
+        ctx: &mut DiffContext,
+        child: &OpTree,
+        group_by: &[Expr],
+        aggregates: &[AggExpr],
+        delta_cte: &str,
+    ) -> Result<Option<String>, PgTrickleError> {
+        let rescan_aggs = rescan_aggregates(aggregates, false, &[]);
+        if rescan_aggs.is_empty() {
+            return Ok(None);
+        }
+        let Some(from_sql) = child_to_from_sql(child, &ctx.cte_registry) else {
+            return Err(PgTrickleError::UnsupportedOperator(
+                "GROUPING SETS / CUBE / ROLLUP with MIN, MAX, DISTINCT or group-rescan \
+                 aggregates requires a FROM clause of tables, joins and filters"
+                    .into(),
+            ));
+        };
+
+        let group_sql: Vec<String> = group_by.iter().map(|e| e.to_sql()).collect();
+        let gid_col = quote_ident(GROUPING_ID_COL);
+
+        let mut selects = vec![format!("GROUPING({}) AS {gid_col}", group_sql.join(", "))];
+        for (expr, sql) in group_by.iter().zip(&group_sql) {
+            selects.push(format!("{sql} AS {}", quote_ident(&expr.output_name())));
+        }
+        for agg in &rescan_aggs {
+            selects.push(format!(
+                "{} AS {}",
+                agg_to_rescan_sql(agg),
+                quote_ident(&agg.alias),
+            ));
+        }
+
+        let corr: Vec<String> = group_by
+            .iter()
+            .zip(&group_sql)
+            .enumerate()
+            .map(|(i, (expr, sql))| {
+                format!(
+                    "((__pgt_d2.{gid_col} & {bit}) <> 0 OR {sql} IS NOT DISTINCT FROM __pgt_d2.{col})",
+                    bit = self.bit(i),
+                    col = quote_ident(&expr.output_name()),
+                )
+            })
+            .collect();
+        let group_filter = format!(
+            "EXISTS (SELECT 1 FROM {delta_cte} __pgt_d2 WHERE {})",
+            corr.join(" AND "),
+        );
+
+        let sets_sql: Vec<String> = self
+            .sets
+            .iter()
+            .map(|set| {
+                let cols: Vec<&str> = set.iter().map(|&i| group_sql[i].as_str()).collect();
+                format!("({})", cols.join(", "))
+            })
+            .collect();
+
+        // Same outer-WHERE detection as the plain aggregate rescan.
+        let has_outer_where = from_sql.contains(" WHERE ") && !from_sql.starts_with('(');
+        let filter_kw = if has_outer_where { "  AND" } else { "WHERE" };
+        let rescan_sql = format!(
+            "SELECT {selects}\nFROM {from_sql}\n{filter_kw} {group_filter}\nGROUP BY GROUPING SETS ({sets})",
+            selects = selects.join(",\n       "),
+            sets = sets_sql.join(", "),
+        );
+
+        let rescan_cte = ctx.next_cte_name("gsets_rescan");
+        ctx.add_cte(rescan_cte.clone(), rescan_sql);
+        Ok(Some(rescan_cte))
+    }
+}
+
+/// Differentiate a GroupingSets node.
+pub fn diff_grouping_sets(
+    ctx: &mut DiffContext,
+    op: &OpTree,
+) -> Result<DiffResult, PgTrickleError> {
+    let OpTree::GroupingSets {
+        group_by,
+        sets,
+        aggregates,
+        grouping_funcs,
+        child,
+    } = op
+    else {
+        return Err(PgTrickleError::InternalError(
+            "diff_grouping_sets called on non-GroupingSets node".into(),
+        ));
+    };
+    let layout = GroupingSetsLayout {
+        column_count: group_by.len(),
+        sets,
+        grouping_funcs,
+    };
+    diff_grouped_aggregate(ctx, group_by, aggregates, child, Some(&layout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dvm::operators::test_helpers::*;
+
+    /// ROLLUP(region, city) → (region, city), (region), ().
+    fn rollup_region_city(aggregates: Vec<AggExpr>) -> OpTree {
+        grouping_sets(
+            vec![colref("region"), colref("city")],
+            vec![vec![0, 1], vec![0], vec![]],
+            aggregates,
+            vec![],
+            scan(
+                1,
+                "sales",
+                "public",
+                "s",
+                &["id", "region", "city", "amount"],
+            ),
+        )
+    }
+
+    #[test]
+    fn test_grouping_set_id_bits() {
+        // GROUPING(a, b, c): bit 2 = a, bit 1 = b, bit 0 = c.
+        assert_eq!(grouping_set_id(&[0, 1, 2], 3), 0);
+        assert_eq!(grouping_set_id(&[0, 2], 3), 0b010);
+        assert_eq!(grouping_set_id(&[1], 3), 0b101);
+        assert_eq!(grouping_set_id(&[], 3), 0b111);
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_fans_out_delta() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = rollup_region_city(vec![sum_col("amount", "total")]);
+        let result = diff_grouping_sets(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(
+            &sql,
+            "CROSS JOIN (VALUES (0), (1), (3)) __pgt_gs(__pgt_gid)",
+        );
+        assert_sql_contains(
+            &sql,
+            "CASE WHEN (__pgt_gs.__pgt_gid & 2) = 0 THEN \"region\" END AS \"region\"",
+        );
+        assert_sql_contains(
+            &sql,
+            "CASE WHEN (__pgt_gs.__pgt_gid & 1) = 0 THEN \"city\" END AS \"city\"",
+        );
+        // Never the P5 bypass, which cannot fan out.
+        assert_sql_not_contains(&sql, "c.action = 'I'");
+        // Rows are matched to the stream table by row ID.
+        assert_sql_contains(&sql, "st.__pgt_row_id = pgtrickle.pg_trickle_hash_multi");
+        assert_eq!(
+            result.columns,
+            vec!["region", "city", "__pgt_count", "total"]
+        );
+        assert!(result.is_deduplicated);
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_keeps_empty_set_row() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = rollup_region_city(vec![sum_col("amount", "total")]);
+        let result = diff_grouping_sets(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        // The grand total is never deleted and turns NULL when empty.
+        assert_sql_contains(&sql, "WHEN d.\"__pgt_grouping_id\" <> 3 AND");
+        assert_sql_contains(&sql, "WHEN m.new_count <= 0 THEN NULL");
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_without_empty_set_deletes() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = grouping_sets(
+            vec![colref("region"), colref("city")],
+            vec![vec![0], vec![1]],
+            vec![count_star("cnt")],
+            vec![],
+            scan(1, "sales", "public", "s", &["id", "region", "city"]),
+        );
+        let result = diff_grouping_sets(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(&sql, "(VALUES (1), (2))");
+        assert_sql_not_contains(&sql, "\"__pgt_grouping_id\" <>");
+        assert_sql_contains(&sql, "<= 0 THEN 'D'");
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_full_cube_uses_series() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let sets = (0..8usize)
+            .map(|mask| (0..3).filter(|i| mask & (1 << i) != 0).collect())
+            .collect();
+        let tree = grouping_sets(
+            vec![colref("a"), colref("b"), colref("c")],
+            sets,
+            vec![count_star("cnt")],
+            vec![],
+            scan(1, "t", "public", "t", &["id", "a", "b", "c"]),
+        );
+        let result = diff_grouping_sets(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(&sql, "CROSS JOIN generate_series(0, 7) __pgt_gs(__pgt_gid)");
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_grouping_outputs() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = grouping_sets(
+            vec![colref("region"), colref("city")],
+            vec![vec![0, 1], vec![0], vec![]],
+            vec![count_star("cnt")],
+            vec![
+                GroupingFuncExpr {
+                    args: vec![0, 1],
+                    alias: "g_all".to_string(),
+                },
+                GroupingFuncExpr {
+                    args: vec![1, 0],
+                    alias: "g_swapped".to_string(),
+                },
+            ],
+            scan(1, "sales", "public", "s", &["id", "region", "city"]),
+        );
+        let result = diff_grouping_sets(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_eq!(
+            result.columns,
+            vec!["region", "city", "g_all", "g_swapped", "__pgt_count", "cnt"]
+        );
+        assert_sql_contains(&sql, "(m.\"__pgt_grouping_id\") AS \"g_all\"");
+        assert_sql_contains(
+            &sql,
+            "(((m.\"__pgt_grouping_id\" >> 0) & 1) << 1) | ((m.\"__pgt_grouping_id\" >> 1) & 1)) AS \"g_swapped\"",
+        );
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_rescan_uses_native_grouping_sets() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = rollup_region_city(vec![max_col("amount", "top")]);
+        let result = diff_grouping_sets(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(&sql, "GROUPING(region, city) AS \"__pgt_grouping_id\"");
+        assert_sql_contains(
+            &sql,
+            "GROUP BY GROUPING SETS ((region, city), (region), ())",
+        );
+        assert_sql_contains(
+            &sql,
+            "((__pgt_d2.\"__pgt_grouping_id\" & 2) <> 0 OR region IS NOT DISTINCT FROM __pgt_d2.\"region\")",
+        );
+        assert_sql_contains(
+            &sql,
+            "r.\"__pgt_grouping_id\" IS NOT DISTINCT FROM d.\"__pgt_grouping_id\"",
+        );
+    }
+
+    #[test]
+    fn test_diff_grouping_sets_rejects_intermediate() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        ctx.st_user_columns = Some(vec!["other".to_string()]);
+        ctx.st_has_pgt_count = true;
+        let tree = rollup_region_city(vec![sum_col("amount", "total")]);
+        let err = diff_grouping_sets(&mut ctx, &tree).unwrap_err();
+        assert!(matches!(err, PgTrickleError::UnsupportedOperator(_)));
+    }
+
+    #[test]
+    fn test_full_refresh_row_id_matches_delta_layout() {
+        assert_eq!(
+            full_refresh_row_id_sql(&[colref("region"), colref("city")]),
+            "pgtrickle.pg_trickle_hash_multi(ARRAY[(GROUPING(region, city))::TEXT, \
+             (region)::TEXT, (city)::TEXT])"
+        );
+    }
+}
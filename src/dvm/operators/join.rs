@@ -711,7 +711,9 @@ fn collect_equijoin_keys(
     keys: &mut Vec<EquiKeyPair>,
 ) {
     match expr {
-        Expr::BinaryOp { op, left, right } if op == "=" => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op == "=" => {
             // Found an equality — determine which side belongs to which table.
             let left_stripped = left.strip_qualifier().to_sql();
             let right_stripped = right.strip_qualifier().to_sql();
@@ -734,7 +736,9 @@ fn collect_equijoin_keys(
                 }
             }
         }
-        Expr::BinaryOp { op, left, right } if op.eq_ignore_ascii_case("AND") => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op.eq_ignore_ascii_case("AND") => {
             // AND conjunction — recurse into both sides
             collect_equijoin_keys(left, left_alias, right_alias, keys);
             collect_equijoin_keys(right, left_alias, right_alias, keys);
@@ -21,7 +21,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::dvm::diff::quote_ident;
 use crate::dvm::operators::aggregate::agg_to_rescan_sql;
-use crate::dvm::parser::{Expr, OpTree};
+use crate::dvm::parser::{Expr, OpTree, lateral_function_output_columns};
 
 // ── Snapshot SQL generation ─────────────────────────────────────────────
 
@@ -29,7 +29,10 @@ use crate::dvm::parser::{Expr, OpTree};
 ///
 /// For `Scan` nodes, returns the quoted `"schema"."table"` reference.
 /// For join nodes, returns a parenthesized subquery with disambiguated
-/// column names matching the diff engine's output format.
+/// column names matching the diff engine's output format. Every other
+/// operator becomes a derived table whose columns match
+/// [`OpTree::output_columns`], so any subquery can sit on either side of a
+/// join.
 ///
 /// Used in join delta formulas where one side of the join must reference
 /// the current full state of the other side.
@@ -108,24 +111,23 @@ pub fn build_snapshot_sql(op: &OpTree) -> String {
             condition,
             left,
             right,
-        } => build_join_snapshot("JOIN", condition, left, right),
+        } => build_join_snapshot("JOIN", condition, left, right, &[]),
         OpTree::LeftJoin {
             condition,
             left,
             right,
-        } => build_join_snapshot("LEFT JOIN", condition, left, right),
+        } => build_join_snapshot("LEFT JOIN", condition, left, right, &[]),
         OpTree::FullJoin {
             condition,
             left,
             right,
-        } => build_join_snapshot("FULL JOIN", condition, left, right),
+        } => build_join_snapshot("FULL JOIN", condition, left, right, &[]),
         OpTree::Filter { predicate, child } => {
-            let child_snap = build_snapshot_sql(child);
             if matches!(child.as_ref(), OpTree::Scan { .. }) {
                 let alias = child.alias();
                 format!(
                     "(SELECT * FROM {} {} WHERE {})",
-                    child_snap,
+                    build_snapshot_sql(child),
                     quote_ident(alias),
                     predicate.to_sql()
                 )
@@ -135,55 +137,37 @@ pub fn build_snapshot_sql(op: &OpTree) -> String {
                 // Wrap in an outer SELECT to apply the HAVING predicate.
                 format!(
                     "(SELECT * FROM {} __having_sub WHERE {})",
-                    child_snap,
+                    build_snapshot_sql(child),
                     predicate.to_sql()
                 )
             } else {
-                // For non-Scan children (e.g. Filter over Join), the filter
-                // is applied by diff_filter in the diff pipeline. The
-                // snapshot represents the unfiltered child state.
-                child_snap
-            }
-        }
-        OpTree::Project {
-            expressions,
-            aliases,
-            child,
-        } => {
-            // A Project renames/transforms columns. The snapshot must preserve
-            // these aliases so that downstream join conditions can reference
-            // the projected column names (e.g., `__pgt_scalar_1` from a
-            // scalar subquery CROSS JOIN rewrite).
-            //
-            // When the child is a join, inline its FROM clause to keep the
-            // original table aliases (e.g., "a", "b") accessible so that
-            // project expressions like COALESCE(a.k, b.k) can reference them.
-            // Without this, wrapping the join snapshot as "full_join" hides
-            // the inner aliases, causing "missing FROM-clause entry" errors.
-            let selects: Vec<String> = expressions
-                .iter()
-                .zip(aliases.iter())
-                .map(|(expr, alias)| {
-                    let expr_sql = expr.to_sql();
-                    let alias_ident = quote_ident(alias);
-                    if expr_sql == *alias {
-                        alias_ident
-                    } else {
-                        format!("{expr_sql} AS {alias_ident}")
-                    }
-                })
-                .collect();
-            if let Some(from_clause) = build_snapshot_inline_from_for_join(child) {
-                format!("(SELECT {} FROM {})", selects.join(", "), from_clause)
-            } else {
-                let inner = build_snapshot_sql(child);
-                let child_alias = child.alias();
-                format!(
-                    "(SELECT {} FROM {} {})",
-                    selects.join(", "),
-                    inner,
-                    quote_ident(child_alias),
-                )
+                // Filters stacked on a join keep the join's disambiguated
+                // column names, so the predicates go into the join snapshot
+                // itself. Everything else is evaluated over the inlined child.
+                let mut predicates = vec![predicate];
+                let mut base = child.as_ref();
+                while let OpTree::Filter { predicate, child } = base {
+                    predicates.push(predicate);
+                    base = child.as_ref();
+                }
+                match base {
+                    OpTree::InnerJoin {
+                        condition,
+                        left,
+                        right,
+                    } => build_join_snapshot("JOIN", condition, left, right, &predicates),
+                    OpTree::LeftJoin {
+                        condition,
+                        left,
+                        right,
+                    } => build_join_snapshot("LEFT JOIN", condition, left, right, &predicates),
+                    OpTree::FullJoin {
+                        condition,
+                        left,
+                        right,
+                    } => build_join_snapshot("FULL JOIN", condition, left, right, &predicates),
+                    _ => format!("({})", build_snapshot_select(op)),
+                }
             }
         }
         OpTree::Subquery {
@@ -225,38 +209,6 @@ pub fn build_snapshot_sql(op: &OpTree) -> String {
                 )
             }
         }
-        OpTree::Aggregate {
-            group_by,
-            aggregates,
-            child,
-        } => {
-            let inner = build_snapshot_sql(child);
-            let child_alias = child.alias();
-            let mut selects = Vec::new();
-            for expr in group_by {
-                selects.push(expr.to_sql());
-            }
-            for agg in aggregates {
-                selects.push(format!(
-                    "{} AS {}",
-                    agg_to_rescan_sql(agg),
-                    quote_ident(&agg.alias),
-                ));
-            }
-            let gb = if group_by.is_empty() {
-                String::new()
-            } else {
-                let cols: Vec<String> = group_by.iter().map(|e| e.to_sql()).collect();
-                format!(" GROUP BY {}", cols.join(", "))
-            };
-            format!(
-                "(SELECT {} FROM {} {}{})",
-                selects.join(", "),
-                inner,
-                quote_ident(child_alias),
-                gb,
-            )
-        }
         OpTree::SemiJoin {
             condition,
             left,
@@ -293,14 +245,132 @@ pub fn build_snapshot_sql(op: &OpTree) -> String {
                 ra = quote_ident(right_alias),
             )
         }
+        // Only valid inside the recursive term of its own CTE, where the
+        // CTE name is in scope.
+        OpTree::RecursiveSelfRef { cte_name, .. } => quote_ident(cte_name),
+        OpTree::Project { .. }
+        | OpTree::Aggregate { .. }
+        | OpTree::GroupingSets { .. }
+        | OpTree::Window { .. }
+        | OpTree::Distinct { .. }
+        | OpTree::UnionAll { .. }
+        | OpTree::Intersect { .. }
+        | OpTree::Except { .. }
+        | OpTree::RecursiveCte { .. }
+        | OpTree::LateralFunction { .. }
+        | OpTree::LateralSubquery { .. }
+        | OpTree::ScalarSubquery { .. }
+        | OpTree::ConstantSelect { .. } => format!("({})", build_snapshot_select(op)),
+    }
+}
+
+/// Build the bare `SELECT` statement behind an operator's snapshot.
+///
+/// [`build_snapshot_sql`] parenthesizes this into a derived table. Set
+/// operations and recursive CTEs use the bare form to splice their branches
+/// together — PostgreSQL rejects a recursive self-reference that sits
+/// inside a subquery.
+///
+/// Output column names match [`OpTree::output_columns`].
+fn build_snapshot_select(op: &OpTree) -> String {
+    match op {
+        OpTree::Project {
+            expressions,
+            aliases,
+            child,
+        } => {
+            // A Project renames/transforms columns. The snapshot must preserve
+            // these aliases so that downstream join conditions can reference
+            // the projected column names (e.g., `__pgt_scalar_1` from a
+            // scalar subquery CROSS JOIN rewrite).
+            let selects: Vec<String> = expressions
+                .iter()
+                .zip(aliases.iter())
+                .map(|(expr, alias)| select_item(expr.to_sql(), alias))
+                .collect();
+            format!(
+                "SELECT {}{}",
+                selects.join(", "),
+                build_inline_snapshot(child).from_where()
+            )
+        }
+        OpTree::Aggregate {
+            group_by,
+            aggregates,
+            child,
+        } => {
+            let mut selects: Vec<String> = group_by
+                .iter()
+                .map(|expr| select_item(expr.to_sql(), &expr.output_name()))
+                .collect();
+            for agg in aggregates {
+                selects.push(format!(
+                    "{} AS {}",
+                    agg_to_rescan_sql(agg),
+                    quote_ident(&agg.alias),
+                ));
+            }
+            let gb = if group_by.is_empty() {
+                String::new()
+            } else {
+                let cols: Vec<String> = group_by.iter().map(|e| e.to_sql()).collect();
+                format!(" GROUP BY {}", cols.join(", "))
+            };
+            format!(
+                "SELECT {}{}{}",
+                selects.join(", "),
+                build_inline_snapshot(child).from_where(),
+                gb,
+            )
+        }
+        OpTree::GroupingSets {
+            group_by,
+            sets,
+            aggregates,
+            grouping_funcs,
+            child,
+        } => {
+            let group_sql: Vec<String> = group_by.iter().map(|e| e.to_sql()).collect();
+            let mut selects: Vec<String> = group_by
+                .iter()
+                .zip(group_sql.iter())
+                .map(|(expr, sql)| select_item(sql.clone(), &expr.output_name()))
+                .collect();
+            for gf in grouping_funcs {
+                let args: Vec<&str> = gf.args.iter().map(|&i| group_sql[i].as_str()).collect();
+                selects.push(format!(
+                    "GROUPING({}) AS {}",
+                    args.join(", "),
+                    quote_ident(&gf.alias),
+                ));
+            }
+            for agg in aggregates {
+                selects.push(format!(
+                    "{} AS {}",
+                    agg_to_rescan_sql(agg),
+                    quote_ident(&agg.alias),
+                ));
+            }
+            let set_list: Vec<String> = sets
+                .iter()
+                .map(|set| {
+                    let cols: Vec<&str> = set.iter().map(|&i| group_sql[i].as_str()).collect();
+                    format!("({})", cols.join(", "))
+                })
+                .collect();
+            format!(
+                "SELECT {}{} GROUP BY GROUPING SETS ({})",
+                selects.join(", "),
+                build_inline_snapshot(child).from_where(),
+                set_list.join(", "),
+            )
+        }
         OpTree::Window {
             window_exprs,
             pass_through,
             child,
             ..
         } => {
-            let inner = build_snapshot_sql(child);
-            let child_alias = child.alias();
             let mut selects: Vec<String> = pass_through
                 .iter()
                 .map(|(_, alias)| quote_ident(alias))
@@ -309,26 +379,270 @@ pub fn build_snapshot_sql(op: &OpTree) -> String {
                 selects.push(format!("{} AS {}", w.to_sql(), quote_ident(&w.alias)));
             }
             format!(
-                "(SELECT {} FROM {} {})",
+                "SELECT {}{}",
                 selects.join(", "),
-                inner,
-                quote_ident(child_alias),
+                build_inline_snapshot(child).from_where()
             )
         }
-        _ => {
-            // This node type cannot appear as a direct join child in a snapshot.
-            // Raise a PostgreSQL-level error so the user gets a clear message
-            // instead of a SQL syntax error from an injected comment.
-            pgrx::error!(
-                "pg_trickle: operator '{}' is not supported as a direct join source \
-                 in DIFFERENTIAL/IMMEDIATE mode; rewrite the defining query to \
-                 place this subquery in a named CTE or derived table.",
-                op.node_kind()
+        OpTree::Distinct { child } => {
+            let inline = build_inline_snapshot(child);
+            format!(
+                "SELECT DISTINCT {}{}",
+                inline.columns.join(", "),
+                inline.from_where()
+            )
+        }
+        OpTree::UnionAll { children } => children
+            .iter()
+            .map(|c| format!("({})", build_snapshot_select(c)))
+            .collect::<Vec<_>>()
+            .join(" UNION ALL "),
+        OpTree::Intersect { left, right, all } | OpTree::Except { left, right, all } => {
+            let set_op = if matches!(op, OpTree::Intersect { .. }) {
+                "INTERSECT"
+            } else {
+                "EXCEPT"
+            };
+            format!(
+                "({}) {set_op}{} ({})",
+                build_snapshot_select(left),
+                if *all { " ALL" } else { "" },
+                build_snapshot_select(right),
+            )
+        }
+        OpTree::RecursiveCte {
+            alias,
+            columns,
+            base,
+            recursive,
+            union_all,
+        } => {
+            let cols = columns
+                .iter()
+                .map(|c| quote_ident(c))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "WITH RECURSIVE {name} ({cols}) AS ({} {} {}) SELECT {cols} FROM {name}",
+                build_snapshot_select(base),
+                if *union_all { "UNION ALL" } else { "UNION" },
+                build_snapshot_select(recursive),
+                name = quote_ident(alias),
+            )
+        }
+        OpTree::ScalarSubquery {
+            subquery,
+            alias,
+            child,
+            ..
+        } => {
+            let mut inline = build_inline_snapshot(child);
+            let scalar_col = snapshot_output_columns(subquery)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            inline.columns.push(format!(
+                "(SELECT __pgt_sq.{} FROM {} __pgt_sq LIMIT 1) AS {}",
+                quote_ident(&scalar_col),
+                build_snapshot_sql(subquery),
+                quote_ident(alias),
+            ));
+            inline.select()
+        }
+        OpTree::ConstantSelect { sql, .. } => sql.clone(),
+        _ => build_inline_snapshot(op).select(),
+    }
+}
+
+/// An operator snapshot expressed as FROM-clause items instead of a
+/// derived table.
+///
+/// Expressions above a child (projections, aggregates, filters, LATERAL
+/// calls) reference the child's original table aliases, e.g. `o.amount` or
+/// `COALESCE(a.k, b.k)`. Wrapping the child as `(snapshot) "filter"` would
+/// hide those aliases and fail with "missing FROM-clause entry", so the
+/// child is spliced into the parent's FROM clause instead.
+struct InlineSnapshot {
+    /// FROM-clause items, e.g. `"public"."a" "a" JOIN "public"."b" "b" ON ...`.
+    from: String,
+    /// WHERE predicates collected from `Filter` nodes.
+    predicates: Vec<String>,
+    /// Select-list items yielding the operator's output columns in order.
+    columns: Vec<String>,
+}
+
+impl InlineSnapshot {
+    /// ` FROM <items>[ WHERE <predicates>]`.
+    fn from_where(&self) -> String {
+        match self.predicates.as_slice() {
+            [] => format!(" FROM {}", self.from),
+            [p] => format!(" FROM {} WHERE {p}", self.from),
+            ps => format!(" FROM {} WHERE ({})", self.from, ps.join(") AND (")),
+        }
+    }
+
+    fn select(&self) -> String {
+        format!("SELECT {}{}", self.columns.join(", "), self.from_where())
+    }
+}
+
+fn build_inline_snapshot(op: &OpTree) -> InlineSnapshot {
+    match op {
+        OpTree::Scan { alias, .. } => InlineSnapshot {
+            from: format!("{} {}", build_snapshot_sql(op), quote_ident(alias)),
+            predicates: Vec::new(),
+            columns: qualified_columns(alias, op),
+        },
+        OpTree::InnerJoin {
+            condition,
+            left,
+            right,
+        } => inline_join_snapshot("JOIN", condition, left, right),
+        OpTree::LeftJoin {
+            condition,
+            left,
+            right,
+        } => inline_join_snapshot("LEFT JOIN", condition, left, right),
+        OpTree::FullJoin {
+            condition,
+            left,
+            right,
+        } => inline_join_snapshot("FULL JOIN", condition, left, right),
+        OpTree::Filter { predicate, child } => {
+            let mut inline = build_inline_snapshot(child);
+            inline.predicates.push(predicate.to_sql());
+            inline
+        }
+        OpTree::LateralFunction {
+            func_sql,
+            alias,
+            column_aliases,
+            with_ordinality,
+            child,
+        } => {
+            let mut inline = build_inline_snapshot(child);
+            let mut srf_cols = lateral_function_output_columns(func_sql, alias, column_aliases);
+            if *with_ordinality {
+                srf_cols.push("ordinality".to_string());
+            }
+            inline.from.push_str(&format!(
+                " CROSS JOIN LATERAL {func_sql}{} AS {} ({})",
+                if *with_ordinality {
+                    " WITH ORDINALITY"
+                } else {
+                    ""
+                },
+                quote_ident(alias),
+                srf_cols
+                    .iter()
+                    .map(|c| quote_ident(c))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+            inline.columns.extend(
+                srf_cols
+                    .iter()
+                    .map(|c| format!("{}.{}", quote_ident(alias), quote_ident(c))),
             );
+            inline
+        }
+        OpTree::LateralSubquery {
+            subquery_sql,
+            alias,
+            column_aliases,
+            output_cols,
+            is_left_join,
+            child,
+            ..
+        } => {
+            let mut inline = build_inline_snapshot(child);
+            let sub_cols = if column_aliases.is_empty() {
+                output_cols
+            } else {
+                column_aliases
+            };
+            let alias_clause = if column_aliases.is_empty() {
+                quote_ident(alias)
+            } else {
+                format!(
+                    "{} ({})",
+                    quote_ident(alias),
+                    sub_cols
+                        .iter()
+                        .map(|c| quote_ident(c))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            if *is_left_join {
+                inline.from.push_str(&format!(
+                    " LEFT JOIN LATERAL ({subquery_sql}) AS {alias_clause} ON true"
+                ));
+            } else {
+                inline.from.push_str(&format!(
+                    " CROSS JOIN LATERAL ({subquery_sql}) AS {alias_clause}"
+                ));
+            }
+            inline.columns.extend(
+                sub_cols
+                    .iter()
+                    .map(|c| format!("{}.{}", quote_ident(alias), quote_ident(c))),
+            );
+            inline
+        }
+        _ => {
+            let alias = op.alias();
+            InlineSnapshot {
+                from: format!("{} {}", build_snapshot_sql(op), quote_ident(alias)),
+                predicates: Vec::new(),
+                columns: qualified_columns(alias, op),
+            }
         }
     }
 }
 
+/// Inline a join's FROM clause, keeping both sides' original aliases visible.
+fn inline_join_snapshot(
+    join_type: &str,
+    condition: &Expr,
+    left: &OpTree,
+    right: &OpTree,
+) -> InlineSnapshot {
+    let mut columns = qualified_columns(left.alias(), left);
+    columns.extend(qualified_columns(right.alias(), right));
+    InlineSnapshot {
+        from: build_snapshot_inline_join_from(join_type, condition, left, right),
+        predicates: Vec::new(),
+        columns,
+    }
+}
+
+/// Select-list items reading `op`'s snapshot columns through `alias`,
+/// renamed to its output column names where the two differ.
+fn qualified_columns(alias: &str, op: &OpTree) -> Vec<String> {
+    snapshot_output_columns(op)
+        .iter()
+        .zip(op.output_columns())
+        .map(|(snap, out)| {
+            let col = format!("{}.{}", quote_ident(alias), quote_ident(snap));
+            if *snap == out {
+                col
+            } else {
+                format!("{col} AS {}", quote_ident(&out))
+            }
+        })
+        .collect()
+}
+
+/// `<expr> AS "alias"`, or just the alias when the expression already is it.
+fn select_item(expr_sql: String, alias: &str) -> String {
+    if expr_sql == alias {
+        quote_ident(alias)
+    } else {
+        format!("{expr_sql} AS {}", quote_ident(alias))
+    }
+}
+
 /// Build an inline FROM clause for a join node using `build_snapshot_sql` for each child.
 ///
 /// Returns the raw FROM clause string (e.g., `<left_snap> "a" FULL JOIN <right_snap> "b" ON ...`)
@@ -356,56 +670,23 @@ fn build_snapshot_inline_join_from(
     )
 }
 
-/// When a `Project` node sits directly over a join, return an inline FROM clause that
-/// keeps the original join table aliases accessible.
-///
-/// This prevents the "missing FROM-clause entry for table 'a'" error that arises when
-/// a Project wraps a join snapshot subquery (aliased as `"full_join"` etc.) — inside
-/// that subquery the original aliases `a`, `b` are no longer visible.
-///
-/// Returns `Some(from_clause)` for direct join children; `None` otherwise.
-fn build_snapshot_inline_from_for_join(op: &OpTree) -> Option<String> {
-    match op {
-        OpTree::InnerJoin {
-            condition,
-            left,
-            right,
-        } => Some(build_snapshot_inline_join_from(
-            "JOIN", condition, left, right,
-        )),
-        OpTree::LeftJoin {
-            condition,
-            left,
-            right,
-        } => Some(build_snapshot_inline_join_from(
-            "LEFT JOIN",
-            condition,
-            left,
-            right,
-        )),
-        OpTree::FullJoin {
-            condition,
-            left,
-            right,
-        } => Some(build_snapshot_inline_join_from(
-            "FULL JOIN",
-            condition,
-            left,
-            right,
-        )),
-        // No recursion through Filter (would lose the predicate) or other wrappers.
-        _ => None,
-    }
-}
-
 /// Build a snapshot subquery for a join node.
 ///
 /// Produces a parenthesized SELECT with disambiguated column names:
 /// ```sql
 /// (SELECT l."id" AS "l__id", ..., r."id" AS "r__id", ...
-///  FROM left_snap l JOIN right_snap r ON condition)
+///  FROM left_snap l JOIN right_snap r ON condition [WHERE filters])
 /// ```
-fn build_join_snapshot(join_type: &str, condition: &Expr, left: &OpTree, right: &OpTree) -> String {
+///
+/// `filters` are the predicates of `Filter` nodes stacked directly on the
+/// join; they are rewritten against the child aliases like the condition.
+fn build_join_snapshot(
+    join_type: &str,
+    condition: &Expr,
+    left: &OpTree,
+    right: &OpTree,
+    filters: &[&Expr],
+) -> String {
     let left_snap = build_snapshot_sql(left);
     let right_snap = build_snapshot_sql(right);
     let left_alias = left.alias();
@@ -439,16 +720,26 @@ fn build_join_snapshot(join_type: &str, condition: &Expr, left: &OpTree, right:
 
     // Rewrite condition for snapshot: use child aliases directly
     let cond_sql = rewrite_join_condition(condition, left, left_alias, right, right_alias);
+    let where_sql = if filters.is_empty() {
+        String::new()
+    } else {
+        let preds: Vec<String> = filters
+            .iter()
+            .map(|f| rewrite_join_condition(f, left, left_alias, right, right_alias))
+            .collect();
+        format!(" WHERE ({})", preds.join(") AND ("))
+    };
 
     format!(
-        "(SELECT {} FROM {} {} {} {} {} ON {})",
+        "(SELECT {} FROM {} {} {} {} {} ON {}{})",
         select_parts.join(", "),
         left_snap,
         quote_ident(left_alias),
         join_type,
         right_snap,
         quote_ident(right_alias),
-        cond_sql
+        cond_sql,
+        where_sql
     )
 }
 
@@ -460,7 +751,7 @@ fn build_join_snapshot(join_type: &str, condition: &Expr, left: &OpTree, right:
 /// For single-column PKs: `pgtrickle.pg_trickle_hash(alias.col::text)`
 /// For multi-column PKs: `pgtrickle.pg_trickle_hash_multi(ARRAY[alias.col1::text, ...])`
 /// For keyless tables: uses all columns as hash input.
-fn build_pk_hash_expr(
+pub(crate) fn build_pk_hash_expr(
     alias: &str,
     columns: &[crate::dvm::parser::Column],
     pk_columns: &[String],
@@ -764,7 +1055,8 @@ fn build_pre_change_inline_join_from(
     )
 }
 
-/// Pre-change variant of [`build_snapshot_inline_from_for_join`].
+/// Pre-change variant of the inline join FROM clause built by
+/// [`build_snapshot_inline_join_from`]; `None` for non-join nodes.
 fn build_pre_change_inline_from_for_join(
     op: &OpTree,
     scan_delta_ctes: &HashMap<String, String>,
@@ -850,16 +1142,26 @@ fn build_pre_change_join_snapshot(
     }
 
     let cond_sql = rewrite_join_condition(condition, left, left_alias, right, right_alias);
+    let where_sql = if filters.is_empty() {
+        String::new()
+    } else {
+        let preds: Vec<String> = filters
+            .iter()
+            .map(|f| rewrite_join_condition(f, left, left_alias, right, right_alias))
+            .collect();
+        format!(" WHERE ({})", preds.join(") AND ("))
+    };
 
     format!(
-        "(SELECT {} FROM {} {} {} {} {} ON {})",
+        "(SELECT {} FROM {} {} {} {} {} ON {}{})",
         select_parts.join(", "),
         left_snap,
         quote_ident(left_alias),
         join_type,
         right_snap,
         quote_ident(right_alias),
-        cond_sql
+        cond_sql,
+        where_sql
     )
 }
 
@@ -1035,17 +1337,24 @@ fn rewrite_expr_for_join(
             op,
             left: l,
             right: r,
+            resolved,
         } => Expr::BinaryOp {
             op: op.clone(),
             left: Box::new(rewrite_expr_for_join(l, left, new_left, right, new_right)),
             right: Box::new(rewrite_expr_for_join(r, left, new_left, right, new_right)),
+            resolved: *resolved,
         },
-        Expr::FuncCall { func_name, args } => Expr::FuncCall {
+        Expr::FuncCall {
+            func_name,
+            args,
+            resolved,
+        } => Expr::FuncCall {
             func_name: func_name.clone(),
             args: args
                 .iter()
                 .map(|a| rewrite_expr_for_join(a, left, new_left, right, new_right))
                 .collect(),
+            resolved: *resolved,
         },
         Expr::Star { table_alias } => {
             // Rewrite star expressions: table.* → new_alias.*
@@ -1067,52 +1376,77 @@ fn rewrite_expr_for_join(
         }
         // Literals and Raw SQL without column references — pass through
         Expr::Literal(_) => expr.clone(),
-        Expr::Raw(sql) => {
-            // Best-effort: rewrite qualified column references in raw SQL
-            // text. For each source alias in left/right children, replace
-            // `alias."col"` and `alias.col` patterns with the new alias.
-            let mut result = sql.clone();
-            let all_aliases = collect_source_aliases(left)
-                .into_iter()
-                .chain(collect_source_aliases(right));
-            for alias in all_aliases {
-                let (new_alias, is_simple) = if has_source_alias(left, &alias) {
-                    (new_left, is_simple_source(left, &alias))
-                } else {
-                    (new_right, is_simple_source(right, &alias))
-                };
-
-                if is_simple {
-                    // Simple: replace alias.col → new_alias.col
-                    // Match both alias."col" and alias.col patterns.
-                    // Also handle quoted form: "alias"."col" → "new_alias"."col"
-                    // (Expr::ColumnRef::to_sql() emits double-quoted identifiers)
-                    let quoted_pattern = format!("\"{}\".", alias.replace('"', "\"\""));
-                    let quoted_replacement = format!("\"{}\".", new_alias.replace('"', "\"\""));
-                    result = result.replace(&quoted_pattern, &quoted_replacement);
-                    let pattern = format!("{}.", alias);
-                    let replacement = format!("{}.", new_alias);
-                    result = result.replace(&pattern, &replacement);
-                } else {
-                    // Nested: alias.col → new_alias."alias__col"
-                    // This is harder in raw SQL — we do a conservative
-                    // pattern replacement for alias."col" → new_alias."alias__col"
-                    // and alias.col → new_alias."alias__col"
-                    // Also handle quoted form "alias"."col"
-                    let quoted_prefix = format!("\"{}\".", alias.replace('"', "\"\""));
-                    if result.contains(&quoted_prefix) {
-                        result = rewrite_raw_quoted_alias_refs(&result, &alias, new_alias);
-                    }
-                    let dot_prefix = format!("{}.", alias);
-                    if result.contains(&dot_prefix) {
-                        // Replace qualified references carefully
-                        result = rewrite_raw_alias_refs(&result, &alias, new_alias);
-                    }
-                }
+        Expr::UnaryOp { .. }
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Array(_)
+        | Expr::Row(_) => {
+            expr.map_children(|c| rewrite_expr_for_join(c, left, new_left, right, new_right))
+        }
+        // Correlated references inside a subquery body are SQL text.
+        Expr::SubLink { .. } => Expr::Raw(rewrite_raw_sql_for_join(
+            &expr.to_sql(),
+            left,
+            new_left,
+            right,
+            new_right,
+        )),
+        Expr::Raw(sql) => Expr::Raw(rewrite_raw_sql_for_join(
+            sql, left, new_left, right, new_right,
+        )),
+    }
+}
+
+/// Best-effort rewrite of qualified column references in raw SQL text. For
+/// each source alias in left/right children, replace `alias."col"` and
+/// `alias.col` patterns with the new alias.
+fn rewrite_raw_sql_for_join(
+    sql: &str,
+    left: &OpTree,
+    new_left: &str,
+    right: &OpTree,
+    new_right: &str,
+) -> String {
+    let mut result = sql.to_string();
+    let all_aliases = collect_source_aliases(left)
+        .into_iter()
+        .chain(collect_source_aliases(right));
+    for alias in all_aliases {
+        let (new_alias, is_simple) = if has_source_alias(left, &alias) {
+            (new_left, is_simple_source(left, &alias))
+        } else {
+            (new_right, is_simple_source(right, &alias))
+        };
+
+        if is_simple {
+            // Simple: replace alias.col → new_alias.col
+            // Match both alias."col" and alias.col patterns.
+            // Also handle quoted form: "alias"."col" → "new_alias"."col"
+            // (Expr::ColumnRef::to_sql() emits double-quoted identifiers)
+            let quoted_pattern = format!("\"{}\".", alias.replace('"', "\"\""));
+            let quoted_replacement = format!("\"{}\".", new_alias.replace('"', "\"\""));
+            result = result.replace(&quoted_pattern, &quoted_replacement);
+            let pattern = format!("{}.", alias);
+            let replacement = format!("{}.", new_alias);
+            result = result.replace(&pattern, &replacement);
+        } else {
+            // Nested: alias.col → new_alias."alias__col"
+            // This is harder in raw SQL — we do a conservative
+            // pattern replacement for alias."col" → new_alias."alias__col"
+            // and alias.col → new_alias."alias__col"
+            // Also handle quoted form "alias"."col"
+            let quoted_prefix = format!("\"{}\".", alias.replace('"', "\"\""));
+            if result.contains(&quoted_prefix) {
+                result = rewrite_raw_quoted_alias_refs(&result, &alias, new_alias);
+            }
+            let dot_prefix = format!("{}.", alias);
+            if result.contains(&dot_prefix) {
+                // Replace qualified references carefully
+                result = rewrite_raw_alias_refs(&result, &alias, new_alias);
             }
-            Expr::Raw(result)
         }
     }
+    result
 }
 
 /// Collect all source table aliases from an OpTree.
@@ -1138,6 +1472,7 @@ fn collect_source_aliases(op: &OpTree) -> Vec<String> {
         OpTree::Filter { child, .. }
         | OpTree::Project { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. } => collect_source_aliases(child),
         OpTree::UnionAll { children } => children.iter().flat_map(collect_source_aliases).collect(),
         // Window, LateralFunction, LateralSubquery, RecursiveCte,
@@ -1266,6 +1601,7 @@ pub fn has_source_alias(op: &OpTree, alias: &str) -> bool {
         OpTree::Filter { child, .. }
         | OpTree::Project { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. } => has_source_alias(child, alias),
         OpTree::Subquery {
             alias: sub_alias,
@@ -1325,6 +1661,7 @@ fn find_column_source(op: &OpTree, column_name: &str) -> Option<String> {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. } => find_column_source(child, column_name),
         _ => None,
     }
@@ -1509,6 +1846,7 @@ fn collect_aliased_keys(
             op,
             left: l_expr,
             right: r_expr,
+            ..
         } if op == "=" => {
             let l_rewritten =
                 rewrite_expr_for_join(l_expr, left, left_alias, right, right_alias).to_sql();
@@ -1520,6 +1858,7 @@ fn collect_aliased_keys(
             op,
             left: l_expr,
             right: r_expr,
+            ..
         } if op.eq_ignore_ascii_case("AND") => {
             collect_aliased_keys(l_expr, left, left_alias, right, right_alias, keys);
             collect_aliased_keys(r_expr, left, left_alias, right, right_alias, keys);
@@ -1566,6 +1905,7 @@ pub fn contains_semijoin(op: &OpTree) -> bool {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. }
         | OpTree::Window { child, .. }
         | OpTree::LateralFunction { child, .. }
@@ -1619,6 +1959,7 @@ pub fn total_scan_count(op: &OpTree) -> usize {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. } => total_scan_count(child),
         OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Window { child, .. }
         | OpTree::Distinct { child, .. } => total_scan_count(child),
         OpTree::UnionAll { children, .. } => children.iter().map(total_scan_count).sum(),
@@ -1654,6 +1995,7 @@ pub fn tree_contains_join(op: &OpTree) -> bool {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Window { child, .. }
         | OpTree::Distinct { child, .. } => tree_contains_join(child),
         OpTree::UnionAll { children, .. } => children.iter().any(tree_contains_join),
@@ -1786,6 +2128,129 @@ mod tests {
         assert!(snap.contains("WHERE"));
     }
 
+    #[test]
+    fn test_snapshot_aggregate_over_filter_keeps_source_alias() {
+        let child = scan(1, "b", "public", "b", &["k", "v"]);
+        let filtered = filter(binop(">", qcolref("b", "v"), lit("0")), child);
+        let node = aggregate(
+            vec![qcolref("b", "k")],
+            vec![sum_col("v", "total")],
+            filtered,
+        );
+        let snap = build_snapshot_sql(&node);
+        assert_sql_contains(&snap, "FROM \"public\".\"b\" \"b\" WHERE (\"b\".\"v\" > 0)");
+        assert_sql_contains(&snap, "\"b\".\"k\" AS \"k\"");
+        assert_sql_contains(&snap, "GROUP BY \"b\".\"k\"");
+    }
+
+    #[test]
+    fn test_snapshot_filter_over_join_applies_predicate() {
+        let a = scan(1, "a", "public", "a", &["id", "x"]);
+        let b = scan(2, "b", "public", "b", &["id"]);
+        let join = inner_join(eq_cond("a", "id", "b", "id"), a, b);
+        let node = filter(binop(">", qcolref("a", "x"), lit("5")), join);
+        let snap = build_snapshot_sql(&node);
+        assert_sql_contains(&snap, "\"a__x\"");
+        assert_sql_contains(&snap, "WHERE");
+        assert_sql_contains(&snap, "> 5");
+    }
+
+    #[test]
+    fn test_snapshot_distinct() {
+        let child = scan(1, "t", "public", "t", &["a", "b"]);
+        let snap = build_snapshot_sql(&distinct(child));
+        assert_eq!(
+            snap,
+            "(SELECT DISTINCT \"t\".\"a\", \"t\".\"b\" FROM \"public\".\"t\" \"t\")"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_union_all_and_set_ops() {
+        let a = scan(1, "a", "public", "a", &["k"]);
+        let b = scan(2, "b", "public", "b", &["k"]);
+        let snap = build_snapshot_sql(&union_all(vec![a.clone(), b.clone()]));
+        assert_eq!(
+            snap,
+            "((SELECT \"a\".\"k\" FROM \"public\".\"a\" \"a\") UNION ALL \
+             (SELECT \"b\".\"k\" FROM \"public\".\"b\" \"b\"))"
+        );
+
+        let snap = build_snapshot_sql(&intersect(a.clone(), b.clone(), false));
+        assert_sql_contains(&snap, ") INTERSECT (");
+        let snap = build_snapshot_sql(&except(a, b, true));
+        assert_sql_contains(&snap, ") EXCEPT ALL (");
+    }
+
+    #[test]
+    fn test_snapshot_grouping_sets() {
+        let child = scan(1, "t", "public", "t", &["a", "b", "v"]);
+        let node = grouping_sets(
+            vec![colref("a"), colref("b")],
+            vec![vec![0, 1], vec![0], vec![]],
+            vec![sum_col("v", "total")],
+            vec![crate::dvm::parser::GroupingFuncExpr {
+                args: vec![0, 1],
+                alias: "g".to_string(),
+            }],
+            child,
+        );
+        let snap = build_snapshot_sql(&node);
+        assert_sql_contains(&snap, "GROUPING(a, b) AS \"g\"");
+        assert_sql_contains(&snap, "GROUP BY GROUPING SETS ((a, b), (a), ())");
+        assert_sql_contains(&snap, "AS \"total\"");
+    }
+
+    #[test]
+    fn test_snapshot_lateral_subquery_inlines_outer_alias() {
+        let child = scan(1, "t", "public", "t", &["id"]);
+        let node = lateral_subquery(
+            "SELECT max(v) AS m FROM public.s WHERE s.tid = t.id",
+            "l",
+            vec![],
+            vec!["m"],
+            false,
+            vec![2],
+            child,
+        );
+        let snap = build_snapshot_sql(&node);
+        assert_sql_contains(
+            &snap,
+            "FROM \"public\".\"t\" \"t\" CROSS JOIN LATERAL (SELECT max(v)",
+        );
+        assert_sql_contains(&snap, "\"l\".\"m\"");
+    }
+
+    #[test]
+    fn test_snapshot_recursive_cte_keeps_self_ref_unwrapped() {
+        let base = scan(1, "nodes", "public", "n", &["id", "parent"]);
+        let base = filter(binop("IS", qcolref("n", "parent"), lit("NULL")), base);
+        let base = project(vec![qcolref("n", "id")], vec!["id"], base);
+        let nodes = scan(1, "nodes", "public", "c", &["id", "parent"]);
+        let self_ref = OpTree::RecursiveSelfRef {
+            cte_name: "tree".to_string(),
+            alias: "t".to_string(),
+            columns: vec!["id".to_string()],
+        };
+        let rec = project(
+            vec![qcolref("c", "id")],
+            vec!["id"],
+            inner_join(eq_cond("c", "parent", "t", "id"), nodes, self_ref),
+        );
+        let node = OpTree::RecursiveCte {
+            alias: "tree".to_string(),
+            columns: vec!["id".to_string()],
+            base: Box::new(base),
+            recursive: Box::new(rec),
+            union_all: true,
+        };
+        let snap = build_snapshot_sql(&node);
+        assert!(snap.starts_with("(WITH RECURSIVE \"tree\" (\"id\") AS (SELECT"));
+        assert_sql_contains(&snap, "UNION ALL SELECT");
+        assert_sql_contains(&snap, "JOIN \"tree\" \"t\" ON");
+        assert!(snap.ends_with("SELECT \"id\" FROM \"tree\")"));
+    }
+
     // ── has_source_alias tests ──────────────────────────────────
 
     #[test]
@@ -12,6 +12,10 @@
 //!
 //! Row identity: `hash(child_row_columns || '/' || srf_result)` — content-based.
 //! This is stable as long as the same source row produces the same expanded values.
+//!
+//! `ROWS FROM (f1(...), f2(...))` and `JSON_TABLE(...)` use the same chain:
+//! their `func_sql` is re-evaluated as a whole, so zipping, NULL padding,
+//! ordinals and NESTED PATH expansion come from PostgreSQL itself.
 
 use crate::dvm::diff::{DiffContext, DiffResult, col_list, quote_ident};
 use crate::dvm::operators::scan::build_hash_expr;
@@ -115,10 +119,7 @@ pub fn diff_lateral_function(
         .map(|c| quote_ident(c))
         .collect::<Vec<_>>()
         .join(", ");
-    let srf_alias_clause = format!(
-        "{}{ordinality_clause} ({col_alias_list})",
-        quote_ident(alias),
-    );
+    let srf_alias_clause = format!("{} ({col_alias_list})", quote_ident(alias));
 
     // ── CTE 2: Re-expand SRF for deleted/updated source rows (DELETE) ──
     // Instead of reading from the stream table (which may not have
@@ -131,7 +132,7 @@ pub fn diff_lateral_function(
                 {child_col_refs_str},\n\
                 {srf_col_refs_str}\n\
          FROM {changed_sources_cte} AS {outer_alias_q},\n\
-              LATERAL {func_sql} AS {srf_alias_clause}\n\
+              LATERAL {func_sql}{ordinality_clause} AS {srf_alias_clause}\n\
          WHERE {outer_alias_q}.\"__pgt_action\" = 'D'",
     );
     ctx.add_cte(old_rows_cte.clone(), old_rows_sql);
@@ -146,7 +147,7 @@ pub fn diff_lateral_function(
                 {child_col_refs_str},\n\
                 {srf_col_refs_str}\n\
          FROM {changed_sources_cte} AS {outer_alias_q},\n\
-              LATERAL {func_sql} AS {srf_alias_clause}\n\
+              LATERAL {func_sql}{ordinality_clause} AS {srf_alias_clause}\n\
          WHERE {outer_alias_q}.\"__pgt_action\" = 'I'",
     );
     ctx.add_cte(expand_cte.clone(), expand_sql);
@@ -179,6 +180,7 @@ pub fn diff_lateral_function(
 mod tests {
     use super::*;
     use crate::dvm::operators::test_helpers::*;
+    use crate::dvm::parser::rows_from_function_columns;
 
     /// Build a LateralFunction node for tests.
     fn lateral_func(
@@ -297,6 +299,64 @@ mod tests {
         assert_sql_contains(&sql, "AS \"e\" (\"value\")");
     }
 
+    #[test]
+    fn test_diff_lateral_function_ordinality_precedes_alias() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let child = scan(1, "t", "public", "t", &["id", "arr"]);
+        let tree = lateral_func("unnest(t.arr)", "elem", vec!["val"], true, child);
+        let result = diff_lateral_function(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(
+            &sql,
+            "LATERAL unnest(t.arr) WITH ORDINALITY AS \"elem\" (\"val\", \"ordinality\")",
+        );
+    }
+
+    #[test]
+    fn test_diff_lateral_function_rows_from_zipped() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let child = scan(1, "t", "public", "t", &["id", "xs", "ys"]);
+        let tree = lateral_func(
+            "ROWS FROM (unnest(t.xs), unnest(t.ys)) WITH ORDINALITY",
+            "u",
+            vec!["x", "y", "pos"],
+            false,
+            child,
+        );
+        let result = diff_lateral_function(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_eq!(result.columns, vec!["id", "xs", "ys", "x", "y", "pos"]);
+        assert_sql_contains(
+            &sql,
+            "LATERAL ROWS FROM (unnest(t.xs), unnest(t.ys)) WITH ORDINALITY AS \"u\" (\"x\", \"y\", \"pos\")",
+        );
+        // The ordinal is part of the row identity, so equal zipped values
+        // at different positions stay distinct rows.
+        assert_sql_contains(&sql, "\"u\".\"pos\"::TEXT");
+    }
+
+    #[test]
+    fn test_diff_lateral_function_json_table_nested_columns() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let child = scan(1, "events", "public", "e", &["id", "doc"]);
+        let tree = lateral_func(
+            "JSON_TABLE(e.doc, '$.items[*]' COLUMNS (sku text PATH '$.sku', \
+             NESTED PATH '$.tags[*]' COLUMNS (tag text PATH '$')))",
+            "jt",
+            vec!["sku", "tag"],
+            false,
+            child,
+        );
+        let result = diff_lateral_function(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_eq!(result.columns, vec!["id", "doc", "sku", "tag"]);
+        assert_sql_contains(&sql, "NESTED PATH '$.tags[*]'");
+        assert_sql_contains(&sql, "AS \"jt\" (\"sku\", \"tag\")");
+    }
+
     #[test]
     fn test_diff_lateral_function_old_rows_reexpands_with_delete_action() {
         let mut ctx = test_ctx_with_st("public", "my_st");
@@ -444,6 +504,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rows_from_function_columns_defaults() {
+        assert_eq!(rows_from_function_columns("unnest(t.xs)"), vec!["unnest"]);
+        assert_eq!(
+            rows_from_function_columns("pg_catalog.generate_series(1, 3)"),
+            vec!["generate_series"],
+        );
+        assert_eq!(
+            rows_from_function_columns("jsonb_each(t.props)"),
+            vec!["key", "value"],
+        );
+    }
+
     #[test]
     fn test_lateral_function_source_oids() {
         let child = scan(42, "t", "public", "t", &["id", "data"]);
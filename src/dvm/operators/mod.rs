@@ -10,6 +10,7 @@ pub mod distinct;
 pub mod except;
 pub mod filter;
 pub mod full_join;
+pub mod grouping_sets;
 pub mod intersect;
 pub mod join;
 pub mod join_common;
@@ -75,7 +75,9 @@ pub fn diff_project(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, Pg
     // the ST should store NULL.  We communicate this via agg_sum_coalesce_defaults.
     let saved_coalesce_defaults = ctx.agg_sum_coalesce_defaults.clone();
     for (expr, alias) in expressions.iter().zip(aliases.iter()) {
-        if let crate::dvm::parser::Expr::FuncCall { func_name, args } = expr
+        if let crate::dvm::parser::Expr::FuncCall {
+            func_name, args, ..
+        } = expr
             && func_name.eq_ignore_ascii_case("coalesce")
             && args.len() >= 2
             && let (
@@ -344,23 +346,33 @@ fn resolve_expr_to_child(expr: &Expr, child_cols: &[String]) -> String {
                 }
             }
         }
-        Expr::BinaryOp { op, left, right } => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } => {
             let l = resolve_expr_to_child(left, child_cols);
             let r = resolve_expr_to_child(right, child_cols);
             format!("({l} {op} {r})")
         }
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name, args, ..
+        } => {
             let resolved_args: Vec<String> = args
                 .iter()
                 .map(|a| resolve_expr_to_child(a, child_cols))
                 .collect();
             format!("{}({})", func_name, resolved_args.join(", "))
         }
+        Expr::SubLink { .. } => {
+            crate::dvm::operators::filter::replace_column_refs_in_raw(&expr.to_sql(), child_cols)
+        }
         Expr::Raw(sql) => {
             // Best-effort: replace column refs in raw SQL
             crate::dvm::operators::filter::replace_column_refs_in_raw(sql, child_cols)
         }
-        _ => expr.to_sql(),
+        Expr::Literal(_) | Expr::Star { .. } => expr.to_sql(),
+        _ => expr
+            .map_children(|c| Expr::Raw(resolve_expr_to_child(c, child_cols)))
+            .to_sql(),
     }
 }
 
@@ -368,6 +380,7 @@ fn resolve_expr_to_child(expr: &Expr, child_cols: &[String]) -> String {
 mod tests {
     use super::*;
     use crate::dvm::operators::test_helpers::*;
+    use crate::dvm::parser::Resolved;
 
     #[test]
     fn test_diff_project_basic_columns() {
@@ -463,6 +476,7 @@ mod tests {
         let expr = Expr::FuncCall {
             func_name: "upper".to_string(),
             args: vec![colref("x")],
+            resolved: Resolved::default(),
         };
         let result = resolve_expr_to_child(&expr, &child_cols);
         assert_eq!(result, "upper(\"x\")");
@@ -2063,7 +2063,9 @@ pub fn recursive_term_is_non_monotone(op: &OpTree) -> Option<String> {
     match op {
         // Non-monotone operators: return immediately with reason
         OpTree::Except { .. } => Some("EXCEPT".into()),
-        OpTree::Aggregate { .. } => Some("GROUP BY / aggregate".into()),
+        OpTree::Aggregate { .. } | OpTree::GroupingSets { .. } => {
+            Some("GROUP BY / aggregate".into())
+        }
         OpTree::Window { .. } => Some("window function".into()),
         OpTree::Distinct { .. } => Some("DISTINCT".into()),
         OpTree::Intersect { all: false, .. } => Some("INTERSECT (set)".into()),
@@ -2398,7 +2400,7 @@ fn generate_nonlinear_seeds(
 mod tests {
     use super::*;
     use crate::dvm::operators::test_helpers::test_ctx;
-    use crate::dvm::parser::{Column, Expr, OpTree};
+    use crate::dvm::parser::{Column, Expr, OpTree, Resolved};
 
     fn make_column(name: &str) -> Column {
         Column {
@@ -2469,6 +2471,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -2578,6 +2581,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -2607,6 +2611,7 @@ mod tests {
                     table_alias: Some("r".to_string()),
                     column_name: "to_node".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan),
             right: Box::new(self_ref),
@@ -2637,6 +2642,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan),
             right: Box::new(self_ref),
@@ -2760,6 +2766,7 @@ mod tests {
                     table_alias: Some("r2".to_string()),
                     column_name: "src".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(r1),
             right: Box::new(r2),
@@ -2821,6 +2828,7 @@ mod tests {
                     table_alias: Some("r2".to_string()),
                     column_name: "src".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(r1),
             right: Box::new(r2),
@@ -2879,6 +2887,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -2966,6 +2975,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -3006,6 +3016,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -3055,6 +3066,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -3099,6 +3111,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -3131,6 +3144,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -3277,6 +3291,7 @@ mod tests {
                     table_alias: Some("r".to_string()),
                     column_name: "src".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan),
             right: Box::new(self_ref),
@@ -3437,6 +3452,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan),
             right: Box::new(self_ref),
@@ -3502,6 +3518,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan),
             right: Box::new(self_ref),
@@ -3592,6 +3609,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan),
             right: Box::new(self_ref),
@@ -3894,6 +3912,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(recursive_base),
             right: Box::new(self_ref),
@@ -4004,6 +4023,7 @@ mod tests {
                     table_alias: Some("t".to_string()),
                     column_name: "id".to_string(),
                 }),
+                resolved: Resolved::default(),
             },
             left: Box::new(recursive_base),
             right: Box::new(self_ref),
@@ -835,8 +835,8 @@ pub(crate) fn compute_varbit_key_cols_mask(
 ///
 /// A predicate is pushable when every column reference resolves to a column
 /// in `scan_columns` (identified by the scan's alias or unqualified), and
-/// the expression contains no `Raw` or `Star` nodes (which can't be
-/// reliably rewritten to use `old_`/`new_` column prefixes).
+/// the expression contains no `Raw`, `SubLink` or `Star` nodes (which can't
+/// be reliably rewritten to use `old_`/`new_` column prefixes).
 pub fn is_predicate_pushable_to_scan(
     expr: &Expr,
     scan_alias: &str,
@@ -859,7 +859,15 @@ pub fn is_predicate_pushable_to_scan(
         Expr::FuncCall { args, .. } => args
             .iter()
             .all(|a| is_predicate_pushable_to_scan(a, scan_alias, scan_col_names)),
-        Expr::Raw(_) | Expr::Star { .. } => false,
+        Expr::UnaryOp { .. }
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Array(_)
+        | Expr::Row(_) => expr
+            .children()
+            .into_iter()
+            .all(|c| is_predicate_pushable_to_scan(c, scan_alias, scan_col_names)),
+        Expr::Raw(_) | Expr::SubLink { .. } | Expr::Star { .. } => false,
     }
 }
 
@@ -878,20 +886,31 @@ pub fn rewrite_predicate_for_scan(expr: &Expr, prefix: &str) -> String {
             format!("c.\"{}{}\"", prefix, cb_name.replace('"', "\"\""))
         }
         Expr::Literal(val) => val.clone(),
-        Expr::BinaryOp { op, left, right } => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } => {
             format!(
                 "({} {op} {})",
                 rewrite_predicate_for_scan(left, prefix),
                 rewrite_predicate_for_scan(right, prefix),
             )
         }
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name, args, ..
+        } => {
             let rewritten: Vec<String> = args
                 .iter()
                 .map(|a| rewrite_predicate_for_scan(a, prefix))
                 .collect();
             format!("{func_name}({})", rewritten.join(", "))
         }
+        Expr::UnaryOp { .. }
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Array(_)
+        | Expr::Row(_) => expr
+            .map_children(|c| Expr::Raw(rewrite_predicate_for_scan(c, prefix)))
+            .to_sql(),
         _ => expr.to_sql(),
     }
 }
@@ -917,6 +936,7 @@ pub fn build_hash_expr(exprs: &[String]) -> String {
 mod tests {
     use super::*;
     use crate::dvm::operators::test_helpers::*;
+    use crate::dvm::parser::Resolved;
 
     // ── diff_scan basic ─────────────────────────────────────────────
 
@@ -1498,6 +1518,7 @@ mod tests {
                 column_name: "status".into(),
             }),
             right: Box::new(Expr::Literal("'shipped'".into())),
+            resolved: Resolved::default(),
         };
         assert!(is_predicate_pushable_to_scan(&expr, "o", &cols));
     }
@@ -1512,6 +1533,7 @@ mod tests {
                 column_name: "amount".into(),
             }),
             right: Box::new(Expr::Literal("100".into())),
+            resolved: Resolved::default(),
         };
         assert!(is_predicate_pushable_to_scan(&expr, "o", &cols));
     }
@@ -1542,6 +1564,7 @@ mod tests {
                 column_name: "status".into(),
             }),
             right: Box::new(Expr::Literal("'shipped'".into())),
+            resolved: Resolved::default(),
         };
         let sql = rewrite_predicate_for_scan(&expr, "old_");
         assert_eq!(sql, "(c.\"old_status\" = 'shipped')");
@@ -1558,6 +1581,7 @@ mod tests {
                     column_name: "status".into(),
                 }),
                 right: Box::new(Expr::Literal("'shipped'".into())),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::BinaryOp {
                 op: ">".into(),
@@ -1566,7 +1590,9 @@ mod tests {
                     column_name: "amount".into(),
                 }),
                 right: Box::new(Expr::Literal("100".into())),
+                resolved: Resolved::default(),
             }),
+            resolved: Resolved::default(),
         };
         let sql = rewrite_predicate_for_scan(&expr, "new_");
         assert_eq!(
@@ -1586,6 +1612,7 @@ mod tests {
                 column_name: "status".into(),
             }),
             right: Box::new(Expr::Literal("'shipped'".into())),
+            resolved: Resolved::default(),
         });
         let tree = scan_with_pk(100, "orders", "public", "o", &["id", "status"], &["id"]);
         let result = diff_scan(&mut ctx, &tree).unwrap();
@@ -5,7 +5,9 @@
 //! and never touch PostgreSQL.
 
 use crate::dvm::diff::DiffContext;
-use crate::dvm::parser::{AggExpr, AggFunc, Column, Expr, OpTree, SortExpr, WindowExpr};
+use crate::dvm::parser::{
+    AggExpr, AggFunc, Column, Expr, GroupingFuncExpr, OpTree, Resolved, SortExpr, WindowExpr,
+};
 use crate::version::Frontier;
 
 // ── DiffContext builder ─────────────────────────────────────────────────
@@ -111,6 +113,23 @@ pub fn aggregate(group_by: Vec<Expr>, aggregates: Vec<AggExpr>, child: OpTree) -
     }
 }
 
+/// Build a GroupingSets node.
+pub fn grouping_sets(
+    group_by: Vec<Expr>,
+    sets: Vec<Vec<usize>>,
+    aggregates: Vec<AggExpr>,
+    grouping_funcs: Vec<GroupingFuncExpr>,
+    child: OpTree,
+) -> OpTree {
+    OpTree::GroupingSets {
+        group_by,
+        sets,
+        aggregates,
+        grouping_funcs,
+        child: Box::new(child),
+    }
+}
+
 /// Build an InnerJoin node.
 pub fn inner_join(condition: Expr, left: OpTree, right: OpTree) -> OpTree {
     OpTree::InnerJoin {
@@ -291,6 +310,7 @@ pub fn binop(op: &str, left: Expr, right: Expr) -> Expr {
         op: op.to_string(),
         left: Box::new(left),
         right: Box::new(right),
+        resolved: Resolved::default(),
     }
 }
 
@@ -705,6 +725,7 @@ pub fn natural_join_cond(left: &OpTree, right: &OpTree) -> Expr {
                     table_alias: Some(right_alias.clone()),
                     column_name: col_name.clone(),
                 }),
+                resolved: Resolved::default(),
             });
         }
         parts
@@ -713,6 +734,7 @@ pub fn natural_join_cond(left: &OpTree, right: &OpTree) -> Expr {
                 op: "AND".into(),
                 left: Box::new(acc),
                 right: Box::new(part),
+                resolved: Resolved::default(),
             })
             .unwrap_or(Expr::Literal("TRUE".into()))
     }
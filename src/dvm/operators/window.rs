@@ -12,9 +12,26 @@
 //! 4. Reconstruct current input for changed partitions from ST + delta
 //! 5. Recompute window function on current input (emitted as 'I' actions)
 //! 6. Combine deletes + inserts into final delta
+//!
+//! WIN-SUFFIX (v0.49.0): Cumulative windows (`ROW_NUMBER()`, `COUNT()`,
+//! `SUM()` over one ORDER BY column with an `UNBOUNDED PRECEDING` ..
+//! `CURRENT ROW` frame) recompute only the *suffix* of each changed
+//! partition — the rows at or after the earliest changed ORDER BY key.
+//! The recomputed suffix is seeded from the stored window value of the
+//! last peer group before that key, so rows ahead of the change are never
+//! rewritten. A late insert into a long ledger partition then touches only
+//! the rows that follow it.
+//!
+//! TOPK-PART (v0.49.0): A Filter such as `rn <= 3` above the Window (per-group
+//! TopK, DISTINCT ON) means the ST stores only the leading rows of each
+//! partition, so ST + delta no longer reconstructs the full input. Inserts
+//! still work from the stored prefix; a partition that loses a stored row
+//! while holding `N` rows is refilled from the source instead. Any other
+//! predicate over the Window refills every changed partition.
 
 use crate::dvm::diff::{DiffContext, DiffResult, col_list, prefixed_col_list, quote_ident};
-use crate::dvm::parser::{AggFunc, OpTree, WindowExpr};
+use crate::dvm::operators::aggregate::child_to_from_sql;
+use crate::dvm::parser::{AggFunc, CteRegistry, Expr, OpTree, WindowExpr};
 use crate::error::PgTrickleError;
 
 /// Build a mapping from aggregate SQL expressions (uppercased) to their
@@ -127,6 +144,351 @@ fn render_window_sql(
     format!("{}({args_sql}) OVER ({over_clause})", w.func_name)
 }
 
+// ── Suffix recompute (WIN-SUFFIX) ──────────────────────────────────────
+
+/// How a cumulative window value is continued from a seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedKind {
+    /// `ROW_NUMBER()` — seed + position within the suffix.
+    RowNumber,
+    /// `COUNT(..)` — seed + running count within the suffix.
+    Count,
+    /// `SUM(x)` — seed + running sum within the suffix (NULL-aware).
+    Sum,
+}
+
+/// Plan for recomputing only the changed suffix of each partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SuffixPlan {
+    /// The single ORDER BY column (pass-through output name).
+    order_col: String,
+    ascending: bool,
+    nulls_first: bool,
+    /// Seed kind for each window expression, in `window_exprs` order.
+    kinds: Vec<SeedKind>,
+    /// A `SUM()` uses a ROWS frame. Peers of the seed row then hold
+    /// different running values, so a partition whose preceding peer group
+    /// has more than one row falls back to whole-partition recompute.
+    rows_sum: bool,
+}
+
+/// Whether a frame clause is cumulative (`UNBOUNDED PRECEDING` through
+/// `CURRENT ROW`). Returns `Some(true)` for ROWS frames, `Some(false)` for
+/// RANGE frames (including the default frame) and `None` otherwise.
+fn cumulative_frame_is_rows(frame: Option<&str>) -> Option<bool> {
+    let Some(frame) = frame else {
+        return Some(false);
+    };
+    let normalized = frame.split_whitespace().collect::<Vec<_>>().join(" ");
+    match normalized.to_uppercase().as_str() {
+        "RANGE UNBOUNDED PRECEDING" | "RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW" => {
+            Some(false)
+        }
+        "ROWS UNBOUNDED PRECEDING" | "ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW" => {
+            Some(true)
+        }
+        _ => None,
+    }
+}
+
+/// Decide whether a Window node can be maintained by suffix recompute.
+///
+/// Requires every window expression to be a seedable cumulative function
+/// over the node's PARTITION BY and the same single pass-through ORDER BY
+/// column, with its output stored in the ST. Window-over-Aggregate shapes
+/// (aggregate aliases or auxiliary columns) always recompute partitions.
+fn plan_suffix_recompute(
+    window_exprs: &[WindowExpr],
+    partition_cols: &[String],
+    pt_aliases: &[String],
+    has_aux_or_agg: bool,
+    st_stored_cols: Option<&Vec<String>>,
+) -> Option<SuffixPlan> {
+    if has_aux_or_agg || window_exprs.is_empty() {
+        return None;
+    }
+    let first = &window_exprs[0];
+    let [sort] = first.order_by.as_slice() else {
+        return None;
+    };
+    let Expr::ColumnRef { column_name, .. } = &sort.expr else {
+        return None;
+    };
+    let key_stored = st_stored_cols.is_none_or(|cols| cols.contains(column_name));
+    if !pt_aliases.contains(column_name) || !key_stored {
+        return None;
+    }
+
+    let mut kinds = Vec::with_capacity(window_exprs.len());
+    let mut rows_sum = false;
+    for w in window_exprs {
+        let same_order = matches!(
+            w.order_by.as_slice(),
+            [s] if matches!(&s.expr, Expr::ColumnRef { column_name: c, .. } if c == column_name)
+                && s.ascending == sort.ascending
+                && s.nulls_first == sort.nulls_first
+        );
+        let same_partition = w.partition_by.len() == partition_cols.len()
+            && w.partition_by
+                .iter()
+                .zip(partition_cols)
+                .all(|(e, c)| &e.output_name() == c);
+        let stored = st_stored_cols.is_none_or(|cols| cols.contains(&w.alias));
+        if !same_order || !same_partition || !stored {
+            return None;
+        }
+        let is_rows = cumulative_frame_is_rows(w.frame_clause.as_deref())?;
+        let kind = match (w.func_name.to_lowercase().as_str(), w.args.len()) {
+            ("row_number", 0) => SeedKind::RowNumber,
+            ("count", 0 | 1) => SeedKind::Count,
+            ("sum", 1) => SeedKind::Sum,
+            _ => return None,
+        };
+        rows_sum |= kind == SeedKind::Sum && is_rows;
+        kinds.push(kind);
+    }
+
+    Some(SuffixPlan {
+        order_col: column_name.clone(),
+        ascending: sort.ascending,
+        nulls_first: sort.nulls_first,
+        kinds,
+        rows_sum,
+    })
+}
+
+/// Predicate: `key` sorts at or after `bound` in the window order.
+///
+/// A NULL `bound` denotes the NULL peer group. The result is never NULL,
+/// so `NOT (...)` selects exactly the rows ordered before `bound`.
+fn order_at_or_after(key: &str, bound: &str, ascending: bool, nulls_first: bool) -> String {
+    let cmp = if ascending { ">=" } else { "<=" };
+    if nulls_first {
+        format!("({bound} IS NULL OR ({key} IS NOT NULL AND {key} {cmp} {bound}))")
+    } else {
+        format!(
+            "(CASE WHEN {bound} IS NULL THEN {key} IS NULL \
+             ELSE ({key} IS NULL OR {key} {cmp} {bound}) END)"
+        )
+    }
+}
+
+/// Aggregate returning the earliest `key` in the window order (NULL for
+/// the NULL peer group).
+fn order_earliest_key(key: &str, ascending: bool, nulls_first: bool) -> String {
+    let agg = if ascending { "MIN" } else { "MAX" };
+    if nulls_first {
+        format!("CASE WHEN bool_or({key} IS NULL) THEN NULL ELSE {agg}({key}) END")
+    } else {
+        format!("{agg}({key})")
+    }
+}
+
+/// ORDER BY suffix that walks the window order backwards.
+fn order_reversed(ascending: bool, nulls_first: bool) -> &'static str {
+    match (ascending, nulls_first) {
+        (true, false) => "DESC NULLS FIRST",
+        (true, true) => "DESC NULLS LAST",
+        (false, true) => "ASC NULLS LAST",
+        (false, false) => "ASC NULLS FIRST",
+    }
+}
+
+/// Continue a suffix-local window value `local` from `seed`.
+fn seeded_value(kind: SeedKind, seed: &str, local: &str) -> String {
+    match kind {
+        SeedKind::RowNumber | SeedKind::Count => format!("COALESCE({seed}, 0) + {local}"),
+        SeedKind::Sum => {
+            format!("CASE WHEN {seed} IS NULL THEN {local} ELSE {seed} + COALESCE({local}, 0) END")
+        }
+    }
+}
+
+/// Column name holding the seed for the `i`-th window expression.
+fn seed_col(i: usize) -> String {
+    format!("__pgt_seed_{i}")
+}
+
+// ── Partition-local TopK (TOPK-PART) ───────────────────────────────────
+
+/// How a Filter directly above a Window truncates each partition in the ST.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowTruncation {
+    /// `rn <= n` (also `rn < n + 1` and `rn = 1`) over a `ROW_NUMBER()`,
+    /// `RANK()` or `DENSE_RANK()` alias, with only ranking functions in the
+    /// Window. The ST holds a prefix of each partition in window order, and
+    /// a partition stored with fewer than `n` rows is complete.
+    TopN(i64),
+    /// Any other predicate: the ST holds an arbitrary subset of each
+    /// partition.
+    Subset,
+}
+
+/// Classify the predicate of a Filter whose child is a Window, possibly
+/// wrapped in a (non-renaming) Subquery.
+pub fn classify_window_filter(predicate: &Expr, child: &OpTree) -> WindowTruncation {
+    let mut node = child;
+    let window_exprs = loop {
+        match node {
+            OpTree::Subquery {
+                child,
+                column_aliases,
+                ..
+            } if column_aliases.is_empty() => node = child,
+            OpTree::Window { window_exprs, .. } => break window_exprs,
+            _ => return WindowTruncation::Subset,
+        }
+    };
+    let Expr::BinaryOp {
+        op, left, right, ..
+    } = predicate
+    else {
+        return WindowTruncation::Subset;
+    };
+    let (col, lit, op) = match (left.as_ref(), right.as_ref()) {
+        (Expr::ColumnRef { column_name, .. }, Expr::Literal(v)) => (column_name, v, op.as_str()),
+        (Expr::Literal(v), Expr::ColumnRef { column_name, .. }) => {
+            let flipped = match op.as_str() {
+                ">=" => "<=",
+                ">" => "<",
+                other => other,
+            };
+            (column_name, v, flipped)
+        }
+        _ => return WindowTruncation::Subset,
+    };
+    let is_ranking = |w: &WindowExpr| {
+        matches!(
+            w.func_name.to_lowercase().as_str(),
+            "row_number" | "rank" | "dense_rank"
+        )
+    };
+    if !window_exprs.iter().all(is_ranking) || !window_exprs.iter().any(|w| &w.alias == col) {
+        return WindowTruncation::Subset;
+    }
+    let Ok(v) = lit.trim().parse::<i64>() else {
+        return WindowTruncation::Subset;
+    };
+    let n = match op {
+        "<=" => v,
+        "<" => v - 1,
+        "=" if v == 1 => 1,
+        _ => return WindowTruncation::Subset,
+    };
+    if n >= 1 {
+        WindowTruncation::TopN(n)
+    } else {
+        WindowTruncation::Subset
+    }
+}
+
+/// `left.c IS NOT DISTINCT FROM right.c` for every partition column.
+fn partition_match(left: &str, right: &str, partition_cols: &[String]) -> String {
+    if partition_cols.is_empty() {
+        return "TRUE".to_string();
+    }
+    partition_cols
+        .iter()
+        .map(|c| {
+            let qc = quote_ident(c);
+            format!("{left}.{qc} IS NOT DISTINCT FROM {right}.{qc}")
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Partitions of a stored TopN prefix that must be refilled: those stored
+/// with at least `n` rows of which the delta removed or replaced one.
+/// Partitions stored with fewer rows were complete, so ST + delta still
+/// rebuilds them.
+fn topn_refill_parts_sql(
+    n: i64,
+    partition_cols: &[String],
+    old_rows_cte: &str,
+    keep_cte: &str,
+) -> String {
+    if partition_cols.is_empty() {
+        return format!(
+            "SELECT 1 AS __pgt_dummy\n\
+             FROM {old_rows_cte} o\n\
+             HAVING COUNT(*) >= {n}\n\
+             AND COUNT(*) > (SELECT COUNT(*) FROM {keep_cte} k)"
+        );
+    }
+    let pcols = prefixed_col_list("o", partition_cols);
+    format!(
+        "SELECT {pcols}\n\
+         FROM {old_rows_cte} o\n\
+         GROUP BY {pcols}\n\
+         HAVING COUNT(*) >= {n}\n\
+         AND COUNT(*) > (\n\
+             SELECT COUNT(*) FROM {keep_cte} k WHERE {keep_match}\n\
+         )",
+        keep_match = partition_match("k", "o", partition_cols),
+    )
+}
+
+/// FROM clause from which truncated partitions of a Window can be re-read,
+/// or `None` when the Window's input cannot be rebuilt from the source:
+/// Window-over-Aggregate, star pass-through, or a child that
+/// `child_to_from_sql` cannot express. A Filter over such a Window is
+/// rejected when a DIFFERENTIAL stream table is created (see
+/// `check_window_truncation_support`).
+pub(crate) fn window_refill_from(
+    child: &OpTree,
+    pass_through: &[(Expr, String)],
+    registry: &CteRegistry,
+) -> Option<String> {
+    if !build_agg_alias_map(child).is_empty()
+        || pass_through
+            .iter()
+            .any(|(e, _)| matches!(e, Expr::Star { .. }))
+    {
+        return None;
+    }
+    child_to_from_sql(child, registry)
+}
+
+/// Read the pass-through columns of the partitions listed in `parts_cte`
+/// from the Window's source FROM clause (as built by `child_to_from_sql`).
+/// The partition test sits outside the derived table so that unqualified
+/// PARTITION BY columns cannot bind to `parts_cte`.
+fn window_refill_source_sql(
+    from_sql: &str,
+    pass_through: &[(Expr, String)],
+    partition_cols: &[String],
+    parts_cte: &str,
+) -> String {
+    let selects = pass_through
+        .iter()
+        .map(|(e, alias)| format!("{} AS {}", e.to_sql(), quote_ident(alias)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let pt_aliases: Vec<String> = pass_through.iter().map(|(_, a)| a.clone()).collect();
+    format!(
+        "-- Partitions refilled from the source\n\
+         SELECT {pt_cols_src}\n\
+         FROM (SELECT {selects} FROM {from_sql}) src\n\
+         WHERE EXISTS (\n\
+             SELECT 1 FROM {parts_cte} __pgt_rf WHERE {in_parts}\n\
+         )",
+        pt_cols_src = prefixed_col_list("src", &pt_aliases),
+        in_parts = partition_match("src", "__pgt_rf", partition_cols),
+    )
+}
+
+/// Error for a Filter over a Window whose truncated partitions cannot be
+/// re-read from the source.
+pub(crate) fn truncated_window_unsupported() -> PgTrickleError {
+    PgTrickleError::UnsupportedOperator(
+        "a filter on window function output (e.g. WHERE rn <= N) requires the \
+         window input to be re-readable from its source tables; window functions \
+         over GROUP BY results, SELECT * pass-through and set operations are not \
+         supported for DIFFERENTIAL mode"
+            .into(),
+    )
+}
+
 /// Differentiate a Window node.
 pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgTrickleError> {
     let OpTree::Window {
@@ -141,6 +503,11 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
         ));
     };
 
+    // TOPK-PART: take the truncation set by a Filter directly above this
+    // Window before differentiating the child, so nested Windows never
+    // see it.
+    let truncation = ctx.window_truncation.take();
+
     // ── Differentiate child to get the delta ───────────────────────────
     let child_result = ctx.diff_node(child)?;
 
@@ -183,9 +550,94 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
     // form from the source query.
     let partition_cols: Vec<String> = partition_by.iter().map(|e| e.output_name()).collect();
 
+    // Build aggregate alias map for Window-over-Aggregate queries.
+    // When the child is an Aggregate, ORDER BY expressions like SUM(val)
+    // must be rewritten to their output aliases (e.g., "dept_total").
+    let agg_map = build_agg_alias_map(child);
+
+    // Match surviving old rows against child delta rows using the most
+    // stable child key we can recover. This is critical for Window over
+    // Aggregate: aggregate updates arrive as replacement rows keyed by the
+    // GROUP BY columns, not necessarily as full-row DELETE+INSERT pairs.
+    // When key columns are available, ANY child delta row for the same key
+    // should replace the previous input row.
+    let child_key_cols = child.row_id_key_columns().and_then(|keys| {
+        if !keys.is_empty() && keys.iter().all(|key| pt_aliases.contains(key)) {
+            Some(keys)
+        } else {
+            None
+        }
+    });
+
+    // TOPK-PART: when the ST holds only part of each partition, read the
+    // partitions that ST + delta cannot rebuild back from the source.
+    // Creation rejects a Window whose input cannot be re-read; stream
+    // tables created before that check keep the ST + delta recompute.
+    let refill_from = truncation.as_ref().and_then(|_| {
+        window_refill_from(child, pass_through, &ctx.cte_registry).filter(|_| aux_cols.is_empty())
+    });
+
+    // WIN-SUFFIX: recompute only the changed suffix of each partition for
+    // cumulative windows.
+    let suffix_plan =
+        if refill_from.is_none() && crate::config::pg_trickle_window_suffix_recompute() {
+            plan_suffix_recompute(
+                window_exprs,
+                &partition_cols,
+                &pt_aliases,
+                !aux_cols.is_empty() || !agg_map.is_empty(),
+                st_stored_cols.as_ref(),
+            )
+        } else {
+            None
+        };
+
     // ── CTE 1: Find changed partition keys ─────────────────────────────
     let changed_parts_cte = ctx.next_cte_name("win_parts");
-    if partition_cols.is_empty() {
+    if let Some(plan) = &suffix_plan {
+        // One row per changed partition with the earliest changed ORDER BY
+        // key. Keys come from the child delta (old values on 'D', new values
+        // on 'I') and, when the child is keyed, from the stored rows that a
+        // delta row replaces.
+        let key = quote_ident(&plan.order_col);
+        let mut source_cols = partition_cols.clone();
+        source_cols.push(plan.order_col.clone());
+        let source_list = col_list(&source_cols);
+        let mut sources = vec![format!(
+            "SELECT {source_list} FROM {child}",
+            child = child_result.cte_name
+        )];
+        if let Some(keys) = &child_key_cols {
+            let key_cond = keys
+                .iter()
+                .map(|c| {
+                    let qc = quote_ident(c);
+                    format!("d2.{qc} IS NOT DISTINCT FROM st.{qc}")
+                })
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            sources.push(format!(
+                "SELECT {st_list} FROM {st_table} st\n\
+                 WHERE EXISTS (SELECT 1 FROM {child} d2 WHERE {key_cond})",
+                st_list = prefixed_col_list("st", &source_cols),
+                child = child_result.cte_name,
+            ));
+        }
+        let earliest = order_earliest_key(&key, plan.ascending, plan.nulls_first);
+        let (part_select, group_by) = if partition_cols.is_empty() {
+            (String::new(), String::new())
+        } else {
+            let pcols = col_list(&partition_cols);
+            (format!("{pcols}, "), format!("\nGROUP BY {pcols}"))
+        };
+        let parts_sql = format!(
+            "SELECT {part_select}{earliest} AS __pgt_wstart\n\
+             FROM (\n{sources}\n) c{group_by}\n\
+             HAVING COUNT(*) > 0",
+            sources = sources.join("\nUNION ALL\n"),
+        );
+        ctx.add_cte(changed_parts_cte.clone(), parts_sql);
+    } else if partition_cols.is_empty() {
         // Un-partitioned: any change means recompute everything.
         // Emit a single dummy row to trigger recomputation.
         let parts_sql = format!(
@@ -260,13 +712,92 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
         .collect::<Vec<_>>()
         .join(", ");
 
-    let old_rows_sql = format!(
-        "SELECT st.\"__pgt_row_id\", {all_cols_st}\n\
-         FROM {st_table} st\n\
-         WHERE EXISTS (\n\
-         SELECT 1 FROM {changed_parts_cte} cp WHERE {partition_join_st_cp}\n\
-         )",
-    );
+    // WIN-SUFFIX: per changed partition, look up the last peer group
+    // ordered before the earliest changed key and take its stored window
+    // values as seeds. The suffix scope (`__pgt_wstart`, `__pgt_whole`)
+    // then limits which stored rows are replaced.
+    let suffix_scope_cte = suffix_plan.as_ref().map(|plan| {
+        let seed_cte = ctx.next_cte_name("win_seed");
+        let key = format!("st.{}", quote_ident(&plan.order_col));
+        let before = format!(
+            "NOT {}",
+            order_at_or_after(&key, "cp.__pgt_wstart", plan.ascending, plan.nulls_first)
+        );
+        let whole = if plan.rows_sum {
+            "COALESCE(pv.__pgt_prev_cnt > 1, FALSE)"
+        } else {
+            "FALSE"
+        };
+        let seed_selects: String = window_exprs
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                format!(
+                    ",\n       CASE WHEN {whole} THEN NULL ELSE pv.{alias} END AS {seed}",
+                    alias = quote_ident(&w.alias),
+                    seed = seed_col(i),
+                )
+            })
+            .collect();
+        let prev_aggs: String = window_exprs
+            .iter()
+            .map(|w| {
+                let qa = quote_ident(&w.alias);
+                format!(", MAX(st.{qa}) AS {qa}")
+            })
+            .collect();
+        let cp_part_cols = if partition_cols.is_empty() {
+            String::new()
+        } else {
+            format!("{}, ", prefixed_col_list("cp", &partition_cols))
+        };
+        let seed_sql = format!(
+            "SELECT {cp_part_cols}cp.__pgt_wstart,\n       \
+             {whole} AS __pgt_whole{seed_selects}\n\
+             FROM {changed_parts_cte} cp\n\
+             LEFT JOIN LATERAL (\n\
+                 SELECT TRUE AS __pgt_has_prev, {key} AS __pgt_prev_key\n\
+                 FROM {st_table} st\n\
+                 WHERE {partition_join_st_cp} AND {before}\n\
+                 ORDER BY {key} {rev}\n\
+                 LIMIT 1\n\
+             ) pk ON TRUE\n\
+             LEFT JOIN LATERAL (\n\
+                 SELECT COUNT(*) AS __pgt_prev_cnt{prev_aggs}\n\
+                 FROM {st_table} st\n\
+                 WHERE pk.__pgt_has_prev AND {partition_join_st_cp}\n\
+                   AND {key} IS NOT DISTINCT FROM pk.__pgt_prev_key\n\
+             ) pv ON TRUE",
+            rev = order_reversed(plan.ascending, plan.nulls_first),
+        );
+        ctx.add_cte(seed_cte.clone(), seed_sql);
+        seed_cte
+    });
+
+    let old_rows_sql = if let (Some(plan), Some(scope_cte)) = (&suffix_plan, &suffix_scope_cte) {
+        let in_suffix = order_at_or_after(
+            &format!("st.{}", quote_ident(&plan.order_col)),
+            "cp.__pgt_wstart",
+            plan.ascending,
+            plan.nulls_first,
+        );
+        format!(
+            "SELECT st.\"__pgt_row_id\", {all_cols_st}\n\
+             FROM {st_table} st\n\
+             WHERE EXISTS (\n\
+             SELECT 1 FROM {scope_cte} cp WHERE {partition_join_st_cp}\n\
+             AND (cp.__pgt_whole OR {in_suffix})\n\
+             )",
+        )
+    } else {
+        format!(
+            "SELECT st.\"__pgt_row_id\", {all_cols_st}\n\
+             FROM {st_table} st\n\
+             WHERE EXISTS (\n\
+             SELECT 1 FROM {changed_parts_cte} cp WHERE {partition_join_st_cp}\n\
+             )",
+        )
+    };
     ctx.add_cte(old_rows_cte.clone(), old_rows_sql);
 
     // ── CTE 3: Reconstruct current input for changed partitions ────────
@@ -311,19 +842,6 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
             parts.join("")
         };
 
-        // Match surviving old rows against child delta rows using the most
-        // stable child key we can recover. This is critical for Window over
-        // Aggregate: aggregate updates arrive as replacement rows keyed by the
-        // GROUP BY columns, not necessarily as full-row DELETE+INSERT pairs.
-        // When key columns are available, ANY child delta row for the same key
-        // should replace the previous input row.
-        let child_key_cols = child.row_id_key_columns().and_then(|keys| {
-            if !keys.is_empty() && keys.iter().all(|key| pt_aliases.contains(key)) {
-                Some(keys)
-            } else {
-                None
-            }
-        });
         let key_match_cond = child_key_cols.as_ref().map(|keys| {
             keys.iter()
                 .map(|c| {
@@ -351,7 +869,18 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
                 .join(" AND ")
         };
 
-        if let Some(key_cond) = key_match_cond {
+        let inserts_sql = format!(
+            "-- Newly inserted rows\n\
+             SELECT {pt_cols_delta}{aux_delta}\n\
+             FROM {child_delta} d\n\
+             WHERE d.\"__pgt_action\" = 'I'\n\
+             AND EXISTS (\n\
+                 SELECT 1 FROM {changed_parts_cte} cp WHERE {partition_join_delta_cp}\n\
+             )",
+            child_delta = child_result.cte_name,
+        );
+
+        let survivors_sql = if let Some(key_cond) = key_match_cond {
             // Key-based matching: keys are unique, simple NOT EXISTS is safe.
             format!(
                 "-- Surviving old rows (key-matched exclusion)\n\
@@ -360,14 +889,6 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
                  WHERE NOT EXISTS (\n\
                      SELECT 1 FROM {child_delta} d2\n\
                      WHERE {key_cond}\n\
-                 )\n\
-                 UNION ALL\n\
-                 -- Newly inserted rows\n\
-                 SELECT {pt_cols_delta}{aux_delta}\n\
-                 FROM {child_delta} d\n\
-                 WHERE d.\"__pgt_action\" = 'I'\n\
-                 AND EXISTS (\n\
-                     SELECT 1 FROM {changed_parts_cte} cp WHERE {partition_join_delta_cp}\n\
                  )",
                 child_delta = child_result.cte_name,
             )
@@ -428,17 +949,58 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
                          WHERE _xd.\"__pgt_action\" = 'D'\n\
                      ) d2\n\
                      WHERE {delete_match_cond} AND d2.__pgt_xrn = o.__pgt_xrn\n\
-                 )\n\
-                 UNION ALL\n\
-                 -- Newly inserted rows\n\
-                 SELECT {pt_cols_delta}{aux_delta}\n\
-                 FROM {child_delta} d\n\
-                 WHERE d.\"__pgt_action\" = 'I'\n\
-                 AND EXISTS (\n\
-                     SELECT 1 FROM {changed_parts_cte} cp WHERE {partition_join_delta_cp}\n\
                  )",
                 child_delta = child_result.cte_name,
             )
+        };
+
+        // TOPK-PART: rebuild from the source the partitions that ST + delta
+        // cannot reconstruct. For a stored prefix (`TopN`) that is a full
+        // partition that lost a stored row; for any other truncation it is
+        // every changed partition.
+        match (&truncation, &refill_from) {
+            (Some(WindowTruncation::TopN(n)), Some(from_sql)) => {
+                let keep_cte = ctx.next_cte_name("win_keep");
+                ctx.add_cte(keep_cte.clone(), survivors_sql);
+                let refill_cte = ctx.next_cte_name("win_refill");
+                ctx.add_cte(
+                    refill_cte.clone(),
+                    topn_refill_parts_sql(*n, &partition_cols, &old_rows_cte, &keep_cte),
+                );
+                let not_refilled = |prefix: &str| {
+                    format!(
+                        "NOT EXISTS (\n    SELECT 1 FROM {refill_cte} __pgt_rf WHERE {}\n)",
+                        partition_match(prefix, "__pgt_rf", &partition_cols)
+                    )
+                };
+                format!(
+                    "-- Surviving old rows of partitions that keep their stored prefix\n\
+                     SELECT {pt_cols_k}\n\
+                     FROM {keep_cte} k\n\
+                     WHERE {keep_guard}\n\
+                     UNION ALL\n\
+                     {inserts_sql}\n\
+                     AND {insert_guard}\n\
+                     UNION ALL\n\
+                     {source}",
+                    pt_cols_k = prefixed_col_list("k", &pt_aliases),
+                    keep_guard = not_refilled("k"),
+                    insert_guard = not_refilled("d"),
+                    source = window_refill_source_sql(
+                        from_sql,
+                        pass_through,
+                        &partition_cols,
+                        &refill_cte
+                    ),
+                )
+            }
+            (Some(WindowTruncation::Subset), Some(from_sql)) => window_refill_source_sql(
+                from_sql,
+                pass_through,
+                &partition_cols,
+                &changed_parts_cte,
+            ),
+            _ => format!("{survivors_sql}\nUNION ALL\n{inserts_sql}"),
         }
     };
     ctx.add_cte(current_input_cte.clone(), current_input_sql);
@@ -446,11 +1008,6 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
     // ── CTE 4: Recompute window functions on current input ─────────────
     let recomputed_cte = ctx.next_cte_name("win_recomp");
 
-    // Build aggregate alias map for Window-over-Aggregate queries.
-    // When the child is an Aggregate, ORDER BY expressions like SUM(val)
-    // must be rewritten to their output aliases (e.g., "dept_total").
-    let agg_map = build_agg_alias_map(child);
-
     let window_func_selects: Vec<String> = window_exprs
         .iter()
         .map(|w| {
@@ -482,15 +1039,64 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
         parts.join("")
     };
 
+    let wf_selects = window_func_selects.join(",\n       ");
+    let recompute_source = if let (Some(plan), Some(scope_cte)) = (&suffix_plan, &suffix_scope_cte)
+    {
+        // WIN-SUFFIX: window values computed over the suffix alone restart
+        // at the suffix boundary; continue them from the partition's seed.
+        // The suffix key is a pass-through column, so recomputed rows never
+        // collide in content (and thus row id) with the untouched prefix.
+        let seeded: Vec<String> = window_exprs
+            .iter()
+            .zip(&plan.kinds)
+            .enumerate()
+            .map(|(i, (w, kind))| {
+                let qa = quote_ident(&w.alias);
+                format!(
+                    "{} AS {qa}",
+                    seeded_value(*kind, &format!("sd.{}", seed_col(i)), &format!("r.{qa}"))
+                )
+            })
+            .collect();
+        let seed_join = if partition_cols.is_empty() {
+            "TRUE".to_string()
+        } else {
+            partition_cols
+                .iter()
+                .map(|c| {
+                    let qc = quote_ident(c);
+                    format!("r.{qc} IS NOT DISTINCT FROM sd.{qc}")
+                })
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        };
+        format!(
+            "SELECT {pt_cols_r},\n\
+                    {seeded}\n\
+             FROM (\n\
+                   SELECT {pt_cols_ci},\n\
+                          {wf_selects}\n\
+                   FROM {current_input_cte} ci\n\
+             ) r\n\
+             JOIN {scope_cte} sd ON {seed_join}",
+            pt_cols_r = prefixed_col_list("r", &pt_aliases),
+            seeded = seeded.join(",\n       "),
+        )
+    } else {
+        format!(
+            "SELECT {pt_cols_ci},\n\
+                    {wf_selects}{aux_ci}\n\
+             FROM {current_input_cte} ci"
+        )
+    };
+
     let recomputed_sql = format!(
         "SELECT pgtrickle.pg_trickle_hash(\
                row_to_json(w)::text || '/' || row_number() OVER ()::text\
          ) AS \"__pgt_row_id\",\n\
                {all_cols_w}{aux_w}\n\
          FROM (\n\
-               SELECT {pt_cols_ci},\n\
-                      {wf_selects}{aux_ci}\n\
-               FROM {current_input_cte} ci\n\
+               {recompute_source}\n\
          ) w",
         all_cols_w = all_output_cols
             .iter()
@@ -507,7 +1113,6 @@ pub fn diff_window(ctx: &mut DiffContext, op: &OpTree) -> Result<DiffResult, PgT
                 .collect::<Vec<String>>()
                 .join("")
         },
-        wf_selects = window_func_selects.join(",\n       "),
     );
     ctx.add_cte(recomputed_cte.clone(), recomputed_sql);
 
@@ -714,4 +1319,377 @@ mod tests {
         assert_sql_contains(&sql, "DELETE");
         assert_sql_contains(&sql, "INSERT");
     }
+
+    // ── WIN-SUFFIX: suffix recompute for cumulative windows ─────────────
+
+    fn ledger_window(func: &str, args: Vec<Expr>, frame: Option<&str>) -> OpTree {
+        let child = scan(1, "ledger", "public", "l", &["id", "acct", "ts", "amount"]);
+        let mut wf = window_expr(
+            func,
+            args,
+            vec![colref("acct")],
+            vec![sort_asc(colref("ts"))],
+            "running",
+        );
+        wf.frame_clause = frame.map(str::to_string);
+        window(
+            vec![wf],
+            vec![colref("acct")],
+            vec![
+                (colref("id"), "id".to_string()),
+                (colref("acct"), "acct".to_string()),
+                (colref("ts"), "ts".to_string()),
+                (colref("amount"), "amount".to_string()),
+            ],
+            child,
+        )
+    }
+
+    #[test]
+    fn test_diff_window_running_sum_recomputes_suffix() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = ledger_window("sum", vec![colref("amount")], None);
+        let result = diff_window(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        // Earliest changed key per partition, and a seed looked up from the
+        // last peer group before it.
+        assert_sql_contains(&sql, "MIN(\"ts\") AS __pgt_wstart");
+        assert_sql_contains(&sql, "GROUP BY \"acct\"");
+        assert_sql_contains(&sql, "ORDER BY st.\"ts\" DESC NULLS FIRST");
+        assert_sql_contains(&sql, "AS __pgt_seed_0");
+        // Only suffix rows are replaced.
+        assert_sql_contains(&sql, "cp.__pgt_whole OR");
+        // RANGE frame: peers share a value, so no whole-partition fallback.
+        assert_sql_contains(&sql, "FALSE AS __pgt_whole");
+        assert_sql_contains(
+            &sql,
+            "CASE WHEN sd.__pgt_seed_0 IS NULL THEN r.\"running\" \
+             ELSE sd.__pgt_seed_0 + COALESCE(r.\"running\", 0) END",
+        );
+    }
+
+    #[test]
+    fn test_diff_window_rows_sum_falls_back_on_tied_seed() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = ledger_window(
+            "sum",
+            vec![colref("amount")],
+            Some("ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW"),
+        );
+        let result = diff_window(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(
+            &sql,
+            "COALESCE(pv.__pgt_prev_cnt > 1, FALSE) AS __pgt_whole",
+        );
+    }
+
+    #[test]
+    fn test_diff_window_row_number_suffix_seeded_from_count() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = ledger_window("row_number", vec![], None);
+        let result = diff_window(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(&sql, "COALESCE(sd.__pgt_seed_0, 0) + r.\"running\"");
+        assert!(result.columns.contains(&"running".to_string()));
+    }
+
+    #[test]
+    fn test_diff_window_non_cumulative_frame_recomputes_partition() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = ledger_window(
+            "sum",
+            vec![colref("amount")],
+            Some("ROWS BETWEEN 3 PRECEDING AND CURRENT ROW"),
+        );
+        let result = diff_window(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert!(
+            !sql.contains("__pgt_wstart"),
+            "unexpected suffix plan: {sql}"
+        );
+        assert_sql_contains(&sql, "DISTINCT");
+    }
+
+    #[test]
+    fn test_plan_suffix_recompute_rejects_rank_and_missing_st_column() {
+        let partition = vec!["acct".to_string()];
+        let pt = vec!["acct".to_string(), "ts".to_string()];
+        let rank = window_expr(
+            "rank",
+            vec![],
+            vec![colref("acct")],
+            vec![sort_asc(colref("ts"))],
+            "rk",
+        );
+        assert!(plan_suffix_recompute(&[rank], &partition, &pt, false, None).is_none());
+
+        let rn = window_expr(
+            "row_number",
+            vec![],
+            vec![colref("acct")],
+            vec![sort_asc(colref("ts"))],
+            "rn",
+        );
+        let stored = vec!["acct".to_string(), "ts".to_string()];
+        assert!(
+            plan_suffix_recompute(
+                std::slice::from_ref(&rn),
+                &partition,
+                &pt,
+                false,
+                Some(&stored)
+            )
+            .is_none()
+        );
+        assert!(
+            plan_suffix_recompute(std::slice::from_ref(&rn), &partition, &pt, true, None).is_none()
+        );
+        let plan = plan_suffix_recompute(&[rn], &partition, &pt, false, None).unwrap();
+        assert_eq!(plan.order_col, "ts");
+        assert_eq!(plan.kinds, vec![SeedKind::RowNumber]);
+        assert!(!plan.rows_sum);
+    }
+
+    #[test]
+    fn test_cumulative_frame_is_rows() {
+        assert_eq!(cumulative_frame_is_rows(None), Some(false));
+        assert_eq!(
+            cumulative_frame_is_rows(Some("RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW")),
+            Some(false)
+        );
+        assert_eq!(
+            cumulative_frame_is_rows(Some("ROWS UNBOUNDED PRECEDING")),
+            Some(true)
+        );
+        assert_eq!(
+            cumulative_frame_is_rows(Some(
+                "ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING"
+            )),
+            None
+        );
+        assert_eq!(
+            cumulative_frame_is_rows(Some("GROUPS UNBOUNDED PRECEDING")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_order_at_or_after_null_placement() {
+        assert_eq!(
+            order_at_or_after("k", "b", true, false),
+            "(CASE WHEN b IS NULL THEN k IS NULL ELSE (k IS NULL OR k >= b) END)"
+        );
+        assert_eq!(
+            order_at_or_after("k", "b", false, true),
+            "(b IS NULL OR (k IS NOT NULL AND k <= b))"
+        );
+        assert_eq!(order_earliest_key("k", true, false), "MIN(k)");
+        assert_eq!(
+            order_earliest_key("k", false, true),
+            "CASE WHEN bool_or(k IS NULL) THEN NULL ELSE MAX(k) END"
+        );
+        assert_eq!(order_reversed(false, true), "ASC NULLS LAST");
+    }
+
+    fn leaderboard_window(funcs: &[(&str, &str)]) -> OpTree {
+        let child = scan(1, "scores", "public", "s", &["id", "region", "score"]);
+        let wfs = funcs
+            .iter()
+            .map(|(func, alias)| {
+                window_expr(
+                    func,
+                    vec![],
+                    vec![colref("region")],
+                    vec![sort_asc(colref("score"))],
+                    alias,
+                )
+            })
+            .collect();
+        window(
+            wfs,
+            vec![colref("region")],
+            vec![
+                (colref("id"), "id".to_string()),
+                (colref("region"), "region".to_string()),
+                (colref("score"), "score".to_string()),
+            ],
+            child,
+        )
+    }
+
+    #[test]
+    fn test_classify_window_filter() {
+        let rn = leaderboard_window(&[("ROW_NUMBER", "rn")]);
+        let wrapped = subquery("t", vec![], rn.clone());
+        assert_eq!(
+            classify_window_filter(&binop("<=", colref("rn"), lit("3")), &wrapped),
+            WindowTruncation::TopN(3)
+        );
+        assert_eq!(
+            classify_window_filter(&binop("<", colref("rn"), lit("4")), &rn),
+            WindowTruncation::TopN(3)
+        );
+        assert_eq!(
+            classify_window_filter(&binop("=", lit("1"), colref("rn")), &rn),
+            WindowTruncation::TopN(1)
+        );
+        assert_eq!(
+            classify_window_filter(&binop("=", colref("rn"), lit("2")), &rn),
+            WindowTruncation::Subset
+        );
+        assert_eq!(
+            classify_window_filter(&binop(">", colref("rn"), lit("3")), &rn),
+            WindowTruncation::Subset
+        );
+        // A renaming Subquery hides which column the predicate reads.
+        let renamed = subquery("t", vec!["a", "b", "c", "d"], rn);
+        assert_eq!(
+            classify_window_filter(&binop("<=", colref("rn"), lit("3")), &renamed),
+            WindowTruncation::Subset
+        );
+        // A running total next to the rank needs the whole partition.
+        let mixed = leaderboard_window(&[("RANK", "rk"), ("SUM", "total")]);
+        assert_eq!(
+            classify_window_filter(&binop("<=", colref("rk"), lit("3")), &mixed),
+            WindowTruncation::Subset
+        );
+    }
+
+    #[test]
+    fn test_diff_window_topn_refills_partition_from_source() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = filter(
+            binop("<=", colref("rn"), lit("3")),
+            subquery("t", vec![], leaderboard_window(&[("ROW_NUMBER", "rn")])),
+        );
+        let result = ctx.diff_node(&tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        // Full partitions that lost a stored row are read from the source.
+        assert_sql_contains(&sql, "HAVING COUNT(*) >= 3");
+        assert_sql_contains(&sql, "-- Partitions refilled from the source");
+        assert_sql_contains(&sql, "FROM \"public\".\"scores\" AS \"s\"");
+        assert_sql_contains(
+            &sql,
+            "src.\"region\" IS NOT DISTINCT FROM __pgt_rf.\"region\"",
+        );
+        // Other partitions keep the stored prefix plus inserts.
+        assert_sql_contains(&sql, "keep their stored prefix");
+        // A refilled partition is recomputed whole, never suffix-seeded.
+        assert!(!sql.contains("__pgt_seed_0"));
+        assert!(ctx.window_truncation.is_none());
+    }
+
+    #[test]
+    fn test_diff_window_subset_filter_refills_every_changed_partition() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = filter(
+            binop(">", colref("rn"), lit("3")),
+            subquery("t", vec![], leaderboard_window(&[("ROW_NUMBER", "rn")])),
+        );
+        let result = ctx.diff_node(&tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert_sql_contains(&sql, "-- Partitions refilled from the source");
+        assert!(!sql.contains("keep their stored prefix"));
+    }
+
+    /// A filtered Window whose input cannot be re-read from the source is
+    /// rejected at creation; an existing one keeps the ST + delta
+    /// recompute instead of failing its refreshes.
+    fn assert_truncated_window_rejected(window_tree: OpTree) {
+        let tree = filter(
+            binop("<=", colref("rn"), lit("3")),
+            subquery("t", vec![], window_tree),
+        );
+        let mut ctx = test_ctx_with_st("public", "st");
+        let result = ctx.diff_node(&tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+        assert!(!sql.contains("refilled from the source"));
+        let result = crate::dvm::parser::ParseResult {
+            tree,
+            cte_registry: CteRegistry::default(),
+            has_recursion: false,
+            warnings: vec![],
+        };
+        assert!(crate::dvm::parser::check_window_truncation_support(&result).is_err());
+    }
+
+    fn rn_over(order: Expr) -> WindowExpr {
+        window_expr("ROW_NUMBER", vec![], vec![], vec![sort_asc(order)], "rn")
+    }
+
+    #[test]
+    fn test_truncated_window_over_aggregate_is_rejected() {
+        let agg = aggregate(
+            vec![colref("region")],
+            vec![sum_col("score", "total")],
+            scan(1, "scores", "public", "s", &["id", "region", "score"]),
+        );
+        assert_truncated_window_rejected(window(
+            vec![rn_over(colref("total"))],
+            vec![],
+            vec![
+                (colref("region"), "region".to_string()),
+                (colref("total"), "total".to_string()),
+            ],
+            agg,
+        ));
+    }
+
+    #[test]
+    fn test_truncated_window_with_star_pass_through_is_rejected() {
+        assert_truncated_window_rejected(window(
+            vec![rn_over(colref("score"))],
+            vec![],
+            vec![(Expr::Star { table_alias: None }, "*".to_string())],
+            scan(1, "scores", "public", "s", &["id", "region", "score"]),
+        ));
+    }
+
+    #[test]
+    fn test_truncated_window_over_union_is_rejected() {
+        let union = union_all(vec![
+            scan(1, "scores_a", "public", "a", &["id", "score"]),
+            scan(2, "scores_b", "public", "b", &["id", "score"]),
+        ]);
+        assert_truncated_window_rejected(window(
+            vec![rn_over(colref("score"))],
+            vec![],
+            vec![
+                (colref("id"), "id".to_string()),
+                (colref("score"), "score".to_string()),
+            ],
+            union,
+        ));
+    }
+
+    #[test]
+    fn test_truncated_window_over_scan_is_accepted() {
+        let result = crate::dvm::parser::ParseResult {
+            tree: filter(
+                binop("<=", colref("rn"), lit("3")),
+                subquery("t", vec![], leaderboard_window(&[("ROW_NUMBER", "rn")])),
+            ),
+            cte_registry: CteRegistry::default(),
+            has_recursion: false,
+            warnings: vec![],
+        };
+        assert!(crate::dvm::parser::check_window_truncation_support(&result).is_ok());
+    }
+
+    #[test]
+    fn test_diff_window_without_filter_keeps_st_reconstruction() {
+        let mut ctx = test_ctx_with_st("public", "st");
+        let tree = leaderboard_window(&[("ROW_NUMBER", "rn")]);
+        let result = diff_window(&mut ctx, &tree).unwrap();
+        let sql = ctx.build_with_query(&result.cte_name);
+
+        assert!(!sql.contains("refilled from the source"));
+    }
 }
@@ -20,7 +20,7 @@
 use crate::error::PgTrickleError;
 use pgrx::prelude::*;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // Thread-local accumulator for advisory warnings emitted during one
 // `parse_defining_query_inner` call.  The accumulator is initialized at the
@@ -195,6 +195,422 @@ pub use sublinks::*;
 pub use types::*;
 pub use validation::*;
 
+// ── Operator and cast resolution ──────────────────────────────────────
+
+// Thread-local operator, function and cast resolutions for the defining
+// query being parsed.  `parse_defining_query_inner` installs them with a
+// `ResolutionScope`; `node_to_expr` reads them to fill `Resolved` on
+// `Expr::UnaryOp`, `Expr::BinaryOp`, `Expr::FuncCall` and `Expr::Cast`.
+// `None` outside a defining-query parse.
+thread_local! {
+    static EXPR_RESOLUTIONS: RefCell<Option<ExprResolutions>> = const { RefCell::new(None) };
+}
+
+/// Operator, function and cast resolutions of one defining query, keyed by
+/// the parse location of the operator token, function name or cast.
+#[derive(Default)]
+struct ExprResolutions {
+    /// Addresses of the raw `A_Expr` / `FuncCall` / `TypeCast` nodes of the
+    /// parse the locations belong to.  Nodes of other parses (re-parsed
+    /// fragments) share location numbers but not addresses, so they are not
+    /// looked up.
+    raw_nodes: HashSet<usize>,
+    operators: HashMap<i32, Resolved>,
+    functions: HashMap<i32, Resolved>,
+    casts: HashMap<i32, Resolved>,
+}
+
+/// Installs the resolutions of one defining query for the duration of its
+/// parse and restores those of an enclosing parse on drop.
+struct ResolutionScope {
+    previous: Option<ExprResolutions>,
+}
+
+impl ResolutionScope {
+    fn enter(query: &str, raw_stmt: *mut pg_sys::RawStmt) -> Self {
+        let resolutions = resolve_query_exprs(query, raw_stmt);
+        let previous = EXPR_RESOLUTIONS.with(|r| r.replace(Some(resolutions)));
+        Self { previous }
+    }
+}
+
+impl Drop for ResolutionScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        EXPR_RESOLUTIONS.with(|r| *r.borrow_mut() = previous);
+    }
+}
+
+/// Resolution of the operator of a raw `A_Expr`, or `Resolved::default()`
+/// when it is not part of the defining query being parsed.
+fn resolved_operator(aexpr: &pg_sys::A_Expr) -> Resolved {
+    resolution_at(aexpr as *const _ as usize, aexpr.location, |r| &r.operators)
+}
+
+/// Resolution of the function of a raw `FuncCall`, or `Resolved::default()`
+/// when it is not part of the defining query being parsed.
+fn resolved_function(fcall: &pg_sys::FuncCall) -> Resolved {
+    resolution_at(fcall as *const _ as usize, fcall.location, |r| &r.functions)
+}
+
+/// Resolution of a raw `TypeCast` whose operand converted to `inner`, or
+/// `Resolved::default()` when it is not part of the defining query being
+/// parsed.
+///
+/// Analysis folds a cast of a literal into a constant and leaves no
+/// coercion node, so its target type and input function are looked up
+/// directly.  The stored query is re-analyzed on every refresh, so the
+/// input function's volatility still applies.
+fn resolved_cast(tc: &pg_sys::TypeCast, inner: &Expr) -> Resolved {
+    if matches!(inner, Expr::Literal(_)) {
+        return literal_cast_resolution(tc.typeName);
+    }
+    resolution_at(tc as *const _ as usize, tc.location, |r| &r.casts)
+}
+
+fn resolution_at(
+    node: usize,
+    location: i32,
+    table: fn(&ExprResolutions) -> &HashMap<i32, Resolved>,
+) -> Resolved {
+    EXPR_RESOLUTIONS.with(|r| {
+        r.borrow()
+            .as_ref()
+            .filter(|res| res.raw_nodes.contains(&node))
+            .and_then(|res| table(res).get(&location).copied())
+            .unwrap_or_default()
+    })
+}
+
+/// Resolve the operators, function calls and explicit casts of a defining
+/// query.
+///
+/// Records the raw `A_Expr` / `FuncCall` / `TypeCast` nodes of `raw_stmt`,
+/// then runs parse analysis on a fresh parse of `query` and keys every
+/// operator, function call and explicit coercion by its location, which
+/// analysis copies from the raw node.  Analysis runs in an internal
+/// subtransaction; if it fails (for example because a referenced relation
+/// does not exist yet) nothing is resolved and the validators fall back to
+/// name-based lookups.
+#[cfg(any(not(test), feature = "pg_test"))]
+fn resolve_query_exprs(query: &str, raw_stmt: *mut pg_sys::RawStmt) -> ExprResolutions {
+    let mut resolutions = ExprResolutions::default();
+    // SAFETY: raw_stmt is the RawStmt of the parse in progress; the walker
+    // only reads node tags and records addresses.
+    unsafe {
+        pg_sys::raw_expression_tree_walker_impl(
+            (*raw_stmt).stmt,
+            Some(raw_resolution_walker),
+            &mut resolutions.raw_nodes as *mut HashSet<usize> as *mut std::ffi::c_void,
+        );
+    }
+
+    // Subtransactions cannot be started during a parallel operation.
+    // SAFETY: Reads backend transaction state.
+    if unsafe { pg_sys::IsInParallelMode() } {
+        return resolutions;
+    }
+    let Ok(c_sql) = std::ffi::CString::new(query) else {
+        return resolutions;
+    };
+    // SAFETY: Called within a transaction; CurrentMemoryContext and
+    // CurrentResourceOwner are valid and restored below.
+    let (old_cxt, old_owner) =
+        unsafe { (pg_sys::CurrentMemoryContext, pg_sys::CurrentResourceOwner) };
+    // SAFETY: BeginInternalSubTransaction sets up a sub-transaction that is
+    // released or rolled back below.
+    unsafe { pg_sys::BeginInternalSubTransaction(std::ptr::null()) };
+    let c_sql = &c_sql;
+    let analyzed = PgTryBuilder::new(|| {
+        let mut analyzed = AnalyzedExprs::default();
+        // SAFETY: raw_parser and parse_analyze_fixedparams are called with a
+        // valid C string inside a backend transaction; the walker only reads
+        // the analyzed tree and calls catalog lookups.
+        unsafe {
+            let stmts = pg_list::<pg_sys::RawStmt>(pg_sys::raw_parser(
+                c_sql.as_ptr(),
+                pg_sys::RawParseMode::RAW_PARSE_DEFAULT,
+            ));
+            if let Some(stmt) = stmts.head() {
+                let query_node = pg_sys::parse_analyze_fixedparams(
+                    stmt,
+                    c_sql.as_ptr(),
+                    std::ptr::null(),
+                    0,
+                    std::ptr::null_mut(),
+                );
+                if !query_node.is_null() {
+                    pg_sys::query_tree_walker_impl(
+                        query_node,
+                        Some(analyzed_expr_walker),
+                        &mut analyzed as *mut AnalyzedExprs as *mut std::ffi::c_void,
+                        0,
+                    );
+                }
+            }
+        }
+        Some(analyzed)
+    })
+    .catch_others(|_| None)
+    .execute();
+    // SAFETY: Ends the sub-transaction begun above and restores the outer
+    // memory context and resource owner.
+    unsafe {
+        if analyzed.is_some() {
+            pg_sys::ReleaseCurrentSubTransaction();
+        } else {
+            pg_sys::RollbackAndReleaseCurrentSubTransaction();
+        }
+        pg_sys::MemoryContextSwitchTo(old_cxt);
+        pg_sys::CurrentResourceOwner = old_owner;
+    }
+
+    if let Some(analyzed) = analyzed {
+        resolutions.operators = analyzed.operators;
+        resolutions.functions = analyzed.functions;
+        resolutions.casts = analyzed.casts;
+    }
+    resolutions
+}
+
+/// Target type OID and input-function volatility of a cast of a literal.
+#[cfg(any(not(test), feature = "pg_test"))]
+fn literal_cast_resolution(type_name: *mut pg_sys::TypeName) -> Resolved {
+    // SAFETY: type_name is a raw parse-tree TypeName; with missing_ok the
+    // lookup returns InvalidOid for an unknown type instead of raising.
+    let type_oid = unsafe { pg_sys::LookupTypeNameOid(std::ptr::null_mut(), type_name, true) };
+    if type_oid == pg_sys::InvalidOid {
+        return Resolved::default();
+    }
+    let mut typinput = pg_sys::InvalidOid;
+    let mut typioparam = pg_sys::InvalidOid;
+    // SAFETY: type_oid names an existing type; both out-pointers are valid.
+    let volatility = unsafe {
+        pg_sys::getTypeInputInfo(type_oid, &mut typinput, &mut typioparam);
+        pg_sys::func_volatile(typinput) as u8 as char
+    };
+    Resolved {
+        oid: Some(type_oid.to_u32()),
+        volatility: Some(volatility),
+    }
+}
+
+/// Test stub: catalog lookups are unavailable without a PostgreSQL backend.
+#[cfg(all(test, not(feature = "pg_test")))]
+fn literal_cast_resolution(_type_name: *mut pg_sys::TypeName) -> Resolved {
+    Resolved::default()
+}
+
+/// Test stub: parse analysis is unavailable without a PostgreSQL backend.
+#[cfg(all(test, not(feature = "pg_test")))]
+fn resolve_query_exprs(_query: &str, _raw_stmt: *mut pg_sys::RawStmt) -> ExprResolutions {
+    ExprResolutions::default()
+}
+
+/// Walker callback for [`resolve_query_exprs`] over the raw parse tree:
+/// records the address of every `A_Expr`, `FuncCall` and `TypeCast`.
+#[cfg(any(not(test), feature = "pg_test"))]
+unsafe extern "C-unwind" fn raw_resolution_walker(
+    node: *mut pg_sys::Node,
+    context: *mut std::ffi::c_void,
+) -> bool {
+    if node.is_null() {
+        return false;
+    }
+    if is_node_type!(node, T_A_Expr)
+        || is_node_type!(node, T_FuncCall)
+        || is_node_type!(node, T_TypeCast)
+    {
+        // SAFETY: context is the `raw_nodes` set of `resolve_query_exprs`.
+        let raw_nodes = unsafe { &mut *(context as *mut HashSet<usize>) };
+        raw_nodes.insert(node as usize);
+    }
+    // SAFETY: node is a valid raw parse-tree node.
+    unsafe { pg_sys::raw_expression_tree_walker_impl(node, Some(raw_resolution_walker), context) }
+}
+
+/// Context for [`analyzed_expr_walker`].
+#[cfg(any(not(test), feature = "pg_test"))]
+#[derive(Default)]
+struct AnalyzedExprs {
+    operators: HashMap<i32, Resolved>,
+    functions: HashMap<i32, Resolved>,
+    casts: HashMap<i32, Resolved>,
+}
+
+/// Walker callback for [`resolve_query_exprs`] over the analyzed tree.
+///
+/// - `OpExpr` / `DistinctExpr` → operator OID and the volatility of its
+///   function
+/// - `FuncExpr` called by name / `Aggref` → function OID and volatility
+/// - explicit `FuncExpr` / `CoerceViaIO` / `RelabelType` /
+///   `ArrayCoerceExpr` / `CoerceToDomain` → target type OID and the
+///   volatility of the coercion.  Stacked coercions of one cast share a
+///   location; the outermost (visited first) gives the type and the
+///   volatilities are combined.
+/// - `T_Query` (CTEs, FROM subqueries, sublinks) → recurse via
+///   `query_tree_walker`
+///
+/// # Safety
+/// `node` and `context` must be valid pointers provided by the PG walker.
+#[cfg(any(not(test), feature = "pg_test"))]
+unsafe extern "C-unwind" fn analyzed_expr_walker(
+    node: *mut pg_sys::Node,
+    context: *mut std::ffi::c_void,
+) -> bool {
+    if node.is_null() {
+        return false;
+    }
+    if is_node_type!(node, T_Query) {
+        // SAFETY: node tag verified as T_Query.
+        return unsafe {
+            pg_sys::query_tree_walker_impl(
+                node as *mut pg_sys::Query,
+                Some(analyzed_expr_walker),
+                context,
+                0,
+            )
+        };
+    }
+
+    // SAFETY: context is our AnalyzedExprs.
+    let analyzed = unsafe { &mut *(context as *mut AnalyzedExprs) };
+    let explicit = pg_sys::CoercionForm::COERCE_EXPLICIT_CAST;
+    // DistinctExpr is an OpExpr with its own tag.
+    let op = cast_node!(node, T_OpExpr, pg_sys::OpExpr)
+        .or_else(|| cast_node!(node, T_DistinctExpr, pg_sys::OpExpr));
+    let call = if let Some(f) = cast_node!(node, T_FuncExpr, pg_sys::FuncExpr) {
+        (f.funcformat == pg_sys::CoercionForm::COERCE_EXPLICIT_CALL)
+            .then_some((f.funcid, f.location))
+    } else {
+        cast_node!(node, T_Aggref, pg_sys::Aggref).map(|a| (a.aggfnoid, a.location))
+    };
+    if let Some(op) = op {
+        if op.location >= 0 {
+            analyzed.operators.entry(op.location).or_insert(Resolved {
+                oid: Some(op.opno.to_u32()),
+                volatility: Some(function_volatility(node)),
+            });
+        }
+    } else if let Some((func_oid, location)) = call {
+        if location >= 0 {
+            // SAFETY: func_oid comes from an analyzed node.
+            let volatility = unsafe { pg_sys::func_volatile(func_oid) } as u8 as char;
+            analyzed.functions.entry(location).or_insert(Resolved {
+                oid: Some(func_oid.to_u32()),
+                volatility: Some(volatility),
+            });
+        }
+    } else {
+        let cast = if let Some(f) = cast_node!(node, T_FuncExpr, pg_sys::FuncExpr) {
+            (f.funcformat == explicit).then_some((f.funcresulttype, f.location, 'i'))
+        } else if let Some(c) = cast_node!(node, T_CoerceViaIO, pg_sys::CoerceViaIO) {
+            (c.coerceformat == explicit).then_some((c.resulttype, c.location, 'i'))
+        } else if let Some(r) = cast_node!(node, T_RelabelType, pg_sys::RelabelType) {
+            (r.relabelformat == explicit).then_some((r.resulttype, r.location, 'i'))
+        } else if let Some(a) = cast_node!(node, T_ArrayCoerceExpr, pg_sys::ArrayCoerceExpr) {
+            // The per-element coercion is a separate expression over a
+            // placeholder; all of it belongs to the cast.
+            (a.coerceformat == explicit).then(|| {
+                let elem = subtree_volatility(a.elemexpr as *mut pg_sys::Node);
+                (a.resulttype, a.location, elem)
+            })
+        } else if let Some(d) = cast_node!(node, T_CoerceToDomain, pg_sys::CoerceToDomain) {
+            (d.coercionformat == explicit).then_some((d.resulttype, d.location, 'i'))
+        } else {
+            None
+        };
+        if let Some((type_oid, location, extra)) = cast
+            && location >= 0
+        {
+            let volatility = max_volatility(function_volatility(node), extra);
+            let entry = analyzed.casts.entry(location).or_insert(Resolved {
+                oid: Some(type_oid.to_u32()),
+                volatility: None,
+            });
+            entry.volatility = Some(match entry.volatility {
+                Some(v) => max_volatility(v, volatility),
+                None => volatility,
+            });
+        }
+    }
+
+    // SAFETY: node is a valid analyzed expression node.
+    unsafe { pg_sys::expression_tree_walker_impl(node, Some(analyzed_expr_walker), context) }
+}
+
+/// Volatility of the functions a single analyzed node calls itself
+/// (operator, cast or I/O functions; not those of its arguments).
+#[cfg(any(not(test), feature = "pg_test"))]
+fn function_volatility(node: *mut pg_sys::Node) -> char {
+    let mut volatility = 'i';
+    volatility_walker_visit(node, &mut volatility);
+    volatility
+}
+
+#[cfg(any(not(test), feature = "pg_test"))]
+fn volatility_walker_visit(node: *mut pg_sys::Node, volatility: &mut char) {
+    if node.is_null() {
+        return;
+    }
+    // SAFETY: node is a valid analyzed expression node; the checker only
+    // reads pg_proc through func_volatile.
+    unsafe {
+        pg_sys::check_functions_in_node(
+            node,
+            Some(volatility_checker),
+            volatility as *mut char as *mut std::ffi::c_void,
+        );
+    }
+}
+
+/// `check_functions_in_node` callback folding `func_volatile` into the
+/// `char` behind `context`.
+#[cfg(any(not(test), feature = "pg_test"))]
+unsafe extern "C-unwind" fn volatility_checker(
+    func_id: pg_sys::Oid,
+    context: *mut std::ffi::c_void,
+) -> bool {
+    // SAFETY: func_id comes from an analyzed node; context is a `char`.
+    let volatility = unsafe { pg_sys::func_volatile(func_id) } as u8 as char;
+    let acc = unsafe { &mut *(context as *mut char) };
+    *acc = max_volatility(*acc, volatility);
+    false
+}
+
+/// Volatility of every function called in an analyzed subtree.
+#[cfg(any(not(test), feature = "pg_test"))]
+fn subtree_volatility(node: *mut pg_sys::Node) -> char {
+    let mut volatility = 'i';
+    volatility_walker_visit(node, &mut volatility);
+    if !node.is_null() {
+        // SAFETY: node is a valid analyzed expression node.
+        unsafe {
+            pg_sys::expression_tree_walker_impl(
+                node,
+                Some(volatility_walker),
+                &mut volatility as *mut char as *mut std::ffi::c_void,
+            );
+        }
+    }
+    volatility
+}
+
+/// Walker callback for [`subtree_volatility`].
+#[cfg(any(not(test), feature = "pg_test"))]
+unsafe extern "C-unwind" fn volatility_walker(
+    node: *mut pg_sys::Node,
+    context: *mut std::ffi::c_void,
+) -> bool {
+    if node.is_null() {
+        return false;
+    }
+    // SAFETY: context is a `char` (see `volatility_checker`).
+    volatility_walker_visit(node, unsafe { &mut *(context as *mut char) });
+    // SAFETY: node is a valid analyzed expression node.
+    unsafe { pg_sys::expression_tree_walker_impl(node, Some(volatility_walker), context) }
+}
+
 // ── SAF-2: Safe façades for common unsafe FFI operations ──────────────────
 //
 // These thin wrappers encapsulate the single `// SAFETY:` reasoning block
@@ -518,6 +934,7 @@ mod tests {
             op: "+".to_string(),
             left: Box::new(col("a")),
             right: Box::new(col("b")),
+            resolved: Resolved::default(),
         };
         assert_eq!(e.to_sql(), "(a + b)");
     }
@@ -530,8 +947,10 @@ mod tests {
                 op: "+".to_string(),
                 left: Box::new(col("a")),
                 right: Box::new(col("b")),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::Literal("2".to_string())),
+            resolved: Resolved::default(),
         };
         assert_eq!(e.to_sql(), "((a + b) * 2)");
     }
@@ -541,6 +960,7 @@ mod tests {
         let e = Expr::FuncCall {
             func_name: "now".to_string(),
             args: vec![],
+            resolved: Resolved::default(),
         };
         assert_eq!(e.to_sql(), "now()");
     }
@@ -550,6 +970,7 @@ mod tests {
         let e = Expr::FuncCall {
             func_name: "coalesce".to_string(),
             args: vec![col("x"), Expr::Literal("0".to_string())],
+            resolved: Resolved::default(),
         };
         assert_eq!(e.to_sql(), "coalesce(x, 0)");
     }
@@ -871,6 +1292,7 @@ mod tests {
                 op: ">".to_string(),
                 left: Box::new(col("amount")),
                 right: Box::new(Expr::Literal("100".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(scan_node("t", 1, &["id", "amount"])),
         };
@@ -1049,12 +1471,14 @@ mod tests {
                 op: ">".to_string(),
                 left: Box::new(qualified_col("a", "x")),
                 right: Box::new(Expr::Literal("10".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(OpTree::InnerJoin {
                 condition: Expr::BinaryOp {
                     op: "=".to_string(),
                     left: Box::new(qualified_col("a", "id")),
                     right: Box::new(qualified_col("b", "id")),
+                    resolved: Resolved::default(),
                 },
                 left: Box::new(scan_node("a", 100, &["id", "x"])),
                 right: Box::new(scan_node("b", 200, &["id", "val"])),
@@ -1089,6 +1513,7 @@ mod tests {
                 op: ">".to_string(),
                 left: Box::new(col("val")),
                 right: Box::new(Expr::Literal("0".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(join),
         };
@@ -1353,6 +1778,98 @@ mod tests {
         assert_eq!(tree.source_oids(), vec![10, 20]);
     }
 
+    fn distance_filter(op: &str, left: Expr, right: Expr) -> OpTree {
+        OpTree::Filter {
+            predicate: Expr::BinaryOp {
+                op: "<".to_string(),
+                left: Box::new(Expr::BinaryOp {
+                    op: op.to_string(),
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    resolved: Resolved::default(),
+                }),
+                right: Box::new(Expr::Literal("0.3".to_string())),
+                resolved: Resolved::default(),
+            },
+            child: Box::new(scan_node("docs", 1, &["id", "embedding"])),
+        }
+    }
+
+    #[test]
+    fn test_check_ivm_support_pgvector_distance_to_constant() {
+        // embedding <=> '[1,0,0]'::vector < 0.3 only depends on the row.
+        let tree = distance_filter(
+            "<=>",
+            col("embedding"),
+            Expr::Cast {
+                expr: Box::new(Expr::Literal("'[1,0,0]'".to_string())),
+                type_name: "vector(3)".to_string(),
+                resolved: Resolved::default(),
+            },
+        );
+        assert!(check_ivm_support(&tree).is_ok());
+
+        // Constant on the left, plain string literal.
+        let tree = distance_filter(
+            "<#>",
+            Expr::Literal("'[1,0,0]'".to_string()),
+            col("embedding"),
+        );
+        assert!(check_ivm_support(&tree).is_ok());
+
+        // A literal holding an escaped quote (E'\'') is still one constant.
+        let tree = distance_filter(
+            "<->",
+            col("embedding"),
+            Expr::Cast {
+                expr: Box::new(Expr::Literal("''''".to_string())),
+                type_name: "vector".to_string(),
+                resolved: Resolved::default(),
+            },
+        );
+        assert!(check_ivm_support(&tree).is_ok());
+    }
+
+    #[test]
+    fn test_check_ivm_support_pgvector_distance_cross_row() {
+        let tree = distance_filter(
+            "<->",
+            qualified_col("a", "embedding"),
+            qualified_col("b", "embedding"),
+        );
+        let err = check_ivm_support(&tree).unwrap_err();
+        assert!(err.to_string().contains("pgvector distance operator"));
+
+        // A cast of a column is not a constant.
+        let tree = distance_filter(
+            "<+>",
+            col("embedding"),
+            Expr::Cast {
+                expr: Box::new(col("other")),
+                type_name: "vector".to_string(),
+                resolved: Resolved::default(),
+            },
+        );
+        assert!(check_ivm_support(&tree).is_err());
+
+        // Raw SQL text is never taken for a constant, whatever it looks like.
+        let tree = distance_filter(
+            "<=>",
+            col("embedding"),
+            Expr::Raw("CAST('x' AS vector) || other".to_string()),
+        );
+        assert!(check_ivm_support(&tree).is_err());
+    }
+
+    #[test]
+    fn test_check_ivm_support_pgvector_distance_in_raw_predicate() {
+        let tree = OpTree::Filter {
+            predicate: Expr::Raw("(embedding <=> '[1,0,0]') IS NOT NULL".to_string()),
+            child: Box::new(scan_node("docs", 1, &["id", "embedding"])),
+        };
+        assert!(check_ivm_support(&tree).is_err());
+    }
+
     #[test]
     fn test_check_ivm_support_subquery() {
         let tree = OpTree::Subquery {
@@ -1397,6 +1914,7 @@ mod tests {
                     op: "=".to_string(),
                     left: Box::new(col("active")),
                     right: Box::new(Expr::Literal("true".to_string())),
+                    resolved: Resolved::default(),
                 },
                 child: Box::new(scan_node("users", 1, &["id", "name", "active"])),
             }),
@@ -1407,6 +1925,7 @@ mod tests {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("active", "id")),
                 right: Box::new(qualified_col("orders", "user_id")),
+                resolved: Resolved::default(),
             },
             left: Box::new(sub),
             right: Box::new(scan_node("orders", 2, &["user_id", "amount"])),
@@ -1619,6 +2138,7 @@ mod tests {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("t1", "user_id")),
                 right: Box::new(qualified_col("t2", "user_id")),
+                resolved: Resolved::default(),
             },
             left: Box::new(t1),
             right: Box::new(t2),
@@ -2089,6 +2609,7 @@ mod tests {
             op: "+".to_string(),
             left: Box::new(col("a")),
             right: Box::new(col("b")),
+            resolved: Resolved::default(),
         };
         assert_eq!(e.output_name(), "(a + b)");
     }
@@ -2098,6 +2619,7 @@ mod tests {
         let e = Expr::FuncCall {
             func_name: "upper".to_string(),
             args: vec![col("name")],
+            resolved: Resolved::default(),
         };
         assert_eq!(e.output_name(), "upper(name)");
     }
@@ -2124,6 +2646,7 @@ mod tests {
             op: "+".to_string(),
             left: Box::new(qualified_col("t", "a")),
             right: Box::new(qualified_col("t", "b")),
+            resolved: Resolved::default(),
         };
         let stripped = e.strip_qualifier();
         assert_eq!(stripped.to_sql(), "(a + b)");
@@ -2134,6 +2657,7 @@ mod tests {
         let e = Expr::FuncCall {
             func_name: "coalesce".to_string(),
             args: vec![qualified_col("t", "x"), Expr::Literal("0".to_string())],
+            resolved: Resolved::default(),
         };
         let stripped = e.strip_qualifier();
         assert_eq!(stripped.to_sql(), "coalesce(x, 0)");
@@ -2199,6 +2723,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(qualified_col("a", "id")),
             right: Box::new(qualified_col("b", "id")),
+            resolved: Resolved::default(),
         };
         let rewritten = e.rewrite_aliases("a", "x", "b", "y");
         assert_eq!(rewritten.to_sql(), "(\"x\".\"id\" = \"y\".\"id\")");
@@ -2209,6 +2734,7 @@ mod tests {
         let e = Expr::FuncCall {
             func_name: "coalesce".to_string(),
             args: vec![qualified_col("a", "x"), qualified_col("b", "y")],
+            resolved: Resolved::default(),
         };
         let rewritten = e.rewrite_aliases("a", "left", "b", "right");
         assert_eq!(
@@ -2247,8 +2773,10 @@ mod tests {
             left: Box::new(Expr::FuncCall {
                 func_name: "sum".to_string(),
                 args: vec![col("amount")],
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::Literal("100".to_string())),
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "(total > 100)");
@@ -2263,8 +2791,10 @@ mod tests {
             left: Box::new(Expr::FuncCall {
                 func_name: "count".to_string(),
                 args: vec![],
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::Literal("5".to_string())),
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "(cnt >= 5)");
@@ -2284,17 +2814,22 @@ mod tests {
                 left: Box::new(Expr::FuncCall {
                     func_name: "sum".to_string(),
                     args: vec![col("amount")],
+                    resolved: Resolved::default(),
                 }),
                 right: Box::new(Expr::Literal("100".to_string())),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::BinaryOp {
                 op: ">".to_string(),
                 left: Box::new(Expr::FuncCall {
                     func_name: "count".to_string(),
                     args: vec![],
+                    resolved: Resolved::default(),
                 }),
                 right: Box::new(Expr::Literal("2".to_string())),
+                resolved: Resolved::default(),
             }),
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "((total > 100) AND (cnt > 2))");
@@ -2307,6 +2842,7 @@ mod tests {
         let pred = Expr::FuncCall {
             func_name: "SUM".to_string(),
             args: vec![col("price")],
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "revenue");
@@ -2319,6 +2855,7 @@ mod tests {
         let pred = Expr::FuncCall {
             func_name: "coalesce".to_string(),
             args: vec![col("x"), Expr::Literal("0".to_string())],
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "coalesce(x, 0)");
@@ -2333,8 +2870,10 @@ mod tests {
             left: Box::new(Expr::FuncCall {
                 func_name: "avg".to_string(),
                 args: vec![col("score")],
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::Literal("75.0".to_string())),
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "(avg_score > 75.0)");
@@ -2348,6 +2887,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(col("region")),
             right: Box::new(Expr::Literal("'US'".to_string())),
+            resolved: Resolved::default(),
         };
         let rewritten = rewrite_having_expr(&pred, &aggs);
         assert_eq!(rewritten.to_sql(), "(region = 'US')");
@@ -2506,6 +3046,7 @@ mod tests {
                         Expr::Literal("' > '".to_string()),
                         Expr::Literal("2".to_string()),
                     ],
+                    resolved: Resolved::default(),
                 },
                 col("total_headcount"),
             ],
@@ -2630,6 +3171,7 @@ mod tests {
                 op: ">=".to_string(),
                 left: Box::new(col("side")),
                 right: Box::new(Expr::Literal("2".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(scan),
         };
@@ -3504,6 +4046,364 @@ mod tests {
         );
     }
 
+    // ── Typed expressions ───────────────────────────────────────────
+
+    fn subquery(sql: &str, exprs: Vec<Expr>) -> Box<SubQuery> {
+        Box::new(SubQuery {
+            sql: sql.to_string(),
+            exprs,
+        })
+    }
+
+    fn case_on(cond: Expr, then: Expr) -> Expr {
+        Expr::Case {
+            operand: None,
+            whens: vec![(cond, then)],
+            else_result: Some(Box::new(Expr::Literal("0".to_string()))),
+        }
+    }
+
+    #[test]
+    fn test_nested_unary_minus_to_sql_is_not_a_comment() {
+        let neg = |expr: Expr| Expr::UnaryOp {
+            op: "-".to_string(),
+            expr: Box::new(expr),
+            resolved: Resolved::default(),
+        };
+        let sql = neg(neg(col("x"))).to_sql();
+        assert_eq!(sql, "(- (- x))");
+        assert!(!sql.contains("--"));
+        assert_eq!(
+            neg(neg(Expr::Literal("5".to_string()))).to_sql(),
+            "(- (- 5))"
+        );
+    }
+
+    #[test]
+    fn test_typed_expr_to_sql_matches_deparse() {
+        let case = case_on(
+            Expr::BinaryOp {
+                op: ">".to_string(),
+                left: Box::new(qualified_col("t", "x")),
+                right: Box::new(Expr::Literal("0".to_string())),
+                resolved: Resolved::default(),
+            },
+            col("y"),
+        );
+        assert_eq!(
+            case.to_sql(),
+            "CASE WHEN (\"t\".\"x\" > 0) THEN y ELSE 0 END"
+        );
+
+        let cast = Expr::Cast {
+            expr: Box::new(Expr::UnaryOp {
+                op: "-".to_string(),
+                expr: Box::new(col("x")),
+                resolved: Resolved::default(),
+            }),
+            type_name: "numeric".to_string(),
+            resolved: Resolved::default(),
+        };
+        assert_eq!(cast.to_sql(), "CAST((- x) AS numeric)");
+        assert_eq!(
+            Expr::Array(vec![col("a"), col("b")]).to_sql(),
+            "ARRAY[a, b]"
+        );
+        assert_eq!(Expr::Row(vec![col("a")]).to_sql(), "ROW(a)");
+
+        let sub = Expr::SubLink {
+            kind: SubLinkKind::In,
+            test: Some(Box::new(col("id"))),
+            query: subquery("SELECT id FROM t", vec![col("id")]),
+        };
+        assert_eq!(sub.to_sql(), "id IN (SELECT id FROM t)");
+    }
+
+    #[test]
+    fn test_typed_expr_referenced_columns() {
+        let expr = Expr::Cast {
+            expr: Box::new(case_on(
+                Expr::BinaryOp {
+                    op: "=".to_string(),
+                    left: Box::new(qualified_col("o", "status")),
+                    right: Box::new(Expr::Literal("'open'".to_string())),
+                    resolved: Resolved::default(),
+                },
+                Expr::Array(vec![col("amount"), qualified_col("o", "status")]),
+            )),
+            type_name: "text".to_string(),
+            resolved: Resolved::default(),
+        };
+        assert_eq!(
+            expr.referenced_columns(),
+            Some(vec![
+                (Some("o".to_string()), "status".to_string()),
+                (None, "amount".to_string()),
+            ])
+        );
+
+        let sub = Expr::SubLink {
+            kind: SubLinkKind::Scalar,
+            test: None,
+            query: subquery(
+                "SELECT max(x) FROM t",
+                vec![Expr::FuncCall {
+                    func_name: "max".to_string(),
+                    args: vec![col("x")],
+                    resolved: Resolved::default(),
+                }],
+            ),
+        };
+        assert_eq!(sub.referenced_columns(), None);
+        assert_eq!(Expr::Raw("x + 1".to_string()).referenced_columns(), None);
+    }
+
+    #[test]
+    fn test_typed_expr_strip_qualifier_recurses_into_case() {
+        let expr = case_on(qualified_col("t", "flag"), qualified_col("t", "v"));
+        assert_eq!(
+            expr.strip_qualifier().to_sql(),
+            "CASE WHEN flag THEN v ELSE 0 END"
+        );
+    }
+
+    #[test]
+    fn test_typed_expr_rewrite_aliases_recurses_into_cast() {
+        let expr = Expr::Cast {
+            expr: Box::new(qualified_col("l", "id")),
+            type_name: "bigint".to_string(),
+            resolved: Resolved::default(),
+        };
+        assert_eq!(
+            expr.rewrite_aliases("l", "dl", "r", "r_snap").to_sql(),
+            "CAST(\"dl\".\"id\" AS bigint)"
+        );
+    }
+
+    #[test]
+    fn test_collect_volatilities_case_is_structural() {
+        // A CASE over columns and literals needs no re-parse (the test
+        // stub for Raw would report volatile).
+        let expr = case_on(col("flag"), Expr::Literal("1".to_string()));
+        assert_eq!(worst_volatility(&expr).unwrap(), 'i');
+
+        let with_func = case_on(
+            col("flag"),
+            Expr::FuncCall {
+                func_name: "random".to_string(),
+                args: vec![],
+                resolved: Resolved::default(),
+            },
+        );
+        assert_eq!(worst_volatility(&with_func).unwrap(), 'v');
+    }
+
+    #[test]
+    fn test_contains_clock_expr_inside_cast() {
+        let expr = Expr::BinaryOp {
+            op: ">".to_string(),
+            left: Box::new(col("ts")),
+            right: Box::new(Expr::Cast {
+                expr: Box::new(Expr::FuncCall {
+                    func_name: "now".to_string(),
+                    args: vec![],
+                    resolved: Resolved::default(),
+                }),
+                type_name: "date".to_string(),
+                resolved: Resolved::default(),
+            }),
+            resolved: Resolved::default(),
+        };
+        assert!(contains_clock_expr(&expr));
+    }
+
+    #[test]
+    fn test_pgvector_distance_to_typed_cast_is_row_local() {
+        let vector = Expr::Cast {
+            expr: Box::new(Expr::Literal("'[1,0,0]'".to_string())),
+            type_name: "vector(3)".to_string(),
+            resolved: Resolved::default(),
+        };
+        let tree = distance_filter("<=>", col("embedding"), vector);
+        assert!(check_ivm_support(&tree).is_ok());
+    }
+
+    #[test]
+    fn test_collect_volatilities_uses_resolved_unary_and_cast() {
+        // The name-based operator stub reports immutable; the resolution
+        // recorded at parse time wins.
+        let unary = Expr::UnaryOp {
+            op: "@@".to_string(),
+            expr: Box::new(col("x")),
+            resolved: Resolved {
+                oid: Some(90_001),
+                volatility: Some('v'),
+            },
+        };
+        assert_eq!(worst_volatility(&unary).unwrap(), 'v');
+
+        let cast = Expr::Cast {
+            expr: Box::new(col("ts")),
+            type_name: "timestamptz".to_string(),
+            resolved: Resolved {
+                oid: Some(1184),
+                volatility: Some('s'),
+            },
+        };
+        assert_eq!(worst_volatility(&cast).unwrap(), 's');
+
+        let unresolved = Expr::Cast {
+            expr: Box::new(col("x")),
+            type_name: "bigint".to_string(),
+            resolved: Resolved::default(),
+        };
+        assert_eq!(worst_volatility(&unresolved).unwrap(), 'i');
+    }
+
+    #[test]
+    fn test_collect_volatilities_uses_resolved_binary_and_function() {
+        let binary = Expr::BinaryOp {
+            op: "+".to_string(),
+            left: Box::new(col("x")),
+            right: Box::new(col("y")),
+            resolved: Resolved {
+                oid: Some(90_002),
+                volatility: Some('v'),
+            },
+        };
+        assert_eq!(worst_volatility(&binary).unwrap(), 'v');
+
+        // The name-based function stub reports volatile; the resolved
+        // overload is immutable.
+        let call = Expr::FuncCall {
+            func_name: "lower".to_string(),
+            args: vec![col("name")],
+            resolved: Resolved {
+                oid: Some(870),
+                volatility: Some('i'),
+            },
+        };
+        assert_eq!(worst_volatility(&call).unwrap(), 'i');
+
+        let unresolved = Expr::FuncCall {
+            func_name: "lower".to_string(),
+            args: vec![col("name")],
+            resolved: Resolved::default(),
+        };
+        assert_eq!(worst_volatility(&unresolved).unwrap(), 'v');
+    }
+
+    #[test]
+    fn test_is_distinct_from_is_a_typed_binary_op() {
+        let expr = Expr::BinaryOp {
+            op: "IS NOT DISTINCT FROM".to_string(),
+            left: Box::new(qualified_col("a", "x")),
+            right: Box::new(col("y")),
+            resolved: Resolved::default(),
+        };
+        assert_eq!(expr.to_sql(), "(\"a\".\"x\" IS NOT DISTINCT FROM y)");
+        assert_eq!(
+            expr.strip_qualifier().to_sql(),
+            "(x IS NOT DISTINCT FROM y)"
+        );
+        assert_eq!(
+            expr.referenced_columns(),
+            Some(vec![
+                (Some("a".to_string()), "x".to_string()),
+                (None, "y".to_string()),
+            ])
+        );
+        assert_eq!(worst_volatility(&expr).unwrap(), 'i');
+    }
+
+    #[test]
+    fn test_sublink_body_is_walked_not_scanned() {
+        let random = Expr::FuncCall {
+            func_name: "random".to_string(),
+            args: vec![],
+            resolved: Resolved::default(),
+        };
+        let sub = Expr::SubLink {
+            kind: SubLinkKind::Exists,
+            test: None,
+            query: subquery("SELECT 1 FROM t WHERE random() > 0.5", vec![random]),
+        };
+        assert_eq!(worst_volatility(&sub).unwrap(), 'v');
+        assert!(!contains_clock_expr(&sub));
+
+        // The SQL text mentions `now(` only inside a string literal.
+        let sub = Expr::SubLink {
+            kind: SubLinkKind::Scalar,
+            test: None,
+            query: subquery(
+                "SELECT max(x) FROM t WHERE note = 'now('",
+                vec![Expr::BinaryOp {
+                    op: "=".to_string(),
+                    left: Box::new(col("note")),
+                    right: Box::new(Expr::Literal("'now('".to_string())),
+                    resolved: Resolved::default(),
+                }],
+            ),
+        };
+        assert!(!contains_clock_expr(&sub));
+
+        let sub = Expr::SubLink {
+            kind: SubLinkKind::Scalar,
+            test: None,
+            query: subquery(
+                "SELECT max(x) FROM t WHERE ts > now()",
+                vec![Expr::BinaryOp {
+                    op: ">".to_string(),
+                    left: Box::new(col("ts")),
+                    right: Box::new(Expr::FuncCall {
+                        func_name: "now".to_string(),
+                        args: vec![],
+                        resolved: Resolved::default(),
+                    }),
+                    resolved: Resolved::default(),
+                }],
+            ),
+        };
+        assert!(contains_clock_expr(&sub));
+    }
+
+    #[test]
+    fn test_pgvector_distance_in_sublink_body() {
+        let body_distance = |right: Expr| Expr::SubLink {
+            kind: SubLinkKind::Exists,
+            test: None,
+            query: subquery(
+                "SELECT 1 FROM refs r WHERE ...",
+                vec![Expr::BinaryOp {
+                    op: "<".to_string(),
+                    left: Box::new(Expr::BinaryOp {
+                        op: "<->".to_string(),
+                        left: Box::new(qualified_col("r", "embedding")),
+                        right: Box::new(right),
+                        resolved: Resolved::default(),
+                    }),
+                    right: Box::new(Expr::Literal("0.3".to_string())),
+                    resolved: Resolved::default(),
+                }],
+            ),
+        };
+        let filter = |predicate: Expr| OpTree::Filter {
+            predicate,
+            child: Box::new(scan_node("docs", 1, &["id", "embedding"])),
+        };
+
+        let constant = Expr::Cast {
+            expr: Box::new(Expr::Literal("'[1,0,0]'".to_string())),
+            type_name: "vector".to_string(),
+            resolved: Resolved::default(),
+        };
+        assert!(check_ivm_support(&filter(body_distance(constant))).is_ok());
+
+        let correlated = qualified_col("docs", "embedding");
+        let err = check_ivm_support(&filter(body_distance(correlated))).unwrap_err();
+        assert!(err.to_string().contains("pgvector distance operator"));
+    }
+
     #[test]
     fn test_expr_funccall_coalesce_format() {
         let expr = Expr::FuncCall {
@@ -3519,6 +4419,7 @@ mod tests {
                 },
                 Expr::Raw("0".to_string()),
             ],
+            resolved: Resolved::default(),
         };
         assert_eq!(expr.to_sql(), "COALESCE(a, b, 0)");
     }
@@ -3534,6 +4435,7 @@ mod tests {
                 },
                 Expr::Raw("0".to_string()),
             ],
+            resolved: Resolved::default(),
         };
         assert_eq!(expr.to_sql(), "NULLIF(a, 0)");
     }
@@ -3556,6 +4458,7 @@ mod tests {
                     column_name: "c".to_string(),
                 },
             ],
+            resolved: Resolved::default(),
         };
         let l = Expr::FuncCall {
             func_name: "LEAST".to_string(),
@@ -3573,6 +4476,7 @@ mod tests {
                     column_name: "c".to_string(),
                 },
             ],
+            resolved: Resolved::default(),
         };
         assert_eq!(g.to_sql(), "GREATEST(a, b, c)");
         assert_eq!(l.to_sql(), "LEAST(a, b, c)");
@@ -3943,6 +4847,7 @@ mod tests {
             op: "+".to_string(),
             left: Box::new(col("a")),
             right: Box::new(Expr::Literal("1".to_string())),
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         // collect_volatilities with no FuncCall should leave worst at 'i'.
@@ -3967,6 +4872,7 @@ mod tests {
                 op: ">".to_string(),
                 left: Box::new(col("amount")),
                 right: Box::new(Expr::Literal("100".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(make_scan(1, "orders", "o", &["id", "amount"])),
         };
@@ -3993,8 +4899,10 @@ mod tests {
             left: Box::new(Expr::FuncCall {
                 func_name: "random".to_string(),
                 args: vec![],
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::Literal("0.5".to_string())),
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         collect_volatilities(&expr, &mut worst).unwrap();
@@ -4011,14 +4919,194 @@ mod tests {
                 op: "*".to_string(),
                 left: Box::new(col("a")),
                 right: Box::new(Expr::Literal("2".to_string())),
+                resolved: Resolved::default(),
             }),
             right: Box::new(col("b")),
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         collect_volatilities(&expr, &mut worst).unwrap();
         assert_eq!(worst, 'i');
     }
 
+    // ── SLIDE-1: sliding-window filter recognition ─────────────────
+
+    fn now_call() -> Expr {
+        Expr::FuncCall {
+            func_name: "now".to_string(),
+            args: vec![],
+            resolved: Resolved::default(),
+        }
+    }
+
+    fn one_hour_ago() -> Expr {
+        Expr::BinaryOp {
+            op: "-".to_string(),
+            left: Box::new(now_call()),
+            right: Box::new(Expr::Raw("CAST('1 hour' AS interval)".to_string())),
+            resolved: Resolved::default(),
+        }
+    }
+
+    fn cmp(op: &str, left: Expr, right: Expr) -> Expr {
+        Expr::BinaryOp {
+            op: op.to_string(),
+            left: Box::new(left),
+            right: Box::new(right),
+            resolved: Resolved::default(),
+        }
+    }
+
+    #[test]
+    fn test_is_clock_expr_variants() {
+        assert!(is_clock_expr(&now_call()));
+        assert!(is_clock_expr(&Expr::FuncCall {
+            func_name: "pg_catalog.transaction_timestamp".to_string(),
+            args: vec![],
+            resolved: Resolved::default()
+        }));
+        assert!(is_clock_expr(&Expr::Raw("CURRENT_TIMESTAMP".to_string())));
+        assert!(is_clock_expr(&Expr::Raw("CURRENT_DATE".to_string())));
+        assert!(is_clock_expr(&Expr::Raw("LOCALTIMESTAMP".to_string())));
+        // Not stable within a refresh — never treated as the refresh clock.
+        assert!(!is_clock_expr(&Expr::FuncCall {
+            func_name: "clock_timestamp".to_string(),
+            args: vec![],
+            resolved: Resolved::default()
+        }));
+        assert!(!is_clock_expr(&col("ts")));
+    }
+
+    #[test]
+    fn test_sliding_window_predicate_recognised() {
+        assert!(is_sliding_window_predicate(&cmp(
+            ">",
+            col("ts"),
+            one_hour_ago()
+        )));
+        assert!(is_sliding_window_predicate(&cmp(
+            "<=",
+            Expr::Raw("CURRENT_DATE".to_string()),
+            col("due")
+        )));
+        // Clock-free conjuncts are allowed alongside the window.
+        let pred = cmp(
+            "AND",
+            cmp("=", col("kind"), Expr::Literal("'click'".to_string())),
+            cmp(">=", col("ts"), one_hour_ago()),
+        );
+        assert!(is_sliding_window_predicate(&pred));
+    }
+
+    #[test]
+    fn test_sliding_comparisons_put_clock_bound_on_the_right() {
+        let pred = cmp(
+            "AND",
+            cmp("=", col("kind"), Expr::Literal("'click'".to_string())),
+            cmp(
+                "AND",
+                cmp(">=", col("ts"), one_hour_ago()),
+                cmp("<=", Expr::Raw("CURRENT_DATE".to_string()), col("due")),
+            ),
+        );
+        let comparisons: Vec<(String, &str, String)> = sliding_comparisons(&pred)
+            .into_iter()
+            .map(|(value, op, bound)| (value.to_sql(), op, bound.to_sql()))
+            .collect();
+        assert_eq!(
+            comparisons,
+            vec![
+                ("ts".to_string(), ">=", one_hour_ago().to_sql()),
+                ("due".to_string(), ">=", "CURRENT_DATE".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sliding_comparisons_skip_clock_free_conjuncts() {
+        let pred = cmp(">", col("ts"), Expr::Literal("'2026-01-01'".to_string()));
+        assert!(sliding_comparisons(&pred).is_empty());
+    }
+
+    #[test]
+    fn test_sliding_window_predicate_rejected() {
+        // No clock at all.
+        assert!(!is_sliding_window_predicate(&cmp(
+            ">",
+            col("ts"),
+            Expr::Literal("'2026-01-01'".to_string())
+        )));
+        // Equality is not a window.
+        assert!(!is_sliding_window_predicate(&cmp(
+            "=",
+            col("ts"),
+            now_call()
+        )));
+        // Clock under OR cannot be maintained conjunct by conjunct.
+        let pred = cmp(
+            "OR",
+            cmp(">", col("ts"), one_hour_ago()),
+            cmp("=", col("pinned"), Expr::Literal("true".to_string())),
+        );
+        assert!(!is_sliding_window_predicate(&pred));
+        // Offset must be constant.
+        let shifted = cmp("-", now_call(), col("ttl"));
+        assert!(!is_sliding_window_predicate(&cmp(">", col("ts"), shifted)));
+    }
+
+    #[test]
+    fn test_sliding_window_filter_needs_pk_scan() {
+        let pred = cmp(">", col("ts"), one_hour_ago());
+        let keyed = scan_with_pk("e", 1, &["id", "ts"], &["id"]);
+        let keyless = scan_node("e", 1, &["id", "ts"]);
+        assert!(is_sliding_window_filter(&pred, &keyed));
+        assert!(!is_sliding_window_filter(&pred, &keyless));
+    }
+
+    #[test]
+    fn test_substitute_clock_preserves_types() {
+        let clock = "t0()";
+        let pred = cmp(">", col("ts"), one_hour_ago());
+        assert_eq!(
+            substitute_clock(&pred, clock).to_sql(),
+            "(ts > ((t0()) - CAST('1 hour' AS interval)))"
+        );
+        let date = cmp(">=", col("d"), Expr::Raw("CURRENT_DATE".to_string()));
+        assert_eq!(
+            substitute_clock(&date, clock).to_sql(),
+            "(d >= CAST(t0() AS date))"
+        );
+        let local = cmp(">=", col("ts"), Expr::Raw("LOCALTIMESTAMP".to_string()));
+        assert_eq!(
+            substitute_clock(&local, clock).to_sql(),
+            "(ts >= CAST(t0() AS timestamp))"
+        );
+    }
+
+    #[test]
+    fn test_volatility_outside_sliding_windows_ignores_clock() {
+        let tree = OpTree::Filter {
+            predicate: cmp(">", col("ts"), one_hour_ago()),
+            child: Box::new(scan_with_pk("e", 1, &["id", "ts"], &["id"])),
+        };
+        let mut worst = 'i';
+        tree_collect_volatility(&tree, &mut worst).unwrap();
+        // Test stub reports every function call as volatile.
+        assert_eq!(worst, 'v');
+
+        let result = ParseResult {
+            tree,
+            cte_registry: CteRegistry::default(),
+            has_recursion: false,
+            warnings: Vec::new(),
+        };
+        assert_eq!(
+            tree_worst_volatility_outside_sliding_windows(&result).unwrap(),
+            'i'
+        );
+        assert_eq!(collect_sliding_window_filters(&result).len(), 1);
+    }
+
     // ── GROUPING SETS rewrite helpers ──────────────────────────────
 
     #[test]
@@ -4101,6 +5189,39 @@ mod tests {
     // The ROLLUP/CUBE expansion logic is verified via the compute_grouping_value
     // tests above plus E2E integration tests.
 
+    // ── GSETS-NATIVE grouping-set crossing ─────────────────────────
+
+    #[test]
+    fn test_cross_grouping_sets_plain_and_rollup() {
+        // GROUP BY a, ROLLUP(b, c) over columns [a, b, c]
+        let sets = cross_grouping_sets(&[0], &[vec![vec![1, 2], vec![1], vec![]]], 3);
+        assert_eq!(sets, Some(vec![vec![0, 1, 2], vec![0, 1], vec![0]]));
+    }
+
+    #[test]
+    fn test_cross_grouping_sets_two_specs() {
+        // GROUPING SETS ((a), (b)), GROUPING SETS ((c), ())
+        let sets = cross_grouping_sets(&[], &[vec![vec![0], vec![1]], vec![vec![2], vec![]]], 3);
+        assert_eq!(sets, Some(vec![vec![0, 2], vec![0], vec![1, 2], vec![1]]));
+    }
+
+    #[test]
+    fn test_cross_grouping_sets_duplicate_set_rejected() {
+        // GROUP BY b, ROLLUP(a), ROLLUP(a) yields (a, b) twice.
+        let rollup = vec![vec![0], vec![]];
+        assert_eq!(
+            cross_grouping_sets(&[1], &[rollup.clone(), rollup], 2),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cross_grouping_sets_too_many_sets_rejected() {
+        // Four sets over a single column cannot all be distinct.
+        let spec = vec![vec![0], vec![]];
+        assert_eq!(cross_grouping_sets(&[], &[spec.clone(), spec], 1), None);
+    }
+
     // ── S12/S13/S14 rewrite helper tests ────────────────────────────
 
     // Note: rewrite_scalar_subquery_in_where(), rewrite_sublinks_in_or(),
@@ -4286,6 +5407,7 @@ mod tests {
                 op: "=".into(),
                 left: Box::new(qualified_col("a", "id")),
                 right: Box::new(qualified_col("b", "id")),
+                resolved: Resolved::default(),
             },
             child: Box::new(cross),
         };
@@ -4323,12 +5445,15 @@ mod tests {
                 op: "=".into(),
                 left: Box::new(qualified_col("a", "x")),
                 right: Box::new(qualified_col("b", "x")),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::BinaryOp {
                 op: "=".into(),
                 left: Box::new(qualified_col("b", "y")),
                 right: Box::new(qualified_col("c", "y")),
+                resolved: Resolved::default(),
             }),
+            resolved: Resolved::default(),
         };
         let filter = OpTree::Filter {
             predicate: pred,
@@ -4383,12 +5508,15 @@ mod tests {
                 op: "=".into(),
                 left: Box::new(qualified_col("a", "id")),
                 right: Box::new(qualified_col("b", "id")),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::BinaryOp {
                 op: "=".into(),
                 left: Box::new(qualified_col("a", "name")),
                 right: Box::new(Expr::Literal("'foo'".into())),
+                resolved: Resolved::default(),
             }),
+            resolved: Resolved::default(),
         };
         let filter = OpTree::Filter {
             predicate: pred,
@@ -4424,6 +5552,7 @@ mod tests {
                 op: "=".into(),
                 left: Box::new(qualified_col("a", "id")),
                 right: Box::new(qualified_col("b", "id")),
+                resolved: Resolved::default(),
             },
             left: Box::new(a),
             right: Box::new(b),
@@ -4433,6 +5562,7 @@ mod tests {
                 op: ">".into(),
                 left: Box::new(qualified_col("a", "id")),
                 right: Box::new(Expr::Literal("10".into())),
+                resolved: Resolved::default(),
             },
             child: Box::new(join),
         };
@@ -4455,6 +5585,7 @@ mod tests {
                 op: "=".into(),
                 left: Box::new(col("x")),
                 right: Box::new(col("y")),
+                resolved: Resolved::default(),
             },
             child: Box::new(cross),
         };
@@ -4631,6 +5762,7 @@ mod tests {
                     op: "=".into(),
                     left: Box::new(qualified_col("t1", "a")),
                     right: Box::new(qualified_col("t2", "a")),
+                    resolved: Resolved::default(),
                 },
                 left: Box::new(scan_node("t1", 1, &["a", "b"])),
                 right: Box::new(scan_node("t2", 2, &["a", "c"])),
@@ -4655,6 +5787,7 @@ mod tests {
                 op: "=".into(),
                 left: Box::new(qualified_col("t1", "id")),
                 right: Box::new(qualified_col("t2", "t1_id")),
+                resolved: Resolved::default(),
             },
             left: Box::new(scan_node("t1", 1, &["id", "name", "extra"])),
             right: Box::new(scan_node("t2", 2, &["t1_id", "val", "extra"])),
@@ -4768,6 +5901,7 @@ mod tests {
         let e = Expr::FuncCall {
             func_name: "COUNT".to_string(),
             args: vec![Expr::Star { table_alias: None }],
+            resolved: Resolved::default(),
         };
         assert_eq!(e.output_name(), "COUNT(*)");
     }
@@ -4778,6 +5912,7 @@ mod tests {
             op: "+".to_string(),
             left: Box::new(col("a")),
             right: Box::new(Expr::Literal("1".to_string())),
+            resolved: Resolved::default(),
         };
         assert_eq!(e.output_name(), "(a + 1)");
     }
@@ -5003,6 +6138,7 @@ mod tests {
             op: "AND".to_string(),
             left: Box::new(Expr::Literal("a".to_string())),
             right: Box::new(Expr::Literal("b".to_string())),
+            resolved: Resolved::default(),
         };
         let parts = split_and_predicates(expr);
         assert_eq!(parts.len(), 2);
@@ -5016,8 +6152,10 @@ mod tests {
                 op: "AND".to_string(),
                 left: Box::new(Expr::Literal("a".to_string())),
                 right: Box::new(Expr::Literal("b".to_string())),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::Literal("c".to_string())),
+            resolved: Resolved::default(),
         };
         let parts = split_and_predicates(expr);
         assert_eq!(parts.len(), 3);
@@ -5029,6 +6167,7 @@ mod tests {
             op: "OR".to_string(),
             left: Box::new(Expr::Literal("a".to_string())),
             right: Box::new(Expr::Literal("b".to_string())),
+            resolved: Resolved::default(),
         };
         let parts = split_and_predicates(expr);
         assert_eq!(parts.len(), 1);
@@ -5060,6 +6199,7 @@ mod tests {
             op: "AND".to_string(),
             left: Box::new(Expr::Literal("x > 0".to_string())),
             right: Box::new(Expr::Literal("y < 10".to_string())),
+            resolved: Resolved::default(),
         };
         let parts = split_and_predicates(expr);
         assert_eq!(parts.len(), 2);
@@ -5133,6 +6273,7 @@ mod tests {
         let expr = Expr::FuncCall {
             func_name: "COALESCE".to_string(),
             args: vec![col("x"), Expr::Literal("0".to_string())],
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         collect_volatilities(&expr, &mut worst).unwrap();
@@ -5144,6 +6285,7 @@ mod tests {
         let expr = Expr::FuncCall {
             func_name: "NULLIF".to_string(),
             args: vec![col("x"), Expr::Literal("0".to_string())],
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         collect_volatilities(&expr, &mut worst).unwrap();
@@ -5156,6 +6298,7 @@ mod tests {
             let expr = Expr::FuncCall {
                 func_name: name.to_string(),
                 args: vec![col("a"), col("b")],
+                resolved: Resolved::default(),
             };
             let mut worst = 'i';
             collect_volatilities(&expr, &mut worst).unwrap();
@@ -5169,6 +6312,7 @@ mod tests {
         let expr = Expr::FuncCall {
             func_name: "random".to_string(),
             args: vec![],
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         collect_volatilities(&expr, &mut worst).unwrap();
@@ -5184,9 +6328,11 @@ mod tests {
                 Expr::FuncCall {
                     func_name: "random".to_string(),
                     args: vec![],
+                    resolved: Resolved::default(),
                 },
                 Expr::Literal("0".to_string()),
             ],
+            resolved: Resolved::default(),
         };
         let mut worst = 'i';
         collect_volatilities(&expr, &mut worst).unwrap();
@@ -5224,6 +6370,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(left),
             right: Box::new(right),
+            resolved: Resolved::default(),
         }
     }
 
@@ -5232,6 +6379,7 @@ mod tests {
             op: "AND".to_string(),
             left: Box::new(left),
             right: Box::new(right),
+            resolved: Resolved::default(),
         }
     }
 
@@ -5322,6 +6470,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(qualified_col("li", "order_id")),
             right: Box::new(qualified_col("o", "id")),
+            resolved: Resolved::default(),
         };
         let inner_alias_oids = vec![("li".to_string(), 42u32)];
         let inner_aliases: Vec<&str> = inner_alias_oids.iter().map(|(a, _)| a.as_str()).collect();
@@ -5341,6 +6490,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(qualified_col("o", "id")),
             right: Box::new(qualified_col("li", "order_id")),
+            resolved: Resolved::default(),
         };
         let inner_alias_oids = vec![("li".to_string(), 42u32)];
         let inner_aliases: Vec<&str> = inner_alias_oids.iter().map(|(a, _)| a.as_str()).collect();
@@ -5360,12 +6510,15 @@ mod tests {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("li", "order_id")),
                 right: Box::new(qualified_col("o", "id")),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::BinaryOp {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("li", "status")),
                 right: Box::new(Expr::Literal("'active'".to_string())),
+                resolved: Resolved::default(),
             }),
+            resolved: Resolved::default(),
         };
         let inner_alias_oids = vec![("li".to_string(), 42u32)];
         let inner_aliases: Vec<&str> = inner_alias_oids.iter().map(|(a, _)| a.as_str()).collect();
@@ -5383,6 +6536,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(qualified_col("a", "col")),
             right: Box::new(qualified_col("b", "col")),
+            resolved: Resolved::default(),
         };
         let inner_alias_oids: Vec<(String, u32)> = vec![("x".to_string(), 1)];
         let inner_aliases: Vec<&str> = inner_alias_oids.iter().map(|(a, _)| a.as_str()).collect();
@@ -5398,6 +6552,7 @@ mod tests {
             op: "=".to_string(),
             left: Box::new(qualified_col("li", "col1")),
             right: Box::new(qualified_col("li", "col2")),
+            resolved: Resolved::default(),
         };
         let inner_alias_oids = vec![("li".to_string(), 42u32)];
         let inner_aliases: Vec<&str> = inner_alias_oids.iter().map(|(a, _)| a.as_str()).collect();
@@ -5416,12 +6571,15 @@ mod tests {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("li", "order_id")),
                 right: Box::new(qualified_col("o", "id")),
+                resolved: Resolved::default(),
             }),
             right: Box::new(Expr::BinaryOp {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("li", "region")),
                 right: Box::new(qualified_col("o", "region")),
+                resolved: Resolved::default(),
             }),
+            resolved: Resolved::default(),
         };
         let inner_alias_oids = vec![("li".to_string(), 42u32)];
         let inner_aliases: Vec<&str> = inner_alias_oids.iter().map(|(a, _)| a.as_str()).collect();
@@ -5520,6 +6678,7 @@ mod tests {
                 op: "=".to_string(),
                 left: Box::new(qualified_col("a", "id")),
                 right: Box::new(qualified_col("b", "a_id")),
+                resolved: Resolved::default(),
             },
             left: Box::new(left),
             right: Box::new(right),
@@ -5547,6 +6706,7 @@ mod tests {
                 op: ">".to_string(),
                 left: Box::new(col("a")),
                 right: Box::new(Expr::Literal("10".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(scan),
         };
@@ -5564,6 +6724,7 @@ mod tests {
                 op: "=".to_string(),
                 left: Box::new(col("active")),
                 right: Box::new(Expr::Literal("true".to_string())),
+                resolved: Resolved::default(),
             },
             child: Box::new(scan),
         };
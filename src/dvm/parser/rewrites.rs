@@ -818,6 +818,17 @@ fn check_from_item_for_matview_or_foreign(node: *mut pg_sys::Node) -> Result<(),
     Ok(())
 }
 
+/// Whether a SELECT uses `DISTINCT ON (...)`.
+///
+/// The raw parser represents plain `DISTINCT` as a list of NIL entries and
+/// `DISTINCT ON` as a list of real expression nodes.
+pub(crate) fn has_distinct_on(select: &pg_sys::SelectStmt) -> bool {
+    !select.distinctClause.is_null()
+        && pg_list::<pg_sys::Node>(select.distinctClause)
+            .iter_ptr()
+            .any(|ptr| !ptr.is_null())
+}
+
 /// Lightweight check that rejects LIMIT / OFFSET in a defining query.
 ///
 /// Uses `raw_parser()` to parse the query and inspects the top-level
@@ -972,15 +983,10 @@ pub fn rewrite_distinct_on(query: &str) -> Result<String, PgTrickleError> {
     };
 
     // Only rewrite if DISTINCT ON (not plain DISTINCT or no DISTINCT)
-    if select.distinctClause.is_null() {
+    if !has_distinct_on(select) {
         return Ok(query.to_string());
     }
     let distinct_list = pg_list::<pg_sys::Node>(select.distinctClause);
-    let has_real_exprs = distinct_list.iter_ptr().any(|ptr| !ptr.is_null());
-    if !has_real_exprs {
-        // Plain DISTINCT (all NULLs in distinctClause) — no rewrite needed
-        return Ok(query.to_string());
-    }
 
     // Set operations with DISTINCT ON don't make sense — skip
     if select.op != pg_sys::SetOperation::SETOP_NONE {
@@ -1062,6 +1068,7 @@ pub fn rewrite_distinct_on(query: &str) -> Result<String, PgTrickleError> {
     let target_list = pg_list::<pg_sys::Node>(select.targetList);
     let mut outer_cols = Vec::new();
     let mut inner_target_parts = Vec::new();
+    let mut has_star = false;
     for node_ptr in target_list.iter_ptr() {
         // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
         if node_ptr.is_null() || !is_node_type!(node_ptr, T_ResTarget) {
@@ -1087,6 +1094,8 @@ pub fn rewrite_distinct_on(query: &str) -> Result<String, PgTrickleError> {
             .map(|e| e.to_sql())
             .unwrap_or_else(|_| "?".to_string());
 
+        has_star |= alias.is_none() && (expr_sql == "*" || expr_sql.ends_with(".*"));
+
         // For outer SELECT, use the alias if it exists, otherwise the expression
         let outer_name = alias.as_deref().unwrap_or(&expr_sql);
         outer_cols.push(format!("__pgt_do.\"{}\"", outer_name.replace('"', "\"\"")));
@@ -1166,6 +1175,18 @@ pub fn rewrite_distinct_on(query: &str) -> Result<String, PgTrickleError> {
 
     // Build the rewritten query
     let inner_targets = inner_target_parts.join(", ");
+
+    // `SELECT DISTINCT ON (k) * ...` — the outer SELECT must list the
+    // expanded columns so `__pgt_rn` stays out of the result. Resolve them
+    // from the catalog by probing the inner SELECT.
+    if has_star {
+        let probe =
+            format!("SELECT {inner_targets} FROM {from_sql}{where_sql}{group_sql}{having_sql}");
+        outer_cols = crate::dvm::get_defining_query_columns(&probe)?
+            .iter()
+            .map(|c| format!("__pgt_do.\"{}\"", c.replace('"', "\"\"")))
+            .collect();
+    }
     let outer_select = outer_cols.join(", ");
     let rewritten = format!(
         "SELECT {outer_select} FROM (\
@@ -1188,8 +1209,11 @@ pub fn rewrite_distinct_on(query: &str) -> Result<String, PgTrickleError> {
 /// equivalent `UNION ALL` of separate `GROUP BY` queries.
 ///
 /// This is called **before** the DVM parser so the downstream operator tree
-/// only ever sees plain `GROUP BY` + `UNION ALL` — no new OpTree variants
-/// are needed.
+/// only ever sees plain `GROUP BY` + `UNION ALL`.
+///
+/// Queries accepted by [`native_grouping_sets`] are returned unchanged: the
+/// parser turns them into a single [`OpTree::GroupingSets`] node, which is
+/// not subject to `pg_trickle.max_grouping_set_branches`.
 ///
 /// # Algorithm
 ///
@@ -1233,6 +1257,12 @@ pub fn rewrite_grouping_sets(query: &str) -> Result<String, PgTrickleError> {
         return Ok(query.to_string());
     }
 
+    // GSETS-NATIVE: queries the GroupingSets operator can maintain keep
+    // their grouping sets; the parser builds the operator from them.
+    if native_grouping_sets(select)?.is_some() {
+        return Ok(query.to_string());
+    }
+
     // ── Separate plain columns from GroupingSet specifications ──────
     let mut plain_col_exprs: Vec<String> = Vec::new();
     let mut grouping_set_specs: Vec<Vec<Vec<String>>> = Vec::new();
@@ -1598,6 +1628,250 @@ pub(crate) fn compute_grouping_value(args: &[String], current_set: &[String]) ->
     value
 }
 
+// ── GSETS-NATIVE: grouping sets kept for the GroupingSets operator ──
+
+/// Most grouping columns a query may have to be kept as native grouping
+/// sets (a CUBE over 16 columns has 65 536 sets). Larger queries fall back
+/// to the UNION ALL rewrite and its branch limit.
+const MAX_NATIVE_GROUPING_COLUMNS: usize = 16;
+
+/// Grouping-set layout of a query that is maintained by a single
+/// [`OpTree::GroupingSets`] node instead of the UNION ALL rewrite.
+#[derive(Debug, Clone)]
+pub(crate) struct NativeGroupingSets {
+    /// Every distinct grouping column, plain `GROUP BY` columns first.
+    pub group_by: Vec<Expr>,
+    /// Per grouping set, the indices into `group_by` it groups by.
+    pub sets: Vec<Vec<usize>>,
+    /// `GROUPING(…)` outputs of the target list.
+    pub grouping_funcs: Vec<GroupingFuncExpr>,
+}
+
+/// Decide whether a `GROUPING SETS` / `CUBE` / `ROLLUP` query can be
+/// maintained natively, and if so return its grouping-set layout.
+///
+/// Returns `None` (use the UNION ALL rewrite) unless:
+/// - the query is a plain SELECT without HAVING, DISTINCT, window
+///   functions, LIMIT/OFFSET or WHERE subqueries;
+/// - every grouping expression is a column reference with a unique output
+///   name, and there are at most [`MAX_NATIVE_GROUPING_COLUMNS`] of them;
+/// - no grouping set occurs twice (PostgreSQL emits duplicate rows for
+///   those);
+/// - every target is a grouping column under its own name, a `GROUPING()`
+///   call, or an expression containing an aggregate, and every grouping
+///   column is selected.
+pub(crate) fn native_grouping_sets(
+    select: &pg_sys::SelectStmt,
+) -> Result<Option<NativeGroupingSets>, PgTrickleError> {
+    if select.op != pg_sys::SetOperation::SETOP_NONE
+        || select.groupClause.is_null()
+        || !select.havingClause.is_null()
+        || !select.distinctClause.is_null()
+        || !select.windowClause.is_null()
+        || !select.limitCount.is_null()
+        || !select.limitOffset.is_null()
+        || node_tree_contains_sublink(select.whereClause)
+    {
+        return Ok(None);
+    }
+
+    // Plain GROUP BY columns first, then the columns of the grouping sets.
+    let group_list = pg_list::<pg_sys::Node>(select.groupClause);
+    let mut group_by: Vec<Expr> = Vec::new();
+    let mut specs: Vec<&pg_sys::GroupingSet> = Vec::new();
+    for node_ptr in group_list.iter_ptr() {
+        if node_ptr.is_null() {
+            continue;
+        }
+        if let Some(gs) = cast_node!(node_ptr, T_GroupingSet, pg_sys::GroupingSet) {
+            specs.push(gs);
+        } else if !intern_grouping_expr(node_ptr, &mut group_by)? {
+            return Ok(None);
+        }
+    }
+    let plain: Vec<usize> = (0..group_by.len()).collect();
+    for gs in &specs {
+        if !collect_grouping_set_exprs(gs, &mut group_by)? {
+            return Ok(None);
+        }
+    }
+    if specs.is_empty() || group_by.is_empty() || group_by.len() > MAX_NATIVE_GROUPING_COLUMNS {
+        return Ok(None);
+    }
+
+    let names: Vec<String> = group_by.iter().map(|e| e.output_name()).collect();
+    if (1..names.len()).any(|i| names[..i].contains(&names[i])) {
+        return Ok(None);
+    }
+    let index_of = |name: &str| names.iter().position(|n| n == name);
+
+    let mut spec_sets: Vec<Vec<Vec<usize>>> = Vec::with_capacity(specs.len());
+    for gs in specs {
+        let mut sets = Vec::new();
+        for set in expand_grouping_set(gs)? {
+            let mut indices = Vec::with_capacity(set.len());
+            for name in &set {
+                match index_of(name) {
+                    Some(i) => indices.push(i),
+                    None => return Ok(None),
+                }
+            }
+            sets.push(indices);
+        }
+        spec_sets.push(sets);
+    }
+    let Some(sets) = cross_grouping_sets(&plain, &spec_sets, group_by.len()) else {
+        return Ok(None);
+    };
+
+    // ── Classify the target list ────────────────────────────────────
+    let target_list = pg_list::<pg_sys::Node>(select.targetList);
+    if target_list_has_windows(&target_list) {
+        return Ok(None);
+    }
+    let mut selected = vec![false; group_by.len()];
+    let mut grouping_funcs = Vec::new();
+    for node_ptr in target_list.iter_ptr() {
+        // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
+        if node_ptr.is_null() || !is_node_type!(node_ptr, T_ResTarget) {
+            continue;
+        }
+        // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
+        let rt = pg_deref!(node_ptr as *const pg_sys::ResTarget);
+        if rt.val.is_null() {
+            continue;
+        }
+        let alias = if rt.name.is_null() {
+            None
+        } else {
+            Some(pg_cstr_to_str(rt.name).unwrap_or("?").to_string())
+        };
+
+        if let Some(gf) = cast_node!(rt.val, T_GroupingFunc, pg_sys::GroupingFunc) {
+            let mut args = Vec::new();
+            for name in extract_grouping_func_args(gf)? {
+                match index_of(&name) {
+                    Some(i) => args.push(i),
+                    None => return Ok(None),
+                }
+            }
+            if args.is_empty() {
+                return Ok(None);
+            }
+            grouping_funcs.push(GroupingFuncExpr {
+                args,
+                alias: alias.unwrap_or_else(|| "grouping".to_string()),
+            });
+        } else if is_node_type!(rt.val, T_ColumnRef) {
+            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+            let name = safe_node_to_expr(rt.val)?.output_name();
+            match index_of(&name) {
+                Some(i) if alias.as_deref().is_none_or(|a| a == name) => selected[i] = true,
+                _ => return Ok(None),
+            }
+        // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+        } else if !unsafe { expr_contains_agg(rt.val) } || safe_node_to_expr(rt.val).is_err() {
+            // Non-aggregate expressions over grouping columns, and
+            // expressions nesting GROUPING() (which do not deparse), are
+            // left to the UNION ALL rewrite.
+            return Ok(None);
+        }
+    }
+    if selected.contains(&false) {
+        return Ok(None);
+    }
+
+    Ok(Some(NativeGroupingSets {
+        group_by,
+        sets,
+        grouping_funcs,
+    }))
+}
+
+/// Add the column references of a `GroupingSet` (recursively) to
+/// `group_by`. Returns `false` if any grouping expression is not a plain
+/// column reference.
+fn collect_grouping_set_exprs(
+    gs: &pg_sys::GroupingSet,
+    group_by: &mut Vec<Expr>,
+) -> Result<bool, PgTrickleError> {
+    if gs.content.is_null() {
+        return Ok(true);
+    }
+    let content_list = pg_list::<pg_sys::Node>(gs.content);
+    for node_ptr in content_list.iter_ptr() {
+        if node_ptr.is_null() {
+            continue;
+        }
+        let ok = if let Some(inner) = cast_node!(node_ptr, T_GroupingSet, pg_sys::GroupingSet) {
+            collect_grouping_set_exprs(inner, group_by)?
+        } else {
+            intern_grouping_expr(node_ptr, group_by)?
+        };
+        if !ok {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Add a grouping expression to `group_by` unless it is already there.
+/// Returns `false` if the expression is not a plain column reference.
+fn intern_grouping_expr(
+    node: *mut pg_sys::Node,
+    group_by: &mut Vec<Expr>,
+) -> Result<bool, PgTrickleError> {
+    // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
+    if !is_node_type!(node, T_ColumnRef) {
+        return Ok(false);
+    }
+    // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+    let expr = safe_node_to_expr(node)?;
+    let sql = expr.to_sql();
+    if !group_by.iter().any(|e| e.to_sql() == sql) {
+        group_by.push(expr);
+    }
+    Ok(true)
+}
+
+/// Cross-product the plain `GROUP BY` columns with each grouping-set
+/// specification (`GROUP BY a, ROLLUP(b), CUBE(c)`), as PostgreSQL does.
+///
+/// Each resulting set is sorted and free of repeated columns. Returns
+/// `None` if two sets coincide — a query with duplicate grouping sets
+/// emits duplicate rows, which only the UNION ALL rewrite reproduces.
+pub(crate) fn cross_grouping_sets(
+    plain: &[usize],
+    specs: &[Vec<Vec<usize>>],
+    column_count: usize,
+) -> Option<Vec<Vec<usize>>> {
+    let mut sets: Vec<Vec<usize>> = vec![plain.to_vec()];
+    for spec in specs {
+        let mut combined = Vec::with_capacity(sets.len() * spec.len());
+        for existing in &sets {
+            for set in spec {
+                let mut merged = existing.clone();
+                merged.extend(set.iter().copied());
+                combined.push(merged);
+            }
+        }
+        // More sets than column subsets means some sets repeat.
+        if combined.len() > 1usize << column_count {
+            return None;
+        }
+        sets = combined;
+    }
+    for set in &mut sets {
+        set.sort_unstable();
+        set.dedup();
+    }
+    let mut seen = std::collections::HashSet::with_capacity(sets.len());
+    if !sets.iter().all(|set| seen.insert(set.clone())) {
+        return None;
+    }
+    Some(sets)
+}
+
 // ── Scalar subquery in WHERE → CROSS JOIN rewrite ──────────────────
 
 /// Rewrite scalar subqueries in the WHERE clause into CROSS JOINs.
@@ -1617,7 +1891,23 @@ pub(crate) fn compute_grouping_value(args: &[String], current_set: &[String]) ->
 ///
 /// Only handles EXPR_SUBLINK (scalar subqueries) in the top-level WHERE clause
 /// (both bare and under AND/OR conjunctions). Correlated scalar subqueries
-/// are NOT rewritten (they reference outer columns).
+/// whose correlation is a conjunction of equalities (`inner.k = outer.k`,
+/// with bare or alias-qualified outer columns) are decorrelated into a
+/// grouped derived table joined on the correlation keys:
+///
+/// ```sql
+/// -- Input:
+/// SELECT p.name FROM products p
+/// WHERE p.price > (SELECT avg(p2.price) FROM products p2 WHERE p2.cat = p.cat)
+/// -- Rewrite to:
+/// SELECT p.name FROM products p,
+///   (SELECT cat AS "__pgt_corr_key_1", avg(p2.price) AS "__pgt_scalar_1"
+///    FROM products p2 GROUP BY cat) AS "__pgt_sq_1"
+/// WHERE p.price > "__pgt_sq_1"."__pgt_scalar_1"
+///   AND "p"."cat" = "__pgt_sq_1"."__pgt_corr_key_1"
+/// ```
+///
+/// Other correlated shapes are left in place and rejected by the DVM parser.
 pub fn rewrite_scalar_subquery_in_where(query: &str) -> Result<String, PgTrickleError> {
     let select = match parse_first_select(query)? {
         // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
@@ -1657,18 +1947,22 @@ pub fn rewrite_scalar_subquery_in_where(query: &str) -> Result<String, PgTrickle
     let mut to_decorrelate: Vec<(
         &ScalarSubqueryExtract,
         std::collections::HashMap<String, String>,
+        Vec<String>,
     )> = Vec::new();
 
     for sq in &scalar_subqueries {
         if sq.is_correlated(&outer_tables) {
-            // Dot-qualified correlation (e.g., "outer_table.col") — skip
+            // Dot-qualified correlation (e.g., "outer_table.col") —
+            // decorrelate on the qualified equality keys.
+            let outer_aliases = sq.outer_only_tables(&outer_tables);
+            to_decorrelate.push((sq, std::collections::HashMap::new(), outer_aliases));
             continue;
         }
         let outer_cols = detect_correlation_columns(sq, &outer_tables);
         if outer_cols.is_empty() {
             non_correlated.push(sq);
         } else {
-            to_decorrelate.push((sq, outer_cols));
+            to_decorrelate.push((sq, outer_cols, Vec::new()));
         }
     }
 
@@ -1731,10 +2025,10 @@ pub fn rewrite_scalar_subquery_in_where(query: &str) -> Result<String, PgTrickle
     // Build comma-joined FROM items for decorrelated correlated subqueries.
     // Decorrelated subqueries are added as comma-separated FROM items (not
     // INNER JOIN) to avoid SQL precedence issues with comma-joins.
-    for (sq, outer_cols) in &to_decorrelate {
+    for (sq, outer_cols, outer_aliases) in &to_decorrelate {
         let idx = next_idx;
         next_idx += 1;
-        match decorrelate_scalar_subquery(sq, outer_cols, idx) {
+        match decorrelate_scalar_subquery(sq, outer_cols, outer_aliases, idx) {
             Ok(decorrelated) => {
                 extra_from_items.push(decorrelated.from_item);
                 extra_where_parts.extend(decorrelated.extra_where_conditions);
@@ -2025,6 +2319,8 @@ pub fn rewrite_correlated_scalar_in_select(query: &str) -> Result<String, PgTric
             // has_limit_or_offset is not used for the correlation check below;
             // the LIMIT guard is applied separately via inner.limitCount.
             has_limit_or_offset: !inner.limitCount.is_null() || !inner.limitOffset.is_null(),
+            column_qualifiers: collect_raw_expr_refs(st.inner_select as *mut pg_sys::Node)
+                .qualifiers,
         };
 
         // Check dot-qualified correlation first (e.g., "d.id" in subquery)
@@ -2398,8 +2694,11 @@ pub(crate) struct ScalarSubqueryExtract {
     pub(crate) inner_tables: Vec<String>,
     /// True if the inner SELECT has a LIMIT or OFFSET clause.  Such subqueries
     /// are skipped by the CROSS-JOIN and decorrelation rewrites so that the DVM
-    /// parser treats them as opaque `Expr::Raw` expressions.
+    /// parser treats them as opaque `Expr::SubLink` expressions.
     pub(crate) has_limit_or_offset: bool,
+    /// Relation qualifiers of every column reference in the subquery
+    /// (lowercase), taken from the parse tree.
+    pub(crate) column_qualifiers: Vec<String>,
 }
 
 impl ScalarSubqueryExtract {
@@ -2430,6 +2729,90 @@ impl ScalarSubqueryExtract {
         }
         false
     }
+
+    /// Outer FROM names (tables and aliases) that the subquery references
+    /// as column qualifiers but does not itself bind.
+    fn outer_only_tables(&self, outer_tables: &[String]) -> Vec<String> {
+        outer_tables
+            .iter()
+            .filter(|t| !self.inner_tables.contains(t))
+            .filter(|t| self.column_qualifiers.contains(t))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Column-reference qualifiers and aggregate calls of a raw parse tree.
+#[derive(Default)]
+pub(crate) struct RawExprRefs {
+    /// Lowercased relation qualifiers (`q` in `q.col`, `s.q.col`, `q.*`).
+    pub(crate) qualifiers: Vec<String>,
+    /// Lowercased names of aggregate calls (window calls excluded).
+    pub(crate) aggregates: Vec<String>,
+}
+
+impl RawExprRefs {
+    fn references_any(&self, qualifiers: &[String]) -> bool {
+        self.qualifiers.iter().any(|q| qualifiers.contains(q))
+    }
+}
+
+/// Collect the column qualifiers and aggregate calls under `node`,
+/// including nested subqueries.
+#[cfg(not(test))]
+pub(crate) fn collect_raw_expr_refs(node: *mut pg_sys::Node) -> RawExprRefs {
+    let mut refs = RawExprRefs::default();
+    // SAFETY: node is a raw parse tree node (or null); the context points
+    // at `refs`, which outlives the walk.
+    unsafe {
+        raw_expr_refs_walker(node, &mut refs as *mut RawExprRefs as *mut std::ffi::c_void);
+    }
+    refs
+}
+
+#[cfg(test)]
+pub(crate) fn collect_raw_expr_refs(_node: *mut pg_sys::Node) -> RawExprRefs {
+    RawExprRefs::default()
+}
+
+/// Record one parse tree node for [`collect_raw_expr_refs`], then recurse.
+///
+/// # Safety
+/// `node` must be a raw parse tree node (or null) and `context` a
+/// `*mut RawExprRefs`.
+#[cfg(not(test))]
+unsafe extern "C-unwind" fn raw_expr_refs_walker(
+    node: *mut pg_sys::Node,
+    context: *mut std::ffi::c_void,
+) -> bool {
+    if node.is_null() {
+        return false;
+    }
+    // SAFETY: context is the `RawExprRefs` passed by collect_raw_expr_refs.
+    let refs = unsafe { &mut *(context as *mut RawExprRefs) };
+    if let Some(cref) = cast_node!(node, T_ColumnRef, pg_sys::ColumnRef) {
+        let fields: Vec<*mut pg_sys::Node> =
+            pg_list::<pg_sys::Node>(cref.fields).iter_ptr().collect();
+        if fields.len() >= 2
+            && let Some(q) = cast_node!(fields[fields.len() - 2], T_String, pg_sys::String)
+            && let Ok(q) = pg_cstr_to_str(q.sval)
+        {
+            let q = q.to_lowercase();
+            if !refs.qualifiers.contains(&q) {
+                refs.qualifiers.push(q);
+            }
+        }
+    } else if let Some(fcall) = cast_node!(node, T_FuncCall, pg_sys::FuncCall)
+        // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+        && unsafe { is_agg_node(node) }
+        // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+        && let Ok(name) = unsafe { extract_func_name(fcall.funcname) }
+    {
+        refs.aggregates.push(name.to_lowercase());
+    }
+    // SAFETY: raw_expression_tree_walker_impl handles all raw parse tree
+    // node types; `context` is passed through unchanged.
+    unsafe { pg_sys::raw_expression_tree_walker_impl(node, Some(raw_expr_refs_walker), context) }
 }
 
 /// Collect table names (and aliases) from a SelectStmt's FROM clause.
@@ -2533,6 +2916,7 @@ fn collect_scalar_sublinks_in_where(
                     expr_sql,
                     inner_tables,
                     has_limit_or_offset,
+                    column_qualifiers: collect_raw_expr_refs(sublink.subselect).qualifiers,
                 });
             }
         }
@@ -2720,14 +3104,18 @@ fn flatten_and_conditions(node: *mut pg_sys::Node, out: &mut Vec<*mut pg_sys::No
     out.push(node);
 }
 
-/// Check if a WHERE condition node is an equality between a bare outer column
+/// Check if a WHERE condition node is an equality between an outer column
 /// and an inner expression. Returns the correlation info if so.
 ///
+/// The outer column is either a bare name found in `outer_columns` or a
+/// column qualified by one of `outer_aliases`.
+///
 /// # Safety
 /// Caller must ensure `node` points to a valid parse tree `Node`.
 fn check_correlation_condition(
     node: *mut pg_sys::Node,
     outer_columns: &std::collections::HashMap<String, String>,
+    outer_aliases: &[String],
 ) -> Option<CorrelationCondition> {
     // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
     if !is_node_type!(node, T_A_Expr) {
@@ -2774,6 +3162,27 @@ fn check_correlation_condition(
         });
     }
 
+    // Alias-qualified outer column on either side; the other side must
+    // not reference the outer query as well.
+    let is_outer_qualified = |e: &Expr| {
+        matches!(e, Expr::ColumnRef { table_alias: Some(a), .. }
+            if outer_aliases.contains(&a.to_lowercase()))
+    };
+    let references_outer =
+        |side: *mut pg_sys::Node| collect_raw_expr_refs(side).references_any(outer_aliases);
+    if is_outer_qualified(&left_expr) && !references_outer(aexpr.rexpr) {
+        return Some(CorrelationCondition {
+            outer_column: left_expr.to_sql(),
+            inner_expr_sql: right_expr.to_sql(),
+        });
+    }
+    if is_outer_qualified(&right_expr) && !references_outer(aexpr.lexpr) {
+        return Some(CorrelationCondition {
+            outer_column: right_expr.to_sql(),
+            inner_expr_sql: left_expr.to_sql(),
+        });
+    }
+
     None
 }
 
@@ -2791,9 +3200,13 @@ fn check_correlation_condition(
 ///             FROM partsupp, ... WHERE ... GROUP BY ps_partkey)
 ///     AS "__pgt_sq_1" ON p_partkey = "__pgt_sq_1"."__pgt_corr_key_1"
 /// ```
+///
+/// `outer_aliases` lists outer FROM names used as qualifiers inside the
+/// subquery (`p.cat`); `outer_columns` lists bare outer column names.
 fn decorrelate_scalar_subquery(
     sq: &ScalarSubqueryExtract,
     outer_columns: &std::collections::HashMap<String, String>,
+    outer_aliases: &[String],
     idx: usize,
 ) -> Result<DecorrelatedSubquery, PgTrickleError> {
     let sq_alias = format!("__pgt_sq_{idx}");
@@ -2819,11 +3232,14 @@ fn decorrelate_scalar_subquery(
     // ── Separate correlation vs. regular conditions ──────────────────
     let mut correlations: Vec<CorrelationCondition> = Vec::new();
     let mut regular_conditions: Vec<String> = Vec::new();
+    // Column qualifiers and aggregates outside the extracted equalities.
+    let mut residual_refs: Vec<RawExprRefs> = Vec::new();
 
     for cond_node in &conditions {
-        if let Some(corr) = check_correlation_condition(*cond_node, outer_columns) {
+        if let Some(corr) = check_correlation_condition(*cond_node, outer_columns, outer_aliases) {
             correlations.push(corr);
         } else {
+            residual_refs.push(collect_raw_expr_refs(*cond_node));
             // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
             let expr = safe_node_to_expr(*cond_node)
                 .map(|e| e.to_sql())
@@ -2841,6 +3257,7 @@ fn decorrelate_scalar_subquery(
     // ── Extract the original target list (scalar expression) ─────────
     let target_list = pg_list::<pg_sys::Node>(inner_select.targetList);
     let mut original_target_exprs: Vec<String> = Vec::new();
+    let mut target_refs: Vec<RawExprRefs> = Vec::new();
     for node_ptr in target_list.iter_ptr() {
         // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
         if node_ptr.is_null() || !is_node_type!(node_ptr, T_ResTarget) {
@@ -2851,6 +3268,7 @@ fn decorrelate_scalar_subquery(
         if rt.val.is_null() {
             continue;
         }
+        target_refs.push(collect_raw_expr_refs(rt.val));
         // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
         let expr = safe_node_to_expr(rt.val)
             .map(|e| e.to_sql())
@@ -2858,6 +3276,31 @@ fn decorrelate_scalar_subquery(
         original_target_exprs.push(expr);
     }
 
+    // Any outer reference left outside the extracted equalities keeps the
+    // subquery correlated, so the grouped derived table would be wrong.
+    let still_correlated = residual_refs
+        .iter()
+        .chain(&target_refs)
+        .any(|refs| refs.references_any(outer_aliases));
+    if still_correlated {
+        return Err(PgTrickleError::QueryParseError(
+            "Scalar subquery references the outer query outside equality correlations".into(),
+        ));
+    }
+
+    // The join drops outer rows whose correlation group is empty. That
+    // matches the subquery only when it yields NULL for an empty group,
+    // which COUNT does not.
+    if target_refs.iter().any(|refs| {
+        refs.aggregates
+            .iter()
+            .any(|a| a == "count" || a == "regr_count")
+    }) {
+        return Err(PgTrickleError::QueryParseError(
+            "Correlated COUNT scalar subquery cannot be decorrelated into a join".into(),
+        ));
+    }
+
     // ── FROM clause (unchanged) ──────────────────────────────────────
     let from_sql = extract_from_clause_sql(inner_select)?;
 
@@ -3195,17 +3638,22 @@ unsafe fn negate_node_sql(node: *mut pg_sys::Node) -> Result<String, PgTrickleEr
 /// -- Input:
 /// SELECT * FROM t WHERE status = 'active' OR EXISTS (SELECT 1 FROM vip WHERE vip.id = t.id)
 /// -- Rewrite to:
-/// SELECT * FROM t WHERE status = 'active'
-/// UNION
-/// SELECT t.* FROM t WHERE EXISTS (SELECT 1 FROM vip WHERE vip.id = t.id)
+/// SELECT * FROM t WHERE (status = 'active')
+/// UNION ALL
+/// SELECT * FROM t WHERE (EXISTS (SELECT 1 FROM vip WHERE vip.id = t.id)
+///                        AND (status = 'active') IS NOT TRUE)
 /// ```
 ///
 /// This is called **before** the DVM parser so the downstream operator tree
 /// only ever sees non-OR SubLinks which are already handled by the
 /// SemiJoin/AntiJoin extraction in `extract_where_sublinks()`.
 ///
-/// The UNION (not UNION ALL) handles deduplication of rows that match
-/// multiple OR arms.
+/// Each branch excludes the rows accepted by earlier branches (see
+/// [`disjoint_or_arms`]), so a row matching several arms is emitted once
+/// and UNION ALL keeps duplicate source rows intact. Aggregating queries
+/// apply the grouping on top of the filtered rows (see
+/// [`assemble_or_union`]). When the arms cannot be made disjoint the query
+/// is returned unchanged and the DVM parser rejects it.
 pub fn rewrite_sublinks_in_or(query: &str) -> Result<String, PgTrickleError> {
     let select = match parse_first_select(query)? {
         // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
@@ -3255,67 +3703,24 @@ pub fn rewrite_sublinks_in_or(query: &str) -> Result<String, PgTrickleError> {
 
     // ── Extract OR arms ────────────────────────────────────────────
     let args = pg_list::<pg_sys::Node>(boolexpr.args);
-    let mut arm_exprs: Vec<String> = Vec::new();
-    for arg_ptr in args.iter_ptr() {
-        if arg_ptr.is_null() {
-            continue;
-        }
-        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        let expr = safe_node_to_expr(arg_ptr)
-            .map(|e| e.to_sql())
-            .unwrap_or_else(|_| "TRUE".to_string());
-        arm_exprs.push(expr);
-    }
+    let arms: Vec<OrArm> = args
+        .iter_ptr()
+        .filter(|a| !a.is_null())
+        .map(OrArm::from_node)
+        .collect();
 
-    if arm_exprs.len() < 2 {
+    if arms.len() < 2 {
         return Ok(query.to_string());
     }
 
-    // Build query components (shared across all UNION branches)
-    let from_sql = extract_from_clause_sql(select)?;
-
-    let target_sql = {
-        let target_list = pg_list::<pg_sys::Node>(select.targetList);
-        let mut targets = Vec::new();
-        for node_ptr in target_list.iter_ptr() {
-            // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
-            if node_ptr.is_null() || !is_node_type!(node_ptr, T_ResTarget) {
-                continue;
-            }
-            // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-            let rt = pg_deref!(node_ptr as *const pg_sys::ResTarget);
-            if rt.val.is_null() {
-                continue;
-            }
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let expr = safe_node_to_expr(rt.val)
-                .map(|e| e.to_sql())
-                .unwrap_or_else(|_| "NULL".to_string());
-            let alias_part = if !rt.name.is_null() {
-                let name = pg_cstr_to_str(rt.name).unwrap_or("?");
-                format!(" AS \"{}\"", name.replace('"', "\"\""))
-            } else {
-                String::new()
-            };
-            targets.push(format!("{expr}{alias_part}"));
-        }
-        targets.join(", ")
+    let (branch_wheres, disjoint) = match disjoint_or_arms(&arms) {
+        Some(wheres) => (wheres, true),
+        None => (arms.into_iter().map(|a| a.sql).collect(), false),
     };
 
-    // GROUP BY / HAVING / ORDER BY
-    let group_sql = deparse_group_clause(select);
-    let having_sql = deparse_having_clause(select);
-    let order_sql = deparse_order_clause(select);
-
-    // Build UNION branches — one per OR arm
-    let mut branches: Vec<String> = Vec::new();
-    for arm in &arm_exprs {
-        branches.push(format!(
-            "SELECT {target_sql} FROM {from_sql} WHERE {arm}{group_sql}{having_sql}"
-        ));
-    }
-
-    let rewritten = format!("{}{order_sql}", branches.join(" UNION "));
+    let Some(rewritten) = assemble_or_union(select, &branch_wheres, disjoint)? else {
+        return Ok(query.to_string());
+    };
 
     pgrx::debug1!(
         "[pg_trickle] Rewrote SubLinks-in-OR to UNION: {}",
@@ -3325,51 +3730,262 @@ pub fn rewrite_sublinks_in_or(query: &str) -> Result<String, PgTrickleError> {
     Ok(rewritten)
 }
 
-/// Handle AND conjunction (at any nesting depth) where one or more arms
-/// are OR expressions containing sublinks.
+/// One arm of an OR that contains SubLinks, deparsed for the UNION rewrite.
+struct OrArm {
+    sql: String,
+    has_sublink: bool,
+    /// SQL that holds exactly when the arm is not TRUE and that the anti-/
+    /// semi-join extraction can evaluate. Plain arms use `IS NOT TRUE`
+    /// instead; other SubLink arms have no usable negation.
+    not_true_sql: Option<String>,
+}
+
+impl OrArm {
+    fn from_node(node: *mut pg_sys::Node) -> Self {
+        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+        let sql = safe_node_to_expr(node)
+            .map(|e| e.to_sql())
+            .unwrap_or_else(|_| "TRUE".to_string());
+        let has_sublink = node_tree_contains_sublink(node);
+        let not_true_sql = if has_sublink {
+            sublink_arm_not_true_sql(node, &sql)
+        } else {
+            None
+        };
+        OrArm {
+            sql,
+            has_sublink,
+            not_true_sql,
+        }
+    }
+}
+
+/// "Not TRUE" condition for an OR arm that is a bare EXISTS / IN SubLink or
+/// a NOT EXISTS.
 ///
-/// ```sql
-/// -- Input (single OR+sublink conjunct):
-/// SELECT * FROM t WHERE a > 10 AND (status = 'active' OR EXISTS (...))
-/// -- Rewrite to:
-/// SELECT * FROM t WHERE a > 10 AND status = 'active'
-/// UNION
-/// SELECT * FROM t WHERE a > 10 AND EXISTS (...)
+/// `x IN (...)` is NULL when no value matches but the subquery yields a
+/// NULL, so a bare arm is guarded with `(<arm>) IS NOT TRUE`, which the
+/// extraction turns into a plain-equality anti-join. `NOT EXISTS` is never
+/// NULL, so its guard is the EXISTS itself. `NOT (x IN (...))` is not TRUE
+/// also when the IN is NULL, which no semi-join expresses, so it returns
+/// `None` together with ALL and scalar SubLinks.
+fn sublink_arm_not_true_sql(node: *mut pg_sys::Node, sql: &str) -> Option<String> {
+    let sublink_type =
+        |n: *mut pg_sys::Node| cast_node!(n, T_SubLink, pg_sys::SubLink).map(|sl| sl.subLinkType);
+    match sublink_type(node) {
+        Some(pg_sys::SubLinkType::EXISTS_SUBLINK | pg_sys::SubLinkType::ANY_SUBLINK) => {
+            return Some(format!("({sql}) IS NOT TRUE"));
+        }
+        Some(_) => return None,
+        None => {}
+    }
+    let be = cast_node!(node, T_BoolExpr, pg_sys::BoolExpr)?;
+    if be.boolop != pg_sys::BoolExprType::NOT_EXPR {
+        return None;
+    }
+    let args = pg_list::<pg_sys::Node>(be.args);
+    let inner = args.head().filter(|_| args.len() == 1)?;
+    if sublink_type(inner) != Some(pg_sys::SubLinkType::EXISTS_SUBLINK) {
+        return None;
+    }
+    // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+    safe_node_to_expr(inner).ok().map(|e| e.to_sql())
+}
+
+/// Make OR arms mutually exclusive so their UNION ALL keeps each row once.
 ///
-/// -- Input (multiple OR+sublink conjuncts):
-/// SELECT * FROM t WHERE (a OR EXISTS(s1)) AND (b OR NOT EXISTS(s2))
-/// -- Rewrite to cartesian product of UNION branches:
-/// SELECT * FROM t WHERE a AND b
-/// UNION
-/// SELECT * FROM t WHERE a AND NOT EXISTS(s2)
-/// UNION
-/// SELECT * FROM t WHERE EXISTS(s1) AND b
-/// UNION
-/// SELECT * FROM t WHERE EXISTS(s1) AND NOT EXISTS(s2)
-/// ```
+/// Plain arms are merged into one leading arm; every later arm is guarded
+/// by the negation of the arms before it:
 ///
-/// The `where_node` argument is the entire WHERE clause node (typically a
-/// BoolExpr AND chain). The function flattens all nested AND layers before
-/// searching for OR arms, so arbitrarily deep `AND(AND(OR(...)))` nesting
-/// is handled correctly.
+/// ```sql
+/// a OR EXISTS (s1) OR x IN (s2)
+/// -- becomes the branch predicates
+/// (a)
+/// (EXISTS (s1) AND (a) IS NOT TRUE)
+/// (x IN (s2) AND (a) IS NOT TRUE AND (EXISTS (s1)) IS NOT TRUE)
+/// ```
 ///
-/// A combinatorial guard limits expansion to 16 UNION branches (4 binary
-/// OR+sublink conjuncts). Queries exceeding this produce an error directing
-/// the user to simplify the WHERE clause.
-fn rewrite_and_with_or_sublinks(
-    select: &pg_sys::SelectStmt,
-    where_node: *mut pg_sys::Node,
-) -> Result<String, PgTrickleError> {
-    const MAX_UNION_BRANCHES: usize = 16;
-
-    // Flatten the entire AND chain into a list of atomic conjuncts.
+/// An arm without a usable negation goes last. Returns `None` when more
+/// than one arm lacks a negation.
+fn disjoint_or_arms(arms: &[OrArm]) -> Option<Vec<String>> {
+    let plain: Vec<&str> = arms
+        .iter()
+        .filter(|a| !a.has_sublink)
+        .map(|a| a.sql.as_str())
+        .collect();
+    let negatable: Vec<&OrArm> = arms
+        .iter()
+        .filter(|a| a.has_sublink && a.not_true_sql.is_some())
+        .collect();
+    let opaque: Vec<&OrArm> = arms
+        .iter()
+        .filter(|a| a.has_sublink && a.not_true_sql.is_none())
+        .collect();
+    if opaque.len() > 1 {
+        return None;
+    }
+
+    let mut wheres = Vec::with_capacity(arms.len());
+    let mut guards: Vec<String> = Vec::new();
+    let guarded = |sql: &str, guards: &[String]| {
+        if guards.is_empty() {
+            format!("({sql})")
+        } else {
+            format!("({sql} AND {})", guards.join(" AND "))
+        }
+    };
+
+    if !plain.is_empty() {
+        let merged = plain
+            .iter()
+            .map(|p| format!("({p})"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        wheres.push(format!("({merged})"));
+        guards.push(format!("({merged}) IS NOT TRUE"));
+    }
+    for arm in negatable {
+        wheres.push(guarded(&arm.sql, &guards));
+        if let Some(not_true) = &arm.not_true_sql {
+            guards.push(not_true.clone());
+        }
+    }
+    for arm in opaque {
+        wheres.push(guarded(&arm.sql, &guards));
+    }
+    Some(wheres)
+}
+
+/// Build the UNION query for the OR+sublink rewrite from per-branch WHERE
+/// predicates.
+///
+/// Disjoint branches are combined with UNION ALL, so duplicate source rows
+/// are preserved. Overlapping branches are only combined for a
+/// `SELECT DISTINCT`, where UNION gives the same result; otherwise a UNION
+/// would merge legitimate duplicates and `None` is returned.
+///
+/// Grouping, aggregates and window functions must see every qualifying
+/// row exactly once, so they cannot run per branch. For a single-relation
+/// FROM with disjoint branches, the branches select the relation's rows
+/// into a derived table that keeps its alias, and the original target
+/// list, GROUP BY and HAVING are applied on top. Other shapes return
+/// `None` and are left to the DVM parser.
+fn assemble_or_union(
+    select: &pg_sys::SelectStmt,
+    branch_wheres: &[String],
+    disjoint: bool,
+) -> Result<Option<String>, PgTrickleError> {
+    let from_sql = extract_from_clause_sql(select)?;
+    let target_sql = deparse_select_target_list(select);
+    let group_sql = deparse_group_clause(select);
+    let having_sql = deparse_having_clause(select);
+    let order_sql = deparse_order_clause(select);
+    let is_distinct = !select.distinctClause.is_null();
+
+    let target_lower = target_sql.to_lowercase();
+    let aggregates_rows = !group_sql.is_empty()
+        || !having_sql.is_empty()
+        || !select.windowClause.is_null()
+        || expr_has_aggregate(&target_sql)
+        || contains_word_boundary(&target_lower, "over");
+
+    if !aggregates_rows {
+        let set_op = match (disjoint, is_distinct) {
+            (true, false) => " UNION ALL ",
+            (_, true) => " UNION ",
+            (false, false) => return Ok(None),
+        };
+        let branches: Vec<String> = branch_wheres
+            .iter()
+            .map(|w| format!("SELECT {target_sql} FROM {from_sql} WHERE {w}"))
+            .collect();
+        return Ok(Some(format!("{}{order_sql}", branches.join(set_op))));
+    }
+
+    // Aggregating query: filter the single FROM relation, aggregate on top.
+    if !disjoint {
+        return Ok(None);
+    }
+    let from_list = pg_list::<pg_sys::Node>(select.fromClause);
+    let Some(rv) = from_list
+        .head()
+        .filter(|_| from_list.len() == 1)
+        .and_then(|n| cast_node!(n, T_RangeVar, pg_sys::RangeVar))
+    else {
+        return Ok(None);
+    };
+    let alias = if rv.alias.is_null() {
+        pg_cstr_to_str(rv.relname).unwrap_or("?")
+    } else {
+        // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
+        let alias_struct = pg_deref!(rv.alias);
+        pg_cstr_to_str(alias_struct.aliasname).unwrap_or("?")
+    };
+    let quoted_alias = format!("\"{}\"", alias.replace('"', "\"\""));
+
+    let branches: Vec<String> = branch_wheres
+        .iter()
+        .map(|w| format!("SELECT {quoted_alias}.* FROM {from_sql} WHERE {w}"))
+        .collect();
+    let distinct_sql = if is_distinct { " DISTINCT" } else { "" };
+    Ok(Some(format!(
+        "SELECT{distinct_sql} {target_sql} FROM ({}) AS {quoted_alias}{group_sql}{having_sql}{order_sql}",
+        branches.join(" UNION ALL ")
+    )))
+}
+
+/// Handle AND conjunction (at any nesting depth) where one or more arms
+/// are OR expressions containing sublinks.
+///
+/// ```sql
+/// -- Input (single OR+sublink conjunct):
+/// SELECT * FROM t WHERE a > 10 AND (status = 'active' OR EXISTS (...))
+/// -- Rewrite to:
+/// SELECT * FROM t WHERE a > 10 AND (status = 'active')
+/// UNION ALL
+/// SELECT * FROM t WHERE a > 10 AND (EXISTS (...) AND (status = 'active') IS NOT TRUE)
+///
+/// -- Input (multiple OR+sublink conjuncts):
+/// SELECT * FROM t WHERE (a OR EXISTS(s1)) AND (b OR NOT EXISTS(s2))
+/// -- Rewrite to cartesian product of UNION ALL branches (guards elided):
+/// SELECT * FROM t WHERE a AND b
+/// UNION ALL
+/// SELECT * FROM t WHERE a AND NOT EXISTS(s2)
+/// UNION ALL
+/// SELECT * FROM t WHERE EXISTS(s1) AND b
+/// UNION ALL
+/// SELECT * FROM t WHERE EXISTS(s1) AND NOT EXISTS(s2)
+/// ```
+///
+/// Each OR's arms are made disjoint before the product is taken, so the
+/// branches are mutually exclusive as well. If any OR cannot be made
+/// disjoint, the query is only rewritten for a `SELECT DISTINCT`.
+///
+/// The `where_node` argument is the entire WHERE clause node (typically a
+/// BoolExpr AND chain). The function flattens all nested AND layers before
+/// searching for OR arms, so arbitrarily deep `AND(AND(OR(...)))` nesting
+/// is handled correctly.
+///
+/// A combinatorial guard limits expansion to 16 UNION branches (4 binary
+/// OR+sublink conjuncts). Queries exceeding this produce an error directing
+/// the user to simplify the WHERE clause.
+fn rewrite_and_with_or_sublinks(
+    select: &pg_sys::SelectStmt,
+    where_node: *mut pg_sys::Node,
+) -> Result<String, PgTrickleError> {
+    const MAX_UNION_BRANCHES: usize = 16;
+
+    // Flatten the entire AND chain into a list of atomic conjuncts.
     // AND(a, AND(b, OR(...))) → [a, b, OR(...)]
     let mut conjuncts: Vec<*mut pg_sys::Node> = Vec::new();
     flatten_and_conjuncts(where_node, &mut conjuncts);
 
-    // Separate OR+sublink conjuncts from plain conjuncts.
+    // Separate OR+sublink conjuncts from plain conjuncts. Each OR's arms
+    // are made disjoint when possible so the cartesian product of the
+    // arms yields mutually exclusive branches.
     let mut or_arms_list: Vec<Vec<String>> = Vec::new();
     let mut other_conjuncts: Vec<String> = Vec::new();
+    let mut disjoint = true;
 
     for arg_ptr in conjuncts {
         if arg_ptr.is_null() {
@@ -3384,19 +4000,19 @@ fn rewrite_and_with_or_sublinks(
             {
                 // SAFETY: PgList::from_pg is safe for both null and valid list pointers from the parser.
                 let or_args = unsafe { pgrx::PgList::<pg_sys::Node>::from_pg(inner_bool.args) };
-                let mut arms = Vec::new();
-                for a in or_args.iter_ptr() {
-                    if a.is_null() {
-                        continue;
-                    }
-                    // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-                    let expr = safe_node_to_expr(a)
-                        .map(|e| e.to_sql())
-                        .unwrap_or_else(|_| "TRUE".to_string());
-                    arms.push(expr);
-                }
+                let arms: Vec<OrArm> = or_args
+                    .iter_ptr()
+                    .filter(|a| !a.is_null())
+                    .map(OrArm::from_node)
+                    .collect();
                 if arms.len() >= 2 {
-                    or_arms_list.push(arms);
+                    match disjoint_or_arms(&arms) {
+                        Some(wheres) => or_arms_list.push(wheres),
+                        None => {
+                            disjoint = false;
+                            or_arms_list.push(arms.into_iter().map(|a| a.sql).collect());
+                        }
+                    }
                     continue;
                 }
             }
@@ -3443,26 +4059,18 @@ fn rewrite_and_with_or_sublinks(
         combinations = next;
     }
 
-    // Build query components
-    let from_sql = extract_from_clause_sql(select)?;
-    let target_sql = deparse_select_target_list(select);
-    let group_sql = deparse_group_clause(select);
-    let having_sql = deparse_having_clause(select);
-    let order_sql = deparse_order_clause(select);
-
-    let mut branches: Vec<String> = Vec::with_capacity(combinations.len());
-    for combo in &combinations {
-        let combo_where = combo.join(" AND ");
-        branches.push(format!(
-            "SELECT {target_sql} FROM {from_sql} WHERE {and_prefix}{combo_where}{group_sql}{having_sql}"
-        ));
-    }
+    let branch_wheres: Vec<String> = combinations
+        .iter()
+        .map(|combo| format!("{and_prefix}{}", combo.join(" AND ")))
+        .collect();
 
-    let rewritten = format!("{}{order_sql}", branches.join(" UNION "));
+    let Some(rewritten) = assemble_or_union(select, &branch_wheres, disjoint)? else {
+        return deparse_full_select(select);
+    };
 
     pgrx::debug1!(
         "[pg_trickle] Rewrote AND(..OR-sublinks..) to {} UNION branches: {}",
-        branches.len(),
+        branch_wheres.len(),
         rewritten
     );
 
@@ -3585,439 +4193,12 @@ pub(crate) fn deparse_order_clause(select: &pg_sys::SelectStmt) -> String {
             pg_sys::SortByNulls::SORTBY_NULLS_LAST => " NULLS LAST",
             _ => "",
         };
-        sorts.push(format!("{expr}{dir}{nulls}"));
-    }
-    if sorts.is_empty() {
-        String::new()
-    } else {
-        format!(" ORDER BY {}", sorts.join(", "))
-    }
-}
-
-// ── ROWS FROM() multi-function rewrite ─────────────────────────────
-
-/// Rewrite `ROWS FROM(f1(...), f2(...), ...)` into a form the DVM parser
-/// supports.
-///
-/// **All-unnest optimisation:** when every function is `unnest`, merge into
-/// a single multi-argument `unnest(A, B, ...)` call — PostgreSQL's built-in
-/// multi-arg `unnest()` already implements zip-with-NULL-padding semantics.
-///
-/// **General case:** rewrite to an ordinal-based LEFT JOIN LATERAL chain:
-/// ```sql
-/// generate_series(1, 2147483647) WITH ORDINALITY AS __pgt_idx(v, ord)
-/// LEFT JOIN LATERAL f1(...) WITH ORDINALITY AS __pgt_f0(col, ord)
-///   ON __pgt_f0.ord = __pgt_idx.ord
-/// LEFT JOIN LATERAL f2(...) WITH ORDINALITY AS __pgt_f1(col, ord)
-///   ON __pgt_f1.ord = __pgt_idx.ord
-/// ```
-/// with a `WHERE __pgt_f0.ord IS NOT NULL OR __pgt_f1.ord IS NOT NULL`
-/// filter to stop the infinite `generate_series` once all SRFs are exhausted.
-pub fn rewrite_rows_from(query: &str) -> Result<String, PgTrickleError> {
-    let select = match parse_first_select(query)? {
-        // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-        Some(s) => pg_deref!(s),
-        None => return Ok(query.to_string()),
-    };
-
-    // Set operations — recurse into each branch.
-    if select.op != pg_sys::SetOperation::SETOP_NONE {
-        return rewrite_rows_from_in_set_op(select);
-    }
-
-    // Walk the FROM clause looking for a multi-function ROWS FROM.
-    if select.fromClause.is_null() {
-        return Ok(query.to_string());
-    }
-
-    let from_list = pg_list::<pg_sys::Node>(select.fromClause);
-    let mut found_rows_from = false;
-    for node_ptr in from_list.iter_ptr() {
-        if node_ptr.is_null() {
-            continue;
-        }
-        if has_multi_rows_from(node_ptr) {
-            found_rows_from = true;
-            break;
-        }
-    }
-
-    if !found_rows_from {
-        return Ok(query.to_string());
-    }
-
-    // ── Extract query components ─────────────────────────────────────
-
-    // SELECT list
-    // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-    let target_sql = unsafe { deparse_target_list(select.targetList)? };
-
-    // WHERE clause
-    let where_sql = if select.whereClause.is_null() {
-        None
-    } else {
-        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        let expr = unsafe { node_to_expr(select.whereClause)? };
-        Some(expr.to_sql())
-    };
-
-    // GROUP BY
-    let group_list = pg_list::<pg_sys::Node>(select.groupClause);
-    let group_sql = if group_list.is_empty() {
-        None
-    } else {
-        let mut groups = Vec::new();
-        for gn in group_list.iter_ptr() {
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let expr = unsafe { node_to_expr(gn)? };
-            groups.push(expr.to_sql());
-        }
-        Some(groups.join(", "))
-    };
-
-    // HAVING
-    let having_sql = if select.havingClause.is_null() {
-        None
-    } else {
-        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        let expr = unsafe { node_to_expr(select.havingClause)? };
-        Some(expr.to_sql())
-    };
-
-    // ORDER BY + LIMIT + OFFSET
-    let order_sql = deparse_order_clause(select);
-    let limit_sql = if select.limitCount.is_null() {
-        None
-    } else {
-        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        let expr = unsafe { node_to_expr(select.limitCount)? };
-        Some(expr.to_sql())
-    };
-    let offset_sql = if select.limitOffset.is_null() {
-        None
-    } else {
-        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        let expr = unsafe { node_to_expr(select.limitOffset)? };
-        Some(expr.to_sql())
-    };
-
-    // DISTINCT
-    let distinct_list = pg_list::<pg_sys::Node>(select.distinctClause);
-    let distinct_prefix = if !distinct_list.is_empty() {
-        "SELECT DISTINCT"
-    } else {
-        "SELECT"
-    };
-
-    // ── Rewrite FROM items ──────────────────────────────────────────
-    let mut from_parts = Vec::new();
-    for node_ptr in from_list.iter_ptr() {
-        if node_ptr.is_null() {
-            continue;
-        }
-        let sql = rewrite_from_item_rows_from(node_ptr)?;
-        from_parts.push(sql);
-    }
-
-    // ── Rebuild query ───────────────────────────────────────────────
-    let mut parts = Vec::new();
-    parts.push(format!("{distinct_prefix} {target_sql}"));
-    parts.push(format!("FROM {}", from_parts.join(", ")));
-    if let Some(w) = &where_sql {
-        parts.push(format!("WHERE {w}"));
-    }
-    if let Some(g) = &group_sql {
-        parts.push(format!("GROUP BY {g}"));
-    }
-    if let Some(h) = &having_sql {
-        parts.push(format!("HAVING {h}"));
-    }
-    if !order_sql.is_empty() {
-        parts.push(order_sql.trim().to_string());
-    }
-    if let Some(l) = &limit_sql {
-        parts.push(format!("LIMIT {l}"));
-    }
-    if let Some(o) = &offset_sql {
-        parts.push(format!("OFFSET {o}"));
-    }
-
-    let rewritten = parts.join(" ");
-    pgrx::debug1!("[pg_trickle] rewrite_rows_from: {}", rewritten);
-    Ok(rewritten)
-}
-
-/// Check if a FROM-clause node is (or contains) a multi-function ROWS FROM.
-///
-/// # Safety
-/// Caller must ensure `node` points to a valid parse tree Node.
-fn has_multi_rows_from(node: *mut pg_sys::Node) -> bool {
-    if node.is_null() {
-        return false;
-    }
-    if let Some(rf) = cast_node!(node, T_RangeFunction, pg_sys::RangeFunction) {
-        let func_list = pg_list::<pg_sys::Node>(rf.functions);
-        return rf.is_rowsfrom && func_list.len() > 1;
-    }
-    if let Some(join) = cast_node!(node, T_JoinExpr, pg_sys::JoinExpr) {
-        return has_multi_rows_from(join.larg) || has_multi_rows_from(join.rarg);
-    }
-    false
-}
-
-/// Rewrite a single FROM-clause item, replacing any multi-function ROWS FROM
-/// with its rewritten equivalent.
-///
-/// # Safety
-/// Caller must ensure `node` points to a valid parse tree Node.
-fn rewrite_from_item_rows_from(node: *mut pg_sys::Node) -> Result<String, PgTrickleError> {
-    if node.is_null() {
-        return Ok("".to_string());
-    }
-
-    if let Some(rf) = cast_node!(node, T_RangeFunction, pg_sys::RangeFunction) {
-        let func_list = pg_list::<pg_sys::Node>(rf.functions);
-
-        if !rf.is_rowsfrom || func_list.len() <= 1 {
-            // Single function — deparse normally.
-            // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-            return unsafe { deparse_from_item_to_sql(node) };
-        }
-
-        // ── Multi-function ROWS FROM ────────────────────────────────
-        // Extract each function call + its args.
-        let mut func_sqls: Vec<String> = Vec::new();
-        let mut func_names: Vec<String> = Vec::new();
-        let mut func_args: Vec<Vec<String>> = Vec::new();
-
-        for inner_node in func_list.iter_ptr() {
-            if inner_node.is_null() {
-                continue;
-            }
-            let inner_list = pg_list::<pg_sys::Node>(inner_node as *mut pg_sys::List);
-            if inner_list.is_empty() {
-                continue;
-            }
-            // INVARIANT: inner_list is non-empty (checked above), so head() cannot fail.
-            let func_node = match inner_list.head() {
-                Some(n) => n,
-                None => continue,
-            };
-            // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
-            if !is_node_type!(func_node, T_FuncCall) {
-                continue;
-            }
-            // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-            let fcall = pg_deref!(func_node as *const pg_sys::FuncCall);
-            // SAFETY: Parse-tree node pointer from raw_parser; valid within current memory context.
-            let name = unsafe { extract_func_name(fcall.funcname)? };
-            // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-            let sql = unsafe { deparse_func_call(func_node as *const pg_sys::FuncCall)? };
-
-            let args_list = pg_list::<pg_sys::Node>(fcall.args);
-            let mut args = Vec::new();
-            for n in args_list.iter_ptr() {
-                // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-                let expr = unsafe { node_to_expr(n)? };
-                args.push(expr.to_sql());
-            }
-
-            func_names.push(name);
-            func_sqls.push(sql);
-            func_args.push(args);
-        }
-
-        if func_sqls.is_empty() {
-            return Ok("(SELECT 1 WHERE false) AS __pgt_empty".to_string());
-        }
-
-        // Extract alias + column aliases from the ROWS FROM node.
-        let rows_alias = if !rf.alias.is_null() {
-            // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-            let a = pg_deref!(rf.alias);
-            pg_cstr_to_str(a.aliasname)
-                .unwrap_or("__pgt_rf")
-                .to_string()
-        } else {
-            "__pgt_rf".to_string()
-        };
-        let col_aliases = if !rf.alias.is_null() {
-            // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-            let a = pg_deref!(rf.alias);
-            extract_alias_colnames(a)?
-        } else {
-            Vec::new()
-        };
-
-        let with_ordinality = rf.ordinality;
-
-        // ── All-unnest optimisation ─────────────────────────────────
-        let all_unnest = func_names.iter().all(|n| n == "unnest");
-        if all_unnest {
-            // Merge: ROWS FROM(unnest(A), unnest(B)) → unnest(A, B) AS t(c1, c2)
-            let all_args: Vec<String> = func_args.iter().flat_map(|a| a.clone()).collect();
-            let mut result = format!("unnest({})", all_args.join(", "));
-            if with_ordinality {
-                result.push_str(" WITH ORDINALITY");
-            }
-            if !col_aliases.is_empty() {
-                result.push_str(&format!(" AS {}({})", rows_alias, col_aliases.join(", ")));
-            } else {
-                result.push_str(&format!(" AS {rows_alias}"));
-            }
-            return Ok(result);
-        }
-
-        // ── General case: ordinal-based FULL OUTER JOIN chain ────────
-        // Each SRF is wrapped in a subquery that adds row_number() so we
-        // can match them by ordinal.  We chain them via FULL OUTER JOIN
-        // which naturally handles different-length results by padding
-        // with NULLs — matching ROWS FROM semantics.
-        //
-        //   LATERAL (SELECT __pgt_f0.__pgt_c0, __pgt_f1.__pgt_c1 FROM
-        //     (SELECT val AS __pgt_c0, row_number() OVER () AS __pgt_rn
-        //      FROM f0(...) AS val) AS __pgt_f0
-        //     FULL OUTER JOIN
-        //     (SELECT val AS __pgt_c1, row_number() OVER () AS __pgt_rn
-        //      FROM f1(...) AS val) AS __pgt_f1
-        //     ON __pgt_f0.__pgt_rn = __pgt_f1.__pgt_rn
-        //   ) AS alias
-        let mut subqueries = Vec::new();
-        let mut select_parts = Vec::new();
-
-        for (i, func_sql) in func_sqls.iter().enumerate() {
-            let f_alias = format!("__pgt_f{i}");
-            let col_alias = format!("__pgt_c{i}");
-
-            // Each SRF is wrapped in a subquery with its result column
-            // given a known alias and row_number() for ordinal matching.
-            let sub = format!(
-                "(SELECT {col_alias}, row_number() OVER () AS __pgt_rn \
-                 FROM {func_sql} AS {col_alias}) AS {f_alias}"
-            );
-            subqueries.push((f_alias.clone(), sub));
-            select_parts.push(format!("{f_alias}.{col_alias}"));
-        }
-
-        // Chain subqueries via FULL OUTER JOIN on ordinal.
-        // For >2 SRFs, the join ON uses COALESCE of prior ordinals.
-        let inner_from = if subqueries.len() == 1 {
-            subqueries[0].1.clone()
-        } else {
-            let mut chain = subqueries[0].1.clone();
-            let mut coalesce_parts = vec![format!("{}.__pgt_rn", subqueries[0].0)];
-            for (f_alias, sub) in subqueries.iter().skip(1) {
-                let on_left = if coalesce_parts.len() == 1 {
-                    coalesce_parts[0].clone()
-                } else {
-                    format!("COALESCE({})", coalesce_parts.join(", "))
-                };
-                chain = format!("{chain} FULL OUTER JOIN {sub} ON {on_left} = {f_alias}.__pgt_rn");
-                coalesce_parts.push(format!("{f_alias}.__pgt_rn"));
-            }
-            chain
-        };
-
-        let inner_select = select_parts.join(", ");
-
-        // Use LATERAL so SRF arguments can reference columns from
-        // earlier FROM items (e.g. `unnest(m.arr)` where `m` is a
-        // preceding table).
-        let mut result = format!("LATERAL (SELECT {inner_select} FROM {inner_from})");
-        if with_ordinality {
-            // Ordinality for the whole ROWS FROM — add row_number outside.
-            result = format!(
-                "LATERAL (SELECT __pgt_rfo.*, row_number() OVER () AS ordinality \
-                 FROM {result} AS __pgt_rfo)"
-            );
-        }
-        if !col_aliases.is_empty() {
-            result.push_str(&format!(" AS {}({})", rows_alias, col_aliases.join(", ")));
-        } else {
-            result.push_str(&format!(" AS {rows_alias}"));
-        }
-
-        Ok(result)
-    } else if let Some(join) = cast_node!(node, T_JoinExpr, pg_sys::JoinExpr) {
-        let left = rewrite_from_item_rows_from(join.larg)?;
-        let right = rewrite_from_item_rows_from(join.rarg)?;
-        let join_type = match join.jointype {
-            pg_sys::JoinType::JOIN_LEFT => "LEFT JOIN",
-            pg_sys::JoinType::JOIN_FULL => "FULL JOIN",
-            pg_sys::JoinType::JOIN_RIGHT => "RIGHT JOIN",
-            pg_sys::JoinType::JOIN_INNER if join.quals.is_null() => "CROSS JOIN",
-            pg_sys::JoinType::JOIN_INNER => "JOIN",
-            _ => "JOIN",
-        };
-        let on_clause = if join.quals.is_null() {
-            String::new()
-        } else {
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let cond = unsafe { node_to_expr(join.quals)? };
-            format!(" ON {}", cond.to_sql())
-        };
-        Ok(format!("{left} {join_type} {right}{on_clause}"))
-    } else {
-        // Not a ROWS FROM — deparse normally using existing helper.
-        // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-        unsafe { deparse_from_item_to_sql(node) }
-    }
-}
-
-/// Handle UNION/INTERSECT/EXCEPT branches for ROWS FROM rewriting.
-fn rewrite_rows_from_in_set_op(select: &pg_sys::SelectStmt) -> Result<String, PgTrickleError> {
-    // Recurse into each branch, rewrite, then reconstruct.
-    let left = if select.larg.is_null() {
-        return Ok(String::new());
-    } else {
-        // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-        let left_select = pg_deref!(select.larg);
-        if left_select.op != pg_sys::SetOperation::SETOP_NONE {
-            rewrite_rows_from_in_set_op(left_select)?
-        } else {
-            // Leaf branch — build a temporary query and rewrite it.
-            // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-            let leaf_sql = unsafe { deparse_select_stmt_to_sql(select.larg as *const _)? };
-            rewrite_rows_from(&leaf_sql)?
-        }
-    };
-
-    let right = if select.rarg.is_null() {
-        return Ok(left);
-    } else {
-        // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-        let right_select = pg_deref!(select.rarg);
-        if right_select.op != pg_sys::SetOperation::SETOP_NONE {
-            rewrite_rows_from_in_set_op(right_select)?
-        } else {
-            // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-            let leaf_sql = unsafe { deparse_select_stmt_to_sql(select.rarg as *const _)? };
-            rewrite_rows_from(&leaf_sql)?
-        }
-    };
-
-    let op_str = match select.op {
-        pg_sys::SetOperation::SETOP_UNION if select.all => "UNION ALL",
-        pg_sys::SetOperation::SETOP_UNION => "UNION",
-        pg_sys::SetOperation::SETOP_INTERSECT if select.all => "INTERSECT ALL",
-        pg_sys::SetOperation::SETOP_INTERSECT => "INTERSECT",
-        pg_sys::SetOperation::SETOP_EXCEPT if select.all => "EXCEPT ALL",
-        pg_sys::SetOperation::SETOP_EXCEPT => "EXCEPT",
-        _ => "UNION",
-    };
-
-    let body = format!("{left} {op_str} {right}");
-
-    // Preserve any WITH clause attached to this set-operation SelectStmt.
-    // The CTE definitions live on the outermost SETOP node — they are NOT
-    // propagated to larg/rarg — so reconstructing from the arms alone drops them.
-    if !select.withClause.is_null() {
-        // SAFETY: withClause is non-null and points to a valid WithClause node
-        // obtained from the raw parse tree of a validated SQL string.
-        let with_sql = deparse_with_clause_with_view_subs(select.withClause, &[])?;
-        Ok(format!("{with_sql} {body}"))
+        sorts.push(format!("{expr}{dir}{nulls}"));
+    }
+    if sorts.is_empty() {
+        String::new()
     } else {
-        Ok(body)
+        format!(" ORDER BY {}", sorts.join(", "))
     }
 }
 
@@ -5367,9 +5548,9 @@ pub(crate) fn push_filter_into_cross_joins(tree: OpTree) -> Result<OpTree, PgTri
 
     for part in parts {
         // Guard (a): never promote predicates containing scalar subqueries.
-        // Correlated subqueries (e.g. TPC-H Q17) appear as Expr::Raw with
-        // an embedded SELECT — promoting them removes column references that
-        // the correlation still needs.
+        // Correlated subqueries (e.g. TPC-H Q17) appear as Expr::SubLink —
+        // promoting them removes column references that the correlation
+        // still needs.
         if expr_contains_subquery(&part) {
             remaining.push(part);
             continue;
@@ -5436,27 +5617,26 @@ fn has_cross_join(op: &OpTree) -> bool {
 }
 
 /// Returns `true` if an `Expr` tree contains a scalar subquery (embedded
-/// SELECT).  Correlated scalar subqueries arrive as `Expr::Raw(sql)` from
-/// the parser; we also recurse into `BinaryOp` / `FuncCall` children.
+/// SELECT).  Correlated scalar subqueries arrive as `Expr::SubLink` from
+/// the parser; hand-built `Expr::Raw` fragments are checked textually.
 fn expr_contains_subquery(expr: &Expr) -> bool {
     match expr {
+        Expr::SubLink { .. } => true,
         Expr::Raw(sql) => {
             // Case-insensitive check for SELECT keyword inside raw SQL
             let upper = sql.to_uppercase();
             upper.contains("SELECT") || upper.contains("EXISTS")
         }
-        Expr::BinaryOp { left, right, .. } => {
-            expr_contains_subquery(left) || expr_contains_subquery(right)
-        }
-        Expr::FuncCall { args, .. } => args.iter().any(expr_contains_subquery),
-        _ => false,
+        _ => expr.children().into_iter().any(expr_contains_subquery),
     }
 }
 
 /// Split an AND-connected expression into its individual conjuncts.
 pub(crate) fn split_and_predicates(expr: Expr) -> Vec<Expr> {
     match expr {
-        Expr::BinaryOp { op, left, right } if op.eq_ignore_ascii_case("AND") => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op.eq_ignore_ascii_case("AND") => {
             let mut parts = split_and_predicates(*left);
             parts.extend(split_and_predicates(*right));
             parts
@@ -5481,6 +5661,7 @@ pub(crate) fn join_and_predicates(parts: Vec<Expr>) -> Result<Expr, PgTrickleErr
             op: "AND".to_string(),
             left: Box::new(result),
             right: Box::new(part),
+            resolved: Resolved::default(),
         };
     }
     Ok(result)
@@ -5507,18 +5688,11 @@ fn collect_expr_source_aliases(expr: &Expr, tree: &OpTree, aliases: &mut Vec<Str
                 aliases.push(alias);
             }
         }
-        Expr::BinaryOp { left, right, .. } => {
-            collect_expr_source_aliases(left, tree, aliases);
-            collect_expr_source_aliases(right, tree, aliases);
-        }
-        Expr::FuncCall { args, .. } => {
-            for arg in args {
-                collect_expr_source_aliases(arg, tree, aliases);
-            }
-        }
-        Expr::Raw(sql) => {
-            // Best-effort: look for `alias.column` patterns in raw SQL.
+        Expr::SubLink { .. } | Expr::Raw(_) => {
+            // Best-effort: look for `alias.column` patterns in the SQL text
+            // (correlated references inside a subquery body).
             // This won't catch everything but handles common cases.
+            let sql = expr.to_sql();
             for alias in collect_tree_scan_aliases(tree) {
                 if sql.contains(&format!("{}.", alias)) || sql.contains(&format!("\"{}\".", alias))
                 {
@@ -5526,7 +5700,12 @@ fn collect_expr_source_aliases(expr: &Expr, tree: &OpTree, aliases: &mut Vec<Str
                 }
             }
         }
-        _ => {}
+        Expr::ColumnRef { .. } | Expr::Literal(_) | Expr::Star { .. } => {}
+        _ => {
+            for child in expr.children() {
+                collect_expr_source_aliases(child, tree, aliases);
+            }
+        }
     }
 }
 
@@ -5545,6 +5724,7 @@ fn scan_has_alias(op: &OpTree, alias: &str) -> bool {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. } => scan_has_alias(child, alias),
         OpTree::LateralFunction { child, .. } | OpTree::LateralSubquery { child, .. } => {
             scan_has_alias(child, alias)
@@ -5573,6 +5753,7 @@ fn find_scan_for_column(op: &OpTree, column_name: &str) -> Option<String> {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. } => find_scan_for_column(child, column_name),
         OpTree::LateralFunction { child, .. } | OpTree::LateralSubquery { child, .. } => {
             find_scan_for_column(child, column_name)
@@ -5598,6 +5779,7 @@ fn collect_tree_scan_aliases(op: &OpTree) -> Vec<String> {
         | OpTree::Project { child, .. }
         | OpTree::Subquery { child, .. }
         | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
         | OpTree::Distinct { child, .. } => collect_tree_scan_aliases(child),
         OpTree::LateralFunction { child, .. } | OpTree::LateralSubquery { child, .. } => {
             collect_tree_scan_aliases(child)
@@ -5635,6 +5817,7 @@ fn promote_predicate(tree: OpTree, pred: Expr, aliases: &[String]) -> OpTree {
                         op: "AND".to_string(),
                         left: Box::new(condition),
                         right: Box::new(pred),
+                        resolved: Resolved::default(),
                     },
                     left,
                     right,
@@ -5674,6 +5857,591 @@ fn promote_predicate(tree: OpTree, pred: Expr, aliases: &[String]) -> OpTree {
     }
 }
 
+// ── Source rename rewrite ───────────────────────────────────────────────
+//
+// REN-1 (v0.49.0): when a source column or table is renamed, the stored
+// query texts are patched in place rather than re-deparsed, so the user's
+// formatting survives. Every identifier that must change is located through
+// the `location` offsets the raw parser records on `RangeVar` and
+// `ColumnRef` nodes. A renamed column that is a bare target entry keeps its
+// old output name via `AS`, so the stream table's own columns never change.
+
+/// A rename of a source relation and/or its columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceRename {
+    pub old_schema: String,
+    pub old_table: String,
+    pub new_schema: String,
+    pub new_table: String,
+    /// Renamed columns as `(old, new)` pairs.
+    pub columns: Vec<(String, String)>,
+}
+
+impl SourceRename {
+    /// True when neither the relation nor any column was renamed.
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && !self.table_renamed() && !self.schema_changed()
+    }
+
+    fn table_renamed(&self) -> bool {
+        self.old_table != self.new_table
+    }
+
+    fn schema_changed(&self) -> bool {
+        self.old_schema != self.new_schema
+    }
+
+    fn new_column(&self, old: &str) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|(o, _)| o == old)
+            .map(|(_, n)| n.as_str())
+    }
+
+    fn is_old_relation(&self, schema: Option<&str>, relname: &str) -> bool {
+        relname == self.old_table && schema.is_none_or(|s| s == self.old_schema)
+    }
+}
+
+/// A relation or column reference collected from the raw parse tree.
+#[derive(Debug, Clone, PartialEq)]
+enum RenameRef {
+    /// A `RangeVar` in a FROM clause.
+    Relation {
+        location: i32,
+        catalog: bool,
+        schema: Option<String>,
+        relname: String,
+        alias: Option<String>,
+    },
+    /// A `ColumnRef`; `None` fields are `*`.
+    Column {
+        location: i32,
+        fields: Vec<Option<String>>,
+        /// Whether an unqualified name resolves to the renamed relation:
+        /// it is in the FROM clause of this or an enclosing SELECT and no
+        /// nearer FROM item has a column of that name. `None` when that
+        /// cannot be decided or the new name would be captured by another
+        /// relation.
+        visible: Option<bool>,
+        /// A target entry without an explicit output name.
+        bare_target: bool,
+    },
+    /// A `JOIN ... USING (...)` or `NATURAL JOIN`; neither carries
+    /// locations for its column names.
+    Using { columns: Vec<String>, natural: bool },
+}
+
+/// Rewrite `query` for a renamed source.
+///
+/// Returns `Ok(None)` when the query uses a construct whose meaning would
+/// change or cannot be patched in place (`*` or `USING`/`NATURAL` over a
+/// renamed column, or an unqualified renamed column whose binding cannot
+/// be decided), and the rewritten text otherwise (unchanged when the
+/// query does not reference the renamed names).
+pub fn rewrite_source_rename(
+    query: &str,
+    rename: &SourceRename,
+) -> Result<Option<String>, PgTrickleError> {
+    if rename.is_empty() {
+        return Ok(Some(query.to_string()));
+    }
+    let refs = collect_rename_refs(query, rename)?;
+    Ok(plan_rename_edits(query, &refs, rename).and_then(|edits| apply_rename_edits(query, edits)))
+}
+
+/// Rewrite a stored TopK `ORDER BY` list, which is appended to
+/// `base_query` at refresh time.
+pub fn rewrite_source_rename_order_by(
+    base_query: &str,
+    order_by: &str,
+    rename: &SourceRename,
+) -> Result<Option<String>, PgTrickleError> {
+    if rename.is_empty() {
+        return Ok(Some(order_by.to_string()));
+    }
+    let combined = format!("{base_query} ORDER BY {order_by}");
+    let offset = combined.len() - order_by.len();
+    let refs = collect_rename_refs(&combined, rename)?;
+    let Some(edits) = plan_rename_edits(&combined, &refs, rename) else {
+        return Ok(None);
+    };
+    let edits = edits
+        .into_iter()
+        .filter(|(start, _, _)| *start >= offset)
+        .map(|(start, end, text)| (start - offset, end - offset, text))
+        .collect();
+    Ok(apply_rename_edits(order_by, edits))
+}
+
+/// Compute the `(start, end, replacement)` byte edits for a rename, or
+/// `None` when the query cannot be rewritten safely.
+fn plan_rename_edits(
+    query: &str,
+    refs: &[RenameRef],
+    rename: &SourceRename,
+) -> Option<Vec<(usize, usize, String)>> {
+    use crate::dvm::diff::quote_ident;
+
+    let mut edits = Vec::new();
+    // Names that qualify columns of the renamed relation: its alias, or its
+    // old name when it has none.
+    let mut qualifiers: Vec<String> = Vec::new();
+    let mut unaliased = false;
+
+    for r in refs {
+        let RenameRef::Relation {
+            location,
+            catalog,
+            schema,
+            relname,
+            alias,
+        } = r
+        else {
+            continue;
+        };
+        if *catalog || !rename.is_old_relation(schema.as_deref(), relname) {
+            continue;
+        }
+        match alias {
+            Some(a) => qualifiers.push(a.clone()),
+            None => {
+                qualifiers.push(relname.clone());
+                unaliased = true;
+            }
+        }
+        let qualify = schema.is_none() && rename.schema_changed();
+        if !rename.table_renamed() && !rename.schema_changed() {
+            continue;
+        }
+        let spans = dotted_name_spans(query, *location);
+        if spans.len() != 1 + usize::from(schema.is_some()) {
+            return None;
+        }
+        if schema.is_some() && rename.schema_changed() {
+            edits.push((spans[0].0, spans[0].1, quote_ident(&rename.new_schema)));
+        }
+        if rename.table_renamed() || qualify {
+            let (start, end) = spans[spans.len() - 1];
+            let text = if qualify {
+                format!(
+                    "{}.{}",
+                    quote_ident(&rename.new_schema),
+                    quote_ident(&rename.new_table)
+                )
+            } else {
+                quote_ident(&rename.new_table)
+            };
+            edits.push((start, end, text));
+        }
+    }
+
+    for r in refs {
+        match r {
+            RenameRef::Relation { .. } => {}
+            RenameRef::Using { columns, natural } => {
+                if (*natural && !rename.columns.is_empty())
+                    || columns.iter().any(|c| rename.new_column(c).is_some())
+                {
+                    return None;
+                }
+            }
+            RenameRef::Column {
+                location,
+                fields,
+                visible,
+                bare_target,
+            } => {
+                let Some((last, qualifier)) = fields.split_last() else {
+                    continue;
+                };
+                if qualifier.iter().any(Option::is_none) {
+                    continue;
+                }
+                let qualifier: Vec<&str> = qualifier.iter().flatten().map(String::as_str).collect();
+                // Does the reference point at the renamed relation?
+                let refers = match qualifier.as_slice() {
+                    [] => (*visible)?,
+                    [rel] => qualifiers.iter().any(|q| q.as_str() == *rel),
+                    [.., schema, rel] => rename.is_old_relation(Some(*schema), rel),
+                };
+                if !refers {
+                    continue;
+                }
+                let new_col = match last {
+                    // `*` / `t.*` expands to the renamed column under its new name.
+                    None if !rename.columns.is_empty() => return None,
+                    None => None,
+                    Some(col) => rename.new_column(col),
+                };
+                let n = qualifier.len();
+                let rewrite_rel = rename.table_renamed()
+                    && (n >= 2 || (n == 1 && unaliased && qualifier[0] == rename.old_table));
+                let rewrite_schema = n >= 2 && rename.schema_changed();
+                if new_col.is_none() && !rewrite_rel && !rewrite_schema {
+                    continue;
+                }
+                let spans = dotted_name_spans(query, *location);
+                if spans.len() != fields.len() {
+                    return None;
+                }
+                if rewrite_schema {
+                    let (start, end) = spans[n - 2];
+                    edits.push((start, end, quote_ident(&rename.new_schema)));
+                }
+                if rewrite_rel {
+                    let (start, end) = spans[n - 1];
+                    edits.push((start, end, quote_ident(&rename.new_table)));
+                }
+                if let (Some(new), Some(old)) = (new_col, last) {
+                    let (start, end) = spans[n];
+                    let text = if *bare_target {
+                        format!("{} AS {}", quote_ident(new), quote_ident(old))
+                    } else {
+                        quote_ident(new)
+                    };
+                    edits.push((start, end, text));
+                }
+            }
+        }
+    }
+    Some(edits)
+}
+
+/// Apply non-overlapping byte edits to `text`. Returns `None` on overlap.
+fn apply_rename_edits(text: &str, mut edits: Vec<(usize, usize, String)>) -> Option<String> {
+    edits.sort_by_key(|(start, _, _)| *start);
+    let mut out = String::with_capacity(text.len() + 16 * edits.len());
+    let mut pos = 0;
+    for (start, end, replacement) in edits {
+        if start < pos || end > text.len() {
+            return None;
+        }
+        out.push_str(&text[pos..start]);
+        out.push_str(&replacement);
+        pos = end;
+    }
+    out.push_str(&text[pos..]);
+    Some(out)
+}
+
+/// Byte spans of the segments of a dotted name (`a`, `s.t`, `"T".col`,
+/// `t.*`) starting at byte offset `location`.
+///
+/// Returns an empty list when `location` does not start an identifier.
+fn dotted_name_spans(query: &str, location: i32) -> Vec<(usize, usize)> {
+    let bytes = query.as_bytes();
+    let Ok(mut pos) = usize::try_from(location) else {
+        return Vec::new();
+    };
+    let is_ident_start = |b: u8| b.is_ascii_alphabetic() || b == b'_' || b >= 0x80;
+    let is_ident_char = |b: u8| is_ident_start(b) || b.is_ascii_digit() || b == b'$';
+    let skip_ws = |mut p: usize| {
+        while p < bytes.len() && bytes[p].is_ascii_whitespace() {
+            p += 1;
+        }
+        p
+    };
+
+    let mut spans = Vec::new();
+    loop {
+        let start = pos;
+        match bytes.get(pos) {
+            Some(b'"') => {
+                pos += 1;
+                loop {
+                    match bytes.get(pos) {
+                        Some(b'"') if bytes.get(pos + 1) == Some(&b'"') => pos += 2,
+                        Some(b'"') => {
+                            pos += 1;
+                            break;
+                        }
+                        Some(_) => pos += 1,
+                        None => return Vec::new(),
+                    }
+                }
+            }
+            Some(b'*') => pos += 1,
+            Some(&b) if is_ident_start(b) => {
+                while pos < bytes.len() && is_ident_char(bytes[pos]) {
+                    pos += 1;
+                }
+            }
+            _ => return Vec::new(),
+        }
+        spans.push((start, pos));
+        let next = skip_ws(pos);
+        if bytes.get(next) != Some(&b'.') {
+            return spans;
+        }
+        pos = skip_ws(next + 1);
+    }
+}
+
+/// FROM clause of one enclosing SELECT, as seen by [`rename_ref_walker`].
+#[cfg(not(test))]
+struct RenameScope {
+    /// The renamed relation is one of its FROM items.
+    own: bool,
+    /// The other FROM items: a relation, or `None` when the item's columns
+    /// are not known (subquery, function, ...).
+    others: Vec<Option<pg_sys::Oid>>,
+}
+
+/// Walker state for [`collect_rename_refs`].
+#[cfg(not(test))]
+struct RenameWalk<'a> {
+    rename: &'a SourceRename,
+    /// FROM clauses of the enclosing SELECTs, innermost last.
+    scopes: Vec<RenameScope>,
+    bare_targets: std::collections::HashSet<i32>,
+    refs: Vec<RenameRef>,
+    error: Option<PgTrickleError>,
+}
+
+/// Parse `query` and collect every relation, column and USING reference.
+#[cfg(not(test))]
+fn collect_rename_refs(
+    query: &str,
+    rename: &SourceRename,
+) -> Result<Vec<RenameRef>, PgTrickleError> {
+    let stmts = parse_query(query)?;
+    let Some(raw_stmt) = stmts.head() else {
+        return Ok(Vec::new());
+    };
+    // SAFETY: raw_stmt is a valid pointer from the parser.
+    let root = unsafe { (*raw_stmt).stmt };
+    let mut walk = RenameWalk {
+        rename,
+        scopes: Vec::new(),
+        bare_targets: std::collections::HashSet::new(),
+        refs: Vec::new(),
+        error: None,
+    };
+    // SAFETY: root is a raw parse tree node; the context points at `walk`,
+    // which outlives the walk.
+    unsafe {
+        rename_ref_walker(root, &mut walk as *mut RenameWalk as *mut std::ffi::c_void);
+    }
+    match walk.error {
+        Some(e) => Err(e),
+        None => Ok(walk.refs),
+    }
+}
+
+/// Test stub: the raw parser is unavailable without a PostgreSQL backend.
+#[cfg(test)]
+fn collect_rename_refs(
+    _query: &str,
+    _rename: &SourceRename,
+) -> Result<Vec<RenameRef>, PgTrickleError> {
+    Err(PgTrickleError::QueryParseError(
+        "collect_rename_refs unavailable in unit tests".into(),
+    ))
+}
+
+/// Collect the FROM items (through joins) of one SELECT into `scope`.
+#[cfg(not(test))]
+fn collect_scope_items(node: *mut pg_sys::Node, rename: &SourceRename, scope: &mut RenameScope) {
+    if let Some(rv) = cast_node!(node, T_RangeVar, pg_sys::RangeVar) {
+        let schema = (!rv.schemaname.is_null())
+            .then(|| pg_cstr_to_str(rv.schemaname).ok())
+            .flatten();
+        if rv.catalogname.is_null()
+            && pg_cstr_to_str(rv.relname).is_ok_and(|r| rename.is_old_relation(schema, r))
+        {
+            scope.own = true;
+        } else if rv.catalogname.is_null() {
+            // SAFETY: rv is a valid RangeVar from the raw parser; a missing
+            // relation returns InvalidOid instead of raising.
+            let relid = unsafe {
+                pg_sys::RangeVarGetRelidExtended(
+                    rv,
+                    pg_sys::NoLock as pg_sys::LOCKMODE,
+                    pg_sys::RVROption::RVR_MISSING_OK,
+                    None,
+                    std::ptr::null_mut(),
+                )
+            };
+            scope
+                .others
+                .push((relid != pg_sys::InvalidOid).then_some(relid));
+        } else {
+            scope.others.push(None);
+        }
+    } else if let Some(join) = cast_node!(node, T_JoinExpr, pg_sys::JoinExpr) {
+        collect_scope_items(join.larg, rename, scope);
+        collect_scope_items(join.rarg, rename, scope);
+    } else {
+        scope.others.push(None);
+    }
+}
+
+/// Whether a relation has a (non-dropped) column called `name`.
+#[cfg(not(test))]
+fn relation_has_column(relid: pg_sys::Oid, name: &std::ffi::CStr) -> bool {
+    // SAFETY: name is a valid NUL-terminated string; an unknown relation or
+    // column yields InvalidAttrNumber.
+    let attnum = unsafe { pg_sys::get_attnum(relid, name.as_ptr()) };
+    attnum != pg_sys::InvalidAttrNumber as pg_sys::AttrNumber
+}
+
+/// Resolve an unqualified column name (`None` for `*`) against the
+/// enclosing scopes, innermost first; see `visible` on
+/// [`RenameRef::Column`].
+///
+/// A renamed column binds to the renamed relation only when no nearer FROM
+/// item has a column of that name. Rewriting it is unsafe (`None`) when a
+/// nearer item's columns are unknown, or when a relation in the same or a
+/// nearer scope already has a column with the new name.
+#[cfg(not(test))]
+fn unqualified_refers(
+    scopes: &[RenameScope],
+    rename: &SourceRename,
+    name: Option<&str>,
+) -> Result<Option<bool>, PgTrickleError> {
+    // Only a renamed column can change meaning; other names keep their
+    // text whatever they resolve to.
+    let (old, new) = match name {
+        Some(col) => match rename.new_column(col) {
+            Some(new) => (col, new),
+            None => return Ok(Some(scopes.iter().any(|s| s.own))),
+        },
+        // `*` expands the innermost FROM clause only.
+        None => return Ok(Some(scopes.last().is_some_and(|s| s.own))),
+    };
+    let to_cstring = |name: &str| {
+        std::ffi::CString::new(name).map_err(|e| PgTrickleError::QueryParseError(e.to_string()))
+    };
+    if !scopes.iter().any(|s| s.own) {
+        return Ok(Some(false));
+    }
+    let (old, new) = (to_cstring(old)?, to_cstring(new)?);
+    for scope in scopes.iter().rev() {
+        let mut captured = false;
+        for item in &scope.others {
+            match *item {
+                Some(relid) if !scope.own && relation_has_column(relid, &old) => {
+                    return Ok(Some(false));
+                }
+                Some(relid) => captured |= relation_has_column(relid, &new),
+                None if !scope.own => return Ok(None),
+                None => {}
+            }
+        }
+        if captured {
+            return Ok(None);
+        }
+        if scope.own {
+            return Ok(Some(true));
+        }
+    }
+    Ok(Some(false))
+}
+
+/// Record one parse tree node for [`collect_rename_refs`], then recurse.
+///
+/// # Safety
+/// `node` must be a raw parse tree node (or null) and `context` a
+/// `*mut RenameWalk`.
+#[cfg(not(test))]
+unsafe extern "C-unwind" fn rename_ref_walker(
+    node: *mut pg_sys::Node,
+    context: *mut std::ffi::c_void,
+) -> bool {
+    if node.is_null() {
+        return false;
+    }
+    // SAFETY: context is the `RenameWalk` passed by collect_rename_refs.
+    let walk = unsafe { &mut *(context as *mut RenameWalk) };
+    let result = (|| -> Result<(), PgTrickleError> {
+        if let Some(rv) = cast_node!(node, T_RangeVar, pg_sys::RangeVar) {
+            let alias = if rv.alias.is_null() {
+                None
+            } else {
+                // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
+                Some(pg_cstr_to_str(pg_deref!(rv.alias).aliasname)?.to_string())
+            };
+            walk.refs.push(RenameRef::Relation {
+                location: rv.location,
+                catalog: !rv.catalogname.is_null(),
+                schema: if rv.schemaname.is_null() {
+                    None
+                } else {
+                    Some(pg_cstr_to_str(rv.schemaname)?.to_string())
+                },
+                relname: pg_cstr_to_str(rv.relname)?.to_string(),
+                alias,
+            });
+        } else if let Some(cref) = cast_node!(node, T_ColumnRef, pg_sys::ColumnRef) {
+            let mut fields = Vec::new();
+            for field in pg_list::<pg_sys::Node>(cref.fields).iter_ptr() {
+                match cast_node!(field, T_String, pg_sys::String) {
+                    Some(s) => fields.push(Some(pg_cstr_to_str(s.sval)?.to_string())),
+                    None => fields.push(None),
+                }
+            }
+            let visible = match fields.as_slice() {
+                [name] => unqualified_refers(&walk.scopes, walk.rename, name.as_deref())?,
+                _ => Some(false),
+            };
+            walk.refs.push(RenameRef::Column {
+                location: cref.location,
+                fields,
+                visible,
+                bare_target: walk.bare_targets.contains(&cref.location),
+            });
+        } else if let Some(join) = cast_node!(node, T_JoinExpr, pg_sys::JoinExpr) {
+            let mut columns = Vec::new();
+            for col in pg_list::<pg_sys::Node>(join.usingClause).iter_ptr() {
+                if let Some(s) = cast_node!(col, T_String, pg_sys::String) {
+                    columns.push(pg_cstr_to_str(s.sval)?.to_string());
+                }
+            }
+            if join.isNatural || !columns.is_empty() {
+                walk.refs.push(RenameRef::Using {
+                    columns,
+                    natural: join.isNatural,
+                });
+            }
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        walk.error = Some(e);
+        return true;
+    }
+
+    let select = cast_node!(node, T_SelectStmt, pg_sys::SelectStmt);
+    if let Some(select) = select {
+        let mut scope = RenameScope {
+            own: false,
+            others: Vec::new(),
+        };
+        for item in pg_list::<pg_sys::Node>(select.fromClause).iter_ptr() {
+            collect_scope_items(item, walk.rename, &mut scope);
+        }
+        walk.scopes.push(scope);
+        for target in pg_list::<pg_sys::Node>(select.targetList).iter_ptr() {
+            if let Some(rt) = cast_node!(target, T_ResTarget, pg_sys::ResTarget)
+                && rt.name.is_null()
+                && let Some(cref) = cast_node!(rt.val, T_ColumnRef, pg_sys::ColumnRef)
+            {
+                walk.bare_targets.insert(cref.location);
+            }
+        }
+    }
+    // SAFETY: raw_expression_tree_walker_impl handles all raw parse tree
+    // node types; `context` is passed through unchanged.
+    let stop =
+        unsafe { pg_sys::raw_expression_tree_walker_impl(node, Some(rename_ref_walker), context) };
+    if select.is_some() {
+        // SAFETY: as above; the nested walk has finished with the context.
+        unsafe { &mut *(context as *mut RenameWalk) }.scopes.pop();
+    }
+    stop
+}
+
 #[cfg(feature = "pg_test")]
 #[pg_schema]
 mod pg_tests {
@@ -5701,6 +6469,7 @@ mod pg_tests {
             OpTree::Project { child, .. }
             | OpTree::Filter { child, .. }
             | OpTree::Aggregate { child, .. }
+            | OpTree::GroupingSets { child, .. }
             | OpTree::Distinct { child }
             | OpTree::Subquery { child, .. }
             | OpTree::Window { child, .. }
@@ -6043,6 +6812,85 @@ mod unit_tests {
         assert!(contains_word_boundary("RECURSIVE", "RECURSIVE"));
     }
 
+    // ── disjoint_or_arms ────────────────────────────────────────────────
+
+    fn or_arm(sql: &str, has_sublink: bool, not_true_sql: Option<&str>) -> OrArm {
+        OrArm {
+            sql: sql.to_string(),
+            has_sublink,
+            not_true_sql: not_true_sql.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_disjoint_or_arms_merges_plain_arms_first() {
+        let arms = vec![
+            or_arm("EXISTS (s1)", true, Some("(EXISTS (s1)) IS NOT TRUE")),
+            or_arm("a = 1", false, None),
+            or_arm("b = 2", false, None),
+        ];
+        assert_eq!(
+            disjoint_or_arms(&arms).unwrap(),
+            vec![
+                "((a = 1) OR (b = 2))".to_string(),
+                "(EXISTS (s1) AND ((a = 1) OR (b = 2)) IS NOT TRUE)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disjoint_or_arms_guards_later_sublinks() {
+        let arms = vec![
+            or_arm("x IN (s1)", true, Some("(x IN (s1)) IS NOT TRUE")),
+            or_arm("x > ALL (s2)", true, None),
+            or_arm("NOT EXISTS (s3)", true, Some("EXISTS (s3)")),
+        ];
+        assert_eq!(
+            disjoint_or_arms(&arms).unwrap(),
+            vec![
+                "(x IN (s1))".to_string(),
+                "(NOT EXISTS (s3) AND (x IN (s1)) IS NOT TRUE)".to_string(),
+                "(x > ALL (s2) AND (x IN (s1)) IS NOT TRUE AND EXISTS (s3))".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disjoint_or_arms_keeps_rows_where_earlier_in_is_null() {
+        // `x IN (s1)` is NULL when s1 holds a NULL and no match; the guard
+        // must still admit the row into the later branch.
+        let arms = vec![
+            or_arm("x IN (s1)", true, Some("(x IN (s1)) IS NOT TRUE")),
+            or_arm("EXISTS (s2)", true, Some("(EXISTS (s2)) IS NOT TRUE")),
+        ];
+        let wheres = disjoint_or_arms(&arms).unwrap();
+        assert_eq!(wheres[1], "(EXISTS (s2) AND (x IN (s1)) IS NOT TRUE)");
+    }
+
+    #[test]
+    fn test_disjoint_or_arms_not_in_without_negation_goes_last() {
+        let arms = vec![
+            or_arm("NOT (x IN (s1))", true, None),
+            or_arm("EXISTS (s2)", true, Some("(EXISTS (s2)) IS NOT TRUE")),
+        ];
+        assert_eq!(
+            disjoint_or_arms(&arms).unwrap(),
+            vec![
+                "(EXISTS (s2))".to_string(),
+                "(NOT (x IN (s1)) AND (EXISTS (s2)) IS NOT TRUE)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disjoint_or_arms_rejects_two_opaque_arms() {
+        let arms = vec![
+            or_arm("x > ALL (s1)", true, None),
+            or_arm("a AND EXISTS (s2)", true, None),
+        ];
+        assert!(disjoint_or_arms(&arms).is_none());
+    }
+
     // ── query-level structural detection (pure-regex, no parser) ────────
     // These call the same pattern-based helpers used internally.
 
@@ -6109,4 +6957,176 @@ mod unit_tests {
     fn test_expr_has_aggregate_string_agg() {
         assert!(expr_has_aggregate("string_agg(name, ',')"));
     }
+
+    // ── REN-1: source rename rewrite ────────────────────────────────────
+
+    fn col_rename() -> SourceRename {
+        SourceRename {
+            old_schema: "public".into(),
+            old_table: "orders".into(),
+            new_schema: "public".into(),
+            new_table: "orders".into(),
+            columns: vec![("amount".into(), "total".into())],
+        }
+    }
+
+    fn relation(query: &str, text: &str, schema: Option<&str>, alias: Option<&str>) -> RenameRef {
+        RenameRef::Relation {
+            location: query.find(text).unwrap() as i32,
+            catalog: false,
+            schema: schema.map(String::from),
+            relname: "orders".into(),
+            alias: alias.map(String::from),
+        }
+    }
+
+    fn column(query: &str, text: &str, fields: &[&str], bare_target: bool) -> RenameRef {
+        RenameRef::Column {
+            location: query.find(text).unwrap() as i32,
+            fields: fields
+                .iter()
+                .map(|f| (*f != "*").then(|| f.to_string()))
+                .collect(),
+            visible: Some(true),
+            bare_target,
+        }
+    }
+
+    fn rewrite(query: &str, refs: &[RenameRef], rename: &SourceRename) -> Option<String> {
+        plan_rename_edits(query, refs, rename).and_then(|e| apply_rename_edits(query, e))
+    }
+
+    #[test]
+    fn test_dotted_name_spans() {
+        assert_eq!(dotted_name_spans("x amount y", 2), vec![(2, 8)]);
+        assert_eq!(dotted_name_spans("o . amount", 0), vec![(0, 1), (4, 10)]);
+        assert_eq!(
+            dotted_name_spans("\"My \"\"T\"\".col", 0),
+            vec![(0, 10), (11, 14)]
+        );
+        assert_eq!(dotted_name_spans("o.*", 0), vec![(0, 1), (2, 3)]);
+        assert!(dotted_name_spans("(a)", 0).is_empty());
+        assert!(dotted_name_spans("a", -1).is_empty());
+    }
+
+    #[test]
+    fn test_rename_column_keeps_output_name() {
+        let q = "SELECT id, amount FROM orders WHERE amount > 0";
+        let refs = vec![
+            column(q, "id", &["id"], true),
+            column(q, "amount FROM", &["amount"], true),
+            relation(q, "orders", None, None),
+            column(q, "amount >", &["amount"], false),
+        ];
+        assert_eq!(
+            rewrite(q, &refs, &col_rename()).as_deref(),
+            Some("SELECT id, \"total\" AS \"amount\" FROM orders WHERE \"total\" > 0")
+        );
+    }
+
+    #[test]
+    fn test_rename_column_qualified_by_alias() {
+        let q = "SELECT o.amount AS amt FROM orders o JOIN items i ON i.amount = o.id";
+        let refs = vec![
+            column(q, "o.amount", &["o", "amount"], false),
+            relation(q, "orders", None, Some("o")),
+            column(q, "i.amount", &["i", "amount"], false),
+            column(q, "o.id", &["o", "id"], false),
+        ];
+        assert_eq!(
+            rewrite(q, &refs, &col_rename()).as_deref(),
+            Some("SELECT o.\"total\" AS amt FROM orders o JOIN items i ON i.amount = o.id")
+        );
+    }
+
+    #[test]
+    fn test_rename_table_rewrites_relation_and_qualifiers() {
+        let rename = SourceRename {
+            new_table: "sales".into(),
+            columns: vec![],
+            ..col_rename()
+        };
+        let q = "SELECT orders.id FROM public.orders";
+        let refs = vec![
+            column(q, "orders.id", &["orders", "id"], true),
+            relation(q, "public.orders", Some("public"), None),
+        ];
+        assert_eq!(
+            rewrite(q, &refs, &rename).as_deref(),
+            Some("SELECT \"sales\".id FROM public.\"sales\"")
+        );
+    }
+
+    #[test]
+    fn test_set_schema_qualifies_unqualified_relation() {
+        let rename = SourceRename {
+            new_schema: "archive".into(),
+            columns: vec![],
+            ..col_rename()
+        };
+        let q = "SELECT id FROM orders";
+        let refs = vec![
+            column(q, "id", &["id"], true),
+            relation(q, "orders", None, None),
+        ];
+        assert_eq!(
+            rewrite(q, &refs, &rename).as_deref(),
+            Some("SELECT id FROM \"archive\".\"orders\"")
+        );
+    }
+
+    #[test]
+    fn test_rename_column_rejects_star_and_using() {
+        let q = "SELECT * FROM orders";
+        let refs = vec![
+            column(q, "*", &["*"], true),
+            relation(q, "orders", None, None),
+        ];
+        assert_eq!(rewrite(q, &refs, &col_rename()), None);
+
+        let using = vec![RenameRef::Using {
+            columns: vec!["amount".into()],
+            natural: false,
+        }];
+        assert_eq!(plan_rename_edits("", &using, &col_rename()), None);
+
+        // A table-only rename keeps `*` valid.
+        let rename = SourceRename {
+            new_table: "sales".into(),
+            columns: vec![],
+            ..col_rename()
+        };
+        assert_eq!(
+            rewrite(q, &refs, &rename).as_deref(),
+            Some("SELECT * FROM \"sales\"")
+        );
+    }
+
+    #[test]
+    fn test_rename_column_ignores_invisible_unqualified_refs() {
+        let q = "SELECT amount FROM other";
+        let refs = vec![RenameRef::Column {
+            location: 7,
+            fields: vec![Some("amount".into())],
+            visible: Some(false),
+            bare_target: true,
+        }];
+        assert_eq!(rewrite(q, &refs, &col_rename()).as_deref(), Some(q));
+    }
+
+    #[test]
+    fn test_rename_column_rejects_unresolved_unqualified_refs() {
+        // `amount` may belong to f()'s result, which the walker cannot see.
+        let q = "SELECT id FROM orders WHERE EXISTS (SELECT 1 FROM f() s WHERE amount > 0)";
+        let refs = vec![
+            relation(q, "orders", None, None),
+            RenameRef::Column {
+                location: q.find("amount").unwrap() as i32,
+                fields: vec![Some("amount".into())],
+                visible: None,
+                bare_target: false,
+            },
+        ];
+        assert_eq!(rewrite(q, &refs, &col_rename()), None);
+    }
 }
@@ -21,6 +21,19 @@ struct SublinkWrapper {
     inner_tree: OpTree,
 }
 
+/// How a SubLink appears in a WHERE conjunct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SublinkNegation {
+    /// `<sublink>` → SemiJoin.
+    None,
+    /// `NOT <sublink>` → AntiJoin. `NOT IN` is unknown (and drops the outer
+    /// row) when a comparison is NULL, so its anti-join is null-aware.
+    Not,
+    /// `<sublink> IS NOT TRUE` → AntiJoin on plain equality: a NULL `IN`
+    /// keeps the outer row. Emitted by `rewrite_sublinks_in_or()` guards.
+    NotTrue,
+}
+
 /// Walk a WHERE clause node tree and extract SubLinks into SemiJoin/AntiJoin
 /// wrappers, returning the remaining non-SubLink predicates.
 ///
@@ -29,8 +42,12 @@ struct SublinkWrapper {
 /// - `NOT EXISTS (SELECT ... FROM inner WHERE cond)` → AntiJoin
 /// - `x IN (SELECT col FROM inner WHERE cond)` → SemiJoin with equality
 /// - `x NOT IN (SELECT col FROM inner)` → AntiJoin with equality
-/// - SubLinks under AND conjunctions (each extracted independently)
-/// - SubLinks under OR → not supported (returns error)
+/// - `(EXISTS ...) IS NOT TRUE` / `(x IN ...) IS NOT TRUE` → AntiJoin, as
+///   emitted for the guards of `rewrite_sublinks_in_or()`
+/// - SubLinks under AND conjunctions, at any nesting depth (each extracted
+///   independently)
+/// - SubLinks under OR → rewritten beforehand into UNION ALL branches by
+///   `rewrite_sublinks_in_or()`; any that remain are rejected
 ///
 /// # Safety
 /// Caller must ensure `node` points to a valid `pg_sys::Node`.
@@ -41,37 +58,27 @@ fn extract_where_sublinks(
     // Case 1: The node itself is a SubLink
     // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
     if is_node_type!(node, T_SubLink) {
-        let wrapper = parse_sublink_to_wrapper(node, false, cte_ctx)?;
+        let wrapper = parse_sublink_to_wrapper(node, SublinkNegation::None, cte_ctx)?;
         return Ok((vec![wrapper], None));
     }
 
-    // Case 2: BoolExpr
-    if let Some(boolexpr) = cast_node!(node, T_BoolExpr, pg_sys::BoolExpr) {
-        // Case 2a: NOT wrapping a SubLink → negated
-        if boolexpr.boolop == pg_sys::BoolExprType::NOT_EXPR {
-            let args = pg_list::<pg_sys::Node>(boolexpr.args);
-            if args.len() == 1 {
-                // INVARIANT: args.len() == 1 guarantees head() returns Some.
-                let arg = args.head().ok_or_else(|| {
-                    PgTrickleError::InternalError(
-                        "BoolExpr NOT_EXPR args list unexpectedly empty".into(),
-                    )
-                })?;
-                // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
-                if is_node_type!(arg, T_SubLink) {
-                    let wrapper = parse_sublink_to_wrapper(arg, true, cte_ctx)?;
-                    return Ok((vec![wrapper], None));
-                }
-            }
-        }
+    // Case 2: NOT / IS NOT TRUE wrapping a SubLink → negated
+    if let Some((sublink, negation)) = negated_sublink(node)? {
+        let wrapper = parse_sublink_to_wrapper(sublink, negation, cte_ctx)?;
+        return Ok((vec![wrapper], None));
+    }
 
-        // Case 2b: AND conjunction — extract SubLinks from each arg
+    if let Some(boolexpr) = cast_node!(node, T_BoolExpr, pg_sys::BoolExpr) {
+        // Case 3: AND conjunction — extract SubLinks from each conjunct.
+        // Nested ANDs (e.g. the parenthesised branch predicates produced by
+        // the OR rewrite) are flattened first.
         if boolexpr.boolop == pg_sys::BoolExprType::AND_EXPR {
-            let args = pg_list::<pg_sys::Node>(boolexpr.args);
+            let mut conjuncts = Vec::new();
+            flatten_and_conjuncts(node, &mut conjuncts);
             let mut wrappers = Vec::new();
             let mut remaining_exprs = Vec::new();
 
-            for arg_ptr in args.iter_ptr() {
+            for arg_ptr in conjuncts {
                 if arg_ptr.is_null() {
                     continue;
                 }
@@ -79,48 +86,34 @@ fn extract_where_sublinks(
                 // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
                 if is_node_type!(arg_ptr, T_SubLink) {
                     // Direct SubLink under AND
-                    let wrapper = parse_sublink_to_wrapper(arg_ptr, false, cte_ctx)?;
+                    let wrapper =
+                        parse_sublink_to_wrapper(arg_ptr, SublinkNegation::None, cte_ctx)?;
                     wrappers.push(wrapper);
-                } else if let Some(inner_bool) = cast_node!(arg_ptr, T_BoolExpr, pg_sys::BoolExpr) {
-                    // NOT SubLink under AND → negated
-                    if inner_bool.boolop == pg_sys::BoolExprType::NOT_EXPR {
-                        let inner_args = pg_list::<pg_sys::Node>(inner_bool.args);
-                        if inner_args.len() == 1 {
-                            // INVARIANT: inner_args.len() == 1 guarantees head() returns Some.
-                            let inner_arg = inner_args.head().ok_or_else(|| {
-                                PgTrickleError::InternalError(
-                                    "BoolExpr NOT_EXPR inner args list unexpectedly empty".into(),
-                                )
-                            })?;
-                            // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
-                            if is_node_type!(inner_arg, T_SubLink) {
-                                let wrapper = parse_sublink_to_wrapper(inner_arg, true, cte_ctx)?;
-                                wrappers.push(wrapper);
-                                continue;
-                            }
-                        }
-                    }
-                    // Check for SubLinks inside OR — should have been
-                    // rewritten to UNION by rewrite_sublinks_in_or().
-                    // If still present, it's a deeply nested case.
-                    if inner_bool.boolop == pg_sys::BoolExprType::OR_EXPR
-                        && node_tree_contains_sublink(arg_ptr)
-                    {
-                        return Err(PgTrickleError::UnsupportedOperator(
-                            "Subquery expressions (EXISTS, IN) inside OR conditions are not \
-                             supported in this nesting pattern. Consider rewriting \
-                             using UNION or separate stream tables."
-                                .into(),
-                        ));
-                    }
-                    // Regular boolean expression — keep as remaining
-                    // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-                    remaining_exprs.push(safe_node_to_expr(arg_ptr)?);
-                } else {
-                    // Regular predicate — keep as remaining
-                    // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-                    remaining_exprs.push(safe_node_to_expr(arg_ptr)?);
+                    continue;
+                }
+                if let Some((sublink, negation)) = negated_sublink(arg_ptr)? {
+                    // NOT / IS NOT TRUE SubLink under AND → negated
+                    let wrapper = parse_sublink_to_wrapper(sublink, negation, cte_ctx)?;
+                    wrappers.push(wrapper);
+                    continue;
+                }
+                // Check for SubLinks inside OR — should have been
+                // rewritten to UNION by rewrite_sublinks_in_or().
+                // If still present, it's a deeply nested case.
+                if let Some(inner_bool) = cast_node!(arg_ptr, T_BoolExpr, pg_sys::BoolExpr)
+                    && inner_bool.boolop == pg_sys::BoolExprType::OR_EXPR
+                    && node_tree_contains_sublink(arg_ptr)
+                {
+                    return Err(PgTrickleError::UnsupportedOperator(
+                        "Subquery expressions (EXISTS, IN) inside OR conditions are not \
+                         supported in this nesting pattern. Consider rewriting \
+                         using UNION or separate stream tables."
+                            .into(),
+                    ));
                 }
+                // Regular predicate — keep as remaining
+                // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+                remaining_exprs.push(safe_node_to_expr(arg_ptr)?);
             }
 
             let remaining = if remaining_exprs.is_empty() {
@@ -133,6 +126,7 @@ fn extract_where_sublinks(
                             op: "AND".to_string(),
                             left: Box::new(acc),
                             right: Box::new(expr),
+                            resolved: Resolved::default(),
                         })
                         .ok_or_else(|| {
                             PgTrickleError::InternalError(
@@ -145,7 +139,7 @@ fn extract_where_sublinks(
             return Ok((wrappers, remaining));
         }
 
-        // Case 2c: OR containing SubLinks — should have been
+        // Case 4: OR containing SubLinks — should have been
         // rewritten to UNION by rewrite_sublinks_in_or().
         // If still present, it's a deeply nested case.
         if boolexpr.boolop == pg_sys::BoolExprType::OR_EXPR && node_tree_contains_sublink(node) {
@@ -164,6 +158,49 @@ fn extract_where_sublinks(
     Ok((vec![], Some(expr)))
 }
 
+/// The SubLink under a negating wrapper, if `node` is one, and the form of
+/// the negation.
+///
+/// Recognises `NOT <sublink>` and `<sublink> IS NOT TRUE` where the SubLink
+/// is EXISTS or IN; both map onto the anti-join.
+///
+/// # Safety
+/// Caller must ensure `node` points to a valid `pg_sys::Node`.
+fn negated_sublink(
+    node: *mut pg_sys::Node,
+) -> Result<Option<(*mut pg_sys::Node, SublinkNegation)>, PgTrickleError> {
+    if let Some(boolexpr) = cast_node!(node, T_BoolExpr, pg_sys::BoolExpr)
+        && boolexpr.boolop == pg_sys::BoolExprType::NOT_EXPR
+    {
+        let args = pg_list::<pg_sys::Node>(boolexpr.args);
+        if args.len() == 1 {
+            // INVARIANT: args.len() == 1 guarantees head() returns Some.
+            let arg = args.head().ok_or_else(|| {
+                PgTrickleError::InternalError(
+                    "BoolExpr NOT_EXPR args list unexpectedly empty".into(),
+                )
+            })?;
+            // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
+            if is_node_type!(arg, T_SubLink) {
+                return Ok(Some((arg, SublinkNegation::Not)));
+            }
+        }
+        return Ok(None);
+    }
+    if let Some(bt) = cast_node!(node, T_BooleanTest, pg_sys::BooleanTest)
+        && bt.booltesttype == pg_sys::BoolTestType::IS_NOT_TRUE
+    {
+        let arg = bt.arg as *mut pg_sys::Node;
+        if let Some(sl) = cast_node!(arg, T_SubLink, pg_sys::SubLink)
+            && (sl.subLinkType == pg_sys::SubLinkType::EXISTS_SUBLINK
+                || sl.subLinkType == pg_sys::SubLinkType::ANY_SUBLINK)
+        {
+            return Ok(Some((arg, SublinkNegation::NotTrue)));
+        }
+    }
+    Ok(None)
+}
+
 /// Check if a node tree contains any T_SubLink node (shallow walk).
 ///
 /// # Safety
@@ -242,6 +279,10 @@ pub(crate) fn node_tree_contains_sublink(node: *mut pg_sys::Node) -> bool {
             }
         }
     }
+    // Recurse into IS [NOT] TRUE / FALSE / UNKNOWN tests.
+    if let Some(bt) = cast_node!(node, T_BooleanTest, pg_sys::BooleanTest) {
+        return node_tree_contains_sublink(bt.arg as *mut pg_sys::Node);
+    }
     false
 }
 
@@ -502,17 +543,18 @@ fn deparse_from_item(node: *mut pg_sys::Node) -> Result<String, PgTrickleError>
 /// Caller must ensure `node` points to a valid `pg_sys::SubLink` (T_SubLink node).
 fn parse_sublink_to_wrapper(
     node: *mut pg_sys::Node,
-    negated: bool,
+    negation: SublinkNegation,
     cte_ctx: &mut CteParseContext,
 ) -> Result<SublinkWrapper, PgTrickleError> {
     // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
     let sublink = pg_deref!(node as *const pg_sys::SubLink);
+    let negated = negation != SublinkNegation::None;
 
     match sublink.subLinkType {
         pg_sys::SubLinkType::EXISTS_SUBLINK => parse_exists_sublink(sublink, negated, cte_ctx),
         pg_sys::SubLinkType::ANY_SUBLINK => {
             // ANY_SUBLINK is used for both `x IN (SELECT ...)` and `x = ANY (SELECT ...)`
-            parse_any_sublink(sublink, negated, cte_ctx)
+            parse_any_sublink(sublink, negation, cte_ctx)
         }
         pg_sys::SubLinkType::ALL_SUBLINK => {
             // ALL_SUBLINK: `x op ALL (SELECT col FROM ...)`.
@@ -718,6 +760,7 @@ fn parse_exists_sublink(
                         table_alias: Some(sub_alias.clone()),
                         column_name: inner_col_name,
                     }),
+                    resolved: Resolved::default(),
                 })
                 .collect();
             equalities
@@ -726,6 +769,7 @@ fn parse_exists_sublink(
                     op: "AND".to_string(),
                     left: Box::new(acc),
                     right: Box::new(eq),
+                    resolved: Resolved::default(),
                 })
                 .ok_or_else(|| {
                     PgTrickleError::InternalError(
@@ -779,7 +823,8 @@ pub(crate) fn collect_tree_source_aliases(tree: &OpTree) -> Vec<String> {
         OpTree::Filter { child, .. }
         | OpTree::Project { child, .. }
         | OpTree::Distinct { child, .. }
-        | OpTree::Aggregate { child, .. } => collect_tree_source_aliases(child),
+        | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. } => collect_tree_source_aliases(child),
         OpTree::Subquery { alias, .. } => vec![alias.clone()],
         _ => vec![],
     }
@@ -805,7 +850,9 @@ pub(crate) fn split_exists_correlation(
     inner_aliases: &[String],
 ) -> (Vec<(Expr, String)>, Option<Expr>) {
     match where_expr {
-        Expr::BinaryOp { op, left, right } if op == "AND" => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op == "AND" => {
             let (mut lc, lr) = split_exists_correlation(left, inner_aliases);
             let (rc, rr) = split_exists_correlation(right, inner_aliases);
             lc.extend(rc);
@@ -814,6 +861,7 @@ pub(crate) fn split_exists_correlation(
                     op: "AND".to_string(),
                     left: Box::new(l),
                     right: Box::new(r),
+                    resolved: Resolved::default(),
                 }),
                 (Some(l), None) | (None, Some(l)) => Some(l),
                 (None, None) => None,
@@ -840,7 +888,10 @@ pub(crate) fn split_exists_correlation(
 /// Returns `None` for non-equality predicates or predicates where both / neither
 /// side is an inner reference.
 fn try_extract_exists_corr_pair(pred: &Expr, inner_aliases: &[String]) -> Option<(Expr, String)> {
-    let Expr::BinaryOp { op, left, right } = pred else {
+    let Expr::BinaryOp {
+        op, left, right, ..
+    } = pred
+    else {
         return None;
     };
     if op != "=" {
@@ -895,20 +946,149 @@ fn qualify_inner_col_refs(expr: Expr, alias: &str) -> Expr {
             table_alias: Some(alias.to_string()),
             column_name,
         },
-        Expr::BinaryOp { op, left, right } => Expr::BinaryOp {
+        Expr::BinaryOp {
+            op,
+            left,
+            right,
+            resolved,
+        } => Expr::BinaryOp {
             op,
             left: Box::new(qualify_inner_col_refs(*left, alias)),
             right: Box::new(qualify_inner_col_refs(*right, alias)),
+            resolved,
         },
-        Expr::FuncCall { func_name, args } => Expr::FuncCall {
+        Expr::FuncCall {
+            func_name,
+            args,
+            resolved,
+        } => Expr::FuncCall {
             func_name,
             args: args
                 .into_iter()
                 .map(|a| qualify_inner_col_refs(a, alias))
                 .collect(),
+            resolved,
         },
-        other => other,
+        other => other.map_children(|c| qualify_inner_col_refs(c.clone(), alias)),
+    }
+}
+
+/// Wrap an IN / ALL subquery's FROM tree for GROUP BY / HAVING.
+///
+/// The inner WHERE becomes a Filter on the FROM tree, the grouping an
+/// Aggregate (including aggregates referenced only by HAVING), and HAVING a
+/// Filter on top. The result is wrapped in a Subquery so the semi/anti-join
+/// treats it as a derived table. Returns the wrapped tree and its aggregates.
+fn wrap_grouped_sublink_inner(
+    mut inner_tree: OpTree,
+    inner_select: &pg_sys::SelectStmt,
+    target_list: &pgrx::PgList<pg_sys::Node>,
+) -> Result<(OpTree, Vec<AggExpr>), PgTrickleError> {
+    let group_list = pg_list::<pg_sys::Node>(inner_select.groupClause);
+    let has_having = !inner_select.havingClause.is_null();
+
+    // Apply inner WHERE as a Filter on the FROM tree
+    if !inner_select.whereClause.is_null() {
+        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+        let inner_where = safe_node_to_expr(inner_select.whereClause)?;
+        inner_tree = OpTree::Filter {
+            predicate: inner_where,
+            child: Box::new(inner_tree),
+        };
+    }
+
+    // Parse GROUP BY expressions
+    let mut group_by = Vec::new();
+    for node_ptr in group_list.iter_ptr() {
+        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+        let expr = safe_node_to_expr(node_ptr)?;
+        group_by.push(expr);
+    }
+
+    // Extract aggregates from the target list
+    // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+    let (mut aggregates, _non_agg_exprs) = unsafe { extract_aggregates(target_list)? };
+
+    // Extract additional aggregates from HAVING expression.
+    // The HAVING clause may reference aggregates not present in the
+    // SELECT list (e.g., `SELECT col FROM T GROUP BY col HAVING SUM(x) > 0`).
+    if has_having {
+        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+        let having_expr = safe_node_to_expr(inner_select.havingClause)?;
+        let having_aggs = extract_aggregates_from_expr(&having_expr, aggregates.len());
+        for ha in &having_aggs {
+            // Avoid duplicates: only add if no existing aggregate matches
+            let already_present = aggregates.iter().any(|a| {
+                a.function.sql_name() == ha.function.sql_name()
+                    && a.argument.as_ref().map(|e| e.to_sql())
+                        == ha.argument.as_ref().map(|e| e.to_sql())
+            });
+            if !already_present {
+                aggregates.push(ha.clone());
+            }
+        }
+    }
+
+    // Build Aggregate node
+    inner_tree = OpTree::Aggregate {
+        group_by,
+        aggregates: aggregates.clone(),
+        child: Box::new(inner_tree),
+    };
+
+    // Apply HAVING as Filter on top of Aggregate
+    if has_having {
+        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+        let having_expr = safe_node_to_expr(inner_select.havingClause)?;
+        let rewritten = rewrite_having_expr(&having_expr, &aggregates);
+        inner_tree = OpTree::Filter {
+            predicate: rewritten,
+            child: Box::new(inner_tree),
+        };
     }
+
+    // Wrap in Subquery so the SemiJoin treats it as a derived table
+    let sub_alias = format!("__pgt_in_sub_{}", inner_tree.alias());
+    inner_tree = OpTree::Subquery {
+        alias: sub_alias,
+        column_aliases: Vec::new(),
+        child: Box::new(inner_tree),
+    };
+
+    Ok((inner_tree, aggregates))
+}
+
+/// Build the semi/anti-join condition pairing the left-hand values of an
+/// `IN` / `NOT IN` with the inner SELECT columns.
+///
+/// `IN` and `IS NOT TRUE` use plain equality, ANDed per column. `NOT IN`
+/// is unknown (and drops the outer row) unless every inner row is known to
+/// differ in some column, so the anti-join must match an inner row whenever
+/// no column pair compares FALSE — including a NULL on either side:
+/// `(a = x) IS NOT FALSE AND (b = y) IS NOT FALSE`.
+fn in_sublink_condition(lhs: Vec<Expr>, rhs: Vec<Expr>, negation: SublinkNegation) -> Expr {
+    let null_aware = negation == SublinkNegation::Not;
+    lhs.into_iter()
+        .zip(rhs)
+        .map(|(l, r)| {
+            if null_aware {
+                Expr::Raw(format!("({} = {}) IS NOT FALSE", l.to_sql(), r.to_sql()))
+            } else {
+                Expr::BinaryOp {
+                    op: "=".to_string(),
+                    left: Box::new(l),
+                    right: Box::new(r),
+                    resolved: Resolved::default(),
+                }
+            }
+        })
+        .reduce(|acc, e| Expr::BinaryOp {
+            op: "AND".to_string(),
+            left: Box::new(acc),
+            right: Box::new(e),
+            resolved: Resolved::default(),
+        })
+        .unwrap_or_else(|| Expr::Literal("TRUE".into()))
 }
 
 /// Parse an ANY SubLink (IN / = ANY) into a SublinkWrapper.
@@ -928,9 +1108,10 @@ fn qualify_inner_col_refs(expr: Expr, alias: &str) -> Expr {
 /// Caller must ensure `sublink` points to a valid `pg_sys::SubLink`.
 fn parse_any_sublink(
     sublink: &pg_sys::SubLink,
-    negated: bool,
+    negation: SublinkNegation,
     cte_ctx: &mut CteParseContext,
 ) -> Result<SublinkWrapper, PgTrickleError> {
+    let negated = negation != SublinkNegation::None;
     if sublink.subselect.is_null() {
         return Err(PgTrickleError::QueryParseError(
             "IN/ANY subquery has NULL subselect".into(),
@@ -971,37 +1152,27 @@ fn parse_any_sublink(
         }
     }
 
-    // Extract the test expression (left-hand side of IN)
-    let test_expr = if sublink.testexpr.is_null() {
+    // Extract the test expression(s) (left-hand side of IN). A row
+    // constructor `(a, b) IN (SELECT x, y ...)` contributes one value per
+    // field.
+    if sublink.testexpr.is_null() {
         return Err(PgTrickleError::QueryParseError(
             "IN subquery has NULL test expression".into(),
         ));
+    }
+    let test_exprs = if let Some(row) = cast_node!(sublink.testexpr, T_RowExpr, pg_sys::RowExpr) {
+        let mut fields = Vec::new();
+        for n in pg_list::<pg_sys::Node>(row.args).iter_ptr() {
+            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+            fields.push(safe_node_to_expr(n)?);
+        }
+        fields
     } else {
         // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        safe_node_to_expr(sublink.testexpr)?
+        vec![safe_node_to_expr(sublink.testexpr)?]
     };
 
-    // ── G12-SQL-IN: Multi-column IN (subquery) guard ────────────────
-    //
-    // Multi-column IN like `(a, b) IN (SELECT x, y FROM t)` produces a
-    // RowExpr test expression with multiple inner SELECT targets. The
-    // DVM semi-join rewrite currently only handles single-column IN:
-    // it extracts the first target column and builds `test = col1`,
-    // silently ignoring additional columns. This produces incorrect
-    // results for multi-column comparisons.
-    //
-    // Detect this case and return a structured error. The DVM would need
-    // composite equality (`a = x AND b = y`) to handle this correctly.
-    if matches!(test_expr, Expr::Raw(ref s) if s.starts_with("ROW(")) {
-        return Err(PgTrickleError::QueryParseError(
-            "multi-column IN (subquery) is not supported in DIFFERENTIAL mode. \
-             Rewrite as EXISTS (SELECT 1 FROM ... WHERE a = x AND b = y) \
-             for equivalent semantics."
-                .into(),
-        ));
-    }
-
-    // Extract the inner SELECT target (the column being compared)
+    // Extract the inner SELECT targets (the columns being compared)
     let target_list = pg_list::<pg_sys::Node>(inner_select.targetList);
     if target_list.is_empty() {
         return Err(PgTrickleError::QueryParseError(
@@ -1009,35 +1180,31 @@ fn parse_any_sublink(
         ));
     }
 
-    // G12-SQL-IN: Also detect multi-column via inner target count
-    if target_list.len() > 1 {
-        return Err(PgTrickleError::QueryParseError(
-            "multi-column IN (subquery) is not supported in DIFFERENTIAL mode: \
-             the inner SELECT returns multiple columns. Rewrite as \
-             EXISTS (SELECT 1 FROM ... WHERE a = x AND b = y) for equivalent semantics."
-                .into(),
-        ));
-    }
-
-    let first_target = target_list.head().ok_or_else(|| {
-        PgTrickleError::InternalError(
-            "IN sublink target_list unexpectedly empty after non-empty check".into(),
-        )
-    })?;
-    let inner_col_expr = if let Some(rt) = cast_node!(first_target, T_ResTarget, pg_sys::ResTarget)
-    {
+    let mut inner_col_exprs = Vec::with_capacity(target_list.len());
+    for target in target_list.iter_ptr() {
+        let Some(rt) = cast_node!(target, T_ResTarget, pg_sys::ResTarget) else {
+            return Err(PgTrickleError::QueryParseError(
+                "IN subquery target is not a ResTarget".into(),
+            ));
+        };
         if rt.val.is_null() {
             return Err(PgTrickleError::QueryParseError(
                 "IN subquery target column is NULL".into(),
             ));
         }
         // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        safe_node_to_expr(rt.val)?
-    } else {
-        return Err(PgTrickleError::QueryParseError(
-            "IN subquery target is not a ResTarget".into(),
-        ));
-    };
+        inner_col_exprs.push(safe_node_to_expr(rt.val)?);
+    }
+
+    // G12-SQL-IN: `(a, b) IN (SELECT x, y ...)` compares column-wise, so
+    // both sides must have the same width.
+    if test_exprs.len() != inner_col_exprs.len() {
+        return Err(PgTrickleError::QueryParseError(format!(
+            "IN subquery returns {} column(s) but the left-hand side has {}",
+            inner_col_exprs.len(),
+            test_exprs.len()
+        )));
+    }
 
     // ── GROUP BY / HAVING handling ──────────────────────────────────
     //
@@ -1050,85 +1217,18 @@ fn parse_any_sublink(
     let has_having = !inner_select.havingClause.is_null();
 
     if has_group_by || has_having {
-        // Apply inner WHERE as a Filter on the FROM tree
-        if !inner_select.whereClause.is_null() {
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let inner_where = safe_node_to_expr(inner_select.whereClause)?;
-            inner_tree = OpTree::Filter {
-                predicate: inner_where,
-                child: Box::new(inner_tree),
-            };
-        }
-
-        // Parse GROUP BY expressions
-        let mut group_by = Vec::new();
-        for node_ptr in group_list.iter_ptr() {
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let expr = safe_node_to_expr(node_ptr)?;
-            group_by.push(expr);
-        }
+        inner_tree = wrap_grouped_sublink_inner(inner_tree, inner_select, &target_list)?.0;
 
-        // Extract aggregates from the target list
-        // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
-        let (mut aggregates, _non_agg_exprs) = unsafe { extract_aggregates(&target_list)? };
-
-        // Extract additional aggregates from HAVING expression.
-        // The HAVING clause may reference aggregates not present in the
-        // SELECT list (e.g., `SELECT col FROM T GROUP BY col HAVING SUM(x) > 0`).
-        if has_having {
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let having_expr = safe_node_to_expr(inner_select.havingClause)?;
-            let having_aggs = extract_aggregates_from_expr(&having_expr, aggregates.len());
-            for ha in &having_aggs {
-                // Avoid duplicates: only add if no existing aggregate matches
-                let already_present = aggregates.iter().any(|a| {
-                    a.function.sql_name() == ha.function.sql_name()
-                        && a.argument.as_ref().map(|e| e.to_sql())
-                            == ha.argument.as_ref().map(|e| e.to_sql())
-                });
-                if !already_present {
-                    aggregates.push(ha.clone());
-                }
-            }
-        }
-
-        // Build Aggregate node
-        inner_tree = OpTree::Aggregate {
-            group_by,
-            aggregates: aggregates.clone(),
-            child: Box::new(inner_tree),
-        };
-
-        // Apply HAVING as Filter on top of Aggregate
-        if has_having {
-            // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let having_expr = safe_node_to_expr(inner_select.havingClause)?;
-            let rewritten = rewrite_having_expr(&having_expr, &aggregates);
-            inner_tree = OpTree::Filter {
-                predicate: rewritten,
-                child: Box::new(inner_tree),
-            };
-        }
-
-        // Wrap in Subquery so the SemiJoin treats it as a derived table
-        let sub_alias = format!("__pgt_in_sub_{}", inner_tree.alias());
-        inner_tree = OpTree::Subquery {
-            alias: sub_alias,
-            column_aliases: Vec::new(),
-            child: Box::new(inner_tree),
-        };
-
-        // Build the equality condition using the output column name.
-        // The inner_col_expr (from the SELECT list) is a group-by column
+        // Build the equality condition using the output column names.
+        // Each inner column (from the SELECT list) is a group-by column
         // that passes through Aggregate → Filter → Subquery.
         // Qualify inner column refs with the Subquery alias to prevent
         // ambiguity when outer and inner tables share column names.
-        let inner_col_qualified = qualify_inner_col_refs(inner_col_expr, inner_tree.alias());
-        let equality = Expr::BinaryOp {
-            op: "=".to_string(),
-            left: Box::new(test_expr),
-            right: Box::new(inner_col_qualified),
-        };
+        let inner_cols_qualified = inner_col_exprs
+            .into_iter()
+            .map(|e| qualify_inner_col_refs(e, inner_tree.alias()))
+            .collect();
+        let equality = in_sublink_condition(test_exprs, inner_cols_qualified, negation);
 
         return Ok(SublinkWrapper {
             negated,
@@ -1143,14 +1243,13 @@ fn parse_any_sublink(
     // ambiguity when outer and inner tables share column names (e.g.,
     // both have "id").  Without this, the join-condition rewriter
     // resolves bare "id" against the left (outer) tree first.
-    let inner_col_qualified = qualify_inner_col_refs(inner_col_expr, inner_tree.alias());
+    let inner_cols_qualified = inner_col_exprs
+        .into_iter()
+        .map(|e| qualify_inner_col_refs(e, inner_tree.alias()))
+        .collect();
 
-    // Build the equality condition: test_expr = inner_col_expr
-    let equality = Expr::BinaryOp {
-        op: "=".to_string(),
-        left: Box::new(test_expr),
-        right: Box::new(inner_col_qualified),
-    };
+    // Build the equality condition: test_expr = inner_col_expr, per column
+    let equality = in_sublink_condition(test_exprs, inner_cols_qualified, negation);
 
     // Combine with inner WHERE clause if present
     let condition = if inner_select.whereClause.is_null() {
@@ -1162,6 +1261,7 @@ fn parse_any_sublink(
             op: "AND".to_string(),
             left: Box::new(equality),
             right: Box::new(inner_where),
+            resolved: Resolved::default(),
         }
     };
 
@@ -1185,7 +1285,9 @@ fn extract_aggregates_from_expr(expr: &Expr, start_idx: usize) -> Vec<AggExpr> {
 
 fn extract_aggregates_from_expr_inner(expr: &Expr, start_idx: usize, out: &mut Vec<AggExpr>) {
     match expr {
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name, args, ..
+        } => {
             let name_lower = func_name.to_lowercase();
             let agg_func = match name_lower.as_str() {
                 "count" => {
@@ -1229,11 +1331,12 @@ fn extract_aggregates_from_expr_inner(expr: &Expr, start_idx: usize, out: &mut V
                 }
             }
         }
-        Expr::BinaryOp { left, right, .. } => {
-            extract_aggregates_from_expr_inner(left, start_idx, out);
-            extract_aggregates_from_expr_inner(right, start_idx, out);
+        // Operators, CASE, casts and constructors: recurse into operands.
+        _ => {
+            for child in expr.children() {
+                extract_aggregates_from_expr_inner(child, start_idx, out);
+            }
         }
-        _ => {}
     }
 }
 
@@ -1316,20 +1419,26 @@ fn parse_all_sublink(
             "ALL sublink target_list unexpectedly empty after non-empty check".into(),
         )
     })?;
-    let inner_col_expr = if let Some(rt) = cast_node!(first_target, T_ResTarget, pg_sys::ResTarget)
-    {
-        if rt.val.is_null() {
+    let (inner_col_expr, target_is_agg, target_has_agg) =
+        if let Some(rt) = cast_node!(first_target, T_ResTarget, pg_sys::ResTarget) {
+            if rt.val.is_null() {
+                return Err(PgTrickleError::QueryParseError(
+                    "ALL subquery target column is NULL".into(),
+                ));
+            }
+            (
+                // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+                safe_node_to_expr(rt.val)?,
+                // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+                unsafe { is_agg_node(rt.val) },
+                // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+                unsafe { expr_contains_agg(rt.val) },
+            )
+        } else {
             return Err(PgTrickleError::QueryParseError(
-                "ALL subquery target column is NULL".into(),
+                "ALL subquery target is not a ResTarget".into(),
             ));
-        }
-        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-        safe_node_to_expr(rt.val)?
-    } else {
-        return Err(PgTrickleError::QueryParseError(
-            "ALL subquery target is not a ResTarget".into(),
-        ));
-    };
+        };
 
     // Extract the comparison operator from operName.
     // For `x = ALL (...)`, operName is a list containing "=".
@@ -1350,11 +1459,65 @@ fn parse_all_sublink(
     // incorrectly included. SQL semantics for ALL require that a NULL
     // in the subquery makes the whole ALL expression indeterminate (hence
     // the outer row should be excluded).
-    let inner_col_sql = inner_col_expr.to_sql();
-    let negated_cond = Expr::Raw(format!(
-        "(({inner_col_sql}) IS NULL OR NOT ({} {op_name} {inner_col_sql}))",
-        test_expr.to_sql()
-    ));
+    let all_violation = |inner_col: &Expr| {
+        let inner_col_sql = inner_col.to_sql();
+        Expr::Raw(format!(
+            "(({inner_col_sql}) IS NULL OR NOT ({} {op_name} {inner_col_sql}))",
+            test_expr.to_sql()
+        ))
+    };
+
+    // ── Aggregated inner SELECT ─────────────────────────────────────
+    //
+    // `x > ALL (SELECT max(v) FROM t GROUP BY g)` compares against one
+    // value per group. Wrap the inner tree the same way as IN with
+    // GROUP BY / HAVING and compare against the Subquery's output column.
+    //
+    // Empty inner relation: with GROUP BY there are no groups, so no
+    // violating row exists and every outer row qualifies — as in
+    // PostgreSQL, where ALL over zero rows is true. Without GROUP BY the
+    // Aggregate is a scalar aggregate: its snapshot (`SELECT max(v) FROM t`)
+    // and its delta (the scalar-aggregate guard in `diff_aggregate`, which
+    // never deletes the singleton row) both keep exactly one row. That row
+    // is NULL for MAX/SUM/… (a violation, so every outer row is excluded,
+    // matching `x > ALL (SELECT NULL)`) and 0 for COUNT (compared like
+    // any other value).
+    let has_grouping = !pg_list::<pg_sys::Node>(inner_select.groupClause).is_empty()
+        || !inner_select.havingClause.is_null();
+    if has_grouping || target_has_agg {
+        if target_has_agg && !target_is_agg {
+            return Err(PgTrickleError::UnsupportedOperator(
+                "ALL (subquery) whose SELECT expression wraps an aggregate is not \
+                 supported in DIFFERENTIAL mode. Select the bare aggregate and move \
+                 the arithmetic to the outer side of the comparison."
+                    .into(),
+            ));
+        }
+        let (wrapped, aggregates) =
+            wrap_grouped_sublink_inner(inner_tree, inner_select, &target_list)?;
+        let inner_col = if target_is_agg {
+            // Target-list aggregates come first; the ALL target is the
+            // only target, so it is the first aggregate.
+            let agg = aggregates.first().ok_or_else(|| {
+                PgTrickleError::InternalError(
+                    "ALL sublink aggregate target produced no aggregate".into(),
+                )
+            })?;
+            Expr::ColumnRef {
+                table_alias: Some(wrapped.alias().to_string()),
+                column_name: agg.alias.clone(),
+            }
+        } else {
+            qualify_inner_col_refs(inner_col_expr, wrapped.alias())
+        };
+        return Ok(SublinkWrapper {
+            negated: !negated,
+            condition: all_violation(&inner_col),
+            inner_tree: wrapped,
+        });
+    }
+
+    let negated_cond = all_violation(&inner_col_expr);
 
     // Combine with inner WHERE clause if present
     let condition = if inner_select.whereClause.is_null() {
@@ -1366,6 +1529,7 @@ fn parse_all_sublink(
             op: "AND".to_string(),
             left: Box::new(negated_cond),
             right: Box::new(inner_where),
+            resolved: Resolved::default(),
         }
     };
 
@@ -1447,6 +1611,9 @@ unsafe fn parse_defining_query_inner(query: &str) -> Result<ParseResult, PgTrick
         ));
     }
 
+    // Operator and cast resolutions for the `Expr` nodes built below.
+    let _resolutions = ResolutionScope::enter(query, raw_stmt);
+
     // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
     let select = pg_deref!(stmt_ptr);
 
@@ -1732,6 +1899,22 @@ unsafe fn parse_select_stmt(
     _full_query: &str,
     cte_ctx: &mut CteParseContext,
 ) -> Result<OpTree, PgTrickleError> {
+    // DISTINCT ON below the top level (FROM subqueries, inlined views) is
+    // lowered to `ROW_NUMBER() ... = 1` the same way `rewrite_distinct_on`
+    // lowers the outermost SELECT, and the rewritten statement is parsed.
+    if has_distinct_on(select) {
+        let sql = deparse_select_stmt_with_view_subs(select as *const _, &[])?;
+        let lowered = rewrite_distinct_on(&sql)?;
+        if lowered != sql
+            && let Some(lowered_select) = parse_first_select(&lowered)?
+        {
+            // SAFETY: Pointer returned by raw_parser for `lowered`; valid within current memory context.
+            let lowered_select = pg_deref!(lowered_select);
+            // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+            return unsafe { parse_select_stmt(lowered_select, &lowered, cte_ctx) };
+        }
+    }
+
     // G13-SD: Track recursion depth.
     let prev_depth = cte_ctx.descend()?;
     // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
@@ -1892,7 +2075,20 @@ unsafe fn parse_select_stmt_inner(
     let has_aggregates = unsafe { target_list_has_aggregates(&target_list) };
     let has_windows = target_list_has_windows(&target_list);
 
-    if has_windows {
+    if let Some(native) = native_grouping_sets(select)? {
+        // ── GSETS-NATIVE: GROUPING SETS / CUBE / ROLLUP ───────────────
+        // Kept in the query by `rewrite_grouping_sets`; one node maintains
+        // every grouping set from a single pass over the child delta.
+        // SAFETY: Parse-tree pointer from PostgreSQL's raw_parser; valid within current memory context.
+        let (aggregates, _non_agg_exprs) = unsafe { extract_aggregates(&target_list)? };
+        tree = OpTree::GroupingSets {
+            group_by: native.group_by,
+            sets: native.sets,
+            aggregates,
+            grouping_funcs: native.grouping_funcs,
+            child: Box::new(tree),
+        };
+    } else if has_windows {
         // ── Window function path ───────────────────────────────────────
         // Extract window expressions and pass-through columns.
         let (window_exprs, pass_through) =
@@ -1978,7 +2174,7 @@ unsafe fn parse_select_stmt_inner(
         // the Nth output column. Resolve these to the actual target
         // expressions now so that rename detection below can match them.
         for gb_expr in &mut group_by {
-            if let Expr::Raw(s) = &gb_expr
+            if let Expr::Literal(s) | Expr::Raw(s) = &gb_expr
                 && let Ok(pos) = s.parse::<usize>()
                 && pos >= 1
                 && pos <= target_exprs.len()
@@ -2076,9 +2272,10 @@ unsafe fn parse_select_stmt_inner(
         let distinct_list = pg_list::<pg_sys::Node>(select.distinctClause);
         let has_real_exprs = distinct_list.iter_ptr().any(|ptr| !ptr.is_null());
         if has_real_exprs {
-            // DISTINCT ON (expr, ...) — reject
+            // DISTINCT ON (expr, ...) that could not be lowered to
+            // ROW_NUMBER() by parse_select_stmt — reject
             return Err(PgTrickleError::UnsupportedOperator(
-                "DISTINCT ON is not supported in defining queries. \
+                "DISTINCT ON is not supported in this position of a defining query. \
                  Use plain DISTINCT or rewrite with window functions."
                     .into(),
             ));
@@ -2219,7 +2416,7 @@ unsafe fn parse_appended_scalar_target_subqueries(
 ///
 /// Accepts both:
 /// - raw parser `T_SubLink` nodes for `EXPR_SUBLINK`, and
-/// - `Expr::Raw("(SELECT ...)")` fallback expressions produced by `node_to_expr()`.
+/// - `Expr::SubLink` / `Expr::Raw("(SELECT ...)")` expressions produced by `node_to_expr()`.
 unsafe fn parse_scalar_target_subquery(
     node: *mut pg_sys::Node,
     cte_ctx: &mut CteParseContext,
@@ -2268,8 +2465,10 @@ unsafe fn parse_scalar_target_subquery(
 
     // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
     let expr = safe_node_to_expr(node)?;
-    let Expr::Raw(raw_sql) = expr else {
-        return Ok(None);
+    let raw_sql = match expr {
+        Expr::Raw(sql) => sql,
+        sublink @ Expr::SubLink { .. } => sublink.to_sql(),
+        _ => return Ok(None),
     };
 
     let Some(inner_sql) = extract_bare_scalar_subquery_sql(&raw_sql) else {
@@ -2782,13 +2981,10 @@ unsafe fn parse_from_item_inner(
             ));
         }
 
-        // We support a single function. ROWS FROM(f1, f2, ...) is not supported.
+        // ROWS FROM(f1, f2, ...) zips several SRFs into one lateral source.
         if rf.is_rowsfrom && func_list.len() > 1 {
-            return Err(PgTrickleError::UnsupportedOperator(
-                "ROWS FROM() with multiple functions is not supported. \
-                 Use a single set-returning function in FROM instead."
-                    .into(),
-            ));
+            // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
+            return unsafe { parse_rows_from(rf) };
         }
 
         // The first element is a List node; its first element is the FuncCall.
@@ -2870,14 +3066,15 @@ unsafe fn parse_from_item_inner(
             "jt".to_string()
         };
 
-        // Extract column aliases from the alias node
-        let column_aliases = if !jt.alias.is_null() {
+        // Output columns are the COLUMNS entries with NESTED PATH columns
+        // flattened in place; `AS jt(c1, ...)` renames a leading prefix.
+        // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
+        let mut column_aliases = unsafe { json_table_column_names(jt.columns) };
+        if !jt.alias.is_null() {
             // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
             let a = pg_deref!(jt.alias);
-            extract_alias_colnames(a)?
-        } else {
-            Vec::new()
-        };
+            apply_alias_colnames(&mut column_aliases, extract_alias_colnames(a)?)?;
+        }
 
         // JSON_TABLE is inherently lateral (references the left-hand table).
         // Model it as LateralFunction with a placeholder child that gets
@@ -3057,6 +3254,129 @@ pub(crate) fn extract_alias_colnames(alias: &pg_sys::Alias) -> Result<Vec<String
     Ok(names)
 }
 
+/// Rename the leading output columns of a FROM item with the names from
+/// `AS alias(c1, c2, ...)`, the way PostgreSQL applies a partial alias list.
+fn apply_alias_colnames(
+    columns: &mut [String],
+    aliases: Vec<String>,
+) -> Result<(), PgTrickleError> {
+    if aliases.len() > columns.len() {
+        return Err(PgTrickleError::QueryParseError(format!(
+            "table alias specifies {} columns but the function returns {}",
+            aliases.len(),
+            columns.len(),
+        )));
+    }
+    for (column, alias) in columns.iter_mut().zip(aliases) {
+        *column = alias;
+    }
+    Ok(())
+}
+
+/// Parse `ROWS FROM (f1(...), f2(...) AS (c type, ...), ...)` into a
+/// [`OpTree::LateralFunction`].
+///
+/// The zipped call (including any `WITH ORDINALITY`) is kept verbatim in
+/// `func_sql` so PostgreSQL pads the shorter results with NULLs exactly as
+/// in the defining query. Every output column is resolved here — per
+/// function from its column definition list, the known JSON SRF names or
+/// the function name, then the ordinal — and stored in `column_aliases`.
+///
+/// # Safety
+/// `rf` must be a valid `RangeFunction` from raw_parser with `is_rowsfrom`.
+unsafe fn parse_rows_from(rf: &pg_sys::RangeFunction) -> Result<OpTree, PgTrickleError> {
+    let mut func_sqls = Vec::new();
+    let mut column_aliases = Vec::new();
+    for item in pg_list::<pg_sys::Node>(rf.functions).iter_ptr() {
+        // SAFETY: Each element of `functions` is a [FuncCall, coldeflist] List.
+        let (sql, cols) = unsafe { deparse_rows_from_item(item as *mut pg_sys::List)? };
+        func_sqls.push(sql);
+        column_aliases.extend(cols);
+    }
+
+    let mut func_sql = format!("ROWS FROM ({})", func_sqls.join(", "));
+    if rf.ordinality {
+        func_sql.push_str(" WITH ORDINALITY");
+        column_aliases.push("ordinality".to_string());
+    }
+
+    let alias = if !rf.alias.is_null() {
+        // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
+        let a = pg_deref!(rf.alias);
+        apply_alias_colnames(&mut column_aliases, extract_alias_colnames(a)?)?;
+        pg_cstr_to_str(a.aliasname).unwrap_or("srf").to_string()
+    } else {
+        "srf".to_string()
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    if let Some(dup) = column_aliases.iter().find(|c| !seen.insert(c.as_str())) {
+        return Err(PgTrickleError::UnsupportedOperator(format!(
+            "ROWS FROM() produces more than one column named \"{dup}\". \
+             Name the columns with a column alias list, e.g. AS {alias}(a, b)."
+        )));
+    }
+
+    // Placeholder child; the real child is attached in the FROM-list loop.
+    Ok(OpTree::LateralFunction {
+        func_sql,
+        alias,
+        column_aliases,
+        with_ordinality: false,
+        child: Box::new(OpTree::Scan {
+            table_oid: 0,
+            table_name: String::new(),
+            schema: String::new(),
+            columns: vec![],
+            pk_columns: vec![],
+            alias: String::new(),
+        }),
+    })
+}
+
+/// Deparse one `ROWS FROM` item — a `[FuncCall, coldeflist]` List — to
+/// `f(args)` or `f(args) AS (c1 type, ...)`, plus its output column names.
+///
+/// # Safety
+/// `item` must be a valid two-element List from a `RangeFunction`.
+unsafe fn deparse_rows_from_item(
+    item: *mut pg_sys::List,
+) -> Result<(String, Vec<String>), PgTrickleError> {
+    let parts = pg_list::<pg_sys::Node>(item);
+    let func_node = parts
+        .head()
+        .ok_or_else(|| PgTrickleError::QueryParseError("ROWS FROM item has no function".into()))?;
+    // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
+    if !is_node_type!(func_node, T_FuncCall) {
+        return Err(PgTrickleError::QueryParseError(
+            "ROWS FROM item does not contain a FuncCall node".into(),
+        ));
+    }
+    // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
+    let func_sql = unsafe { deparse_func_call(func_node as *const pg_sys::FuncCall)? };
+
+    let coldefs = parts.get_ptr(1).unwrap_or(std::ptr::null_mut());
+    let mut defs = Vec::new();
+    let mut names = Vec::new();
+    for node_ptr in pg_list::<pg_sys::Node>(coldefs as *mut pg_sys::List).iter_ptr() {
+        let Some(cd) = cast_node!(node_ptr, T_ColumnDef, pg_sys::ColumnDef) else {
+            continue;
+        };
+        let name = pg_cstr_to_str(cd.colname)?.to_string();
+        // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
+        let type_name = unsafe { deparse_typename(cd.typeName) };
+        defs.push(format!("{name} {type_name}"));
+        names.push(name);
+    }
+
+    if defs.is_empty() {
+        let names = rows_from_function_columns(&func_sql);
+        Ok((func_sql, names))
+    } else {
+        Ok((format!("{func_sql} AS ({})", defs.join(", ")), names))
+    }
+}
+
 /// Resolve a table name to its OID via SPI.
 fn resolve_table_oid(schema: &str, table: &str) -> Result<u32, PgTrickleError> {
     let sql = format!(
@@ -3260,7 +3580,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
     // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
     } else if is_node_type!(node, T_A_Const) {
         // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
-        Ok(Expr::Raw(unsafe { deparse_node(node) }))
+        Ok(Expr::Literal(unsafe { deparse_node(node) }))
     } else if let Some(aexpr) = cast_node!(node, T_A_Expr, pg_sys::A_Expr) {
         match aexpr.kind {
             pg_sys::A_Expr_Kind::AEXPR_OP => {
@@ -3270,7 +3590,11 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                 if aexpr.lexpr.is_null() {
                     // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
                     let right = safe_node_to_expr(aexpr.rexpr)?;
-                    return Ok(Expr::Raw(format!("{op_name}{}", right.to_sql())));
+                    return Ok(Expr::UnaryOp {
+                        op: op_name,
+                        expr: Box::new(right),
+                        resolved: resolved_operator(aexpr),
+                    });
                 }
                 // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
                 let left = safe_node_to_expr(aexpr.lexpr)?;
@@ -3280,31 +3604,27 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                     op: op_name,
                     left: Box::new(left),
                     right: Box::new(right),
+                    resolved: resolved_operator(aexpr),
                 })
             }
-            pg_sys::A_Expr_Kind::AEXPR_DISTINCT => {
-                // IS DISTINCT FROM
-                // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-                let left = safe_node_to_expr(aexpr.lexpr)?;
-                // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-                let right = safe_node_to_expr(aexpr.rexpr)?;
-                Ok(Expr::Raw(format!(
-                    "{} IS DISTINCT FROM {}",
-                    left.to_sql(),
-                    right.to_sql()
-                )))
-            }
-            pg_sys::A_Expr_Kind::AEXPR_NOT_DISTINCT => {
-                // IS NOT DISTINCT FROM
+            pg_sys::A_Expr_Kind::AEXPR_DISTINCT | pg_sys::A_Expr_Kind::AEXPR_NOT_DISTINCT => {
+                // IS [NOT] DISTINCT FROM — analysis resolves the `=` operator
+                // it is built on at the same location.
+                let op = if aexpr.kind == pg_sys::A_Expr_Kind::AEXPR_DISTINCT {
+                    "IS DISTINCT FROM"
+                } else {
+                    "IS NOT DISTINCT FROM"
+                };
                 // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
                 let left = safe_node_to_expr(aexpr.lexpr)?;
                 // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
                 let right = safe_node_to_expr(aexpr.rexpr)?;
-                Ok(Expr::Raw(format!(
-                    "{} IS NOT DISTINCT FROM {}",
-                    left.to_sql(),
-                    right.to_sql()
-                )))
+                Ok(Expr::BinaryOp {
+                    op: op.to_string(),
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    resolved: resolved_operator(aexpr),
+                })
             }
             pg_sys::A_Expr_Kind::AEXPR_IN => {
                 // x IN (v1, v2, v3)
@@ -3530,6 +3850,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                         op: "AND".to_string(),
                         left: Box::new(result),
                         right: Box::new(arg.clone()),
+                        resolved: Resolved::default(),
                     };
                 }
                 Ok(result)
@@ -3546,6 +3867,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                         op: "OR".to_string(),
                         left: Box::new(result),
                         right: Box::new(arg.clone()),
+                        resolved: Resolved::default(),
                     };
                 }
                 Ok(result)
@@ -3557,6 +3879,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                 Ok(Expr::FuncCall {
                     func_name: "NOT".to_string(),
                     args: vec![inner],
+                    resolved: Resolved::default(),
                 })
             }
             _ => Ok(Expr::Raw("/* unknown bool expr */".to_string())),
@@ -3623,6 +3946,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
             Ok(Expr::FuncCall {
                 func_name,
                 args: vec![Expr::Raw("*".to_string())],
+                resolved: resolved_function(fcall),
             })
         } else {
             let args_list = pg_list::<pg_sys::Node>(fcall.args);
@@ -3633,18 +3957,23 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                     args.push(e);
                 }
             }
-            Ok(Expr::FuncCall { func_name, args })
+            Ok(Expr::FuncCall {
+                func_name,
+                args,
+                resolved: resolved_function(fcall),
+            })
         }
     } else if let Some(tc) = cast_node!(node, T_TypeCast, pg_sys::TypeCast) {
         // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
         let inner = safe_node_to_expr(tc.arg)?;
         // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
         let type_name = unsafe { deparse_typename(tc.typeName) };
-        Ok(Expr::Raw(format!(
-            "CAST({} AS {})",
-            inner.to_sql(),
+        let resolved = resolved_cast(tc, &inner);
+        Ok(Expr::Cast {
+            expr: Box::new(inner),
             type_name,
-        )))
+            resolved,
+        })
     } else if let Some(nt) = cast_node!(node, T_NullTest, pg_sys::NullTest) {
         // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
         let arg = safe_node_to_expr(nt.arg as *mut pg_sys::Node)?;
@@ -3655,16 +3984,18 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
         };
         Ok(Expr::Raw(format!("{} {op}", arg.to_sql())))
     } else if let Some(case_expr) = cast_node!(node, T_CaseExpr, pg_sys::CaseExpr) {
-        let mut sql = String::from("CASE");
-
         // Simple CASE: CASE <arg> WHEN ...
-        if !case_expr.arg.is_null() {
+        let operand = if !case_expr.arg.is_null() {
             // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let arg = safe_node_to_expr(case_expr.arg as *mut pg_sys::Node)?;
-            sql.push_str(&format!(" {}", arg.to_sql()));
-        }
+            Some(Box::new(safe_node_to_expr(
+                case_expr.arg as *mut pg_sys::Node,
+            )?))
+        } else {
+            None
+        };
 
         let when_list = pg_list::<pg_sys::Node>(case_expr.args);
+        let mut whens = Vec::new();
         for w in when_list.iter_ptr() {
             // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
             let case_when = pg_deref!(w as *const pg_sys::CaseWhen);
@@ -3672,17 +4003,23 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
             let cond = safe_node_to_expr(case_when.expr as *mut pg_sys::Node)?;
             // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
             let result = safe_node_to_expr(case_when.result as *mut pg_sys::Node)?;
-            sql.push_str(&format!(" WHEN {} THEN {}", cond.to_sql(), result.to_sql()));
+            whens.push((cond, result));
         }
 
-        if !case_expr.defresult.is_null() {
+        let else_result = if !case_expr.defresult.is_null() {
             // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            let def = safe_node_to_expr(case_expr.defresult as *mut pg_sys::Node)?;
-            sql.push_str(&format!(" ELSE {}", def.to_sql()));
-        }
+            Some(Box::new(safe_node_to_expr(
+                case_expr.defresult as *mut pg_sys::Node,
+            )?))
+        } else {
+            None
+        };
 
-        sql.push_str(" END");
-        Ok(Expr::Raw(sql))
+        Ok(Expr::Case {
+            operand,
+            whens,
+            else_result,
+        })
     } else if let Some(coalesce) = cast_node!(node, T_CoalesceExpr, pg_sys::CoalesceExpr) {
         let args_list = pg_list::<pg_sys::Node>(coalesce.args);
         let mut args = Vec::new();
@@ -3693,6 +4030,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
         Ok(Expr::FuncCall {
             func_name: "COALESCE".to_string(),
             args,
+            resolved: Resolved::default(),
         })
     } else if let Some(nullif) = cast_node!(node, T_NullIfExpr, pg_sys::NullIfExpr) {
         let args_list = pg_list::<pg_sys::Node>(nullif.args);
@@ -3704,6 +4042,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
         Ok(Expr::FuncCall {
             func_name: "NULLIF".to_string(),
             args,
+            resolved: Resolved::default(),
         })
     } else if let Some(mmexpr) = cast_node!(node, T_MinMaxExpr, pg_sys::MinMaxExpr) {
         let func_name = if mmexpr.op == pg_sys::MinMaxOp::IS_GREATEST {
@@ -3720,6 +4059,7 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
         Ok(Expr::FuncCall {
             func_name: func_name.to_string(),
             args,
+            resolved: Resolved::default(),
         })
     } else if let Some(svf) = cast_node!(node, T_SQLValueFunction, pg_sys::SQLValueFunction) {
         let kw = sql_value_function_name(svf.op);
@@ -3740,26 +4080,33 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
     } else if let Some(sublink) = cast_node!(node, T_SubLink, pg_sys::SubLink) {
         match sublink.subLinkType {
             pg_sys::SubLinkType::EXPR_SUBLINK => {
-                // Scalar subquery — reconstruct as Raw SQL
-                // The subselect is a SelectStmt; deparse it back to SQL
+                // Scalar subquery — the subselect is a SelectStmt; deparse it
+                // back to SQL
                 if sublink.subselect.is_null() {
                     return Err(PgTrickleError::QueryParseError(
                         "Scalar subquery has NULL subselect".into(),
                     ));
                 }
-                let inner_sql = deparse_select_to_sql(sublink.subselect)?;
-                Ok(Expr::Raw(format!("({inner_sql})")))
+                let query = subquery_body(sublink.subselect)?;
+                Ok(Expr::SubLink {
+                    kind: SubLinkKind::Scalar,
+                    test: None,
+                    query: Box::new(query),
+                })
             }
             pg_sys::SubLinkType::EXISTS_SUBLINK => {
                 // EXISTS in an expression context (e.g., inside CASE WHEN)
-                // Reconstruct as Raw SQL
                 if sublink.subselect.is_null() {
                     return Err(PgTrickleError::QueryParseError(
                         "EXISTS subquery has NULL subselect".into(),
                     ));
                 }
-                let inner_sql = deparse_select_to_sql(sublink.subselect)?;
-                Ok(Expr::Raw(format!("EXISTS ({inner_sql})")))
+                let query = subquery_body(sublink.subselect)?;
+                Ok(Expr::SubLink {
+                    kind: SubLinkKind::Exists,
+                    test: None,
+                    query: Box::new(query),
+                })
             }
             pg_sys::SubLinkType::ANY_SUBLINK => {
                 // IN/ANY in an expression context
@@ -3770,8 +4117,12 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
                 }
                 // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
                 let test = safe_node_to_expr(sublink.testexpr)?;
-                let inner_sql = deparse_select_to_sql(sublink.subselect)?;
-                Ok(Expr::Raw(format!("{} IN ({inner_sql})", test.to_sql())))
+                let query = subquery_body(sublink.subselect)?;
+                Ok(Expr::SubLink {
+                    kind: SubLinkKind::In,
+                    test: Some(Box::new(test)),
+                    query: Box::new(query),
+                })
             }
             _ => {
                 let kind_desc = match sublink.subLinkType {
@@ -3786,20 +4137,20 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
         }
     } else if let Some(arrexpr) = cast_node!(node, T_ArrayExpr, pg_sys::ArrayExpr) {
         let elems = pg_list::<pg_sys::Node>(arrexpr.elements);
-        let mut elem_sql = Vec::new();
+        let mut items = Vec::new();
         for n in elems.iter_ptr() {
             // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            elem_sql.push(safe_node_to_expr(n)?.to_sql());
+            items.push(safe_node_to_expr(n)?);
         }
-        Ok(Expr::Raw(format!("ARRAY[{}]", elem_sql.join(", "))))
+        Ok(Expr::Array(items))
     } else if let Some(rowexpr) = cast_node!(node, T_RowExpr, pg_sys::RowExpr) {
         let fields = pg_list::<pg_sys::Node>(rowexpr.args);
-        let mut field_sql = Vec::new();
+        let mut items = Vec::new();
         for n in fields.iter_ptr() {
             // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
-            field_sql.push(safe_node_to_expr(n)?.to_sql());
+            items.push(safe_node_to_expr(n)?);
         }
-        Ok(Expr::Raw(format!("ROW({})", field_sql.join(", "))))
+        Ok(Expr::Row(items))
     } else if let Some(indir) = cast_node!(node, T_A_Indirection, pg_sys::A_Indirection) {
         // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
         let base = safe_node_to_expr(indir.arg)?;
@@ -4014,6 +4365,122 @@ pub(crate) unsafe fn node_to_expr(node: *mut pg_sys::Node) -> Result<Expr, PgTri
     }
 }
 
+/// Build the [`SubQuery`] of a raw SubLink `subselect`: its SQL text and
+/// the scalar expressions of its body.
+///
+/// The expressions cover the target list, quals, grouping, ordering and
+/// limits, plus those of set-operation branches, CTEs, FROM subqueries,
+/// join quals and table functions.  If any part cannot be converted (or
+/// is not visited, like named windows), the whole body is kept as a single
+/// `Expr::Raw` instead, so checks that walk `exprs` still see all of it.
+fn subquery_body(subselect: *mut pg_sys::Node) -> Result<SubQuery, PgTrickleError> {
+    let sql = deparse_select_to_sql(subselect)?;
+    let mut body = SubQueryExprs {
+        exprs: Vec::new(),
+        complete: true,
+    };
+    body.select(subselect);
+    let exprs = if body.complete {
+        body.exprs
+    } else {
+        vec![Expr::Raw(sql.clone())]
+    };
+    Ok(SubQuery { sql, exprs })
+}
+
+/// Accumulator for [`subquery_body`].
+struct SubQueryExprs {
+    exprs: Vec<Expr>,
+    complete: bool,
+}
+
+impl SubQueryExprs {
+    fn expr(&mut self, node: *mut pg_sys::Node) {
+        if node.is_null() {
+            return;
+        }
+        // SAFETY: Node pointer from a valid parse-tree list; allocated by raw_parser.
+        match safe_node_to_expr(node) {
+            Ok(expr) => self.exprs.push(expr),
+            Err(_) => self.complete = false,
+        }
+    }
+
+    fn expr_list(&mut self, list: *mut pg_sys::List) {
+        for node in pg_list::<pg_sys::Node>(list).iter_ptr() {
+            self.expr(node);
+        }
+    }
+
+    fn select(&mut self, node: *mut pg_sys::Node) {
+        let Some(select) = cast_node!(node, T_SelectStmt, pg_sys::SelectStmt) else {
+            self.complete = false;
+            return;
+        };
+        if !select.withClause.is_null() {
+            // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
+            let with = pg_deref!(select.withClause);
+            for cte in pg_list::<pg_sys::Node>(with.ctes).iter_ptr() {
+                match cast_node!(cte, T_CommonTableExpr, pg_sys::CommonTableExpr) {
+                    Some(cte) => self.select(cte.ctequery),
+                    None => self.complete = false,
+                }
+            }
+        }
+        if select.op != pg_sys::SetOperation::SETOP_NONE {
+            self.select(select.larg as *mut pg_sys::Node);
+            self.select(select.rarg as *mut pg_sys::Node);
+        }
+        if !select.windowClause.is_null() {
+            self.complete = false;
+        }
+
+        for target in pg_list::<pg_sys::Node>(select.targetList).iter_ptr() {
+            match cast_node!(target, T_ResTarget, pg_sys::ResTarget) {
+                Some(rt) => self.expr(rt.val),
+                None => self.complete = false,
+            }
+        }
+        for item in pg_list::<pg_sys::Node>(select.fromClause).iter_ptr() {
+            self.from_item(item);
+        }
+        self.expr_list(select.distinctClause);
+        self.expr(select.whereClause);
+        self.expr_list(select.groupClause);
+        self.expr(select.havingClause);
+        for sort in pg_list::<pg_sys::Node>(select.sortClause).iter_ptr() {
+            match cast_node!(sort, T_SortBy, pg_sys::SortBy) {
+                Some(sb) => self.expr(sb.node),
+                None => self.complete = false,
+            }
+        }
+        self.expr(select.limitOffset);
+        self.expr(select.limitCount);
+        for row in pg_list::<pg_sys::List>(select.valuesLists).iter_ptr() {
+            self.expr_list(row);
+        }
+    }
+
+    fn from_item(&mut self, node: *mut pg_sys::Node) {
+        if let Some(join) = cast_node!(node, T_JoinExpr, pg_sys::JoinExpr) {
+            self.from_item(join.larg);
+            self.from_item(join.rarg);
+            self.expr(join.quals);
+        } else if let Some(sub) = cast_node!(node, T_RangeSubselect, pg_sys::RangeSubselect) {
+            self.select(sub.subquery);
+        } else if let Some(rf) = cast_node!(node, T_RangeFunction, pg_sys::RangeFunction) {
+            // Each element is a (function call, column definitions) pair.
+            for pair in pg_list::<pg_sys::List>(rf.functions).iter_ptr() {
+                if let Some(call) = pg_list::<pg_sys::Node>(pair).head() {
+                    self.expr(call);
+                }
+            }
+        } else if !is_node_type!(node, T_RangeVar) {
+            self.complete = false;
+        }
+    }
+}
+
 /// Append a `RETURNING <type>` clause from a `JsonOutput` node to a SQL string.
 ///
 /// # Safety
@@ -4145,6 +4612,28 @@ unsafe fn deparse_json_table(jt: *const pg_sys::JsonTable) -> Result<String, PgT
     Ok(sql)
 }
 
+/// Output column names of a JSON_TABLE COLUMNS list, in PostgreSQL's order:
+/// declaration order, with the columns of each `NESTED PATH` spliced in at
+/// the position of the NESTED clause.
+///
+/// # Safety
+/// `columns` must be null or a valid pg_sys::List of JsonTableColumn nodes.
+unsafe fn json_table_column_names(columns: *mut pg_sys::List) -> Vec<String> {
+    let mut names = Vec::new();
+    for node_ptr in pg_list::<pg_sys::Node>(columns).iter_ptr() {
+        let Some(col) = cast_node!(node_ptr, T_JsonTableColumn, pg_sys::JsonTableColumn) else {
+            continue;
+        };
+        if col.coltype == pg_sys::JsonTableColumnType::JTC_NESTED {
+            // SAFETY: Nested column lists come from the same parse tree.
+            names.extend(unsafe { json_table_column_names(col.columns) });
+        } else if let Ok(name) = pg_cstr_to_str(col.name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
 /// Deparse the PASSING clause of JSON_TABLE.
 ///
 /// # Safety
@@ -4691,6 +5180,23 @@ pub(crate) unsafe fn deparse_from_item_to_sql(
                 "RangeFunction with no functions in deparse".into(),
             ));
         }
+        if rf.is_rowsfrom && func_list.len() > 1 {
+            let mut items = Vec::new();
+            for item in func_list.iter_ptr() {
+                // SAFETY: Each element of `functions` is a [FuncCall, coldeflist] List.
+                let (sql, _) = unsafe { deparse_rows_from_item(item as *mut pg_sys::List)? };
+                items.push(sql);
+            }
+            let mut result = format!("ROWS FROM ({})", items.join(", "));
+            if rf.ordinality {
+                result.push_str(" WITH ORDINALITY");
+            }
+            if !rf.alias.is_null() {
+                // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
+                result.push_str(&deparse_alias_clause(pg_deref!(rf.alias))?);
+            }
+            return Ok(result);
+        }
         let inner_list_node = func_list.head().ok_or_else(|| {
             PgTrickleError::QueryParseError(
                 "RangeFunction func_list head is None in deparse".into(),
@@ -4710,11 +5216,12 @@ pub(crate) unsafe fn deparse_from_item_to_sql(
         // SAFETY: Parse-tree node pointers from raw_parser; valid within current memory context.
         let func_sql = unsafe { deparse_func_call(func_node as *const pg_sys::FuncCall)? };
         let mut result = func_sql;
+        if rf.ordinality {
+            result.push_str(" WITH ORDINALITY");
+        }
         if !rf.alias.is_null() {
             // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-            let a = pg_deref!(rf.alias);
-            let alias_name = pg_cstr_to_str(a.aliasname).unwrap_or("");
-            result.push_str(&format!(" AS {alias_name}"));
+            result.push_str(&deparse_alias_clause(pg_deref!(rf.alias))?);
         }
         Ok(result)
     } else if let Some(jt) = cast_node!(node, T_JsonTable, pg_sys::JsonTable) {
@@ -4722,9 +5229,7 @@ pub(crate) unsafe fn deparse_from_item_to_sql(
         let mut result = unsafe { deparse_json_table(jt as *const pg_sys::JsonTable)? };
         if !jt.alias.is_null() {
             // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
-            let a = pg_deref!(jt.alias);
-            let alias_name = pg_cstr_to_str(a.aliasname).unwrap_or("");
-            result.push_str(&format!(" AS {alias_name}"));
+            result.push_str(&deparse_alias_clause(pg_deref!(jt.alias))?);
         }
         Ok(result)
     } else {
@@ -4735,6 +5240,17 @@ pub(crate) unsafe fn deparse_from_item_to_sql(
     }
 }
 
+/// Deparse ` AS alias` or ` AS alias(c1, c2, ...)` for a FROM item.
+fn deparse_alias_clause(alias: &pg_sys::Alias) -> Result<String, PgTrickleError> {
+    let alias_name = pg_cstr_to_str(alias.aliasname).unwrap_or("");
+    let colnames = extract_alias_colnames(alias)?;
+    if colnames.is_empty() {
+        Ok(format!(" AS {alias_name}"))
+    } else {
+        Ok(format!(" AS {alias_name}({})", colnames.join(", ")))
+    }
+}
+
 /// Deparse a sort clause (list of SortBy nodes) into SQL text.
 ///
 /// # Safety
@@ -5026,7 +5542,9 @@ pub(crate) fn collect_correlation_equalities(
     out: &mut Vec<CorrelationPredicate>,
 ) {
     match expr {
-        Expr::BinaryOp { op, left, right } if op == "=" => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op == "=" => {
             // Check if this is inner.col = outer.col or outer.col = inner.col
             if let Some(pred) =
                 try_extract_correlation(left, right, inner_aliases, inner_alias_oids)
@@ -5038,7 +5556,9 @@ pub(crate) fn collect_correlation_equalities(
                 out.push(pred);
             }
         }
-        Expr::BinaryOp { op, left, right } if op == "AND" => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op == "AND" => {
             collect_correlation_equalities(left, inner_aliases, inner_alias_oids, out);
             collect_correlation_equalities(right, inner_aliases, inner_alias_oids, out);
         }
@@ -5128,7 +5648,10 @@ unsafe fn deparse_node(node: *mut pg_sys::Node) -> String {
         if is_node_type!(val_ptr as *mut pg_sys::Node, T_String) {
             // SAFETY: Pointer verified non-null; parse-tree node allocated by raw_parser in a valid memory context.
             let s = pg_deref!(val_ptr as *const pg_sys::String);
-            return format!("'{}'", pg_cstr_to_str(s.sval).unwrap_or(""));
+            return format!(
+                "'{}'",
+                pg_cstr_to_str(s.sval).unwrap_or("").replace('\'', "''")
+            );
         }
         // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
         if is_node_type!(val_ptr as *mut pg_sys::Node, T_Integer) {
@@ -5871,7 +6394,7 @@ unsafe fn target_list_has_aggregates(target_list: &pgrx::PgList<pg_sys::Node>) -
 /// Check if a single raw-parse-tree node is an aggregate function call.
 ///
 /// Does NOT recurse — only checks the immediate node.
-unsafe fn is_agg_node(node: *mut pg_sys::Node) -> bool {
+pub(crate) unsafe fn is_agg_node(node: *mut pg_sys::Node) -> bool {
     if node.is_null() {
         return false;
     }
@@ -5945,7 +6468,7 @@ unsafe extern "C-unwind" fn agg_check_walker(
 /// Uses PostgreSQL's `raw_expression_tree_walker_impl` for correct
 /// recursion into all node types (A_Expr, CaseExpr, BoolExpr, etc.).
 #[cfg(not(test))]
-unsafe fn expr_contains_agg(node: *mut pg_sys::Node) -> bool {
+pub(crate) unsafe fn expr_contains_agg(node: *mut pg_sys::Node) -> bool {
     if node.is_null() {
         return false;
     }
@@ -5962,7 +6485,7 @@ unsafe fn expr_contains_agg(node: *mut pg_sys::Node) -> bool {
 }
 
 #[cfg(test)]
-unsafe fn expr_contains_agg(node: *mut pg_sys::Node) -> bool {
+pub(crate) unsafe fn expr_contains_agg(node: *mut pg_sys::Node) -> bool {
     if node.is_null() {
         return false;
     }
@@ -6329,6 +6852,10 @@ unsafe fn extract_aggregates(
                 non_aggs.push(expr);
             }
         // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
+        } else if is_node_type!(rt.val, T_GroupingFunc) {
+            // GROUPING() outputs are computed by the GroupingSets node.
+            continue;
+        // SAFETY: is_a reads the node tag field, valid for any non-null Node* from the parser.
         } else if is_node_type!(rt.val, T_JsonObjectAgg) {
             // ── F11: SQL/JSON standard JSON_OBJECTAGG(key: value ...) ──
             // This node type is separate from T_FuncCall, so we handle it
@@ -6511,7 +7038,11 @@ unsafe fn target_alias_for_res_target(rt: &pg_sys::ResTarget, ordinal: usize) ->
 /// re-invoke the aggregate function.
 pub(crate) fn rewrite_having_expr(expr: &Expr, aggregates: &[AggExpr]) -> Expr {
     match expr {
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name,
+            args,
+            resolved,
+        } => {
             let name_lower = func_name.to_lowercase();
             for agg in aggregates {
                 let agg_name = agg.function.sql_name().to_lowercase();
@@ -6542,14 +7073,21 @@ pub(crate) fn rewrite_having_expr(expr: &Expr, aggregates: &[AggExpr]) -> Expr {
                     .iter()
                     .map(|a| rewrite_having_expr(a, aggregates))
                     .collect(),
+                resolved: *resolved,
             }
         }
-        Expr::BinaryOp { op, left, right } => Expr::BinaryOp {
+        Expr::BinaryOp {
+            op,
+            left,
+            right,
+            resolved,
+        } => Expr::BinaryOp {
             op: op.clone(),
             left: Box::new(rewrite_having_expr(left, aggregates)),
             right: Box::new(rewrite_having_expr(right, aggregates)),
+            resolved: *resolved,
         },
-        _ => expr.clone(),
+        _ => expr.map_children(|c| rewrite_having_expr(c, aggregates)),
     }
 }
 
@@ -22,20 +22,98 @@ pub enum Expr {
     },
     /// A literal value.
     Literal(String),
-    /// A binary operation: `left op right`.
+    /// A binary operation: `left op right`, including `AND` / `OR` and
+    /// `IS [NOT] DISTINCT FROM`. `resolved` holds the `pg_operator` OID and
+    /// its function's volatility.
     BinaryOp {
         op: String,
         left: Box<Expr>,
         right: Box<Expr>,
+        resolved: Resolved,
+    },
+    /// A function call: `func(args...)`. `resolved` holds the `pg_proc`
+    /// OID and its volatility.
+    FuncCall {
+        func_name: String,
+        args: Vec<Expr>,
+        resolved: Resolved,
     },
-    /// A function call: `func(args...)`.
-    FuncCall { func_name: String, args: Vec<Expr> },
     /// A star expression: `*` or `table.*`.
     Star { table_alias: Option<String> },
+    /// A prefix operator: `op expr` (e.g. `-x`). `resolved` holds the
+    /// `pg_operator` OID and its function's volatility.
+    UnaryOp {
+        op: String,
+        expr: Box<Expr>,
+        resolved: Resolved,
+    },
+    /// A type cast: `CAST(expr AS type_name)`. `resolved` holds the target
+    /// `pg_type` OID and the volatility of the cast function.
+    Cast {
+        expr: Box<Expr>,
+        type_name: String,
+        resolved: Resolved,
+    },
+    /// A CASE expression. `operand` is set for the simple form
+    /// `CASE x WHEN v THEN ...`; `whens` holds `(condition, result)` pairs.
+    Case {
+        operand: Option<Box<Expr>>,
+        whens: Vec<(Expr, Expr)>,
+        else_result: Option<Box<Expr>>,
+    },
+    /// An array constructor: `ARRAY[e1, e2, ...]`.
+    Array(Vec<Expr>),
+    /// A row constructor: `ROW(e1, e2, ...)`.
+    Row(Vec<Expr>),
+    /// A subquery used as an expression; `test` is the left operand of
+    /// `x IN (SELECT ...)`.
+    SubLink {
+        kind: SubLinkKind,
+        test: Option<Box<Expr>>,
+        query: Box<SubQuery>,
+    },
     /// Raw SQL text (fallback for complex expressions).
     Raw(String),
 }
 
+/// The form of an [`Expr::SubLink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubLinkKind {
+    /// Scalar subquery: `(SELECT ...)`.
+    Scalar,
+    /// `EXISTS (SELECT ...)`.
+    Exists,
+    /// `test IN (SELECT ...)`.
+    In,
+}
+
+/// Catalog facts for an [`Expr`] node, resolved by parse analysis of the
+/// defining query.
+///
+/// Both fields are `None` when nothing could be resolved (hand-built nodes,
+/// re-parsed fragments, or a query that fails analysis); validators then
+/// fall back to looking the operator or function up by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Resolved {
+    /// `pg_operator` OID of an operator, `pg_proc` OID of a function, or
+    /// `pg_type` OID of a cast target.
+    pub oid: Option<u32>,
+    /// Volatility of the implementing function: `'i'`, `'s'` or `'v'`.
+    pub volatility: Option<char>,
+}
+
+/// The body of an [`Expr::SubLink`].
+///
+/// The subquery is planned as its own query, so it is rendered from `sql`;
+/// `exprs` holds every scalar expression of the body (target list, quals,
+/// grouping, ordering and those of nested subqueries and CTEs) so checks
+/// can walk them instead of searching the text.
+#[derive(Debug, Clone)]
+pub struct SubQuery {
+    pub sql: String,
+    pub exprs: Vec<Expr>,
+}
+
 impl Expr {
     /// Convert expression back to SQL text.
     pub fn to_sql(&self) -> String {
@@ -52,10 +130,14 @@ impl Expr {
                 None => column_name.clone(),
             },
             Expr::Literal(val) => val.clone(),
-            Expr::BinaryOp { op, left, right } => {
+            Expr::BinaryOp {
+                op, left, right, ..
+            } => {
                 format!("({} {op} {})", left.to_sql(), right.to_sql())
             }
-            Expr::FuncCall { func_name, args } => {
+            Expr::FuncCall {
+                func_name, args, ..
+            } => {
                 let arg_strs: Vec<String> = args.iter().map(|a| a.to_sql()).collect();
                 format!("{func_name}({})", arg_strs.join(", "))
             }
@@ -63,10 +145,169 @@ impl Expr {
                 Some(alias) => format!("{alias}.*"),
                 None => "*".to_string(),
             },
+            // Spaced and parenthesized: `- -x` must not become the comment `--x`.
+            Expr::UnaryOp { op, expr, .. } => format!("({op} {})", expr.to_sql()),
+            Expr::Cast {
+                expr, type_name, ..
+            } => format!("CAST({} AS {type_name})", expr.to_sql()),
+            Expr::Case {
+                operand,
+                whens,
+                else_result,
+            } => {
+                let mut sql = String::from("CASE");
+                if let Some(operand) = operand {
+                    sql.push_str(&format!(" {}", operand.to_sql()));
+                }
+                for (cond, result) in whens {
+                    sql.push_str(&format!(" WHEN {} THEN {}", cond.to_sql(), result.to_sql()));
+                }
+                if let Some(def) = else_result {
+                    sql.push_str(&format!(" ELSE {}", def.to_sql()));
+                }
+                sql.push_str(" END");
+                sql
+            }
+            Expr::Array(elems) => {
+                let elem_strs: Vec<String> = elems.iter().map(|e| e.to_sql()).collect();
+                format!("ARRAY[{}]", elem_strs.join(", "))
+            }
+            Expr::Row(fields) => {
+                let field_strs: Vec<String> = fields.iter().map(|f| f.to_sql()).collect();
+                format!("ROW({})", field_strs.join(", "))
+            }
+            Expr::SubLink { kind, test, query } => {
+                let query = &query.sql;
+                match kind {
+                    SubLinkKind::Scalar => format!("({query})"),
+                    SubLinkKind::Exists => format!("EXISTS ({query})"),
+                    SubLinkKind::In => match test {
+                        Some(test) => format!("{} IN ({query})", test.to_sql()),
+                        None => format!("IN ({query})"),
+                    },
+                }
+            }
             Expr::Raw(sql) => sql.clone(),
         }
     }
 
+    /// Direct sub-expressions of this node, in SQL order.
+    ///
+    /// Subquery bodies of `SubLink` are a scope of their own and not
+    /// included (see [`SubQuery::exprs`]); only the `IN` test operand is.
+    pub fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::BinaryOp { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+            Expr::FuncCall { args, .. } => args.iter().collect(),
+            Expr::UnaryOp { expr, .. } | Expr::Cast { expr, .. } => vec![expr.as_ref()],
+            Expr::Case {
+                operand,
+                whens,
+                else_result,
+            } => {
+                let mut out: Vec<&Expr> = operand.iter().map(|o| o.as_ref()).collect();
+                for (cond, result) in whens {
+                    out.push(cond);
+                    out.push(result);
+                }
+                out.extend(else_result.iter().map(|e| e.as_ref()));
+                out
+            }
+            Expr::Array(items) | Expr::Row(items) => items.iter().collect(),
+            Expr::SubLink { test, .. } => test.iter().map(|t| t.as_ref()).collect(),
+            Expr::ColumnRef { .. } | Expr::Literal(_) | Expr::Star { .. } | Expr::Raw(_) => {
+                Vec::new()
+            }
+        }
+    }
+
+    /// Rebuild this node with `f` applied to each direct sub-expression.
+    ///
+    /// Leaves are returned unchanged; `f` decides whether to recurse.
+    pub fn map_children(&self, mut f: impl FnMut(&Expr) -> Expr) -> Expr {
+        match self {
+            Expr::BinaryOp {
+                op,
+                left,
+                right,
+                resolved,
+            } => Expr::BinaryOp {
+                op: op.clone(),
+                left: Box::new(f(left)),
+                right: Box::new(f(right)),
+                resolved: *resolved,
+            },
+            Expr::FuncCall {
+                func_name,
+                args,
+                resolved,
+            } => Expr::FuncCall {
+                func_name: func_name.clone(),
+                args: args.iter().map(&mut f).collect(),
+                resolved: *resolved,
+            },
+            Expr::UnaryOp { op, expr, resolved } => Expr::UnaryOp {
+                op: op.clone(),
+                expr: Box::new(f(expr)),
+                resolved: *resolved,
+            },
+            Expr::Cast {
+                expr,
+                type_name,
+                resolved,
+            } => Expr::Cast {
+                expr: Box::new(f(expr)),
+                type_name: type_name.clone(),
+                resolved: *resolved,
+            },
+            Expr::Case {
+                operand,
+                whens,
+                else_result,
+            } => Expr::Case {
+                operand: operand.as_ref().map(|o| Box::new(f(o))),
+                whens: whens.iter().map(|(c, r)| (f(c), f(r))).collect(),
+                else_result: else_result.as_ref().map(|e| Box::new(f(e))),
+            },
+            Expr::Array(elems) => Expr::Array(elems.iter().map(&mut f).collect()),
+            Expr::Row(fields) => Expr::Row(fields.iter().map(&mut f).collect()),
+            Expr::SubLink { kind, test, query } => Expr::SubLink {
+                kind: *kind,
+                test: test.as_ref().map(|t| Box::new(f(t))),
+                query: query.clone(),
+            },
+            Expr::ColumnRef { .. } | Expr::Literal(_) | Expr::Star { .. } | Expr::Raw(_) => {
+                self.clone()
+            }
+        }
+    }
+
+    /// Column references in this expression as `(table_alias, column_name)`,
+    /// in order of first appearance.
+    ///
+    /// Returns `None` when the set cannot be determined exactly: the
+    /// expression contains `*`, a subquery, or an unparsed `Raw` fragment.
+    pub fn referenced_columns(&self) -> Option<Vec<(Option<String>, String)>> {
+        fn walk(expr: &Expr, out: &mut Vec<(Option<String>, String)>) -> bool {
+            match expr {
+                Expr::ColumnRef {
+                    table_alias,
+                    column_name,
+                } => {
+                    let col = (table_alias.clone(), column_name.clone());
+                    if !out.contains(&col) {
+                        out.push(col);
+                    }
+                    true
+                }
+                Expr::Star { .. } | Expr::SubLink { .. } | Expr::Raw(_) => false,
+                _ => expr.children().into_iter().all(|c| walk(c, out)),
+            }
+        }
+        let mut out = Vec::new();
+        walk(self, &mut out).then_some(out)
+    }
+
     /// Return the output column name for this expression.
     ///
     /// For `ColumnRef`, returns just the `column_name` (stripping the table
@@ -90,16 +331,27 @@ impl Expr {
                 table_alias: None,
                 column_name: column_name.clone(),
             },
-            Expr::BinaryOp { op, left, right } => Expr::BinaryOp {
+            Expr::BinaryOp {
+                op,
+                left,
+                right,
+                resolved,
+            } => Expr::BinaryOp {
                 op: op.clone(),
                 left: Box::new(left.strip_qualifier()),
                 right: Box::new(right.strip_qualifier()),
+                resolved: *resolved,
             },
-            Expr::FuncCall { func_name, args } => Expr::FuncCall {
+            Expr::FuncCall {
+                func_name,
+                args,
+                resolved,
+            } => Expr::FuncCall {
                 func_name: func_name.clone(),
                 args: args.iter().map(|a| a.strip_qualifier()).collect(),
+                resolved: *resolved,
             },
-            _ => self.clone(),
+            _ => self.map_children(|c| c.strip_qualifier()),
         }
     }
 
@@ -132,19 +384,30 @@ impl Expr {
                     column_name: column_name.clone(),
                 }
             }
-            Expr::BinaryOp { op, left, right } => Expr::BinaryOp {
+            Expr::BinaryOp {
+                op,
+                left,
+                right,
+                resolved,
+            } => Expr::BinaryOp {
                 op: op.clone(),
                 left: Box::new(left.rewrite_aliases(old_left, new_left, old_right, new_right)),
                 right: Box::new(right.rewrite_aliases(old_left, new_left, old_right, new_right)),
+                resolved: *resolved,
             },
-            Expr::FuncCall { func_name, args } => Expr::FuncCall {
+            Expr::FuncCall {
+                func_name,
+                args,
+                resolved,
+            } => Expr::FuncCall {
                 func_name: func_name.clone(),
                 args: args
                     .iter()
                     .map(|a| a.rewrite_aliases(old_left, new_left, old_right, new_right))
                     .collect(),
+                resolved: *resolved,
             },
-            _ => self.clone(),
+            _ => self.map_children(|c| c.rewrite_aliases(old_left, new_left, old_right, new_right)),
         }
     }
 }
@@ -267,15 +530,33 @@ pub fn lateral_function_output_columns(
     infer_default_lateral_function_columns(func_sql).unwrap_or_else(|| vec![alias.to_string()])
 }
 
-fn infer_default_lateral_function_columns(func_sql: &str) -> Option<Vec<String>> {
+/// Default output column names of one function inside `ROWS FROM (...)`.
+///
+/// PostgreSQL names a scalar result after the function (not the table
+/// alias) once more than one function is zipped, so the fallback here is
+/// the unqualified function name.
+pub fn rows_from_function_columns(func_sql: &str) -> Vec<String> {
+    infer_default_lateral_function_columns(func_sql)
+        .or_else(|| srf_function_name(func_sql).map(|name| vec![name]))
+        .unwrap_or_default()
+}
+
+/// Unqualified, unquoted name of the function called by `func_sql`.
+fn srf_function_name(func_sql: &str) -> Option<String> {
     let open_paren = func_sql.find('(')?;
     let func_head = func_sql[..open_paren].trim();
-    let func_name = func_head
-        .rsplit('.')
-        .next()
-        .unwrap_or(func_head)
-        .replace('"', "")
-        .to_ascii_lowercase();
+    Some(
+        func_head
+            .rsplit('.')
+            .next()
+            .unwrap_or(func_head)
+            .replace('"', "")
+            .to_ascii_lowercase(),
+    )
+}
+
+fn infer_default_lateral_function_columns(func_sql: &str) -> Option<Vec<String>> {
+    let func_name = srf_function_name(func_sql)?;
 
     match func_name.as_str() {
         "json_each" | "json_each_text" | "jsonb_each" | "jsonb_each_text" => {
@@ -450,6 +731,16 @@ impl AggFunc {
                 | AggFunc::VectorSum
         )
     }
+
+    /// Returns true for lattice aggregates (MIN, MAX, BOOL_OR, BIT_OR):
+    /// under insert-only input their value only moves one way, so they can
+    /// take part in a circular dependency's fixed-point iteration.
+    pub fn is_lattice(&self) -> bool {
+        matches!(
+            self,
+            AggFunc::Min | AggFunc::Max | AggFunc::BoolOr | AggFunc::BitOr
+        )
+    }
 }
 
 /// An aggregate expression in a GROUP BY query.
@@ -469,6 +760,14 @@ pub struct AggExpr {
     pub order_within_group: Option<Vec<SortExpr>>,
 }
 
+/// A `GROUPING(col, …)` output of an [`OpTree::GroupingSets`] node.
+#[derive(Debug, Clone)]
+pub struct GroupingFuncExpr {
+    /// Indices into the node's `group_by`, in argument order.
+    pub args: Vec<usize>,
+    pub alias: String,
+}
+
 /// G12-AGG: Classify the maintenance strategy for a single aggregate expression.
 ///
 /// Returns one of:
@@ -887,6 +1186,18 @@ pub enum OpTree {
         aggregates: Vec<AggExpr>,
         child: Box<OpTree>,
     },
+    /// GROUP BY GROUPING SETS / CUBE / ROLLUP with aggregates.
+    ///
+    /// `group_by` holds every distinct grouping column and `sets` lists,
+    /// per grouping set, the indices of the columns it groups by. Rows of a
+    /// set carry NULL in the columns the set does not group by.
+    GroupingSets {
+        group_by: Vec<Expr>,
+        sets: Vec<Vec<usize>>,
+        aggregates: Vec<AggExpr>,
+        grouping_funcs: Vec<GroupingFuncExpr>,
+        child: Box<OpTree>,
+    },
     /// DISTINCT.
     Distinct { child: Box<OpTree> },
     /// UNION ALL.
@@ -1001,7 +1312,8 @@ pub enum OpTree {
     /// A set-returning function in the FROM clause with implicit LATERAL semantics.
     ///
     /// Examples: `jsonb_array_elements(p.data)`, `unnest(a.tags)`,
-    /// `generate_series(1, 10)`.
+    /// `generate_series(1, 10)`, `ROWS FROM (unnest(a.xs), unnest(a.ys))`,
+    /// `JSON_TABLE(e.doc, '$.items[*]' COLUMNS (...))`.
     ///
     /// The function call is stored as raw SQL because SRFs may reference
     /// columns from the left-hand side of the implicit cross join (LATERAL),
@@ -1015,9 +1327,12 @@ pub enum OpTree {
         func_sql: String,
         /// The FROM alias, e.g. `child` from `... AS child`.
         alias: String,
-        /// Column aliases from `AS alias(c1, c2)`, if any.
+        /// Column aliases from `AS alias(c1, c2)`, if any. For `ROWS FROM`
+        /// and `JSON_TABLE` the parser resolves every output column here.
         column_aliases: Vec<String>,
         /// Whether `WITH ORDINALITY` was specified (adds a `bigint` ordinal column).
+        /// Always false for `ROWS FROM`, whose `func_sql` carries the clause
+        /// itself and names the ordinal in `column_aliases`.
         with_ordinality: bool,
         /// The left-hand FROM item that this function may reference (LATERAL dependency).
         child: Box<OpTree>,
@@ -1279,6 +1594,12 @@ impl ParseResult {
                 group_by,
                 aggregates,
                 child,
+            }
+            | OpTree::GroupingSets {
+                group_by,
+                aggregates,
+                child,
+                ..
             } => {
                 for expr in group_by {
                     Self::collect_funcs_from_expr(expr, names);
@@ -1350,20 +1671,21 @@ impl ParseResult {
     /// Extract function names from an Expr recursively.
     fn collect_funcs_from_expr(expr: &Expr, names: &mut std::collections::HashSet<String>) {
         match expr {
-            Expr::FuncCall { func_name, args } => {
+            Expr::FuncCall {
+                func_name, args, ..
+            } => {
                 names.insert(func_name.to_lowercase());
                 for arg in args {
                     Self::collect_funcs_from_expr(arg, names);
                 }
             }
-            Expr::BinaryOp { left, right, .. } => {
-                Self::collect_funcs_from_expr(left, names);
-                Self::collect_funcs_from_expr(right, names);
+            // Raw may contain functions, but we don't parse them here —
+            // the volatility checker handles those separately.
+            _ => {
+                for child in expr.children() {
+                    Self::collect_funcs_from_expr(child, names);
+                }
             }
-            // ColumnRef, Literal, Star, Raw: no function calls
-            // (Raw may contain functions, but we don't parse them here —
-            // the volatility checker handles those separately.)
-            _ => {}
         }
     }
 }
@@ -1389,6 +1711,7 @@ impl OpTree {
             OpTree::LeftJoin { .. } => "left_join",
             OpTree::FullJoin { .. } => "full_join",
             OpTree::Aggregate { .. } => "agg",
+            OpTree::GroupingSets { .. } => "gsets",
             OpTree::Distinct { .. } => "distinct",
             OpTree::UnionAll { .. } => "union",
             OpTree::Intersect { .. } => "intersect",
@@ -1417,6 +1740,7 @@ impl OpTree {
             OpTree::LeftJoin { .. } => "left join",
             OpTree::FullJoin { .. } => "full join",
             OpTree::Aggregate { .. } => "aggregate",
+            OpTree::GroupingSets { .. } => "grouping sets",
             OpTree::Distinct { .. } => "distinct",
             OpTree::UnionAll { .. } => "union all",
             OpTree::Intersect { all, .. } => {
@@ -1456,7 +1780,7 @@ impl OpTree {
     /// around the `Aggregate`).
     pub fn needs_pgt_count(&self) -> bool {
         match self {
-            OpTree::Aggregate { .. } => true,
+            OpTree::Aggregate { .. } | OpTree::GroupingSets { .. } => true,
             OpTree::Distinct { child, .. } => {
                 // UNION (dedup) = Distinct { UnionAll }; uses union-dedup count path instead
                 !matches!(child.as_ref(), OpTree::UnionAll { .. })
@@ -1485,7 +1809,7 @@ impl OpTree {
     /// operator tree is not an aggregate query.
     pub fn avg_aux_columns(&self) -> Vec<(String, String, String)> {
         match self {
-            OpTree::Aggregate { aggregates, .. } => {
+            OpTree::Aggregate { aggregates, .. } | OpTree::GroupingSets { aggregates, .. } => {
                 let mut aux = Vec::new();
                 for agg in aggregates {
                     if agg.function.is_algebraic_via_aux() && !agg.is_distinct {
@@ -1519,7 +1843,7 @@ impl OpTree {
     /// These are needed in addition to the sum/count from `avg_aux_columns`.
     pub fn sum2_aux_columns(&self) -> Vec<(String, String)> {
         match self {
-            OpTree::Aggregate { aggregates, .. } => {
+            OpTree::Aggregate { aggregates, .. } | OpTree::GroupingSets { aggregates, .. } => {
                 let mut aux = Vec::new();
                 for agg in aggregates {
                     if agg.function.needs_sum_of_squares() && !agg.is_distinct {
@@ -1555,7 +1879,7 @@ impl OpTree {
     /// For regression aggs, argument = Y (first), second_arg = X (second).
     pub fn covar_aux_columns(&self) -> Vec<(String, String)> {
         match self {
-            OpTree::Aggregate { aggregates, .. } => {
+            OpTree::Aggregate { aggregates, .. } | OpTree::GroupingSets { aggregates, .. } => {
                 let mut aux = Vec::new();
                 for agg in aggregates {
                     if agg.function.needs_cross_products() && !agg.is_distinct {
@@ -1622,6 +1946,9 @@ impl OpTree {
         match self {
             OpTree::Aggregate {
                 aggregates, child, ..
+            }
+            | OpTree::GroupingSets {
+                aggregates, child, ..
             } => {
                 if !child.contains_full_join() {
                     return Vec::new();
@@ -1967,6 +2294,11 @@ impl OpTree {
                 let cols = child.output_columns();
                 if cols.is_empty() { None } else { Some(cols) }
             }
+            OpTree::GroupingSets { .. } => {
+                // The row ID also hashes the grouping-set id, which is not an
+                // output column; see `dvm::grouping_sets_row_id_sql`.
+                None
+            }
             // Join, UnionAll, RecursiveCte: complex hash, no simple column list
             _ => None,
         }
@@ -1994,6 +2326,17 @@ impl OpTree {
                 cols.extend(aggregates.iter().map(|a| a.alias.clone()));
                 cols
             }
+            OpTree::GroupingSets {
+                group_by,
+                aggregates,
+                grouping_funcs,
+                ..
+            } => {
+                let mut cols: Vec<String> = group_by.iter().map(|e| e.output_name()).collect();
+                cols.extend(grouping_funcs.iter().map(|g| g.alias.clone()));
+                cols.extend(aggregates.iter().map(|a| a.alias.clone()));
+                cols
+            }
             OpTree::Distinct { child } => child.output_columns(),
             OpTree::UnionAll { children } => children
                 .first()
@@ -2126,7 +2469,9 @@ impl OpTree {
             OpTree::Filter { child, .. }
             | OpTree::Distinct { child }
             | OpTree::Subquery { child, .. } => child.has_incomplete_join_pk(),
-            OpTree::Aggregate { child, .. } => child.has_incomplete_join_pk(),
+            OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
+                child.has_incomplete_join_pk()
+            }
             OpTree::InnerJoin { left, right, .. }
             | OpTree::LeftJoin { left, right, .. }
             | OpTree::FullJoin { left, right, .. } => {
@@ -2144,7 +2489,9 @@ impl OpTree {
             OpTree::Project { child, .. }
             | OpTree::Filter { child, .. }
             | OpTree::Distinct { child } => child.source_oids(),
-            OpTree::Aggregate { child, .. } => child.source_oids(),
+            OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
+                child.source_oids()
+            }
             OpTree::InnerJoin { left, right, .. }
             | OpTree::LeftJoin { left, right, .. }
             | OpTree::FullJoin { left, right, .. }
@@ -2222,13 +2569,15 @@ impl OpTree {
     /// Returns an empty vec when there is no `Aggregate` node.
     pub fn aggregate_strategies(&self) -> Vec<(String, &'static str)> {
         match self {
-            OpTree::Aggregate { aggregates, .. } => aggregates
-                .iter()
-                .map(|agg| {
-                    let strategy = classify_agg_strategy(agg);
-                    (agg.alias.clone(), strategy)
-                })
-                .collect(),
+            OpTree::Aggregate { aggregates, .. } | OpTree::GroupingSets { aggregates, .. } => {
+                aggregates
+                    .iter()
+                    .map(|agg| {
+                        let strategy = classify_agg_strategy(agg);
+                        (agg.alias.clone(), strategy)
+                    })
+                    .collect()
+            }
             OpTree::Project { child, .. }
             | OpTree::Filter { child, .. }
             | OpTree::Subquery { child, .. }
@@ -2350,6 +2699,9 @@ impl OpTree {
         match self {
             OpTree::Aggregate {
                 group_by, child, ..
+            }
+            | OpTree::GroupingSets {
+                group_by, child, ..
             } => {
                 for expr in group_by {
                     Self::collect_column_names_from_expr(expr, names);
@@ -2430,16 +2782,11 @@ impl OpTree {
             Expr::ColumnRef { column_name, .. } => {
                 names.insert(column_name.clone());
             }
-            Expr::BinaryOp { left, right, .. } => {
-                Self::collect_column_names_from_expr(left, names);
-                Self::collect_column_names_from_expr(right, names);
-            }
-            Expr::FuncCall { args, .. } => {
-                for arg in args {
-                    Self::collect_column_names_from_expr(arg, names);
+            _ => {
+                for child in expr.children() {
+                    Self::collect_column_names_from_expr(child, names);
                 }
             }
-            Expr::Literal(_) | Expr::Star { .. } | Expr::Raw(_) => {}
         }
     }
 
@@ -2471,7 +2818,9 @@ impl OpTree {
             | OpTree::LateralFunction { child, .. }
             | OpTree::LateralSubquery { child, .. }
             | OpTree::ScalarSubquery { child, .. } => child.collect_scan_alias_map(map),
-            OpTree::Aggregate { child, .. } => child.collect_scan_alias_map(map),
+            OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
+                child.collect_scan_alias_map(map)
+            }
             OpTree::InnerJoin { left, right, .. }
             | OpTree::LeftJoin { left, right, .. }
             | OpTree::FullJoin { left, right, .. }
@@ -2516,7 +2865,9 @@ impl OpTree {
             OpTree::Project { child, .. }
             | OpTree::Filter { child, .. }
             | OpTree::Distinct { child } => child.collect_source_columns(map),
-            OpTree::Aggregate { child, .. } => child.collect_source_columns(map),
+            OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
+                child.collect_source_columns(map)
+            }
             OpTree::InnerJoin { left, right, .. }
             | OpTree::LeftJoin { left, right, .. }
             | OpTree::FullJoin { left, right, .. }
@@ -2625,6 +2976,12 @@ impl OpTree {
                 group_by,
                 aggregates,
                 child,
+            }
+            | OpTree::GroupingSets {
+                group_by,
+                aggregates,
+                child,
+                ..
             } => {
                 for expr in group_by {
                     if !collect_refs_from_expr(expr, refs) {
@@ -2754,7 +3111,9 @@ impl OpTree {
                 subquery.apply_column_pruning(refs);
                 child.apply_column_pruning(refs);
             }
-            OpTree::Aggregate { child, .. } => child.apply_column_pruning(refs),
+            OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
+                child.apply_column_pruning(refs)
+            }
             OpTree::InnerJoin { left, right, .. }
             | OpTree::LeftJoin { left, right, .. }
             | OpTree::FullJoin { left, right, .. }
@@ -2793,7 +3152,7 @@ struct ColumnRefSet {
 }
 
 /// Extract column references from an expression.
-/// Returns `false` on Star/Raw (cannot determine exact columns).
+/// Returns `false` on Star/SubLink/Raw (cannot determine exact columns).
 fn collect_refs_from_expr(expr: &Expr, refs: &mut ColumnRefSet) -> bool {
     match expr {
         Expr::ColumnRef {
@@ -2818,7 +3177,16 @@ fn collect_refs_from_expr(expr: &Expr, refs: &mut ColumnRefSet) -> bool {
             collect_refs_from_expr(left, refs) && collect_refs_from_expr(right, refs)
         }
         Expr::FuncCall { args, .. } => args.iter().all(|a| collect_refs_from_expr(a, refs)),
-        Expr::Star { .. } | Expr::Raw(_) => false,
+        Expr::UnaryOp { .. }
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Array(_)
+        | Expr::Row(_) => expr
+            .children()
+            .into_iter()
+            .all(|c| collect_refs_from_expr(c, refs)),
+        // A subquery may reference any column of the outer query.
+        Expr::Star { .. } | Expr::SubLink { .. } | Expr::Raw(_) => false,
     }
 }
 
@@ -3074,7 +3442,9 @@ fn extract_equijoin_col_names(condition: &Expr) -> Vec<(String, String)> {
 
 fn collect_equijoin_col_names(expr: &Expr, pairs: &mut Vec<(String, String)>) {
     match expr {
-        Expr::BinaryOp { op, left, right } if op == "=" => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op == "=" => {
             if let (
                 Expr::ColumnRef {
                     column_name: left_col,
@@ -3091,7 +3461,9 @@ fn collect_equijoin_col_names(expr: &Expr, pairs: &mut Vec<(String, String)>) {
                 pairs.push((right_col.clone(), left_col.clone()));
             }
         }
-        Expr::BinaryOp { op, left, right } if op.eq_ignore_ascii_case("AND") => {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op.eq_ignore_ascii_case("AND") => {
             collect_equijoin_col_names(left, pairs);
             collect_equijoin_col_names(right, pairs);
         }
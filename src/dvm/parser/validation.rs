@@ -168,20 +168,25 @@ pub fn lookup_operator_volatility(_op_name: &str) -> Result<char, PgTrickleError
 }
 
 /// Recursively scan an `Expr` tree and update `worst` with the volatility
-/// of any `FuncCall` or `BinaryOp` operator nodes found.
+/// of any `FuncCall`, `BinaryOp` or `UnaryOp` operator nodes found.
 ///
-/// For `Expr::BinaryOp`, looks up the operator's implementing function in
-/// `pg_operator` → `pg_proc.provolatile` to detect custom volatile operators
-/// (Gap G7.2).
+/// Function calls, operators and casts use the volatility resolved at
+/// parse time.  Unresolved function calls and operators fall back to a
+/// lookup by name in `pg_proc` / `pg_operator` → `pg_proc.provolatile`,
+/// which still catches custom volatile operators (Gap G7.2). CASE,
+/// array/row constructors and subquery bodies ([`SubQuery::exprs`]) are
+/// walked structurally.
 ///
-/// For `Expr::Raw` nodes, re-parses the SQL fragment via `raw_parser()` and
-/// walks the parse tree to detect function calls or operators that may be
-/// volatile. This closes the G7.1 gap where expressions like
-/// `CASE WHEN now() > x THEN ...` deparsed to `Expr::Raw` would bypass
-/// volatility checking.
+/// For `Expr::Raw` nodes, re-parses the SQL via `raw_parser()` and walks
+/// the parse tree to detect function calls or operators that may be
+/// volatile (Gap G7.1).
 pub fn collect_volatilities(expr: &Expr, worst: &mut char) -> Result<(), PgTrickleError> {
     match expr {
-        Expr::FuncCall { func_name, args } => {
+        Expr::FuncCall {
+            func_name,
+            args,
+            resolved,
+        } => {
             // COALESCE, NULLIF, GREATEST, LEAST are parser constructs that
             // don't appear in pg_proc. They are inherently immutable — their
             // result depends only on their arguments. Skip the pg_proc lookup
@@ -198,7 +203,9 @@ pub fn collect_volatilities(expr: &Expr, worst: &mut char) -> Result<(), PgTrick
                 upper.as_str(),
                 "COALESCE" | "NULLIF" | "GREATEST" | "LEAST" | "NOT"
             );
-            if !is_builtin_construct {
+            if let Some(vol) = resolved.volatility {
+                *worst = max_volatility(*worst, vol);
+            } else if !is_builtin_construct {
                 let vol = lookup_function_volatility(func_name)?;
                 *worst = max_volatility(*worst, vol);
             }
@@ -206,19 +213,53 @@ pub fn collect_volatilities(expr: &Expr, worst: &mut char) -> Result<(), PgTrick
                 collect_volatilities(arg, worst)?;
             }
         }
-        Expr::BinaryOp { op, left, right } => {
+        Expr::BinaryOp {
+            op,
+            left,
+            right,
+            resolved,
+        } => {
             // G7.2: Check the operator's implementing function volatility.
-            let vol = lookup_operator_volatility(op)?;
+            // IS [NOT] DISTINCT FROM is implemented by `=`.
+            let vol = match resolved.volatility {
+                Some(vol) => vol,
+                None if op.ends_with("DISTINCT FROM") => lookup_operator_volatility("=")?,
+                None => lookup_operator_volatility(op)?,
+            };
             *worst = max_volatility(*worst, vol);
             collect_volatilities(left, worst)?;
             collect_volatilities(right, worst)?;
         }
+        Expr::UnaryOp { op, expr, resolved } => {
+            let vol = match resolved.volatility {
+                Some(vol) => vol,
+                None => lookup_operator_volatility(op)?,
+            };
+            *worst = max_volatility(*worst, vol);
+            collect_volatilities(expr, worst)?;
+        }
+        Expr::Cast { expr, resolved, .. } => {
+            if let Some(vol) = resolved.volatility {
+                *worst = max_volatility(*worst, vol);
+            }
+            collect_volatilities(expr, worst)?;
+        }
+        Expr::Case { .. } | Expr::Array(_) | Expr::Row(_) => {
+            for child in expr.children() {
+                collect_volatilities(child, worst)?;
+            }
+        }
+        Expr::SubLink { test, query, .. } => {
+            for child in test.iter().map(|t| t.as_ref()).chain(&query.exprs) {
+                collect_volatilities(child, worst)?;
+            }
+        }
         Expr::Raw(sql) => {
             // Re-parse the raw SQL fragment to find any embedded function calls.
             collect_raw_expr_volatility(sql, worst)?;
         }
         // ColumnRef, Literal, Star: no function calls.
-        _ => {}
+        Expr::ColumnRef { .. } | Expr::Literal(_) | Expr::Star { .. } => {}
     }
     Ok(())
 }
@@ -450,9 +491,32 @@ pub fn tree_worst_volatility_with_registry(result: &ParseResult) -> Result<char,
     Ok(worst)
 }
 
+/// SLIDE-1: Like [`tree_worst_volatility_with_registry`], but ignores the
+/// clock comparisons of recognised sliding-window filters (see
+/// [`is_sliding_window_filter`]). Those are maintained by the refresh, so
+/// they do not warrant the "stable functions" warning at creation time.
+pub fn tree_worst_volatility_outside_sliding_windows(
+    result: &ParseResult,
+) -> Result<char, PgTrickleError> {
+    let mut worst = 'i';
+    for (_name, body) in &result.cte_registry.entries {
+        tree_collect_volatility_inner(body, &mut worst, true)?;
+    }
+    tree_collect_volatility_inner(&result.tree, &mut worst, true)?;
+    Ok(worst)
+}
+
 pub(crate) fn tree_collect_volatility(
     tree: &OpTree,
     worst: &mut char,
+) -> Result<(), PgTrickleError> {
+    tree_collect_volatility_inner(tree, worst, false)
+}
+
+fn tree_collect_volatility_inner(
+    tree: &OpTree,
+    worst: &mut char,
+    skip_sliding: bool,
 ) -> Result<(), PgTrickleError> {
     match tree {
         OpTree::Scan { .. } | OpTree::CteScan { .. } | OpTree::RecursiveSelfRef { .. } => {}
@@ -462,11 +526,28 @@ pub(crate) fn tree_collect_volatility(
             for expr in expressions {
                 collect_volatilities(expr, worst)?;
             }
-            tree_collect_volatility(child, worst)?;
+            tree_collect_volatility_inner(child, worst, skip_sliding)?;
         }
         OpTree::Filter { predicate, child } => {
-            collect_volatilities(predicate, worst)?;
-            tree_collect_volatility(child, worst)?;
+            if skip_sliding && is_sliding_window_filter(predicate, child) {
+                // Skip the clock bound only; the compared column expression
+                // still counts.
+                for conjunct in split_conjuncts(predicate) {
+                    match conjunct {
+                        Expr::BinaryOp { left, right, .. } if contains_clock_expr(conjunct) => {
+                            for side in [left, right] {
+                                if !contains_clock_expr(side) {
+                                    collect_volatilities(side, worst)?;
+                                }
+                            }
+                        }
+                        _ => collect_volatilities(conjunct, worst)?,
+                    }
+                }
+            } else {
+                collect_volatilities(predicate, worst)?;
+            }
+            tree_collect_volatility_inner(child, worst, skip_sliding)?;
         }
         OpTree::InnerJoin {
             condition,
@@ -494,13 +575,19 @@ pub(crate) fn tree_collect_volatility(
             right,
         } => {
             collect_volatilities(condition, worst)?;
-            tree_collect_volatility(left, worst)?;
-            tree_collect_volatility(right, worst)?;
+            tree_collect_volatility_inner(left, worst, skip_sliding)?;
+            tree_collect_volatility_inner(right, worst, skip_sliding)?;
         }
         OpTree::Aggregate {
             group_by,
             aggregates,
             child,
+        }
+        | OpTree::GroupingSets {
+            group_by,
+            aggregates,
+            child,
+            ..
         } => {
             for expr in group_by {
                 collect_volatilities(expr, worst)?;
@@ -516,28 +603,28 @@ pub(crate) fn tree_collect_volatility(
                     collect_volatilities(second, worst)?;
                 }
             }
-            tree_collect_volatility(child, worst)?;
+            tree_collect_volatility_inner(child, worst, skip_sliding)?;
         }
         OpTree::Distinct { child }
         | OpTree::Subquery { child, .. }
         | OpTree::LateralFunction { child, .. }
         | OpTree::LateralSubquery { child, .. } => {
-            tree_collect_volatility(child, worst)?;
+            tree_collect_volatility_inner(child, worst, skip_sliding)?;
         }
         OpTree::UnionAll { children } => {
             for child in children {
-                tree_collect_volatility(child, worst)?;
+                tree_collect_volatility_inner(child, worst, skip_sliding)?;
             }
         }
         OpTree::Intersect { left, right, .. } | OpTree::Except { left, right, .. } => {
-            tree_collect_volatility(left, worst)?;
-            tree_collect_volatility(right, worst)?;
+            tree_collect_volatility_inner(left, worst, skip_sliding)?;
+            tree_collect_volatility_inner(right, worst, skip_sliding)?;
         }
         OpTree::RecursiveCte {
             base, recursive, ..
         } => {
-            tree_collect_volatility(base, worst)?;
-            tree_collect_volatility(recursive, worst)?;
+            tree_collect_volatility_inner(base, worst, skip_sliding)?;
+            tree_collect_volatility_inner(recursive, worst, skip_sliding)?;
         }
         OpTree::Window {
             window_exprs,
@@ -562,13 +649,13 @@ pub(crate) fn tree_collect_volatility(
             for (expr, _) in pass_through {
                 collect_volatilities(expr, worst)?;
             }
-            tree_collect_volatility(child, worst)?;
+            tree_collect_volatility_inner(child, worst, skip_sliding)?;
         }
         OpTree::ScalarSubquery {
             subquery, child, ..
         } => {
-            tree_collect_volatility(subquery, worst)?;
-            tree_collect_volatility(child, worst)?;
+            tree_collect_volatility_inner(subquery, worst, skip_sliding)?;
+            tree_collect_volatility_inner(child, worst, skip_sliding)?;
         }
         // Constant anchors have no expressions to scan — volatility is unaffected.
         OpTree::ConstantSelect { .. } => {}
@@ -576,6 +663,292 @@ pub(crate) fn tree_collect_volatility(
     Ok(())
 }
 
+// ── SLIDE-1: Sliding-window filters over the transaction clock ─────────────
+//
+// A filter such as `WHERE ts > now() - interval '1 hour'` over a base table
+// changes its result set without any DML: rows age out of (or into) the
+// window as the clock advances. Recognised forms are maintained by emitting
+// expiry deltas for the rows that crossed the window boundary between the
+// previous refresh clock and the current one (see `diff_filter`).
+
+/// Returns `true` when `expr` is a bare transaction-clock reference:
+/// `now()`, `transaction_timestamp()`, `CURRENT_TIMESTAMP`,
+/// `LOCALTIMESTAMP` or `CURRENT_DATE`.
+///
+/// `clock_timestamp()` and `statement_timestamp()` are deliberately excluded:
+/// they are not stable within the refresh transaction, so a boundary
+/// evaluated at two points of the same refresh could disagree.
+pub fn is_clock_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::FuncCall {
+            func_name, args, ..
+        } if args.is_empty() => {
+            let base = func_name.rsplit('.').next().unwrap_or(func_name);
+            base.eq_ignore_ascii_case("now") || base.eq_ignore_ascii_case("transaction_timestamp")
+        }
+        Expr::Raw(sql) => matches!(
+            sql.trim().to_ascii_uppercase().as_str(),
+            "CURRENT_TIMESTAMP" | "LOCALTIMESTAMP" | "CURRENT_DATE"
+        ),
+        _ => false,
+    }
+}
+
+/// Returns `true` when `expr` references the transaction clock anywhere.
+///
+/// Subquery bodies are walked through [`SubQuery::exprs`]. Raw SQL
+/// fragments are checked textually, which errs on the side of reporting a
+/// clock reference.
+pub fn contains_clock_expr(expr: &Expr) -> bool {
+    if is_clock_expr(expr) {
+        return true;
+    }
+    match expr {
+        Expr::Raw(sql) => sql_mentions_clock(sql),
+        Expr::SubLink { query, .. } => expr
+            .children()
+            .into_iter()
+            .chain(&query.exprs)
+            .any(contains_clock_expr),
+        _ => expr.children().into_iter().any(contains_clock_expr),
+    }
+}
+
+fn sql_mentions_clock(sql: &str) -> bool {
+    let upper = sql.to_ascii_uppercase();
+    upper.contains("NOW(")
+        || upper.contains("CURRENT_TIMESTAMP")
+        || upper.contains("CURRENT_DATE")
+        || upper.contains("LOCALTIMESTAMP")
+        || upper.contains("TRANSACTION_TIMESTAMP(")
+}
+
+/// Split a predicate into its top-level `AND` conjuncts (borrowing).
+pub fn split_conjuncts(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op.eq_ignore_ascii_case("AND") => {
+            let mut out = split_conjuncts(left);
+            out.extend(split_conjuncts(right));
+            out
+        }
+        other => vec![other],
+    }
+}
+
+/// A constant offset usable in a clock bound: a literal, a quoted or numeric
+/// constant, or a cast of a quoted constant (`interval '1 hour'`).
+fn is_clock_offset_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) => true,
+        Expr::Cast { expr, .. } => is_clock_offset_constant(expr),
+        Expr::Raw(sql) => {
+            let s = sql.trim();
+            !contains_clock_expr(expr)
+                && (s.starts_with('\'') || s.parse::<f64>().is_ok() || s.starts_with("CAST('"))
+        }
+        _ => false,
+    }
+}
+
+/// A clock bound: a clock reference, optionally shifted by constants
+/// (`now() - interval '1 hour'`).
+fn is_clock_bound(expr: &Expr) -> bool {
+    if is_clock_expr(expr) {
+        return true;
+    }
+    match expr {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if op == "+" || op == "-" => {
+            (is_clock_bound(left) && is_clock_offset_constant(right))
+                || (op == "+" && is_clock_offset_constant(left) && is_clock_bound(right))
+        }
+        _ => false,
+    }
+}
+
+/// `col <op> clock_bound` (either orientation) with a range operator.
+fn is_sliding_comparison(expr: &Expr) -> bool {
+    match expr {
+        Expr::BinaryOp {
+            op, left, right, ..
+        } if matches!(op.as_str(), "<" | "<=" | ">" | ">=") => {
+            (is_clock_bound(right) && !contains_clock_expr(left))
+                || (is_clock_bound(left) && !contains_clock_expr(right))
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` when `predicate` is a sliding-window predicate: every
+/// conjunct is either clock-free or a range comparison against a clock bound,
+/// and at least one conjunct is of the latter kind.
+pub fn is_sliding_window_predicate(predicate: &Expr) -> bool {
+    let conjuncts = split_conjuncts(predicate);
+    let mut sliding = false;
+    for conjunct in conjuncts {
+        if is_sliding_comparison(conjunct) {
+            sliding = true;
+        } else if contains_clock_expr(conjunct) {
+            return false;
+        }
+    }
+    sliding
+}
+
+/// The sliding comparisons of a sliding-window predicate as
+/// `(value, op, clock_bound)`, flipped where needed so that the clock bound
+/// is on the right.
+///
+/// The clock only moves forward, so a row leaves a `>` / `>=` comparison
+/// and enters a `<` / `<=` one when the bound reaches its value.
+pub fn sliding_comparisons(predicate: &Expr) -> Vec<(&Expr, &'static str, &Expr)> {
+    split_conjuncts(predicate)
+        .into_iter()
+        .filter(|c| is_sliding_comparison(c))
+        .filter_map(|c| {
+            let Expr::BinaryOp {
+                op, left, right, ..
+            } = c
+            else {
+                return None;
+            };
+            let (op, flipped) = match op.as_str() {
+                "<" => ("<", ">"),
+                "<=" => ("<=", ">="),
+                ">" => (">", "<"),
+                ">=" => (">=", "<="),
+                _ => return None,
+            };
+            if is_clock_bound(right) && !contains_clock_expr(left) {
+                Some((left.as_ref(), op, right.as_ref()))
+            } else {
+                Some((right.as_ref(), flipped, left.as_ref()))
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` when a `Filter` node can be maintained as a sliding window:
+/// the predicate is a sliding-window predicate and the filter sits directly
+/// on a base-table scan with a primary key (needed to address expired rows).
+pub fn is_sliding_window_filter(predicate: &Expr, child: &OpTree) -> bool {
+    matches!(child, OpTree::Scan { pk_columns, .. } if !pk_columns.is_empty())
+        && is_sliding_window_predicate(predicate)
+}
+
+/// Replace every clock reference in `expr` with `clock_sql`, a
+/// `timestamptz`-valued SQL expression. `LOCALTIMESTAMP` and `CURRENT_DATE`
+/// are cast so the substituted expression keeps its original type.
+pub fn substitute_clock(expr: &Expr, clock_sql: &str) -> Expr {
+    if is_clock_expr(expr) {
+        let sql = match expr {
+            Expr::Raw(raw) if raw.trim().eq_ignore_ascii_case("LOCALTIMESTAMP") => {
+                format!("CAST({clock_sql} AS timestamp)")
+            }
+            Expr::Raw(raw) if raw.trim().eq_ignore_ascii_case("CURRENT_DATE") => {
+                format!("CAST({clock_sql} AS date)")
+            }
+            _ => format!("({clock_sql})"),
+        };
+        return Expr::Raw(sql);
+    }
+    match expr {
+        Expr::BinaryOp {
+            op,
+            left,
+            right,
+            resolved,
+        } => Expr::BinaryOp {
+            op: op.clone(),
+            left: Box::new(substitute_clock(left, clock_sql)),
+            right: Box::new(substitute_clock(right, clock_sql)),
+            resolved: *resolved,
+        },
+        Expr::FuncCall {
+            func_name,
+            args,
+            resolved,
+        } => Expr::FuncCall {
+            func_name: func_name.clone(),
+            args: args
+                .iter()
+                .map(|a| substitute_clock(a, clock_sql))
+                .collect(),
+            resolved: *resolved,
+        },
+        other => other.map_children(|c| substitute_clock(c, clock_sql)),
+    }
+}
+
+/// Collect every sliding-window filter in the query as
+/// `(predicate, scan)` pairs, including those inside CTE bodies.
+pub fn collect_sliding_window_filters(result: &ParseResult) -> Vec<(&Expr, &OpTree)> {
+    let mut out = Vec::new();
+    for (_name, body) in &result.cte_registry.entries {
+        collect_sliding_window_filters_inner(body, &mut out);
+    }
+    collect_sliding_window_filters_inner(&result.tree, &mut out);
+    out
+}
+
+fn collect_sliding_window_filters_inner<'a>(
+    tree: &'a OpTree,
+    out: &mut Vec<(&'a Expr, &'a OpTree)>,
+) {
+    match tree {
+        OpTree::Filter { predicate, child } => {
+            if is_sliding_window_filter(predicate, child) {
+                out.push((predicate, child));
+            }
+            collect_sliding_window_filters_inner(child, out);
+        }
+        OpTree::Project { child, .. }
+        | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
+        | OpTree::Distinct { child }
+        | OpTree::Subquery { child, .. }
+        | OpTree::Window { child, .. }
+        | OpTree::LateralFunction { child, .. }
+        | OpTree::LateralSubquery { child, .. } => {
+            collect_sliding_window_filters_inner(child, out);
+        }
+        OpTree::InnerJoin { left, right, .. }
+        | OpTree::LeftJoin { left, right, .. }
+        | OpTree::FullJoin { left, right, .. }
+        | OpTree::Intersect { left, right, .. }
+        | OpTree::Except { left, right, .. }
+        | OpTree::SemiJoin { left, right, .. }
+        | OpTree::AntiJoin { left, right, .. } => {
+            collect_sliding_window_filters_inner(left, out);
+            collect_sliding_window_filters_inner(right, out);
+        }
+        OpTree::UnionAll { children } => {
+            for child in children {
+                collect_sliding_window_filters_inner(child, out);
+            }
+        }
+        OpTree::RecursiveCte {
+            base, recursive, ..
+        } => {
+            collect_sliding_window_filters_inner(base, out);
+            collect_sliding_window_filters_inner(recursive, out);
+        }
+        OpTree::ScalarSubquery {
+            subquery, child, ..
+        } => {
+            collect_sliding_window_filters_inner(subquery, out);
+            collect_sliding_window_filters_inner(child, out);
+        }
+        OpTree::Scan { .. }
+        | OpTree::CteScan { .. }
+        | OpTree::RecursiveSelfRef { .. }
+        | OpTree::ConstantSelect { .. } => {}
+    }
+}
+
 /// Check if an operator tree is supported for differential maintenance.
 pub fn check_ivm_support(tree: &OpTree) -> Result<(), PgTrickleError> {
     check_ivm_support_inner(tree)
@@ -591,6 +964,79 @@ pub fn check_ivm_support_with_registry(result: &ParseResult) -> Result<(), PgTri
     check_ivm_support_inner(&result.tree)
 }
 
+/// TOPK-PART: Reject a Filter over a Window (per-group TopK, DISTINCT ON,
+/// any predicate on window output) when the Window's truncated partitions
+/// cannot be re-read from the source tables.
+///
+/// Runs at creation and ALTER so AUTO mode can fall back to FULL.
+/// Refreshes never raise it: `diff_window` keeps the ST + delta recompute
+/// for such a Window in stream tables created before this check.
+pub fn check_window_truncation_support(result: &ParseResult) -> Result<(), PgTrickleError> {
+    for (_, body) in &result.cte_registry.entries {
+        check_window_truncation(body, &result.cte_registry)?;
+    }
+    check_window_truncation(&result.tree, &result.cte_registry)
+}
+
+fn check_window_truncation(tree: &OpTree, registry: &CteRegistry) -> Result<(), PgTrickleError> {
+    match tree {
+        OpTree::Scan { .. }
+        | OpTree::CteScan { .. }
+        | OpTree::RecursiveSelfRef { .. }
+        | OpTree::ConstantSelect { .. } => Ok(()),
+        OpTree::Filter { child, .. } => {
+            if let OpTree::Window {
+                child: window_child,
+                pass_through,
+                ..
+            } = unwrap_transparent(child)
+                && crate::dvm::operators::window::window_refill_from(
+                    window_child,
+                    pass_through,
+                    registry,
+                )
+                .is_none()
+            {
+                return Err(crate::dvm::operators::window::truncated_window_unsupported());
+            }
+            check_window_truncation(child, registry)
+        }
+        OpTree::Project { child, .. }
+        | OpTree::Distinct { child }
+        | OpTree::Subquery { child, .. }
+        | OpTree::Aggregate { child, .. }
+        | OpTree::GroupingSets { child, .. }
+        | OpTree::Window { child, .. }
+        | OpTree::LateralFunction { child, .. }
+        | OpTree::LateralSubquery { child, .. } => check_window_truncation(child, registry),
+        OpTree::InnerJoin { left, right, .. }
+        | OpTree::LeftJoin { left, right, .. }
+        | OpTree::FullJoin { left, right, .. }
+        | OpTree::Intersect { left, right, .. }
+        | OpTree::Except { left, right, .. }
+        | OpTree::SemiJoin { left, right, .. }
+        | OpTree::AntiJoin { left, right, .. } => {
+            check_window_truncation(left, registry)?;
+            check_window_truncation(right, registry)
+        }
+        OpTree::UnionAll { children } => children
+            .iter()
+            .try_for_each(|c| check_window_truncation(c, registry)),
+        OpTree::RecursiveCte {
+            base, recursive, ..
+        } => {
+            check_window_truncation(base, registry)?;
+            check_window_truncation(recursive, registry)
+        }
+        OpTree::ScalarSubquery {
+            child, subquery, ..
+        } => {
+            check_window_truncation(child, registry)?;
+            check_window_truncation(subquery, registry)
+        }
+    }
+}
+
 /// Check that both children of a join resolve to direct table references.
 ///
 /// Previously used to reject nested joins. Kept for test coverage — the callers
@@ -651,22 +1097,64 @@ pub(crate) fn resolves_to_base_table(op: &OpTree) -> bool {
     }
 }
 
-/// F4 (v0.37.0): Returns true if an expression contains any pgvector distance
-/// operator: `<->` (L2), `<=>` (cosine), `<#>` (inner product), `<+>` (L1).
+/// pgvector distance operators: `<->` (L2), `<=>` (cosine), `<#>` (inner
+/// product), `<+>` (L1).
+const PGVECTOR_DISTANCE_OPS: [&str; 4] = ["<->", "<=>", "<#>", "<+>"];
+
+/// F4 (v0.37.0): Returns true if an expression contains a pgvector distance
+/// operator that is not row-local.
 ///
-/// These operators are non-monotone and non-linear — no differentiation rule
-/// exists for them. Stream tables using these in WHERE/ORDER BY predicates
-/// are "FULL-fallback safe": they always produce correct results via FULL
-/// refresh. This function is used to emit a clear INFO/WARNING instead of a
-/// silent fallback.
-fn contains_pgvector_distance_op_in_expr(expr: &Expr) -> bool {
+/// A distance against a constant vector (`embedding <=> '[...]'::vector`)
+/// depends only on the current row, so a filter on it is differentiated like
+/// any other predicate (PGVEC-LOCAL). A distance between two row-dependent
+/// operands has no differentiation rule; stream tables using one in WHERE
+/// are "FULL-fallback safe" and get a clear INFO/WARNING instead of a silent
+/// fallback. Subquery bodies are walked through [`SubQuery::exprs`];
+/// operators inside raw SQL fragments are treated as cross-row.
+fn contains_cross_row_pgvector_distance(expr: &Expr) -> bool {
     match expr {
-        Expr::Raw(s) => {
-            s.contains("<->") || s.contains("<=>") || s.contains("<#>") || s.contains("<+>")
-        }
-        // Recurse into structured expressions if needed in the future.
-        // For now, Raw is the primary representation for complex predicates.
-        _ => false,
+        Expr::BinaryOp {
+            op, left, right, ..
+        } => {
+            (PGVECTOR_DISTANCE_OPS.contains(&op.as_str())
+                && !is_constant_expr(left)
+                && !is_constant_expr(right))
+                || contains_cross_row_pgvector_distance(left)
+                || contains_cross_row_pgvector_distance(right)
+        }
+        Expr::SubLink { query, .. } => expr
+            .children()
+            .into_iter()
+            .chain(&query.exprs)
+            .any(contains_cross_row_pgvector_distance),
+        Expr::Raw(sql) => PGVECTOR_DISTANCE_OPS.iter().any(|op| sql.contains(op)),
+        _ => expr
+            .children()
+            .into_iter()
+            .any(contains_cross_row_pgvector_distance),
+    }
+}
+
+/// PGVEC-LOCAL: Returns true if an expression references no columns and
+/// calls no functions — a literal, a cast of a literal, an array or row of
+/// them, or arithmetic on them.
+///
+/// Decided on the parsed tree only: the parser turns every constant into
+/// `Expr::Literal`, so an `Expr::Raw` fragment is never treated as constant.
+fn is_constant_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) => true,
+        Expr::BinaryOp { .. }
+        | Expr::UnaryOp { .. }
+        | Expr::Cast { .. }
+        | Expr::Case { .. }
+        | Expr::Array(_)
+        | Expr::Row(_) => expr.children().into_iter().all(is_constant_expr),
+        Expr::ColumnRef { .. }
+        | Expr::FuncCall { .. }
+        | Expr::Star { .. }
+        | Expr::SubLink { .. }
+        | Expr::Raw(_) => false,
     }
 }
 
@@ -675,15 +1163,17 @@ pub(crate) fn check_ivm_support_inner(tree: &OpTree) -> Result<(), PgTrickleErro
         OpTree::Scan { .. } => Ok(()),
         OpTree::Project { child, .. } => check_ivm_support(child),
         OpTree::Filter { predicate, child } => {
-            // F4 (v0.37.0): pgvector distance operators in WHERE clauses are
-            // FULL-fallback safe — no differentiation rule exists for non-monotone,
-            // non-linear operators like <->, <=>, <#>, <+>. Document the fallback
-            // so users get a clear INFO message instead of a silent FULL refresh.
-            if contains_pgvector_distance_op_in_expr(predicate) {
+            // F4 (v0.37.0): pgvector distance operators between two row-dependent
+            // operands are FULL-fallback safe — no differentiation rule exists
+            // for them. Document the fallback so users get a clear INFO message
+            // instead of a silent FULL refresh. PGVEC-LOCAL: distances against a
+            // constant vector are row-local and stay DIFFERENTIAL.
+            if contains_cross_row_pgvector_distance(predicate) {
                 return Err(PgTrickleError::UnsupportedOperator(
-                    "pgvector distance operator (<->, <=>, <#>, <+>) in WHERE clause — \
-                     falling back to FULL refresh (expected and safe: distance operators \
-                     are non-monotone and cannot be differentiated)"
+                    "pgvector distance operator (<->, <=>, <#>, <+>) between two \
+                     row-dependent operands in WHERE clause — falling back to FULL refresh \
+                     (expected and safe: only distances to a constant vector can be \
+                     differentiated)"
                         .to_string(),
                 ));
             }
@@ -699,6 +1189,9 @@ pub(crate) fn check_ivm_support_inner(tree: &OpTree) -> Result<(), PgTrickleErro
         }
         OpTree::Aggregate {
             child, aggregates, ..
+        }
+        | OpTree::GroupingSets {
+            child, aggregates, ..
         } => {
             for agg in aggregates {
                 match agg.function {
@@ -862,7 +1355,9 @@ fn check_immediate_support(tree: &OpTree) -> Result<(), PgTrickleError> {
         }
 
         // Aggregates — allowed, recurse into child.
-        OpTree::Aggregate { child, .. } => check_immediate_support(child),
+        OpTree::Aggregate { child, .. } | OpTree::GroupingSets { child, .. } => {
+            check_immediate_support(child)
+        }
 
         // UNION ALL — allowed (each branch is a scan/filter/project chain).
         OpTree::UnionAll { children } => {
@@ -942,6 +1437,15 @@ fn check_immediate_support(tree: &OpTree) -> Result<(), PgTrickleError> {
 /// Returns `Ok(())` if all operators are monotone. Returns `Err` with a
 /// descriptive error if a non-monotone operator is found.
 ///
+/// # Lattice aggregates
+///
+/// An `Aggregate` whose aggregates are all MIN, MAX, BOOL_OR or BIT_OR is
+/// accepted: adding input rows can only move each group's value one way, so
+/// the values still reach a fixed point. The scheduler detects value changes
+/// by content rather than row count, and recomputes the whole SCC from
+/// scratch when source rows are deleted or updated; there is no incremental
+/// delete-and-rederive for cycles.
+///
 /// # Non-monotone operators
 ///
 /// - **Aggregate**: COUNT/SUM can decrease when rows are deleted
@@ -1013,13 +1517,24 @@ pub fn check_monotonicity(tree: &OpTree) -> Result<(), PgTrickleError> {
             check_monotonicity(subquery)
         }
 
+        // Lattice aggregates — monotone if the child is.
+        OpTree::Aggregate {
+            aggregates, child, ..
+        }
+        | OpTree::GroupingSets {
+            aggregates, child, ..
+        } if aggregates.iter().all(|agg| agg.function.is_lattice()) => check_monotonicity(child),
+
         // ── Non-monotone operators ────────────────────────────────────
-        OpTree::Aggregate { .. } => Err(PgTrickleError::UnsupportedOperator(
-            "Aggregate is not monotone — cannot participate in a circular dependency. \
-             Aggregates (COUNT, SUM, AVG, etc.) can decrease when input rows are removed, \
-             which prevents fixed-point convergence."
-                .into(),
-        )),
+        OpTree::Aggregate { .. } | OpTree::GroupingSets { .. } => {
+            Err(PgTrickleError::UnsupportedOperator(
+                "Aggregate is not monotone — cannot participate in a circular dependency. \
+                 Aggregates (COUNT, SUM, AVG, etc.) can decrease when input rows are removed, \
+                 which prevents fixed-point convergence. Only MIN, MAX, BOOL_OR and BIT_OR \
+                 are allowed in a cycle."
+                    .into(),
+            ))
+        }
 
         OpTree::Except { .. } => Err(PgTrickleError::UnsupportedOperator(
             "EXCEPT is not monotone — cannot participate in a circular dependency. \
@@ -1148,6 +1663,61 @@ mod monotonicity_tests {
         assert!(format!("{}", err).contains("Aggregate"));
     }
 
+    fn agg(function: AggFunc) -> AggExpr {
+        AggExpr {
+            function,
+            argument: Some(Expr::ColumnRef {
+                table_alias: None,
+                column_name: "cost".to_string(),
+            }),
+            alias: "a".to_string(),
+            is_distinct: false,
+            second_arg: None,
+            filter: None,
+            order_within_group: None,
+        }
+    }
+
+    #[test]
+    fn test_lattice_aggregate_is_monotone() {
+        let tree = OpTree::Aggregate {
+            group_by: vec![],
+            aggregates: vec![
+                agg(AggFunc::Min),
+                agg(AggFunc::Max),
+                agg(AggFunc::BoolOr),
+                agg(AggFunc::BitOr),
+            ],
+            child: Box::new(scan()),
+        };
+        assert!(check_monotonicity(&tree).is_ok());
+    }
+
+    #[test]
+    fn test_mixed_lattice_aggregate_is_not_monotone() {
+        let tree = OpTree::Aggregate {
+            group_by: vec![],
+            aggregates: vec![agg(AggFunc::Min), agg(AggFunc::Sum)],
+            child: Box::new(scan()),
+        };
+        let err = check_monotonicity(&tree).unwrap_err();
+        assert!(format!("{}", err).contains("MIN, MAX, BOOL_OR and BIT_OR"));
+    }
+
+    #[test]
+    fn test_lattice_aggregate_over_non_monotone_child() {
+        let tree = OpTree::Aggregate {
+            group_by: vec![],
+            aggregates: vec![agg(AggFunc::Max)],
+            child: Box::new(OpTree::Except {
+                left: Box::new(scan()),
+                right: Box::new(scan()),
+                all: false,
+            }),
+        };
+        assert!(check_monotonicity(&tree).is_err());
+    }
+
     #[test]
     fn test_except_is_not_monotone() {
         let tree = OpTree::Except {
@@ -198,6 +198,11 @@ fn infer_plan_row_id_schema(op: &OpTree) -> Result<RowIdSchema, String> {
                 Ok(RowIdSchema::GroupByKey { columns })
             }
         }
+        OpTree::GroupingSets { child, .. } => {
+            // Hash of the grouping-set id and the grouping columns.
+            let _child_schema = infer_plan_row_id_schema(child)?;
+            Ok(RowIdSchema::Derived)
+        }
         OpTree::Distinct { child } => {
             let _child_schema = infer_plan_row_id_schema(child)?;
             Ok(RowIdSchema::AllColumns {
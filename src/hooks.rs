@@ -100,6 +100,8 @@ pub(crate) enum DdlCommandKind {
     CreateTable,
     /// Any CREATE/ALTER VIEW command.
     ViewChange,
+    /// REFRESH MATERIALIZED VIEW [CONCURRENTLY].
+    MatviewRefresh,
     /// CREATE TRIGGER command.
     CreateTrigger,
     /// CREATE OR REPLACE FUNCTION / ALTER FUNCTION.
@@ -112,6 +114,8 @@ pub(crate) enum DdlCommandKind {
     PolicyChange,
     /// CREATE EXTENSION.
     ExtensionChange,
+    /// ALTER SCHEMA (RENAME TO renames every relation in it).
+    SchemaChange,
     /// Any other DDL that pg_trickle does not need to react to.
     Ignored,
 }
@@ -124,11 +128,13 @@ impl DdlCommandKind {
     /// future PostgreSQL naming changes require only this function to be updated.
     pub(crate) fn from_event(object_type: &str, command_tag: &str) -> Self {
         match (object_type, command_tag) {
-            ("table", "ALTER TABLE") => Self::AlterTable,
+            // RENAME COLUMN reports the column as the affected object.
+            ("table", "ALTER TABLE") | ("table column", "ALTER TABLE") => Self::AlterTable,
             ("table", "CREATE TABLE") => Self::CreateTable,
             ("view", "CREATE VIEW")
             | ("view", "CREATE OR REPLACE VIEW")
             | ("view", "ALTER VIEW") => Self::ViewChange,
+            ("materialized view", "REFRESH MATERIALIZED VIEW") => Self::MatviewRefresh,
             ("trigger", "CREATE TRIGGER") => Self::CreateTrigger,
             ("function", "CREATE FUNCTION")
             | ("function", "CREATE OR REPLACE FUNCTION")
@@ -139,6 +145,7 @@ impl DdlCommandKind {
             | ("policy", "ALTER POLICY")
             | ("policy", "DROP POLICY") => Self::PolicyChange,
             ("extension", "CREATE EXTENSION") => Self::ExtensionChange,
+            ("schema", "ALTER SCHEMA") => Self::SchemaChange,
             _ => Self::Ignored,
         }
     }
@@ -219,6 +226,9 @@ fn handle_ddl_command(cmd: &DdlCommand) {
         DdlCommandKind::ViewChange => {
             handle_view_change(cmd);
         }
+        DdlCommandKind::MatviewRefresh => {
+            handle_matview_refresh(cmd);
+        }
         DdlCommandKind::CreateTrigger => {
             handle_create_trigger(cmd);
         }
@@ -246,6 +256,9 @@ fn handle_ddl_command(cmd: &DdlCommand) {
                 }
             }
         }
+        DdlCommandKind::SchemaChange => {
+            handle_schema_change(cmd);
+        }
         DdlCommandKind::Ignored => {}
     }
 }
@@ -330,6 +343,66 @@ fn handle_view_change(cmd: &DdlCommand) {
     shmem::bump_cache_generation();
 }
 
+// ── Materialized view refresh capture (MV-CDC, v0.49.0) ───────────────────
+
+/// Handle REFRESH MATERIALIZED VIEW [CONCURRENTLY] on a matview that is a
+/// stream table source.
+///
+/// A matview only changes when it is refreshed, so its delta is computed
+/// here, once, against the snapshot table and written to the change buffer
+/// in the refreshing transaction. Stream tables reading the matview then see
+/// an empty buffer (and skip work) until the next REFRESH. A capture failure
+/// aborts the REFRESH so that no change is lost.
+fn handle_matview_refresh(cmd: &DdlCommand) {
+    let identity = cmd.object_identity.as_deref().unwrap_or("unknown");
+
+    // A failed lookup must not skip the capture: the delta would be lost.
+    let tracked = match Spi::get_one_with_args::<bool>(
+        "SELECT EXISTS(SELECT 1 FROM pgtrickle.pgt_change_tracking WHERE source_relid = $1)",
+        &[cmd.objid.into()],
+    ) {
+        Ok(tracked) => tracked.unwrap_or(false),
+        Err(e) => pgrx::error!(
+            "pg_trickle: failed to look up change tracking of materialized view {}: {}",
+            identity,
+            e
+        ),
+    };
+    if !tracked {
+        return;
+    }
+
+    // REFRESH ... WITH NO DATA leaves the matview unscannable. Keep the
+    // snapshot; the next populating REFRESH diffs against it.
+    let populated = match Spi::get_one_with_args::<bool>(
+        "SELECT relispopulated FROM pg_class WHERE oid = $1",
+        &[cmd.objid.into()],
+    ) {
+        Ok(populated) => populated.unwrap_or(false),
+        Err(e) => pgrx::error!(
+            "pg_trickle: failed to read the state of materialized view {}: {}",
+            identity,
+            e
+        ),
+    };
+    if !populated {
+        return;
+    }
+
+    let change_schema = config::pg_trickle_change_buffer_schema().replace('"', "\"\"");
+    if let Err(e) = cdc::poll_matview_changes(cmd.objid, &change_schema) {
+        pgrx::error!(
+            "pg_trickle: failed to capture changes of materialized view {}: {}",
+            identity,
+            e
+        );
+    }
+    log!(
+        "pg_trickle_ddl_tracker: captured refresh delta of materialized view {}",
+        identity
+    );
+}
+
 // ── Function DDL handling ──────────────────────────────────────────────────
 
 /// Handle CREATE OR REPLACE FUNCTION / ALTER FUNCTION on a function that
@@ -716,6 +789,53 @@ fn handle_policy_change(cmd: &DdlCommand) {
     shmem::bump_cache_generation();
 }
 
+// ── ALTER SCHEMA handling ──────────────────────────────────────────────────
+
+/// REN-1: Handle ALTER SCHEMA on a schema that contains tracked sources.
+///
+/// `ALTER SCHEMA ... RENAME TO` renames every relation in the schema, so
+/// each tracked table in it goes through the ALTER TABLE path, whose
+/// snapshot comparison detects the new qualified name.
+fn handle_schema_change(cmd: &DdlCommand) {
+    let sources: Vec<(pg_sys::Oid, String)> = match Spi::connect(|client| {
+        let table = client
+            .select(
+                "SELECT DISTINCT d.source_relid, c.oid::regclass::text \
+                 FROM pgtrickle.pgt_dependencies d \
+                 JOIN pg_class c ON c.oid = d.source_relid \
+                 WHERE c.relnamespace = $1 AND d.source_type = 'TABLE'",
+                None,
+                &[cmd.objid.into()],
+            )
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        let mut out = Vec::new();
+        for row in table {
+            let map_spi = |e: pgrx::spi::SpiError| PgTrickleError::SpiError(e.to_string());
+            if let (Some(oid), Some(name)) = (
+                row.get::<pg_sys::Oid>(1).map_err(map_spi)?,
+                row.get::<String>(2).map_err(map_spi)?,
+            ) {
+                out.push((oid, name));
+            }
+        }
+        Ok::<_, PgTrickleError>(out)
+    }) {
+        Ok(sources) => sources,
+        Err(e) => {
+            pgrx::warning!(
+                "pg_trickle_ddl_tracker: failed to query sources in schema {}: {}",
+                cmd.object_identity.as_deref().unwrap_or("unknown"),
+                e,
+            );
+            return;
+        }
+    };
+
+    for (source_oid, identity) in &sources {
+        handle_alter_table(*source_oid, identity);
+    }
+}
+
 // ── ALTER TABLE handling ───────────────────────────────────────────────────
 
 /// Handle ALTER TABLE on an object that may be an upstream dependency or
@@ -744,6 +864,8 @@ fn handle_alter_table(objid: pg_sys::Oid, identity: &str) {
     // changes.  Benign DDL (adding indexes, comments, statistics) and
     // constraint-only changes skip reinit when column tracking is populated.
     let mut reinit_pgt_ids = Vec::new();
+    let mut partition_pgt_ids = Vec::new();
+    let mut rename_pgt_ids = Vec::new();
     for pgt_id in &affected_pgt_ids {
         let kind = match detect_schema_change_kind(objid, *pgt_id) {
             Ok(k) => k,
@@ -868,26 +990,69 @@ fn handle_alter_table(objid: pg_sys::Oid, identity: &str) {
                 reinit_pgt_ids.push(*pgt_id);
             }
             SchemaChangeKind::PartitionChange => {
-                // PT2: ATTACH/DETACH PARTITION on a partitioned source table.
-                // Pre-existing rows in newly attached partitions are not
-                // captured by CDC triggers, so reinitialize to pick up all data.
-                pgrx::info!(
-                    "pg_trickle: partition structure changed on {} — \
-                     stream table {} marked for reinit",
+                // PT2/PT5: ATTACH/DETACH PARTITION on a partitioned source
+                // table — handled for all affected STs at once below.
+                partition_pgt_ids.push(*pgt_id);
+            }
+            SchemaChangeKind::Rename => {
+                // REN-1: RENAME COLUMN / RENAME TO / SET SCHEMA — handled for
+                // all affected STs at once below.
+                rename_pgt_ids.push(*pgt_id);
+            }
+        }
+    }
+
+    // REN-1: Rewrite the stored queries for the new names; STs whose query
+    // cannot be rewritten are treated like any other column change.
+    let renamed = !rename_pgt_ids.is_empty();
+    if renamed {
+        let (unrewritable, wal_reinit) = handle_source_rename(objid, identity, &rename_pgt_ids);
+        for pgt_id in &unrewritable {
+            if config::pg_trickle_block_source_ddl() {
+                pgrx::error!(
+                    "pg_trickle: ALTER TABLE on {} blocked — the defining query of stream \
+                     table {} cannot be rewritten for the rename while \
+                     pg_trickle.block_source_ddl = true (the default). \
+                     To proceed: SET pg_trickle.block_source_ddl = false, run the DDL, \
+                     then run ALTER STREAM TABLE ... to update the defining query, \
+                     and re-enable: SET pg_trickle.block_source_ddl = true.",
                     identity,
                     pgt_id,
                 );
-                if let Err(e) = StreamTableMeta::mark_for_reinitialize(*pgt_id) {
-                    pgrx::warning!(
-                        "pg_trickle_ddl_tracker: failed to mark ST {} for reinit \
-                         after partition change: {}",
-                        pgt_id,
-                        e,
-                    );
-                }
-                reinit_pgt_ids.push(*pgt_id);
             }
         }
+        for pgt_id in unrewritable.into_iter().chain(wal_reinit) {
+            if let Err(e) = StreamTableMeta::mark_for_reinitialize(pgt_id) {
+                pgrx::warning!(
+                    "pg_trickle_ddl_tracker: failed to mark ST {} for reinit after rename: {}",
+                    pgt_id,
+                    e,
+                );
+            }
+            reinit_pgt_ids.push(pgt_id);
+        }
+    }
+
+    // PT5: Turn the moved partitions into change-buffer deltas; STs that
+    // cannot consume them are reinitialized instead.
+    if !partition_pgt_ids.is_empty() {
+        for pgt_id in handle_partition_change(objid, identity, &partition_pgt_ids) {
+            pgrx::info!(
+                "pg_trickle: partition structure changed on {} — \
+                 stream table {} marked for reinit",
+                identity,
+                pgt_id,
+            );
+            if let Err(e) = StreamTableMeta::mark_for_reinitialize(pgt_id) {
+                pgrx::warning!(
+                    "pg_trickle_ddl_tracker: failed to mark ST {} for reinit \
+                     after partition change: {}",
+                    pgt_id,
+                    e,
+                );
+            }
+            reinit_pgt_ids.push(pgt_id);
+        }
     }
 
     // Cascade: find STs that depend on the affected STs (transitive).
@@ -949,6 +1114,9 @@ fn handle_alter_table(objid: pg_sys::Oid, identity: &str) {
         );
         // G8.1: Notify other backends to flush their delta/MERGE template caches.
         shmem::bump_cache_generation();
+    } else if renamed {
+        // REN-1: Cached delta/MERGE templates still carry the old names.
+        shmem::bump_cache_generation();
     } else {
         log!(
             "pg_trickle_ddl_tracker: ALTER TABLE on {} → benign for all {} dependent ST(s), \
@@ -959,6 +1127,394 @@ fn handle_alter_table(objid: pg_sys::Oid, identity: &str) {
     }
 }
 
+/// PT5 (v0.49.0): Apply ATTACH/DETACH PARTITION on a partitioned source as
+/// a delta instead of a reinitialize.
+///
+/// The partitions that moved are found by comparing the child OIDs stored in
+/// each ST's column snapshot with the current ones. Rows of an attached
+/// partition are written to the source's change buffer as inserts, rows of a
+/// detached one as deletes — once, since the buffer is shared by all STs on
+/// the source. The snapshots are then refreshed so the next DDL event has
+/// the right baseline.
+///
+/// Returns the STs that must be reinitialized instead: IMMEDIATE-mode STs
+/// (no change buffer), STs whose snapshot predates PT5 or disagrees with the
+/// others, and all of them if the capture fails.
+fn handle_partition_change(source_oid: pg_sys::Oid, identity: &str, pgt_ids: &[i64]) -> Vec<i64> {
+    let current = match crate::catalog::query_partition_children(source_oid) {
+        Ok(children) => children,
+        Err(e) => {
+            pgrx::warning!(
+                "pg_trickle_ddl_tracker: failed to read partitions of {}: {}",
+                identity,
+                e,
+            );
+            return pgt_ids.to_vec();
+        }
+    };
+
+    let mut reinit = Vec::new();
+    let mut incremental = Vec::new();
+    let mut moved: Option<(Vec<u32>, Vec<u32>)> = None;
+    for &pgt_id in pgt_ids {
+        let immediate = StreamTableMeta::get_by_id(pgt_id)
+            .ok()
+            .flatten()
+            .is_none_or(|st| st.refresh_mode.is_immediate());
+        let stored = crate::catalog::get_column_snapshot(pgt_id, source_oid)
+            .ok()
+            .flatten()
+            .and_then(|snapshot| match snapshot.0 {
+                serde_json::Value::Object(obj) => stored_partition_children(&obj),
+                _ => None,
+            });
+        let Some(stored) = stored.filter(|_| !immediate) else {
+            reinit.push(pgt_id);
+            continue;
+        };
+        let diff = partition_membership_diff(&stored, &current);
+        match &moved {
+            Some(first) if *first != diff => reinit.push(pgt_id),
+            _ => {
+                moved = Some(diff);
+                incremental.push(pgt_id);
+            }
+        }
+    }
+
+    let Some((attached, detached)) = moved else {
+        return reinit;
+    };
+
+    let change_schema = config::pg_trickle_change_buffer_schema();
+    let captured = attached
+        .iter()
+        .map(|oid| (*oid, 'I'))
+        .chain(detached.iter().map(|oid| (*oid, 'D')))
+        .try_fold(0i64, |total, (oid, action)| {
+            cdc::capture_partition_rows(source_oid, pg_sys::Oid::from(oid), action, &change_schema)
+                .map(|n| total + n)
+        });
+    match captured {
+        Ok(rows) => {
+            pgrx::info!(
+                "pg_trickle: partition structure changed on {} — {} partition(s) attached, \
+                 {} detached, {} row(s) written to the change buffer",
+                identity,
+                attached.len(),
+                detached.len(),
+                rows,
+            );
+            for pgt_id in &incremental {
+                if let Err(e) =
+                    crate::catalog::store_column_snapshot_for_pgt_id(*pgt_id, source_oid)
+                {
+                    pgrx::warning!(
+                        "pg_trickle_ddl_tracker: failed to store column snapshot for ST {}: {}",
+                        pgt_id,
+                        e,
+                    );
+                }
+            }
+            reinit
+        }
+        Err(e) => {
+            pgrx::warning!(
+                "pg_trickle_ddl_tracker: failed to capture partition rows of {}: {} \
+                 — falling back to reinit",
+                identity,
+                e,
+            );
+            reinit.extend(incremental);
+            reinit
+        }
+    }
+}
+
+/// PT5: Child partition OIDs stored in a column snapshot, if recorded.
+fn stored_partition_children(obj: &serde_json::Map<String, serde_json::Value>) -> Option<Vec<u32>> {
+    obj.get("partition_children")?
+        .as_array()?
+        .iter()
+        .map(|v| v.as_u64().map(|oid| oid as u32))
+        .collect()
+}
+
+/// PT5: Split a partition membership change into `(attached, detached)`.
+fn partition_membership_diff(stored: &[u32], current: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let attached = current
+        .iter()
+        .filter(|oid| !stored.contains(oid))
+        .copied()
+        .collect();
+    let detached = stored
+        .iter()
+        .filter(|oid| !current.contains(oid))
+        .copied()
+        .collect();
+    (attached, detached)
+}
+
+/// REN-1: Carry a source RENAME COLUMN / RENAME TO / SET SCHEMA over to the
+/// dependent stream tables without a reinitialize.
+///
+/// The old names come from each ST's column snapshot. The stored defining
+/// query, original query and TopK `ORDER BY` are rewritten in place, the
+/// tracked columns renamed and the snapshot refreshed; the frontier is kept.
+/// Change buffer columns are renamed before the CDC trigger is rebuilt so
+/// captured rows survive.
+///
+/// Returns `(unrewritable, wal_reinit)`: STs whose query cannot be rewritten,
+/// and rewritten STs that still need a reinitialize because a column was
+/// renamed on a source using WAL-based CDC (the fallback drops the slot).
+fn handle_source_rename(
+    source_oid: pg_sys::Oid,
+    identity: &str,
+    pgt_ids: &[i64],
+) -> (Vec<i64>, Vec<i64>) {
+    let mut unrewritable = Vec::new();
+    let mut rewritten = Vec::new();
+    let mut renamed_columns: Vec<(String, String)> = Vec::new();
+
+    for &pgt_id in pgt_ids {
+        let rename = match crate::catalog::get_column_snapshot(pgt_id, source_oid) {
+            Ok(Some(snapshot)) => match &snapshot.0 {
+                serde_json::Value::Object(obj) => {
+                    source_rename_from_snapshot(source_oid, obj).unwrap_or(None)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(rename) = rename else {
+            unrewritable.push(pgt_id);
+            continue;
+        };
+        if renamed_columns.is_empty() {
+            renamed_columns = rename.columns.clone();
+        }
+
+        match rewrite_stream_table_for_rename(pgt_id, source_oid, &rename) {
+            Ok(true) => rewritten.push(pgt_id),
+            Ok(false) => {
+                pgrx::info!(
+                    "pg_trickle: the defining query of stream table {} cannot be rewritten \
+                     for the rename of {} (SELECT *, USING or NATURAL over a renamed column)",
+                    pgt_id,
+                    identity,
+                );
+                unrewritable.push(pgt_id);
+            }
+            Err(e) => {
+                pgrx::warning!(
+                    "pg_trickle_ddl_tracker: failed to rewrite ST {} for the rename of {}: {}",
+                    pgt_id,
+                    identity,
+                    e,
+                );
+                unrewritable.push(pgt_id);
+            }
+        }
+    }
+
+    if !renamed_columns.is_empty() {
+        let change_schema = config::pg_trickle_change_buffer_schema();
+        if let Err(e) =
+            cdc::rename_change_buffer_columns(source_oid, &change_schema, &renamed_columns)
+        {
+            // The trigger rebuild would drop the old buffer columns and
+            // lose their captured values — reinitialize instead.
+            pgrx::warning!(
+                "pg_trickle_ddl_tracker: failed to rename change buffer columns for {}: {} \
+                 — falling back to reinit",
+                identity,
+                e,
+            );
+            unrewritable.append(&mut rewritten);
+        }
+    }
+
+    if !rewritten.is_empty() {
+        pgrx::info!(
+            "pg_trickle: {} renamed — rewrote the defining query of {} stream table(s)",
+            identity,
+            rewritten.len(),
+        );
+    }
+
+    let on_wal = !renamed_columns.is_empty()
+        && StDependency::get_all()
+            .unwrap_or_default()
+            .iter()
+            .any(|dep| dep.source_relid == source_oid && dep.cdc_mode != CdcMode::Trigger);
+    let wal_reinit = if on_wal { rewritten } else { Vec::new() };
+
+    (unrewritable, wal_reinit)
+}
+
+/// REN-1: Rewrite the catalog entries of one stream table for a source
+/// rename. Returns `false` when the defining query cannot be rewritten.
+fn rewrite_stream_table_for_rename(
+    pgt_id: i64,
+    source_oid: pg_sys::Oid,
+    rename: &crate::dvm::SourceRename,
+) -> Result<bool, PgTrickleError> {
+    let st = StreamTableMeta::get_by_id(pgt_id)?
+        .ok_or_else(|| PgTrickleError::NotFound(format!("stream table {pgt_id}")))?;
+
+    let Some(defining_query) = crate::dvm::rewrite_source_rename(&st.defining_query, rename)?
+    else {
+        return Ok(false);
+    };
+    let original_query = match &st.original_query {
+        Some(q) => match crate::dvm::rewrite_source_rename(q, rename)? {
+            Some(rewritten) => Some(rewritten),
+            None => return Ok(false),
+        },
+        None => None,
+    };
+    let topk_order_by = match &st.topk_order_by {
+        Some(o) => {
+            match crate::dvm::rewrite_source_rename_order_by(&st.defining_query, o, rename)? {
+                Some(rewritten) => Some(rewritten),
+                None => return Ok(false),
+            }
+        }
+        None => None,
+    };
+
+    StreamTableMeta::update_queries_after_rename(
+        pgt_id,
+        &defining_query,
+        original_query.as_deref(),
+        topk_order_by.as_deref(),
+    )?;
+
+    if !rename.columns.is_empty()
+        && let Some(cols) = StDependency::get_for_st(pgt_id)?
+            .into_iter()
+            .find(|dep| dep.source_relid == source_oid)
+            .and_then(|dep| dep.columns_used)
+    {
+        StDependency::update_columns_used(
+            pgt_id,
+            source_oid,
+            Some(rename_columns_used(&cols, &rename.columns)),
+        )?;
+    }
+
+    crate::catalog::store_column_snapshot_for_pgt_id(pgt_id, source_oid)?;
+    crate::template_cache::invalidate(pgt_id);
+    crate::refresh::invalidate_merge_cache(pgt_id);
+    shmem::signal_dag_invalidation(pgt_id);
+    Ok(true)
+}
+
+/// REN-1: Derive the rename between a stored column snapshot and the
+/// current catalog state, if the change is a pure rename.
+fn source_rename_from_snapshot(
+    source_oid: pg_sys::Oid,
+    obj: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Option<crate::dvm::SourceRename>, PgTrickleError> {
+    let Some(serde_json::Value::Array(entries)) = obj.get("columns") else {
+        return Ok(None);
+    };
+
+    let current: Vec<(String, i64, i64)> = Spi::connect(|client| {
+        let sql = format!(
+            "SELECT attname::text, atttypid::bigint, attnum::bigint \
+             FROM pg_attribute \
+             WHERE attrelid = {} AND attnum > 0 AND NOT attisdropped \
+               AND attgenerated = '' \
+             ORDER BY attnum",
+            source_oid.to_u32(),
+        );
+        let result = client
+            .select(&sql, None, &[])
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        let mut out = Vec::new();
+        for row in result {
+            let name: String = row
+                .get(1)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                .unwrap_or_default();
+            let type_oid: i64 = row
+                .get(2)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                .unwrap_or(0);
+            let ordinal: i64 = row
+                .get(3)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                .unwrap_or(0);
+            out.push((name, type_oid, ordinal));
+        }
+        Ok(out)
+    })?;
+
+    let Some(columns) = diff_renamed_columns(entries, &current) else {
+        return Ok(None);
+    };
+
+    // Snapshots stored before REN-1 carry no relation name; a table rename
+    // on those is only noticed once the snapshot is refreshed.
+    let (new_schema, new_table) = crate::catalog::query_relation_name(source_oid)?;
+    let stored_name = |key: &str, current: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(current)
+            .to_string()
+    };
+    let rename = crate::dvm::SourceRename {
+        old_schema: stored_name("schema_name", &new_schema),
+        old_table: stored_name("table_name", &new_table),
+        new_schema,
+        new_table,
+        columns,
+    };
+
+    Ok((!rename.is_empty()).then_some(rename))
+}
+
+/// Pure comparison for REN-1: pair stored and current columns by ordinal.
+///
+/// Returns the `(old, new)` name pairs that differ, or `None` when the
+/// column set changed in any other way (added, dropped, retyped).
+fn diff_renamed_columns(
+    stored_entries: &[serde_json::Value],
+    current: &[(String, i64, i64)],
+) -> Option<Vec<(String, String)>> {
+    if stored_entries.len() != current.len() {
+        return None;
+    }
+    let mut renames = Vec::new();
+    for entry in stored_entries {
+        let name = entry["name"].as_str()?;
+        let type_oid = entry["type_oid"].as_i64()?;
+        let ordinal = entry["ordinal"].as_i64()?;
+        let (cur_name, cur_type, _) = current.iter().find(|(_, _, o)| *o == ordinal)?;
+        if *cur_type != type_oid {
+            return None;
+        }
+        if cur_name != name {
+            renames.push((name.to_string(), cur_name.clone()));
+        }
+    }
+    Some(renames)
+}
+
+/// Apply REN-1 column renames to a dependency's tracked column list.
+fn rename_columns_used(columns_used: &[String], renames: &[(String, String)]) -> Vec<String> {
+    columns_used
+        .iter()
+        .map(|col| {
+            renames
+                .iter()
+                .find(|(old, _)| old == col)
+                .map_or_else(|| col.clone(), |(_, new)| new.clone())
+        })
+        .collect()
+}
+
 /// When ALTER TABLE is detected on a source using WAL-based CDC, abort the
 /// WAL transition and fall back to trigger-based CDC.
 ///
@@ -1510,10 +2066,15 @@ pub enum SchemaChangeKind {
     ConstraintChange,
     /// RLS state changed (ENABLE/DISABLE ROW LEVEL SECURITY or FORCE/NO FORCE).
     RlsChange,
-    /// Partition structure changed (ATTACH/DETACH PARTITION) — requires reinitialize
-    /// because pre-existing rows in newly attached partitions are not captured by
-    /// CDC triggers.
+    /// Partition structure changed (ATTACH/DETACH PARTITION). Pre-existing rows
+    /// of an attached or detached partition are not captured by CDC, so they
+    /// are written to the change buffer directly (PT5), or the stream table is
+    /// reinitialized when that is not possible.
     PartitionChange,
+    /// Source relation or columns renamed (RENAME COLUMN / RENAME TO / SET
+    /// SCHEMA). The stored queries are rewritten for the new names (REN-1),
+    /// or the stream table is reinitialized when that is not possible.
+    Rename,
     /// Other DDL (comment, owner change, etc.) — no reinitialize needed.
     Benign,
 }
@@ -1553,6 +2114,11 @@ pub fn detect_schema_change_kind(
                 if let Some(serde_json::Value::Array(entries)) = obj.get("columns")
                     && !entries.is_empty()
                 {
+                    // REN-1: A rename keeps every column's ordinal and type.
+                    if source_rename_from_snapshot(source_oid, obj)?.is_some() {
+                        return Ok(SchemaChangeKind::Rename);
+                    }
+
                     let col_kind = detect_from_snapshot(source_oid, entries)?;
 
                     // Column-level changes take priority.
@@ -1578,13 +2144,25 @@ pub fn detect_schema_change_kind(
                     }
 
                     // PT2: Check if partition structure changed.
-                    let stored_child_count = obj
-                        .get("partition_child_count")
-                        .and_then(|v| v.as_i64())
-                        .unwrap_or(0);
-                    let current_child_count =
-                        crate::catalog::query_partition_child_count(source_oid).unwrap_or(0);
-                    if stored_child_count != current_child_count {
+                    // PT5: Compare the child OIDs when the snapshot has them.
+                    let partitions_changed = match stored_partition_children(obj) {
+                        Some(stored) => {
+                            stored
+                                != crate::catalog::query_partition_children(source_oid)
+                                    .unwrap_or_default()
+                        }
+                        None => {
+                            let stored_child_count = obj
+                                .get("partition_child_count")
+                                .and_then(|v| v.as_i64())
+                                .unwrap_or(0);
+                            let current_child_count =
+                                crate::catalog::query_partition_child_count(source_oid)
+                                    .unwrap_or(0);
+                            stored_child_count != current_child_count
+                        }
+                    };
+                    if partitions_changed {
                         return Ok(SchemaChangeKind::PartitionChange);
                     }
 
@@ -1722,6 +2300,234 @@ fn get_tracked_columns(
     Ok(cols.unwrap_or_default())
 }
 
+// ── ProcessUtility hook: DDL-style stream table commands (F11b) ────────────
+//
+// PostgreSQL's grammar cannot be extended, so the hook intercepts the
+// materialized-view spelling of stream-table DDL (see `api::stream_ddl`):
+//
+// - `CREATE MATERIALIZED VIEW ... WITH (pgtrickle.*) AS ...` → create
+// - `ALTER MATERIALIZED VIEW <st> SET (pgtrickle.*)`         → alter
+// - `REFRESH MATERIALIZED VIEW <st>`                          → refresh
+// - `DROP MATERIALIZED VIEW <st> [CASCADE]`                   → drop
+//
+// Statements on ordinary materialized views pass through untouched.
+
+static mut PREV_PROCESS_UTILITY_HOOK: pg_sys::ProcessUtility_hook_type = None;
+
+/// Install the ProcessUtility hook. Called once from `_PG_init()` when
+/// loaded via `shared_preload_libraries`.
+pub fn register_process_utility_hook() {
+    // SAFETY: Called once from _PG_init in the postmaster before any backend
+    // runs; the previous hook is saved and chained.
+    unsafe {
+        PREV_PROCESS_UTILITY_HOOK = pg_sys::ProcessUtility_hook;
+        pg_sys::ProcessUtility_hook = Some(pg_trickle_process_utility);
+    }
+}
+
+#[pg_guard]
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C-unwind" fn pg_trickle_process_utility(
+    pstmt: *mut pg_sys::PlannedStmt,
+    query_string: *const std::os::raw::c_char,
+    read_only_tree: bool,
+    context: pg_sys::ProcessUtilityContext::Type,
+    params: pg_sys::ParamListInfo,
+    query_env: *mut pg_sys::QueryEnvironment,
+    dest: *mut pg_sys::DestReceiver,
+    qc: *mut pg_sys::QueryCompletion,
+) {
+    if try_handle_stream_ddl(pstmt, query_string) {
+        return;
+    }
+
+    // SAFETY: Chaining to the previous hook (or the standard implementation)
+    // with the exact arguments PostgreSQL passed to us. The saved hook is
+    // only written once, in _PG_init.
+    unsafe {
+        let prev_hook = PREV_PROCESS_UTILITY_HOOK;
+        match prev_hook {
+            Some(prev) => prev(
+                pstmt,
+                query_string,
+                read_only_tree,
+                context,
+                params,
+                query_env,
+                dest,
+                qc,
+            ),
+            None => pg_sys::standard_ProcessUtility(
+                pstmt,
+                query_string,
+                read_only_tree,
+                context,
+                params,
+                query_env,
+                dest,
+                qc,
+            ),
+        }
+    }
+}
+
+/// Which materialized-view statement a utility node is, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatviewDdlKind {
+    /// `CREATE MATERIALIZED VIEW` carrying at least one `pgtrickle.*` option.
+    CreateWithStreamOptions,
+    /// `ALTER` or `REFRESH MATERIALIZED VIEW` — a stream table only if the
+    /// named relation is one.
+    OnExistingRelation,
+    /// `DROP MATERIALIZED VIEW` — may name several relations, which must be
+    /// either all stream tables or all plain materialized views.
+    Drop,
+}
+
+/// Classify the utility statement without touching the catalog.
+fn classify_matview_ddl(node: *mut pg_sys::Node) -> Option<MatviewDdlKind> {
+    if node.is_null() {
+        return None;
+    }
+    // SAFETY: `node` is the non-null utilityStmt of a PlannedStmt; each cast
+    // below is guarded by the matching node-tag check.
+    unsafe {
+        if pgrx::is_a(node, pg_sys::NodeTag::T_CreateTableAsStmt) {
+            let stmt = node as *mut pg_sys::CreateTableAsStmt;
+            if (*stmt).objtype != pg_sys::ObjectType::OBJECT_MATVIEW || (*stmt).into.is_null() {
+                return None;
+            }
+            let options = pgrx::PgList::<pg_sys::DefElem>::from_pg((*(*stmt).into).options);
+            let has_ours = options.iter_ptr().any(|d| {
+                !(*d).defnamespace.is_null()
+                    && std::ffi::CStr::from_ptr((*d).defnamespace)
+                        .to_bytes()
+                        .eq_ignore_ascii_case(b"pgtrickle")
+            });
+            return has_ours.then_some(MatviewDdlKind::CreateWithStreamOptions);
+        }
+        if pgrx::is_a(node, pg_sys::NodeTag::T_AlterTableStmt) {
+            let stmt = node as *mut pg_sys::AlterTableStmt;
+            return ((*stmt).objtype == pg_sys::ObjectType::OBJECT_MATVIEW)
+                .then_some(MatviewDdlKind::OnExistingRelation);
+        }
+        if pgrx::is_a(node, pg_sys::NodeTag::T_RefreshMatViewStmt) {
+            return Some(MatviewDdlKind::OnExistingRelation);
+        }
+        if pgrx::is_a(node, pg_sys::NodeTag::T_DropStmt) {
+            let stmt = node as *mut pg_sys::DropStmt;
+            return ((*stmt).removeType == pg_sys::ObjectType::OBJECT_MATVIEW)
+                .then_some(MatviewDdlKind::Drop);
+        }
+    }
+    None
+}
+
+/// Extract the text of the current statement from a (possibly
+/// multi-statement) query string.
+fn statement_text(
+    pstmt: *mut pg_sys::PlannedStmt,
+    query_string: *const std::os::raw::c_char,
+) -> Option<String> {
+    if query_string.is_null() {
+        return None;
+    }
+    // SAFETY: PostgreSQL passes a valid NUL-terminated query string and a
+    // valid PlannedStmt to ProcessUtility.
+    let (full, loc, len) = unsafe {
+        (
+            std::ffi::CStr::from_ptr(query_string).to_str().ok()?,
+            (*pstmt).stmt_location,
+            (*pstmt).stmt_len,
+        )
+    };
+    let start = if loc > 0 { loc as usize } else { 0 };
+    let end = if len > 0 {
+        (start + len as usize).min(full.len())
+    } else {
+        full.len()
+    };
+    full.get(start..end).map(str::to_string)
+}
+
+/// Handle the statement if it is stream-table DDL. Returns `true` when the
+/// statement was fully handled and must not reach standard_ProcessUtility.
+fn try_handle_stream_ddl(
+    pstmt: *mut pg_sys::PlannedStmt,
+    query_string: *const std::os::raw::c_char,
+) -> bool {
+    use crate::api::stream_ddl::{self, StreamDdl};
+
+    if pstmt.is_null() {
+        return false;
+    }
+    // SAFETY: pstmt is a valid PlannedStmt supplied by PostgreSQL.
+    let Some(kind) = classify_matview_ddl(unsafe { (*pstmt).utilityStmt }) else {
+        return false;
+    };
+
+    // Only act in databases where the extension is installed, and never
+    // while an extension script is running.
+    // SAFETY: Reading backend-local globals / catalog lookup inside a
+    // transaction, which ProcessUtility always runs in for these statements.
+    let installed = unsafe {
+        !pg_sys::creating_extension
+            && pg_sys::get_extension_oid(c"pg_trickle".as_ptr(), true) != pg_sys::InvalidOid
+    };
+    if !installed {
+        return false;
+    }
+
+    let Some(sql) = statement_text(pstmt, query_string) else {
+        return false;
+    };
+
+    let ddl = match stream_ddl::parse_stream_ddl(&sql) {
+        Ok((_, ddl)) => ddl,
+        // The pgtrickle.* option makes a CREATE unambiguously ours, so
+        // report the problem. Anything else is left to PostgreSQL.
+        Err(e) if kind == MatviewDdlKind::CreateWithStreamOptions => pgrx::error!("{}", e),
+        Err(_) => return false,
+    };
+
+    if kind != MatviewDdlKind::CreateWithStreamOptions {
+        let names: Vec<_> = match (kind, &ddl) {
+            (
+                MatviewDdlKind::OnExistingRelation,
+                StreamDdl::Alter { name, .. } | StreamDdl::Refresh { name },
+            ) => vec![name.clone()],
+            (MatviewDdlKind::Drop, StreamDdl::Drop { names, .. }) => names.clone(),
+            // The statement text and the parse tree disagree — leave it
+            // to PostgreSQL.
+            _ => return false,
+        };
+        let mut st_count = 0;
+        for name in &names {
+            match stream_ddl::is_stream_table(name) {
+                Ok(true) => st_count += 1,
+                Ok(false) => {}
+                Err(e) => pgrx::error!("{}", e),
+            }
+        }
+        if st_count == 0 {
+            return false;
+        }
+        // Only DROP names several relations, and one statement cannot be
+        // split between pg_trickle and PostgreSQL.
+        if kind == MatviewDdlKind::Drop && st_count != names.len() {
+            pgrx::error!(
+                "DROP MATERIALIZED VIEW cannot mix stream tables and plain materialized views; \
+                 drop them in separate statements"
+            );
+        }
+    }
+
+    if let Err(e) = stream_ddl::execute_stream_ddl(&ddl) {
+        pgrx::error!("{}", e);
+    }
+    true
+}
+
 // ── Unit tests ─────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1832,6 +2638,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_rename() {
+        assert_eq!(
+            DdlCommandKind::from_event("table column", "ALTER TABLE"),
+            DdlCommandKind::AlterTable,
+        );
+        assert_eq!(
+            DdlCommandKind::from_event("schema", "ALTER SCHEMA"),
+            DdlCommandKind::SchemaChange,
+        );
+    }
+
     #[test]
     fn test_classify_create_table() {
         assert_eq!(
@@ -1864,6 +2682,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_refresh_materialized_view() {
+        assert_eq!(
+            DdlCommandKind::from_event("materialized view", "REFRESH MATERIALIZED VIEW"),
+            DdlCommandKind::MatviewRefresh,
+        );
+        assert_eq!(
+            DdlCommandKind::from_event("materialized view", "CREATE MATERIALIZED VIEW"),
+            DdlCommandKind::Ignored,
+        );
+    }
+
     #[test]
     fn test_classify_create_trigger() {
         assert_eq!(
@@ -2013,4 +2843,86 @@ mod tests {
             SchemaChangeKind::AddColumnOnly,
         );
     }
+
+    // ── PT5: partition membership ──────────────────────────────────────
+
+    #[test]
+    fn test_partition_membership_diff_attach_and_detach() {
+        assert_eq!(
+            partition_membership_diff(&[10, 20], &[10, 20, 30]),
+            (vec![30], vec![])
+        );
+        assert_eq!(
+            partition_membership_diff(&[10, 20], &[20]),
+            (vec![], vec![10])
+        );
+        assert_eq!(
+            partition_membership_diff(&[10, 20], &[20, 30]),
+            (vec![30], vec![10])
+        );
+        assert_eq!(partition_membership_diff(&[10], &[10]), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_stored_partition_children() {
+        let with =
+            serde_json::json!({"partition_child_count": 2, "partition_children": [16384, 16390]});
+        let serde_json::Value::Object(obj) = with else {
+            unreachable!()
+        };
+        assert_eq!(stored_partition_children(&obj), Some(vec![16384, 16390]));
+
+        // Snapshots written before PT5 only carry the count.
+        let without = serde_json::json!({"partition_child_count": 2});
+        let serde_json::Value::Object(obj) = without else {
+            unreachable!()
+        };
+        assert_eq!(stored_partition_children(&obj), None);
+    }
+
+    // ── REN-1: source renames ──────────────────────────────────────────
+
+    fn current_col(name: &str, type_oid: i64, ordinal: i64) -> (String, i64, i64) {
+        (name.to_string(), type_oid, ordinal)
+    }
+
+    #[test]
+    fn test_diff_renamed_columns() {
+        let stored = vec![
+            serde_json::json!({"name": "id", "type_oid": 23, "ordinal": 1}),
+            serde_json::json!({"name": "amount", "type_oid": 1700, "ordinal": 2}),
+        ];
+
+        let renamed = vec![current_col("id", 23, 1), current_col("total", 1700, 2)];
+        assert_eq!(
+            diff_renamed_columns(&stored, &renamed),
+            Some(vec![("amount".to_string(), "total".to_string())])
+        );
+
+        let unchanged = vec![current_col("id", 23, 1), current_col("amount", 1700, 2)];
+        assert_eq!(diff_renamed_columns(&stored, &unchanged), Some(vec![]));
+
+        // Retyped, added or dropped columns are not a rename.
+        let retyped = vec![current_col("id", 23, 1), current_col("total", 701, 2)];
+        assert_eq!(diff_renamed_columns(&stored, &retyped), None);
+        let added = vec![
+            current_col("id", 23, 1),
+            current_col("amount", 1700, 2),
+            current_col("note", 25, 3),
+        ];
+        assert_eq!(diff_renamed_columns(&stored, &added), None);
+        let dropped_and_added = vec![current_col("id", 23, 1), current_col("amount", 1700, 3)];
+        assert_eq!(diff_renamed_columns(&stored, &dropped_and_added), None);
+    }
+
+    #[test]
+    fn test_rename_columns_used() {
+        let cols = vec!["id".to_string(), "amount".to_string()];
+        let renames = vec![("amount".to_string(), "total".to_string())];
+        assert_eq!(
+            rename_columns_used(&cols, &renames),
+            vec!["id".to_string(), "total".to_string()]
+        );
+        assert_eq!(rename_columns_used(&cols, &[]), cols);
+    }
 }
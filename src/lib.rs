@@ -121,6 +121,10 @@ pub extern "C-unwind" fn _PG_init() {
         // spawns a per-database scheduler for each one with pg_trickle installed.
         scheduler::register_launcher_worker();
 
+        // F11b (v0.49.0): Route materialized-view DDL carrying pgtrickle.*
+        // options (or targeting a stream table) to the stream table API.
+        hooks::register_process_utility_hook();
+
         // ERG-B: Warn if cdc_mode='auto' but wal_level is not 'logical'.
         // In this state the extension silently stays in TRIGGER-only CDC mode,
         // which is correct but may surprise users who expect WAL-based CDC.
@@ -1035,6 +1039,33 @@ COMMENT ON TABLE pgtrickle.pgt_distance_subscriptions IS
     requires = [],
 );
 
+// ── POLL-HWM (v0.49.0): Foreign-table polling configuration ─────────────
+extension_sql!(
+    r#"
+-- POLL-HWM (v0.49.0): High-water-mark polling configuration for foreign tables.
+-- A configured source is polled for rows whose watermark column is at or
+-- above the high-water mark instead of being re-read in full; a full
+-- reconciliation runs every reconcile_interval to catch physical deletes.
+-- The mark itself lives in the watermark column's type in a per-source
+-- hwm_<stable_name> table in the change buffer schema.
+CREATE TABLE IF NOT EXISTS pgtrickle.pgt_polling_config (
+    source_relid        OID         NOT NULL PRIMARY KEY,
+    watermark_column    TEXT        NOT NULL,
+    key_columns         TEXT[]      NOT NULL,
+    soft_delete_column  TEXT,
+    reconcile_interval  INTERVAL,
+    last_reconciled_at  TIMESTAMPTZ,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE pgtrickle.pgt_polling_config IS
+    'POLL-HWM (v0.49.0): High-water-mark polling configuration per foreign table. '
+    'Populated via pgtrickle.configure_foreign_table_polling() / pgtrickle.reset_foreign_table_polling().';
+"#,
+    name = "pg_trickle_polling_config_catalog",
+    requires = [],
+);
+
 // ── Launcher notification (must be last) ──────────────────────────────
 //
 // Signal the launcher background worker to re-probe this database.
@@ -332,8 +332,10 @@ fn collect_slot_health_rows() -> Vec<(String, i64, bool, i64, String)> {
 
     for (slot, relid) in trigger_rows {
         let source_oid_u32 = relid as u32;
-        if let Some((mode, _)) = wal_sources.remove(&source_oid_u32) {
-            let slot_name = wal_decoder::slot_name_for_source(pg_sys::Oid::from(source_oid_u32));
+        if let Some((mode, slot_opt)) = wal_sources.remove(&source_oid_u32) {
+            let slot_name = slot_opt.unwrap_or_else(|| {
+                wal_decoder::slot_name_for_source(pg_sys::Oid::from(source_oid_u32))
+            });
             let lag = wal_decoder::get_slot_lag_bytes(&slot_name).unwrap_or(0);
             rows.push((slot_name, relid, true, lag, mode.as_str().to_lowercase()));
         } else {
@@ -17,13 +17,32 @@ pub(crate) fn execute_incremental_truncate_delete(
         name.replace('"', "\"\"")
     );
 
+    // CORR-1 (v0.49.0): Temporal STs close every current version and
+    // archive it into the history table instead of discarding it.
+    let clear_sql = if st.temporal_mode {
+        build_temporal_close_all_sql(&quoted_table)
+    } else {
+        format!("DELETE FROM {quoted_table}")
+    };
+
     let rows_deleted = Spi::connect_mut(|client| {
         let result = client
-            .update(&format!("DELETE FROM {quoted_table}"), None, &[])
+            .update(&clear_sql, None, &[])
             .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
         Ok::<i64, PgTrickleError>(result.len() as i64)
     })?;
 
+    if st.temporal_mode {
+        let archive_sql = build_temporal_archive_sql(
+            &quoted_table,
+            &ensure_temporal_history_table(st.pgt_id)?,
+            &get_st_user_columns(st),
+            None,
+        );
+        Spi::run(&archive_sql) // nosemgrep: rust.spi.run.dynamic-format — identifiers are quoted.
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+
     pgrx::notice!(
         "[pg_trickle] Incremental TRUNCATE: deleted {} row(s) from {}.{} \
          (pure TRUNCATE window — skipping full query re-execution)",
@@ -31,6 +31,8 @@ pub mod columns;
 pub mod conflict;
 pub mod delete;
 pub mod insert;
+pub mod side_tables;
+pub mod temporal;
 pub mod update;
 
 // Re-export sub-module items into merge namespace so callers don't need
@@ -39,6 +41,8 @@ pub(crate) use columns::*;
 pub(crate) use conflict::*;
 pub(crate) use delete::*;
 pub use insert::execute_topk_refresh;
+pub use side_tables::{drop_agg_side_tables, maintain_agg_side_tables, rebuild_agg_side_tables};
+pub(crate) use temporal::*;
 pub(crate) use update::*;
 
 pub fn execute_full_refresh(st: &StreamTableMeta) -> Result<(i64, i64), PgTrickleError> {
@@ -139,10 +143,6 @@ pub fn execute_full_refresh(st: &StreamTableMeta) -> Result<(i64, i64), PgTrickl
         Vec::new()
     };
 
-    // Truncate
-    Spi::run(&format!("TRUNCATE {quoted_table}")) // nosemgrep: rust.spi.run.dynamic-format — TRUNCATE DDL cannot be parameterized; quoted_table is a PostgreSQL-quoted identifier
-        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
-
     // Compute row_id using the same hash formula as the delta query so
     // the MERGE ON clause matches during subsequent differential refreshes.
     // For INTERSECT/EXCEPT, compute per-branch multiplicities for dual-count
@@ -167,19 +167,47 @@ pub fn execute_full_refresh(st: &StreamTableMeta) -> Result<(i64, i64), PgTrickl
         }
     } else if let Some(ua_sql) = crate::dvm::try_union_all_refresh_sql(query) {
         ua_sql
+    } else if let Some(row_id_sql) = crate::dvm::grouping_sets_row_id_sql(query) {
+        // GSETS-NATIVE: the row ID hashes GROUPING(…) inside the query.
+        crate::api::inject_row_id(&effective_query, &row_id_sql)
     } else {
         let row_id_expr = crate::dvm::row_id_expr_for_query(query);
         format!("SELECT {row_id_expr} AS __pgt_row_id, sub.* FROM ({effective_query}) sub",)
     };
 
-    let insert_sql = format!("INSERT INTO {quoted_table} {insert_body}");
+    // CYC-LATTICE: the empty pass of an SCC recompute clears the
+    // table through the normal path, so downstream diff capture and the
+    // refresh history still see every removed row.
+    let insert_body = if scc_recompute_active() {
+        set_refresh_reason("scc_recompute");
+        format!("SELECT * FROM ({insert_body}) __pgt_recompute WHERE false")
+    } else {
+        insert_body
+    };
 
-    let rows_inserted = Spi::connect_mut(|client| {
-        let result = client
-            .update(&insert_sql, None, &[])
+    // CORR-1 (v0.49.0): Temporal STs diff the recomputed result against the
+    // current versions instead of truncating, so history survives a FULL
+    // refresh and unchanged rows keep their __pgt_valid_from.
+    let (rows_inserted, rows_closed) = if st.temporal_mode {
+        execute_temporal_full_refresh(st, &quoted_table, &insert_body)?
+    } else {
+        Spi::run(&format!("TRUNCATE {quoted_table}")) // nosemgrep: rust.spi.run.dynamic-format — TRUNCATE DDL cannot be parameterized; quoted_table is a PostgreSQL-quoted identifier
             .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
-        Ok::<usize, PgTrickleError>(result.len())
-    })?;
+
+        let insert_sql = format!("INSERT INTO {quoted_table} {insert_body}");
+
+        let rows_inserted = Spi::connect_mut(|client| {
+            let result = client
+                .update(&insert_sql, None, &[])
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+            Ok::<usize, PgTrickleError>(result.len())
+        })?;
+        (rows_inserted, 0)
+    };
+
+    // MINMAX-MS / ORDSET-HIST: Rebuild (or drop) the aggregate side tables
+    // from the same snapshot the storage table was just recomputed from.
+    rebuild_agg_side_tables(st)?;
 
     // ST-ST-3: Capture the full-refresh diff into the change buffer.
     // If diff capture fails, downstream DIFFERENTIAL STs would silently
@@ -247,7 +275,7 @@ pub fn execute_full_refresh(st: &StreamTableMeta) -> Result<(i64, i64), PgTrickl
         warn_default_partition_growth(schema, name);
     }
 
-    Ok((rows_inserted as i64, 0))
+    Ok((rows_inserted as i64, rows_closed as i64))
 }
 
 /// Post-full-refresh cleanup helper (G3 + G4).
@@ -285,6 +313,7 @@ pub fn post_full_refresh_cleanup(st: &StreamTableMeta) {
 
     // G3: Advance WAL slots past the current LSN so WAL segments produced
     // before and during the full refresh can be reclaimed by PostgreSQL.
+    // Shared slots are skipped: they still hold changes for other sources.
     for slot in deps
         .iter()
         .filter(|d| {
@@ -294,6 +323,7 @@ pub fn post_full_refresh_cleanup(st: &StreamTableMeta) {
             )
         })
         .filter_map(|d| d.slot_name.as_deref())
+        .filter(|slot| !crate::wal_decoder::is_shared_slot(slot))
     {
         match crate::wal_decoder::advance_slot_to_current(slot) {
             Ok(()) => {
@@ -326,29 +356,76 @@ pub fn post_full_refresh_cleanup(st: &StreamTableMeta) {
     // path where the delta SQL actually reads from the change buffer.
 }
 
-/// Poll all FOREIGN_TABLE and MATVIEW dependencies for a stream table before
-/// selecting a new differential frontier.
+/// Poll all FOREIGN_TABLE dependencies for a stream table before selecting a
+/// new differential frontier.
 ///
 /// Polling writes synthetic CDC rows into the local change buffers and updates
 /// the per-source snapshot tables. Callers must do this before capturing the
 /// new upper frontier so the synthetic rows fall within the refresh window.
+///
+/// MATVIEW dependencies are not polled: their deltas are captured when the
+/// matview is refreshed (MV-CDC, see `hooks::handle_matview_refresh`).
 pub fn poll_foreign_table_sources_for_st(st: &StreamTableMeta) -> Result<(), PgTrickleError> {
     let change_schema = crate::config::pg_trickle_change_buffer_schema().replace('"', "\"\"");
 
     for dep in StDependency::get_for_st(st.pgt_id)?
         .into_iter()
-        .filter(|dep| dep.source_type == "FOREIGN_TABLE" || dep.source_type == "MATVIEW")
+        .filter(|dep| dep.source_type == "FOREIGN_TABLE")
     {
-        if dep.source_type == "FOREIGN_TABLE" {
-            crate::cdc::poll_foreign_table_changes(dep.source_relid, &change_schema)?;
-        } else {
-            crate::cdc::poll_matview_changes(dep.source_relid, &change_schema)?;
-        }
+        crate::cdc::poll_foreign_table_changes(dep.source_relid, &change_schema)?;
     }
 
     Ok(())
 }
 
+/// SLIDE-1: Returns `true` when a sliding-window filter in the defining
+/// query has rows that crossed its boundary since the previous refresh.
+///
+/// Such rows need an expiry delta even though no source changed, so the
+/// scheduler and the no-change short-circuit must not skip the refresh.
+/// Only the next boundary of each filter is read from the sources, once per
+/// refresh; the per-tick check reads no table. A failing check is logged
+/// and counts as crossed, so expiry is never silently stopped.
+pub fn sliding_window_boundary_crossed(st: &StreamTableMeta) -> bool {
+    let Some(boundaries) = crate::dvm::sliding_window_boundaries(
+        st.pgt_id,
+        &st.defining_query,
+        &st.pgt_schema,
+        &st.pgt_name,
+    ) else {
+        return false;
+    };
+    let clock_key = st
+        .frontier
+        .as_ref()
+        .map(|f| format!("{:?}/{:?}", f.clock, f.data_timestamp));
+    let crossed =
+        crate::dvm::sliding_window_crossed_checks(st.pgt_id, &boundaries, clock_key.as_deref())
+            .and_then(|checks| {
+                checks.iter().try_fold(false, |crossed, check| {
+                    if crossed {
+                        return Ok(true);
+                    }
+                    Spi::get_one::<bool>(check) // nosemgrep: rust.spi.query.dynamic-format
+                        .map(|v| v.unwrap_or(false))
+                        .map_err(|e| {
+                            PgTrickleError::SpiError(format!("sliding-window boundary: {e}"))
+                        })
+                })
+            });
+    match crossed {
+        Ok(crossed) => crossed,
+        Err(e) => {
+            pgrx::warning!(
+                "[pg_trickle] SLIDE-1: sliding-window check failed for {}.{}, refreshing: {e}",
+                st.pgt_schema,
+                st.pgt_name,
+            );
+            true
+        }
+    }
+}
+
 /// Execute a NO_DATA refresh: just advance the data timestamp.
 pub fn execute_no_data_refresh(st: &StreamTableMeta) -> Result<(), PgTrickleError> {
     // G12-ERM-1: Record the effective mode for this execution path.
@@ -735,7 +812,10 @@ pub fn execute_differential_refresh(
         false
     };
 
-    if !any_changes && !any_st_changes {
+    // SLIDE-1: The stored frontier clock advances with this refresh, so
+    // sliding-window rows that crossed their boundary must be applied now
+    // even when no source changed.
+    if !any_changes && !any_st_changes && !sliding_window_boundary_crossed(st) {
         return Ok((0, 0));
     }
 
@@ -743,7 +823,8 @@ pub fn execute_differential_refresh(
     // When the stream table is marked append-only, check whether any
     // DELETE or UPDATE actions appeared in the change buffers. If so,
     // revert the flag and fall through to the normal MERGE path.
-    let mut is_append_only = st.is_append_only;
+    // Sliding windows expire rows, so the insert-only fast path never applies.
+    let mut is_append_only = st.is_append_only && !sliding_window;
     if is_append_only {
         let has_non_insert = catalog_source_oids.iter().any(|oid| {
             let prev_lsn = prev_frontier.get_lsn(*oid);
@@ -1790,6 +1871,11 @@ pub fn execute_differential_refresh(
         return result;
     }
 
+    // ── MINMAX-MS / ORDSET-HIST: Bring aggregate side tables up to date ─
+    // The delta query reads new MIN/MAX/MODE/PERCENTILE values from the
+    // side tables, so they must absorb this cycle's changes first.
+    maintain_agg_side_tables(st, prev_frontier, new_frontier)?;
+
     // ── A-3a: Append-only INSERT fast path ───────────────────────────
     // When the stream table is marked append-only (and hasn't been
     // reverted by the heuristic check above), skip MERGE entirely and
@@ -2081,7 +2167,11 @@ pub fn execute_differential_refresh(
     //
     // If the delta is empty (all changes cancel out), return early —
     // there is nothing to MERGE.
-    let hash_merge_result: Option<(usize, &str)> = if let Some(ref pk) = st.st_partition_key {
+    // CORR-1: Temporal STs apply deltas through their own close-out/insert
+    // statements, so the partitioned MERGE variants are skipped.
+    let hash_merge_result: Option<(usize, &str)> = if let Some(ref pk) = st.st_partition_key
+        && !st.temporal_mode
+    {
         let method = crate::api::parse_partition_method(pk);
         if method == crate::api::PartitionMethod::Hash {
             // A1-3b: Per-partition MERGE for HASH partitioned STs.
@@ -2153,6 +2243,76 @@ pub fn execute_differential_refresh(
     let (merge_count, strategy_label) = if let Some(result) = hash_merge_result {
         // A1-3b: HASH per-partition MERGE already executed above.
         result
+    } else if st.temporal_mode {
+        // ── CORR-1 (v0.49.0): Temporal (SCD Type 2) apply ───────────
+        // Rows are never physically deleted or overwritten: changed and
+        // deleted rows get their current version closed, and new values
+        // are inserted as fresh versions.
+        let t_mat_start = Instant::now();
+        let _ = Spi::run(&format!("DROP TABLE IF EXISTS __pgt_delta_{}", st.pgt_id)); // nosemgrep: rust.spi.run.dynamic-format — st.pgt_id is a plain i64, not user-supplied input.
+        let materialize_sql = format!(
+            "CREATE TEMP TABLE __pgt_delta_{pgt_id} ON COMMIT DROP AS \
+             SELECT * FROM {using_clause} AS d",
+            pgt_id = st.pgt_id,
+            using_clause = resolved.trigger_using_sql,
+        );
+        Spi::run(&materialize_sql).map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        let t_mat = t_mat_start.elapsed();
+
+        let quoted_table = format!(
+            "\"{}\".\"{}\"",
+            schema.replace('"', "\"\""),
+            name.replace('"', "\"\""),
+        );
+        let t_apply_start = Instant::now();
+        let (closed, aux_updated, inserted) = apply_temporal_changes(
+            st,
+            &quoted_table,
+            &format!("__pgt_delta_{}", st.pgt_id),
+            TemporalSource::Delta,
+        )?;
+        let t_apply = t_apply_start.elapsed();
+
+        pgrx::info!(
+            "[PGS_PROFILE] temporal: materialize={:.2}ms apply={:.2}ms \
+             closed={} aux_updated={} inserted={} for {}.{}",
+            t_mat.as_secs_f64() * 1000.0,
+            t_apply.as_secs_f64() * 1000.0,
+            closed,
+            aux_updated,
+            inserted,
+            schema,
+            name,
+        );
+
+        // ST-ST-2: Downstream consumers receive the logical delta.
+        if has_downstream_st_consumers(st.pgt_id) {
+            let user_cols = get_st_user_columns(st);
+            if let Err(e) = capture_delta_to_st_buffer(st, &user_cols) {
+                pgrx::warning!(
+                    "[pg_trickle] ST-ST: delta capture failed for {}.{}: {} \
+                     — marking downstream STs for reinit",
+                    schema,
+                    name,
+                    e,
+                );
+                if let Ok(downstream_ids) =
+                    crate::catalog::StDependency::get_downstream_pgt_ids(st.pgt_relid)
+                {
+                    for ds_id in &downstream_ids {
+                        if let Err(e2) = StreamTableMeta::mark_for_reinitialize(*ds_id) {
+                            pgrx::warning!(
+                                "[pg_trickle] ST-ST: failed to mark downstream ST {} for reinit: {}",
+                                ds_id,
+                                e2,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        (closed + aux_updated + inserted, "temporal")
     } else if use_delete_insert {
         // ── PH-D1: DELETE+INSERT path ───────────────────────────────
         // For small deltas against large tables, separate DELETE + INSERT
@@ -2558,8 +2718,11 @@ pub fn execute_differential_refresh(
     // Skip full-query reconciliation for recursive CTEs — it bypasses the
     // ivm_recursive_max_depth guard and would insert suppressed rows.
     let query_has_recursive_cte = dvm::query_has_recursive_cte(&st.defining_query).unwrap_or(false);
+    // CORR-1: Temporal STs skip it too — its DELETE would bypass the
+    // history table.
     let phantom_cleanup_count = if query_has_join
         && !query_has_recursive_cte
+        && !st.temporal_mode
         && !resolved.is_deduplicated
         && !st.has_keyless_source
         && st.st_partition_key.is_none()
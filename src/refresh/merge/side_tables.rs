@@ -0,0 +1,222 @@
+// Sub-module of src/refresh/merge — see mod.rs for overview.
+//
+// MINMAX-MS / ORDSET-HIST (v0.49.0): Aggregate side tables.
+//
+// A side table in the change buffer schema backs one top-level aggregate of
+// a DIFFERENTIAL stream table with a count per (group hash, value), the
+// primary key doubling as the ordered index:
+//
+//   - `minmax_{pgt_id}_{ordinal}`: MIN/MAX ordered multiset;
+//   - `hist_{pgt_id}_{ordinal}`: MODE / PERCENTILE_* value-count histogram;
+//   - `hist_{pgt_id}_{ordinal}_s{digits}`: PERCENTILE_CONT rounded histogram
+//     (values bucketed to `digits` significant digits, plus a `total`).
+//
+// Lifecycle:
+//
+//   1. FULL refresh (and reinitialize) rebuilds the side tables from source
+//      according to `pg_trickle.minmax_multiset` /
+//      `pg_trickle.ordered_set_histogram`, dropping any that are not wanted;
+//   2. DIFFERENTIAL refresh applies the cycle's child delta to the side
+//      tables before the delta query runs;
+//   3. the delta query reads new aggregate values from the side tables
+//      instead of re-aggregating the group (see `diff_aggregate`).
+#[allow(unused_imports)]
+use super::*;
+
+use crate::dvm::diff::quote_ident;
+use crate::dvm::operators::aggregate::{MinMaxMultisetSpec, OrderedSetHistogramSpec};
+
+/// Unquoted names of the side tables that currently exist for `pgt_id`.
+fn existing_side_tables(pgt_id: i64, change_schema: &str) -> Result<Vec<String>, PgTrickleError> {
+    let minmax = format!("minmax\\_{pgt_id}\\_%");
+    let hist = format!("hist\\_{pgt_id}\\_%");
+    Spi::connect(|client| {
+        let rows = client.select(
+            "SELECT c.relname::text FROM pg_catalog.pg_class c \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relkind = 'r' \
+               AND (c.relname LIKE $2 OR c.relname LIKE $3) \
+             ORDER BY 1",
+            None,
+            &[
+                change_schema.into(),
+                minmax.as_str().into(),
+                hist.as_str().into(),
+            ],
+        )?;
+        let mut names = Vec::new();
+        for row in rows {
+            if let Some(name) = row.get::<String>(1)? {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        PgTrickleError::SpiError(format!("aggregate side table lookup failed: {e}"))
+    })
+}
+
+/// Declared type of a stream table column, including a non-default
+/// collation so the side table orders values exactly like the aggregate.
+fn st_column_type(pgt_relid: pg_sys::Oid, column: &str) -> Option<String> {
+    Spi::get_one_with_args::<String>(
+        "SELECT format_type(a.atttypid, a.atttypmod) || \
+                CASE WHEN a.attcollation <> t.typcollation \
+                     THEN ' COLLATE ' || quote_ident(cn.nspname) || '.' || quote_ident(co.collname) \
+                     ELSE '' END \
+         FROM pg_catalog.pg_attribute a \
+         JOIN pg_catalog.pg_type t ON t.oid = a.atttypid \
+         LEFT JOIN pg_catalog.pg_collation co ON co.oid = a.attcollation \
+         LEFT JOIN pg_catalog.pg_namespace cn ON cn.oid = co.collnamespace \
+         WHERE a.attrelid = $1 AND a.attname = $2 AND NOT a.attisdropped",
+        &[pgt_relid.into(), column.into()],
+    )
+    .unwrap_or(None)
+}
+
+/// A side table to (re)create on FULL refresh.
+struct WantedSideTable {
+    name: String,
+    val_type: String,
+    /// Centroid sketches also accumulate the raw values of each bucket.
+    has_total: bool,
+    populate_sql: String,
+}
+
+/// The side tables `st` should have under the current GUC settings.
+fn wanted_side_tables(st: &StreamTableMeta) -> Vec<WantedSideTable> {
+    if st.refresh_mode != RefreshMode::Differential {
+        return Vec::new();
+    }
+    let mut wanted = Vec::new();
+    if crate::config::pg_trickle_minmax_multiset() {
+        for spec in crate::dvm::query_minmax_multiset_candidates(&st.defining_query) {
+            if let Some(val_type) = st_column_type(st.pgt_relid, &spec.alias) {
+                wanted.push(WantedSideTable {
+                    name: MinMaxMultisetSpec::table_name(st.pgt_id, spec.ordinal),
+                    val_type,
+                    has_total: false,
+                    populate_sql: spec.populate_sql,
+                });
+            }
+        }
+    }
+    if crate::config::pg_trickle_ordered_set_histogram() {
+        let sketch_digits = crate::config::pg_trickle_percentile_sketch_digits();
+        for spec in crate::dvm::query_ordered_set_histogram_candidates(&st.defining_query) {
+            let Some(val_type) = st_column_type(st.pgt_relid, &spec.alias) else {
+                continue;
+            };
+            let digits =
+                sketch_digits.filter(|_| spec.sketchable && val_type == "double precision");
+            wanted.push(WantedSideTable {
+                name: OrderedSetHistogramSpec::table_name(st.pgt_id, spec.ordinal, digits),
+                val_type,
+                has_total: digits.is_some(),
+                populate_sql: spec.populate_sql(digits),
+            });
+        }
+    }
+    wanted
+}
+
+/// Rebuild (or drop) the aggregate side tables of `st` after a FULL refresh.
+///
+/// Every side table wanted under the current GUC settings is freshly
+/// populated; any other side table of the stream table is dropped. When the
+/// set of side tables changes, cached delta templates are invalidated
+/// because they embed the side-table lookups.
+pub fn rebuild_agg_side_tables(st: &StreamTableMeta) -> Result<(), PgTrickleError> {
+    let change_schema = crate::config::pg_trickle_change_buffer_schema();
+    let existing = existing_side_tables(st.pgt_id, &change_schema)?;
+    let wanted = wanted_side_tables(st);
+
+    if existing.is_empty() && wanted.is_empty() {
+        return Ok(());
+    }
+
+    let qschema = quote_ident(&change_schema);
+    for name in &existing {
+        let drop_sql = format!("DROP TABLE IF EXISTS {qschema}.{}", quote_ident(name));
+        Spi::run(&drop_sql) // nosemgrep: rust.spi.run.dynamic-format — DDL cannot be parameterized; identifiers are quoted.
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+
+    let mut created = Vec::with_capacity(wanted.len());
+    for side in &wanted {
+        let table = format!("{qschema}.{}", quote_ident(&side.name));
+        let (total_col, total_list) = if side.has_total {
+            (", total DOUBLE PRECISION NOT NULL", ", total")
+        } else {
+            ("", "")
+        };
+        // The partial index keeps the per-refresh cleanup of exhausted
+        // values from scanning the whole side table.
+        let statements = [
+            format!(
+                "CREATE TABLE {table} (grp BIGINT NOT NULL, val {} NOT NULL, \
+                 cnt BIGINT NOT NULL{total_col}, PRIMARY KEY (grp, val))",
+                side.val_type
+            ),
+            format!("CREATE INDEX ON {table} (grp) WHERE cnt <= 0"),
+            format!(
+                "INSERT INTO {table} (grp, val, cnt{total_list}) {}",
+                side.populate_sql
+            ),
+        ];
+        for sql in &statements {
+            Spi::run(sql) // nosemgrep: rust.spi.run.dynamic-format — DDL/DML over quoted identifiers and the parsed defining query.
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        }
+        created.push(side.name.clone());
+    }
+    created.sort();
+
+    if existing != created {
+        crate::refresh::invalidate_merge_cache(st.pgt_id);
+        crate::template_cache::invalidate(st.pgt_id);
+        crate::shmem::bump_cache_generation();
+    }
+    Ok(())
+}
+
+/// Apply this cycle's source changes to the aggregate side tables of `st`.
+///
+/// Must run before the delta query of the same cycle, which reads the
+/// updated side tables. A no-op (one catalog lookup) when the stream table
+/// has no side tables.
+pub fn maintain_agg_side_tables(
+    st: &StreamTableMeta,
+    prev_frontier: &Frontier,
+    new_frontier: &Frontier,
+) -> Result<(), PgTrickleError> {
+    let change_schema = crate::config::pg_trickle_change_buffer_schema();
+    if existing_side_tables(st.pgt_id, &change_schema)?.is_empty() {
+        return Ok(());
+    }
+    let statements = dvm::generate_side_table_maintenance(
+        &st.defining_query,
+        prev_frontier,
+        new_frontier,
+        &st.pgt_schema,
+        &st.pgt_name,
+    )?;
+    for sql in &statements {
+        Spi::run(sql) // nosemgrep: rust.spi.run.dynamic-format — generated maintenance DML over the change buffers.
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Drop every aggregate side table of a stream table (DROP STREAM TABLE).
+pub fn drop_agg_side_tables(pgt_id: i64) -> Result<(), PgTrickleError> {
+    let change_schema = crate::config::pg_trickle_change_buffer_schema();
+    let qschema = quote_ident(&change_schema);
+    for name in existing_side_tables(pgt_id, &change_schema)? {
+        let drop_sql = format!("DROP TABLE IF EXISTS {qschema}.{}", quote_ident(&name));
+        Spi::run(&drop_sql) // nosemgrep: rust.spi.run.dynamic-format — DDL cannot be parameterized; identifiers are quoted.
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+    Ok(())
+}
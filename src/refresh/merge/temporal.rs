@@ -0,0 +1,351 @@
+// Sub-module of src/refresh/merge — see mod.rs for overview.
+//
+// CORR-1 (v0.49.0): History-preserving (SCD Type 2) apply for temporal
+// stream tables.
+//
+// The storage table of a temporal stream table holds only current versions,
+// so a plain SELECT returns what a non-temporal stream table would. Closed
+// versions are moved into `{change_schema}.history_{pgt_id}`, one row per
+// version with the visible columns as `jsonb`:
+//
+//   (__pgt_row_id, valid_from, valid_to, row_data)
+//
+// Deltas are applied in four statements against a materialized change set:
+//
+//   1. close-out — set `__pgt_valid_to = now()` on current versions that
+//      were deleted or whose visible columns changed;
+//   2. archive — move the closed versions from storage into the history
+//      table, keeping their `__pgt_valid_to` as `valid_to`;
+//   3. aux refresh — update auxiliary `__pgt_*` columns in place on current
+//      versions whose visible columns did not change (keeps aggregate
+//      bookkeeping correct without minting a new version);
+//   4. insert — add a new current version for every row that no longer has
+//      one, with `__pgt_valid_from = now()`.
+//
+// The same four statements serve differential refresh (source =
+// `__pgt_delta_{pgt_id}`, filtered on `__pgt_action`) and FULL refresh
+// (source = the complete recomputed result).
+#[allow(unused_imports)]
+use super::*;
+
+use crate::dvm::diff::quote_ident;
+
+/// Storage columns maintained by the temporal apply path and never copied
+/// from a change set.
+pub(crate) const TEMPORAL_SYSTEM_COLUMNS: [&str; 3] =
+    ["__pgt_row_id", "__pgt_valid_from", "__pgt_valid_to"];
+
+/// Which kind of change set the temporal statements read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TemporalSource {
+    /// A differential delta with an `__pgt_action` column (`'I'` / `'D'`).
+    /// Only rows whose `__pgt_row_id` appears in the delta are touched.
+    Delta,
+    /// A complete recomputation of the defining query. Every current
+    /// version is a candidate for close-out.
+    Full,
+}
+
+/// Whether a storage column is user-visible (versioned) rather than an
+/// auxiliary `__pgt_*` bookkeeping column.
+pub(crate) fn is_versioned_column(col: &str) -> bool {
+    !col.starts_with("__pgt_")
+}
+
+/// Split the columns to copy into (visible, auxiliary).
+pub(crate) fn split_temporal_columns(cols: &[String]) -> (Vec<String>, Vec<String>) {
+    cols.iter()
+        .filter(|c| !TEMPORAL_SYSTEM_COLUMNS.contains(&c.as_str()))
+        .cloned()
+        .partition(|c| is_versioned_column(c))
+}
+
+/// `d."col"` equality-with-NULLs predicate over `cols`, or `TRUE` when
+/// there are no columns to compare.
+fn not_distinct_clause(cols: &[String]) -> String {
+    if cols.is_empty() {
+        return "TRUE".to_string();
+    }
+    format!("NOT ({})", build_is_distinct_clause(cols))
+}
+
+/// Build `LATERAL (SELECT st."a", st."b") v` over the visible columns, so
+/// `to_jsonb(v)` yields the row object of a version aliased `st`.
+pub(crate) fn visible_row_projection<'a>(cols: impl Iterator<Item = &'a str>) -> String {
+    let select_list = cols
+        .map(|c| format!("st.{}", quote_ident(c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("LATERAL (SELECT {select_list}) v")
+}
+
+/// Build the close-out statement.
+///
+/// A current version is closed when its row was touched and no incoming
+/// row carries identical visible values — i.e. the row was deleted, or it
+/// was replaced by a row with different values. Closed versions get
+/// `__pgt_valid_to = now()` and are moved out of storage by
+/// [`build_temporal_archive_sql`].
+pub(crate) fn build_temporal_close_sql(
+    quoted_table: &str,
+    source: &str,
+    visible_cols: &[String],
+    kind: TemporalSource,
+) -> String {
+    let unchanged = not_distinct_clause(visible_cols);
+    match kind {
+        TemporalSource::Delta => format!(
+            "UPDATE {quoted_table} AS st SET __pgt_valid_to = now() \
+             WHERE st.__pgt_row_id IN (SELECT __pgt_row_id FROM {source}) \
+               AND NOT EXISTS (\
+                 SELECT 1 FROM {source} AS d \
+                 WHERE d.__pgt_row_id = st.__pgt_row_id \
+                   AND d.__pgt_action = 'I' \
+                   AND {unchanged})",
+        ),
+        TemporalSource::Full => format!(
+            "UPDATE {quoted_table} AS st SET __pgt_valid_to = now() \
+             WHERE NOT EXISTS (\
+                 SELECT 1 FROM {source} AS d \
+                 WHERE d.__pgt_row_id = st.__pgt_row_id \
+                   AND {unchanged})",
+        ),
+    }
+}
+
+/// Build the statement that moves closed versions (`__pgt_valid_to` set)
+/// from storage into `history`.
+///
+/// `delta_source` limits the search to the rows of a differential delta so
+/// the storage table is not scanned on every refresh.
+pub(crate) fn build_temporal_archive_sql(
+    quoted_table: &str,
+    history: &str,
+    visible_cols: &[String],
+    delta_source: Option<&str>,
+) -> String {
+    let scope = delta_source
+        .map(|source| format!(" AND st.__pgt_row_id IN (SELECT __pgt_row_id FROM {source})"))
+        .unwrap_or_default();
+    format!(
+        "WITH closed AS (\
+           DELETE FROM {quoted_table} AS st \
+           WHERE st.__pgt_valid_to IS NOT NULL{scope} RETURNING *) \
+         INSERT INTO {history} (__pgt_row_id, valid_from, valid_to, row_data) \
+         SELECT st.__pgt_row_id, st.__pgt_valid_from, st.__pgt_valid_to, to_jsonb(v) \
+         FROM closed AS st, {projection}",
+        projection = visible_row_projection(visible_cols.iter().map(String::as_str)),
+    )
+}
+
+/// Build the in-place auxiliary-column UPDATE, or `None` when the stream
+/// table has no auxiliary columns.
+///
+/// Runs after close-out, so every current version that still matches an
+/// incoming row has identical visible values; only its bookkeeping columns
+/// (e.g. `__pgt_count`) may need to move.
+pub(crate) fn build_temporal_aux_update_sql(
+    quoted_table: &str,
+    source: &str,
+    aux_cols: &[String],
+    kind: TemporalSource,
+) -> Option<String> {
+    if aux_cols.is_empty() {
+        return None;
+    }
+    let action_filter = match kind {
+        TemporalSource::Delta => "WHERE __pgt_action = 'I' ",
+        TemporalSource::Full => "",
+    };
+    Some(format!(
+        "UPDATE {quoted_table} AS st \
+         SET {set_clause} \
+         FROM (SELECT DISTINCT ON (__pgt_row_id) * FROM {source} \
+               {action_filter}ORDER BY __pgt_row_id) AS d \
+         WHERE st.__pgt_row_id = d.__pgt_row_id \
+           AND ({is_distinct})",
+        set_clause = format_update_set(aux_cols),
+        is_distinct = build_is_distinct_clause(aux_cols),
+    ))
+}
+
+/// Build the INSERT of new current versions.
+///
+/// Every incoming row whose `__pgt_row_id` has no current version in storage
+/// (new rows, and rows just closed by [`build_temporal_close_sql`]) gets a
+/// version starting at the transaction timestamp.
+pub(crate) fn build_temporal_insert_sql(
+    quoted_table: &str,
+    source: &str,
+    cols: &[String],
+    kind: TemporalSource,
+) -> String {
+    let col_list = format_col_list(cols);
+    let d_col_list = format_prefixed_col_list("d", cols);
+    let action_filter = match kind {
+        TemporalSource::Delta => "d.__pgt_action = 'I' AND ",
+        TemporalSource::Full => "",
+    };
+    format!(
+        "INSERT INTO {quoted_table} (__pgt_row_id, {col_list}, __pgt_valid_from) \
+         SELECT DISTINCT ON (d.__pgt_row_id) d.__pgt_row_id, {d_col_list}, now() \
+         FROM {source} AS d \
+         WHERE {action_filter}NOT EXISTS (\
+           SELECT 1 FROM {quoted_table} AS st \
+           WHERE st.__pgt_row_id = d.__pgt_row_id) \
+         ORDER BY d.__pgt_row_id",
+    )
+}
+
+/// Build the statement that closes every current version (pure TRUNCATE
+/// window on the only source). Archive them with
+/// [`build_temporal_archive_sql`].
+pub(crate) fn build_temporal_close_all_sql(quoted_table: &str) -> String {
+    format!("UPDATE {quoted_table} SET __pgt_valid_to = now()")
+}
+
+/// Quoted name of the history table of a temporal stream table.
+pub(crate) fn temporal_history_table(pgt_id: i64) -> String {
+    let change_schema = crate::config::pg_trickle_change_buffer_schema();
+    format!(
+        "{}.{}",
+        quote_ident(&change_schema),
+        quote_ident(&format!("history_{pgt_id}"))
+    )
+}
+
+/// Create the history table of a temporal stream table if it is missing
+/// (stream tables created before the table existed get it on first apply).
+/// Returns its quoted name.
+pub(crate) fn ensure_temporal_history_table(pgt_id: i64) -> Result<String, PgTrickleError> {
+    let history = temporal_history_table(pgt_id);
+    let exists = Spi::get_one_with_args::<bool>(
+        "SELECT to_regclass($1) IS NOT NULL",
+        &[history.as_str().into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+    .unwrap_or(false);
+    if !exists {
+        Spi::run(&format!(
+            "CREATE TABLE {history} (\
+               __pgt_row_id BIGINT NOT NULL, \
+               valid_from TIMESTAMPTZ NOT NULL, \
+               valid_to TIMESTAMPTZ NOT NULL, \
+               row_data JSONB NOT NULL)"
+        )) // nosemgrep: rust.spi.run.dynamic-format — DDL cannot be parameterized; history is derived from numeric pgt_id.
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        Spi::run(&format!(
+            "CREATE INDEX ON {history} (__pgt_row_id, valid_from)"
+        )) // nosemgrep: rust.spi.run.dynamic-format — DDL cannot be parameterized; history is derived from numeric pgt_id.
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+    }
+    Ok(history)
+}
+
+/// Drop the history table of a stream table (DROP STREAM TABLE).
+pub(crate) fn drop_temporal_history_table(pgt_id: i64) -> Result<(), PgTrickleError> {
+    let drop_sql = format!("DROP TABLE IF EXISTS {}", temporal_history_table(pgt_id));
+    Spi::run(&drop_sql) // nosemgrep: rust.spi.run.dynamic-format — DDL cannot be parameterized; identifiers are quoted.
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))
+}
+
+/// Columns present in both the stream table storage and the change-set
+/// temp table, in storage order, excluding the temporal system columns.
+fn temporal_apply_columns(
+    pgt_relid: pg_sys::Oid,
+    source: &str,
+) -> Result<Vec<String>, PgTrickleError> {
+    Spi::connect(|client| {
+        let rows = client
+            .select(
+                "SELECT a.attname::text FROM pg_attribute a \
+                 WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped \
+                   AND a.attname::text NOT IN \
+                       ('__pgt_row_id', '__pgt_valid_from', '__pgt_valid_to') \
+                   AND EXISTS (\
+                     SELECT 1 FROM pg_attribute s \
+                     WHERE s.attrelid = $2::regclass AND s.attname = a.attname \
+                       AND s.attnum > 0 AND NOT s.attisdropped) \
+                 ORDER BY a.attnum",
+                None,
+                &[pgt_relid.into(), source.into()],
+            )
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        let mut cols = Vec::new();
+        for row in rows {
+            if let Some(name) = row
+                .get::<String>(1)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+            {
+                cols.push(name);
+            }
+        }
+        Ok(cols)
+    })
+}
+
+fn run_counted(sql: &str, label: &str) -> Result<usize, PgTrickleError> {
+    Spi::connect_mut(|client| {
+        let result = client
+            .update(sql, None, &[])
+            .map_err(|e| PgTrickleError::SpiError(format!("[{label}] {e}")))?;
+        Ok::<usize, PgTrickleError>(result.len())
+    })
+}
+
+/// Apply a materialized change set to a temporal stream table.
+///
+/// `source` names a temp table holding `__pgt_row_id` plus the stream
+/// table's columns (and `__pgt_action` for [`TemporalSource::Delta`]).
+/// Returns `(closed, aux_updated, inserted)` row counts.
+pub(crate) fn apply_temporal_changes(
+    st: &StreamTableMeta,
+    quoted_table: &str,
+    source: &str,
+    kind: TemporalSource,
+) -> Result<(usize, usize, usize), PgTrickleError> {
+    let cols = temporal_apply_columns(st.pgt_relid, source)?;
+    let (visible, aux) = split_temporal_columns(&cols);
+    let history = ensure_temporal_history_table(st.pgt_id)?;
+
+    let closed = run_counted(
+        &build_temporal_close_sql(quoted_table, source, &visible, kind),
+        "TEMPORAL-CLOSE",
+    )?;
+    let delta_source = (kind == TemporalSource::Delta).then_some(source);
+    run_counted(
+        &build_temporal_archive_sql(quoted_table, &history, &visible, delta_source),
+        "TEMPORAL-ARCHIVE",
+    )?;
+    let aux_updated = match build_temporal_aux_update_sql(quoted_table, source, &aux, kind) {
+        Some(sql) => run_counted(&sql, "TEMPORAL-AUX")?,
+        None => 0,
+    };
+    let inserted = run_counted(
+        &build_temporal_insert_sql(quoted_table, source, &cols, kind),
+        "TEMPORAL-INSERT",
+    )?;
+    Ok((closed, aux_updated, inserted))
+}
+
+/// FULL refresh of a temporal stream table.
+///
+/// Instead of TRUNCATE + INSERT, the recomputed result is materialized and
+/// diffed against the current versions so unchanged rows keep their
+/// `__pgt_valid_from`. Returns `(inserted, closed)`.
+pub(crate) fn execute_temporal_full_refresh(
+    st: &StreamTableMeta,
+    quoted_table: &str,
+    insert_body: &str,
+) -> Result<(usize, usize), PgTrickleError> {
+    let source = format!("__pgt_full_{}", st.pgt_id);
+    let _ = Spi::run(&format!("DROP TABLE IF EXISTS {source}")); // nosemgrep: rust.spi.run.dynamic-format — source is derived from numeric pgt_id.
+    Spi::run(&format!(
+        "CREATE TEMP TABLE {source} ON COMMIT DROP AS {insert_body}"
+    ))
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+    let (closed, _, inserted) =
+        apply_temporal_changes(st, quoted_table, &source, TemporalSource::Full)?;
+    Ok((inserted, closed))
+}
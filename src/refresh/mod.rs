@@ -53,9 +53,11 @@ pub use codegen::{
     prewarm_merge_cache, set_fallback_leaf_oids,
 };
 pub(crate) use merge::compute_amplification_ratio;
+pub(crate) use merge::drop_temporal_history_table;
 pub use merge::{
-    execute_differential_refresh, execute_full_refresh, execute_no_data_refresh,
-    execute_topk_refresh, poll_foreign_table_sources_for_st, post_full_refresh_cleanup,
+    drop_agg_side_tables, execute_differential_refresh, execute_full_refresh,
+    execute_no_data_refresh, execute_topk_refresh, poll_foreign_table_sources_for_st,
+    post_full_refresh_cleanup, rebuild_agg_side_tables, sliding_window_boundary_crossed,
 };
 pub use orchestrator::{
     RefreshAction, determine_refresh_action, execute_reinitialize_refresh, validate_topk_metadata,
@@ -208,5 +210,47 @@ pub fn take_refresh_reason() -> Option<&'static str> {
     LAST_REFRESH_REASON.with(|r| r.borrow_mut().take())
 }
 
+// ── CYC-LATTICE: SCC recompute ──────────────────────────────────────────
+//
+// When source rows of a cyclic SCC are deleted or updated, derived rows can
+// keep supporting each other around the cycle. The scheduler then empties
+// every member with one FULL refresh that inserts nothing and iterates the
+// SCC from scratch. This is a full recompute of the SCC, not an incremental
+// delete-and-rederive.
+
+thread_local! {
+    static SCC_RECOMPUTE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// While alive, FULL refreshes in this thread empty the stream table instead
+/// of recomputing it. Used by the scheduler's SCC recompute pass.
+///
+/// The flag is cleared on drop, so an ERROR raised by a member's refresh
+/// (which unwinds through the scheduler) cannot leave later FULL refreshes
+/// in the same backend inserting nothing.
+#[must_use = "the SCC empty pass ends when the guard is dropped"]
+pub struct SccRecomputeGuard {
+    _private: (),
+}
+
+impl SccRecomputeGuard {
+    /// Start the SCC empty pass for this thread.
+    pub fn begin() -> Self {
+        SCC_RECOMPUTE.with(|c| c.set(true));
+        SccRecomputeGuard { _private: () }
+    }
+}
+
+impl Drop for SccRecomputeGuard {
+    fn drop(&mut self) {
+        SCC_RECOMPUTE.with(|c| c.set(false));
+    }
+}
+
+/// Whether FULL refreshes currently empty the stream table.
+pub(crate) fn scc_recompute_active() -> bool {
+    SCC_RECOMPUTE.with(|c| c.get())
+}
+
 #[cfg(test)]
 mod tests;
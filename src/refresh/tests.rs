@@ -16,6 +16,11 @@ use crate::refresh::merge::columns::{
     pg_quote_literal,
 };
 use crate::refresh::merge::delete::{build_hash_child_merge, should_warn_amplification};
+use crate::refresh::merge::temporal::{
+    TemporalSource, build_temporal_archive_sql, build_temporal_aux_update_sql,
+    build_temporal_close_all_sql, build_temporal_close_sql, build_temporal_insert_sql,
+    split_temporal_columns,
+};
 
 // ── Helper: build a minimal StreamTableMeta for testing ─────────
 
@@ -2591,3 +2596,126 @@ fn test_diff_cost_factors_ordering() {
             < QueryComplexityClass::JoinAggregate.diff_cost_factor()
     );
 }
+
+// ── CORR-1: temporal (SCD Type 2) apply builders ────────────────
+
+#[test]
+fn test_split_temporal_columns() {
+    let cols: Vec<String> = ["region", "__pgt_count", "total", "__pgt_valid_to"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let (visible, aux) = split_temporal_columns(&cols);
+    assert_eq!(visible, vec!["region".to_string(), "total".to_string()]);
+    assert_eq!(aux, vec!["__pgt_count".to_string()]);
+}
+
+const HISTORY: &str = "\"pgtrickle_changes\".\"history_7\"";
+
+#[test]
+fn test_temporal_close_sql_delta_stamps_valid_to() {
+    let cols = vec!["region".to_string(), "total".to_string()];
+    let sql = build_temporal_close_sql(
+        "\"public\".\"t\"",
+        "__pgt_delta_7",
+        &cols,
+        TemporalSource::Delta,
+    );
+    assert!(sql.starts_with("UPDATE \"public\".\"t\" AS st SET __pgt_valid_to = now()"));
+    assert!(sql.contains("st.__pgt_row_id IN (SELECT __pgt_row_id FROM __pgt_delta_7)"));
+    assert!(sql.contains("d.__pgt_action = 'I'"));
+    assert!(sql.contains("NOT (st.\"region\"::text IS DISTINCT FROM d.\"region\"::text"));
+}
+
+#[test]
+fn test_temporal_close_sql_full_considers_all_current_rows() {
+    let cols = vec!["v".to_string()];
+    let sql = build_temporal_close_sql("\"s\".\"t\"", "__pgt_full_1", &cols, TemporalSource::Full);
+    assert!(sql.contains("SET __pgt_valid_to = now()"));
+    assert!(!sql.contains("__pgt_action"));
+    assert!(!sql.contains("IN (SELECT __pgt_row_id"));
+    assert!(sql.contains("NOT EXISTS (SELECT 1 FROM __pgt_full_1 AS d"));
+}
+
+#[test]
+fn test_temporal_close_sql_without_visible_columns() {
+    let sql = build_temporal_close_sql("\"s\".\"t\"", "d_tab", &[], TemporalSource::Delta);
+    assert!(sql.contains("AND TRUE)"));
+}
+
+#[test]
+fn test_temporal_archive_sql_moves_closed_versions() {
+    let cols = vec!["region".to_string(), "total".to_string()];
+    let sql = build_temporal_archive_sql("\"public\".\"t\"", HISTORY, &cols, Some("__pgt_delta_7"));
+    assert!(sql.starts_with(
+        "WITH closed AS (DELETE FROM \"public\".\"t\" AS st WHERE st.__pgt_valid_to IS NOT NULL"
+    ));
+    assert!(sql.contains("AND st.__pgt_row_id IN (SELECT __pgt_row_id FROM __pgt_delta_7)"));
+    assert!(sql.contains("RETURNING *) INSERT INTO \"pgtrickle_changes\".\"history_7\""));
+    assert!(
+        sql.contains("SELECT st.__pgt_row_id, st.__pgt_valid_from, st.__pgt_valid_to, to_jsonb(v)")
+    );
+    assert!(sql.contains("FROM closed AS st, LATERAL (SELECT st.\"region\", st.\"total\") v"));
+
+    let full = build_temporal_archive_sql("\"s\".\"t\"", HISTORY, &cols, None);
+    assert!(full.contains("WHERE st.__pgt_valid_to IS NOT NULL RETURNING *"));
+}
+
+#[test]
+fn test_temporal_aux_update_sql() {
+    assert!(
+        build_temporal_aux_update_sql("\"s\".\"t\"", "d_tab", &[], TemporalSource::Delta).is_none()
+    );
+    let aux = vec!["__pgt_count".to_string()];
+    let sql = build_temporal_aux_update_sql("\"s\".\"t\"", "d_tab", &aux, TemporalSource::Delta)
+        .expect("aux columns present");
+    assert!(sql.contains("SET \"__pgt_count\" = d.\"__pgt_count\""));
+    assert!(sql.contains("WHERE __pgt_action = 'I' ORDER BY __pgt_row_id"));
+    assert!(!sql.contains("__pgt_valid_to"));
+}
+
+#[test]
+fn test_temporal_insert_sql_stamps_valid_from() {
+    let cols = vec!["region".to_string(), "__pgt_count".to_string()];
+    let sql = build_temporal_insert_sql("\"s\".\"t\"", "d_tab", &cols, TemporalSource::Delta);
+    assert!(sql.starts_with(
+        "INSERT INTO \"s\".\"t\" (__pgt_row_id, \"region\", \"__pgt_count\", __pgt_valid_from)"
+    ));
+    assert!(sql.contains("d.\"__pgt_count\", now()"));
+    assert!(sql.contains("WHERE d.__pgt_action = 'I' AND NOT EXISTS"));
+    assert!(!sql.contains("__pgt_valid_to"));
+    assert!(!sql.contains("ON CONFLICT"));
+
+    let full = build_temporal_insert_sql("\"s\".\"t\"", "f_tab", &cols, TemporalSource::Full);
+    assert!(full.contains("WHERE NOT EXISTS"));
+}
+
+#[test]
+fn test_temporal_close_all_sql() {
+    let sql = build_temporal_close_all_sql("\"s\".\"t\"");
+    assert_eq!(sql, "UPDATE \"s\".\"t\" SET __pgt_valid_to = now()");
+}
+
+// ── CYC-LATTICE: SCC recompute guard ───────────────────────────────
+
+#[test]
+fn test_scc_recompute_guard_clears_on_drop() {
+    assert!(!scc_recompute_active());
+    {
+        let _guard = SccRecomputeGuard::begin();
+        assert!(scc_recompute_active());
+    }
+    assert!(!scc_recompute_active());
+}
+
+#[test]
+fn test_scc_recompute_guard_clears_on_unwind() {
+    // A PostgreSQL ERROR inside a member refresh unwinds as a panic.
+    let result = std::panic::catch_unwind(|| {
+        let _guard = SccRecomputeGuard::begin();
+        assert!(scc_recompute_active());
+        panic!("refresh failed");
+    });
+    assert!(result.is_err());
+    assert!(!scc_recompute_active());
+}
@@ -1387,9 +1387,25 @@ fn execute_worker_cyclic_scc(job: &SchedulerJob) -> RefreshOutcome {
     // #536: Use holdback-aware watermark for dynamic workers.
     let tick_watermark: Option<String> = compute_worker_tick_watermark();
 
-    let mut prev_row_counts: HashMap<i64, i64> = member_ids
+    // CYC-LATTICE: retractions invalidate rows the cycle derived from the
+    // removed source rows — empty the members and recompute from scratch.
+    if scc_has_retractions(member_ids) {
+        log!(
+            "pg_trickle refresh worker: SCC sources retracted rows — recomputing {} members (job {})",
+            member_ids.len(),
+            job.job_id,
+        );
+        let subtxn = SubTransaction::begin();
+        if !empty_scc_members(member_ids, tick_watermark.as_deref()) {
+            subtxn.rollback();
+            return RefreshOutcome::RetryableFailure;
+        }
+        subtxn.commit();
+    }
+
+    let mut prev_fingerprints: HashMap<i64, (i64, i64)> = member_ids
         .iter()
-        .map(|&id| (id, get_st_row_count(id).unwrap_or(0)))
+        .map(|&id| (id, get_st_fingerprint(id).unwrap_or((0, 0))))
         .collect();
 
     for iteration in 0..max_iter {
@@ -1452,10 +1468,10 @@ fn execute_worker_cyclic_scc(job: &SchedulerJob) -> RefreshOutcome {
 
         let mut total_changes: i64 = 0;
         for &pgt_id in member_ids {
-            let new_count = get_st_row_count(pgt_id).unwrap_or(0);
-            let old_count = prev_row_counts.get(&pgt_id).copied().unwrap_or(0);
-            total_changes += (new_count - old_count).abs();
-            prev_row_counts.insert(pgt_id, new_count);
+            let new_fp = get_st_fingerprint(pgt_id).unwrap_or((0, 0));
+            let old_fp = prev_fingerprints.get(&pgt_id).copied().unwrap_or((0, 0));
+            total_changes += fingerprint_changes(old_fp, new_fp);
+            prev_fingerprints.insert(pgt_id, new_fp);
         }
 
         if total_changes == 0 {
@@ -2521,6 +2537,13 @@ pub extern "C-unwind" fn pg_trickle_scheduler_main(_arg: pg_sys::Datum) {
     // ticks where that source's change buffer has exceeded the alert threshold.
     let mut backpressure_cycles: HashMap<u32, i32> = HashMap::new();
 
+    // CDC-HOLD (v0.49.0): Whether the "deferring refreshes" message has been
+    // logged for the current hold period, so it is emitted once per pause.
+    let mut cdc_hold_logged = false;
+
+    // WAL-STREAM (v0.49.0): Timestamp of the last WAL receiver liveness check.
+    let mut last_wal_receiver_check_ms: u64 = 0;
+
     // DB-5: Timestamp for daily history retention cleanup.
     let mut last_history_cleanup_ms: u64 = 0;
     const HISTORY_CLEANUP_INTERVAL_MS: u64 = 24 * 60 * 60 * 1000; // 24 hours
@@ -2762,6 +2785,19 @@ pub extern "C-unwind" fn pg_trickle_scheduler_main(_arg: pg_sys::Datum) {
             monitor::check_slot_health_and_alert();
         }));
 
+        // WAL-STREAM (v0.49.0): (Re)start the streaming receiver for the
+        // shared slot when it has members but no active consumer.
+        if config::pg_trickle_wal_receiver()
+            && now_ms.saturating_sub(last_wal_receiver_check_ms) >= 5_000
+        {
+            last_wal_receiver_check_ms = now_ms;
+            BackgroundWorker::transaction(AssertUnwindSafe(|| {
+                if let Err(e) = wal_decoder::receiver::ensure_wal_receiver(&db_name) {
+                    log!("pg_trickle: WAL receiver check failed: {}", e);
+                }
+            }));
+        }
+
         // SCAL-1 (v0.31.0): Back-pressure detection — check change buffer sizes
         // and emit change_buffer_backpressure alerts when buffers are persistently
         // large across multiple consecutive refresh cycles.
@@ -3040,6 +3076,23 @@ pub extern "C-unwind" fn pg_trickle_scheduler_main(_arg: pg_sys::Datum) {
             }));
         }
 
+        // CDC-HOLD (v0.49.0): While CDC is paused in 'hold' capture mode the
+        // triggers (and the WAL decoder above) keep filling the change
+        // buffers, but no refreshes are dispatched.  Frontiers stay put, so
+        // the first tick after un-pausing consumes the held changes
+        // differentially.
+        if config::pg_trickle_cdc_hold_active() {
+            if !cdc_hold_logged {
+                log!("pg_trickle: CDC paused in 'hold' mode — deferring refreshes");
+                cdc_hold_logged = true;
+            }
+            continue;
+        }
+        if cdc_hold_logged {
+            log!("pg_trickle: CDC hold lifted — resuming refreshes");
+            cdc_hold_logged = false;
+        }
+
         // Collect jobs to spawn (populated inside the transaction, spawned after).
         let mut pending_spawns: Vec<(String, i64)> = Vec::new();
 
@@ -4885,6 +4938,12 @@ fn has_table_source_changes(st: &StreamTableMeta) -> bool {
         );
     }
 
+    // SLIDE-1: Sliding-window filters (`ts > now() - interval '1 hour'`)
+    // change without source DML; wake at expiry boundaries.
+    if refresh::sliding_window_boundary_crossed(st) {
+        return true;
+    }
+
     let change_schema = config::pg_trickle_change_buffer_schema();
     let source_oids = get_source_oids_for_st(st.pgt_id);
 
@@ -5009,18 +5068,112 @@ fn has_stream_table_source_changes(st: &StreamTableMeta) -> bool {
 
 // ── Fixpoint Iteration for Cyclic SCCs (CYC-5) ───────────────────────────
 
-/// Query the current number of rows in a stream table directly from the DB.
+/// Query the current row count and an order-independent content hash of a
+/// stream table directly from the DB.
 ///
 /// Used by [`iterate_to_fixpoint`] to compute per-pass deltas for convergence
-/// detection without relying on CDC change buffers.
-fn get_st_row_count(pgt_id: i64) -> Option<i64> {
+/// detection without relying on CDC change buffers. CYC-LATTICE: the hash
+/// catches passes that change values without changing the row count, as
+/// MIN/MAX/BOOL_OR/BIT_OR members of a cycle do while they tighten.
+fn get_st_fingerprint(pgt_id: i64) -> Option<(i64, i64)> {
     let st = load_st_by_id(pgt_id)?;
     let sql = format!(
-        "SELECT count(*)::bigint FROM \"{}\".\"{}\"",
+        "SELECT count(*)::bigint, COALESCE(sum(hashtext(t::text)::bigint), 0)::bigint \
+         FROM \"{}\".\"{}\" t",
         st.pgt_schema.replace('"', "\"\""),
         st.pgt_name.replace('"', "\"\""),
     );
-    Spi::get_one::<i64>(&sql).ok().flatten()
+    match Spi::get_two::<i64, i64>(&sql) {
+        Ok((Some(rows), Some(hash))) => Some((rows, hash)),
+        _ => None,
+    }
+}
+
+/// Number of changes between two fingerprints: the row-count difference, or
+/// one when only the content changed.
+fn fingerprint_changes(old: (i64, i64), new: (i64, i64)) -> i64 {
+    (new.0 - old.0).abs().max(i64::from(old.1 != new.1))
+}
+
+/// CYC-LATTICE: Returns `true` if a source feeding the SCC from outside has
+/// unconsumed DELETE, UPDATE or TRUNCATE changes.
+///
+/// Fixed-point iteration only adds derived rows (or tightens lattice
+/// aggregates), so a retraction requires recomputing the SCC from scratch.
+fn scc_has_retractions(member_ids: &[i64]) -> bool {
+    let change_schema = config::pg_trickle_change_buffer_schema();
+    let mut arms: Vec<String> = Vec::new();
+
+    for &pgt_id in member_ids {
+        let Some(st) = load_st_by_id(pgt_id) else {
+            continue;
+        };
+        let frontier = st.frontier.clone().unwrap_or_default();
+        let deps = crate::catalog::StDependency::get_for_st(pgt_id).unwrap_or_default();
+        for dep in &deps {
+            match dep.source_type.as_str() {
+                "TABLE" | "FOREIGN_TABLE" => {
+                    let buf =
+                        crate::cdc::buffer_qualified_name_for_oid(&change_schema, dep.source_relid);
+                    let lsn = frontier.get_lsn(dep.source_relid.to_u32());
+                    arms.push(format!(
+                        "SELECT 1 FROM {buf} WHERE action <> 'I' AND lsn > '{lsn}'::pg_lsn"
+                    ));
+                }
+                "STREAM_TABLE" => {
+                    let Some(upstream_id) =
+                        crate::catalog::StreamTableMeta::pgt_id_for_relid(dep.source_relid)
+                    else {
+                        continue;
+                    };
+                    if member_ids.contains(&upstream_id)
+                        || !crate::cdc::has_st_change_buffer(upstream_id, &change_schema)
+                    {
+                        continue;
+                    }
+                    let lsn = frontier.get_st_lsn(upstream_id);
+                    arms.push(format!(
+                        "SELECT 1 FROM {change_schema}.changes_pgt_{upstream_id} \
+                         WHERE action <> 'I' AND lsn > '{lsn}'::pg_lsn"
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if arms.is_empty() {
+        return false;
+    }
+    let sql = format!("SELECT EXISTS({})", arms.join(" UNION ALL "));
+    Spi::get_one::<bool>(&sql) // nosemgrep: rust.spi.query.dynamic-format
+        .unwrap_or(Some(false))
+        .unwrap_or(false)
+}
+
+/// CYC-LATTICE: Empty pass of an SCC recompute.
+///
+/// Empties every member with a FULL refresh that inserts nothing, so rows
+/// derived only through each other around the cycle are gone before the
+/// fixed-point loop recomputes the SCC from its sources. Returns `false` if
+/// a member failed; the caller's sub-transaction is then rolled back.
+fn empty_scc_members(member_ids: &[i64], tick_watermark: Option<&str>) -> bool {
+    let _recompute = refresh::SccRecomputeGuard::begin();
+    let mut ok = true;
+    for &pgt_id in member_ids {
+        let Some(st) = load_st_by_id(pgt_id) else {
+            continue;
+        };
+        if st.status != StStatus::Active && st.status != StStatus::Initializing {
+            continue;
+        }
+        let outcome = execute_scheduled_refresh(&st, RefreshAction::Full, tick_watermark, None);
+        if !matches!(outcome, RefreshOutcome::Success) {
+            ok = false;
+            break;
+        }
+    }
+    ok
 }
 
 /// Iterate a cyclic SCC to a fixed point.
@@ -5087,15 +5240,35 @@ fn iterate_to_fixpoint(
         }
     }
 
-    // Seed per-member row counts from the current DB state.
+    // CYC-LATTICE: retractions invalidate rows the cycle derived from the
+    // removed source rows — empty the members and recompute from scratch.
+    if scc_has_retractions(&member_ids) {
+        log!(
+            "pg_trickle: SCC sources retracted rows — recomputing [{}]",
+            member_names.join(", "),
+        );
+        let subtxn = SubTransaction::begin();
+        if !empty_scc_members(&member_ids, tick_watermark) {
+            subtxn.rollback();
+            for &pgt_id in &member_ids {
+                let retry = retry_states.entry(pgt_id).or_default();
+                retry.record_failure(retry_policy, now_ms);
+            }
+            return;
+        }
+        subtxn.commit();
+    }
+
+    // Seed per-member fingerprints from the current DB state.
     //
-    // Convergence is detected by comparing each member's count(*) before
-    // and after each pass.  Counts are always read OUTSIDE sub-transactions
-    // (after subtxn.commit()) so that pgrx's nested Spi::get_one context
-    // sees the fully committed state and not a potentially stale snapshot.
-    let mut prev_row_counts: HashMap<i64, i64> = member_ids
+    // Convergence is detected by comparing each member's row count and
+    // content hash before and after each pass.  Fingerprints are always
+    // read OUTSIDE sub-transactions (after subtxn.commit()) so that pgrx's
+    // nested Spi::get_two context sees the fully committed state and not a
+    // potentially stale snapshot.
+    let mut prev_fingerprints: HashMap<i64, (i64, i64)> = member_ids
         .iter()
-        .map(|&id| (id, get_st_row_count(id).unwrap_or(0)))
+        .map(|&id| (id, get_st_fingerprint(id).unwrap_or((0, 0))))
         .collect();
 
     for iteration in 0..max_iter {
@@ -5172,7 +5345,7 @@ fn iterate_to_fixpoint(
 
             subtxn.commit();
         }
-        // subtxn is committed; now read row counts in the outer transaction
+        // subtxn is committed; now read fingerprints in the outer transaction
         // where the full sub-transaction contents are visible.
 
         if !any_refreshed {
@@ -5182,10 +5355,10 @@ fn iterate_to_fixpoint(
 
         let mut total_changes: i64 = 0;
         for &pgt_id in &member_ids {
-            let new_count = get_st_row_count(pgt_id).unwrap_or(0);
-            let old_count = prev_row_counts.get(&pgt_id).copied().unwrap_or(0);
-            total_changes += (new_count - old_count).abs();
-            prev_row_counts.insert(pgt_id, new_count);
+            let new_fp = get_st_fingerprint(pgt_id).unwrap_or((0, 0));
+            let old_fp = prev_fingerprints.get(&pgt_id).copied().unwrap_or((0, 0));
+            total_changes += fingerprint_changes(old_fp, new_fp);
+            prev_fingerprints.insert(pgt_id, new_fp);
         }
 
         if total_changes == 0 {
@@ -5898,8 +6071,16 @@ fn execute_scheduled_refresh(
                         Err(e) => Err(e),
                     }
                 } else {
+                    // WAL-STREAM: streamed sources advance only as far as
+                    // the receiver has flushed.
+                    let mut diff_positions = slot_positions.clone();
+                    wal_decoder::receiver::cap_to_flushed_lsn(
+                        st.pgt_id,
+                        &mut diff_positions,
+                        &prev_frontier,
+                    );
                     let mut new_frontier =
-                        version::compute_new_frontier(&slot_positions, &data_ts_frontier);
+                        version::compute_new_frontier(&diff_positions, &data_ts_frontier);
                     augment_frontier(&mut new_frontier);
 
                     match refresh::execute_differential_refresh(st, &prev_frontier, &new_frontier) {
@@ -7439,4 +7620,23 @@ mod tests {
         // burst = max(1*3/2=1, 1+1=2) = 2
         assert_eq!(compute_per_db_quota(1, 4, 10, 0), 2);
     }
+
+    // ── CYC-LATTICE: fixpoint convergence fingerprints ──────────────────
+
+    #[test]
+    fn test_fingerprint_changes_row_count_delta() {
+        assert_eq!(fingerprint_changes((10, 7), (13, 9)), 3);
+        assert_eq!(fingerprint_changes((10, 7), (8, 9)), 2);
+    }
+
+    #[test]
+    fn test_fingerprint_changes_value_only() {
+        // A MIN tightening rewrites a value without changing the row count.
+        assert_eq!(fingerprint_changes((10, 7), (10, 9)), 1);
+    }
+
+    #[test]
+    fn test_fingerprint_changes_converged() {
+        assert_eq!(fingerprint_changes((10, 7), (10, 7)), 0);
+    }
 }
@@ -30,6 +30,11 @@ pub struct Frontier {
     pub sources: HashMap<String, SourceVersion>,
     /// The overall data timestamp for this frontier (ISO 8601).
     pub data_timestamp: Option<String>,
+    /// SLIDE-1: Transaction timestamp of the refresh that stored this
+    /// frontier. Stamped by the catalog on write; sliding-window filters
+    /// use it as the previous clock when computing expiry deltas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<String>,
 }
 
 /// Version information for a single source table.
@@ -359,6 +364,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_frontier_clock_optional_in_json() {
+        // Frontiers written before SLIDE-1 carry no clock.
+        let legacy: Frontier =
+            serde_json::from_str(r#"{"sources":{},"data_timestamp":null}"#).unwrap();
+        assert!(legacy.clock.is_none());
+        assert!(!serde_json::to_string(&legacy).unwrap().contains("clock"));
+
+        let stamped: Frontier = serde_json::from_str(
+            r#"{"sources":{},"data_timestamp":null,"clock":"2026-02-17 10:00:00+00"}"#,
+        )
+        .unwrap();
+        assert_eq!(stamped.clock.as_deref(), Some("2026-02-17 10:00:00+00"));
+    }
+
     #[test]
     fn test_canonical_data_timestamp_alignment() {
         let period = 96u64;
@@ -8,11 +8,25 @@
 //! # Architecture
 //!
 //! The WAL decoder uses a **polling** approach via SPI:
-//! - Calls `pg_logical_slot_get_changes()` during the scheduler tick
-//! - Decodes `pgoutput` protocol messages into typed buffer table rows
+//! - Calls `pg_logical_slot_get_binary_changes()` during the scheduler tick
+//! - Decodes `pgoutput` protocol messages (see [`pgoutput`]) into typed
+//!   buffer table rows, dispatching each change by relation OID
 //! - Writes changes to the same `pgtrickle_changes.changes_<oid>` tables
 //!   used by trigger-based CDC
 //!
+//! Publication filtering happens inside `pgoutput`, so a slot only emits
+//! changes for the publications named in the poll.  With
+//! `pg_trickle.wal_shared_slot = on`, all WAL sources of a database share a
+//! single slot and one poll per tick feeds every source's buffer.
+//!
+//! With `pg_trickle.wal_receiver = on` the shared slot is not polled at all:
+//! a per-database background worker (see [`receiver`]) streams it over a
+//! replication connection and writes changes as they arrive.
+//!
+//! Slots created before v0.49.0 use the `test_decoding` plugin; they keep
+//! working through the legacy text parser until the source is transitioned
+//! again.
+//!
 //! # Transition Lifecycle
 //!
 //! ```text
@@ -41,16 +55,73 @@ use crate::config;
 use crate::error::PgTrickleError;
 use crate::monitor;
 
+mod pgoutput;
+pub(crate) mod receiver;
+
+use pgoutput::{PgOutputMessage, RelationMessage};
+
 // ── Naming Conventions ─────────────────────────────────────────────────────
 
+/// Prefix of the per-database slot used when `pg_trickle.wal_shared_slot`
+/// is enabled.
+const SHARED_SLOT_PREFIX: &str = "pgtrickle_shared_";
+
 /// Replication slot name for a source table: `pgtrickle_<oid>`.
+///
+/// WAL-PGO (v0.49.0): when `pg_trickle.wal_shared_slot` is on, new
+/// transitions use the database-wide shared slot instead.  Sources that
+/// already have a slot keep it — use [`slot_name_in_use`] for those.
 pub fn slot_name_for_source(source_oid: pg_sys::Oid) -> String {
+    if config::pg_trickle_wal_shared_slot() {
+        return shared_slot_name();
+    }
+    per_source_slot_name(source_oid)
+}
+
+/// Dedicated per-source slot name, used for sources whose catalog row has
+/// no recorded slot (WAL sources transitioned before v0.49.0).
+fn per_source_slot_name(source_oid: pg_sys::Oid) -> String {
     // CITUS-4: Use stable_name so slot names survive OID reassignment.
     let stable = crate::citus::stable_name_for_oid(source_oid)
         .unwrap_or_else(|_| source_oid.to_u32().to_string());
     format!("pgtrickle_{}", stable)
 }
 
+/// Name of the shared slot for the current database:
+/// `pgtrickle_shared_<database oid>`.
+///
+/// Slot names are cluster-wide, so the database OID keeps the shared slots
+/// of different databases apart.
+pub fn shared_slot_name() -> String {
+    let db_oid = Spi::get_one::<i64>(
+        "SELECT oid::bigint FROM pg_database WHERE datname = current_database()",
+    )
+    .ok()
+    .flatten()
+    .unwrap_or(0);
+    format!("{}{}", SHARED_SLOT_PREFIX, db_oid)
+}
+
+/// Whether `slot_name` is a shared (multi-source) slot.
+pub fn is_shared_slot(slot_name: &str) -> bool {
+    slot_name.starts_with(SHARED_SLOT_PREFIX)
+}
+
+/// Replication slot currently recorded for a source in the catalog.
+///
+/// Falls back to the dedicated per-source slot name when the catalog has no
+/// slot (WAL sources transitioned before v0.49.0 recorded none).
+pub fn slot_name_in_use(source_oid: pg_sys::Oid) -> String {
+    Spi::get_one_with_args::<String>(
+        "SELECT slot_name FROM pgtrickle.pgt_dependencies \
+         WHERE source_relid = $1 AND slot_name IS NOT NULL LIMIT 1",
+        &[source_oid.into()],
+    )
+    .ok()
+    .flatten()
+    .unwrap_or_else(|| per_source_slot_name(source_oid))
+}
+
 /// Publication name for a source table: `pgtrickle_cdc_<stable_name>`.
 pub fn publication_name_for_source(source_oid: pg_sys::Oid) -> String {
     // CITUS-4: Use stable_name so publication names survive OID reassignment.
@@ -316,7 +387,8 @@ fn create_replication_slot_internal(slot_name: &str) -> Result<String, PgTrickle
     let c_slot_name = CString::new(slot_name)
         .map_err(|e| PgTrickleError::ReplicationSlotError(format!("Invalid slot name: {}", e)))?;
     // STAB-5: Use compile-time CStr literal — no NUL bytes possible, no runtime allocation.
-    let c_plugin = c"test_decoding";
+    // WAL-PGO: new slots use the built-in pgoutput plugin (binary protocol).
+    let c_plugin = c"pgoutput";
 
     // SAFETY: Calling PostgreSQL C API functions for replication slot management.
     // These are the same functions called by pg_create_logical_replication_slot(),
@@ -438,6 +510,37 @@ pub fn drop_replication_slot(slot_name: &str) -> Result<(), PgTrickleError> {
     Ok(())
 }
 
+/// Drop a source's replication slot unless other sources still share it.
+///
+/// Per-source slots are always dropped.  A shared slot is kept while any
+/// other source is in WAL or TRANSITIONING mode on it.
+pub fn release_source_slot(source_oid: pg_sys::Oid, slot_name: &str) -> Result<(), PgTrickleError> {
+    if is_shared_slot(slot_name) {
+        let still_used = Spi::get_one_with_args::<bool>(
+            "SELECT EXISTS(SELECT 1 FROM pgtrickle.pgt_dependencies \
+             WHERE slot_name = $1 AND source_relid <> $2 \
+               AND cdc_mode IN ('WAL', 'TRANSITIONING'))",
+            &[slot_name.into(), source_oid.into()],
+        )
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+        .unwrap_or(false);
+        if still_used {
+            return Ok(());
+        }
+    }
+    drop_replication_slot(slot_name)
+}
+
+/// Output plugin of an existing slot (`pgoutput` or legacy `test_decoding`).
+fn slot_plugin(slot_name: &str) -> Result<Option<String>, PgTrickleError> {
+    Spi::get_one_with_args::<String>(
+        "SELECT plugin::text FROM pg_replication_slots \
+         WHERE slot_name = $1 AND database = current_database()",
+        &[slot_name.into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))
+}
+
 /// Get the confirmed flush LSN for a replication slot.
 ///
 /// Returns the LSN up to which the slot consumer has confirmed processing.
@@ -519,7 +622,11 @@ const MAX_CHANGES_PER_POLL: i64 = 10_000;
 /// right after slot creation or on a loaded test machine.
 const MAX_CONSECUTIVE_WAL_ERRORS: u32 = 20;
 
-/// Poll WAL changes from a replication slot and write them to the buffer table.
+/// Poll WAL changes from a legacy `test_decoding` slot and write them to
+/// the buffer table.
+///
+/// Slots created since v0.49.0 use `pgoutput` and are polled by
+/// [`poll_pgoutput_changes`] instead.
 ///
 /// Uses `pg_logical_slot_get_changes()` with the `test_decoding` plugin to
 /// retrieve decoded WAL changes. Each change is parsed and inserted into
@@ -626,6 +733,237 @@ pub fn poll_wal_changes(
     Ok((count, last_lsn))
 }
 
+/// Per-source decoding metadata for the `pgoutput` path.
+pub(crate) struct DecodeTarget {
+    pub source_oid: pg_sys::Oid,
+    pub pk_columns: Vec<String>,
+    pub columns: Vec<(String, String)>,
+}
+
+/// Outcome of one `pgoutput` slot poll.
+pub(crate) struct SlotPollOutcome {
+    /// Number of DML changes written to change buffers.
+    pub changes: i64,
+    /// LSN of the last message consumed, if any.
+    pub last_lsn: Option<String>,
+    /// Sources whose `Relation` message no longer matches the tracked
+    /// column set; their changes in this batch were not written.
+    pub schema_mismatches: Vec<u32>,
+}
+
+/// Poll a `pgoutput` slot and dispatch decoded changes by relation OID.
+///
+/// Calls `pg_logical_slot_get_binary_changes()` with protocol version 1 and
+/// the given publications, so `pgoutput` only emits changes for published
+/// tables.  Each `Relation` message is cached for the duration of the poll
+/// (the plugin re-sends it on every call) and used to map tuple values to
+/// column names.  Changes for relations not in `targets` are skipped —
+/// that only happens when a publication still lists a table whose stream
+/// tables were dropped.
+///
+/// A `Relation` message whose column set no longer matches the tracked
+/// columns marks that source as mismatched instead of failing the whole
+/// poll, so one altered table cannot stall the other sources of a shared
+/// slot.  The caller must flag the mismatched sources for reinit before it
+/// records the new slot position, since their changes were not written.
+pub(crate) fn poll_pgoutput_changes(
+    slot_name: &str,
+    publication_names: &[String],
+    targets: &std::collections::HashMap<u32, DecodeTarget>,
+    change_schema: &str,
+) -> Result<SlotPollOutcome, PgTrickleError> {
+    let max_changes_per_poll = crate::config::pg_trickle_wal_max_changes_per_poll();
+    let pubs = pgoutput::publication_names_option(publication_names);
+
+    let mut outcome = SlotPollOutcome {
+        changes: 0,
+        last_lsn: None,
+        schema_mismatches: Vec::new(),
+    };
+    let mut relations: std::collections::HashMap<u32, RelationMessage> =
+        std::collections::HashMap::new();
+
+    Spi::connect(|client| {
+        let result = client
+            .select(
+                "SELECT lsn::text, data \
+                 FROM pg_logical_slot_get_binary_changes(\
+                     $1, NULL, $2::int, \
+                     'proto_version', $3, \
+                     'publication_names', $4\
+                 )",
+                None,
+                &[
+                    slot_name.into(),
+                    max_changes_per_poll.into(),
+                    pgoutput::PROTO_VERSION.into(),
+                    pubs.as_str().into(),
+                ],
+            )
+            .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+
+        for row in result {
+            let lsn = row
+                .get::<String>(1)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                .unwrap_or_default();
+            let data = row
+                .get::<Vec<u8>>(2)
+                .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+                .unwrap_or_default();
+
+            let msg = pgoutput::decode_message(&data)
+                .map_err(|e| PgTrickleError::WalTransitionError(e.to_string()))?;
+
+            apply_pgoutput_message(
+                msg,
+                &lsn,
+                targets,
+                &mut relations,
+                &mut outcome,
+                change_schema,
+            )?;
+
+            outcome.last_lsn = Some(lsn);
+        }
+
+        Ok::<(), PgTrickleError>(())
+    })?;
+
+    Ok(outcome)
+}
+
+/// Write one decoded `pgoutput` message to the change buffers.
+///
+/// `Relation` messages are cached in `relations` and checked against the
+/// tracked column set; DML for tracked, non-mismatched relations is written
+/// with `lsn` as the change LSN.  Shared by the SPI poll and the streaming
+/// [`receiver`].
+pub(crate) fn apply_pgoutput_message(
+    msg: PgOutputMessage,
+    lsn: &str,
+    targets: &std::collections::HashMap<u32, DecodeTarget>,
+    relations: &mut std::collections::HashMap<u32, RelationMessage>,
+    outcome: &mut SlotPollOutcome,
+    change_schema: &str,
+) -> Result<(), PgTrickleError> {
+    match msg {
+        PgOutputMessage::Relation(rel) => {
+            if let Some(target) = targets.get(&rel.relid) {
+                let decoded: std::collections::HashMap<String, String> = rel
+                    .columns
+                    .iter()
+                    .map(|c| (c.name.clone(), String::new()))
+                    .collect();
+                if detect_schema_mismatch(&decoded, &target.columns)
+                    && !outcome.schema_mismatches.contains(&rel.relid)
+                {
+                    outcome.schema_mismatches.push(rel.relid);
+                }
+            }
+            relations.insert(rel.relid, rel);
+        }
+        PgOutputMessage::Insert { relid, new } => {
+            if let Some((target, rel)) = dispatch_target(relid, targets, relations, outcome)? {
+                let new_vals = pgoutput::tuple_to_map(rel, &new, None)
+                    .map_err(|e| PgTrickleError::WalTransitionError(e.to_string()))?;
+                write_change_values(
+                    target.source_oid.to_u32(),
+                    lsn,
+                    'I',
+                    &new_vals,
+                    &std::collections::HashMap::new(),
+                    change_schema,
+                    &target.pk_columns,
+                    &target.columns,
+                )?;
+                outcome.changes += 1;
+            }
+        }
+        PgOutputMessage::Update { relid, old, new } => {
+            if let Some((target, rel)) = dispatch_target(relid, targets, relations, outcome)? {
+                let old_vals = match old {
+                    Some(ref old) => pgoutput::tuple_to_map(rel, old, None)
+                        .map_err(|e| PgTrickleError::WalTransitionError(e.to_string()))?,
+                    None => std::collections::HashMap::new(),
+                };
+                let new_vals = pgoutput::tuple_to_map(rel, &new, Some(&old_vals))
+                    .map_err(|e| PgTrickleError::WalTransitionError(e.to_string()))?;
+                write_change_values(
+                    target.source_oid.to_u32(),
+                    lsn,
+                    'U',
+                    &new_vals,
+                    &old_vals,
+                    change_schema,
+                    &target.pk_columns,
+                    &target.columns,
+                )?;
+                outcome.changes += 1;
+            }
+        }
+        PgOutputMessage::Delete { relid, old } => {
+            if let Some((target, rel)) = dispatch_target(relid, targets, relations, outcome)? {
+                let old_vals = pgoutput::tuple_to_map(rel, &old, None)
+                    .map_err(|e| PgTrickleError::WalTransitionError(e.to_string()))?;
+                write_change_values(
+                    target.source_oid.to_u32(),
+                    lsn,
+                    'D',
+                    &old_vals,
+                    &std::collections::HashMap::new(),
+                    change_schema,
+                    &target.pk_columns,
+                    &target.columns,
+                )?;
+                outcome.changes += 1;
+            }
+        }
+        PgOutputMessage::Truncate { relids, .. } => {
+            for relid in relids {
+                if let Some(target) = targets.get(&relid) {
+                    mark_downstream_for_reinit(target.source_oid, "TRUNCATE detected")?;
+                    outcome.changes += 1;
+                }
+            }
+        }
+        PgOutputMessage::Begin { .. }
+        | PgOutputMessage::Commit { .. }
+        | PgOutputMessage::Origin { .. }
+        | PgOutputMessage::Type { .. }
+        | PgOutputMessage::Message { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// Resolve the target and cached relation for a DML message, skipping
+/// untracked and schema-mismatched relations.
+///
+/// `pgoutput` always sends a `Relation` message before the first change of
+/// a relation in a decoding session, so a tracked relation without one is a
+/// protocol error rather than something to skip silently.
+fn dispatch_target<'a>(
+    relid: u32,
+    targets: &'a std::collections::HashMap<u32, DecodeTarget>,
+    relations: &'a std::collections::HashMap<u32, RelationMessage>,
+    outcome: &SlotPollOutcome,
+) -> Result<Option<(&'a DecodeTarget, &'a RelationMessage)>, PgTrickleError> {
+    if outcome.schema_mismatches.contains(&relid) {
+        return Ok(None);
+    }
+    let Some(target) = targets.get(&relid) else {
+        return Ok(None);
+    };
+    let rel = relations.get(&relid).ok_or_else(|| {
+        PgTrickleError::WalTransitionError(format!(
+            "pgoutput change for relation OID {} arrived before its Relation message",
+            relid
+        ))
+    })?;
+    Ok(Some((target, rel)))
+}
+
 /// Parse the action type from a pgoutput data string.
 ///
 /// The `pgoutput` plugin with `proto_version = 1` outputs text lines like:
@@ -750,7 +1088,7 @@ fn write_decoded_change(
 ) -> Result<(), PgTrickleError> {
     // Handle TRUNCATE specially — mark downstream STs for reinit
     if *action == 'T' {
-        mark_downstream_for_reinit(pg_sys::Oid::from(source_oid))?;
+        mark_downstream_for_reinit(pg_sys::Oid::from(source_oid), "TRUNCATE detected")?;
         return Ok(());
     }
 
@@ -765,6 +1103,36 @@ fn write_decoded_change(
         std::collections::HashMap::new()
     };
 
+    write_change_values(
+        source_oid,
+        lsn,
+        *action,
+        &parsed,
+        &old_parsed,
+        change_schema,
+        pk_columns,
+        columns,
+    )
+}
+
+/// Write one decoded change (already mapped to column values) to the
+/// source's change buffer.
+///
+/// `parsed` holds the new tuple for INSERT/UPDATE and the old tuple for
+/// DELETE; `old_parsed` holds the old tuple of an UPDATE.  Columns missing
+/// from the maps are written as SQL NULL.  Shared by the legacy
+/// `test_decoding` parser and the native `pgoutput` decoder.
+#[allow(clippy::too_many_arguments)]
+fn write_change_values(
+    source_oid: u32,
+    lsn: &str,
+    action: char,
+    parsed: &std::collections::HashMap<String, String>,
+    old_parsed: &std::collections::HashMap<String, String>,
+    change_schema: &str,
+    pk_columns: &[String],
+    columns: &[(String, String)],
+) -> Result<(), PgTrickleError> {
     let has_pk = !pk_columns.is_empty();
 
     // A42-13: Build a fully parameterized INSERT.
@@ -799,7 +1167,7 @@ fn write_decoded_change(
     // pk_hash column (uses subsequent $N params for PK column values)
     if has_pk {
         col_names.push("pk_hash".to_string());
-        let pk_hash_expr = build_pk_hash_parameterized(pk_columns, &parsed, &mut param_values);
+        let pk_hash_expr = build_pk_hash_parameterized(pk_columns, parsed, &mut param_values);
         placeholders.push(pk_hash_expr);
     }
 
@@ -812,7 +1180,7 @@ fn write_decoded_change(
     // A44-10: UPDATE is decomposed into D-row (OLD values) + I-row (NEW values)
     // emitted as a single multi-row VALUES INSERT for atomicity (single SPI call,
     // single heap operation). This avoids the risk of a crash between D and I rows.
-    if *action == 'U' {
+    if action == 'U' {
         // A44-10 atomicity: single multi-row INSERT for D+I pair.
         // D-row: OLD values with action='D'. I-row: NEW values with action='I'.
         // D-row must appear before I-row (change_id ordering invariant).
@@ -825,8 +1193,8 @@ fn write_decoded_change(
         assert_valid_identifier(&buf_name, "change buffer name")?;
         assert_valid_identifier(change_schema, "change schema")?;
 
-        let d_pk_hash_expr = build_pk_hash_from_values(pk_columns, &old_parsed);
-        let i_pk_hash_expr = build_pk_hash_from_values(pk_columns, &parsed);
+        let d_pk_hash_expr = build_pk_hash_from_values(pk_columns, old_parsed);
+        let i_pk_hash_expr = build_pk_hash_from_values(pk_columns, parsed);
 
         // col_names: INSERT column list (shared for both rows).
         let mut col_names_u: Vec<String> = vec!["lsn".to_string(), "action".to_string()];
@@ -1048,10 +1416,14 @@ fn build_pk_hash_from_values(
 
 /// Mark all downstream stream tables for reinitialization.
 ///
-/// Called when a TRUNCATE is detected via WAL decoding. Since TRUNCATE
-/// invalidates all existing change tracking, downstream STs need a
-/// full refresh to resync.
-fn mark_downstream_for_reinit(source_oid: pg_sys::Oid) -> Result<(), PgTrickleError> {
+/// Called when a TRUNCATE is detected via WAL decoding, or when a source's
+/// changes were skipped because its decoded schema no longer matches the
+/// tracked columns. Either way the change buffer no longer describes the
+/// source, so downstream STs need a full refresh to resync.
+pub(crate) fn mark_downstream_for_reinit(
+    source_oid: pg_sys::Oid,
+    reason: &str,
+) -> Result<(), PgTrickleError> {
     Spi::run_with_args(
         "UPDATE pgtrickle.pgt_stream_tables \
          SET needs_reinit = true, updated_at = now() \
@@ -1064,7 +1436,8 @@ fn mark_downstream_for_reinit(source_oid: pg_sys::Oid) -> Result<(), PgTrickleEr
     .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
 
     warning!(
-        "pg_trickle: TRUNCATE detected on source OID {} via WAL — downstream STs marked for reinit",
+        "pg_trickle: {} on source OID {} via WAL — downstream STs marked for reinit",
+        reason,
         source_oid.to_u32()
     );
 
@@ -1113,7 +1486,7 @@ pub fn check_and_complete_transition(
         }
     }
 
-    let default_slot = slot_name_for_source(source_oid);
+    let default_slot = per_source_slot_name(source_oid);
     let slot_name = dep.slot_name.as_deref().unwrap_or(&default_slot);
 
     // Check if the decoder has caught up
@@ -1212,12 +1585,14 @@ fn complete_wal_transition(
     change_schema: &str,
 ) -> Result<(), PgTrickleError> {
     let oid_u32 = source_oid.to_u32();
+    let slot_name = slot_name_in_use(source_oid);
 
     // Step 1: Drop the CDC trigger (WAL decoder now covers all changes)
     cdc::drop_change_trigger(source_oid, change_schema)?;
 
-    // Step 2: Update catalog to WAL mode
-    StDependency::update_cdc_mode_for_source(source_oid, CdcMode::Wal, None, None)?;
+    // Step 2: Update catalog to WAL mode.  The slot name is kept so that
+    // sources sharing a slot can be found in steady-state WAL mode.
+    StDependency::update_cdc_mode_for_source(source_oid, CdcMode::Wal, Some(&slot_name), None)?;
 
     info!(
         "pg_trickle: completed WAL transition for source OID {} — trigger dropped, WAL active",
@@ -1225,7 +1600,6 @@ fn complete_wal_transition(
     );
 
     // Emit NOTIFY for transition completion
-    let slot_name = slot_name_for_source(source_oid);
     monitor::emit_cdc_transition_notify(
         source_oid,
         CdcMode::Transitioning,
@@ -1246,10 +1620,11 @@ pub fn abort_wal_transition(
     change_schema: &str,
 ) -> Result<(), PgTrickleError> {
     let oid_u32 = source_oid.to_u32();
-    let slot_name = slot_name_for_source(source_oid);
+    let slot_name = slot_name_in_use(source_oid);
 
-    // Step 1: Drop the replication slot (stops WAL retention)
-    if let Err(e) = drop_replication_slot(&slot_name) {
+    // Step 1: Drop the replication slot (stops WAL retention).  A shared
+    // slot is kept while other sources still decode from it.
+    if let Err(e) = release_source_slot(source_oid, &slot_name) {
         warning!(
             "pg_trickle: failed to drop replication slot {} during abort: {}",
             slot_name,
@@ -1371,8 +1746,8 @@ pub fn force_source_to_trigger(
         None
     };
 
-    let slot_name = slot_name_for_source(source_oid);
-    if let Err(e) = drop_replication_slot(&slot_name) {
+    let slot_name = slot_name_in_use(source_oid);
+    if let Err(e) = release_source_slot(source_oid, &slot_name) {
         warning!(
             "pg_trickle: failed to drop replication slot {} while forcing trigger CDC: {}",
             slot_name,
@@ -1457,6 +1832,9 @@ pub fn advance_wal_transitions_phase1(change_schema: &str) -> Result<Phase1Resul
     let mut processed_sources = std::collections::HashSet::new();
     let mut pending_slots = Vec::new();
     let mut pending_aborts: Vec<PendingAbort> = Vec::new();
+    // WAL-PGO: shared slots are polled once per tick for all their sources.
+    let mut slot_results: std::collections::HashMap<String, SlotPollResult> =
+        std::collections::HashMap::new();
 
     for dep in &all_deps {
         // Only process TABLE sources (not STREAM_TABLE or VIEW)
@@ -1543,18 +1921,16 @@ pub fn advance_wal_transitions_phase1(change_schema: &str) -> Result<Phase1Resul
             }
             CdcMode::Transitioning => {
                 // Poll WAL changes (both trigger and WAL are active).
-                // Use catch_unwind for the same reason as the Wal branch:
-                // a missing/invalid slot causes a PG ERROR → Rust panic.
-                let poll_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    poll_source_changes(dep, change_schema)
-                }));
-                let poll_err = match poll_result {
-                    Ok(Ok(())) => None,
-                    Ok(Err(e)) => Some(e.to_string()),
-                    Err(_panic) => {
-                        Some("PG error during TRANSITIONING WAL poll (likely missing slot)".into())
-                    }
-                };
+                // poll_source_guarded uses catch_unwind for the same reason as
+                // the Wal branch: a missing/invalid slot causes a PG ERROR →
+                // Rust panic.
+                let poll_err = poll_source_guarded(
+                    dep,
+                    &all_deps,
+                    change_schema,
+                    &mut slot_results,
+                    "PG error during TRANSITIONING WAL poll (likely missing slot)",
+                );
 
                 if let Some(err_msg) = poll_err {
                     let count = bump_wal_error_count(source_key);
@@ -1604,16 +1980,16 @@ pub fn advance_wal_transitions_phase1(change_schema: &str) -> Result<Phase1Resul
             }
             CdcMode::Wal => {
                 // Poll WAL changes (steady-state WAL mode).
-                // Use catch_unwind because a missing/invalid slot causes a
-                // PG ERROR → Rust panic that would bypass the error counter.
-                let poll_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    poll_source_changes(dep, change_schema)
-                }));
-                let poll_err = match poll_result {
-                    Ok(Ok(())) => None,
-                    Ok(Err(e)) => Some(e.to_string()),
-                    Err(_panic) => Some("PG error during WAL poll (likely missing slot)".into()),
-                };
+                // poll_source_guarded uses catch_unwind because a
+                // missing/invalid slot causes a PG ERROR → Rust panic that
+                // would bypass the error counter.
+                let poll_err = poll_source_guarded(
+                    dep,
+                    &all_deps,
+                    change_schema,
+                    &mut slot_results,
+                    "PG error during WAL poll (likely missing slot)",
+                );
 
                 if let Some(err_msg) = poll_err {
                     let count = bump_wal_error_count(source_key);
@@ -1758,7 +2134,7 @@ pub fn finish_wal_transition(
         // Drop the replication slot that was already created in Phase 2.
         // If this fails (e.g. slot was already cleaned up), log and continue —
         // the transition abort is still the correct outcome.
-        if let Err(e) = crate::wal_decoder::drop_replication_slot(slot_name) {
+        if let Err(e) = release_source_slot(source_oid, slot_name) {
             log!(
                 "pg_trickle: A41-3: could not drop slot '{}' during abort (non-fatal): {}",
                 slot_name,
@@ -1798,7 +2174,11 @@ pub fn finish_wal_transition(
     // transition promptly.  During TRANSITIONING both triggers and the
     // WAL decoder are active, so any changes between the slot's creation
     // LSN and now are already captured by triggers — no data is lost.
-    if let Err(e) = advance_slot_to_current(slot_name) {
+    // A shared slot is never pre-advanced: it still holds undecoded
+    // changes for the other sources on it.
+    if is_shared_slot(slot_name) {
+        // Drained by the next tick's poll instead.
+    } else if let Err(e) = advance_slot_to_current(slot_name) {
         log!(
             "pg_trickle: could not pre-advance slot '{}' (non-fatal): {}",
             slot_name,
@@ -1969,13 +2349,83 @@ fn emit_auto_cdc_stuck_log(dep: &StDependency) {
     );
 }
 
+/// Result of polling one slot this tick: the sources whose relation schema
+/// no longer matches, or the error message when the poll itself failed.
+type SlotPollResult = Result<Vec<u32>, String>;
+
+/// Poll WAL changes for a source, reusing this tick's result when the
+/// source shares its slot with sources that were already polled.
+///
+/// A shared slot is drained once per tick and feeds every source on it, so
+/// later sources on the same slot must not poll again (they would just see
+/// an empty slot) but must see the outcome of the shared poll.
+fn poll_source_guarded(
+    dep: &StDependency,
+    all_deps: &[StDependency],
+    change_schema: &str,
+    slot_results: &mut std::collections::HashMap<String, SlotPollResult>,
+    panic_msg: &str,
+) -> Option<String> {
+    let slot_name = dep
+        .slot_name
+        .clone()
+        .unwrap_or_else(|| per_source_slot_name(dep.source_relid));
+
+    // WAL-STREAM: the receiver worker holds this slot; polling it would
+    // fail with "replication slot is active".
+    if receiver::streams_slot(&slot_name) {
+        return None;
+    }
+
+    let result = match slot_results.get(&slot_name) {
+        Some(cached) => cached.clone(),
+        None => {
+            let poll_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                poll_source_changes(dep, all_deps, change_schema)
+            }));
+            let result: SlotPollResult = match poll_result {
+                Ok(Ok(mismatches)) => Ok(mismatches),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_panic) => Err(panic_msg.to_string()),
+            };
+            if is_shared_slot(&slot_name) {
+                slot_results.insert(slot_name, result.clone());
+            }
+            result
+        }
+    };
+
+    match result {
+        Ok(mismatches) if mismatches.contains(&dep.source_relid.to_u32()) => Some(format!(
+            "Schema change detected for source OID {} — \
+             decoded columns don't match expected columns",
+            dep.source_relid.to_u32()
+        )),
+        Ok(_) => None,
+        Err(e) => Some(e),
+    }
+}
+
 /// Poll WAL changes for a source that's in TRANSITIONING or WAL mode.
-fn poll_source_changes(dep: &StDependency, change_schema: &str) -> Result<(), PgTrickleError> {
+///
+/// Returns the source OIDs whose decoded schema no longer matches the
+/// tracked columns (only reported by the `pgoutput` path; the legacy
+/// `test_decoding` path returns an error instead).
+fn poll_source_changes(
+    dep: &StDependency,
+    all_deps: &[StDependency],
+    change_schema: &str,
+) -> Result<Vec<u32>, PgTrickleError> {
     let slot_name = match &dep.slot_name {
         Some(name) => name.clone(),
-        None => slot_name_for_source(dep.source_relid),
+        None => per_source_slot_name(dep.source_relid),
     };
 
+    if slot_plugin(&slot_name)?.as_deref() == Some("pgoutput") {
+        return poll_pgoutput_slot(&slot_name, dep, all_deps, change_schema);
+    }
+
+    // Legacy test_decoding slot (created before v0.49.0).
     // Resolve qualified source table name for filtering test_decoding output
     let source_table_name = cdc::get_qualified_table_name(dep.source_relid)?;
 
@@ -2012,7 +2462,79 @@ fn poll_source_changes(dep: &StDependency, change_schema: &str) -> Result<(), Pg
         );
     }
 
-    Ok(())
+    Ok(Vec::new())
+}
+
+/// Poll a `pgoutput` slot on behalf of every source that uses it.
+///
+/// For a per-source slot that is just `dep`'s source; for a shared slot it
+/// is every TABLE source in WAL or TRANSITIONING mode recorded with the
+/// same slot name.  Each member's publication is passed to `pgoutput`, and
+/// each member's `decoder_confirmed_lsn` is advanced after the poll.
+fn poll_pgoutput_slot(
+    slot_name: &str,
+    dep: &StDependency,
+    all_deps: &[StDependency],
+    change_schema: &str,
+) -> Result<Vec<u32>, PgTrickleError> {
+    let mut members: Vec<&StDependency> = vec![dep];
+    if is_shared_slot(slot_name) {
+        for other in all_deps {
+            if other.source_type == "TABLE"
+                && matches!(other.cdc_mode, CdcMode::Wal | CdcMode::Transitioning)
+                && other.slot_name.as_deref() == Some(slot_name)
+                && !members.iter().any(|m| m.source_relid == other.source_relid)
+            {
+                members.push(other);
+            }
+        }
+    }
+
+    let mut targets = std::collections::HashMap::new();
+    let mut publications = Vec::with_capacity(members.len());
+    for member in &members {
+        targets.insert(
+            member.source_relid.to_u32(),
+            DecodeTarget {
+                source_oid: member.source_relid,
+                pk_columns: cdc::resolve_pk_columns(member.source_relid)?,
+                columns: cdc::resolve_source_column_defs(member.source_relid)?,
+            },
+        );
+        publications.push(publication_name_for_source(member.source_relid));
+    }
+
+    let outcome = poll_pgoutput_changes(slot_name, &publications, &targets, change_schema)?;
+
+    // The slot has moved past the skipped changes of mismatched sources, so
+    // their stream tables are recomputed; flag them in the same transaction
+    // that records the new position.
+    for relid in &outcome.schema_mismatches {
+        mark_downstream_for_reinit(pg_sys::Oid::from(*relid), "schema change detected")?;
+    }
+
+    if let Some(ref lsn) = outcome.last_lsn {
+        for member in &members {
+            StDependency::update_cdc_mode_for_source(
+                member.source_relid,
+                member.cdc_mode,
+                member.slot_name.as_deref(),
+                Some(lsn),
+            )?;
+        }
+    }
+
+    if outcome.changes > 0 {
+        log!(
+            "pg_trickle: polled {} WAL changes from slot '{}' for {} source(s) (last LSN: {})",
+            outcome.changes,
+            slot_name,
+            members.len(),
+            outcome.last_lsn.as_deref().unwrap_or("none")
+        );
+    }
+
+    Ok(outcome.schema_mismatches)
 }
 
 /// Check health of a WAL decoder for a source in WAL mode.
@@ -2026,7 +2548,7 @@ pub fn check_decoder_health(
     pgt_id: i64,
     change_schema: &str,
 ) -> Result<(), PgTrickleError> {
-    let slot_name = slot_name_for_source(source_oid);
+    let slot_name = slot_name_in_use(source_oid);
 
     // Check wal_level hasn't been changed (takes effect after restart)
     let wal_level = Spi::get_one::<String>("SELECT current_setting('wal_level')")
@@ -0,0 +1,911 @@
+//! Streaming WAL receiver for the shared replication slot.
+//!
+//! With `pg_trickle.wal_receiver = on`, the scheduler starts one
+//! `pg_trickle wal receiver` background worker per database.  The worker
+//! opens a replication connection through PostgreSQL's own
+//! `libpqwalreceiver` module, starts logical replication from the
+//! database's shared `pgoutput` slot and writes each change to the change
+//! buffers as it arrives — with the same decoding and buffer writes as the
+//! SPI poll ([`super::apply_pgoutput_message`]).  Per-source slots keep
+//! being polled by the scheduler.
+//!
+//! # Batching and feedback
+//!
+//! Changes are written in a worker-local transaction that is committed at a
+//! source-transaction boundary once `pg_trickle.wal_receiver_batch_size`
+//! changes have accumulated, or as soon as the stream is drained.  The end
+//! LSN of the last applied commit is stored as the members'
+//! `decoder_confirmed_lsn` in that same transaction, and only after it has
+//! committed is the LSN reported to the server as flushed.  A restarted
+//! worker requests streaming from the stored LSN, so a crash between the
+//! commit and the status update cannot duplicate changes.
+//!
+//! Any error while streaming aborts the open batch and ends the worker;
+//! the scheduler starts a new one, which resumes from the stored LSN.
+//!
+//! # Frontiers
+//!
+//! Every change is stamped with the commit LSN of its source transaction
+//! (the `Begin` message's final LSN), so the whole transaction lands on one
+//! side of any frontier.  Capture is asynchronous, so a differential
+//! refresh must not advance a streamed source's frontier beyond what the
+//! receiver has flushed; [`cap_to_flushed_lsn`] applies that cap.
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, Instant};
+
+use pgrx::bgworkers::*;
+use pgrx::prelude::*;
+
+use super::pgoutput::{self, PgOutputMessage, RelationMessage};
+use super::{
+    DecodeTarget, SlotPollOutcome, abort_wal_transition, apply_pgoutput_message,
+    drop_replication_slot, is_shared_slot, mark_downstream_for_reinit, publication_name_for_source,
+    shared_slot_name,
+};
+use crate::catalog::{CdcMode, StDependency};
+use crate::cdc;
+use crate::config;
+use crate::error::PgTrickleError;
+use crate::version::{self, Frontier};
+
+/// `application_name` of the replication connection, as shown in
+/// `pg_stat_replication`.
+const APPLICATION_NAME: &CStr = c"pg_trickle wal receiver";
+
+/// Interval between standby status updates while nothing is flushed
+/// (PostgreSQL's default `wal_receiver_status_interval`).
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Minimum interval between catalog writes of an idle flush position.
+///
+/// Recording the LSN writes WAL itself, which the walsender answers with
+/// another keepalive; without a floor the two would chase each other.
+const IDLE_CONFIRM_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Interval between checks for sources joining or leaving the slot.
+const PLAN_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether `slot_name` is consumed by the WAL receiver instead of the
+/// scheduler's SPI poll.
+pub(crate) fn streams_slot(slot_name: &str) -> bool {
+    config::pg_trickle_wal_receiver() && is_shared_slot(slot_name)
+}
+
+/// Start the database's WAL receiver if its shared slot has members but no
+/// active consumer.
+///
+/// Called periodically from the scheduler loop inside a transaction.  The
+/// worker exits on its own once the slot has no members or the GUC is
+/// turned off, so this only ever (re)starts it.
+pub(crate) fn ensure_wal_receiver(db_name: &str) -> Result<(), PgTrickleError> {
+    let slot_name = shared_slot_name();
+    let needs_worker = Spi::get_one_with_args::<bool>(
+        "SELECT EXISTS(SELECT 1 FROM pgtrickle.pgt_dependencies \
+                       WHERE slot_name = $1 AND cdc_mode IN ('WAL', 'TRANSITIONING')) \
+            AND EXISTS(SELECT 1 FROM pg_replication_slots \
+                       WHERE slot_name = $1 AND database = current_database() \
+                         AND NOT active)",
+        &[slot_name.as_str().into()],
+    )
+    .map_err(|e| PgTrickleError::SpiError(e.to_string()))?
+    .unwrap_or(false);
+
+    if !needs_worker {
+        return Ok(());
+    }
+
+    match BackgroundWorkerBuilder::new("pg_trickle wal receiver")
+        .set_function("pg_trickle_wal_receiver_main")
+        .set_library("pg_trickle")
+        .enable_spi_access()
+        // Pass the database name via bgw_extra (max 128 bytes).
+        .set_extra(db_name)
+        // No restart_time — the scheduler restarts it on its next check.
+        .set_restart_time(None)
+        .load_dynamic()
+    {
+        Ok(_) => {
+            log!(
+                "pg_trickle: started WAL receiver for slot '{}' in database '{}'",
+                slot_name,
+                db_name
+            );
+            Ok(())
+        }
+        Err(_) => Err(PgTrickleError::ReplicationSlotError(format!(
+            "could not start WAL receiver for slot '{}' (max_worker_processes reached?)",
+            slot_name
+        ))),
+    }
+}
+
+/// Cap the frontier positions of streamed sources at the LSN the receiver
+/// has flushed.
+///
+/// A member's `decoder_confirmed_lsn` is the end of the last applied commit
+/// record.  Every source transaction whose commit record starts before it
+/// is already in the change buffer, but the next one may start exactly
+/// there, so a differential refresh may advance to just below that LSN.  A
+/// position is never moved below the previous frontier.
+pub(crate) fn cap_to_flushed_lsn(
+    pgt_id: i64,
+    positions: &mut HashMap<u32, String>,
+    prev_frontier: &Frontier,
+) {
+    if !config::pg_trickle_wal_receiver() {
+        return;
+    }
+
+    let deps = match StDependency::get_for_st(pgt_id) {
+        Ok(deps) => deps,
+        Err(e) => {
+            // Without the catalog we cannot tell which sources are streamed;
+            // holding every source is the safe choice.
+            log!(
+                "pg_trickle: could not load dependencies of pgt_id {} ({}); holding frontier",
+                pgt_id,
+                e
+            );
+            for (oid, lsn) in positions.iter_mut() {
+                *lsn = prev_frontier.get_lsn(*oid);
+            }
+            return;
+        }
+    };
+
+    for dep in deps {
+        if dep.cdc_mode != CdcMode::Wal || !dep.slot_name.as_deref().is_some_and(is_shared_slot) {
+            continue;
+        }
+        let oid = dep.source_relid.to_u32();
+        if let Some(lsn) = positions.get_mut(&oid) {
+            *lsn = capped_position(
+                lsn,
+                dep.decoder_confirmed_lsn.as_deref(),
+                &prev_frontier.get_lsn(oid),
+            );
+        }
+    }
+}
+
+/// `min(position, flushed - 1)`, but never below `prev`.  A source with no
+/// flushed LSN yet stays at `prev`.
+fn capped_position(position: &str, flushed: Option<&str>, prev: &str) -> String {
+    let Some(flushed) = flushed else {
+        return prev.to_string();
+    };
+    let applied = version::u64_to_lsn(version::lsn_to_u64(flushed).saturating_sub(1));
+    let capped = version::lsn_min(position, &applied);
+    if version::lsn_gt(prev, capped) {
+        prev.to_string()
+    } else {
+        capped.to_string()
+    }
+}
+
+// ── Replication protocol ───────────────────────────────────────────────────
+
+/// One `CopyData` message received from the walsender.
+#[derive(Debug, PartialEq, Eq)]
+enum CopyData<'a> {
+    /// `w`: one `pgoutput` message.  Its `dataStart` is the position of
+    /// the individual WAL record and is not used: changes are stamped with
+    /// their transaction's commit LSN instead.
+    XLogData { wal_end: u64, payload: &'a [u8] },
+    /// `k`: primary keepalive.
+    Keepalive { wal_end: u64, reply_requested: bool },
+}
+
+/// Big-endian `u64` at `at`; the caller has checked the length.
+fn read_u64(buf: &[u8], at: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[at..at + 8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Parse an XLogData (`w`) or primary keepalive (`k`) message.
+fn parse_copy_data(buf: &[u8]) -> Result<CopyData<'_>, PgTrickleError> {
+    match buf.first() {
+        // 'w', dataStart, walEnd, sendTime, payload
+        Some(b'w') if buf.len() >= 25 => Ok(CopyData::XLogData {
+            wal_end: read_u64(buf, 9),
+            payload: &buf[25..],
+        }),
+        // 'k', walEnd, sendTime, replyRequested
+        Some(b'k') if buf.len() >= 18 => Ok(CopyData::Keepalive {
+            wal_end: read_u64(buf, 1),
+            reply_requested: buf[17] != 0,
+        }),
+        Some(kind) => Err(PgTrickleError::WalTransitionError(format!(
+            "unexpected replication message '{}' ({} bytes)",
+            *kind as char,
+            buf.len()
+        ))),
+        None => Err(PgTrickleError::WalTransitionError(
+            "empty replication message".to_string(),
+        )),
+    }
+}
+
+/// Standby status update (`r`).  The applied position is reported equal to
+/// the flushed one: changes are applied when they reach the buffer.
+fn standby_status_update(written: u64, flushed: u64, now: i64, reply: bool) -> [u8; 34] {
+    let mut msg = [0u8; 34];
+    msg[0] = b'r';
+    msg[1..9].copy_from_slice(&written.to_be_bytes());
+    msg[9..17].copy_from_slice(&flushed.to_be_bytes());
+    msg[17..25].copy_from_slice(&flushed.to_be_bytes());
+    msg[25..33].copy_from_slice(&now.to_be_bytes());
+    msg[33] = u8::from(reply);
+    msg
+}
+
+/// Quote a conninfo value (`'...'` with `\` and `'` escaped).
+fn conninfo_value(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        if c == '\'' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Connection string for the replication connection.
+///
+/// Uses `pg_trickle.wal_receiver_conninfo` when set, otherwise the server's
+/// first Unix socket directory and port as the bootstrap superuser.  The
+/// database is always the worker's own.
+fn receiver_conninfo(db_name: &str) -> Result<String, PgTrickleError> {
+    let mut conninfo = config::pg_trickle_wal_receiver_conninfo();
+    if conninfo.trim().is_empty() {
+        let (socket_dir, port, user) = Spi::get_three::<String, String, String>(
+            "SELECT trim(split_part(current_setting('unix_socket_directories'), ',', 1)), \
+                    current_setting('port'), \
+                    (SELECT rolname::text FROM pg_roles WHERE oid = 10)",
+        )
+        .map_err(|e| PgTrickleError::SpiError(e.to_string()))?;
+        conninfo = format!(
+            "host={} port={} user={}",
+            conninfo_value(socket_dir.as_deref().unwrap_or("/tmp")),
+            conninfo_value(port.as_deref().unwrap_or("5432")),
+            conninfo_value(user.as_deref().unwrap_or("postgres"))
+        );
+    }
+    Ok(format!("{} dbname={}", conninfo, conninfo_value(db_name)))
+}
+
+// ── Worker ─────────────────────────────────────────────────────────────────
+
+/// What the receiver streams: the shared slot and its member sources.
+struct StreamPlan {
+    slot_name: String,
+    /// Sorted publication names of the members.
+    publications: Vec<String>,
+    targets: HashMap<u32, DecodeTarget>,
+    /// Highest `decoder_confirmed_lsn` recorded for the members.
+    confirmed_lsn: u64,
+}
+
+/// Load the shared slot's members, or `None` when it has none.
+fn load_stream_plan() -> Result<Option<StreamPlan>, PgTrickleError> {
+    let slot_name = shared_slot_name();
+    let mut targets = HashMap::new();
+    let mut publications = Vec::new();
+    let mut confirmed_lsn = 0u64;
+
+    for dep in StDependency::get_all()? {
+        if dep.source_type != "TABLE"
+            || !matches!(dep.cdc_mode, CdcMode::Wal | CdcMode::Transitioning)
+            || dep.slot_name.as_deref() != Some(slot_name.as_str())
+        {
+            continue;
+        }
+        // Every member's LSN is written in the same transaction, so the
+        // highest one is what the receiver last flushed; members that
+        // joined since carry an older (or no) LSN.
+        if let Some(lsn) = dep.decoder_confirmed_lsn.as_deref() {
+            confirmed_lsn = confirmed_lsn.max(version::lsn_to_u64(lsn));
+        }
+        let oid = dep.source_relid.to_u32();
+        if targets.contains_key(&oid) {
+            continue;
+        }
+        targets.insert(
+            oid,
+            DecodeTarget {
+                source_oid: dep.source_relid,
+                pk_columns: cdc::resolve_pk_columns(dep.source_relid)?,
+                columns: cdc::resolve_source_column_defs(dep.source_relid)?,
+            },
+        );
+        publications.push(publication_name_for_source(dep.source_relid));
+    }
+
+    if targets.is_empty() {
+        return Ok(None);
+    }
+    publications.sort();
+    Ok(Some(StreamPlan {
+        slot_name,
+        publications,
+        targets,
+        confirmed_lsn,
+    }))
+}
+
+/// `libpqwalreceiver`'s function table.
+fn walrcv() -> &'static pg_sys::WalReceiverFunctionsType {
+    // SAFETY: checked non-null in `pg_trickle_wal_receiver_main` right after
+    // loading the module; it is never reset afterwards.
+    unsafe { &*pg_sys::WalReceiverFunctions }
+}
+
+/// Result of one `walrcv_receive` call.
+enum Received {
+    Data(Vec<u8>),
+    /// Nothing buffered; wait on the connection's socket.
+    Idle,
+    /// The server ended the COPY stream.
+    End,
+}
+
+/// How a batch transaction ended.
+enum BatchEnd {
+    /// The batch size was reached, or a source needs to fall back.
+    Full,
+    /// All received data is applied.
+    Drained,
+    /// The server ended the stream.
+    EndOfStream,
+    /// The members' publications changed; reconnect with the new set.
+    Replan,
+}
+
+/// Why a streaming session ended.
+enum SessionEnd {
+    Shutdown,
+    Replan,
+}
+
+/// An open replication stream and its decode state.
+struct Stream {
+    conn: *mut pg_sys::WalReceiverConn,
+    plan: StreamPlan,
+    relations: HashMap<u32, RelationMessage>,
+    /// Between a `Begin` and its `Commit`.
+    in_xact: bool,
+    /// Commit LSN of the open transaction, from its `Begin`; every change
+    /// of the transaction is stamped with it.
+    xact_lsn: u64,
+    /// Highest WAL end the server reported.
+    received_lsn: u64,
+    /// LSN up to which every source transaction has been applied.
+    applied_lsn: u64,
+    /// LSN recorded in the catalog and reported as flushed.
+    flushed_lsn: u64,
+    /// LSN recorded by the running batch; flushed once it commits.
+    recorded_lsn: u64,
+    reply_requested: bool,
+    wait_fd: pg_sys::pgsocket,
+    schema_mismatches: Vec<u32>,
+    last_record: Instant,
+    last_status: Instant,
+    last_plan_check: Instant,
+}
+
+impl Stream {
+    /// Connect and start logical replication from the stored LSN.
+    fn open(conninfo: &str, plan: StreamPlan) -> Self {
+        let conninfo_c = CString::new(conninfo)
+            .unwrap_or_else(|_| error!("pg_trickle wal receiver: conninfo contains a NUL byte"));
+        let slot_c = CString::new(plan.slot_name.as_str())
+            .unwrap_or_else(|_| error!("pg_trickle wal receiver: invalid slot name"));
+
+        // SAFETY: plain calls into libpqwalreceiver; all pointers passed are
+        // valid for the duration of the call or palloc'd.
+        let conn = unsafe {
+            let mut err: *mut std::os::raw::c_char = std::ptr::null_mut();
+            let conn = (walrcv().walrcv_connect.expect("walrcv_connect"))(
+                conninfo_c.as_ptr(),
+                true,  // replication
+                true,  // logical
+                false, // must_use_password
+                APPLICATION_NAME.as_ptr(),
+                &mut err,
+            );
+            if conn.is_null() {
+                let msg = if err.is_null() {
+                    "unknown error".to_string()
+                } else {
+                    CStr::from_ptr(err).to_string_lossy().into_owned()
+                };
+                error!("pg_trickle wal receiver: could not connect: {}", msg);
+            }
+
+            let mut options: pg_sys::WalRcvStreamOptions = std::mem::zeroed();
+            options.logical = true;
+            options.slotname = pg_sys::pstrdup(slot_c.as_ptr());
+            options.startpoint = plan.confirmed_lsn;
+            options.proto.logical.proto_version = 1; // pgoutput::PROTO_VERSION
+            let mut publications: *mut pg_sys::List = std::ptr::null_mut();
+            for name in &plan.publications {
+                let name_c = CString::new(name.as_str()).unwrap_or_default();
+                publications = pg_sys::lappend(
+                    publications,
+                    pg_sys::makeString(pg_sys::pstrdup(name_c.as_ptr())).cast(),
+                );
+            }
+            options.proto.logical.publication_names = publications;
+            (walrcv()
+                .walrcv_startstreaming
+                .expect("walrcv_startstreaming"))(conn, &options);
+            conn
+        };
+
+        let now = Instant::now();
+        Stream {
+            conn,
+            relations: HashMap::new(),
+            in_xact: false,
+            xact_lsn: 0,
+            received_lsn: plan.confirmed_lsn,
+            applied_lsn: plan.confirmed_lsn,
+            flushed_lsn: plan.confirmed_lsn,
+            recorded_lsn: plan.confirmed_lsn,
+            reply_requested: false,
+            wait_fd: -1, // PGINVALID_SOCKET
+            schema_mismatches: Vec::new(),
+            last_record: now,
+            last_status: now,
+            last_plan_check: now,
+            plan,
+        }
+    }
+
+    fn receive(&mut self) -> Received {
+        let mut buf: *mut std::os::raw::c_char = std::ptr::null_mut();
+        let mut fd: pg_sys::pgsocket = -1;
+        // SAFETY: `buf` points into libpq's buffer until the next call; it
+        // is copied before returning.
+        unsafe {
+            let len =
+                (walrcv().walrcv_receive.expect("walrcv_receive"))(self.conn, &mut buf, &mut fd);
+            match len {
+                0 => {
+                    self.wait_fd = fd;
+                    Received::Idle
+                }
+                n if n > 0 => Received::Data(
+                    std::slice::from_raw_parts(buf as *const u8, n as usize).to_vec(),
+                ),
+                _ => Received::End,
+            }
+        }
+    }
+
+    /// Sleep until the connection is readable, the latch is set or
+    /// `timeout` passes.
+    fn wait(&self, timeout: Duration) {
+        let mut events = pg_sys::WL_LATCH_SET | pg_sys::WL_TIMEOUT | pg_sys::WL_EXIT_ON_PM_DEATH;
+        if self.wait_fd >= 0 {
+            events |= pg_sys::WL_SOCKET_READABLE;
+        }
+        // SAFETY: MyLatch is always valid inside a background worker.
+        unsafe {
+            pg_sys::WaitLatchOrSocket(
+                pg_sys::MyLatch,
+                events as i32,
+                self.wait_fd,
+                timeout.as_millis().max(1) as std::os::raw::c_long,
+                pg_sys::PG_WAIT_EXTENSION,
+            );
+            pg_sys::ResetLatch(pg_sys::MyLatch);
+        }
+    }
+
+    /// Apply received messages until a batch boundary.  Runs inside the
+    /// batch transaction.
+    fn read_batch(&mut self, change_schema: &str) -> Result<BatchEnd, PgTrickleError> {
+        let batch_size = config::pg_trickle_wal_receiver_batch_size();
+        let mut outcome = SlotPollOutcome {
+            changes: 0,
+            last_lsn: None,
+            schema_mismatches: Vec::new(),
+        };
+
+        let mut end = loop {
+            let buf = match self.receive() {
+                Received::Data(buf) => buf,
+                // A transaction arrives in one piece once decoded; only
+                // the network can split it, so wait for the rest.
+                Received::Idle if self.in_xact => {
+                    self.wait(Duration::from_secs(1));
+                    continue;
+                }
+                Received::Idle => break BatchEnd::Drained,
+                Received::End if self.in_xact => {
+                    return Err(PgTrickleError::WalTransitionError(
+                        "replication stream ended inside a transaction".to_string(),
+                    ));
+                }
+                Received::End => break BatchEnd::EndOfStream,
+            };
+
+            match parse_copy_data(&buf)? {
+                CopyData::XLogData { wal_end, payload } => {
+                    self.received_lsn = self.received_lsn.max(wal_end);
+                    let msg = pgoutput::decode_message(payload)
+                        .map_err(|e| PgTrickleError::WalTransitionError(e.to_string()))?;
+                    let commit_end = match msg {
+                        PgOutputMessage::Begin { final_lsn, .. } => {
+                            self.in_xact = true;
+                            self.xact_lsn = final_lsn;
+                            None
+                        }
+                        PgOutputMessage::Commit { end_lsn, .. } => Some(end_lsn),
+                        _ => None,
+                    };
+                    apply_pgoutput_message(
+                        msg,
+                        &version::u64_to_lsn(self.xact_lsn),
+                        &self.plan.targets,
+                        &mut self.relations,
+                        &mut outcome,
+                        change_schema,
+                    )?;
+                    if let Some(end_lsn) = commit_end {
+                        self.in_xact = false;
+                        self.applied_lsn = self.applied_lsn.max(end_lsn);
+                        if outcome.changes >= batch_size || !outcome.schema_mismatches.is_empty() {
+                            break BatchEnd::Full;
+                        }
+                    }
+                }
+                CopyData::Keepalive {
+                    wal_end,
+                    reply_requested,
+                } => {
+                    self.received_lsn = self.received_lsn.max(wal_end);
+                    self.reply_requested |= reply_requested;
+                    // Everything before the keepalive has been sent, so
+                    // outside a transaction it is all applied.
+                    if !self.in_xact {
+                        self.applied_lsn = self.applied_lsn.max(wal_end);
+                    }
+                }
+            }
+        };
+
+        // The slot moves past the skipped changes of mismatched sources, so
+        // their stream tables are recomputed; flag them in the transaction
+        // that records the new position, before it is acknowledged.
+        for relid in &outcome.schema_mismatches {
+            mark_downstream_for_reinit(pg_sys::Oid::from(*relid), "schema change detected")?;
+        }
+
+        if self.applied_lsn > self.recorded_lsn
+            && (outcome.changes > 0 || self.last_record.elapsed() >= IDLE_CONFIRM_INTERVAL)
+        {
+            StDependency::update_confirmed_lsn_for_slot(
+                &self.plan.slot_name,
+                &version::u64_to_lsn(self.applied_lsn),
+            )?;
+            self.recorded_lsn = self.applied_lsn;
+            self.last_record = Instant::now();
+        }
+
+        self.schema_mismatches = outcome.schema_mismatches;
+
+        if self.last_plan_check.elapsed() >= PLAN_CHECK_INTERVAL
+            && matches!(end, BatchEnd::Full | BatchEnd::Drained)
+        {
+            self.last_plan_check = Instant::now();
+            match load_stream_plan()? {
+                Some(plan) if plan.publications == self.plan.publications => {
+                    self.plan.targets = plan.targets;
+                }
+                _ => end = BatchEnd::Replan,
+            }
+        }
+
+        if outcome.changes > 0 {
+            debug1!(
+                "pg_trickle wal receiver: wrote {} changes from slot '{}' (flushed up to {})",
+                outcome.changes,
+                self.plan.slot_name,
+                version::u64_to_lsn(self.recorded_lsn)
+            );
+        }
+
+        Ok(end)
+    }
+
+    /// Report positions to the server when something was flushed, a reply
+    /// was requested or the status interval has passed.
+    fn maybe_send_status(&mut self) {
+        let advanced = self.recorded_lsn > self.flushed_lsn;
+        self.flushed_lsn = self.recorded_lsn;
+        if !advanced && !self.reply_requested && self.last_status.elapsed() < STATUS_INTERVAL {
+            return;
+        }
+
+        // SAFETY: GetCurrentTimestamp has no preconditions.
+        let now = unsafe { pg_sys::GetCurrentTimestamp() };
+        let msg = standby_status_update(
+            self.received_lsn.max(self.flushed_lsn),
+            self.flushed_lsn,
+            now,
+            false,
+        );
+        // SAFETY: `msg` is valid for the duration of the call.
+        unsafe {
+            (walrcv().walrcv_send.expect("walrcv_send"))(
+                self.conn,
+                msg.as_ptr().cast(),
+                msg.len() as i32,
+            );
+        }
+        self.reply_requested = false;
+        self.last_status = Instant::now();
+    }
+
+    fn close(self) {
+        // SAFETY: `conn` is open; it is not used after disconnecting.
+        unsafe {
+            let mut next_tli: pg_sys::TimeLineID = 0;
+            (walrcv().walrcv_endstreaming.expect("walrcv_endstreaming"))(self.conn, &mut next_tli);
+            (walrcv().walrcv_disconnect.expect("walrcv_disconnect"))(self.conn);
+        }
+    }
+}
+
+/// Stream the slot until shutdown or until its member set changes.
+fn run_session(db_name: &str, plan: StreamPlan) -> SessionEnd {
+    let conninfo =
+        match BackgroundWorker::transaction(AssertUnwindSafe(|| receiver_conninfo(db_name))) {
+            Ok(conninfo) => conninfo,
+            Err(e) => {
+                warning!("pg_trickle wal receiver: {}", e);
+                return SessionEnd::Shutdown;
+            }
+        };
+    let change_schema = config::pg_trickle_change_buffer_schema();
+
+    let mut stream = Stream::open(&conninfo, plan);
+    log!(
+        "pg_trickle wal receiver: streaming slot '{}' from {} for {} source(s)",
+        stream.plan.slot_name,
+        version::u64_to_lsn(stream.plan.confirmed_lsn),
+        stream.plan.targets.len()
+    );
+
+    let mut idle = false;
+    loop {
+        if idle {
+            let mut timeout = STATUS_INTERVAL.saturating_sub(stream.last_status.elapsed());
+            if stream.applied_lsn > stream.recorded_lsn {
+                timeout =
+                    timeout.min(IDLE_CONFIRM_INTERVAL.saturating_sub(stream.last_record.elapsed()));
+            }
+            stream.wait(timeout);
+        }
+
+        if BackgroundWorker::sigterm_received() {
+            stream.maybe_send_status();
+            stream.close();
+            return SessionEnd::Shutdown;
+        }
+        // SAFETY: ConfigReloadPending is set by the SIGHUP handler.
+        unsafe {
+            if pg_sys::ConfigReloadPending != 0 {
+                pg_sys::ConfigReloadPending = 0;
+                pg_sys::ProcessConfigFile(pg_sys::GucContext::PGC_SIGHUP);
+            }
+        }
+        if !config::pg_trickle_wal_receiver() {
+            stream.maybe_send_status();
+            stream.close();
+            return SessionEnd::Shutdown;
+        }
+
+        // An error raised inside the transaction aborts the batch and ends
+        // the worker; nothing past the recorded LSN is kept.
+        let end = BackgroundWorker::transaction(AssertUnwindSafe(|| {
+            match stream.read_batch(&change_schema) {
+                Ok(end) => end,
+                Err(e) => error!("pg_trickle wal receiver: {}", e),
+            }
+        }));
+        stream.maybe_send_status();
+
+        // Sources whose columns no longer match fall back to triggers.
+        if !stream.schema_mismatches.is_empty() {
+            let mismatches = std::mem::take(&mut stream.schema_mismatches);
+            BackgroundWorker::transaction(AssertUnwindSafe(|| {
+                for relid in &mismatches {
+                    warning!(
+                        "pg_trickle: schema change detected for source OID {} in the WAL \
+                         stream — falling back to triggers",
+                        relid
+                    );
+                    if let Err(e) =
+                        abort_wal_transition(pg_sys::Oid::from(*relid), 0, &change_schema)
+                    {
+                        warning!(
+                            "pg_trickle: WAL fallback for source OID {} failed: {}",
+                            relid,
+                            e
+                        );
+                    }
+                }
+            }));
+            stream.close();
+            return SessionEnd::Replan;
+        }
+
+        match end {
+            BatchEnd::Full => idle = false,
+            BatchEnd::Drained => idle = true,
+            BatchEnd::EndOfStream | BatchEnd::Replan => {
+                stream.close();
+                return SessionEnd::Replan;
+            }
+        }
+    }
+}
+
+/// Main entry point for the WAL receiver background worker.
+///
+/// Streams the database's shared slot until the slot has no members, the
+/// GUC is turned off or the worker receives SIGTERM.
+///
+/// # Safety
+/// Called directly by PostgreSQL as a background worker entry point.
+#[pg_guard]
+#[unsafe(no_mangle)]
+pub extern "C-unwind" fn pg_trickle_wal_receiver_main(_arg: pg_sys::Datum) {
+    BackgroundWorker::attach_signal_handlers(SignalWakeFlags::SIGHUP | SignalWakeFlags::SIGTERM);
+
+    let db_name = BackgroundWorker::get_extra().to_string();
+    BackgroundWorker::connect_worker_to_spi(Some(db_name.as_str()), None);
+
+    // SAFETY: loads PostgreSQL's own replication client; its _PG_init
+    // installs `WalReceiverFunctions`.
+    unsafe {
+        pg_sys::load_file(c"libpqwalreceiver".as_ptr(), false);
+        if pg_sys::WalReceiverFunctions.is_null() {
+            warning!("pg_trickle wal receiver: libpqwalreceiver is not available, exiting");
+            return;
+        }
+    }
+
+    loop {
+        let plan = BackgroundWorker::transaction(AssertUnwindSafe(|| match load_stream_plan() {
+            Ok(Some(plan)) => Some(plan),
+            Ok(None) => {
+                // The last member left while the slot was held by this
+                // worker, so its own drop attempt failed.
+                let slot_name = shared_slot_name();
+                if let Err(e) = drop_replication_slot(&slot_name) {
+                    warning!("pg_trickle wal receiver: {}", e);
+                }
+                None
+            }
+            Err(e) => {
+                warning!(
+                    "pg_trickle wal receiver: could not load slot members: {}",
+                    e
+                );
+                None
+            }
+        }));
+        let Some(plan) = plan else {
+            log!(
+                "pg_trickle wal receiver: no sources left on the shared slot in '{}', exiting",
+                db_name
+            );
+            return;
+        };
+
+        match run_session(&db_name, plan) {
+            SessionEnd::Shutdown => {
+                log!("pg_trickle wal receiver: shutting down (db='{}')", db_name);
+                return;
+            }
+            SessionEnd::Replan => continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xlog_data(start: u64, end: u64, payload: &[u8]) -> Vec<u8> {
+        let mut buf = vec![b'w'];
+        buf.extend_from_slice(&start.to_be_bytes());
+        buf.extend_from_slice(&end.to_be_bytes());
+        buf.extend_from_slice(&42i64.to_be_bytes());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[test]
+    fn test_parse_xlog_data() {
+        let buf = xlog_data(0x1_0000_0010, 0x1_0000_0100, b"B...");
+        assert_eq!(
+            parse_copy_data(&buf).unwrap(),
+            CopyData::XLogData {
+                wal_end: 0x1_0000_0100,
+                payload: b"B...",
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_keepalive() {
+        let mut buf = vec![b'k'];
+        buf.extend_from_slice(&0x2A_u64.to_be_bytes());
+        buf.extend_from_slice(&7i64.to_be_bytes());
+        buf.push(1);
+        assert_eq!(
+            parse_copy_data(&buf).unwrap(),
+            CopyData::Keepalive {
+                wal_end: 0x2A,
+                reply_requested: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated_and_unknown_messages() {
+        assert!(parse_copy_data(&[]).is_err());
+        assert!(parse_copy_data(&[b'w', 0, 0]).is_err());
+        assert!(parse_copy_data(&[b'k'; 10]).is_err());
+        assert!(parse_copy_data(&[b'x'; 40]).is_err());
+    }
+
+    #[test]
+    fn test_standby_status_update_layout() {
+        let msg = standby_status_update(0x30, 0x20, 5, true);
+        assert_eq!(msg[0], b'r');
+        assert_eq!(read_u64(&msg, 1), 0x30);
+        assert_eq!(read_u64(&msg, 9), 0x20);
+        assert_eq!(read_u64(&msg, 17), 0x20);
+        assert_eq!(read_u64(&msg, 25), 5);
+        assert_eq!(msg[33], 1);
+    }
+
+    #[test]
+    fn test_capped_position() {
+        // Receiver behind the tick position: cap just below the flushed
+        // LSN, where the next unapplied commit record may start.
+        assert_eq!(
+            capped_position("0/500", Some("0/300"), "0/100"),
+            "0/000002FF"
+        );
+        // Receiver ahead: the tick position wins.
+        assert_eq!(capped_position("0/500", Some("0/900"), "0/100"), "0/500");
+        // Nothing flushed yet, or flushed behind the frontier: hold.
+        assert_eq!(capped_position("0/500", None, "0/100"), "0/100");
+        assert_eq!(capped_position("0/500", Some("0/80"), "0/100"), "0/100");
+        assert_eq!(
+            capped_position("0/500", Some("0/101"), "0/100"),
+            "0/00000100"
+        );
+    }
+
+    #[test]
+    fn test_conninfo_value_quoting() {
+        assert_eq!(conninfo_value("/tmp"), "'/tmp'");
+        assert_eq!(conninfo_value("it's"), "'it\\'s'");
+        assert_eq!(conninfo_value("a\\b"), "'a\\\\b'");
+    }
+}
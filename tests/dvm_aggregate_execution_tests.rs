@@ -8,7 +8,7 @@ mod common;
 
 use common::TestDb;
 use pg_trickle::dvm::DiffContext;
-use pg_trickle::dvm::parser::{AggExpr, AggFunc, Column, Expr, OpTree, SortExpr};
+use pg_trickle::dvm::parser::{AggExpr, AggFunc, Column, Expr, OpTree, Resolved, SortExpr};
 use pg_trickle::version::Frontier;
 
 fn int_col(name: &str) -> Column {
@@ -51,6 +51,7 @@ fn binop(op: &str, left: Expr, right: Expr) -> Expr {
         op: op.to_string(),
         left: Box::new(left),
         right: Box::new(right),
+        resolved: Resolved::default(),
     }
 }
 
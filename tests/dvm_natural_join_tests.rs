@@ -22,7 +22,7 @@ mod common;
 
 use common::TestDb;
 use pg_trickle::dvm::DiffContext;
-use pg_trickle::dvm::parser::{Column, Expr, OpTree};
+use pg_trickle::dvm::parser::{Column, Expr, OpTree, Resolved};
 use pg_trickle::version::Frontier;
 
 // ── column helpers ────────────────────────────────────────────────────────────
@@ -74,6 +74,7 @@ fn natural_cond(left_alias: &str, right_alias: &str, shared_col: &str) -> Expr {
             table_alias: Some(right_alias.to_string()),
             column_name: shared_col.to_string(),
         }),
+        resolved: Resolved::default(),
     }
 }
 
@@ -14,7 +14,7 @@ mod common;
 
 use common::TestDb;
 use pg_trickle::dvm::DiffContext;
-use pg_trickle::dvm::parser::{Column, Expr, OpTree};
+use pg_trickle::dvm::parser::{Column, Expr, OpTree, Resolved};
 use pg_trickle::version::Frontier;
 
 // ── column helpers ────────────────────────────────────────────────────────────
@@ -63,6 +63,7 @@ fn eq_cond(left_alias: &str, left_col: &str, right_alias: &str, right_col: &str)
             table_alias: Some(right_alias.to_string()),
             column_name: right_col.to_string(),
         }),
+        resolved: Resolved::default(),
     }
 }
 
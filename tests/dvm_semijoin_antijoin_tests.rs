@@ -8,7 +8,7 @@ mod common;
 
 use common::TestDb;
 use pg_trickle::dvm::DiffContext;
-use pg_trickle::dvm::parser::{Column, Expr, OpTree};
+use pg_trickle::dvm::parser::{Column, Expr, OpTree, Resolved};
 use pg_trickle::version::Frontier;
 
 fn int_col(name: &str) -> Column {
@@ -55,6 +55,7 @@ fn eq_cond(left_alias: &str, left_col: &str, right_alias: &str, right_col: &str)
             table_alias: Some(right_alias.to_string()),
             column_name: right_col.to_string(),
         }),
+        resolved: Resolved::default(),
     }
 }
 
@@ -434,6 +435,7 @@ fn build_nested_semi_join_tree() -> OpTree {
                 table_alias: Some("v".to_string()),
                 column_name: "customer_id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(right_outer),
         right: Box::new(right_inner),
@@ -450,6 +452,7 @@ fn build_nested_semi_join_tree() -> OpTree {
                 table_alias: Some("c".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(left),
         right: Box::new(right),
@@ -490,6 +493,7 @@ fn build_nested_anti_join_tree() -> OpTree {
                 table_alias: Some("v".to_string()),
                 column_name: "customer_id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(right_outer),
         right: Box::new(right_inner),
@@ -506,6 +510,7 @@ fn build_nested_anti_join_tree() -> OpTree {
                 table_alias: Some("c".to_string()),
                 column_name: "id".to_string(),
             }),
+            resolved: Resolved::default(),
         },
         left: Box::new(left),
         right: Box::new(right),
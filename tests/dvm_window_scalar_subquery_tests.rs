@@ -8,7 +8,7 @@ mod common;
 
 use common::TestDb;
 use pg_trickle::dvm::DiffContext;
-use pg_trickle::dvm::parser::{Column, Expr, OpTree, SortExpr, WindowExpr};
+use pg_trickle::dvm::parser::{Column, Expr, OpTree, Resolved, SortExpr, WindowExpr};
 use pg_trickle::version::Frontier;
 
 fn int_col(name: &str) -> Column {
@@ -238,6 +238,7 @@ fn build_shared_source_scalar_tree() -> OpTree {
                 column_name: "id".to_string(),
             }),
             right: Box::new(Expr::Literal("2".to_string())),
+            resolved: Resolved::default(),
         },
         child: Box::new(inner_scan),
     };
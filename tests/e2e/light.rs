@@ -924,6 +924,27 @@ impl E2eDb {
         .await
     }
 
+    /// Catalog `pgt_id` of a stream table.
+    pub async fn pgt_id(&self, st_name: &str) -> i64 {
+        self.query_scalar::<i64>(&format!(
+            "SELECT pgt_id FROM pgtrickle.pgt_stream_tables WHERE pgt_name = '{st_name}'"
+        ))
+        .await
+    }
+
+    /// Schema-qualified names of the aggregate side tables of stream table
+    /// `pgt_id` with the given prefix (`minmax`, `hist`), sorted by name.
+    pub async fn side_tables(&self, prefix: &str, pgt_id: i64) -> Vec<String> {
+        self.query_scalar::<Vec<String>>(&format!(
+            "SELECT COALESCE(array_agg('pgtrickle_changes.' || quote_ident(c.relname) \
+                                       ORDER BY c.relname), '{{}}')::text[] \
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = 'pgtrickle_changes' AND c.relkind = 'r' \
+               AND c.relname LIKE '{prefix}\\_{pgt_id}\\_%'"
+        ))
+        .await
+    }
+
     /// Get the OID of a table (as i32).
     pub async fn table_oid(&self, table: &str) -> i32 {
         self.query_scalar::<i32>(&format!("SELECT '{table}'::regclass::oid::int"))
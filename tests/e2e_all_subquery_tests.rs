@@ -295,3 +295,101 @@ async fn test_all_subquery_not_equals_operator() {
     let val: i32 = db.query_scalar("SELECT val FROM public.all_ne_st").await;
     assert_eq!(val, 30);
 }
+
+/// `> ALL` against a grouped aggregate: the inner Aggregate is maintained
+/// incrementally and each group's maximum is compared.
+#[tokio::test]
+async fn test_all_subquery_grouped_aggregate_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE all_bids (id INT PRIMARY KEY, amount INT)")
+        .await;
+    db.execute("CREATE TABLE all_lots (id INT PRIMARY KEY, lot INT, price INT)")
+        .await;
+    db.execute("INSERT INTO all_bids VALUES (1, 50), (2, 100), (3, 150)")
+        .await;
+    db.execute("INSERT INTO all_lots VALUES (1, 1, 40), (2, 1, 90), (3, 2, 60)")
+        .await;
+
+    let q = "SELECT b.id, b.amount FROM all_bids b \
+             WHERE b.amount > ALL (SELECT max(l.price) FROM all_lots l GROUP BY l.lot)";
+
+    db.create_st("all_grp_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("public.all_grp_st", q).await;
+    assert_eq!(db.count("public.all_grp_st").await, 2);
+
+    // A new lot maximum of 120 disqualifies bid 2.
+    db.execute("INSERT INTO all_lots VALUES (4, 2, 120)").await;
+    db.refresh_st("all_grp_st").await;
+    db.assert_st_matches_query("public.all_grp_st", q).await;
+    assert_eq!(db.count("public.all_grp_st").await, 1);
+
+    db.execute("DELETE FROM all_lots WHERE id = 4").await;
+    db.refresh_st("all_grp_st").await;
+    db.assert_st_matches_query("public.all_grp_st", q).await;
+    assert_eq!(db.count("public.all_grp_st").await, 2);
+}
+
+/// Empty inner relation. A scalar MAX still yields one (NULL) row, so no
+/// bid qualifies; a grouped MAX yields no rows, so every bid qualifies;
+/// a scalar COUNT yields 0 and is compared like any other value.
+#[tokio::test]
+async fn test_all_subquery_aggregate_over_empty_inner_relation() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE all_ebids (id INT PRIMARY KEY, amount INT)")
+        .await;
+    db.execute("CREATE TABLE all_elots (id INT PRIMARY KEY, lot INT, price INT)")
+        .await;
+    db.execute("INSERT INTO all_ebids VALUES (1, 0), (2, 100)")
+        .await;
+
+    let scalar_q = "SELECT b.id, b.amount FROM all_ebids b \
+                    WHERE b.amount > ALL (SELECT max(l.price) FROM all_elots l)";
+    let grouped_q = "SELECT b.id, b.amount FROM all_ebids b \
+                     WHERE b.amount > ALL (SELECT max(l.price) FROM all_elots l GROUP BY l.lot)";
+    let count_q = "SELECT b.id, b.amount FROM all_ebids b \
+                   WHERE b.amount > ALL (SELECT count(*) FROM all_elots l)";
+
+    db.create_st("all_empty_scalar_st", scalar_q, "1m", "DIFFERENTIAL")
+        .await;
+    db.create_st("all_empty_grouped_st", grouped_q, "1m", "DIFFERENTIAL")
+        .await;
+    db.create_st("all_empty_count_st", count_q, "1m", "DIFFERENTIAL")
+        .await;
+    assert_eq!(db.count("public.all_empty_scalar_st").await, 0);
+    assert_eq!(db.count("public.all_empty_grouped_st").await, 2);
+    assert_eq!(db.count("public.all_empty_count_st").await, 1);
+
+    // Non-empty, then empty again.
+    db.execute("INSERT INTO all_elots VALUES (1, 1, 50)").await;
+    for st in [
+        "all_empty_scalar_st",
+        "all_empty_grouped_st",
+        "all_empty_count_st",
+    ] {
+        db.refresh_st(st).await;
+    }
+    db.assert_st_matches_query("public.all_empty_scalar_st", scalar_q)
+        .await;
+    db.assert_st_matches_query("public.all_empty_grouped_st", grouped_q)
+        .await;
+    db.assert_st_matches_query("public.all_empty_count_st", count_q)
+        .await;
+
+    db.execute("DELETE FROM all_elots").await;
+    for st in [
+        "all_empty_scalar_st",
+        "all_empty_grouped_st",
+        "all_empty_count_st",
+    ] {
+        db.refresh_st(st).await;
+    }
+    db.assert_st_matches_query("public.all_empty_scalar_st", scalar_q)
+        .await;
+    db.assert_st_matches_query("public.all_empty_grouped_st", grouped_q)
+        .await;
+    db.assert_st_matches_query("public.all_empty_count_st", count_q)
+        .await;
+    assert_eq!(db.count("public.all_empty_scalar_st").await, 0);
+}
@@ -0,0 +1,180 @@
+//! E2E tests for `pg_trickle.cdc_capture_mode = 'hold'` (CDC-HOLD).
+//!
+//! While `cdc_paused = on` in hold mode:
+//!
+//! 1. CDC triggers keep writing to the change buffer.
+//! 2. The scheduler does not dispatch refreshes.
+//! 3. After un-pausing, the stream table catches up with a DIFFERENTIAL
+//!    refresh — no reinitialization required.
+//!
+//! Discard mode is covered for contrast: changes made while paused never
+//! reach the change buffer.
+
+mod common;
+mod e2e;
+
+use e2e::E2eDb;
+use std::time::Duration;
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Configure the scheduler for fast testing.
+async fn configure_fast_scheduler(db: &E2eDb) {
+    db.execute("ALTER SYSTEM SET pg_trickle.scheduler_interval_ms = 100")
+        .await;
+    db.execute("ALTER SYSTEM SET pg_trickle.min_schedule_seconds = 1")
+        .await;
+    db.execute("ALTER SYSTEM SET pg_trickle.auto_backoff = off")
+        .await;
+    db.reload_config_and_wait().await;
+    let _ = db.wait_for_scheduler(Duration::from_secs(90)).await;
+}
+
+async fn pause_cdc(db: &E2eDb, mode: &str) {
+    db.alter_system_set_and_wait("pg_trickle.cdc_capture_mode", &format!("'{mode}'"), mode)
+        .await;
+    db.alter_system_set_and_wait("pg_trickle.cdc_paused", "on", "on")
+        .await;
+}
+
+async fn resume_cdc(db: &E2eDb) {
+    db.alter_system_set_and_wait("pg_trickle.cdc_paused", "off", "off")
+        .await;
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+/// Hold mode keeps capturing changes into the buffer while paused.
+#[tokio::test]
+async fn test_cdc_hold_captures_changes_while_paused() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE hold_src (id INT PRIMARY KEY, val INT)")
+        .await;
+    db.execute("INSERT INTO hold_src VALUES (1, 10)").await;
+    db.create_st(
+        "hold_st",
+        "SELECT id, val FROM hold_src",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    let oid = db.table_oid("hold_src").await;
+    let buffer = db.change_buffer_table(oid as i64).await;
+
+    pause_cdc(&db, "hold").await;
+    db.execute("INSERT INTO hold_src VALUES (2, 20), (3, 30)")
+        .await;
+    db.execute("UPDATE hold_src SET val = 11 WHERE id = 1")
+        .await;
+
+    let buffered: i64 = db
+        .query_scalar(&format!("SELECT count(*) FROM {buffer}"))
+        .await;
+    assert!(
+        buffered > 0,
+        "hold mode must keep writing to the change buffer while paused"
+    );
+
+    let mode: String = db
+        .query_scalar("SELECT capture_mode FROM pgtrickle.cdc_pause_status()")
+        .await;
+    assert_eq!(mode, "hold");
+
+    resume_cdc(&db).await;
+    db.refresh_st("hold_st").await;
+    db.assert_st_matches_query("hold_st", "SELECT id, val FROM hold_src")
+        .await;
+}
+
+/// Discard mode drops changes made while paused.
+#[tokio::test]
+async fn test_cdc_discard_drops_changes_while_paused() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE discard_src (id INT PRIMARY KEY, val INT)")
+        .await;
+    db.create_st(
+        "discard_st",
+        "SELECT id, val FROM discard_src",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    let oid = db.table_oid("discard_src").await;
+    let buffer = db.change_buffer_table(oid as i64).await;
+
+    pause_cdc(&db, "discard").await;
+    db.execute("INSERT INTO discard_src VALUES (1, 10)").await;
+
+    let buffered: i64 = db
+        .query_scalar(&format!("SELECT count(*) FROM {buffer}"))
+        .await;
+    assert_eq!(
+        buffered, 0,
+        "discard mode must not write to the change buffer while paused"
+    );
+
+    resume_cdc(&db).await;
+}
+
+/// The scheduler defers refreshes while held and resumes differentially.
+#[tokio::test]
+async fn test_cdc_hold_defers_scheduler_and_resumes_differentially() {
+    let db = E2eDb::new_on_postgres_db().await.with_extension().await;
+    configure_fast_scheduler(&db).await;
+
+    db.execute("CREATE TABLE public.hold_sched_src (id INT PRIMARY KEY, val INT)")
+        .await;
+    db.execute(
+        "SELECT pgtrickle.create_stream_table(\
+             'public.hold_sched_st', \
+             'SELECT id, val FROM public.hold_sched_src', \
+             schedule => '1s', \
+             refresh_mode => 'DIFFERENTIAL'\
+         )",
+    )
+    .await;
+    common::wait_for_first_refresh(&db.pool, "public.hold_sched_st", Duration::from_secs(30)).await;
+
+    pause_cdc(&db, "hold").await;
+    // Let any in-flight refresh finish before changing the source.
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    db.execute("INSERT INTO public.hold_sched_src VALUES (1, 10), (2, 20)")
+        .await;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    let held_count: i64 = db
+        .query_scalar("SELECT count(*) FROM public.hold_sched_st")
+        .await;
+    assert_eq!(
+        held_count, 0,
+        "scheduler must not refresh while CDC is held"
+    );
+
+    resume_cdc(&db).await;
+    let caught_up = db
+        .wait_for_condition(
+            "hold_resume",
+            "SELECT count(*) = 2 FROM public.hold_sched_st",
+            Duration::from_secs(60),
+            Duration::from_millis(200),
+        )
+        .await;
+    assert!(caught_up, "stream table should catch up after un-pausing");
+
+    let last_action: String = db
+        .query_scalar(
+            "SELECT h.action::text FROM pgtrickle.pgt_refresh_history h \
+             JOIN pgtrickle.pgt_stream_tables st ON st.pgt_id = h.pgt_id \
+             WHERE st.pgt_name = 'hold_sched_st' AND h.status = 'COMPLETED' \
+               AND h.rows_inserted > 0 \
+             ORDER BY h.refresh_id DESC LIMIT 1",
+        )
+        .await;
+    assert_eq!(
+        last_action, "DIFFERENTIAL",
+        "held changes should be applied differentially, not by reinitialization"
+    );
+}
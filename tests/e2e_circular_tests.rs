@@ -11,6 +11,8 @@
 //! 4. Non-convergence hits max_iterations → ERROR status
 //! 5. Drop cycle member → scc_id cleared on remaining STs
 //! 6. allow_circular=false (default) rejects cycles
+//! 7. Lattice aggregate (MIN) cycle converges on value changes and is
+//!    re-derived after deletions
 //!
 //! Prerequisites: full E2E image (`just build-e2e-image`)
 
@@ -58,6 +60,26 @@ async fn wait_for_refresh(db: &E2eDb, pgt_name: &str, timeout: Duration) -> bool
     }
 }
 
+/// Wait until `sql` returns true, nudging the scheduler every 5 seconds.
+async fn wait_for_condition(db: &E2eDb, sql: &str, timeout: Duration) -> bool {
+    let start = std::time::Instant::now();
+    let mut last_nudge = start;
+    loop {
+        if start.elapsed() > timeout {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        if last_nudge.elapsed() >= Duration::from_secs(5) {
+            db.nudge_launcher_rescan().await;
+            last_nudge = std::time::Instant::now();
+        }
+        let done: bool = db.query_scalar(sql).await;
+        if done {
+            return true;
+        }
+    }
+}
+
 /// Wait until a stream table reaches the given status.
 async fn wait_for_status(db: &E2eDb, pgt_name: &str, status: &str, timeout: Duration) -> bool {
     let start = std::time::Instant::now();
@@ -699,3 +721,91 @@ async fn test_circular_default_rejects_cycles() {
         err_msg
     );
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Test 7: Lattice aggregate (MIN) in a cycle — shortest paths
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Single-source shortest paths as a MIN aggregate over its own output.
+/// Path costs tighten without changing the row count, so convergence must be
+/// detected by content. Deleting edges leaves costs that only support each
+/// other around a graph cycle; the SCC is re-derived from scratch instead.
+#[tokio::test]
+async fn test_circular_lattice_min_shortest_paths() {
+    let db = E2eDb::new_on_postgres_db().await.with_extension().await;
+    configure_circular_scheduler(&db).await;
+
+    db.execute(
+        "CREATE TABLE cyc_sp_edges (src INT NOT NULL, dst INT NOT NULL, w INT NOT NULL, \
+         PRIMARY KEY (src, dst))",
+    )
+    .await;
+    db.execute(
+        "INSERT INTO cyc_sp_edges VALUES (1, 2, 5), (1, 3, 1), (3, 2, 1), (2, 4, 1), (4, 2, 1)",
+    )
+    .await;
+
+    db.execute(
+        "SELECT pgtrickle.create_stream_table('cyc_sp_dist', \
+         $$SELECT e.dst, MIN(e.w) AS cost FROM cyc_sp_edges e WHERE e.src = 1 GROUP BY e.dst$$, \
+         '1s', 'DIFFERENTIAL', false)",
+    )
+    .await;
+    db.execute(
+        "SELECT pgtrickle.create_stream_table('cyc_sp_next', \
+         $$SELECT d.dst, d.cost FROM cyc_sp_dist d$$, \
+         '1s', 'DIFFERENTIAL', false)",
+    )
+    .await;
+
+    // cyc_sp_dist: direct edges from node 1 plus one more hop from cyc_sp_next.
+    db.execute(
+        "SELECT pgtrickle.alter_stream_table('cyc_sp_dist', \
+         query => $$SELECT p.dst, MIN(p.cost) AS cost FROM ( \
+             SELECT e.dst, e.w AS cost FROM cyc_sp_edges e WHERE e.src = 1 \
+             UNION ALL \
+             SELECT e.dst, n.cost + e.w AS cost \
+             FROM cyc_sp_next n JOIN cyc_sp_edges e ON e.src = n.dst \
+           ) p GROUP BY p.dst$$)",
+    )
+    .await;
+
+    let scc_id: Option<i32> = db
+        .query_scalar_opt(
+            "SELECT scc_id FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'cyc_sp_dist'",
+        )
+        .await;
+    assert!(scc_id.is_some(), "MIN aggregate cycle should be accepted");
+
+    // 1→3→2 (cost 2) replaces the direct 1→2 (cost 5); 4 is reached at 3.
+    assert!(
+        wait_for_condition(
+            &db,
+            "SELECT COALESCE(array_agg(dst || ':' || cost ORDER BY dst), '{}') \
+                 = ARRAY['2:2', '3:1', '4:3'] FROM cyc_sp_dist",
+            Duration::from_secs(600),
+        )
+        .await,
+        "shortest paths did not converge to 2:2, 3:1, 4:3"
+    );
+
+    // Without 1→2 and 3→2, nodes 2 and 4 are only reachable from each other.
+    db.execute("DELETE FROM cyc_sp_edges WHERE dst = 2 AND src IN (1, 3)")
+        .await;
+    assert!(
+        wait_for_condition(
+            &db,
+            "SELECT COALESCE(array_agg(dst || ':' || cost ORDER BY dst), '{}') \
+                 = ARRAY['3:1'] FROM cyc_sp_dist",
+            Duration::from_secs(600),
+        )
+        .await,
+        "unreachable nodes were not removed after re-derivation"
+    );
+    let status: String = db
+        .query_scalar(
+            "SELECT status FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'cyc_sp_dist'",
+        )
+        .await;
+    assert_eq!(status, "ACTIVE");
+}
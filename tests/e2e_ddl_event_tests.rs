@@ -166,18 +166,22 @@ async fn test_rename_source_table() {
     db.execute("ALTER TABLE evt_rename_src RENAME TO evt_renamed_src")
         .await;
 
-    // The ST may or may not still work after renaming the source.
-    // The defining query still references 'evt_rename_src' which is now gone.
-    // Refresh should reveal the problem.
-    let result = db
-        .try_execute("SELECT pgtrickle.refresh_stream_table('evt_rename_st')")
+    // REN-1: The defining query is rewritten to the new name.
+    let defining_query: String = db
+        .query_scalar(
+            "SELECT defining_query FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'evt_rename_st'",
+        )
         .await;
-
-    // After renaming source, refresh with old name should fail
     assert!(
-        result.is_err(),
-        "Refresh should fail after source table rename since defining query references old name"
+        defining_query.contains("evt_renamed_src"),
+        "defining query should reference the renamed table: {defining_query}"
     );
+
+    db.execute("INSERT INTO evt_renamed_src VALUES (2, 'more')")
+        .await;
+    db.refresh_st("evt_rename_st").await;
+    db.assert_st_matches_query("evt_rename_st", "SELECT id, val FROM evt_renamed_src")
+        .await;
 }
 
 /// F18: CREATE OR REPLACE FUNCTION on a function used by a DIFFERENTIAL
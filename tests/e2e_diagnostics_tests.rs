@@ -45,12 +45,13 @@ async fn test_diagnostics_explain_query_rewrite_grouping_sets_fires() {
         .await;
 
     // The grouping_sets rewrite should set changed = true for the grouping_sets pass.
+    // HAVING keeps the query off the native grouping-sets operator.
     let changed_count: i64 = db
         .query_scalar(
             "SELECT COUNT(*) \
              FROM pgtrickle.explain_query_rewrite(\
                'SELECT region, SUM(amount) FROM diag_gs \
-                GROUP BY GROUPING SETS ((region), ())')\
+                GROUP BY GROUPING SETS ((region), ()) HAVING SUM(amount) > 0')\
              WHERE pass_name = 'grouping_sets' AND changed = true",
         )
         .await;
@@ -61,6 +62,29 @@ async fn test_diagnostics_explain_query_rewrite_grouping_sets_fires() {
     );
 }
 
+/// DT-1: a GROUPING SETS query the native operator handles is not rewritten.
+#[tokio::test]
+async fn test_diagnostics_explain_query_rewrite_grouping_sets_native() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE diag_gs_native (id INT PRIMARY KEY, region TEXT, amount NUMERIC)")
+        .await;
+
+    let changed_count: i64 = db
+        .query_scalar(
+            "SELECT COUNT(*) \
+             FROM pgtrickle.explain_query_rewrite(\
+               'SELECT region, SUM(amount) FROM diag_gs_native \
+                GROUP BY GROUPING SETS ((region), ())')\
+             WHERE pass_name = 'grouping_sets' AND changed = true",
+        )
+        .await;
+
+    assert_eq!(
+        changed_count, 0,
+        "grouping_sets pass should leave natively maintained grouping sets unchanged"
+    );
+}
+
 /// DT-1: a TopK (ORDER BY + LIMIT) query fires the `topk_detection` pass.
 #[tokio::test]
 async fn test_diagnostics_explain_query_rewrite_topk_detected() {
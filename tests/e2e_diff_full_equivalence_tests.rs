@@ -601,8 +601,6 @@ async fn test_diff_full_equivalence_distinct_on() {
     db.assert_st_matches_query("dfe_dis_st", q).await;
 
     // Cycle 3: replace the current winner of 'a' with a new one
-    // (Deleting the winner without a replacement requires a full-table
-    // re-scan for the partition which the Window operator doesn't do.)
     db.execute("DELETE FROM dfe_dis WHERE cat = 'a' AND score = 30")
         .await;
     db.execute("INSERT INTO dfe_dis (cat, score, label) VALUES ('a', 25, 'comeback')")
@@ -620,6 +618,90 @@ async fn test_diff_full_equivalence_distinct_on() {
     db.execute("DELETE FROM dfe_dis WHERE cat = 'c'").await;
     db.refresh_st("dfe_dis_st").await;
     db.assert_st_matches_query("dfe_dis_st", q).await;
+
+    // Cycle 6: delete the winner of 'a' without a replacement — the
+    // runner-up is refilled from the source
+    db.execute("DELETE FROM dfe_dis WHERE cat = 'a' AND score = 25")
+        .await;
+    db.refresh_st("dfe_dis_st").await;
+    db.assert_st_matches_query("dfe_dis_st", q).await;
+}
+
+/// `SELECT DISTINCT ON (k) *` — the star is expanded so the helper
+/// `__pgt_rn` column stays out of the stream table.
+#[tokio::test]
+async fn test_diff_full_equivalence_distinct_on_star() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE dfe_dos (id SERIAL PRIMARY KEY, device INT, ts INT, reading INT)")
+        .await;
+    db.execute(
+        "INSERT INTO dfe_dos (device, ts, reading) VALUES (1, 1, 10), (1, 2, 11), (2, 1, 20)",
+    )
+    .await;
+
+    let q = "SELECT DISTINCT ON (device) * FROM dfe_dos ORDER BY device, ts DESC";
+    db.create_st("dfe_dos_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("dfe_dos_st", q).await;
+
+    let rn_cols: i64 = db
+        .query_scalar(
+            "SELECT count(*) FROM information_schema.columns \
+             WHERE table_name = 'dfe_dos_st' AND column_name = '__pgt_rn'",
+        )
+        .await;
+    assert_eq!(rn_cols, 0);
+
+    db.execute("INSERT INTO dfe_dos (device, ts, reading) VALUES (2, 5, 25)")
+        .await;
+    db.refresh_st("dfe_dos_st").await;
+    db.assert_st_matches_query("dfe_dos_st", q).await;
+
+    db.execute("UPDATE dfe_dos SET ts = 9 WHERE device = 1 AND ts = 1")
+        .await;
+    db.refresh_st("dfe_dos_st").await;
+    db.assert_st_matches_query("dfe_dos_st", q).await;
+}
+
+/// DISTINCT ON inside a FROM subquery ("latest row per key" joined to a
+/// dimension) is lowered to ROW_NUMBER() = 1 and stays DIFFERENTIAL.
+#[tokio::test]
+async fn test_diff_full_equivalence_distinct_on_in_subquery() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE dfe_dsq_acct (id INT PRIMARY KEY, owner TEXT)")
+        .await;
+    db.execute("CREATE TABLE dfe_dsq_evt (id SERIAL PRIMARY KEY, acct INT, seq INT, state TEXT)")
+        .await;
+    db.execute("INSERT INTO dfe_dsq_acct VALUES (1, 'ann'), (2, 'bo')")
+        .await;
+    db.execute(
+        "INSERT INTO dfe_dsq_evt (acct, seq, state) VALUES (1, 1, 'open'), (1, 2, 'hold'), (2, 1, 'open')",
+    )
+    .await;
+
+    let q = "SELECT a.owner, l.state \
+             FROM dfe_dsq_acct a \
+             JOIN (SELECT DISTINCT ON (acct) acct, state FROM dfe_dsq_evt \
+                   ORDER BY acct, seq DESC) l ON l.acct = a.id";
+    db.create_st("dfe_dsq_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("dfe_dsq_st", q).await;
+
+    let mode: String = db
+        .query_scalar(
+            "SELECT refresh_mode FROM pgtrickle.pgt_stream_tables \
+             WHERE pgt_name = 'dfe_dsq_st'",
+        )
+        .await;
+    assert_eq!(mode, "DIFFERENTIAL");
+
+    db.execute("INSERT INTO dfe_dsq_evt (acct, seq, state) VALUES (2, 2, 'closed')")
+        .await;
+    db.refresh_st("dfe_dsq_st").await;
+    db.assert_st_matches_query("dfe_dsq_st", q).await;
+
+    db.execute("DELETE FROM dfe_dsq_evt WHERE acct = 1 AND seq = 2")
+        .await;
+    db.refresh_st("dfe_dsq_st").await;
+    db.assert_st_matches_query("dfe_dsq_st", q).await;
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -581,7 +581,8 @@ async fn test_concurrent_refresh_safety() {
 
 #[tokio::test]
 async fn test_grouping_sets_accepted_via_rewrite() {
-    // GROUPING SETS are auto-rewritten to UNION ALL of plain GROUP BY queries.
+    // GROUPING SETS are maintained by the native grouping-sets operator, or
+    // auto-rewritten to UNION ALL of plain GROUP BY queries.
     // Note: The rewrite currently has a limitation when GROUPING SETS reference
     // columns that aren't in the select list, which may produce errors.
     let db = E2eDb::new().await.with_extension().await;
@@ -608,7 +609,7 @@ async fn test_grouping_sets_accepted_via_rewrite() {
 
 #[tokio::test]
 async fn test_rollup_accepted_via_rewrite() {
-    // ROLLUP is auto-rewritten to UNION ALL of plain GROUP BY queries.
+    // ROLLUP is maintained by the native grouping-sets operator.
     let db = E2eDb::new().await.with_extension().await;
 
     db.execute(
@@ -632,7 +633,7 @@ async fn test_rollup_accepted_via_rewrite() {
 
 #[tokio::test]
 async fn test_cube_accepted_via_rewrite() {
-    // CUBE is auto-rewritten to UNION ALL of plain GROUP BY queries.
+    // CUBE is maintained by the native grouping-sets operator.
     let db = E2eDb::new().await.with_extension().await;
 
     db.execute(
@@ -656,7 +657,7 @@ async fn test_cube_accepted_via_rewrite() {
 
 #[tokio::test]
 async fn test_grouping_sets_differential_mode_also_rewritten() {
-    // GROUPING SETS are auto-rewritten to UNION ALL even in DIFFERENTIAL mode.
+    // GROUPING SETS are accepted in DIFFERENTIAL mode too.
     let db = E2eDb::new().await.with_extension().await;
 
     db.execute("CREATE TABLE gsd_src (id SERIAL PRIMARY KEY, dept TEXT, amount NUMERIC)")
@@ -1140,6 +1140,46 @@ async fn test_json_table_differential_mode() {
     assert_eq!(count, 5, "Should have 5 tags after insert");
 }
 
+/// JSON_TABLE with NESTED PATH and FOR ORDINALITY columns stays DIFFERENTIAL
+/// and re-expands only the changed documents.
+#[tokio::test]
+async fn test_json_table_nested_path_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE jt_orders (id INT PRIMARY KEY, doc JSONB NOT NULL)")
+        .await;
+    db.execute(
+        "INSERT INTO jt_orders VALUES
+         (1, '{\"lines\": [{\"sku\": \"a\", \"tags\": [\"x\", \"y\"]}, {\"sku\": \"b\", \"tags\": []}]}'),
+         (2, '{\"lines\": [{\"sku\": \"c\", \"tags\": [\"z\"]}]}')",
+    )
+    .await;
+
+    let query = "SELECT o.id, jt.line_no, jt.sku, jt.tag
+         FROM jt_orders o,
+              JSON_TABLE(o.doc, '$.lines[*]'
+                COLUMNS (
+                  line_no FOR ORDINALITY,
+                  sku TEXT PATH '$.sku',
+                  NESTED PATH '$.tags[*]' COLUMNS (tag TEXT PATH '$')
+                )) AS jt";
+    db.create_st("jt_order_tags", query, "1m", "DIFFERENTIAL")
+        .await;
+    db.assert_st_matches_query("jt_order_tags", query).await;
+
+    db.execute(
+        "INSERT INTO jt_orders VALUES (3, '{\"lines\": [{\"sku\": \"d\", \"tags\": [\"x\", \"x\"]}]}')",
+    )
+    .await;
+    db.execute(
+        "UPDATE jt_orders SET doc = '{\"lines\": [{\"sku\": \"a\", \"tags\": [\"y\"]}]}' WHERE id = 1",
+    )
+    .await;
+    db.execute("DELETE FROM jt_orders WHERE id = 2").await;
+    db.refresh_st("jt_order_tags").await;
+    db.assert_st_matches_query("jt_order_tags", query).await;
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // Regression: NULLIF in AUTO mode must resolve to DIFFERENTIAL (not FULL)
 // ═══════════════════════════════════════════════════════════════════════
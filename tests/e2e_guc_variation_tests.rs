@@ -3,7 +3,8 @@
 //! Validates that differential refresh produces correct results under
 //! different GUC configurations: block_source_ddl, use_prepared_statements,
 //! merge_planner_hints, cleanup_use_truncate, merge_work_mem_mb,
-//! max_grouping_set_branches, foreign_table_polling.
+//! max_grouping_set_branches, foreign_table_polling (incl. high-water-mark
+//! polling).
 //!
 //! Prerequisites: `./tests/build_e2e_image.sh`
 
@@ -183,7 +184,9 @@ async fn test_guc_combined_non_default() {
 #[tokio::test]
 async fn test_guc_max_grouping_set_branches_rejects_over_limit() {
     // CUBE(a, b, c) produces 2^3 = 8 branches.  Setting the GUC to 4
-    // should cause creation to fail with a clear error.
+    // should cause creation to fail with a clear error.  The HAVING clause
+    // keeps the query on the UNION ALL rewrite (the native grouping-sets
+    // operator has no branch limit).
     let _gs_lock = GROUPING_SET_BRANCHES_LOCK.lock().await;
     let db = E2eDb::new().await.with_extension().await;
     db.execute("CREATE TABLE gs_limit_src (a TEXT, b TEXT, c TEXT, val INT)")
@@ -196,7 +199,8 @@ async fn test_guc_max_grouping_set_branches_rejects_over_limit() {
         .try_execute_with_config(
             &["SET pg_trickle.max_grouping_set_branches = 4"],
             "SELECT pgtrickle.create_stream_table('gs_limit_st', \
-             $$ SELECT a, b, c, SUM(val) FROM gs_limit_src GROUP BY CUBE (a, b, c) $$, \
+             $$ SELECT a, b, c, SUM(val) FROM gs_limit_src GROUP BY CUBE (a, b, c) \
+             HAVING SUM(val) > 0 $$, \
              '1m', 'FULL')",
         )
         .await;
@@ -410,3 +414,108 @@ async fn test_guc_foreign_table_polling_on_allows_differential() {
     db.alter_system_reset_and_wait("pg_trickle.foreign_table_polling", "off")
         .await;
 }
+
+#[tokio::test]
+async fn test_guc_foreign_table_polling_high_water_mark() {
+    // POLL-HWM: a configured foreign table is polled above the stored
+    // watermark; soft deletes are deletes, hard deletes wait for the next
+    // reconciliation.
+    let _polling_lock = FOREIGN_TABLE_POLLING_LOCK.lock().await;
+    let db = E2eDb::new().await.with_extension().await;
+    let db_name: String = db.query_scalar("SELECT current_database()").await;
+
+    db.execute("CREATE EXTENSION IF NOT EXISTS postgres_fdw")
+        .await;
+    db.execute(
+        "CREATE TABLE ft_hwm_src (id INT PRIMARY KEY, grp TEXT, val INT, \
+         ver BIGINT NOT NULL, deleted BOOLEAN NOT NULL DEFAULT false)",
+    )
+    .await;
+    db.execute(
+        "INSERT INTO ft_hwm_src (id, grp, val, ver) VALUES \
+         (1, 'a', 10, 1), (2, 'a', 20, 2), (3, 'b', 30, 3), (4, 'c', 40, 4)",
+    )
+    .await;
+    db.execute(&format!(
+        "CREATE SERVER loopback_hwm FOREIGN DATA WRAPPER postgres_fdw \
+         OPTIONS (dbname '{db_name}', host '127.0.0.1', port '5432')"
+    ))
+    .await;
+    db.execute(
+        "CREATE USER MAPPING FOR CURRENT_USER SERVER loopback_hwm \
+         OPTIONS (user 'postgres')",
+    )
+    .await;
+    db.execute(
+        "CREATE FOREIGN TABLE ft_hwm_remote \
+         (id INT, grp TEXT, val INT, ver BIGINT, deleted BOOLEAN) \
+         SERVER loopback_hwm OPTIONS (table_name 'ft_hwm_src')",
+    )
+    .await;
+
+    db.alter_system_set_and_wait("pg_trickle.foreign_table_polling", "on", "on")
+        .await;
+
+    db.execute(
+        "SELECT pgtrickle.configure_foreign_table_polling('ft_hwm_remote', 'ver', \
+         ARRAY['id'], soft_delete_column => 'deleted', reconcile_interval => NULL)",
+    )
+    .await;
+
+    let query = "SELECT grp, SUM(val) AS total FROM ft_hwm_remote \
+                 WHERE NOT deleted GROUP BY grp";
+    db.create_st("ft_hwm_st", query, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("ft_hwm_st", query).await;
+
+    // The first poll reconciles in full and records the high-water mark,
+    // stored as the watermark column's own BIGINT.
+    db.refresh_st("ft_hwm_st").await;
+    let stable_name: String = db
+        .query_scalar(
+            "SELECT source_stable_name FROM pgtrickle.pgt_change_tracking \
+             WHERE source_relid = 'ft_hwm_remote'::regclass",
+        )
+        .await;
+    let mark_sql = format!("SELECT high_water_mark FROM pgtrickle_changes.hwm_{stable_name}");
+    let mark: i64 = db.query_scalar(&mark_sql).await;
+    assert_eq!(mark, 4);
+    let key_indexed: bool = db
+        .query_scalar(&format!(
+            "SELECT EXISTS(SELECT 1 FROM pg_indexes WHERE schemaname = 'pgtrickle_changes' \
+             AND indexname = 'snapshot_{stable_name}_poll_key')"
+        ))
+        .await;
+    assert!(key_indexed, "snapshot key columns should be indexed");
+
+    // Update, insert and soft delete, each above the mark.
+    db.execute("UPDATE ft_hwm_src SET val = 25, ver = 5 WHERE id = 2")
+        .await;
+    db.execute("INSERT INTO ft_hwm_src (id, grp, val, ver) VALUES (5, 'd', 50, 6)")
+        .await;
+    db.execute("UPDATE ft_hwm_src SET deleted = true, ver = 7 WHERE id = 3")
+        .await;
+    db.refresh_st("ft_hwm_st").await;
+    db.assert_st_matches_query("ft_hwm_st", query).await;
+    let mark: i64 = db.query_scalar(&mark_sql).await;
+    assert_eq!(mark, 7);
+
+    // A hard delete is invisible to the incremental poll...
+    db.execute("DELETE FROM ft_hwm_src WHERE id = 4").await;
+    db.refresh_st("ft_hwm_st").await;
+    let stale: i64 = db
+        .query_scalar("SELECT count(*) FROM ft_hwm_st WHERE grp = 'c'")
+        .await;
+    assert_eq!(stale, 1, "hard delete should wait for reconciliation");
+
+    // ...until the next reconciliation.
+    db.execute(
+        "SELECT pgtrickle.configure_foreign_table_polling('ft_hwm_remote', 'ver', \
+         ARRAY['id'], soft_delete_column => 'deleted', reconcile_interval => NULL)",
+    )
+    .await;
+    db.refresh_st("ft_hwm_st").await;
+    db.assert_st_matches_query("ft_hwm_st", query).await;
+
+    db.alter_system_reset_and_wait("pg_trickle.foreign_table_polling", "off")
+        .await;
+}
@@ -0,0 +1,120 @@
+//! E2E tests for joins whose sides are arbitrary derived tables.
+//!
+//! A join side's current state is rebuilt from its operator subtree, so
+//! aggregates, filtered aggregates, DISTINCT and UNION ALL subqueries can be
+//! joined directly in DIFFERENTIAL mode without moving them into a CTE.
+//!
+//! Prerequisites: `./tests/build_e2e_image.sh`
+
+mod e2e;
+
+use e2e::E2eDb;
+
+#[tokio::test]
+async fn test_join_aggregate_subquery_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE js_cust (id INT PRIMARY KEY, name TEXT)")
+        .await;
+    db.execute("CREATE TABLE js_ord (id INT PRIMARY KEY, cust_id INT, amount INT)")
+        .await;
+    db.execute("INSERT INTO js_cust VALUES (1, 'alice'), (2, 'bob'), (3, 'carol')")
+        .await;
+    db.execute("INSERT INTO js_ord VALUES (1, 1, 10), (2, 1, 20), (3, 2, 5), (4, 3, -7)")
+        .await;
+
+    let query = "SELECT c.id, c.name, s.total \
+                 FROM js_cust c \
+                 JOIN (SELECT o.cust_id, sum(o.amount) AS total \
+                       FROM js_ord o WHERE o.amount > 0 GROUP BY o.cust_id) s \
+                   ON s.cust_id = c.id";
+    db.create_st("js_agg_st", query, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("js_agg_st", query).await;
+
+    // Changes on both sides of the join
+    db.execute("INSERT INTO js_ord VALUES (5, 2, 15), (6, 3, 9)")
+        .await;
+    db.execute("UPDATE js_cust SET name = 'alicia' WHERE id = 1")
+        .await;
+    db.refresh_st("js_agg_st").await;
+    db.assert_st_matches_query("js_agg_st", query).await;
+
+    db.execute("DELETE FROM js_ord WHERE cust_id = 1").await;
+    db.execute("INSERT INTO js_cust VALUES (4, 'dave')").await;
+    db.execute("INSERT INTO js_ord VALUES (7, 4, 1)").await;
+    db.refresh_st("js_agg_st").await;
+    db.assert_st_matches_query("js_agg_st", query).await;
+}
+
+#[tokio::test]
+async fn test_join_union_all_subquery_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE js_acct (id INT PRIMARY KEY, owner TEXT)")
+        .await;
+    db.execute("CREATE TABLE js_cred (id INT PRIMARY KEY, acct INT, amt INT)")
+        .await;
+    db.execute("CREATE TABLE js_deb (id INT PRIMARY KEY, acct INT, amt INT)")
+        .await;
+    db.execute("INSERT INTO js_acct VALUES (1, 'x'), (2, 'y')")
+        .await;
+    db.execute("INSERT INTO js_cred VALUES (1, 1, 100), (2, 2, 50)")
+        .await;
+    db.execute("INSERT INTO js_deb VALUES (1, 1, 30)").await;
+
+    let query = "SELECT a.id, a.owner, m.amt \
+                 FROM js_acct a \
+                 JOIN (SELECT acct, amt FROM js_cred \
+                       UNION ALL \
+                       SELECT acct, -amt FROM js_deb) m \
+                   ON m.acct = a.id";
+    db.create_st("js_union_st", query, "1m", "DIFFERENTIAL")
+        .await;
+    db.assert_st_matches_query("js_union_st", query).await;
+
+    db.execute("INSERT INTO js_deb VALUES (2, 2, 20)").await;
+    db.execute("INSERT INTO js_acct VALUES (3, 'z')").await;
+    db.execute("INSERT INTO js_cred VALUES (3, 3, 5)").await;
+    db.refresh_st("js_union_st").await;
+    db.assert_st_matches_query("js_union_st", query).await;
+
+    db.execute("DELETE FROM js_acct WHERE id = 1").await;
+    db.refresh_st("js_union_st").await;
+    db.assert_st_matches_query("js_union_st", query).await;
+}
+
+#[tokio::test]
+async fn test_join_distinct_subquery_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE js_prod (id INT PRIMARY KEY, name TEXT)")
+        .await;
+    db.execute("CREATE TABLE js_tag (id INT PRIMARY KEY, prod_id INT, tag TEXT)")
+        .await;
+    db.execute("INSERT INTO js_prod VALUES (1, 'apple'), (2, 'pear')")
+        .await;
+    db.execute(
+        "INSERT INTO js_tag VALUES (1, 1, 'fruit'), (2, 1, 'fruit'), (3, 1, 'red'), (4, 2, 'fruit')",
+    )
+    .await;
+
+    let query = "SELECT p.name, t.tag \
+                 FROM js_prod p \
+                 JOIN (SELECT DISTINCT prod_id, tag FROM js_tag) t \
+                   ON t.prod_id = p.id";
+    db.create_st("js_distinct_st", query, "1m", "DIFFERENTIAL")
+        .await;
+    db.assert_st_matches_query("js_distinct_st", query).await;
+
+    db.execute("DELETE FROM js_tag WHERE id = 1").await;
+    db.execute("UPDATE js_prod SET name = 'green apple' WHERE id = 1")
+        .await;
+    db.refresh_st("js_distinct_st").await;
+    db.assert_st_matches_query("js_distinct_st", query).await;
+
+    db.execute("DELETE FROM js_tag WHERE id = 2").await;
+    db.execute("INSERT INTO js_tag VALUES (5, 2, 'green')")
+        .await;
+    db.refresh_st("js_distinct_st").await;
+    db.assert_st_matches_query("js_distinct_st", query).await;
+}
@@ -0,0 +1,173 @@
+//! MINMAX-MS: Ordered multiset side tables for MIN/MAX aggregates.
+//!
+//! Verifies that:
+//! 1. The side table counts duplicated values, so deleting one copy of an
+//!    extremum keeps it and deleting the last copy falls back to the next
+//!    value in index order
+//! 2. A row moving between groups retracts its value from the old group's
+//!    multiset and tightens the new group's extremum
+//! 3. NULLs and rows rejected by `FILTER` never enter the multiset, and a
+//!    group whose multiset empties gets a NULL extremum
+//! 4. Dropping the stream table drops its side tables
+
+mod e2e;
+
+use e2e::E2eDb;
+
+async fn create_with_multiset(db: &E2eDb, name: &str, query: &str) -> i64 {
+    db.execute_seq(&[
+        "SET pg_trickle.minmax_multiset = on",
+        &format!(
+            "SELECT pgtrickle.create_stream_table('{name}', $${query}$$, '1m', 'DIFFERENTIAL')"
+        ),
+    ])
+    .await;
+    db.pgt_id(name).await
+}
+
+#[tokio::test]
+async fn test_minmax_multiset_duplicate_extremum() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE mm_bids (id INT PRIMARY KEY, lot TEXT, price INT)")
+        .await;
+    db.execute(
+        "INSERT INTO mm_bids VALUES \
+         (1, 'a', 10), (2, 'a', 10), (3, 'a', 12), (4, 'a', 15), (5, 'b', 7)",
+    )
+    .await;
+
+    let query = "SELECT lot, MIN(price) AS best FROM mm_bids GROUP BY lot";
+    let pgt_id = create_with_multiset(&db, "mm_best_bid", query).await;
+    let tables = db.side_tables("minmax", pgt_id).await;
+    assert_eq!(tables.len(), 1);
+    let cnt_of = |price: i32| {
+        format!(
+            "SELECT sum(cnt)::bigint FROM {} WHERE val = {price}",
+            tables[0]
+        )
+    };
+    assert_eq!(db.query_scalar::<i64>(&cnt_of(10)).await, 2);
+
+    // One copy of the minimum remains: the extremum must not move.
+    db.execute("DELETE FROM mm_bids WHERE id = 1").await;
+    db.refresh_st("mm_best_bid").await;
+    assert_eq!(db.query_scalar::<i64>(&cnt_of(10)).await, 1);
+    let best: i32 = db
+        .query_scalar("SELECT best FROM public.mm_best_bid WHERE lot = 'a'")
+        .await;
+    assert_eq!(best, 10);
+
+    // The last copy goes: the next value in the multiset takes over.
+    db.execute("DELETE FROM mm_bids WHERE id = 2").await;
+    db.refresh_st("mm_best_bid").await;
+    let best: i32 = db
+        .query_scalar("SELECT best FROM public.mm_best_bid WHERE lot = 'a'")
+        .await;
+    assert_eq!(best, 12);
+    db.assert_st_matches_query("public.mm_best_bid", query)
+        .await;
+}
+
+#[tokio::test]
+async fn test_minmax_multiset_value_moves_between_groups() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE mm_orders (id INT PRIMARY KEY, region TEXT, amount INT)")
+        .await;
+    db.execute(
+        "INSERT INTO mm_orders \
+         SELECT g, CASE WHEN g % 2 = 0 THEN 'east' ELSE 'west' END, g \
+         FROM generate_series(1, 200) g",
+    )
+    .await;
+
+    let query = "SELECT region, MIN(amount) AS lo, MAX(amount) AS hi \
+                 FROM mm_orders GROUP BY region";
+    create_with_multiset(&db, "mm_extremes", query).await;
+
+    // West's maximum (199) and minimum (1) both move east in one refresh:
+    // west falls back to 197 and 3, east's range widens to 1 .. 200.
+    db.execute("UPDATE mm_orders SET region = 'east' WHERE id IN (1, 199)")
+        .await;
+    db.refresh_st("mm_extremes").await;
+    let (lo, hi): (i32, i32) = region_extremes(&db, "west").await;
+    assert_eq!((lo, hi), (3, 197));
+    let (lo, hi): (i32, i32) = region_extremes(&db, "east").await;
+    assert_eq!((lo, hi), (1, 200));
+    db.assert_st_matches_query("public.mm_extremes", query)
+        .await;
+}
+
+async fn region_extremes(db: &E2eDb, region: &str) -> (i32, i32) {
+    let lo: i32 = db
+        .query_scalar(&format!(
+            "SELECT lo FROM public.mm_extremes WHERE region = '{region}'"
+        ))
+        .await;
+    let hi: i32 = db
+        .query_scalar(&format!(
+            "SELECT hi FROM public.mm_extremes WHERE region = '{region}'"
+        ))
+        .await;
+    (lo, hi)
+}
+
+#[tokio::test]
+async fn test_minmax_multiset_nulls_and_filter() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE mm_readings (id INT PRIMARY KEY, ok BOOLEAN, temp NUMERIC)")
+        .await;
+    db.execute(
+        "INSERT INTO mm_readings VALUES \
+         (1, true, 20.5), (2, true, NULL), (3, false, 99.0), (4, true, 21.0)",
+    )
+    .await;
+
+    let query = "SELECT MAX(temp) FILTER (WHERE ok) AS peak FROM mm_readings";
+    let pgt_id = create_with_multiset(&db, "mm_peak", query).await;
+    let tables = db.side_tables("minmax", pgt_id).await;
+    assert_eq!(tables.len(), 1);
+
+    // Only the two non-NULL readings that pass the filter are stored.
+    let stored: i64 = db
+        .query_scalar(&format!("SELECT sum(cnt)::bigint FROM {}", tables[0]))
+        .await;
+    assert_eq!(stored, 2);
+
+    db.execute("DELETE FROM mm_readings WHERE id = 4").await;
+    db.refresh_st("mm_peak").await;
+    db.assert_st_matches_query("public.mm_peak", query).await;
+
+    // Only a NULL and a filtered-out reading remain: the peak is NULL.
+    db.execute("DELETE FROM mm_readings WHERE id = 1").await;
+    db.refresh_st("mm_peak").await;
+    let peak: Option<String> = db
+        .query_scalar_opt("SELECT peak::text FROM public.mm_peak")
+        .await;
+    assert_eq!(peak, None);
+    db.assert_st_matches_query("public.mm_peak", query).await;
+}
+
+#[tokio::test]
+async fn test_minmax_multiset_dropped_with_stream_table() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE mm_items (id INT PRIMARY KEY, cat TEXT, price INT)")
+        .await;
+    db.execute("INSERT INTO mm_items VALUES (1, 'a', 10), (2, 'a', 20), (3, 'b', 5)")
+        .await;
+
+    let pgt_id = create_with_multiset(
+        &db,
+        "mm_cheapest",
+        "SELECT cat, MIN(price) AS cheapest, MAX(price) AS dearest FROM mm_items GROUP BY cat",
+    )
+    .await;
+    assert_eq!(db.side_tables("minmax", pgt_id).await.len(), 2);
+
+    db.execute("SELECT pgtrickle.drop_stream_table('mm_cheapest')")
+        .await;
+    assert!(db.side_tables("minmax", pgt_id).await.is_empty());
+}
@@ -242,6 +242,61 @@ async fn test_mixed_matview_upstream_full_mode() {
     .await;
 }
 
+/// Table → Materialized View → Stream Table (DIFFERENTIAL, MV-CDC).
+/// The matview delta is captured by REFRESH MATERIALIZED VIEW, not by the
+/// stream table refresh.
+#[tokio::test]
+async fn test_mixed_matview_upstream_differential_refresh_capture() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE mc_sales (id SERIAL PRIMARY KEY, region TEXT, amount INT)")
+        .await;
+    db.execute("INSERT INTO mc_sales (region, amount) VALUES ('East', 100), ('West', 50)")
+        .await;
+    db.execute(
+        "CREATE MATERIALIZED VIEW mc_mv_totals AS
+         SELECT region, SUM(amount) AS total FROM mc_sales GROUP BY region",
+    )
+    .await;
+    db.execute("CREATE UNIQUE INDEX ON mc_mv_totals (region)")
+        .await;
+
+    db.alter_system_set_and_wait("pg_trickle.matview_polling", "on", "on")
+        .await;
+
+    let query = "SELECT region, total FROM mc_mv_totals WHERE total > 60";
+    db.create_st("mc_st_totals", query, "1m", "DIFFERENTIAL")
+        .await;
+    db.assert_st_matches_query("mc_st_totals", query).await;
+
+    let mv_oid: i32 = db.table_oid("mc_mv_totals").await;
+    let buffer = db.change_buffer_table(mv_oid as i64).await;
+
+    // Base-table changes do not reach the buffer until the matview refreshes.
+    db.execute("INSERT INTO mc_sales (region, amount) VALUES ('West', 40), ('North', 75)")
+        .await;
+    assert_eq!(db.count(&buffer).await, 0);
+
+    db.execute("REFRESH MATERIALIZED VIEW mc_mv_totals").await;
+    assert_eq!(
+        db.count(&buffer).await,
+        3,
+        "delete + insert for West, insert for North"
+    );
+    db.refresh_st("mc_st_totals").await;
+    db.assert_st_matches_query("mc_st_totals", query).await;
+
+    db.execute("DELETE FROM mc_sales WHERE region = 'East'")
+        .await;
+    db.execute("REFRESH MATERIALIZED VIEW CONCURRENTLY mc_mv_totals")
+        .await;
+    db.refresh_st("mc_st_totals").await;
+    db.assert_st_matches_query("mc_st_totals", query).await;
+
+    db.alter_system_reset_and_wait("pg_trickle.matview_polling", "off")
+        .await;
+}
+
 // ── Regular table + view combined upstream ───────────────────────────────
 
 /// Stream table joins a raw table with a view (mixed upstream sources).
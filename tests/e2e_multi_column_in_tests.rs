@@ -2,8 +2,10 @@
 //!
 //! Verifies that:
 //! 1. Single-column IN (subquery) works correctly with DIFFERENTIAL mode
-//! 2. Multi-column IN (subquery) is detected and returns a structured error
-//!    with a rewrite suggestion to EXISTS
+//! 2. Multi-column IN (subquery) is lowered to a semi-join on the composite key
+//! 3. Multi-column NOT IN (subquery) is lowered to an anti-join that keeps
+//!    SQL NULL semantics
+//! 4. Single-column NOT IN (subquery) keeps the same NULL semantics
 
 mod e2e;
 
@@ -65,10 +67,10 @@ async fn test_single_column_in_subquery_differential() {
     .await;
 }
 
-// ── Multi-column IN: structured error test ─────────────────────────────
+// ── Multi-column IN: composite semi-join ───────────────────────────────
 
 #[tokio::test]
-async fn test_multi_column_in_subquery_returns_error() {
+async fn test_multi_column_in_subquery_differential() {
     let db = E2eDb::new().await.with_extension().await;
 
     db.execute(
@@ -89,35 +91,112 @@ async fn test_multi_column_in_subquery_returns_error() {
     )
     .await;
 
-    db.execute("INSERT INTO mc_orders VALUES (1, 10, 1, 100), (2, 20, 2, 200)")
+    db.execute("INSERT INTO mc_orders VALUES (1, 10, 1, 100), (2, 20, 2, 200), (3, 10, 2, 300)")
         .await;
     db.execute("INSERT INTO mc_regions VALUES (10, 1), (20, 2)")
         .await;
 
-    // Multi-column IN subquery — should be rejected with a clear error
-    let result = db
-        .try_execute(
-            "SELECT pgtrickle.create_stream_table(\
-                'mc_in_st',\
-                $$SELECT id, amount FROM mc_orders \
-                  WHERE (cust_id, region_id) IN \
-                        (SELECT cust_id, region_id FROM mc_regions)$$,\
-                '24h', 'DIFFERENTIAL'\
-             )",
-        )
-        .await;
-
-    let err = result
-        .expect_err("Multi-column IN should be rejected")
-        .to_string();
-    assert!(
-        err.contains("multi-column IN") || err.contains("not supported"),
-        "Expected structured error about multi-column IN, got: {err}"
-    );
-    assert!(
-        err.contains("EXISTS"),
-        "Error should suggest EXISTS rewrite, got: {err}"
-    );
+    let q = "SELECT id, amount FROM mc_orders \
+             WHERE (cust_id, region_id) IN (SELECT cust_id, region_id FROM mc_regions)";
+    db.create_st("mc_in_st", q, "24h", "DIFFERENTIAL").await;
+    // (10, 2) matches neither pair even though both values appear.
+    assert_eq!(db.count("public.mc_in_st").await, 2);
+    db.assert_st_matches_query("public.mc_in_st", q).await;
+
+    db.execute("INSERT INTO mc_regions VALUES (10, 2)").await;
+    db.refresh_st("mc_in_st").await;
+    db.assert_st_matches_query("public.mc_in_st", q).await;
+
+    db.execute("DELETE FROM mc_regions WHERE cust_id = 10 AND region_id = 1")
+        .await;
+    db.execute("UPDATE mc_orders SET region_id = 2 WHERE id = 2")
+        .await;
+    db.refresh_st("mc_in_st").await;
+    db.assert_st_matches_query("public.mc_in_st", q).await;
+}
+
+// ── Multi-column NOT IN: composite anti-join with NULLs ────────────────
+
+#[tokio::test]
+async fn test_multi_column_not_in_null_semantics_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute(
+        "CREATE TABLE mn_items (id INT PRIMARY KEY, tenant_id INT, entity_id INT, label TEXT)",
+    )
+    .await;
+    db.execute("CREATE TABLE mn_blocked (id INT PRIMARY KEY, tenant_id INT, entity_id INT)")
+        .await;
+
+    db.execute(
+        "INSERT INTO mn_items VALUES \
+         (1, 1, 1, 'a'), (2, 1, 2, 'b'), (3, 2, 1, 'c'), (4, NULL, 5, 'd'), (5, 3, 3, 'e')",
+    )
+    .await;
+    db.execute("INSERT INTO mn_blocked VALUES (1, 1, 1)").await;
+
+    let q = "SELECT id, label FROM mn_items \
+             WHERE (tenant_id, entity_id) NOT IN (SELECT tenant_id, entity_id FROM mn_blocked)";
+    db.create_st("mn_not_in_st", q, "24h", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("public.mn_not_in_st", q).await;
+
+    // An inner row with a NULL tenant makes every item with entity 2
+    // unknown — but (1, 1) and (2, 1) still differ on the entity.
+    db.execute("INSERT INTO mn_blocked VALUES (2, NULL, 2)")
+        .await;
+    db.refresh_st("mn_not_in_st").await;
+    db.assert_st_matches_query("public.mn_not_in_st", q).await;
+
+    // An inner row (NULL, NULL) makes every comparison unknown.
+    db.execute("INSERT INTO mn_blocked VALUES (3, NULL, NULL)")
+        .await;
+    db.refresh_st("mn_not_in_st").await;
+    db.assert_st_matches_query("public.mn_not_in_st", q).await;
+    assert_eq!(db.count("public.mn_not_in_st").await, 0);
+
+    // Removing it brings the rows back.
+    db.execute("DELETE FROM mn_blocked WHERE id IN (2, 3)")
+        .await;
+    db.refresh_st("mn_not_in_st").await;
+    db.assert_st_matches_query("public.mn_not_in_st", q).await;
+}
+
+// ── Single-column NOT IN: NULL in the subquery ─────────────────────────
+
+#[tokio::test]
+async fn test_single_column_not_in_null_semantics_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE sn_items (id INT PRIMARY KEY, cat INT, label TEXT)")
+        .await;
+    db.execute("CREATE TABLE sn_blocked (id INT PRIMARY KEY, cat INT)")
+        .await;
+    db.execute("INSERT INTO sn_items VALUES (1, 1, 'a'), (2, 2, 'b'), (3, NULL, 'c')")
+        .await;
+    db.execute("INSERT INTO sn_blocked VALUES (1, 1)").await;
+
+    let q = "SELECT id, label FROM sn_items \
+             WHERE cat NOT IN (SELECT cat FROM sn_blocked)";
+    db.create_st("sn_not_in_st", q, "24h", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("public.sn_not_in_st", q).await;
+    assert_eq!(db.count("public.sn_not_in_st").await, 1);
+
+    // A NULL in the subquery makes every NOT IN unknown.
+    db.execute("INSERT INTO sn_blocked VALUES (2, NULL)").await;
+    db.refresh_st("sn_not_in_st").await;
+    db.assert_st_matches_query("public.sn_not_in_st", q).await;
+    assert_eq!(db.count("public.sn_not_in_st").await, 0);
+
+    db.execute("DELETE FROM sn_blocked WHERE id = 2").await;
+    db.refresh_st("sn_not_in_st").await;
+    db.assert_st_matches_query("public.sn_not_in_st", q).await;
+    assert_eq!(db.count("public.sn_not_in_st").await, 1);
+
+    // With an empty subquery even the NULL item qualifies.
+    db.execute("DELETE FROM sn_blocked").await;
+    db.refresh_st("sn_not_in_st").await;
+    db.assert_st_matches_query("public.sn_not_in_st", q).await;
+    assert_eq!(db.count("public.sn_not_in_st").await, 3);
 }
 
 // ── Multi-column IN rewritten as EXISTS: positive test ─────────────────
@@ -0,0 +1,192 @@
+//! ORDSET-HIST: Value-count histograms for ordered-set aggregates.
+//!
+//! Verifies that:
+//! 1. MODE picks the most frequent value from the histogram counts and breaks
+//!    ties by sort order, also when a delete or an insert creates a new tie
+//! 2. PERCENTILE_DISC follows the cumulative count across a value repeated
+//!    over the percentile boundary, and PERCENTILE_CONT interpolates between
+//!    neighbouring histogram values, in both sort directions
+//! 3. The rounded histogram stays small for continuous data and keeps
+//!    PERCENTILE_CONT within its error bound
+//! 4. Histograms are not created when the setting is off (the default)
+
+mod e2e;
+
+use e2e::E2eDb;
+
+async fn create_with_histogram(db: &E2eDb, name: &str, query: &str, sketch_digits: i32) -> i64 {
+    db.execute_seq(&[
+        "SET pg_trickle.ordered_set_histogram = on",
+        &format!("SET pg_trickle.percentile_sketch_digits = {sketch_digits}"),
+        &format!(
+            "SELECT pgtrickle.create_stream_table('{name}', $${query}$$, '1m', 'DIFFERENTIAL')"
+        ),
+    ])
+    .await;
+    db.pgt_id(name).await
+}
+
+#[tokio::test]
+async fn test_ordered_set_histogram_mode_ties() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE osh_votes (id INT PRIMARY KEY, poll INT, choice TEXT)")
+        .await;
+    db.execute(
+        "INSERT INTO osh_votes VALUES \
+         (1, 1, 'b'), (2, 1, 'b'), (3, 1, 'c'), (4, 1, 'c'), (5, 1, 'c'), (6, 1, 'a')",
+    )
+    .await;
+
+    let query = "SELECT poll, mode() WITHIN GROUP (ORDER BY choice) AS winner \
+                 FROM osh_votes GROUP BY poll";
+    let pgt_id = create_with_histogram(&db, "osh_winner", query, 0).await;
+    let tables = db.side_tables("hist", pgt_id).await;
+    assert_eq!(tables.len(), 1);
+    let winner = "SELECT winner FROM public.osh_winner WHERE poll = 1";
+    assert_eq!(db.query_scalar::<String>(winner).await, "c");
+
+    // 'b' and 'c' tie at two votes: the first in sort order wins.
+    db.execute("DELETE FROM osh_votes WHERE id = 5").await;
+    db.refresh_st("osh_winner").await;
+    let c_votes: i64 = db
+        .query_scalar(&format!(
+            "SELECT sum(cnt)::bigint FROM {} WHERE val = 'c'",
+            tables[0]
+        ))
+        .await;
+    assert_eq!(c_votes, 2);
+    assert_eq!(db.query_scalar::<String>(winner).await, "b");
+
+    // A new vote for 'a' makes a three-way tie; 'a' sorts first.
+    db.execute("INSERT INTO osh_votes VALUES (7, 1, 'a')").await;
+    db.refresh_st("osh_winner").await;
+    assert_eq!(db.query_scalar::<String>(winner).await, "a");
+    db.assert_st_matches_query("public.osh_winner", query).await;
+}
+
+#[tokio::test]
+async fn test_ordered_set_histogram_percentile_boundaries() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE osh_requests (id INT PRIMARY KEY, endpoint TEXT, latency_ms INT)")
+        .await;
+    // ep1: 10, 20, 20, 20, 40 — the median sits inside the run of 20s.
+    // ep2: 5, 15 — PERCENTILE_CONT interpolates between the two values.
+    db.execute(
+        "INSERT INTO osh_requests VALUES \
+         (1, 'ep1', 10), (2, 'ep1', 20), (3, 'ep1', 20), (4, 'ep1', 20), (5, 'ep1', 40), \
+         (6, 'ep2', 5), (7, 'ep2', 15)",
+    )
+    .await;
+
+    let query = "SELECT endpoint, \
+                 percentile_disc(0.5) WITHIN GROUP (ORDER BY latency_ms) AS p50_disc, \
+                 percentile_cont(0.25) WITHIN GROUP (ORDER BY latency_ms) AS p25, \
+                 percentile_cont(0.25) WITHIN GROUP (ORDER BY latency_ms DESC) AS p25_desc \
+                 FROM osh_requests GROUP BY endpoint";
+    let pgt_id = create_with_histogram(&db, "osh_latency", query, 0).await;
+    assert_eq!(db.side_tables("hist", pgt_id).await.len(), 3);
+    db.assert_st_matches_query("public.osh_latency", query)
+        .await;
+
+    let p25: f64 = db
+        .query_scalar("SELECT p25 FROM public.osh_latency WHERE endpoint = 'ep2'")
+        .await;
+    assert_eq!(p25, 7.5);
+
+    // Two of the three 20s go: ep1's median falls back to the single 20,
+    // and its quartiles now interpolate between 10 and 20 (ascending) and
+    // between 40 and 20 (descending).
+    db.execute("DELETE FROM osh_requests WHERE id IN (2, 3)")
+        .await;
+    db.refresh_st("osh_latency").await;
+    db.assert_st_matches_query("public.osh_latency", query)
+        .await;
+    let (p25, p25_desc): (f64, f64) = (
+        db.query_scalar("SELECT p25 FROM public.osh_latency WHERE endpoint = 'ep1'")
+            .await,
+        db.query_scalar("SELECT p25_desc FROM public.osh_latency WHERE endpoint = 'ep1'")
+            .await,
+    );
+    assert_eq!((p25, p25_desc), (15.0, 30.0));
+
+    // NULL latencies leave the histogram; an emptied group loses its row.
+    db.execute("UPDATE osh_requests SET latency_ms = NULL WHERE id = 6")
+        .await;
+    db.refresh_st("osh_latency").await;
+    db.assert_st_matches_query("public.osh_latency", query)
+        .await;
+    db.execute("DELETE FROM osh_requests WHERE endpoint = 'ep2'")
+        .await;
+    db.refresh_st("osh_latency").await;
+    db.assert_st_matches_query("public.osh_latency", query)
+        .await;
+    assert_eq!(db.count("public.osh_latency").await, 1);
+}
+
+#[tokio::test]
+async fn test_ordered_set_histogram_rounded() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE osh_samples (id INT PRIMARY KEY, seconds DOUBLE PRECISION)")
+        .await;
+    db.execute("INSERT INTO osh_samples SELECT g, random() * 1000 FROM generate_series(1, 5000) g")
+        .await;
+
+    let pgt_id = create_with_histogram(
+        &db,
+        "osh_p99",
+        "SELECT percentile_cont(0.99) WITHIN GROUP (ORDER BY seconds) AS p99 FROM osh_samples",
+        3,
+    )
+    .await;
+    let tables = db.side_tables("hist", pgt_id).await;
+    assert_eq!(tables.len(), 1);
+    assert!(
+        tables[0].ends_with("_s3"),
+        "rounded histogram: {}",
+        tables[0]
+    );
+
+    db.execute("DELETE FROM osh_samples WHERE id % 4 = 0").await;
+    db.execute("INSERT INTO osh_samples SELECT g, 2000 + g FROM generate_series(6001, 6040) g")
+        .await;
+    db.refresh_st("osh_p99").await;
+
+    // Three significant digits leave at most 900 buckets per power of ten,
+    // far fewer than the distinct samples.
+    let buckets: i64 = db.count(&tables[0]).await;
+    assert!(buckets < 2800, "rounded histogram has {buckets} buckets");
+
+    let rel_err: f64 = db
+        .query_scalar(
+            "SELECT abs(st.p99 - q.p99) / q.p99 FROM public.osh_p99 st, \
+             (SELECT percentile_cont(0.99) WITHIN GROUP (ORDER BY seconds) AS p99 \
+              FROM osh_samples) q",
+        )
+        .await;
+    assert!(rel_err < 0.01, "rounded p99 relative error {rel_err}");
+}
+
+#[tokio::test]
+async fn test_ordered_set_histogram_off_by_default() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE osh_plain (id INT PRIMARY KEY, grp INT, v INT)")
+        .await;
+    db.execute("INSERT INTO osh_plain VALUES (1, 1, 10), (2, 1, 20), (3, 2, 30)")
+        .await;
+
+    let query = "SELECT grp, percentile_disc(0.5) WITHIN GROUP (ORDER BY v) AS med \
+                 FROM osh_plain GROUP BY grp";
+    db.create_st("osh_plain_st", query, "1m", "DIFFERENTIAL")
+        .await;
+    let pgt_id = db.pgt_id("osh_plain_st").await;
+    assert!(db.side_tables("hist", pgt_id).await.is_empty());
+
+    db.execute("DELETE FROM osh_plain WHERE id = 1").await;
+    db.refresh_st("osh_plain_st").await;
+    db.assert_st_matches_query("public.osh_plain_st", query)
+        .await;
+}
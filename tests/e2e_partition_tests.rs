@@ -2,7 +2,7 @@
 //!
 //! Validates that pg_trickle works correctly with PostgreSQL's declarative
 //! table partitioning: RANGE, LIST, and HASH partitioned source tables,
-//! ATTACH PARTITION detection and reinitialize, and WAL publication
+//! ATTACH/DETACH PARTITION applied as deltas, and WAL publication
 //! configuration for partitioned tables.
 //!
 //! Prerequisites: `./tests/build_e2e_image.sh`
@@ -237,10 +237,10 @@ async fn test_partition_hash_source() {
         .await;
 }
 
-// ── PT2: ATTACH PARTITION detection ────────────────────────────────────
+// ── PT2/PT5: ATTACH / DETACH PARTITION as deltas ───────────────────────
 
 #[tokio::test]
-async fn test_partition_attach_triggers_reinit() {
+async fn test_partition_attach_captured_as_delta() {
     let db = E2eDb::new().await.with_extension().await;
 
     // Create partitioned source with one partition
@@ -293,36 +293,57 @@ async fn test_partition_attach_triggers_reinit() {
     )
     .await;
 
-    // ATTACH PARTITION — this should trigger reinit detection
+    // ATTACH PARTITION — the attached rows become insert deltas
     db.execute(
         "ALTER TABLE attach_orders ATTACH PARTITION attach_orders_2026
             FOR VALUES FROM ('2026-01-01') TO ('2027-01-01')",
     )
     .await;
 
-    // Check that the stream table is marked for reinit
     let needs_reinit: bool = db
         .query_scalar(
             "SELECT needs_reinit FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'attach_st'",
         )
         .await;
     assert!(
-        needs_reinit,
-        "ATTACH PARTITION should mark dependent stream table for reinitialize"
+        !needs_reinit,
+        "ATTACH PARTITION should not reinitialize the dependent stream table"
     );
 
-    // Refresh should pick up all data including newly attached partition
+    let source_oid: i32 = db.table_oid("attach_orders").await;
+    let buffer = db.change_buffer_table(source_oid as i64).await;
+    let inserts: i64 = db
+        .query_scalar(&format!("SELECT count(*) FROM {buffer} WHERE action = 'I'"))
+        .await;
+    assert_eq!(inserts, 2, "Attached rows should be buffered as inserts");
+
+    // A differential refresh picks up the attached partition's rows
     db.refresh_st("attach_st").await;
 
     let count: i64 = db.count("attach_st").await;
     assert_eq!(
         count, 3,
-        "After reinit, all rows including attached partition data should be visible"
+        "All rows including attached partition data should be visible"
     );
+    db.assert_st_matches_query(
+        "attach_st",
+        "SELECT id, created_at, total FROM attach_orders",
+    )
+    .await;
+
+    // Later DML on the attached partition is captured as usual
+    db.execute("UPDATE attach_orders SET total = 250.00 WHERE created_at = '2026-03-01'")
+        .await;
+    db.refresh_st("attach_st").await;
+    db.assert_st_matches_query(
+        "attach_st",
+        "SELECT id, created_at, total FROM attach_orders",
+    )
+    .await;
 }
 
 #[tokio::test]
-async fn test_partition_detach_triggers_reinit() {
+async fn test_partition_detach_captured_as_delta() {
     let db = E2eDb::new().await.with_extension().await;
 
     db.execute(
@@ -370,25 +391,83 @@ async fn test_partition_detach_triggers_reinit() {
     db.execute("ALTER TABLE detach_orders DETACH PARTITION detach_orders_2026")
         .await;
 
-    // Stream table should be marked for reinit
     let needs_reinit: bool = db
         .query_scalar(
             "SELECT needs_reinit FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'detach_st'",
         )
         .await;
     assert!(
-        needs_reinit,
-        "DETACH PARTITION should mark dependent stream table for reinitialize"
+        !needs_reinit,
+        "DETACH PARTITION should not reinitialize the dependent stream table"
     );
 
     // After refresh, only the remaining partition's data should be visible
     db.refresh_st("detach_st").await;
 
     let count: i64 = db.count("detach_st").await;
-    assert_eq!(
-        count, 1,
-        "After reinit, only remaining partition data should be visible"
-    );
+    assert_eq!(count, 1, "Only remaining partition data should be visible");
+}
+
+#[tokio::test]
+async fn test_partition_detach_differential_aggregate() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute(
+        "CREATE TABLE rot_events (
+            id BIGSERIAL,
+            month DATE NOT NULL,
+            region TEXT NOT NULL,
+            amount INT NOT NULL,
+            PRIMARY KEY (id, month)
+        ) PARTITION BY RANGE (month)",
+    )
+    .await;
+    db.execute(
+        "CREATE TABLE rot_events_01 PARTITION OF rot_events
+            FOR VALUES FROM ('2026-01-01') TO ('2026-02-01')",
+    )
+    .await;
+    db.execute(
+        "CREATE TABLE rot_events_02 PARTITION OF rot_events
+            FOR VALUES FROM ('2026-02-01') TO ('2026-03-01')",
+    )
+    .await;
+    db.execute(
+        "INSERT INTO rot_events (month, region, amount) VALUES
+         ('2026-01-05', 'east', 10), ('2026-01-09', 'west', 20),
+         ('2026-02-03', 'east', 30), ('2026-02-07', 'east', 40)",
+    )
+    .await;
+
+    let query = "SELECT region, SUM(amount) AS total, COUNT(*) AS cnt \
+                 FROM rot_events GROUP BY region";
+    db.create_st("rot_st", query, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("rot_st", query).await;
+
+    // Monthly rotation: retire January, attach March with its data.
+    db.execute("ALTER TABLE rot_events DETACH PARTITION rot_events_01")
+        .await;
+    db.execute("CREATE TABLE rot_events_03 (LIKE rot_events INCLUDING DEFAULTS)")
+        .await;
+    db.execute(
+        "INSERT INTO rot_events_03 (month, region, amount) VALUES ('2026-03-02', 'north', 5)",
+    )
+    .await;
+    db.execute(
+        "ALTER TABLE rot_events ATTACH PARTITION rot_events_03
+            FOR VALUES FROM ('2026-03-01') TO ('2026-04-01')",
+    )
+    .await;
+
+    let needs_reinit: bool = db
+        .query_scalar(
+            "SELECT needs_reinit FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'rot_st'",
+        )
+        .await;
+    assert!(!needs_reinit, "Partition rotation should not reinitialize");
+
+    db.refresh_st("rot_st").await;
+    db.assert_st_matches_query("rot_st", query).await;
 }
 
 // ── PT4: Foreign table source restriction ──────────────────────────────
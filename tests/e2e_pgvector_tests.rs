@@ -228,6 +228,57 @@ async fn test_pgvector_distance_operator_fallback_to_full() {
     db.assert_st_matches_query("dist_st", q).await;
 }
 
+// ═══════════════════════════════════════════════════════════════════════
+// PGVEC-LOCAL: Distance to a constant vector stays DIFFERENTIAL
+// ═══════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn test_pgvector_constant_distance_filter_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+    setup_pgvector(&db).await;
+
+    db.execute(
+        "CREATE TABLE emb_segment (
+            id SERIAL PRIMARY KEY,
+            category TEXT,
+            embedding vector(3)
+        )",
+    )
+    .await;
+    db.execute(
+        "INSERT INTO emb_segment (category, embedding) VALUES
+            ('cat1', '[1,0,0]'),
+            ('cat1', '[0.9,0.1,0]'),
+            ('cat2', '[0,1,0]'),
+            ('cat2', '[0.5,0.5,0]')",
+    )
+    .await;
+
+    let q = "SELECT id, category FROM emb_segment \
+             WHERE embedding <=> '[1,0,0]'::vector < 0.3";
+    db.create_st("segment_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("segment_st", q).await;
+
+    // Rows move in and out of the segment.
+    db.execute("INSERT INTO emb_segment (category, embedding) VALUES ('cat3', '[0.95,0,0.05]')")
+        .await;
+    db.execute("UPDATE emb_segment SET embedding = '[0,0,1]' WHERE id = 2")
+        .await;
+    db.execute("UPDATE emb_segment SET embedding = '[1,0.1,0]' WHERE id = 3")
+        .await;
+    db.execute("DELETE FROM emb_segment WHERE id = 1").await;
+    db.refresh_st("segment_st").await;
+    db.assert_st_matches_query("segment_st", q).await;
+
+    let mode: Option<String> = db
+        .query_scalar_opt(
+            "SELECT effective_refresh_mode FROM pgtrickle.pgt_stream_tables \
+             WHERE pgt_name = 'segment_st'",
+        )
+        .await;
+    assert_eq!(mode.as_deref(), Some("DIFFERENTIAL"));
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // F4-4: HNSW index on centroid stream table
 // ═══════════════════════════════════════════════════════════════════════
@@ -1,8 +1,8 @@
 //! E2E tests for ROWS FROM with multiple set-returning functions.
 //!
-//! Validates the `rewrite_rows_from()` pass that transforms
-//! `ROWS FROM(f1(), f2(), ...)` into either multi-arg `unnest()` (all-unnest
-//! optimisation) or an ordinal-based LEFT JOIN LATERAL chain (general case).
+//! `ROWS FROM(f1(), f2(), ...)` is parsed into a single lateral function
+//! source and maintained by row-scoped recomputation, so zipping, NULL
+//! padding and `WITH ORDINALITY` follow PostgreSQL exactly.
 //!
 //! Prerequisites: `./tests/build_e2e_image.sh`
 
@@ -11,7 +11,7 @@ mod e2e;
 use e2e::E2eDb;
 
 // ═══════════════════════════════════════════════════════════════════════════
-//  All-unnest optimisation (multi-arg unnest merge)
+//  Zipped unnest() calls
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -27,7 +27,7 @@ async fn test_rows_from_dual_unnest_full() {
     )
     .await;
 
-    // ROWS FROM(unnest(names), unnest(scores)) → rewritten to unnest(names, scores)
+    // ROWS FROM(unnest(names), unnest(scores)) zips the two arrays
     db.create_st(
         "rf_dual_unnest",
         "SELECT rf.id, u.name, u.score \
@@ -87,7 +87,7 @@ async fn test_rows_from_triple_unnest_full() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-//  General case (ordinal-based LEFT JOIN LATERAL chain)
+//  Mixed set-returning functions
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -102,8 +102,7 @@ async fn test_rows_from_mixed_srfs_full() {
     )
     .await;
 
-    // ROWS FROM(unnest(arr), generate_series(1, 5)) — mixed SRF types
-    // triggers the general (non-all-unnest) rewrite path.
+    // ROWS FROM(unnest(arr), generate_series(1, 5)) — mixed SRF types.
     db.create_st(
         "rf_mixed_srfs",
         "SELECT m.id, u.val, u.n \
@@ -128,7 +127,7 @@ async fn test_rows_from_mixed_srfs_full() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-//  Differential mode — verifies rewrite works with IVM refresh
+//  Differential mode
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -220,7 +219,7 @@ async fn test_rows_from_dual_unnest_differential_update() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-//  Single-function ROWS FROM (no rewrite needed — pass-through)
+//  Single-function ROWS FROM
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -236,7 +235,7 @@ async fn test_rows_from_single_function_passthrough() {
          FROM rf_single s, \
          ROWS FROM(unnest(s.arr)) AS u(val)";
 
-    // Single-function ROWS FROM — should pass through without rewriting
+    // Single-function ROWS FROM behaves like a plain function in FROM
     db.create_st("rf_single_view", query, "1m", "FULL").await;
 
     let (status, _, populated, errors) = db.pgt_status("rf_single_view").await;
@@ -245,3 +244,42 @@ async fn test_rows_from_single_function_passthrough() {
     assert_eq!(errors, 0);
     db.assert_st_matches_query("rf_single_view", query).await;
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  WITH ORDINALITY and column definition lists
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn test_rows_from_with_ordinality_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE rf_ord (id INT PRIMARY KEY, items JSONB, tags TEXT[])")
+        .await;
+    db.execute(
+        "INSERT INTO rf_ord VALUES \
+         (1, '[{\"sku\": \"a\", \"qty\": 2}, {\"sku\": \"b\", \"qty\": 1}]', ARRAY['x']), \
+         (2, '[{\"sku\": \"c\", \"qty\": 5}]', ARRAY['y', 'y', 'z'])",
+    )
+    .await;
+
+    let query = "SELECT o.id, u.sku, u.qty, u.tag, u.pos \
+         FROM rf_ord o, \
+         ROWS FROM(jsonb_to_recordset(o.items) AS (sku TEXT, qty INT), unnest(o.tags)) \
+         WITH ORDINALITY AS u(sku, qty, tag, pos)";
+
+    db.create_st("rf_ord_view", query, "1m", "DIFFERENTIAL")
+        .await;
+    let (_, mode, _, _) = db.pgt_status("rf_ord_view").await;
+    assert_eq!(mode, "DIFFERENTIAL");
+    db.assert_st_matches_query("rf_ord_view", query).await;
+
+    // Duplicate zipped values at different positions stay distinct rows.
+    db.execute("UPDATE rf_ord SET tags = ARRAY['x', 'x', 'x', 'x'] WHERE id = 1")
+        .await;
+    db.execute("INSERT INTO rf_ord VALUES (3, '[]', ARRAY['w'])")
+        .await;
+    db.execute("DELETE FROM rf_ord WHERE id = 2").await;
+    db.refresh_st("rf_ord_view").await;
+    db.assert_st_matches_query("rf_ord_view", query).await;
+    assert_eq!(db.count("public.rf_ord_view").await, 5);
+}
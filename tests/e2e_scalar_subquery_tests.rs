@@ -118,6 +118,75 @@ async fn test_correlated_scalar_subquery_differential() {
     db.assert_st_matches_query("ss_corr_st", q).await;
 }
 
+/// Alias-qualified correlated scalar subquery in WHERE: the analyst form
+/// `p.price > (SELECT avg(p2.price) ... WHERE p2.cat = p.cat)` is
+/// decorrelated into a grouped join and stays DIFFERENTIAL.
+#[tokio::test]
+async fn test_qualified_correlated_scalar_subquery_in_where_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ss_prod (id INT PRIMARY KEY, cat TEXT, price INT)")
+        .await;
+    db.execute("INSERT INTO ss_prod VALUES (1, 'a', 10), (2, 'a', 30), (3, 'b', 5), (4, 'b', 7)")
+        .await;
+
+    let q = "SELECT p.id, p.cat, p.price FROM ss_prod p \
+             WHERE p.price > (SELECT avg(p2.price) FROM ss_prod p2 WHERE p2.cat = p.cat)";
+    db.create_st("ss_qcorr_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("ss_qcorr_st", q).await;
+
+    let mode: String = db
+        .query_scalar(
+            "SELECT refresh_mode FROM pgtrickle.pgt_stream_tables \
+             WHERE pgt_name = 'ss_qcorr_st'",
+        )
+        .await;
+    assert_eq!(mode, "DIFFERENTIAL");
+
+    // Raising the average of 'a' drops id 2 out of the result.
+    db.execute("INSERT INTO ss_prod VALUES (5, 'a', 80)").await;
+    db.refresh_st("ss_qcorr_st").await;
+    db.assert_st_matches_query("ss_qcorr_st", q).await;
+
+    db.execute("UPDATE ss_prod SET price = 1 WHERE id = 3")
+        .await;
+    db.refresh_st("ss_qcorr_st").await;
+    db.assert_st_matches_query("ss_qcorr_st", q).await;
+
+    db.execute("DELETE FROM ss_prod WHERE id = 5").await;
+    db.refresh_st("ss_qcorr_st").await;
+    db.assert_st_matches_query("ss_qcorr_st", q).await;
+}
+
+/// Correlation and COUNT detection read the parse tree, so a column that
+/// happens to be named `count` neither blocks decorrelation nor is mistaken
+/// for a COUNT aggregate.
+#[tokio::test]
+async fn test_correlated_scalar_subquery_over_column_named_count() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ss_stock (id INT PRIMARY KEY, cat TEXT, count INT)")
+        .await;
+    db.execute("INSERT INTO ss_stock VALUES (1, 'a', 3), (2, 'a', 9), (3, 'b', 4)")
+        .await;
+
+    let q = "SELECT s.id, s.cat, s.count FROM ss_stock s \
+             WHERE s.count >= (SELECT max(s2.count) FROM ss_stock s2 WHERE s2.cat = s.cat)";
+    db.create_st("ss_count_col_st", q, "1m", "DIFFERENTIAL")
+        .await;
+    db.assert_st_matches_query("ss_count_col_st", q).await;
+
+    let mode: String = db
+        .query_scalar(
+            "SELECT refresh_mode FROM pgtrickle.pgt_stream_tables \
+             WHERE pgt_name = 'ss_count_col_st'",
+        )
+        .await;
+    assert_eq!(mode, "DIFFERENTIAL");
+
+    db.execute("INSERT INTO ss_stock VALUES (4, 'b', 7)").await;
+    db.refresh_st("ss_count_col_st").await;
+    db.assert_st_matches_query("ss_count_col_st", q).await;
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // Scalar subquery returning NULL
 // ═══════════════════════════════════════════════════════════════════════
@@ -5,7 +5,7 @@
 //! | Test | DDL Operation | Expected |
 //! |------|---------------|----------|
 //! | SE-1 | Column rename (not in defining query) | No impact |
-//! | SE-2 | Column rename (used in defining query) | ST detects and suspends |
+//! | SE-2 | Column rename (used in defining query) | Query rewritten, stays differential |
 //! | SE-3 | Column added to source | No impact |
 //! | SE-4 | Column type change (INT → BIGINT, compatible) | Refresh succeeds |
 //!
@@ -49,12 +49,13 @@ async fn test_schema_evolution_rename_unused_column_no_impact() {
     assert_eq!(db.count("public.se1_st").await, 3);
 }
 
-// ── SE-2: Rename used column — ST detects mismatch ─────────────────────────
+// ── SE-2: Rename used column — defining query rewritten ────────────────────
 
 /// Renaming a source column that IS referenced in the defining query
-/// should cause the next refresh to fail or the ST to be marked for reinit.
+/// rewrites the stored query (REN-1) and keeps the stream table
+/// differential, with its output column names unchanged.
 #[tokio::test]
-async fn test_schema_evolution_rename_used_column_detected() {
+async fn test_schema_evolution_rename_used_column_rewritten() {
     let db = E2eDb::new().await.with_extension().await;
 
     db.execute("CREATE TABLE se2_src (id SERIAL PRIMARY KEY, amount INT)")
@@ -71,25 +72,25 @@ async fn test_schema_evolution_rename_used_column_detected() {
     .await;
     assert_eq!(db.count("public.se2_st").await, 2);
 
-    // Rename the column used in the defining query.
-    // pg_trickle's DDL hook treats RENAME COLUMN (object_type = "table column")
-    // as Ignored, so we manually mark the ST for reinit so the next refresh
-    // re-executes the defining query and fails on the missing column.
     db.execute("ALTER TABLE se2_src RENAME COLUMN amount TO total")
         .await;
-    db.execute(
-        "UPDATE pgtrickle.pgt_stream_tables SET needs_reinit = TRUE WHERE pgt_name = 'se2_st'",
-    )
-    .await;
 
-    // The next refresh should fail because 'amount' no longer exists
-    let result = db
-        .try_execute("SELECT pgtrickle.refresh_stream_table('se2_st')")
+    let needs_reinit: bool = db
+        .query_scalar(
+            "SELECT needs_reinit FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'se2_st'",
+        )
         .await;
     assert!(
-        result.is_err(),
-        "Refresh should fail after renaming a column used in the defining query"
+        !needs_reinit,
+        "a rewritable rename should not force a reinit"
     );
+
+    db.execute("INSERT INTO se2_src (total) VALUES (30)").await;
+    db.execute("UPDATE se2_src SET total = 11 WHERE total = 10")
+        .await;
+    db.refresh_st("se2_st").await;
+    db.assert_st_matches_query("se2_st", "SELECT id, total AS amount FROM se2_src")
+        .await;
 }
 
 // ── SE-3: Add column to source — no impact ─────────────────────────────────
@@ -0,0 +1,134 @@
+//! SLIDE-1: Sliding `now()` window tests.
+//!
+//! Verifies that:
+//! 1. Rows that age out of `ts > now() - interval ...` are deleted by a
+//!    DIFFERENTIAL refresh even when the source table is idle
+//! 2. Future-dated rows enter a `ts <= now()` window once their time comes
+//! 3. Aggregates over a sliding window stay consistent with a full recompute
+//! 4. Recognised windows no longer trigger the stable-function warning
+
+mod e2e;
+
+use e2e::E2eDb;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_sliding_window_expires_idle_rows() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE sw_events (id INT PRIMARY KEY, kind TEXT, ts TIMESTAMPTZ NOT NULL)")
+        .await;
+    db.execute(
+        "INSERT INTO sw_events VALUES \
+         (1, 'click', now() - interval '59 minutes 57 seconds'), \
+         (2, 'click', now() - interval '10 minutes'), \
+         (3, 'view',  now() - interval '2 hours')",
+    )
+    .await;
+
+    let query = "SELECT id, kind, ts FROM sw_events WHERE ts > now() - interval '1 hour'";
+    db.create_st("sw_recent", query, "24h", "DIFFERENTIAL")
+        .await;
+    assert_eq!(db.count("public.sw_recent").await, 2);
+
+    // No DML: row 1 slides out of the window purely by the clock advancing.
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    db.refresh_st("sw_recent").await;
+
+    assert_eq!(db.count("public.sw_recent").await, 1);
+    db.assert_st_matches_query("public.sw_recent", query).await;
+
+    // Changed rows are still judged against the current clock.
+    db.execute("UPDATE sw_events SET ts = now() WHERE id = 3")
+        .await;
+    db.execute("UPDATE sw_events SET ts = now() - interval '3 hours' WHERE id = 2")
+        .await;
+    db.refresh_st("sw_recent").await;
+
+    db.assert_st_matches_query("public.sw_recent", query).await;
+    let ids: i64 = db
+        .query_scalar("SELECT sum(id)::bigint FROM public.sw_recent")
+        .await;
+    assert_eq!(ids, 3, "only row 3 should remain in the window");
+}
+
+#[tokio::test]
+async fn test_sliding_window_admits_future_rows() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE sw_sched (id INT PRIMARY KEY, due TIMESTAMPTZ NOT NULL)")
+        .await;
+    db.execute(
+        "INSERT INTO sw_sched VALUES \
+         (1, now() - interval '1 minute'), \
+         (2, now() + interval '3 seconds')",
+    )
+    .await;
+
+    let query = "SELECT id, due FROM sw_sched WHERE due <= CURRENT_TIMESTAMP";
+    db.create_st("sw_due", query, "24h", "DIFFERENTIAL").await;
+    assert_eq!(db.count("public.sw_due").await, 1);
+
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    db.refresh_st("sw_due").await;
+
+    assert_eq!(db.count("public.sw_due").await, 2);
+    db.assert_st_matches_query("public.sw_due", query).await;
+}
+
+#[tokio::test]
+async fn test_sliding_window_aggregate_count() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE sw_hits (id INT PRIMARY KEY, page TEXT, ts TIMESTAMPTZ NOT NULL)")
+        .await;
+    db.execute(
+        "INSERT INTO sw_hits VALUES \
+         (1, 'home',  now() - interval '59 minutes 57 seconds'), \
+         (2, 'home',  now() - interval '5 minutes'), \
+         (3, 'about', now() - interval '59 minutes 57 seconds')",
+    )
+    .await;
+
+    let query = "SELECT page, count(*) AS hits FROM sw_hits \
+                 WHERE ts > now() - interval '1 hour' GROUP BY page";
+    db.create_st("sw_hits_per_page", query, "24h", "DIFFERENTIAL")
+        .await;
+    assert_eq!(db.count("public.sw_hits_per_page").await, 2);
+
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    db.execute("INSERT INTO sw_hits VALUES (4, 'home', now())")
+        .await;
+    db.refresh_st("sw_hits_per_page").await;
+
+    // 'about' lost its only hit; 'home' lost one and gained one.
+    db.assert_st_matches_query("public.sw_hits_per_page", query)
+        .await;
+    let home: i64 = db
+        .query_scalar("SELECT hits FROM public.sw_hits_per_page WHERE page = 'home'")
+        .await;
+    assert_eq!(home, 2);
+    assert_eq!(db.count("public.sw_hits_per_page").await, 1);
+}
+
+#[tokio::test]
+async fn test_sliding_window_does_not_warn_stable() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE sw_quiet (id INT PRIMARY KEY, ts TIMESTAMPTZ NOT NULL)")
+        .await;
+
+    let notices = db
+        .try_execute_with_notices(
+            "SELECT pgtrickle.create_stream_table('sw_quiet_st', \
+             $$SELECT id, ts FROM sw_quiet WHERE ts > now() - interval '1 day'$$, \
+             '1m', 'DIFFERENTIAL')",
+        )
+        .await
+        .expect("create_stream_table should succeed");
+
+    assert!(
+        !notices.iter().any(|n| n.contains("stable functions")),
+        "sliding-window filter should not warn about stable functions: {notices:?}"
+    );
+}
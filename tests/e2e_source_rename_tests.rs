@@ -0,0 +1,230 @@
+//! E2E tests for source renames (REN-1).
+//!
+//! Validates that RENAME COLUMN, RENAME TO, SET SCHEMA and ALTER SCHEMA
+//! RENAME on a source table rewrite the stored defining query in place,
+//! keep the frontier and change buffer, and leave the stream table's output
+//! columns unchanged. Queries that cannot be rewritten (`SELECT *`) are
+//! blocked or reinitialized as before.
+//!
+//! Prerequisites: `./tests/build_e2e_image.sh`
+
+mod e2e;
+
+use e2e::E2eDb;
+
+async fn needs_reinit(db: &E2eDb, st: &str) -> bool {
+    db.query_scalar(&format!(
+        "SELECT needs_reinit FROM pgtrickle.pgt_stream_tables WHERE pgt_name = '{st}'"
+    ))
+    .await
+}
+
+// ── RENAME COLUMN ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_rename_column_keeps_buffered_changes() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE ren_orders (id INT PRIMARY KEY, region TEXT, amount INT)")
+        .await;
+    db.execute("INSERT INTO ren_orders VALUES (1, 'eu', 10), (2, 'us', 20)")
+        .await;
+    db.create_st(
+        "ren_totals",
+        "SELECT region, SUM(amount) AS total FROM ren_orders GROUP BY region",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    // Captured before the rename, consumed after it.
+    db.execute("INSERT INTO ren_orders VALUES (3, 'eu', 5)")
+        .await;
+    db.execute("ALTER TABLE ren_orders RENAME COLUMN amount TO net_amount")
+        .await;
+    assert!(!needs_reinit(&db, "ren_totals").await);
+
+    db.execute("INSERT INTO ren_orders VALUES (4, 'us', 7)")
+        .await;
+    db.refresh_st("ren_totals").await;
+    db.assert_st_matches_query(
+        "ren_totals",
+        "SELECT region, SUM(net_amount) AS total FROM ren_orders GROUP BY region",
+    )
+    .await;
+
+    let columns_used: Vec<String> = db
+        .query_scalar(
+            "SELECT d.columns_used FROM pgtrickle.pgt_dependencies d \
+             JOIN pgtrickle.pgt_stream_tables s USING (pgt_id) \
+             WHERE s.pgt_name = 'ren_totals'",
+        )
+        .await;
+    assert!(columns_used.contains(&"net_amount".to_string()));
+    assert!(!columns_used.contains(&"amount".to_string()));
+}
+
+#[tokio::test]
+async fn test_rename_column_in_join_with_aliases() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE ren_customers (id INT PRIMARY KEY, name TEXT)")
+        .await;
+    db.execute("CREATE TABLE ren_sales (id INT PRIMARY KEY, cust_id INT, qty INT)")
+        .await;
+    db.execute("INSERT INTO ren_customers VALUES (1, 'a'), (2, 'b')")
+        .await;
+    db.execute("INSERT INTO ren_sales VALUES (1, 1, 3), (2, 2, 4)")
+        .await;
+    db.create_st(
+        "ren_join_st",
+        "SELECT s.id, c.name, s.qty FROM ren_sales s JOIN ren_customers c ON c.id = s.cust_id",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("ALTER TABLE ren_sales RENAME COLUMN cust_id TO customer_id")
+        .await;
+    assert!(!needs_reinit(&db, "ren_join_st").await);
+
+    db.execute("INSERT INTO ren_sales VALUES (3, 1, 9)").await;
+    db.refresh_st("ren_join_st").await;
+    db.assert_st_matches_query(
+        "ren_join_st",
+        "SELECT s.id, c.name, s.qty FROM ren_sales s JOIN ren_customers c ON c.id = s.customer_id",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_rename_column_leaves_inner_scope_column() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE ren_outer (id INT PRIMARY KEY, k INT, x INT)")
+        .await;
+    db.execute("CREATE TABLE ren_inner (id INT PRIMARY KEY, k INT, x INT)")
+        .await;
+    db.execute("INSERT INTO ren_outer VALUES (1, 1, 0), (2, 2, 0), (3, 3, 0)")
+        .await;
+    db.execute("INSERT INTO ren_inner VALUES (1, 1, 5), (2, 2, -5)")
+        .await;
+    // The unqualified `x` in the subquery is ren_inner.x, the nearer scope.
+    db.create_st(
+        "ren_scope_st",
+        "SELECT o.id FROM ren_outer o \
+         WHERE EXISTS (SELECT 1 FROM ren_inner i WHERE i.k = o.k AND x > 0)",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("ALTER TABLE ren_outer RENAME COLUMN x TO y")
+        .await;
+    assert!(!needs_reinit(&db, "ren_scope_st").await);
+
+    db.execute("INSERT INTO ren_inner VALUES (3, 3, 7)").await;
+    db.refresh_st("ren_scope_st").await;
+    db.assert_st_matches_query(
+        "ren_scope_st",
+        "SELECT o.id FROM ren_outer o \
+         WHERE EXISTS (SELECT 1 FROM ren_inner i WHERE i.k = o.k AND i.x > 0)",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_rename_column_select_star_is_blocked() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE ren_star (id INT PRIMARY KEY, val INT)")
+        .await;
+    db.create_st(
+        "ren_star_st",
+        "SELECT * FROM ren_star",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    // The output column names would change — cannot rewrite in place.
+    let result = db
+        .try_execute("ALTER TABLE ren_star RENAME COLUMN val TO value")
+        .await;
+    assert!(result.is_err(), "rename under SELECT * should be blocked");
+
+    db.execute_seq(&[
+        "SET pg_trickle.block_source_ddl = false",
+        "ALTER TABLE ren_star RENAME COLUMN val TO value",
+        "SET pg_trickle.block_source_ddl = true",
+    ])
+    .await;
+    assert!(needs_reinit(&db, "ren_star_st").await);
+}
+
+// ── RENAME TO / SET SCHEMA ─────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_rename_table_and_set_schema() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE ren_items (id INT PRIMARY KEY, price INT)")
+        .await;
+    db.execute("INSERT INTO ren_items VALUES (1, 100)").await;
+    db.create_st(
+        "ren_items_st",
+        "SELECT ren_items.id, ren_items.price * 2 AS doubled FROM ren_items",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("ALTER TABLE ren_items RENAME TO ren_products")
+        .await;
+    assert!(!needs_reinit(&db, "ren_items_st").await);
+
+    db.execute("CREATE SCHEMA ren_catalog").await;
+    db.execute("ALTER TABLE ren_products SET SCHEMA ren_catalog")
+        .await;
+    assert!(!needs_reinit(&db, "ren_items_st").await);
+
+    db.execute("INSERT INTO ren_catalog.ren_products VALUES (2, 5)")
+        .await;
+    db.refresh_st("ren_items_st").await;
+    db.assert_st_matches_query(
+        "ren_items_st",
+        "SELECT id, price * 2 AS doubled FROM ren_catalog.ren_products",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_rename_source_schema() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE SCHEMA ren_sales_v1").await;
+    db.execute("CREATE TABLE ren_sales_v1.events (id INT PRIMARY KEY, kind TEXT)")
+        .await;
+    db.execute("INSERT INTO ren_sales_v1.events VALUES (1, 'click')")
+        .await;
+    db.create_st(
+        "ren_events_st",
+        "SELECT kind, COUNT(*) AS n FROM ren_sales_v1.events GROUP BY kind",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("ALTER SCHEMA ren_sales_v1 RENAME TO ren_sales_v2")
+        .await;
+    assert!(!needs_reinit(&db, "ren_events_st").await);
+
+    db.execute("INSERT INTO ren_sales_v2.events VALUES (2, 'view'), (3, 'click')")
+        .await;
+    db.refresh_st("ren_events_st").await;
+    db.assert_st_matches_query(
+        "ren_events_st",
+        "SELECT kind, COUNT(*) AS n FROM ren_sales_v2.events GROUP BY kind",
+    )
+    .await;
+}
@@ -0,0 +1,164 @@
+//! E2E tests for DDL-style stream table commands (F11b).
+//!
+//! Covers the materialized-view spelling routed by the ProcessUtility hook
+//! and the `STREAM TABLE` spelling accepted by `pgtrickle.exec_stream_ddl()`.
+
+mod e2e;
+
+use e2e::E2eDb;
+
+#[tokio::test]
+async fn test_matview_ddl_lifecycle() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ddl_orders (id INT PRIMARY KEY, region TEXT, amount INT)")
+        .await;
+    db.execute("INSERT INTO ddl_orders VALUES (1, 'eu', 10), (2, 'us', 20), (3, 'eu', 5)")
+        .await;
+
+    db.execute(
+        "CREATE MATERIALIZED VIEW ddl_totals \
+         WITH (pgtrickle.schedule = '1m', pgtrickle.refresh_mode = 'DIFFERENTIAL') \
+         AS SELECT region, SUM(amount) AS total FROM ddl_orders GROUP BY region",
+    )
+    .await;
+
+    let (status, mode, populated, _) = db.pgt_status("ddl_totals").await;
+    assert_eq!(status, "ACTIVE");
+    assert_eq!(mode, "DIFFERENTIAL");
+    assert!(populated);
+    let relkind: String = db
+        .query_scalar("SELECT relkind::text FROM pg_class WHERE relname = 'ddl_totals'")
+        .await;
+    assert_eq!(
+        relkind, "r",
+        "stream table storage must be a table, not a matview"
+    );
+
+    db.execute("ALTER MATERIALIZED VIEW ddl_totals SET (pgtrickle.schedule = '5m')")
+        .await;
+    let schedule: String = db
+        .query_scalar(
+            "SELECT schedule FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'ddl_totals'",
+        )
+        .await;
+    assert_eq!(schedule, "5m");
+
+    db.execute("INSERT INTO ddl_orders VALUES (4, 'us', 7)")
+        .await;
+    db.execute("REFRESH MATERIALIZED VIEW ddl_totals").await;
+    db.assert_st_matches_query(
+        "ddl_totals",
+        "SELECT region, SUM(amount) AS total FROM ddl_orders GROUP BY region",
+    )
+    .await;
+
+    db.execute("DROP MATERIALIZED VIEW ddl_totals").await;
+    assert!(!db.table_exists("public", "ddl_totals").await);
+    db.execute("DROP MATERIALIZED VIEW IF EXISTS ddl_totals")
+        .await;
+}
+
+#[tokio::test]
+async fn test_matview_ddl_leaves_plain_matviews_alone() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ddl_plain_src (id INT PRIMARY KEY)")
+        .await;
+    db.execute("INSERT INTO ddl_plain_src VALUES (1), (2)")
+        .await;
+
+    db.execute("CREATE MATERIALIZED VIEW ddl_plain AS SELECT id FROM ddl_plain_src")
+        .await;
+    db.execute("REFRESH MATERIALIZED VIEW ddl_plain").await;
+    let is_st: bool = db
+        .query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pgtrickle.pgt_stream_tables \
+             WHERE pgt_name = 'ddl_plain')",
+        )
+        .await;
+    assert!(!is_st);
+    db.execute("DROP MATERIALIZED VIEW ddl_plain").await;
+}
+
+#[tokio::test]
+async fn test_matview_ddl_drop_rejects_mixed_relations() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ddl_mix_src (id INT PRIMARY KEY)")
+        .await;
+    db.execute("CREATE MATERIALIZED VIEW ddl_mix_plain AS SELECT id FROM ddl_mix_src")
+        .await;
+    db.execute(
+        "CREATE MATERIALIZED VIEW ddl_mix_st WITH (pgtrickle.schedule = '1m') \
+         AS SELECT id FROM ddl_mix_src",
+    )
+    .await;
+
+    let err = db
+        .try_execute("DROP MATERIALIZED VIEW ddl_mix_st, ddl_mix_plain")
+        .await
+        .expect_err("mixed DROP must be rejected");
+    assert!(
+        err.to_string()
+            .contains("DROP MATERIALIZED VIEW cannot mix stream tables"),
+        "unexpected error: {err}"
+    );
+
+    // Single-relation statements are routed by the relation they name.
+    db.execute("REFRESH MATERIALIZED VIEW ddl_mix_plain").await;
+    db.execute("REFRESH MATERIALIZED VIEW ddl_mix_st").await;
+    db.execute("DROP MATERIALIZED VIEW ddl_mix_st").await;
+    db.execute("DROP MATERIALIZED VIEW ddl_mix_plain").await;
+}
+
+#[tokio::test]
+async fn test_matview_ddl_rejects_unknown_option() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ddl_bad_src (id INT PRIMARY KEY)")
+        .await;
+
+    let err = db
+        .try_execute(
+            "CREATE MATERIALIZED VIEW ddl_bad \
+             WITH (pgtrickle.schedul = '1m') AS SELECT id FROM ddl_bad_src",
+        )
+        .await
+        .expect_err("misspelled option must be rejected");
+    assert!(
+        err.to_string().contains("unknown option \"schedul\""),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test]
+async fn test_exec_stream_ddl_with_options() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE ddl_exec_src (id INT PRIMARY KEY, val INT)")
+        .await;
+    db.execute("INSERT INTO ddl_exec_src VALUES (1, 1)").await;
+
+    db.execute(
+        "SELECT pgtrickle.exec_stream_ddl($$\
+            CREATE STREAM TABLE ddl_exec_st \
+              WITH (schedule = '2m', refresh_mode = 'FULL') \
+              AS SELECT id, val FROM ddl_exec_src\
+         $$)",
+    )
+    .await;
+    let (_, mode, _, _) = db.pgt_status("ddl_exec_st").await;
+    assert_eq!(mode, "FULL");
+
+    db.execute(
+        "SELECT pgtrickle.exec_stream_ddl(\
+            'ALTER STREAM TABLE ddl_exec_st SET (refresh_mode = ''DIFFERENTIAL'')')",
+    )
+    .await;
+    let (_, mode, _, _) = db.pgt_status("ddl_exec_st").await;
+    assert_eq!(mode, "DIFFERENTIAL");
+
+    db.execute("SELECT pgtrickle.exec_stream_ddl('REFRESH STREAM TABLE ddl_exec_st')")
+        .await;
+    db.execute(
+        "SELECT pgtrickle.exec_stream_ddl('DROP STREAM TABLE IF EXISTS ddl_exec_st, missing_st')",
+    )
+    .await;
+    assert!(!db.table_exists("public", "ddl_exec_st").await);
+}
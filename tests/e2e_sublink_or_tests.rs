@@ -158,3 +158,129 @@ async fn test_exists_with_having_in_subquery_differential() {
     db.refresh_st("eh_st").await;
     db.assert_st_matches_query("eh_st", q).await;
 }
+
+// ═══════════════════════════════════════════════════════════════════════
+// Duplicate rows and aggregation over OR + EXISTS
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Rows matching both arms appear once, and distinct source rows with
+/// identical projections are all kept.
+#[tokio::test]
+async fn test_exists_or_column_keeps_duplicate_rows_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE od_cust (id INT PRIMARY KEY, region TEXT, status TEXT)")
+        .await;
+    db.execute("CREATE TABLE od_ord (id SERIAL PRIMARY KEY, cid INT)")
+        .await;
+    db.execute(
+        "INSERT INTO od_cust VALUES (1, 'eu', 'vip'), (2, 'eu', 'std'), (3, 'eu', 'std'), (4, 'us', NULL)",
+    )
+    .await;
+    db.execute("INSERT INTO od_ord (cid) VALUES (1), (2), (3)")
+        .await;
+
+    let q = "SELECT c.region FROM od_cust c \
+             WHERE c.status = 'vip' OR EXISTS (SELECT 1 FROM od_ord o WHERE o.cid = c.id)";
+    db.create_st("od_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("od_st", q).await;
+    assert_eq!(db.count("public.od_st").await, 3);
+
+    // NULL status must not hide the EXISTS arm.
+    db.execute("INSERT INTO od_ord (cid) VALUES (4)").await;
+    db.refresh_st("od_st").await;
+    db.assert_st_matches_query("od_st", q).await;
+    assert_eq!(db.count("public.od_st").await, 4);
+
+    db.execute("DELETE FROM od_ord WHERE cid IN (1, 2)").await;
+    db.refresh_st("od_st").await;
+    db.assert_st_matches_query("od_st", q).await;
+    assert_eq!(db.count("public.od_st").await, 3);
+}
+
+/// Grouping runs once over the rows accepted by either arm.
+#[tokio::test]
+async fn test_exists_or_column_with_group_by_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE og_cust (id INT PRIMARY KEY, region TEXT, status TEXT)")
+        .await;
+    db.execute("CREATE TABLE og_ord (id SERIAL PRIMARY KEY, cid INT)")
+        .await;
+    db.execute(
+        "INSERT INTO og_cust VALUES (1, 'eu', 'vip'), (2, 'eu', 'std'), (3, 'us', 'vip'), (4, 'us', 'std')",
+    )
+    .await;
+    db.execute("INSERT INTO og_ord (cid) VALUES (1), (2)").await;
+
+    let q = "SELECT c.region, count(*) AS n FROM og_cust c \
+             WHERE c.status = 'vip' OR EXISTS (SELECT 1 FROM og_ord o WHERE o.cid = c.id) \
+             GROUP BY c.region";
+    db.create_st("og_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("og_st", q).await;
+
+    db.execute("INSERT INTO og_ord (cid) VALUES (3), (4)").await;
+    db.refresh_st("og_st").await;
+    db.assert_st_matches_query("og_st", q).await;
+
+    db.execute("UPDATE og_cust SET status = 'std' WHERE id = 1")
+        .await;
+    db.execute("DELETE FROM og_ord WHERE cid = 1").await;
+    db.refresh_st("og_st").await;
+    db.assert_st_matches_query("og_st", q).await;
+}
+
+/// An IN arm that evaluates to NULL (no match, NULL in the subquery) must
+/// not hide the rows of a later arm.
+#[tokio::test]
+async fn test_in_or_exists_with_null_in_subquery_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE on_cust (id INT PRIMARY KEY, region TEXT)")
+        .await;
+    db.execute("CREATE TABLE on_region (region TEXT)").await;
+    db.execute("CREATE TABLE on_ord (id SERIAL PRIMARY KEY, cid INT)")
+        .await;
+    db.execute("INSERT INTO on_cust VALUES (1, 'eu'), (2, 'us'), (3, 'apac')")
+        .await;
+    db.execute("INSERT INTO on_region VALUES ('eu'), (NULL)")
+        .await;
+    db.execute("INSERT INTO on_ord (cid) VALUES (2)").await;
+
+    let q = "SELECT c.id, c.region FROM on_cust c \
+             WHERE c.region IN (SELECT r.region FROM on_region r) \
+             OR EXISTS (SELECT 1 FROM on_ord o WHERE o.cid = c.id)";
+    db.create_st("on_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("on_st", q).await;
+    assert_eq!(db.count("public.on_st").await, 2);
+
+    db.execute("INSERT INTO on_ord (cid) VALUES (3)").await;
+    db.refresh_st("on_st").await;
+    db.assert_st_matches_query("on_st", q).await;
+    assert_eq!(db.count("public.on_st").await, 3);
+}
+
+/// Two NOT IN arms cannot be made disjoint; rather than merging duplicate
+/// rows of a keyless source with UNION, the query falls back to FULL.
+#[tokio::test]
+async fn test_two_not_in_arms_fall_back_to_full() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE oo_events (kind TEXT, src TEXT)")
+        .await;
+    db.execute("CREATE TABLE oo_kinds (kind TEXT)").await;
+    db.execute("CREATE TABLE oo_srcs (src TEXT)").await;
+    db.execute("INSERT INTO oo_events VALUES ('a', 'x'), ('a', 'x'), ('b', 'y')")
+        .await;
+    db.execute("INSERT INTO oo_kinds VALUES ('b')").await;
+    db.execute("INSERT INTO oo_srcs VALUES ('y')").await;
+
+    let q = "SELECT e.kind, e.src FROM oo_events e \
+             WHERE e.kind NOT IN (SELECT k.kind FROM oo_kinds k) \
+             OR e.src NOT IN (SELECT s.src FROM oo_srcs s)";
+    db.create_st("oo_st", q, "1m", "AUTO").await;
+    let mode: String = db
+        .query_scalar(
+            "SELECT refresh_mode FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'oo_st'",
+        )
+        .await;
+    assert_eq!(mode, "FULL");
+    db.assert_st_matches_query("oo_st", q).await;
+    assert_eq!(db.count("public.oo_st").await, 2);
+}
@@ -0,0 +1,208 @@
+//! E2E tests for temporal (SCD Type 2) stream tables (CORR-1).
+//!
+//! Validates that refreshes archive closed versions into the history table
+//! instead of discarding them, that the stream table itself only shows
+//! current rows, and that `pgtrickle.as_of()` and `pgtrickle.history()`
+//! reconstruct past states.
+
+mod e2e;
+
+use e2e::E2eDb;
+
+async fn create_temporal_st(db: &E2eDb, name: &str, query: &str, mode: &str) {
+    db.execute(&format!(
+        "SELECT pgtrickle.create_stream_table('{name}', $${query}$$, \
+         schedule => '1m', refresh_mode => '{mode}', temporal => true)"
+    ))
+    .await;
+}
+
+#[tokio::test]
+async fn test_temporal_differential_keeps_history() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE tmp_acct (id INT PRIMARY KEY, owner TEXT, balance INT)")
+        .await;
+    db.execute("INSERT INTO tmp_acct VALUES (1, 'ann', 100), (2, 'bob', 50)")
+        .await;
+    create_temporal_st(
+        &db,
+        "tmp_acct_st",
+        "SELECT id, owner, balance FROM tmp_acct",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("UPDATE tmp_acct SET balance = 120 WHERE id = 1")
+        .await;
+    db.execute("DELETE FROM tmp_acct WHERE id = 2").await;
+    db.execute("INSERT INTO tmp_acct VALUES (3, 'cid', 10)")
+        .await;
+    db.refresh_st("tmp_acct_st").await;
+
+    // Only current versions are visible in the stream table itself.
+    db.assert_st_matches_query("tmp_acct_st", "SELECT id, owner, balance FROM tmp_acct")
+        .await;
+    // Closed versions: id=1 (old balance), id=2 (deleted).
+    let closed: i64 = db
+        .query_scalar(
+            "SELECT count(*) FROM pgtrickle.history('tmp_acct_st') WHERE valid_to IS NOT NULL",
+        )
+        .await;
+    assert_eq!(closed, 2);
+
+    let versions_of_1: i64 = db
+        .query_scalar("SELECT count(*) FROM pgtrickle.history('tmp_acct_st', '{\"id\": 1}')")
+        .await;
+    assert_eq!(versions_of_1, 2);
+    let first_balance: i32 = db
+        .query_scalar(
+            "SELECT (row_data->>'balance')::int FROM pgtrickle.history('tmp_acct_st', '{\"id\": 1}') \
+             ORDER BY valid_from LIMIT 1",
+        )
+        .await;
+    assert_eq!(first_balance, 100);
+    let bob_closed: bool = db
+        .query_scalar(
+            "SELECT valid_to IS NOT NULL FROM pgtrickle.history('tmp_acct_st', '{\"id\": 2}')",
+        )
+        .await;
+    assert!(bob_closed, "deleted row must be closed, not removed");
+}
+
+#[tokio::test]
+async fn test_temporal_as_of_reconstructs_past_state() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE tmp_price (sku TEXT PRIMARY KEY, price INT)")
+        .await;
+    db.execute("INSERT INTO tmp_price VALUES ('a', 1), ('b', 2)")
+        .await;
+    create_temporal_st(
+        &db,
+        "tmp_price_st",
+        "SELECT sku, price FROM tmp_price",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("CREATE TABLE tmp_marks AS SELECT clock_timestamp() AS before_change")
+        .await;
+    db.execute("UPDATE tmp_price SET price = 5 WHERE sku = 'a'")
+        .await;
+    db.refresh_st("tmp_price_st").await;
+
+    let past: String = db
+        .query_scalar(
+            "SELECT string_agg((j->>'sku') || '=' || (j->>'price'), ',' ORDER BY j->>'sku') \
+             FROM pgtrickle.as_of('tmp_price_st', (SELECT before_change FROM tmp_marks)) AS j",
+        )
+        .await;
+    assert_eq!(past, "a=1,b=2");
+
+    let now_state: String = db
+        .query_scalar(
+            "SELECT string_agg((j->>'sku') || '=' || (j->>'price'), ',' ORDER BY j->>'sku') \
+             FROM pgtrickle.as_of('tmp_price_st', now()) AS j",
+        )
+        .await;
+    assert_eq!(now_state, "a=5,b=2");
+}
+
+#[tokio::test]
+async fn test_temporal_aggregate_full_refresh_preserves_unchanged_versions() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE tmp_sales (id INT PRIMARY KEY, region TEXT, amount INT)")
+        .await;
+    db.execute("INSERT INTO tmp_sales VALUES (1, 'eu', 10), (2, 'us', 20)")
+        .await;
+    create_temporal_st(
+        &db,
+        "tmp_sales_st",
+        "SELECT region, SUM(amount) AS total FROM tmp_sales GROUP BY region",
+        "FULL",
+    )
+    .await;
+
+    db.execute("INSERT INTO tmp_sales VALUES (3, 'eu', 5)")
+        .await;
+    db.refresh_st("tmp_sales_st").await;
+
+    // 'eu' changed (closed + new version); 'us' is untouched.
+    db.assert_st_matches_query(
+        "tmp_sales_st",
+        "SELECT region, SUM(amount) AS total FROM tmp_sales GROUP BY region",
+    )
+    .await;
+    let us_versions: i64 = db
+        .query_scalar(
+            "SELECT count(*) FROM pgtrickle.history('tmp_sales_st', '{\"region\": \"us\"}')",
+        )
+        .await;
+    assert_eq!(us_versions, 1);
+    let eu_totals: String = db
+        .query_scalar(
+            "SELECT string_agg(row_data->>'total', ',' ORDER BY valid_from) \
+             FROM pgtrickle.history('tmp_sales_st', '{\"region\": \"eu\"}')",
+        )
+        .await;
+    assert_eq!(eu_totals, "10,15");
+}
+
+#[tokio::test]
+async fn test_temporal_history_survives_truncate_and_drop_cleans_up() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE tmp_evt (id INT PRIMARY KEY, kind TEXT)")
+        .await;
+    db.execute("INSERT INTO tmp_evt VALUES (1, 'a'), (2, 'b')")
+        .await;
+    create_temporal_st(
+        &db,
+        "tmp_evt_st",
+        "SELECT id, kind FROM tmp_evt",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    db.execute("TRUNCATE tmp_evt").await;
+    db.refresh_st("tmp_evt_st").await;
+    assert_eq!(db.count("public.tmp_evt_st").await, 0);
+    let archived: i64 = db
+        .query_scalar("SELECT count(*) FROM pgtrickle.history('tmp_evt_st')")
+        .await;
+    assert_eq!(archived, 2);
+
+    let history_table: String = db
+        .query_scalar(
+            "SELECT format('%I.history_%s', current_setting('pg_trickle.change_buffer_schema'), pgt_id) \
+             FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'tmp_evt_st'",
+        )
+        .await;
+    db.execute("SELECT pgtrickle.drop_stream_table('tmp_evt_st')")
+        .await;
+    let gone: bool = db
+        .query_scalar(&format!("SELECT to_regclass('{history_table}') IS NULL"))
+        .await;
+    assert!(gone, "history table must be dropped with the stream table");
+}
+
+#[tokio::test]
+async fn test_temporal_functions_reject_non_temporal_st() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute("CREATE TABLE tmp_plain (id INT PRIMARY KEY)")
+        .await;
+    db.create_st(
+        "tmp_plain_st",
+        "SELECT id FROM tmp_plain",
+        "1m",
+        "DIFFERENTIAL",
+    )
+    .await;
+
+    let err = db
+        .try_execute("SELECT * FROM pgtrickle.as_of('tmp_plain_st', now())")
+        .await
+        .expect_err("as_of on a non-temporal stream table must fail");
+    assert!(
+        err.to_string().contains("is not temporal"),
+        "unexpected error: {err}"
+    );
+}
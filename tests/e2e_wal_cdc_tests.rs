@@ -7,6 +7,7 @@
 //! - W2: Automatic fallback on persistent poll errors (slot dropped)
 //! - W2: Health check detects missing prerequisites
 //! - W3: `auto` is the default cdc_mode (no explicit config needed)
+//! - WAL-STREAM: the shared slot is streamed by the WAL receiver worker
 //!
 //! Prerequisites:
 //! - `./tests/build_e2e_image.sh` (Docker image with wal_level=logical)
@@ -917,3 +918,91 @@ async fn test_wal_transition_replica_identity_drop_falls_back_to_trigger() {
         "After REPLICA IDENTITY reset the CDC mode should revert to trigger, got: {final_mode}"
     );
 }
+
+// ── WAL-STREAM: streaming receiver for the shared slot ────────────────
+
+/// With `wal_shared_slot` and `wal_receiver` on, the shared slot is consumed
+/// by the receiver worker over a replication connection, and DML reaches the
+/// stream table through the streamed change buffer.
+#[tokio::test]
+async fn test_wal_receiver_streams_shared_slot() {
+    let db = E2eDb::new_on_postgres_db().await.with_extension().await;
+
+    db.execute("ALTER SYSTEM SET pg_trickle.wal_shared_slot = on")
+        .await;
+    db.execute("ALTER SYSTEM SET pg_trickle.wal_receiver = on")
+        .await;
+    db.execute("SELECT pg_reload_conf()").await;
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    db.execute("CREATE TABLE wal_rcv_src (id INT PRIMARY KEY, grp INT, val INT)")
+        .await;
+    db.execute("ALTER TABLE wal_rcv_src REPLICA IDENTITY FULL")
+        .await;
+    db.execute("INSERT INTO wal_rcv_src VALUES (1, 1, 10), (2, 2, 20)")
+        .await;
+
+    let query = "SELECT grp, sum(val) AS total FROM wal_rcv_src GROUP BY grp";
+    db.create_st("wal_rcv_st", query, "1s", "DIFFERENTIAL")
+        .await;
+
+    let mode = wait_for_cdc_mode(&db, "wal_rcv_src", "WAL", Duration::from_secs(60)).await;
+    assert_eq!(mode, "WAL", "Should transition to WAL mode");
+
+    // The scheduler starts the receiver within a few seconds.
+    let start = std::time::Instant::now();
+    loop {
+        let streaming: bool = db
+            .query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM pg_stat_replication \
+                 WHERE application_name = 'pg_trickle wal receiver')",
+            )
+            .await;
+        if streaming {
+            break;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "WAL receiver should be streaming the shared slot"
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    db.execute("INSERT INTO wal_rcv_src VALUES (3, 1, 5), (4, 3, 7)")
+        .await;
+    db.execute("UPDATE wal_rcv_src SET val = 25 WHERE id = 2")
+        .await;
+    db.execute("DELETE FROM wal_rcv_src WHERE id = 1").await;
+
+    let refreshed = db
+        .wait_for_auto_refresh("wal_rcv_st", Duration::from_secs(30))
+        .await;
+    assert!(
+        refreshed,
+        "Scheduler should refresh from the streamed buffer"
+    );
+
+    // Capture is asynchronous — allow a couple of refreshes to catch up.
+    let start = std::time::Instant::now();
+    loop {
+        let matches: bool = db
+            .query_scalar(&format!(
+                "SELECT NOT EXISTS (\
+                   (SELECT grp, total FROM public.wal_rcv_st EXCEPT ({query})) \
+                   UNION ALL \
+                   (({query}) EXCEPT SELECT grp, total FROM public.wal_rcv_st))"
+            ))
+            .await;
+        if matches || start.elapsed() > Duration::from_secs(30) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    db.assert_st_matches_query("public.wal_rcv_st", query).await;
+
+    db.execute("ALTER SYSTEM RESET pg_trickle.wal_receiver")
+        .await;
+    db.execute("ALTER SYSTEM RESET pg_trickle.wal_shared_slot")
+        .await;
+    db.execute("SELECT pg_reload_conf()").await;
+}
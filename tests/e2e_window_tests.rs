@@ -1029,3 +1029,204 @@ async fn test_window_dense_rank_differential() {
     db.refresh_st("wf_dr_st").await;
     db.assert_st_matches_query("wf_dr_st", q).await;
 }
+
+// ── WIN-SUFFIX: cumulative windows recompute only the changed suffix ──
+
+#[tokio::test]
+async fn test_window_running_sum_suffix_recompute() {
+    let db = E2eDb::new().await.with_extension().await;
+    db.execute(
+        "CREATE TABLE wf_ledger (id INT PRIMARY KEY, acct TEXT NOT NULL, ts INT NOT NULL, amount INT)",
+    )
+    .await;
+    db.execute(
+        "INSERT INTO wf_ledger VALUES
+         (1, 'a', 10, 5), (2, 'a', 20, 7), (3, 'a', 30, -2), (4, 'a', 40, 1),
+         (5, 'b', 10, 3), (6, 'b', 20, NULL)",
+    )
+    .await;
+
+    let q = "SELECT id, acct, ts, amount, \
+             SUM(amount) OVER (PARTITION BY acct ORDER BY ts ROWS UNBOUNDED PRECEDING) AS bal, \
+             ROW_NUMBER() OVER (PARTITION BY acct ORDER BY ts) AS seq \
+             FROM wf_ledger";
+    db.create_st("wf_ledger_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("wf_ledger_st", q).await;
+
+    // Late insert in the middle of 'a': rows before ts = 25 keep their
+    // stored version.
+    db.execute(
+        "CREATE TABLE wf_ledger_prefix AS \
+         SELECT __pgt_row_id FROM wf_ledger_st WHERE acct = 'a' AND ts < 25",
+    )
+    .await;
+    db.execute("INSERT INTO wf_ledger VALUES (7, 'a', 25, 100)")
+        .await;
+    db.refresh_st("wf_ledger_st").await;
+    db.assert_st_matches_query("wf_ledger_st", q).await;
+    let prefix_kept: i64 = db
+        .query_scalar(
+            "SELECT count(*) FROM wf_ledger_st s \
+             JOIN wf_ledger_prefix p USING (__pgt_row_id)",
+        )
+        .await;
+    assert_eq!(
+        prefix_kept, 2,
+        "rows ahead of the change must not be rewritten"
+    );
+
+    // Delete, key move, and a NULL amount in the seed position.
+    db.execute("DELETE FROM wf_ledger WHERE id = 3").await;
+    db.execute("UPDATE wf_ledger SET ts = 5 WHERE id = 4").await;
+    db.execute("INSERT INTO wf_ledger VALUES (8, 'b', 30, 4)")
+        .await;
+    db.refresh_st("wf_ledger_st").await;
+    db.assert_st_matches_query("wf_ledger_st", q).await;
+
+    // Tied keys ahead of the change fall back to whole-partition recompute.
+    // Running values among the ts = 30 peers depend on peer order, so only
+    // rows after them are compared.
+    db.execute("INSERT INTO wf_ledger VALUES (9, 'b', 30, 6), (10, 'b', 50, 1)")
+        .await;
+    db.refresh_st("wf_ledger_st").await;
+    db.execute("INSERT INTO wf_ledger VALUES (11, 'b', 40, 2)")
+        .await;
+    db.refresh_st("wf_ledger_st").await;
+    let tail: String = db
+        .query_scalar(
+            "SELECT string_agg(ts || ':' || bal || ':' || seq, ',' ORDER BY ts) \
+             FROM wf_ledger_st WHERE acct = 'b' AND ts >= 40",
+        )
+        .await;
+    assert_eq!(tail, "40:15:5,50:16:6");
+}
+
+// ── Partition-local TopK (TOPK-PART) ───────────────────────────────────
+
+#[tokio::test]
+async fn test_window_per_group_topk_refills_from_source() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute(
+        "CREATE TABLE wf_board (id INT PRIMARY KEY, region TEXT NOT NULL, player TEXT, score INT)",
+    )
+    .await;
+    db.execute(
+        "INSERT INTO wf_board VALUES
+         (1, 'eu', 'ann', 90), (2, 'eu', 'bob', 80), (3, 'eu', 'cat', 70),
+         (4, 'eu', 'dan', 60), (5, 'eu', 'eve', 50),
+         (6, 'us', 'fay', 95), (7, 'us', 'gus', 85)",
+    )
+    .await;
+
+    let q = "SELECT region, player, score, rn FROM ( \
+               SELECT region, player, score, \
+                      ROW_NUMBER() OVER (PARTITION BY region ORDER BY score DESC) AS rn \
+               FROM wf_board \
+             ) t WHERE rn <= 3";
+    db.create_st("wf_board_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("wf_board_st", q).await;
+
+    // Delete the eu winner with no replacement: 'dan' must move up from
+    // outside the stored top 3.
+    db.execute("DELETE FROM wf_board WHERE id = 1").await;
+    db.refresh_st("wf_board_st").await;
+    db.assert_st_matches_query("wf_board_st", q).await;
+
+    // Drop a stored row's score below the cut-off.
+    db.execute("UPDATE wf_board SET score = 10 WHERE id = 2")
+        .await;
+    db.refresh_st("wf_board_st").await;
+    db.assert_st_matches_query("wf_board_st", q).await;
+
+    // Insert-only change displaces the third row.
+    db.execute("INSERT INTO wf_board VALUES (8, 'eu', 'hal', 99)")
+        .await;
+    db.refresh_st("wf_board_st").await;
+    db.assert_st_matches_query("wf_board_st", q).await;
+
+    // A partition holding fewer than N rows stays complete on delete.
+    db.execute("DELETE FROM wf_board WHERE id = 6").await;
+    db.refresh_st("wf_board_st").await;
+    db.assert_st_matches_query("wf_board_st", q).await;
+    assert_eq!(
+        db.count("public.wf_board_st").await,
+        4,
+        "eu keeps three rows and us keeps one"
+    );
+}
+
+/// A top-N filter over GROUP BY output cannot refill its partitions from
+/// the source: DIFFERENTIAL is rejected and AUTO falls back to FULL.
+#[tokio::test]
+async fn test_window_per_group_topk_over_aggregate_falls_back_to_full() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE wf_sales (id INT PRIMARY KEY, region TEXT, rep TEXT, amount INT)")
+        .await;
+    db.execute(
+        "INSERT INTO wf_sales VALUES
+         (1, 'eu', 'ann', 10), (2, 'eu', 'bob', 30), (3, 'eu', 'cat', 20),
+         (4, 'us', 'dan', 40), (5, 'us', 'eve', 5)",
+    )
+    .await;
+
+    let q = "SELECT region, rep, total FROM ( \
+               SELECT region, rep, SUM(amount) AS total, \
+                      ROW_NUMBER() OVER (PARTITION BY region ORDER BY SUM(amount) DESC) AS rn \
+               FROM wf_sales GROUP BY region, rep \
+             ) t WHERE rn <= 1";
+    let result = db
+        .try_execute(&format!(
+            "SELECT pgtrickle.create_stream_table('wf_sales_st', $${q}$$, '1m', 'DIFFERENTIAL')"
+        ))
+        .await;
+    assert!(result.is_err(), "DIFFERENTIAL should be rejected");
+
+    db.create_st("wf_sales_st", q, "1m", "AUTO").await;
+    let mode: String = db
+        .query_scalar(
+            "SELECT refresh_mode FROM pgtrickle.pgt_stream_tables WHERE pgt_name = 'wf_sales_st'",
+        )
+        .await;
+    assert_eq!(mode, "FULL");
+
+    db.execute("DELETE FROM wf_sales WHERE id = 2").await;
+    db.refresh_st("wf_sales_st").await;
+    db.assert_st_matches_query("wf_sales_st", q).await;
+}
+
+#[tokio::test]
+async fn test_lateral_last_n_per_group_differential() {
+    let db = E2eDb::new().await.with_extension().await;
+
+    db.execute("CREATE TABLE wf_cust (id INT PRIMARY KEY, name TEXT)")
+        .await;
+    db.execute(
+        "CREATE TABLE wf_ord (id INT PRIMARY KEY, cust_id INT NOT NULL, placed_at INT NOT NULL)",
+    )
+    .await;
+    db.execute("INSERT INTO wf_cust VALUES (1, 'a'), (2, 'b')")
+        .await;
+    db.execute(
+        "INSERT INTO wf_ord VALUES (1, 1, 10), (2, 1, 20), (3, 1, 30), (4, 1, 40), (5, 2, 15)",
+    )
+    .await;
+
+    let q = "SELECT c.name, o.id AS order_id, o.placed_at \
+             FROM wf_cust c, \
+             LATERAL (SELECT id, placed_at FROM wf_ord \
+                      WHERE cust_id = c.id ORDER BY placed_at DESC LIMIT 2) o";
+    db.create_st("wf_last_st", q, "1m", "DIFFERENTIAL").await;
+    db.assert_st_matches_query("wf_last_st", q).await;
+
+    // Deleting the newest order pulls an older one back in.
+    db.execute("DELETE FROM wf_ord WHERE id = 4").await;
+    db.refresh_st("wf_last_st").await;
+    db.assert_st_matches_query("wf_last_st", q).await;
+
+    db.execute("INSERT INTO wf_ord VALUES (6, 2, 50), (7, 2, 60)")
+        .await;
+    db.refresh_st("wf_last_st").await;
+    db.assert_st_matches_query("wf_last_st", q).await;
+}